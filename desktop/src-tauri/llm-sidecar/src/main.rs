@@ -2,6 +2,8 @@
 //!
 //! Runs as a separate process to handle LLM inference using mistral.rs.
 //! Communicates with the main Tauri app via JSON-RPC over stdin/stdout.
+//! Batch requests (a JSON array of requests) are answered with an array of responses.
+//! Each message is one line of JSON, capped at `MAX_MESSAGE_BYTES`.
 //!
 //! Uses mistral.rs for automatic:
 //! - KV cache management with PagedAttention
@@ -13,7 +15,7 @@ use std::io::{self, BufRead, Write};
 use std::path::PathBuf;
 use std::sync::Arc;
 
-use anyhow::{anyhow, Result};
+use anyhow::Result;
 
 /// Temporarily redirects stdout to stderr during model loading to prevent
 /// mistral.rs's println! statements from corrupting our JSON-RPC protocol.
@@ -49,13 +51,52 @@ mod stdout_redirect {
     }
 }
 
-#[cfg(not(windows))]
+#[cfg(unix)]
+mod stdout_redirect {
+    use std::io::Write;
+
+    pub struct StdoutRedirect {
+        saved_stdout: libc::c_int,
+    }
+
+    impl StdoutRedirect {
+        pub fn to_stderr() -> Option<Self> {
+            // Flush anything already buffered for the real stdout
+            let _ = std::io::stdout().flush();
+
+            unsafe {
+                let saved_stdout = libc::dup(libc::STDOUT_FILENO);
+                if saved_stdout < 0 {
+                    return None;
+                }
+                if libc::dup2(libc::STDERR_FILENO, libc::STDOUT_FILENO) < 0 {
+                    libc::close(saved_stdout);
+                    return None;
+                }
+                Some(Self { saved_stdout })
+            }
+        }
+    }
+
+    impl Drop for StdoutRedirect {
+        fn drop(&mut self) {
+            // Anything printed while redirected must go to stderr, not the restored stdout
+            let _ = std::io::stdout().flush();
+
+            unsafe {
+                libc::dup2(self.saved_stdout, libc::STDOUT_FILENO);
+                libc::close(self.saved_stdout);
+            }
+        }
+    }
+}
+
+#[cfg(not(any(windows, unix)))]
 mod stdout_redirect {
     pub struct StdoutRedirect;
 
     impl StdoutRedirect {
         pub fn to_stderr() -> Option<Self> {
-            // On Unix, we'd use dup2 here, but for now just skip
             None
         }
     }
@@ -69,9 +110,10 @@ use std::collections::HashMap;
 use mistralrs::{
     GgufModelBuilder, Model, PagedAttentionMetaBuilder, MemoryGpuConfig, PagedCacheType,
     Response, TextMessageRole, RequestBuilder, Tool, ToolType, Function, ToolChoice,
-    ToolCallType,
+    ToolCallType, TextMessages,
     DeviceMapSetting, AutoDeviceMapParams,
 };
+use either::Either;
 
 // ============================================================================
 // JSON-RPC Types
@@ -122,6 +164,52 @@ impl JsonRpcResponse {
     }
 }
 
+/// JSON-RPC error codes. The -327xx/-326xx codes are from the JSON-RPC spec;
+/// -320xx are sidecar-specific so the app can tell failure classes apart.
+mod error_codes {
+    pub const PARSE_ERROR: i32 = -32700;
+    pub const INVALID_REQUEST: i32 = -32600;
+    pub const METHOD_NOT_FOUND: i32 = -32601;
+    pub const INVALID_PARAMS: i32 = -32602;
+    /// Unclassified server error
+    pub const SERVER_ERROR: i32 = -32000;
+    pub const MODEL_NOT_FOUND: i32 = -32001;
+    pub const INVALID_MODEL: i32 = -32002;
+    pub const MODEL_LOAD_FAILED: i32 = -32003;
+    pub const OUT_OF_MEMORY: i32 = -32004;
+    pub const NO_MODEL_LOADED: i32 = -32005;
+    pub const INFERENCE_FAILED: i32 = -32006;
+}
+
+/// Handler error carrying a JSON-RPC error code
+#[derive(Debug, thiserror::Error)]
+#[error("{message}")]
+struct RpcError {
+    code: i32,
+    message: String,
+}
+
+fn rpc_error(code: i32, message: impl Into<String>) -> anyhow::Error {
+    RpcError { code, message: message.into() }.into()
+}
+
+/// Out-of-memory failures get their own code so the app can suggest a smaller model
+fn is_out_of_memory(message: &str) -> bool {
+    let lower = message.to_lowercase();
+    ["out of memory", "outofmemory", "out_of_memory", "insufficient memory", "failed to allocate", "allocation failed"]
+        .iter()
+        .any(|pattern| lower.contains(pattern))
+}
+
+/// Use OUT_OF_MEMORY when the message indicates it, otherwise `code`
+fn rpc_error_or_oom(code: i32, message: String) -> anyhow::Error {
+    if is_out_of_memory(&message) {
+        rpc_error(error_codes::OUT_OF_MEMORY, message)
+    } else {
+        rpc_error(code, message)
+    }
+}
+
 // ============================================================================
 // Request/Response Types
 // ============================================================================
@@ -194,6 +282,37 @@ struct CompleteParams {
     /// Tool choice: "auto", "none", or "required"
     #[serde(default = "default_tool_choice")]
     tool_choice: String,
+    /// Conversation this request belongs to, for prefix cache tracking
+    #[serde(default)]
+    session_id: Option<String>,
+    /// Keep a real system role (true) or merge it into the first user message
+    /// (false). None = detect from the model name.
+    #[serde(default)]
+    native_system_role: Option<bool>,
+    /// Instructions used when tools are injected into the prompt
+    /// (placeholders `{tools}` and `{tool_names}`; None = built-in template)
+    #[serde(default)]
+    tool_prompt_template: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct EndSessionParams {
+    session_id: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct CountTokensParams {
+    messages: Vec<Message>,
+    /// Tokens reserved for the answer when checking whether the prompt fits
+    #[serde(default = "default_max_tokens")]
+    max_tokens: u32,
+    #[serde(default)]
+    native_system_role: Option<bool>,
+    /// Tools counted as `complete` would send them: natively or injected
+    #[serde(default)]
+    tools: Option<Vec<ToolDefinition>>,
+    #[serde(default)]
+    tool_prompt_template: Option<String>,
 }
 
 fn default_max_tokens() -> u32 {
@@ -208,10 +327,33 @@ fn default_tool_choice() -> String {
 // Message Preprocessing (OpenAI-style)
 // ============================================================================
 
+/// Whether system messages should be merged into the first user message.
+/// `native_system_role` comes from the app (same detection and user override as
+/// tool support); without it, fall back to detection by model name.
+fn should_merge_system_messages(model_id: &str, native_system_role: Option<bool>) -> bool {
+    !native_system_role.unwrap_or_else(|| has_native_tool_support(model_id))
+}
+
 /// Preprocess messages to handle models that don't support system messages.
 /// This mimics how the OpenAI API / mistralrs-server handles messages internally.
-/// System messages are prepended to the first user message.
-fn preprocess_messages(messages: Vec<Message>) -> Vec<Message> {
+/// With `merge_system`, system messages are prepended to the first user message;
+/// otherwise they are combined into a single leading system message.
+fn preprocess_messages(messages: Vec<Message>, merge_system: bool) -> Vec<Message> {
+    if !merge_system {
+        let (system, mut rest): (Vec<Message>, Vec<Message>) =
+            messages.into_iter().partition(|m| m.role == "system");
+        if !system.is_empty() {
+            let content = system.into_iter().map(|m| m.content).collect::<Vec<_>>().join("\n\n");
+            rest.insert(0, Message {
+                role: "system".to_string(),
+                content,
+                tool_calls: None,
+                tool_call_id: None,
+            });
+        }
+        return rest;
+    }
+
     let mut result = Vec::new();
     let mut system_content: Option<String> = None;
 
@@ -313,47 +455,59 @@ fn has_native_tool_support(model_id: &str) -> bool {
     NATIVE_TOOL_MODELS.iter().any(|m| name_lower.contains(m))
 }
 
-/// Format tools as a prompt for models without native tool support
-fn format_tools_for_prompt(tools: &[ToolDefinition]) -> String {
-    let mut prompt = String::from(
-        "\n\n=== IMPORTANT: AVAILABLE TOOLS ===\n\
-        You MUST use a tool when the user asks for data or information you don't have.\n\n"
-    );
-
+/// Default instructions for models without native tool support.
+/// `{tools}` is replaced with the tool list and `{tool_names}` with their names.
+const DEFAULT_TOOL_PROMPT_TEMPLATE: &str = "=== IMPORTANT: AVAILABLE TOOLS ===\n\
+    You MUST use a tool when the user asks for data or information you don't have.\n\n\
+    {tools}\
+    === HOW TO USE TOOLS ===\n\
+    When you need to use a tool, respond with ONLY this JSON (nothing else before or after):\n\
+    ```json\n\
+    {\"tool_call\": {\"name\": \"tool_name\", \"arguments\": {\"arg1\": \"value1\"}}}\n\
+    ```\n\n\
+    DO NOT explain. DO NOT add text around it. ONLY output the JSON block if using a tool.\n\
+    If you don't need a tool, respond normally.";
+
+/// Format tools as a prompt for models without native tool support, using the
+/// user's template if one is set
+fn format_tools_for_prompt(tools: &[ToolDefinition], template: Option<&str>) -> String {
+    let mut tool_list = String::new();
     for tool in tools {
-        prompt.push_str(&format!("TOOL: {}\n", tool.name));
-        prompt.push_str(&format!("Description: {}\n", tool.description));
-        prompt.push_str(&format!("Parameters: {}\n\n",
+        tool_list.push_str(&format!("TOOL: {}\n", tool.name));
+        tool_list.push_str(&format!("Description: {}\n", tool.description));
+        tool_list.push_str(&format!("Parameters: {}\n\n",
             serde_json::to_string_pretty(&tool.parameters).unwrap_or_else(|_| "{}".to_string())
         ));
     }
-
-    prompt.push_str(
-        "=== HOW TO USE TOOLS ===\n\
-        When you need to use a tool, respond with ONLY this JSON (nothing else before or after):\n\
-        ```json\n\
-        {\"tool_call\": {\"name\": \"tool_name\", \"arguments\": {\"arg1\": \"value1\"}}}\n\
-        ```\n\n\
-        DO NOT explain. DO NOT add text around it. ONLY output the JSON block if using a tool.\n\
-        If you don't need a tool, respond normally."
-    );
+    let tool_names = tools.iter().map(|t| t.name.as_str()).collect::<Vec<_>>().join(", ");
+
+    let template = template
+        .filter(|t| !t.trim().is_empty())
+        .unwrap_or(DEFAULT_TOOL_PROMPT_TEMPLATE);
+    let mut prompt = template.replace("{tool_names}", &tool_names);
+    if prompt.contains("{tools}") {
+        prompt = prompt.replace("{tools}", &tool_list);
+    } else {
+        // Templates without the placeholder still need the tool list
+        prompt = format!("{}\n\n{}", prompt, tool_list.trim_end());
+    }
 
     prompt
 }
 
 /// Inject tool definitions into the messages for non-native tool support
 /// Tools are APPENDED to the system message (after transcript) so they're closer to user message
-fn inject_tools_into_messages(messages: &mut Vec<Message>, tools: &[ToolDefinition]) {
+fn inject_tools_into_messages(messages: &mut Vec<Message>, tools: &[ToolDefinition], template: Option<&str>) {
     if tools.is_empty() {
         return;
     }
 
-    let tool_prompt = format_tools_for_prompt(tools);
+    let tool_prompt = format_tools_for_prompt(tools, template);
 
     // Find the system message and APPEND tools to it (after transcript content)
     // This puts tool instructions closer to the user message where models pay more attention
     if let Some(system_msg) = messages.iter_mut().find(|m| m.role == "system") {
-        system_msg.content = format!("{}{}", system_msg.content, tool_prompt);
+        system_msg.content = format!("{}\n\n{}", system_msg.content, tool_prompt);
     } else {
         // Insert a new system message at the beginning
         messages.insert(0, Message {
@@ -444,6 +598,9 @@ fn parse_tool_calls_from_response(content: &str) -> Vec<ToolCall> {
 struct LlmState {
     model: Option<Model>,
     model_id: Option<String>,
+    /// Prompt fingerprints per conversation session. Behind its own lock since
+    /// completions only hold a read lock on the state.
+    sessions: std::sync::Mutex<SessionTracker>,
 }
 
 impl LlmState {
@@ -451,8 +608,96 @@ impl LlmState {
         Self {
             model: None,
             model_id: None,
+            sessions: std::sync::Mutex::new(SessionTracker::default()),
         }
     }
+
+    fn unload_model(&mut self) {
+        self.model = None;
+        self.model_id = None;
+        self.sessions.lock().unwrap().clear();
+    }
+}
+
+// ============================================================================
+// Session Prefix Tracking
+// ============================================================================
+
+/// Sessions tracked at once; matches the prefix cache size set on the model
+const MAX_TRACKED_SESSIONS: usize = 16;
+
+/// Tracks the message sequence last sent for each chat session.
+///
+/// mistral.rs reuses the KV cache for a prompt that starts with a previously
+/// processed sequence. Follow-up turns only hit that cache when the earlier
+/// messages (transcript system prompt, history, and the previous answer) are
+/// rendered identically, so this records what was sent and reports how much
+/// of the new prompt is a reusable prefix.
+#[derive(Default)]
+struct SessionTracker {
+    sessions: HashMap<String, TrackedSession>,
+    counter: u64,
+}
+
+struct TrackedSession {
+    fingerprints: Vec<u64>,
+    last_used: u64,
+}
+
+impl SessionTracker {
+    /// Compare a new prompt with the session's previous prompt and answer,
+    /// returning the number of leading messages that are unchanged
+    fn shared_prefix(&self, session_id: &str, fingerprints: &[u64]) -> usize {
+        self.sessions
+            .get(session_id)
+            .map(|previous| {
+                previous.fingerprints
+                    .iter()
+                    .zip(fingerprints)
+                    .take_while(|(a, b)| a == b)
+                    .count()
+            })
+            .unwrap_or(0)
+    }
+
+    fn previous_len(&self, session_id: &str) -> Option<usize> {
+        self.sessions.get(session_id).map(|s| s.fingerprints.len())
+    }
+
+    /// Record the prompt plus the generated answer, which the next turn will extend
+    fn record(&mut self, session_id: &str, fingerprints: Vec<u64>) {
+        self.counter += 1;
+        self.sessions.insert(session_id.to_string(), TrackedSession {
+            fingerprints,
+            last_used: self.counter,
+        });
+
+        if self.sessions.len() > MAX_TRACKED_SESSIONS {
+            if let Some(oldest) = self.sessions
+                .iter()
+                .min_by_key(|(_, s)| s.last_used)
+                .map(|(id, _)| id.clone())
+            {
+                self.sessions.remove(&oldest);
+            }
+        }
+    }
+
+    fn remove(&mut self, session_id: &str) -> bool {
+        self.sessions.remove(session_id).is_some()
+    }
+
+    fn clear(&mut self) {
+        self.sessions.clear();
+    }
+}
+
+fn message_fingerprint(role: &str, content: &str) -> u64 {
+    use std::hash::{Hash, Hasher};
+    let mut hasher = std::collections::hash_map::DefaultHasher::new();
+    role.hash(&mut hasher);
+    content.hash(&mut hasher);
+    hasher.finish()
 }
 
 type SharedState = Arc<RwLock<LlmState>>;
@@ -461,6 +706,9 @@ type SharedState = Arc<RwLock<LlmState>>;
 // Handler Functions
 // ============================================================================
 
+/// Context window the model is loaded with (prompt + generated tokens)
+const CONTEXT_SIZE: usize = 8192;
+
 async fn handle_initialize(state: SharedState, params: InitializeParams) -> Result<serde_json::Value> {
     log::info!("Initializing model: {}", params.model_path);
 
@@ -474,15 +722,14 @@ async fn handle_initialize(state: SharedState, params: InitializeParams) -> Resu
         let mut state_guard = state.write().await;
         if state_guard.model.is_some() {
             log::info!("Unloading previous model: {:?}", state_guard.model_id);
-            state_guard.model = None;
-            state_guard.model_id = None;
+            state_guard.unload_model();
         }
     }
 
     let path = PathBuf::from(&params.model_path);
     if !path.exists() {
         log::error!("Model file not found: {}", params.model_path);
-        return Err(anyhow!("Model file not found: {}", params.model_path));
+        return Err(rpc_error(error_codes::MODEL_NOT_FOUND, format!("Model file not found: {}", params.model_path)));
     }
 
     // Check file size to ensure it's a valid model
@@ -493,7 +740,10 @@ async fn handle_initialize(state: SharedState, params: InitializeParams) -> Resu
 
     if file_size < 10_000_000 {
         log::error!("Model file too small ({} bytes), likely not a valid GGUF", file_size);
-        return Err(anyhow!("Model file too small ({} bytes), likely not a valid GGUF model", file_size));
+        return Err(rpc_error(
+            error_codes::INVALID_MODEL,
+            format!("Model file too small ({} bytes), likely not a valid GGUF model", file_size),
+        ));
     }
 
     // Split path into directory and filename
@@ -502,7 +752,7 @@ async fn handle_initialize(state: SharedState, params: InitializeParams) -> Resu
         .unwrap_or_else(|| ".".to_string());
     let model_filename = path.file_name()
         .map(|f| f.to_string_lossy().to_string())
-        .ok_or_else(|| anyhow!("Invalid model path - no filename"))?;
+        .ok_or_else(|| rpc_error(error_codes::INVALID_MODEL, "Invalid model path - no filename"))?;
 
     // Extract model ID from filename for display purposes
     let model_id = path
@@ -524,7 +774,7 @@ async fn handle_initialize(state: SharedState, params: InitializeParams) -> Resu
     // - FP8 KV cache quantization (halves memory usage)
     // - Prefix caching for system prompt reuse
     let device_map_params = AutoDeviceMapParams::Text {
-        max_seq_len: 8192,
+        max_seq_len: CONTEXT_SIZE,
         max_batch_size: 1,
     };
 
@@ -537,7 +787,7 @@ async fn handle_initialize(state: SharedState, params: InitializeParams) -> Resu
     .with_paged_attn(|| {
         PagedAttentionMetaBuilder::default()
             .with_block_size(32)
-            .with_gpu_memory(MemoryGpuConfig::ContextSize(8192))
+            .with_gpu_memory(MemoryGpuConfig::ContextSize(CONTEXT_SIZE))
             .with_paged_cache_type(PagedCacheType::F8E4M3)
             .build()
     });
@@ -549,7 +799,7 @@ async fn handle_initialize(state: SharedState, params: InitializeParams) -> Resu
         }
         Err(e) => {
             log::error!("Failed to create GgufModelBuilder: {:?}", e);
-            return Err(anyhow!("Failed to create model builder: {:?}", e));
+            return Err(rpc_error_or_oom(error_codes::MODEL_LOAD_FAILED, format!("Failed to create model builder: {:?}", e)));
         }
     };
 
@@ -568,7 +818,7 @@ async fn handle_initialize(state: SharedState, params: InitializeParams) -> Resu
         }
         Err(e) => {
             log::error!("Failed to build model: {:?}", e);
-            return Err(anyhow!("Failed to load model: {:?}", e));
+            return Err(rpc_error_or_oom(error_codes::MODEL_LOAD_FAILED, format!("Failed to load model: {:?}", e)));
         }
     };
 
@@ -628,7 +878,7 @@ async fn handle_complete(
 ) -> Result<serde_json::Value> {
     let state_guard = state.read().await;
     let model = state_guard.model.as_ref()
-        .ok_or_else(|| anyhow!("No model loaded"))?;
+        .ok_or_else(|| rpc_error(error_codes::NO_MODEL_LOADED, "No model loaded"))?;
     let model_id = state_guard.model_id.clone().unwrap_or_else(|| "unknown".to_string());
 
     // Check if model has native tool support
@@ -647,7 +897,7 @@ async fn handle_complete(
     // For non-native tool support, inject tools into messages
     let mut messages_to_process = params.messages.clone();
     if use_prompt_injection {
-        inject_tools_into_messages(&mut messages_to_process, params.tools.as_ref().unwrap());
+        inject_tools_into_messages(&mut messages_to_process, params.tools.as_ref().unwrap(), params.tool_prompt_template.as_deref());
         log::debug!("After tool injection - {} messages:", messages_to_process.len());
         for (i, msg) in messages_to_process.iter().enumerate() {
             let preview = if msg.content.len() > 200 {
@@ -659,9 +909,31 @@ async fn handle_complete(
         }
     }
 
-    // Preprocess messages: merge system messages into first user message
-    // This handles models (like Mistral) that don't support system messages in their chat template
-    let processed_messages = preprocess_messages(messages_to_process);
+    // Preprocess messages: merge system messages into first user message for
+    // models whose chat template doesn't support them
+    let merge_system = should_merge_system_messages(&model_id, params.native_system_role);
+    let processed_messages = preprocess_messages(messages_to_process, merge_system);
+
+    let prompt_fingerprints: Vec<u64> = processed_messages
+        .iter()
+        .map(|m| message_fingerprint(&m.role, &m.content))
+        .collect();
+    let cached_prefix_messages = params.session_id.as_deref().map(|session_id| {
+        let tracker = state_guard.sessions.lock().unwrap();
+        let shared = tracker.shared_prefix(session_id, &prompt_fingerprints);
+        match tracker.previous_len(session_id) {
+            Some(previous) if shared < previous => log::warn!(
+                "Session {}: prompt diverged at message {} of {}, earlier KV cache can't be fully reused",
+                session_id, shared, previous
+            ),
+            Some(_) => log::info!(
+                "Session {}: reusing cached prefix ({} of {} messages)",
+                session_id, shared, prompt_fingerprints.len()
+            ),
+            None => log::info!("Session {}: first turn", session_id),
+        }
+        shared
+    });
 
     if use_prompt_injection {
         log::debug!("After preprocessing - {} messages:", processed_messages.len());
@@ -681,6 +953,9 @@ async fn handle_complete(
     // Add messages
     for msg in &processed_messages {
         match msg.role.as_str() {
+            "system" => {
+                request_builder = request_builder.add_message(TextMessageRole::System, &msg.content);
+            }
             "user" => {
                 request_builder = request_builder.add_message(TextMessageRole::User, &msg.content);
             }
@@ -738,14 +1013,19 @@ async fn handle_complete(
     if params.stream {
         // Streaming response
         let mut stream = model.stream_chat_request(request_builder).await
-            .map_err(|e| anyhow!("Failed to start streaming: {:?}", e))?;
+            .map_err(|e| rpc_error_or_oom(error_codes::INFERENCE_FAILED, format!("Failed to start streaming: {:?}", e)))?;
 
         let mut full_content = String::new();
         let mut tool_calls: Vec<ToolCall> = Vec::new();
+        // (prompt, completion) token counts, reported with the last chunk
+        let mut usage: Option<(usize, usize)> = None;
 
         while let Some(response) = stream.next().await {
             match response {
                 Response::Chunk(chunk) => {
+                    if let Some(ref chunk_usage) = chunk.usage {
+                        usage = Some((chunk_usage.prompt_tokens, chunk_usage.completion_tokens));
+                    }
                     for choice in &chunk.choices {
                         if let Some(ref content) = choice.delta.content {
                             full_content.push_str(content);
@@ -763,18 +1043,48 @@ async fn handle_complete(
                         // Check for tool calls in delta
                         if let Some(ref delta_tool_calls) = choice.delta.tool_calls {
                             for tc in delta_tool_calls {
-                                tool_calls.push(ToolCall {
-                                    id: tc.id.clone(),
-                                    function: FunctionCall {
-                                        name: tc.function.name.clone(),
-                                        arguments: tc.function.arguments.clone(),
-                                    },
-                                });
+                                // Stream the partial call so the UI can show it as it forms
+                                let response = JsonRpcResponse::success(
+                                    request_id,
+                                    serde_json::json!({
+                                        "tool_call_delta": {
+                                            "index": tc.index,
+                                            "id": tc.id,
+                                            "name": tc.function.name,
+                                            "arguments": tc.function.arguments,
+                                        }
+                                    }),
+                                );
+                                let mut handle = stdout.lock();
+                                writeln!(handle, "{}", serde_json::to_string(&response)?)?;
+                                handle.flush()?;
+
+                                // Assemble the call, appending argument fragments for the same index
+                                match tool_calls.get_mut(tc.index) {
+                                    Some(existing) => {
+                                        if existing.id.is_empty() {
+                                            existing.id = tc.id.clone();
+                                        }
+                                        if existing.function.name.is_empty() {
+                                            existing.function.name = tc.function.name.clone();
+                                        }
+                                        existing.function.arguments.push_str(&tc.function.arguments);
+                                    }
+                                    None => tool_calls.push(ToolCall {
+                                        id: tc.id.clone(),
+                                        function: FunctionCall {
+                                            name: tc.function.name.clone(),
+                                            arguments: tc.function.arguments.clone(),
+                                        },
+                                    }),
+                                }
                             }
                         }
                     }
                 }
                 Response::Done(done) => {
+                    usage = Some((done.usage.prompt_tokens, done.usage.completion_tokens));
+
                     // Check for tool calls in final response
                     if let Some(ref choices) = done.choices.first() {
                         if let Some(ref final_tool_calls) = choices.message.tool_calls {
@@ -790,13 +1100,13 @@ async fn handle_complete(
                     break;
                 }
                 Response::InternalError(e) => {
-                    return Err(anyhow!("Internal error during streaming: {:?}", e));
+                    return Err(rpc_error_or_oom(error_codes::INFERENCE_FAILED, format!("Internal error during streaming: {:?}", e)));
                 }
                 Response::ValidationError(e) => {
-                    return Err(anyhow!("Validation error: {:?}", e));
+                    return Err(rpc_error(error_codes::INVALID_PARAMS, format!("Validation error: {:?}", e)));
                 }
                 Response::ModelError(msg, _) => {
-                    return Err(anyhow!("Model error: {}", msg));
+                    return Err(rpc_error_or_oom(error_codes::INFERENCE_FAILED, format!("Model error: {}", msg)));
                 }
                 _ => {}
             }
@@ -818,17 +1128,24 @@ async fn handle_complete(
             ("stop", None)
         };
 
+        if let Some(ref session_id) = params.session_id {
+            record_session_turn(&state_guard, session_id, prompt_fingerprints, &full_content);
+        }
+
         Ok(serde_json::json!({
             "done": true,
             "content": full_content,
             "model": model_id,
             "finish_reason": finish_reason,
-            "tool_calls": response_tool_calls
+            "tool_calls": response_tool_calls,
+            "cached_prefix_messages": cached_prefix_messages,
+            "prompt_tokens": usage.map(|(prompt, _)| prompt),
+            "completion_tokens": usage.map(|(_, completion)| completion)
         }))
     } else {
         // Non-streaming response
         let response = model.send_chat_request(request_builder).await
-            .map_err(|e| anyhow!("Failed to complete: {:?}", e))?;
+            .map_err(|e| rpc_error_or_oom(error_codes::INFERENCE_FAILED, format!("Failed to complete: {:?}", e)))?;
 
         let first_choice = response.choices.first();
 
@@ -863,16 +1180,81 @@ async fn handle_complete(
             "stop"
         };
 
+        if let Some(ref session_id) = params.session_id {
+            record_session_turn(&state_guard, session_id, prompt_fingerprints, &content);
+        }
+
         Ok(serde_json::json!({
             "done": true,
             "content": content,
             "model": model_id,
             "finish_reason": finish_reason,
-            "tool_calls": tool_calls
+            "tool_calls": tool_calls,
+            "cached_prefix_messages": cached_prefix_messages,
+            "prompt_tokens": response.usage.prompt_tokens,
+            "completion_tokens": response.usage.completion_tokens
         }))
     }
 }
 
+/// Remember the prompt and answer of a completed turn for the next one
+fn record_session_turn(state: &LlmState, session_id: &str, mut fingerprints: Vec<u64>, answer: &str) {
+    fingerprints.push(message_fingerprint("assistant", answer));
+    state.sessions.lock().unwrap().record(session_id, fingerprints);
+}
+
+/// Stop tracking a chat session (e.g. when it's deleted)
+async fn handle_end_session(state: SharedState, params: EndSessionParams) -> Result<serde_json::Value> {
+    let state_guard = state.read().await;
+    let removed = state_guard.sessions.lock().unwrap().remove(&params.session_id);
+    Ok(serde_json::json!({
+        "success": removed
+    }))
+}
+
+/// Count the prompt tokens for a conversation using the loaded model's
+/// tokenizer and chat template, without running inference
+async fn handle_count_tokens(state: SharedState, params: CountTokensParams) -> Result<serde_json::Value> {
+    let state_guard = state.read().await;
+    let model = state_guard.model.as_ref()
+        .ok_or_else(|| rpc_error(error_codes::NO_MODEL_LOADED, "No model loaded"))?;
+
+    // Same tool handling and preprocessing as `complete`, so the count matches what would be sent
+    let model_id = state_guard.model_id.as_deref().unwrap_or("unknown");
+    let tools = params.tools.filter(|t| !t.is_empty());
+    let use_native_tools = tools.is_some() && has_native_tool_support(model_id);
+    let mut messages_to_process = params.messages;
+    if let (Some(tools), false) = (&tools, use_native_tools) {
+        inject_tools_into_messages(&mut messages_to_process, tools, params.tool_prompt_template.as_deref());
+    }
+    let native_tools = tools.filter(|_| use_native_tools).map(|tools| convert_tools(&tools));
+
+    let merge_system = should_merge_system_messages(model_id, params.native_system_role);
+    let mut messages = TextMessages::new();
+    for msg in preprocess_messages(messages_to_process, merge_system) {
+        let role = match msg.role.as_str() {
+            "system" => TextMessageRole::System,
+            "assistant" => TextMessageRole::Assistant,
+            "tool" => TextMessageRole::Tool,
+            _ => TextMessageRole::User,
+        };
+        messages = messages.add_message(role, &msg.content);
+    }
+
+    let tokens = model
+        .tokenize(Either::Left(messages), native_tools, true, true, None)
+        .await
+        .map_err(|e| rpc_error(error_codes::INFERENCE_FAILED, format!("Tokenization failed: {}", e)))?;
+
+    let prompt_tokens = tokens.len();
+    Ok(serde_json::json!({
+        "prompt_tokens": prompt_tokens,
+        "context_size": CONTEXT_SIZE,
+        "max_tokens": params.max_tokens,
+        "fits": prompt_tokens + params.max_tokens as usize <= CONTEXT_SIZE
+    }))
+}
+
 async fn handle_current_model(state: SharedState) -> Result<serde_json::Value> {
     let state_guard = state.read().await;
     Ok(serde_json::json!({
@@ -890,14 +1272,94 @@ async fn handle_is_ready(state: SharedState) -> Result<serde_json::Value> {
 async fn handle_shutdown(state: SharedState) -> Result<serde_json::Value> {
     log::info!("Shutting down...");
     let mut state_guard = state.write().await;
-    state_guard.model = None;
-    state_guard.model_id = None;
+    state_guard.unload_model();
 
     Ok(serde_json::json!({
         "success": true
     }))
 }
 
+// ============================================================================
+// Message Framing
+// ============================================================================
+
+/// Largest request line accepted on stdin. Whole meeting transcripts are sent
+/// as chat context, so this is generous; anything bigger is rejected.
+const MAX_MESSAGE_BYTES: usize = 64 * 1024 * 1024;
+
+/// Read buffer size for stdin
+const READ_BUFFER_BYTES: usize = 1024 * 1024;
+
+/// One newline-delimited frame read from stdin
+enum Frame {
+    Message(String),
+    /// Line exceeded the size cap; it was read to the end and discarded
+    TooLarge(usize),
+    /// Line was not valid UTF-8
+    InvalidUtf8(String),
+}
+
+/// Read the next newline-delimited frame in chunks, without buffering more
+/// than `max_bytes` of an oversized line. Returns None at end of input.
+fn read_frame<R: BufRead>(reader: &mut R, max_bytes: usize) -> io::Result<Option<Frame>> {
+    let mut buf = Vec::new();
+    let mut total = 0usize;
+    let mut oversized = false;
+
+    loop {
+        let (consumed, found_newline) = {
+            let available = match reader.fill_buf() {
+                Ok(b) => b,
+                Err(e) if e.kind() == io::ErrorKind::Interrupted => continue,
+                Err(e) => return Err(e),
+            };
+
+            if available.is_empty() {
+                // EOF - deliver a final unterminated line if there is one
+                if total == 0 {
+                    return Ok(None);
+                }
+                break;
+            }
+
+            let (chunk, found_newline) = match available.iter().position(|&b| b == b'\n') {
+                Some(pos) => (&available[..pos], true),
+                None => (available, false),
+            };
+
+            total += chunk.len();
+            if !oversized {
+                if total > max_bytes {
+                    oversized = true;
+                    buf = Vec::new();
+                } else {
+                    buf.extend_from_slice(chunk);
+                }
+            }
+
+            (chunk.len() + found_newline as usize, found_newline)
+        };
+
+        reader.consume(consumed);
+        if found_newline {
+            break;
+        }
+    }
+
+    if oversized {
+        return Ok(Some(Frame::TooLarge(total)));
+    }
+
+    if buf.last() == Some(&b'\r') {
+        buf.pop();
+    }
+
+    match String::from_utf8(buf) {
+        Ok(line) => Ok(Some(Frame::Message(line))),
+        Err(e) => Ok(Some(Frame::InvalidUtf8(e.to_string()))),
+    }
+}
+
 // ============================================================================
 // Main Loop
 // ============================================================================
@@ -907,33 +1369,94 @@ async fn process_request(state: SharedState, request: JsonRpcRequest) -> JsonRpc
         "initialize" => {
             match serde_json::from_value::<InitializeParams>(request.params) {
                 Ok(params) => handle_initialize(state, params).await,
-                Err(e) => Err(anyhow!("Invalid params: {}", e)),
+                Err(e) => Err(rpc_error(error_codes::INVALID_PARAMS, format!("Invalid params: {}", e))),
             }
         }
         "list_models" => {
             match serde_json::from_value::<ListModelsParams>(request.params) {
                 Ok(params) => handle_list_models(state, params).await,
-                Err(e) => Err(anyhow!("Invalid params: {}", e)),
+                Err(e) => Err(rpc_error(error_codes::INVALID_PARAMS, format!("Invalid params: {}", e))),
             }
         }
         "complete" => {
             match serde_json::from_value::<CompleteParams>(request.params) {
                 Ok(params) => handle_complete(state, params, request.id).await,
-                Err(e) => Err(anyhow!("Invalid params: {}", e)),
+                Err(e) => Err(rpc_error(error_codes::INVALID_PARAMS, format!("Invalid params: {}", e))),
+            }
+        }
+        "end_session" => {
+            match serde_json::from_value::<EndSessionParams>(request.params) {
+                Ok(params) => handle_end_session(state, params).await,
+                Err(e) => Err(rpc_error(error_codes::INVALID_PARAMS, format!("Invalid params: {}", e))),
+            }
+        }
+        "count_tokens" => {
+            match serde_json::from_value::<CountTokensParams>(request.params) {
+                Ok(params) => handle_count_tokens(state, params).await,
+                Err(e) => Err(rpc_error(error_codes::INVALID_PARAMS, format!("Invalid params: {}", e))),
             }
         }
         "current_model" => handle_current_model(state).await,
         "is_ready" => handle_is_ready(state).await,
         "shutdown" => handle_shutdown(state).await,
-        _ => Err(anyhow!("Unknown method: {}", request.method)),
+        _ => Err(rpc_error(error_codes::METHOD_NOT_FOUND, format!("Unknown method: {}", request.method))),
     };
 
     match result {
         Ok(value) => JsonRpcResponse::success(request.id, value),
-        Err(e) => JsonRpcResponse::error(request.id, -32000, e.to_string()),
+        Err(e) => {
+            let code = e.downcast_ref::<RpcError>()
+                .map(|rpc| rpc.code)
+                .unwrap_or(error_codes::SERVER_ERROR);
+            JsonRpcResponse::error(request.id, code, e.to_string())
+        }
     }
 }
 
+/// Process a JSON-RPC batch in order, returning one response per request.
+/// Streaming completions aren't allowed in a batch since their token
+/// notifications would interleave with the batch response.
+async fn process_batch(state: SharedState, items: Vec<serde_json::Value>) -> Vec<JsonRpcResponse> {
+    if items.is_empty() {
+        return vec![JsonRpcResponse::error(0, error_codes::INVALID_REQUEST, "Invalid request: empty batch".to_string())];
+    }
+
+    let mut responses = Vec::with_capacity(items.len());
+    for item in items {
+        let request: JsonRpcRequest = match serde_json::from_value(item) {
+            Ok(r) => r,
+            Err(e) => {
+                responses.push(JsonRpcResponse::error(0, error_codes::INVALID_REQUEST, format!("Invalid request: {}", e)));
+                continue;
+            }
+        };
+
+        let is_streaming = request.params.get("stream").and_then(|s| s.as_bool()).unwrap_or(false);
+        if is_streaming {
+            responses.push(JsonRpcResponse::error(
+                request.id,
+                error_codes::INVALID_REQUEST,
+                "Streaming requests are not supported in a batch".to_string(),
+            ));
+            continue;
+        }
+
+        log::debug!("Batch request: {} (id={})", request.method, request.id);
+        responses.push(process_request(state.clone(), request).await);
+    }
+
+    responses
+}
+
+/// Write a single JSON message line to stdout
+fn write_message<T: Serialize>(stdout: &io::Stdout, message: &T) {
+    let mut handle = stdout.lock();
+    if let Err(e) = writeln!(handle, "{}", serde_json::to_string(message).unwrap()) {
+        log::error!("Failed to write response: {}", e);
+    }
+    let _ = handle.flush();
+}
+
 #[tokio::main]
 async fn main() {
     // Set up panic hook to log panics to stderr before exiting
@@ -964,30 +1487,63 @@ async fn main() {
 
     let stdin = io::stdin();
     let stdout = io::stdout();
+    let mut reader = io::BufReader::with_capacity(READ_BUFFER_BYTES, stdin.lock());
+
+    // Read JSON-RPC requests (or batches) line by line from stdin
+    loop {
+        let line = match read_frame(&mut reader, MAX_MESSAGE_BYTES) {
+            Ok(Some(Frame::Message(l))) => l,
+            Ok(Some(Frame::TooLarge(size))) => {
+                log::error!("Request too large: {} bytes (max {})", size, MAX_MESSAGE_BYTES);
+                let response = JsonRpcResponse::error(
+                    0,
+                    error_codes::INVALID_REQUEST,
+                    format!("Request too large: {} bytes (max {} bytes)", size, MAX_MESSAGE_BYTES),
+                );
+                write_message(&stdout, &response);
+                continue;
+            }
+            Ok(Some(Frame::InvalidUtf8(e))) => {
+                log::error!("Request is not valid UTF-8: {}", e);
+                let response = JsonRpcResponse::error(0, error_codes::PARSE_ERROR, format!("Parse error: invalid UTF-8: {}", e));
+                write_message(&stdout, &response);
+                continue;
+            }
+            Ok(None) => break,
+            Err(e) => {
+                log::error!("Failed to read from stdin: {}", e);
+                break;
+            }
+        };
 
-    // Read JSON-RPC requests line by line from stdin
-    for line in stdin.lock().lines() {
-        let line = match line {
-            Ok(l) => l,
+        if line.trim().is_empty() {
+            continue;
+        }
+
+        // Parse message: a single request object or a batch (array of requests)
+        let message: serde_json::Value = match serde_json::from_str(&line) {
+            Ok(m) => m,
             Err(e) => {
-                log::error!("Failed to read line: {}", e);
+                log::error!("Failed to parse request: {}", e);
+                let response = JsonRpcResponse::error(0, error_codes::PARSE_ERROR, format!("Parse error: {}", e));
+                write_message(&stdout, &response);
                 continue;
             }
         };
 
-        if line.trim().is_empty() {
+        if let serde_json::Value::Array(items) = message {
+            log::debug!("Received batch of {} requests", items.len());
+            let responses = process_batch(state.clone(), items).await;
+            write_message(&stdout, &responses);
             continue;
         }
 
-        // Parse request
-        let request: JsonRpcRequest = match serde_json::from_str(&line) {
+        let request: JsonRpcRequest = match serde_json::from_value(message) {
             Ok(r) => r,
             Err(e) => {
-                log::error!("Failed to parse request: {}", e);
-                let response = JsonRpcResponse::error(0, -32700, format!("Parse error: {}", e));
-                let mut handle = stdout.lock();
-                let _ = writeln!(handle, "{}", serde_json::to_string(&response).unwrap());
-                let _ = handle.flush();
+                log::error!("Invalid request: {}", e);
+                let response = JsonRpcResponse::error(0, error_codes::INVALID_REQUEST, format!("Invalid request: {}", e));
+                write_message(&stdout, &response);
                 continue;
             }
         };
@@ -998,11 +1554,7 @@ async fn main() {
         let response = process_request(state.clone(), request).await;
 
         // Send response
-        let mut handle = stdout.lock();
-        if let Err(e) = writeln!(handle, "{}", serde_json::to_string(&response).unwrap()) {
-            log::error!("Failed to write response: {}", e);
-        }
-        let _ = handle.flush();
+        write_message(&stdout, &response);
     }
 
     log::info!("LLM Sidecar shutting down");
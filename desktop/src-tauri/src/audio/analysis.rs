@@ -0,0 +1,258 @@
+//! Offline analysis of saved recordings
+//!
+//! Decodes a recording's audio file and reports loudness, peak, noise floor
+//! and clipping, so a poor transcript can be traced back to audio that was
+//! too quiet, clipped or noisy. Silence gaps are found by inverting the VAD
+//! speech segments, for jumping between speakers or trimming long pauses.
+
+use anyhow::Result;
+use serde::Serialize;
+
+use super::processing::measure_loudness;
+use super::retranscription::{decode_audio_file, decode_audio_file_at_rate};
+use super::vad::get_speech_chunks;
+use crate::state::AppState;
+
+/// Samples at or above this magnitude count as clipped
+const CLIP_THRESHOLD: f32 = 0.999;
+/// Window used for the noise floor estimate
+const NOISE_WINDOW_MS: u32 = 50;
+/// Percentile of window levels taken as the noise floor
+const NOISE_FLOOR_PERCENTILE: f64 = 0.10;
+/// Floor used for windows of digital silence
+const SILENCE_DBFS: f64 = -120.0;
+
+/// VAD redemption time; short so natural pauses between sentences stay visible
+const SILENCE_VAD_REDEMPTION_MS: u32 = 400;
+
+const QUIET_LUFS: f64 = -35.0;
+const LOUD_LUFS: f64 = -10.0;
+const CLIPPING_PERCENT: f64 = 0.1;
+const NOISY_FLOOR_DBFS: f64 = -45.0;
+
+/// Quality report for a recording's saved audio
+#[derive(Debug, Clone, Serialize)]
+pub struct AudioQualityReport {
+    pub recording_id: String,
+    pub duration_seconds: f64,
+    pub sample_rate: u32,
+    /// Integrated loudness (EBU R128), None if the audio is silent
+    pub integrated_lufs: Option<f64>,
+    pub true_peak_dbtp: Option<f64>,
+    /// Level of the quietest windows, roughly the background noise
+    pub noise_floor_dbfs: Option<f64>,
+    pub clipped_samples: u64,
+    pub clipped_percent: f64,
+    /// Human-readable problems found, empty if the audio looks fine
+    pub issues: Vec<String>,
+}
+
+/// A stretch of the recording without speech
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct SilenceGap {
+    pub start_seconds: f64,
+    pub end_seconds: f64,
+    pub duration_seconds: f64,
+}
+
+pub(crate) fn to_dbfs(level: f64) -> f64 {
+    if level > 0.0 {
+        (20.0 * level.log10()).max(SILENCE_DBFS)
+    } else {
+        SILENCE_DBFS
+    }
+}
+
+/// Estimate the noise floor from the RMS level of short windows
+fn noise_floor_dbfs(samples: &[f32], sample_rate: u32) -> Option<f64> {
+    let window = (sample_rate * NOISE_WINDOW_MS / 1000).max(1) as usize;
+    let mut levels: Vec<f64> = samples
+        .chunks_exact(window)
+        .map(|chunk| {
+            let mean_square = chunk.iter().map(|&x| (x as f64).powi(2)).sum::<f64>() / chunk.len() as f64;
+            to_dbfs(mean_square.sqrt())
+        })
+        .collect();
+
+    if levels.is_empty() {
+        return None;
+    }
+
+    levels.sort_by(|a, b| a.total_cmp(b));
+    let index = ((levels.len() - 1) as f64 * NOISE_FLOOR_PERCENTILE).round() as usize;
+    Some(levels[index])
+}
+
+pub(crate) fn count_clipped(samples: &[f32]) -> u64 {
+    samples.iter().filter(|x| x.abs() >= CLIP_THRESHOLD).count() as u64
+}
+
+/// Analyze decoded mono samples
+pub fn analyze_samples(recording_id: &str, samples: &[f32], sample_rate: u32) -> Result<AudioQualityReport> {
+    let loudness = measure_loudness(samples, sample_rate)?;
+    let noise_floor_dbfs = noise_floor_dbfs(samples, sample_rate);
+    let clipped_samples = count_clipped(samples);
+    let clipped_percent = if samples.is_empty() {
+        0.0
+    } else {
+        clipped_samples as f64 * 100.0 / samples.len() as f64
+    };
+
+    let mut issues = Vec::new();
+    match loudness.integrated_lufs {
+        None => issues.push("Recording is silent".to_string()),
+        Some(lufs) if lufs < QUIET_LUFS => {
+            issues.push(format!("Recording is very quiet ({:.1} LUFS)", lufs))
+        }
+        Some(lufs) if lufs > LOUD_LUFS => {
+            issues.push(format!("Recording is very loud ({:.1} LUFS)", lufs))
+        }
+        Some(_) => {}
+    }
+    if clipped_percent >= CLIPPING_PERCENT {
+        issues.push(format!("{:.2}% of samples are clipped", clipped_percent));
+    }
+    if let Some(floor) = noise_floor_dbfs.filter(|&floor| floor > NOISY_FLOOR_DBFS) {
+        issues.push(format!("High background noise (noise floor {:.1} dBFS)", floor));
+    }
+
+    Ok(AudioQualityReport {
+        recording_id: recording_id.to_string(),
+        duration_seconds: samples.len() as f64 / sample_rate as f64,
+        sample_rate,
+        integrated_lufs: loudness.integrated_lufs,
+        true_peak_dbtp: loudness.true_peak_dbtp,
+        noise_floor_dbfs,
+        clipped_samples,
+        clipped_percent,
+        issues,
+    })
+}
+
+/// Turn speech intervals (seconds, in order) into the silences between them
+pub fn silence_gaps_between(speech: &[(f64, f64)], total_seconds: f64, min_gap_seconds: f64) -> Vec<SilenceGap> {
+    let mut gaps = Vec::new();
+    let mut cursor = 0.0f64;
+
+    let mut push_gap = |start: f64, end: f64| {
+        if end - start >= min_gap_seconds {
+            gaps.push(SilenceGap { start_seconds: start, end_seconds: end, duration_seconds: end - start });
+        }
+    };
+
+    for &(start, end) in speech {
+        let start = start.clamp(0.0, total_seconds);
+        if start > cursor {
+            push_gap(cursor, start);
+        }
+        cursor = cursor.max(end.min(total_seconds));
+    }
+    if total_seconds > cursor {
+        push_gap(cursor, total_seconds);
+    }
+
+    gaps
+}
+
+/// Audio file path and sample rate of a recording
+async fn recording_audio_file(state: &AppState, recording_id: &str) -> Result<(String, i32), String> {
+    let db = state.db().await;
+    let recording = db
+        .get_recording(recording_id)
+        .map_err(|e| e.to_string())?
+        .ok_or_else(|| format!("Recording not found: {}", recording_id))?;
+    let audio_path = recording
+        .audio_file_path
+        .ok_or_else(|| format!("Recording {} has no audio file", recording_id))?;
+    Ok((audio_path, recording.sample_rate))
+}
+
+/// Decode a recording's audio file and report loudness, peak, noise floor and clipping
+#[tauri::command]
+pub async fn analyze_recording_audio(
+    recording_id: String,
+    state: tauri::State<'_, AppState>,
+) -> Result<AudioQualityReport, String> {
+    let (audio_path, sample_rate) = recording_audio_file(&state, &recording_id).await?;
+    // Decode at the recorded rate so clipping isn't smoothed away by resampling
+    let sample_rate = u32::try_from(sample_rate).ok().filter(|&rate| rate > 0).unwrap_or(48000);
+
+    tokio::task::spawn_blocking(move || {
+        let (samples, sample_rate) = decode_audio_file_at_rate(&audio_path, sample_rate)?;
+        analyze_samples(&recording_id, &samples, sample_rate)
+    })
+    .await
+    .map_err(|e| e.to_string())?
+    .map_err(|e| e.to_string())
+}
+
+/// List silences of at least `min_gap_seconds` found by VAD over the decoded audio
+#[tauri::command]
+pub async fn detect_silence_gaps(
+    recording_id: String,
+    min_gap_seconds: f64,
+    state: tauri::State<'_, AppState>,
+) -> Result<Vec<SilenceGap>, String> {
+    if !min_gap_seconds.is_finite() || min_gap_seconds <= 0.0 {
+        return Err("min_gap_seconds must be greater than zero".to_string());
+    }
+    let (audio_path, _) = recording_audio_file(&state, &recording_id).await?;
+
+    tokio::task::spawn_blocking(move || {
+        let (samples, sample_rate) = decode_audio_file(&audio_path)?;
+        let speech: Vec<(f64, f64)> = get_speech_chunks(&samples, SILENCE_VAD_REDEMPTION_MS)?
+            .iter()
+            .map(|segment| (segment.start_timestamp_ms / 1000.0, segment.end_timestamp_ms / 1000.0))
+            .collect();
+        let total_seconds = samples.len() as f64 / sample_rate as f64;
+        Ok::<_, anyhow::Error>(silence_gaps_between(&speech, total_seconds, min_gap_seconds))
+    })
+    .await
+    .map_err(|e| e.to_string())?
+    .map_err(|e| e.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_noise_floor_uses_quiet_windows() {
+        // 1s of quiet noise followed by 1s of loud tone at 1kHz sample rate
+        let mut samples: Vec<f32> = (0..1000).map(|i| if i % 2 == 0 { 0.001 } else { -0.001 }).collect();
+        samples.extend((0..1000).map(|i| (i as f32 * 0.3).sin() * 0.5));
+
+        let floor = noise_floor_dbfs(&samples, 1000).unwrap();
+        assert!((floor - -60.0).abs() < 0.5, "unexpected noise floor {}", floor);
+        assert_eq!(noise_floor_dbfs(&[], 1000), None);
+    }
+
+    #[test]
+    fn test_report_flags_clipping_and_silence() {
+        let mut samples = vec![0.0f32; 48000];
+        for sample in samples.iter_mut().take(480) {
+            *sample = 1.0;
+        }
+        let report = analyze_samples("rec", &samples, 48000).unwrap();
+        assert_eq!(report.clipped_samples, 480);
+        assert!((report.clipped_percent - 1.0).abs() < 1e-9);
+        assert!(report.issues.iter().any(|issue| issue.contains("clipped")));
+
+        let report = analyze_samples("rec", &vec![0.0f32; 48000], 48000).unwrap();
+        assert_eq!(report.integrated_lufs, None);
+        assert_eq!(report.true_peak_dbtp, None);
+        assert_eq!(report.issues, vec!["Recording is silent".to_string()]);
+    }
+
+    #[test]
+    fn test_silence_gaps_between_speech() {
+        let speech = [(1.0, 3.0), (3.2, 5.0), (2.0, 4.0), (8.0, 9.5)];
+        let gaps = silence_gaps_between(&speech, 12.0, 0.5);
+        let bounds: Vec<(f64, f64)> = gaps.iter().map(|g| (g.start_seconds, g.end_seconds)).collect();
+        assert_eq!(bounds, vec![(0.0, 1.0), (5.0, 8.0), (9.5, 12.0)]);
+        assert!((gaps[1].duration_seconds - 3.0).abs() < 1e-9);
+
+        assert_eq!(silence_gaps_between(&[], 4.0, 1.0).len(), 1);
+        assert!(silence_gaps_between(&[(0.0, 4.0)], 4.0, 0.1).is_empty());
+    }
+}
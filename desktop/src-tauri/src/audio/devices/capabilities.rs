@@ -0,0 +1,255 @@
+// Audio device capability probing
+// Reports the sample rates and channel counts a device supports, so the UI can
+// warn about devices that will force resampling (e.g. Bluetooth headsets at 16kHz),
+// and picks the stream config for a preferred per-device capture sample rate
+
+use std::collections::HashMap;
+use std::sync::RwLock;
+
+use anyhow::{anyhow, Result};
+use cpal::traits::DeviceTrait;
+use log::info;
+use once_cell::sync::Lazy;
+use serde::Serialize;
+
+use super::configuration::{get_device_and_config, AudioDevice, DeviceType};
+
+/// Sample rate the recording pipeline works at
+pub const PIPELINE_SAMPLE_RATE: u32 = 48000;
+
+/// At or below this rate a device is limited to narrowband/wideband voice audio
+const LOW_SAMPLE_RATE: u32 = 16000;
+
+/// Capture sample rate requested per device name
+static PREFERRED_SAMPLE_RATES: Lazy<RwLock<HashMap<String, u32>>> = Lazy::new(|| RwLock::new(HashMap::new()));
+
+/// Request a capture sample rate for a device (None to use the device default).
+/// Takes effect the next time a stream is opened on the device.
+pub fn set_preferred_sample_rate(device_name: &str, sample_rate: Option<u32>) {
+    let mut rates = PREFERRED_SAMPLE_RATES.write().unwrap();
+    match sample_rate {
+        Some(rate) => rates.insert(device_name.to_string(), rate),
+        None => rates.remove(device_name),
+    };
+}
+
+pub fn get_preferred_sample_rates() -> HashMap<String, u32> {
+    PREFERRED_SAMPLE_RATES.read().unwrap().clone()
+}
+
+/// Replace all preferred rates, e.g. from the `capture_sample_rates` setting
+pub fn set_preferred_sample_rates(rates: HashMap<String, u32>) {
+    *PREFERRED_SAMPLE_RATES.write().unwrap() = rates;
+}
+
+/// One supported stream configuration range
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct SupportedConfigRange {
+    pub channels: u16,
+    pub min_sample_rate: u32,
+    pub max_sample_rate: u32,
+    pub sample_format: String,
+}
+
+impl SupportedConfigRange {
+    pub fn supports_rate(&self, sample_rate: u32) -> bool {
+        (self.min_sample_rate..=self.max_sample_rate).contains(&sample_rate)
+    }
+}
+
+impl From<cpal::SupportedStreamConfigRange> for SupportedConfigRange {
+    fn from(range: cpal::SupportedStreamConfigRange) -> Self {
+        Self {
+            channels: range.channels(),
+            min_sample_rate: range.min_sample_rate().0,
+            max_sample_rate: range.max_sample_rate().0,
+            sample_format: range.sample_format().to_string(),
+        }
+    }
+}
+
+/// Supported configurations of a device, with warnings about ones that need resampling
+#[derive(Debug, Clone, Serialize)]
+pub struct DeviceCapabilities {
+    pub device_name: String,
+    pub device_type: DeviceType,
+    pub default_sample_rate: u32,
+    pub default_channels: u16,
+    pub configs: Vec<SupportedConfigRange>,
+    /// Whether the device can capture at the pipeline rate without resampling
+    pub supports_pipeline_rate: bool,
+    pub warnings: Vec<String>,
+}
+
+/// Warnings for configurations that will degrade or resample audio
+pub fn capability_warnings(configs: &[SupportedConfigRange], default_sample_rate: u32) -> Vec<String> {
+    let mut warnings = Vec::new();
+    let max_rate = configs
+        .iter()
+        .map(|c| c.max_sample_rate)
+        .max()
+        .unwrap_or(default_sample_rate);
+
+    if max_rate <= LOW_SAMPLE_RATE {
+        warnings.push(format!(
+            "Device only supports up to {} Hz (e.g. a Bluetooth headset in hands-free mode); transcription quality may suffer",
+            max_rate
+        ));
+    } else if !configs.iter().any(|c| c.supports_rate(PIPELINE_SAMPLE_RATE)) {
+        warnings.push(format!(
+            "Device does not support {} Hz; audio will be resampled",
+            PIPELINE_SAMPLE_RATE
+        ));
+    } else if default_sample_rate != PIPELINE_SAMPLE_RATE {
+        warnings.push(format!(
+            "Device defaults to {} Hz but supports {} Hz if requested",
+            default_sample_rate, PIPELINE_SAMPLE_RATE
+        ));
+    }
+
+    warnings
+}
+
+/// Supported stream configurations of a device, input first.
+/// Output devices captured through a monitor/loopback report input configs on some platforms.
+pub fn supported_configs(device: &cpal::Device) -> Vec<SupportedConfigRange> {
+    let configs: Vec<SupportedConfigRange> = device
+        .supported_input_configs()
+        .map(|ranges| ranges.map(SupportedConfigRange::from).collect())
+        .unwrap_or_default();
+    if !configs.is_empty() {
+        return configs;
+    }
+
+    device
+        .supported_output_configs()
+        .map(|ranges| ranges.map(SupportedConfigRange::from).collect())
+        .unwrap_or_default()
+}
+
+/// Index of the config range to open at `sample_rate`, preferring the default
+/// config's channel count and sample format
+fn choose_config_range(
+    configs: &[SupportedConfigRange],
+    sample_rate: u32,
+    default_channels: u16,
+    default_format: &str,
+) -> Option<usize> {
+    let score = |c: &SupportedConfigRange| {
+        (c.channels == default_channels) as u8 * 2 + (c.sample_format == default_format) as u8
+    };
+    configs
+        .iter()
+        .enumerate()
+        .filter(|(_, c)| c.supports_rate(sample_rate))
+        .max_by_key(|(index, c)| (score(c), std::cmp::Reverse(*index)))
+        .map(|(index, _)| index)
+}
+
+/// Stream config to open: the device default, or the preferred sample rate for
+/// this device if one is set and supported. Unsupported rates fall back to the
+/// default (and resampling in the pipeline).
+pub fn select_stream_config(
+    device: &cpal::Device,
+    device_name: &str,
+    default_config: cpal::SupportedStreamConfig,
+) -> cpal::SupportedStreamConfig {
+    let Some(rate) = get_preferred_sample_rates().get(device_name).copied() else {
+        return default_config;
+    };
+    if default_config.sample_rate().0 == rate {
+        info!("🎚️ {}: default config already runs at the preferred {} Hz", device_name, rate);
+        return default_config;
+    }
+
+    let ranges: Vec<cpal::SupportedStreamConfigRange> = device
+        .supported_input_configs()
+        .map(|ranges| ranges.collect())
+        .unwrap_or_default();
+    let configs: Vec<SupportedConfigRange> = ranges.iter().cloned().map(SupportedConfigRange::from).collect();
+
+    match choose_config_range(
+        &configs,
+        rate,
+        default_config.channels(),
+        &default_config.sample_format().to_string(),
+    ) {
+        Some(index) => {
+            let config = ranges[index].clone().with_sample_rate(cpal::SampleRate(rate));
+            info!("🎚️ {}: opening at the preferred {} Hz ({} channels, {})",
+                  device_name, rate, config.channels(), config.sample_format());
+            config
+        }
+        None => {
+            info!("🎚️ {}: preferred {} Hz is not supported, using the default {} Hz and resampling",
+                  device_name, rate, default_config.sample_rate().0);
+            default_config
+        }
+    }
+}
+
+/// Probe the supported sample rates and channel counts of a device
+pub async fn probe_device_capabilities(device: &AudioDevice) -> Result<DeviceCapabilities> {
+    let (cpal_device, default_config) = get_device_and_config(device).await?;
+    let configs = supported_configs(&cpal_device);
+    if configs.is_empty() {
+        return Err(anyhow!("Device {} reported no supported configurations", device.name));
+    }
+
+    let default_sample_rate = default_config.sample_rate().0;
+    Ok(DeviceCapabilities {
+        device_name: device.name.clone(),
+        device_type: device.device_type.clone(),
+        default_sample_rate,
+        default_channels: default_config.channels(),
+        supports_pipeline_rate: configs.iter().any(|c| c.supports_rate(PIPELINE_SAMPLE_RATE)),
+        warnings: capability_warnings(&configs, default_sample_rate),
+        configs,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn range(channels: u16, min: u32, max: u32) -> SupportedConfigRange {
+        SupportedConfigRange {
+            channels,
+            min_sample_rate: min,
+            max_sample_rate: max,
+            sample_format: "f32".to_string(),
+        }
+    }
+
+    #[test]
+    fn test_capability_warnings() {
+        // Bluetooth hands-free profile
+        let warnings = capability_warnings(&[range(1, 8000, 16000)], 16000);
+        assert_eq!(warnings.len(), 1);
+        assert!(warnings[0].contains("16000 Hz"));
+
+        // 44.1kHz-only device needs resampling
+        let warnings = capability_warnings(&[range(2, 44100, 44100)], 44100);
+        assert!(warnings[0].contains("resampled"));
+
+        // Supports 48kHz when asked for it
+        let warnings = capability_warnings(&[range(2, 44100, 96000)], 44100);
+        assert!(warnings[0].contains("if requested"));
+
+        assert!(capability_warnings(&[range(2, 48000, 48000)], 48000).is_empty());
+    }
+
+    #[test]
+    fn test_choose_config_range() {
+        let mut mono_i16 = range(1, 8000, 48000);
+        mono_i16.sample_format = "i16".to_string();
+        let configs = vec![mono_i16, range(1, 8000, 48000), range(2, 44100, 44100)];
+
+        // Matching channels and format wins among ranges supporting the rate
+        assert_eq!(choose_config_range(&configs, 48000, 1, "f32"), Some(1));
+        assert_eq!(choose_config_range(&configs, 48000, 1, "i16"), Some(0));
+        // A stereo default prefers the stereo range
+        assert_eq!(choose_config_range(&configs, 44100, 2, "f32"), Some(2));
+        assert_eq!(choose_config_range(&configs, 96000, 1, "f32"), None);
+    }
+}
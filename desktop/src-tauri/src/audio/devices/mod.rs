@@ -7,12 +7,17 @@ pub mod speakers;
 pub mod configuration;
 pub mod platform;
 pub mod fallback;
+pub mod capabilities;
 
 // Re-export all public functions to preserve existing API
 pub use discovery::{list_audio_devices, trigger_audio_permission};
 pub use microphone::{default_input_device, find_builtin_input_device};
 pub use speakers::{default_output_device, find_builtin_output_device};
 pub use configuration::{get_device_and_config, parse_audio_device, AudioDevice, DeviceType, DeviceControl, AudioTranscriptionEngine, LAST_AUDIO_CAPTURE};
+pub use capabilities::{
+    probe_device_capabilities, select_stream_config, get_preferred_sample_rates,
+    set_preferred_sample_rate, set_preferred_sample_rates, DeviceCapabilities, SupportedConfigRange,
+};
 
 // Re-export fallback functions (platform-specific)
 #[cfg(target_os = "macos")]
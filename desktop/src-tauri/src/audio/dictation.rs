@@ -0,0 +1,398 @@
+//! Dictation quick-record mode
+//!
+//! A lightweight, mic-only lifecycle separate from `start_recording`:
+//! no system audio, no diarization, no meeting folder. Audio is captured into
+//! memory, transcribed in short chunks with a small Whisper model, and the
+//! plain text is returned on stop. The model loaded before dictation is
+//! restored when it stops, and recording can't start while dictating.
+
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::thread::JoinHandle;
+use std::time::Duration;
+
+use anyhow::{anyhow, Result};
+use cpal::traits::{DeviceTrait, HostTrait, StreamTrait};
+use cpal::{Sample, SampleFormat, StreamConfig};
+use log::{error, info, warn};
+use serde::Serialize;
+use tauri::{AppHandle, Emitter, Runtime};
+
+use super::processing::{audio_to_mono, resample_audio};
+use crate::whisper_engine::{ModelStatus, WhisperEngine};
+
+/// Whisper's expected sample rate
+const WHISPER_SAMPLE_RATE: u32 = 16000;
+
+/// How much audio to accumulate before transcribing a chunk
+const CHUNK_SECONDS: f32 = 5.0;
+
+/// Small, fast models preferred for dictation, in order
+const DICTATION_MODELS: &[&str] = &[
+    "base-q5_1",
+    "base.en",
+    "base",
+    "base-q8_0",
+    "small-q5_1",
+    "tiny-q5_1",
+    "tiny.en",
+    "tiny",
+];
+
+/// Transcript update emitted as `dictation-update`
+#[derive(Debug, Clone, Serialize)]
+pub struct DictationUpdate {
+    /// Text of the chunk that was just transcribed
+    pub text: String,
+    /// Everything transcribed so far
+    pub full_text: String,
+}
+
+struct DictationSession {
+    stop_flag: Arc<AtomicBool>,
+    capture_thread: JoinHandle<()>,
+    transcription_task: tokio::task::JoinHandle<String>,
+    engine: WhisperEngine,
+    /// Model to load again on stop, if dictation replaced it
+    previous_model: Option<String>,
+}
+
+enum DictationState {
+    Idle,
+    /// Claimed by a start or stop that is still swapping the model or the mic
+    Claimed,
+    Active(DictationSession),
+}
+
+static DICTATION_STATE: Mutex<DictationState> = Mutex::new(DictationState::Idle);
+
+pub fn is_dictating() -> bool {
+    !matches!(*DICTATION_STATE.lock().unwrap(), DictationState::Idle)
+}
+
+/// Returns a claimed state to Idle: when a start fails before the session is
+/// active, or when a stop has finished
+struct ClaimGuard;
+
+impl Drop for ClaimGuard {
+    fn drop(&mut self) {
+        let mut state = DICTATION_STATE.lock().unwrap();
+        if matches!(*state, DictationState::Claimed) {
+            *state = DictationState::Idle;
+        }
+    }
+}
+
+/// Capture mic audio on a dedicated thread (cpal streams aren't Send).
+/// Mono samples at the device rate are appended to `buffer`.
+pub(crate) fn spawn_capture_thread(
+    device_name: Option<String>,
+    buffer: Arc<Mutex<Vec<f32>>>,
+    stop_flag: Arc<AtomicBool>,
+    ready_tx: std::sync::mpsc::Sender<Result<u32, String>>,
+) -> JoinHandle<()> {
+    std::thread::spawn(move || {
+        let stream = match build_mic_stream(device_name.as_deref(), buffer) {
+            Ok((stream, sample_rate)) => {
+                let _ = ready_tx.send(Ok(sample_rate));
+                stream
+            }
+            Err(e) => {
+                let _ = ready_tx.send(Err(e.to_string()));
+                return;
+            }
+        };
+
+        while !stop_flag.load(Ordering::SeqCst) {
+            std::thread::sleep(Duration::from_millis(50));
+        }
+
+        drop(stream);
+        info!("🎙️ Mic capture stopped");
+    })
+}
+
+fn build_mic_stream(
+    device_name: Option<&str>,
+    buffer: Arc<Mutex<Vec<f32>>>,
+) -> Result<(cpal::Stream, u32)> {
+    let host = cpal::default_host();
+    let device = match device_name {
+        Some(name) => host
+            .input_devices()?
+            .find(|d| d.name().map(|n| n == name).unwrap_or(false))
+            .ok_or_else(|| anyhow!("Microphone not found: {}", name))?,
+        None => host
+            .default_input_device()
+            .ok_or_else(|| anyhow!("No default input device found"))?,
+    };
+
+    let config = device.default_input_config()?;
+    let sample_rate = config.sample_rate().0;
+    let channels = config.channels();
+    let stream_config: StreamConfig = config.config();
+
+    info!("🎙️ Dictation using '{}' at {}Hz, {} channels",
+          device.name().unwrap_or_default(), sample_rate, channels);
+
+    let push = move |data: &[f32]| {
+        let mono = if channels > 1 {
+            audio_to_mono(data, channels)
+        } else {
+            data.to_vec()
+        };
+        if let Ok(mut buf) = buffer.lock() {
+            buf.extend_from_slice(&mono);
+        }
+    };
+    let on_error = |err| error!("Dictation stream error: {}", err);
+
+    let stream = match config.sample_format() {
+        SampleFormat::F32 => device.build_input_stream(
+            &stream_config,
+            move |data: &[f32], _: &cpal::InputCallbackInfo| push(data),
+            on_error,
+            None,
+        )?,
+        SampleFormat::I16 => device.build_input_stream(
+            &stream_config,
+            move |data: &[i16], _: &cpal::InputCallbackInfo| {
+                let converted: Vec<f32> = data.iter().map(|&s| s.to_sample()).collect();
+                push(&converted);
+            },
+            on_error,
+            None,
+        )?,
+        SampleFormat::U16 => device.build_input_stream(
+            &stream_config,
+            move |data: &[u16], _: &cpal::InputCallbackInfo| {
+                let converted: Vec<f32> = data.iter().map(|&s| s.to_sample()).collect();
+                push(&converted);
+            },
+            on_error,
+            None,
+        )?,
+        other => return Err(anyhow!("Unsupported sample format: {:?}", other)),
+    };
+
+    stream.play()?;
+    Ok((stream, sample_rate))
+}
+
+/// Make sure a small model is loaded, preferring the fast dictation models.
+/// Returns the model used and the model it replaced, if any.
+async fn ensure_dictation_model(engine: &WhisperEngine) -> Result<(String, Option<String>), String> {
+    let models = engine
+        .discover_models()
+        .await
+        .map_err(|e| format!("Failed to discover models: {}", e))?;
+
+    let available: Vec<&str> = models
+        .iter()
+        .filter(|m| matches!(m.status, ModelStatus::Available))
+        .map(|m| m.name.as_str())
+        .collect();
+
+    let current = engine.get_current_model().await;
+
+    let Some(model) = DICTATION_MODELS.iter().find(|m| available.contains(m)) else {
+        // No small model downloaded; fall back to whatever is loaded
+        return match current {
+            Some(model) if engine.is_model_loaded().await => Ok((model, None)),
+            _ => Err("No Whisper model available for dictation. Please download a small model (e.g. base).".to_string()),
+        };
+    };
+
+    let loaded = engine.is_model_loaded().await;
+    if current.as_deref() == Some(*model) && loaded {
+        return Ok((model.to_string(), None));
+    }
+
+    info!("🎙️ Loading '{}' for dictation", model);
+    engine
+        .load_model(model)
+        .await
+        .map_err(|e| format!("Failed to load model {}: {}", model, e))?;
+
+    Ok((model.to_string(), current.filter(|_| loaded)))
+}
+
+/// Load the model dictation replaced
+async fn restore_model(engine: &WhisperEngine, previous_model: Option<String>) {
+    let Some(model) = previous_model else {
+        return;
+    };
+    info!("🎙️ Restoring model '{}' after dictation", model);
+    if let Err(e) = engine.load_model(&model).await {
+        warn!("Failed to restore model '{}' after dictation: {}", model, e);
+    }
+}
+
+/// Drain the capture buffer, resample and transcribe it
+async fn transcribe_pending(
+    engine: &WhisperEngine,
+    buffer: &Arc<Mutex<Vec<f32>>>,
+    device_sample_rate: u32,
+    min_samples: usize,
+) -> Option<String> {
+    let samples = {
+        let mut buf = buffer.lock().unwrap();
+        if buf.len() < min_samples.max(1) {
+            return None;
+        }
+        std::mem::take(&mut *buf)
+    };
+
+    let audio = resample_audio(&samples, device_sample_rate, WHISPER_SAMPLE_RATE);
+    let language = crate::get_language_preference_internal();
+
+    match engine.transcribe_audio(audio, language).await {
+        Ok(text) => {
+            let text = text.trim().to_string();
+            if text.is_empty() { None } else { Some(text) }
+        }
+        Err(e) => {
+            warn!("Dictation chunk transcription failed: {}", e);
+            None
+        }
+    }
+}
+
+/// Start mic-only dictation. Emits `dictation-update` as chunks are transcribed.
+#[tauri::command]
+pub async fn start_dictation<R: Runtime>(
+    app: AppHandle<R>,
+    device_name: Option<String>,
+) -> Result<String, String> {
+    // Check and claim under one lock so two starts can't both proceed
+    {
+        let mut state = DICTATION_STATE.lock().unwrap();
+        if !matches!(*state, DictationState::Idle) {
+            return Err("Dictation already in progress".to_string());
+        }
+        if super::recording::state::is_recording() {
+            return Err("Cannot start dictation while a recording is in progress".to_string());
+        }
+        *state = DictationState::Claimed;
+    }
+    let _claim = ClaimGuard;
+
+    crate::whisper_engine::commands::whisper_init().await?;
+    let engine = crate::whisper_engine::commands::WHISPER_ENGINE
+        .lock()
+        .unwrap()
+        .as_ref()
+        .cloned()
+        .ok_or_else(|| "Whisper engine not initialized".to_string())?;
+
+    let (model, previous_model) = ensure_dictation_model(&engine).await?;
+
+    let buffer = Arc::new(Mutex::new(Vec::new()));
+    let stop_flag = Arc::new(AtomicBool::new(false));
+    let (ready_tx, ready_rx) = std::sync::mpsc::channel();
+
+    let capture_thread = spawn_capture_thread(device_name, buffer.clone(), stop_flag.clone(), ready_tx);
+    let ready = tokio::task::spawn_blocking(move || ready_rx.recv())
+        .await
+        .map_err(|e| e.to_string())
+        .and_then(|ready| ready.map_err(|_| "Dictation capture thread exited unexpectedly".to_string()))
+        .and_then(|ready| ready);
+    let device_sample_rate = match ready {
+        Ok(rate) => rate,
+        Err(e) => {
+            restore_model(&engine, previous_model).await;
+            return Err(e);
+        }
+    };
+
+    let task_engine = engine.clone();
+    let task_stop = stop_flag.clone();
+    let transcription_task = tokio::spawn(async move {
+        let chunk_samples = (device_sample_rate as f32 * CHUNK_SECONDS) as usize;
+        let mut full_text = String::new();
+
+        loop {
+            let stopping = task_stop.load(Ordering::SeqCst);
+            // On stop, flush whatever is left regardless of size
+            let min_samples = if stopping { 1 } else { chunk_samples };
+
+            if let Some(text) = transcribe_pending(&task_engine, &buffer, device_sample_rate, min_samples).await {
+                if !full_text.is_empty() {
+                    full_text.push(' ');
+                }
+                full_text.push_str(&text);
+
+                let _ = app.emit("dictation-update", DictationUpdate {
+                    text,
+                    full_text: full_text.clone(),
+                });
+            }
+
+            if stopping {
+                break;
+            }
+            tokio::time::sleep(Duration::from_millis(250)).await;
+        }
+
+        full_text
+    });
+
+    *DICTATION_STATE.lock().unwrap() = DictationState::Active(DictationSession {
+        stop_flag,
+        capture_thread,
+        transcription_task,
+        engine,
+        previous_model,
+    });
+
+    info!("🎙️ Dictation started with model '{}'", model);
+    Ok(model)
+}
+
+/// Stop dictation and return the full transcribed text
+#[tauri::command]
+pub async fn stop_dictation<R: Runtime>(app: AppHandle<R>) -> Result<String, String> {
+    let session = {
+        let mut state = DICTATION_STATE.lock().unwrap();
+        match std::mem::replace(&mut *state, DictationState::Claimed) {
+            DictationState::Active(session) => session,
+            other => {
+                *state = other;
+                return Err("Dictation is not active".to_string());
+            }
+        }
+    };
+    // Stays claimed until the model is restored, so recording can't start mid-swap
+    let _claim = ClaimGuard;
+
+    session.stop_flag.store(true, Ordering::SeqCst);
+
+    // Wait for capture to end so the last samples are in the buffer
+    let captured = tokio::task::spawn_blocking(move || session.capture_thread.join())
+        .await
+        .map_err(|e| e.to_string())
+        .and_then(|joined| joined.map_err(|_| "Dictation capture thread panicked".to_string()));
+
+    let text = match captured {
+        Ok(()) => session
+            .transcription_task
+            .await
+            .map_err(|e| format!("Dictation transcription failed: {}", e)),
+        Err(e) => {
+            session.transcription_task.abort();
+            Err(e)
+        }
+    };
+    restore_model(&session.engine, session.previous_model).await;
+    let text = text?;
+
+    let _ = app.emit("dictation-stopped", serde_json::json!({ "text": text }));
+    info!("🎙️ Dictation stopped ({} chars)", text.len());
+
+    Ok(text)
+}
+
+#[tauri::command]
+pub async fn is_dictation_active() -> bool {
+    is_dictating()
+}
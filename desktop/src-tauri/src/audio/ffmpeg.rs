@@ -4,21 +4,130 @@ use ffmpeg_sidecar::{
     paths::sidecar_dir,
     version::ffmpeg_version,
 };
-use log::{debug, error};
+use log::{debug, error, info};
 use once_cell::sync::Lazy;
-use std::path::PathBuf;
+use serde::Serialize;
+use std::path::{Path, PathBuf};
+use std::sync::{Mutex, OnceLock};
 use which::which;
 
 #[cfg(not(windows))]
-const EXECUTABLE_NAME: &str = "ffmpeg";
+pub(crate) const EXECUTABLE_NAME: &str = "ffmpeg";
 
 #[cfg(windows)]
-const EXECUTABLE_NAME: &str = "ffmpeg.exe";
+pub(crate) const EXECUTABLE_NAME: &str = "ffmpeg.exe";
 
 static FFMPEG_PATH: Lazy<Option<PathBuf>> = Lazy::new(find_ffmpeg_path_internal);
 
+/// `ffmpeg` folder in the app data dir, holding the copy from `download_ffmpeg`
+static BUNDLED_FFMPEG_DIR: OnceLock<PathBuf> = OnceLock::new();
+
+/// Set the folder for the downloaded FFmpeg (called once at startup)
+pub fn set_bundled_ffmpeg_dir(dir: PathBuf) {
+    let _ = BUNDLED_FFMPEG_DIR.set(dir);
+}
+
+pub(crate) fn bundled_ffmpeg_dir() -> Option<&'static PathBuf> {
+    BUNDLED_FFMPEG_DIR.get()
+}
+
+/// The downloaded FFmpeg, if it has been installed
+pub fn bundled_ffmpeg_path() -> Option<PathBuf> {
+    bundled_ffmpeg_dir()
+        .map(|dir| dir.join(EXECUTABLE_NAME))
+        .filter(|path| path.is_file())
+}
+
+/// Path to FFmpeg, preferring the downloaded copy in the app data dir
 pub fn find_ffmpeg_path() -> Option<PathBuf> {
-    FFMPEG_PATH.as_ref().map(|p| p.clone())
+    bundled_ffmpeg_path().or_else(|| FFMPEG_PATH.as_ref().map(|p| p.clone()))
+}
+
+/// Whether FFmpeg could be found and run, and which version it is
+#[derive(Debug, Clone, Serialize)]
+pub struct FfmpegStatus {
+    pub available: bool,
+    pub path: Option<String>,
+    /// Version from `ffmpeg -version`, e.g. "7.1" or "N-118000-g1234abcd"
+    pub version: Option<String>,
+    pub error: Option<String>,
+}
+
+/// Result of the last `check_ffmpeg`
+pub(crate) static FFMPEG_STATUS: Mutex<Option<FfmpegStatus>> = Mutex::new(None);
+
+/// Version from the first line of `ffmpeg -version` ("ffmpeg version 7.1 Copyright ...")
+fn parse_ffmpeg_version(output: &str) -> Option<String> {
+    output
+        .lines()
+        .next()?
+        .strip_prefix("ffmpeg version ")?
+        .split_whitespace()
+        .next()
+        .map(str::to_string)
+}
+
+pub(crate) fn run_ffmpeg_version(path: &Path) -> Result<String, String> {
+    let mut command = std::process::Command::new(path);
+    command.arg("-version");
+
+    #[cfg(target_os = "windows")]
+    {
+        use std::os::windows::process::CommandExt;
+        const CREATE_NO_WINDOW: u32 = 0x08000000;
+        command.creation_flags(CREATE_NO_WINDOW);
+    }
+
+    let output = command
+        .output()
+        .map_err(|e| format!("Failed to run {}: {}", path.display(), e))?;
+    if !output.status.success() {
+        return Err(format!("{} -version exited with {}", path.display(), output.status));
+    }
+
+    parse_ffmpeg_version(&String::from_utf8_lossy(&output.stdout))
+        .ok_or_else(|| format!("Unrecognized output from {} -version", path.display()))
+}
+
+pub(crate) fn detect_ffmpeg_status() -> FfmpegStatus {
+    let Some(path) = find_ffmpeg_path() else {
+        return FfmpegStatus {
+            available: false,
+            path: None,
+            version: None,
+            error: Some("FFmpeg was not found".to_string()),
+        };
+    };
+
+    let (version, error) = match run_ffmpeg_version(&path) {
+        Ok(version) => (Some(version), None),
+        Err(e) => (None, Some(e)),
+    };
+    FfmpegStatus {
+        available: version.is_some(),
+        path: Some(path.to_string_lossy().to_string()),
+        version,
+        error,
+    }
+}
+
+/// Locate FFmpeg and run `-version` so missing or broken installs can be
+/// reported upfront. The result is cached; pass `refresh` to check again.
+#[tauri::command]
+pub async fn check_ffmpeg(refresh: Option<bool>) -> Result<FfmpegStatus, String> {
+    if !refresh.unwrap_or(false) {
+        if let Some(status) = FFMPEG_STATUS.lock().unwrap().clone() {
+            return Ok(status);
+        }
+    }
+
+    let status = tokio::task::spawn_blocking(detect_ffmpeg_status)
+        .await
+        .map_err(|e| e.to_string())?;
+    info!("FFmpeg check: available={}, version={:?}", status.available, status.version);
+
+    *FFMPEG_STATUS.lock().unwrap() = Some(status.clone());
+    Ok(status)
 }
 
 fn find_ffmpeg_path_internal() -> Option<PathBuf> {
@@ -196,3 +305,22 @@ fn get_ffmpeg_install_dir() -> Result<PathBuf, anyhow::Error> {
     // Your existing logic for other platforms
     sidecar_dir().map_err(|e| anyhow::anyhow!(e))
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_ffmpeg_version() {
+        assert_eq!(
+            parse_ffmpeg_version("ffmpeg version 7.1 Copyright (c) 2000-2024 the FFmpeg developers\nbuilt with clang"),
+            Some("7.1".to_string())
+        );
+        assert_eq!(
+            parse_ffmpeg_version("ffmpeg version N-118000-g1234abcd-tessus https://evermeet.cx/ffmpeg/"),
+            Some("N-118000-g1234abcd-tessus".to_string())
+        );
+        assert_eq!(parse_ffmpeg_version("command not found"), None);
+        assert_eq!(parse_ffmpeg_version(""), None);
+    }
+}
@@ -0,0 +1,318 @@
+//! Download of a static FFmpeg build
+//!
+//! When FFmpeg is missing, `download_ffmpeg` installs a static build of the
+//! FFmpeg release branch into the `ffmpeg` folder of the app data dir, which
+//! `find_ffmpeg_path` prefers over any other copy. The archive must match both
+//! the SHA-256 GitHub computed for the release asset and the one published in
+//! the release's checksum file, so a tampered checksum file alone can't pass,
+//! and the extracted binary must run `-version` before it replaces anything.
+//!
+//! There are no such builds for macOS, and the builds available there publish
+//! no checksum, so macOS users are asked to install FFmpeg themselves.
+
+use std::io::Read;
+use std::path::{Path, PathBuf};
+
+use anyhow::{anyhow, bail, Context, Result};
+use futures_util::StreamExt;
+use log::{info, warn};
+use sha2::{Digest, Sha256};
+use tauri::{AppHandle, Emitter, Runtime};
+use tokio::io::AsyncWriteExt;
+
+use super::ffmpeg::{
+    bundled_ffmpeg_dir, bundled_ffmpeg_path, detect_ffmpeg_status, run_ffmpeg_version, FfmpegStatus,
+    EXECUTABLE_NAME, FFMPEG_STATUS,
+};
+
+/// Static builds published with a `checksums.sha256` file
+const BUILDS_BASE_URL: &str = "https://github.com/BtbN/FFmpeg-Builds/releases/download/latest";
+const CHECKSUMS_FILE_NAME: &str = "checksums.sha256";
+/// Release metadata with the digest GitHub computed for each asset
+const RELEASE_API_URL: &str = "https://api.github.com/repos/BtbN/FFmpeg-Builds/releases/tags/latest";
+/// FFmpeg release branch to install
+const FFMPEG_RELEASE: &str = "7.1";
+
+/// Archive name of the build for the current platform
+fn platform_archive_name() -> Option<String> {
+    let target = match (std::env::consts::OS, std::env::consts::ARCH) {
+        ("linux", "x86_64") => "linux64",
+        ("linux", "aarch64") => "linuxarm64",
+        ("windows", "x86_64") => "win64",
+        ("windows", "aarch64") => "winarm64",
+        _ => return None,
+    };
+    let extension = if cfg!(windows) { "zip" } else { "tar.xz" };
+    Some(format!(
+        "ffmpeg-n{release}-latest-{target}-gpl-{release}.{extension}",
+        release = FFMPEG_RELEASE
+    ))
+}
+
+/// Expected hash of `file_name` from a `sha256sum`-style list ("<hash>  <name>")
+fn find_checksum(checksums: &str, file_name: &str) -> Option<String> {
+    checksums.lines().find_map(|line| {
+        let mut parts = line.split_whitespace();
+        let hash = parts.next()?;
+        let name = parts.next()?.trim_start_matches('*');
+        (name == file_name && hash.len() == 64 && hash.chars().all(|c| c.is_ascii_hexdigit()))
+            .then(|| hash.to_ascii_lowercase())
+    })
+}
+
+/// SHA-256 GitHub computed for a release asset (`"digest": "sha256:<hash>"`)
+fn find_asset_digest(release: &serde_json::Value, file_name: &str) -> Option<String> {
+    release
+        .get("assets")?
+        .as_array()?
+        .iter()
+        .find(|asset| asset.get("name").and_then(|n| n.as_str()) == Some(file_name))?
+        .get("digest")?
+        .as_str()?
+        .strip_prefix("sha256:")
+        .filter(|hash| hash.len() == 64 && hash.chars().all(|c| c.is_ascii_hexdigit()))
+        .map(|hash| hash.to_ascii_lowercase())
+}
+
+/// Hash the archive must have: GitHub's digest, which the published checksum has to agree with
+async fn expected_archive_hash(archive_name: &str) -> Result<String> {
+    let client = reqwest::Client::builder()
+        .user_agent(concat!("meeting-local/", env!("CARGO_PKG_VERSION")))
+        .build()?;
+
+    let release: serde_json::Value = client
+        .get(RELEASE_API_URL)
+        .send()
+        .await
+        .and_then(|r| r.error_for_status())
+        .context("Failed to fetch FFmpeg release info")?
+        .json()
+        .await
+        .context("Invalid FFmpeg release info")?;
+    let digest = find_asset_digest(&release, archive_name)
+        .ok_or_else(|| anyhow!("No digest available for {}", archive_name))?;
+
+    let checksums = client
+        .get(format!("{}/{}", BUILDS_BASE_URL, CHECKSUMS_FILE_NAME))
+        .send()
+        .await
+        .and_then(|r| r.error_for_status())
+        .context("Failed to fetch FFmpeg checksums")?
+        .text()
+        .await?;
+    let published = find_checksum(&checksums, archive_name)
+        .ok_or_else(|| anyhow!("No checksum published for {}", archive_name))?;
+
+    if digest != published {
+        bail!("Published checksum of {} does not match its release digest", archive_name);
+    }
+    Ok(digest)
+}
+
+/// Whether an archive entry is the FFmpeg executable (`<build>/bin/ffmpeg`)
+fn is_ffmpeg_entry(path: &Path) -> bool {
+    path.file_name().and_then(|n| n.to_str()) == Some(EXECUTABLE_NAME)
+        && path.parent().and_then(|p| p.file_name()).and_then(|n| n.to_str()) == Some("bin")
+}
+
+/// Copy the FFmpeg executable out of the archive to `dest`
+fn extract_ffmpeg(archive: &Path, dest: &Path) -> Result<()> {
+    let file = std::fs::File::open(archive).context("Failed to open FFmpeg archive")?;
+
+    let write_entry = |reader: &mut dyn Read| -> Result<()> {
+        let mut out = std::fs::File::create(dest)
+            .with_context(|| format!("Failed to create {}", dest.display()))?;
+        std::io::copy(reader, &mut out).context("Failed to extract FFmpeg")?;
+        Ok(())
+    };
+
+    if archive.extension().and_then(|e| e.to_str()) == Some("zip") {
+        let mut zip = zip::ZipArchive::new(file).context("Invalid FFmpeg archive")?;
+        for index in 0..zip.len() {
+            let mut entry = zip.by_index(index)?;
+            if entry.enclosed_name().is_some_and(|path| is_ffmpeg_entry(&path)) {
+                return write_entry(&mut entry);
+            }
+        }
+    } else {
+        let mut tar = tar::Archive::new(xz2::read::XzDecoder::new(file));
+        for entry in tar.entries().context("Invalid FFmpeg archive")? {
+            let mut entry = entry?;
+            if is_ffmpeg_entry(&entry.path()?) {
+                return write_entry(&mut entry);
+            }
+        }
+    }
+
+    bail!("FFmpeg executable not found in the archive")
+}
+
+#[cfg(unix)]
+fn make_executable(path: &Path) -> Result<()> {
+    use std::os::unix::fs::PermissionsExt;
+    std::fs::set_permissions(path, std::fs::Permissions::from_mode(0o755))
+        .context("Failed to mark FFmpeg as executable")
+}
+
+#[cfg(not(unix))]
+fn make_executable(_path: &Path) -> Result<()> {
+    Ok(())
+}
+
+/// Stream `url` to `dest`, returning the SHA-256 of the downloaded bytes
+async fn download_with_hash<R: Runtime>(app: &AppHandle<R>, url: &str, dest: &Path) -> Result<String> {
+    let response = reqwest::get(url).await.context("Failed to start FFmpeg download")?;
+    if !response.status().is_success() {
+        bail!("FFmpeg download failed with status: {}", response.status());
+    }
+
+    let total = response.content_length().unwrap_or(0);
+    let mut file = tokio::fs::File::create(dest)
+        .await
+        .with_context(|| format!("Failed to create {}", dest.display()))?;
+    let mut hasher = Sha256::new();
+    let mut downloaded = 0u64;
+    let mut last_progress = 0u8;
+
+    let mut stream = response.bytes_stream();
+    while let Some(chunk) = stream.next().await {
+        let chunk = chunk.context("Failed to read FFmpeg download")?;
+        file.write_all(&chunk).await.context("Failed to write FFmpeg download")?;
+        hasher.update(&chunk);
+        downloaded += chunk.len() as u64;
+
+        let progress = if total > 0 { (downloaded * 100 / total) as u8 } else { 0 };
+        if progress > last_progress {
+            last_progress = progress;
+            let _ = app.emit("ffmpeg-download-progress", serde_json::json!({
+                "progress": progress,
+                "downloadedBytes": downloaded,
+                "totalBytes": total,
+            }));
+        }
+    }
+    file.flush().await?;
+
+    Ok(format!("{:x}", hasher.finalize()))
+}
+
+async fn install_bundled_ffmpeg<R: Runtime>(app: &AppHandle<R>, install_dir: &Path) -> Result<PathBuf> {
+    // No verifiable build exists for other platforms (e.g. macOS)
+    let Some(archive_name) = platform_archive_name() else {
+        bail!(
+            "No FFmpeg download is available for {}/{}. Please install FFmpeg with your package manager.",
+            std::env::consts::OS,
+            std::env::consts::ARCH
+        );
+    };
+
+    let expected = expected_archive_hash(&archive_name).await?;
+
+    tokio::fs::create_dir_all(install_dir)
+        .await
+        .with_context(|| format!("Failed to create {}", install_dir.display()))?;
+    let archive_path = install_dir.join(&archive_name);
+
+    info!("⬇️ Downloading FFmpeg {} from {}", FFMPEG_RELEASE, BUILDS_BASE_URL);
+    let actual = download_with_hash(app, &format!("{}/{}", BUILDS_BASE_URL, archive_name), &archive_path).await;
+    let actual = match actual {
+        Ok(hash) => hash,
+        Err(e) => {
+            let _ = std::fs::remove_file(&archive_path);
+            return Err(e);
+        }
+    };
+    if actual != expected {
+        let _ = std::fs::remove_file(&archive_path);
+        bail!("FFmpeg download is corrupted (SHA-256 {} does not match {})", actual, expected);
+    }
+
+    // Extracted next to the install location, keeping the executable's name so it runs on Windows
+    let ffmpeg_path = install_dir.join(EXECUTABLE_NAME);
+    let staging_dir = install_dir.join(".staging");
+    let staged_path = staging_dir.join(EXECUTABLE_NAME);
+    std::fs::create_dir_all(&staging_dir).context("Failed to create FFmpeg staging folder")?;
+    let staged = staged_path.clone();
+    let archive = archive_path.clone();
+    let result = tokio::task::spawn_blocking(move || -> Result<String> {
+        extract_ffmpeg(&archive, &staged)?;
+        make_executable(&staged)?;
+        run_ffmpeg_version(&staged).map_err(|e| anyhow!(e))
+    })
+    .await
+    .map_err(|e| anyhow!(e))
+    .and_then(|r| r);
+
+    let _ = std::fs::remove_file(&archive_path);
+    let installed = result.and_then(|version| {
+        std::fs::rename(&staged_path, &ffmpeg_path).context("Failed to install FFmpeg")?;
+        Ok(version)
+    });
+    let _ = std::fs::remove_dir_all(&staging_dir);
+    let version = installed?;
+
+    info!("✅ Installed FFmpeg {} at {}", version, ffmpeg_path.display());
+    Ok(ffmpeg_path)
+}
+
+/// Download a static FFmpeg build into the app data dir if none is installed there.
+/// Emits `ffmpeg-download-progress` while downloading; returns the new FFmpeg status.
+#[tauri::command]
+pub async fn download_ffmpeg<R: Runtime>(app: AppHandle<R>) -> Result<FfmpegStatus, String> {
+    if bundled_ffmpeg_path().is_none() {
+        let install_dir = bundled_ffmpeg_dir()
+            .cloned()
+            .ok_or_else(|| "FFmpeg install folder is not set".to_string())?;
+        if let Err(e) = install_bundled_ffmpeg(&app, &install_dir).await {
+            warn!("FFmpeg download failed: {:#}", e);
+            return Err(format!("{:#}", e));
+        }
+    }
+
+    let status = tokio::task::spawn_blocking(detect_ffmpeg_status)
+        .await
+        .map_err(|e| e.to_string())?;
+    *FFMPEG_STATUS.lock().unwrap() = Some(status.clone());
+    Ok(status)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_find_checksum() {
+        let hash = "ab".repeat(32);
+        let checksums = format!(
+            "{}  ffmpeg-n7.1-latest-win64-gpl-7.1.zip\n{}  ffmpeg-n7.1-latest-linux64-gpl-7.1.tar.xz\n",
+            "cd".repeat(32),
+            hash.to_uppercase()
+        );
+        assert_eq!(find_checksum(&checksums, "ffmpeg-n7.1-latest-linux64-gpl-7.1.tar.xz"), Some(hash));
+        assert_eq!(find_checksum(&checksums, "ffmpeg-n7.1-latest-linux64-gpl-7.1.tar"), None);
+        assert_eq!(find_checksum("nothex  file.zip", "file.zip"), None);
+    }
+
+    #[test]
+    fn test_find_asset_digest() {
+        let hash = "ab".repeat(32);
+        let release = serde_json::json!({
+            "assets": [
+                { "name": "checksums.sha256", "digest": format!("sha256:{}", "cd".repeat(32)) },
+                { "name": "ffmpeg-n7.1-latest-linux64-gpl-7.1.tar.xz", "digest": format!("sha256:{}", hash.to_uppercase()) },
+                { "name": "ffmpeg-n7.1-latest-win64-gpl-7.1.zip", "digest": null },
+            ]
+        });
+        assert_eq!(find_asset_digest(&release, "ffmpeg-n7.1-latest-linux64-gpl-7.1.tar.xz"), Some(hash));
+        assert_eq!(find_asset_digest(&release, "ffmpeg-n7.1-latest-win64-gpl-7.1.zip"), None);
+        assert_eq!(find_asset_digest(&release, "missing.zip"), None);
+    }
+
+    #[test]
+    fn test_is_ffmpeg_entry() {
+        let build = "ffmpeg-n7.1-latest-linux64-gpl-7.1";
+        assert!(is_ffmpeg_entry(&Path::new(build).join("bin").join(EXECUTABLE_NAME)));
+        assert!(!is_ffmpeg_entry(&Path::new(build).join("bin").join("ffprobe")));
+        assert!(!is_ffmpeg_entry(&Path::new(build).join("doc").join(EXECUTABLE_NAME)));
+    }
+}
@@ -1,5 +1,5 @@
 use std::sync::OnceLock;
-use log::info;
+use log::{info, warn};
 use serde::Serialize;
 use sysinfo::System;
 
@@ -11,6 +11,18 @@ pub struct HardwareProfile {
     pub gpu_type: GpuType,
     pub memory_gb: u8,
     pub performance_tier: PerformanceTier,
+    /// Per-GPU memory, empty when it couldn't be measured
+    pub gpus: Vec<GpuMemoryInfo>,
+}
+
+/// Memory information for a single GPU
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct GpuMemoryInfo {
+    pub name: String,
+    pub total_vram_mb: u64,
+    pub available_vram_mb: u64,
+    /// GPU shares system memory (Apple Silicon), so VRAM and RAM are the same pool
+    pub unified_memory: bool,
 }
 
 #[derive(Debug, Clone, PartialEq, Serialize)]
@@ -65,6 +77,7 @@ impl HardwareProfile {
         let (has_gpu_acceleration, gpu_type) = Self::detect_gpu();
         let memory_gb = Self::detect_memory_gb();
         let performance_tier = Self::calculate_performance_tier(cpu_cores, &gpu_type, memory_gb);
+        let gpus = Self::query_gpu_memory(&gpu_type);
 
         HardwareProfile {
             cpu_cores,
@@ -72,9 +85,100 @@ impl HardwareProfile {
             gpu_type,
             memory_gb,
             performance_tier,
+            gpus,
         }
     }
 
+    /// Largest total VRAM across detected GPUs, in MB
+    pub fn total_vram_mb(&self) -> Option<u64> {
+        self.gpus.iter().map(|g| g.total_vram_mb).max()
+    }
+
+    /// Largest total VRAM of a dedicated (non-unified) GPU, in MB
+    pub fn dedicated_vram_mb(&self) -> Option<u64> {
+        self.gpus
+            .iter()
+            .filter(|g| !g.unified_memory)
+            .map(|g| g.total_vram_mb)
+            .max()
+    }
+
+    /// Query current GPU memory (not cached, so available VRAM is live)
+    pub fn query_gpu_memory(gpu_type: &GpuType) -> Vec<GpuMemoryInfo> {
+        match gpu_type {
+            GpuType::Cuda => Self::query_cuda_memory(),
+            GpuType::Metal => Self::query_unified_memory(),
+            _ => Vec::new(),
+        }
+    }
+
+    /// Query NVIDIA GPUs through nvidia-smi
+    fn query_cuda_memory() -> Vec<GpuMemoryInfo> {
+        let mut command = std::process::Command::new("nvidia-smi");
+        command.args([
+            "--query-gpu=name,memory.total,memory.free",
+            "--format=csv,noheader,nounits",
+        ]);
+
+        #[cfg(target_os = "windows")]
+        {
+            use std::os::windows::process::CommandExt;
+            const CREATE_NO_WINDOW: u32 = 0x08000000;
+            command.creation_flags(CREATE_NO_WINDOW);
+        }
+
+        match command.output() {
+            Ok(output) if output.status.success() => {
+                let gpus = Self::parse_nvidia_smi_output(&String::from_utf8_lossy(&output.stdout));
+                info!("Detected CUDA GPU memory: {:?}", gpus);
+                gpus
+            }
+            Ok(output) => {
+                warn!("nvidia-smi failed: {}", String::from_utf8_lossy(&output.stderr).trim());
+                Vec::new()
+            }
+            Err(e) => {
+                warn!("Could not run nvidia-smi to query VRAM: {}", e);
+                Vec::new()
+            }
+        }
+    }
+
+    /// Parse `nvidia-smi --query-gpu=name,memory.total,memory.free --format=csv,noheader,nounits`
+    fn parse_nvidia_smi_output(output: &str) -> Vec<GpuMemoryInfo> {
+        output
+            .lines()
+            .filter_map(|line| {
+                let mut parts = line.rsplitn(3, ',').map(str::trim);
+                let free = parts.next()?.parse::<u64>().ok()?;
+                let total = parts.next()?.parse::<u64>().ok()?;
+                let name = parts.next()?.to_string();
+                Some(GpuMemoryInfo {
+                    name,
+                    total_vram_mb: total,
+                    available_vram_mb: free.min(total),
+                    unified_memory: false,
+                })
+            })
+            .collect()
+    }
+
+    /// Apple Silicon shares system memory with the GPU. Metal caps the working
+    /// set at roughly 75% of RAM, so report that as the usable VRAM.
+    fn query_unified_memory() -> Vec<GpuMemoryInfo> {
+        let mut sys = System::new();
+        sys.refresh_memory();
+        let total_mb = sys.total_memory() / 1_048_576 * 3 / 4;
+        let available_mb = (sys.available_memory() / 1_048_576).min(total_mb);
+
+        vec![GpuMemoryInfo {
+            name: "Apple Silicon GPU".to_string(),
+            total_vram_mb: total_mb,
+            available_vram_mb: available_mb,
+            unified_memory: true,
+        }]
+    }
+
     /// Detect number of CPU cores
     fn detect_cpu_cores() -> u8 {
         std::thread::available_parallelism()
@@ -314,4 +418,17 @@ mod tests {
         let high_tier = HardwareProfile::calculate_performance_tier(8, &GpuType::Metal, 16);
         assert_eq!(high_tier, PerformanceTier::Ultra);
     }
+
+    #[test]
+    fn test_parse_nvidia_smi_output() {
+        let output = "NVIDIA GeForce RTX 3080, 10240, 9500\nNVIDIA RTX A6000, 49140, 100\ngarbage line\n";
+        let gpus = HardwareProfile::parse_nvidia_smi_output(output);
+
+        assert_eq!(gpus.len(), 2);
+        assert_eq!(gpus[0].name, "NVIDIA GeForce RTX 3080");
+        assert_eq!(gpus[0].total_vram_mb, 10240);
+        assert_eq!(gpus[0].available_vram_mb, 9500);
+        assert_eq!(gpus[1].total_vram_mb, 49140);
+        assert!(!gpus[1].unified_memory);
+    }
 }
\ No newline at end of file
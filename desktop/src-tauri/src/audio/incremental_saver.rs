@@ -1,4 +1,4 @@
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use anyhow::{Result, anyhow};
 use log::{info, warn, error};
 use super::encode::encode_single_audio;
@@ -7,7 +7,7 @@ use super::recording_state::AudioChunk;
 #[cfg (target_os = "macos")]
 use super::ffmpeg::find_ffmpeg_path;
 
-/// Audio data without device type (we only store mixed audio)
+/// Audio data without device type (mixed mono, or interleaved mic/system stereo)
 #[derive(Clone)]
 struct AudioData {
     data: Vec<f32>,
@@ -18,11 +18,12 @@ struct AudioData {
 /// to minimize memory usage and enable crash recovery
 pub struct IncrementalAudioSaver {
     checkpoint_buffer: Vec<AudioData>,
-    checkpoint_interval_samples: usize,  // 30s at 48kHz = 1,440,000 samples
+    checkpoint_interval_samples: usize,  // 30s at 48kHz = 1,440,000 samples per channel
     checkpoint_count: u32,
     checkpoints_dir: PathBuf,
     meeting_folder: PathBuf,
     sample_rate: u32,
+    channels: u16,
 }
 
 impl IncrementalAudioSaver {
@@ -32,6 +33,12 @@ impl IncrementalAudioSaver {
     /// * `meeting_folder` - Path to the meeting folder (contains .checkpoints/)
     /// * `sample_rate` - Sample rate of audio (typically 48000)
     pub fn new(meeting_folder: PathBuf, sample_rate: u32) -> Result<Self> {
+        Self::with_channels(meeting_folder, sample_rate, 1)
+    }
+
+    /// Create a saver for interleaved audio with the given channel count
+    pub fn with_channels(meeting_folder: PathBuf, sample_rate: u32, channels: u16) -> Result<Self> {
+        let channels = channels.max(1);
         let checkpoints_dir = meeting_folder.join(".checkpoints");
 
         // Verify checkpoints directory exists
@@ -41,11 +48,12 @@ impl IncrementalAudioSaver {
 
         Ok(Self {
             checkpoint_buffer: Vec::new(),
-            checkpoint_interval_samples: sample_rate as usize * 30, // 30 seconds
+            checkpoint_interval_samples: sample_rate as usize * channels as usize * 30, // 30 seconds
             checkpoint_count: 0,
             checkpoints_dir,
             meeting_folder,
             sample_rate,
+            channels,
         })
     }
 
@@ -96,11 +104,11 @@ impl IncrementalAudioSaver {
         encode_single_audio(
             bytemuck::cast_slice(&audio_data),
             self.sample_rate,
-            1,  // mono
+            self.channels,
             &checkpoint_path
         )?;
 
-        let duration_seconds = audio_data.len() as f32 / self.sample_rate as f32;
+        let duration_seconds = audio_data.len() as f32 / (self.sample_rate as f32 * self.channels as f32);
         self.checkpoint_count += 1;
 
         info!("💾 Saved checkpoint {}: {:.2}s of audio ({} samples)",
@@ -145,88 +153,119 @@ impl IncrementalAudioSaver {
     }
 
     /// Merge all checkpoint files into final audio.mp4 using FFmpeg concat
-    /// Uses concat demuxer for fast merging without re-encoding
     async fn merge_checkpoints(&self, output: &PathBuf) -> Result<()> {
-        info!("Merging {} checkpoints into final audio file...", self.checkpoint_count);
+        merge_checkpoint_files(&self.checkpoints_dir, self.checkpoint_count, output).await
+    }
+
+    /// Get the meeting folder path
+    pub fn get_meeting_folder(&self) -> &PathBuf {
+        &self.meeting_folder
+    }
 
-        // Create concat list file for FFmpeg
-        let list_file = self.checkpoints_dir.join("concat_list.txt");
-        let mut list_content = String::new();
+    /// Get current checkpoint count
+    pub fn get_checkpoint_count(&self) -> u32 {
+        self.checkpoint_count
+    }
+}
 
-        for i in 0..self.checkpoint_count {
-            let checkpoint_path = self.checkpoints_dir
-                .join(format!("audio_chunk_{:03}.mp4", i));
+/// Merge `audio_chunk_000.mp4`.. checkpoint files into a single audio file using FFmpeg concat
+/// Uses concat demuxer for fast merging without re-encoding
+async fn merge_checkpoint_files(checkpoints_dir: &Path, checkpoint_count: u32, output: &PathBuf) -> Result<()> {
+    info!("Merging {} checkpoints into final audio file...", checkpoint_count);
 
-            // Verify checkpoint exists
-            if !checkpoint_path.exists() {
-                return Err(anyhow!("Checkpoint file missing: {}", checkpoint_path.display()));
-            }
+    // Create concat list file for FFmpeg
+    let list_file = checkpoints_dir.join("concat_list.txt");
+    let mut list_content = String::new();
 
-            // Use absolute path for FFmpeg (required for safe mode)
-            let abs_path = checkpoint_path.canonicalize()?;
-            list_content.push_str(&format!("file '{}'\n", abs_path.display()));
-        }
+    for i in 0..checkpoint_count {
+        let checkpoint_path = checkpoints_dir
+            .join(format!("audio_chunk_{:03}.mp4", i));
 
-        std::fs::write(&list_file, list_content)?;
-
-        #[cfg(target_os = "macos")]
-        let ffmpeg_path = find_ffmpeg_path()
-            .ok_or_else(|| anyhow!("FFmpeg not found. Please install FFmpeg to finalize recordings."))?;
-        
-        #[cfg(not(target_os = "macos"))]
-        let ffmpeg_path = "ffmpeg";  // Assume ffmpeg is in PATH on Windows/Linux
-        info!("Using FFmpeg at: {:?}", ffmpeg_path);
-
-        // Run FFmpeg concat command
-        // Using concat demuxer with copy codec for fast merging (no re-encoding)
-        
-        let mut command = std::process::Command::new(ffmpeg_path);
-        
-        command.args(&[
-            "-f", "concat",          // Use concat demuxer
-            "-safe", "0",            // Allow absolute paths
-            "-i", list_file.to_str().unwrap(),
-            "-c", "copy",            // Copy codec - no re-encoding!
-            "-y",                    // Overwrite output file
-            output.to_str().unwrap()
-        ]);
-
-        // Hide console window on Windows to prevent CMD popup during finalization
-        #[cfg(target_os = "windows")]
-        {
-            use std::os::windows::process::CommandExt;
-            const CREATE_NO_WINDOW: u32 = 0x08000000;
-            command.creation_flags(CREATE_NO_WINDOW);
+        // Verify checkpoint exists
+        if !checkpoint_path.exists() {
+            return Err(anyhow!("Checkpoint file missing: {}", checkpoint_path.display()));
         }
 
-        let ffmpeg_output = command.output()?;
+        // Use absolute path for FFmpeg (required for safe mode)
+        let abs_path = checkpoint_path.canonicalize()?;
+        list_content.push_str(&format!("file '{}'\n", abs_path.display()));
+    }
 
-        if !ffmpeg_output.status.success() {
-            let stderr = String::from_utf8_lossy(&ffmpeg_output.stderr);
-            error!("FFmpeg merge failed: {}", stderr);
-            return Err(anyhow!("FFmpeg concat failed: {}", stderr));
-        }
+    std::fs::write(&list_file, list_content)?;
+
+    // Also finds the copy installed by download_ffmpeg, which isn't on PATH
+    let ffmpeg_path = find_ffmpeg_path()
+        .ok_or_else(|| anyhow!("FFmpeg not found. Please install FFmpeg to finalize recordings."))?;
+    info!("Using FFmpeg at: {:?}", ffmpeg_path);
+
+    // Run FFmpeg concat command
+    // Using concat demuxer with copy codec for fast merging (no re-encoding)
+    
+    let mut command = std::process::Command::new(ffmpeg_path);
+    
+    command.args(&[
+        "-f", "concat",          // Use concat demuxer
+        "-safe", "0",            // Allow absolute paths
+        "-i", list_file.to_str().unwrap(),
+        "-c", "copy",            // Copy codec - no re-encoding!
+        "-y",                    // Overwrite output file
+        output.to_str().unwrap()
+    ]);
+
+    // Hide console window on Windows to prevent CMD popup during finalization
+    #[cfg(target_os = "windows")]
+    {
+        use std::os::windows::process::CommandExt;
+        const CREATE_NO_WINDOW: u32 = 0x08000000;
+        command.creation_flags(CREATE_NO_WINDOW);
+    }
 
-        // Verify output file was created
-        if !output.exists() {
-            return Err(anyhow!("Merged audio file was not created: {}", output.display()));
-        }
+    let ffmpeg_output = command.output()?;
 
-        info!("✅ Successfully merged {} checkpoints → {}",
-              self.checkpoint_count, output.display());
+    if !ffmpeg_output.status.success() {
+        let stderr = String::from_utf8_lossy(&ffmpeg_output.stderr);
+        error!("FFmpeg merge failed: {}", stderr);
+        return Err(anyhow!("FFmpeg concat failed: {}", stderr));
+    }
 
-        Ok(())
+    // Verify output file was created
+    if !output.exists() {
+        return Err(anyhow!("Merged audio file was not created: {}", output.display()));
     }
 
-    /// Get the meeting folder path
-    pub fn get_meeting_folder(&self) -> &PathBuf {
-        &self.meeting_folder
+    info!("✅ Successfully merged {} checkpoints → {}",
+          checkpoint_count, output.display());
+
+    Ok(())
+}
+
+/// Number of consecutive checkpoint files (`audio_chunk_000.mp4`, `audio_chunk_001.mp4`, ...)
+/// in a meeting folder's `.checkpoints/` directory
+pub fn count_checkpoints(meeting_folder: &Path) -> u32 {
+    let checkpoints_dir = meeting_folder.join(".checkpoints");
+    (0..)
+        .take_while(|i| checkpoints_dir.join(format!("audio_chunk_{:03}.mp4", i)).is_file())
+        .count() as u32
+}
+
+/// Stitch the checkpoints left behind by an interrupted recording into `audio.mp4`
+/// and remove the checkpoints directory. Returns the path to the merged file.
+pub async fn recover_from_checkpoints(meeting_folder: &Path) -> Result<PathBuf> {
+    let checkpoint_count = count_checkpoints(meeting_folder);
+    if checkpoint_count == 0 {
+        return Err(anyhow!("No audio checkpoints to recover in {}", meeting_folder.display()));
     }
 
-    /// Get current checkpoint count
-    pub fn get_checkpoint_count(&self) -> u32 {
-        self.checkpoint_count
+    let checkpoints_dir = meeting_folder.join(".checkpoints");
+    let final_audio_path = meeting_folder.join("audio.mp4");
+    info!("Recovering {} checkpoints from {}", checkpoint_count, checkpoints_dir.display());
+    merge_checkpoint_files(&checkpoints_dir, checkpoint_count, &final_audio_path).await?;
+
+    if let Err(e) = std::fs::remove_dir_all(&checkpoints_dir) {
+        warn!("Failed to clean up checkpoints directory: {}", e);
     }
+
+    Ok(final_audio_path)
 }
 
 #[cfg(test)]
@@ -286,4 +325,18 @@ mod tests {
         assert!(result.is_err());
         assert!(result.unwrap_err().to_string().contains("No audio checkpoints"));
     }
+
+    #[test]
+    fn test_count_checkpoints_stops_at_gap() {
+        let temp_dir = tempdir().unwrap();
+        let meeting_folder = temp_dir.path().join("Interrupted");
+        let checkpoints_dir = meeting_folder.join(".checkpoints");
+        std::fs::create_dir_all(&checkpoints_dir).unwrap();
+        assert_eq!(count_checkpoints(&meeting_folder), 0);
+
+        for name in ["audio_chunk_000.mp4", "audio_chunk_001.mp4", "audio_chunk_003.mp4"] {
+            std::fs::write(checkpoints_dir.join(name), b"").unwrap();
+        }
+        assert_eq!(count_checkpoints(&meeting_folder), 2);
+    }
 }
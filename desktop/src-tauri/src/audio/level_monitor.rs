@@ -281,6 +281,17 @@ impl AudioLevelMonitor {
     }
 }
 
+/// Calculate (RMS, peak) levels for a block of mono samples
+pub fn calculate_levels(samples: &[f32]) -> (f32, f32) {
+    if samples.is_empty() {
+        return (0.0, 0.0);
+    }
+
+    let rms = (samples.iter().map(|&x| x * x).sum::<f32>() / samples.len() as f32).sqrt();
+    let peak = samples.iter().map(|&x| x.abs()).fold(0.0, f32::max);
+    (rms, peak)
+}
+
 /// Process audio data and calculate levels
 fn process_audio_levels(
     data: &[f32],
@@ -300,15 +311,7 @@ fn process_audio_levels(
         data.to_vec()
     };
 
-    // Calculate RMS level
-    let rms = if !mono_data.is_empty() {
-        (mono_data.iter().map(|&x| x * x).sum::<f32>() / mono_data.len() as f32).sqrt()
-    } else {
-        0.0
-    };
-
-    // Calculate peak level
-    let peak = mono_data.iter().map(|&x| x.abs()).fold(0.0, f32::max);
+    let (rms, peak) = calculate_levels(&mono_data);
 
     // Determine if audio is active (threshold for noise floor)
     let is_active = rms > 0.001; // Adjust threshold as needed
@@ -0,0 +1,178 @@
+//! Per-second level timeline of the mixed recording
+//!
+//! When enabled, the recording saver feeds every mixed chunk through a
+//! `LevelTimelineRecorder`, which downsamples it to one RMS/peak point per
+//! second. The result is written to `levels.json` in the meeting folder so the
+//! UI can draw an energy graph without decoding the audio file again.
+
+use std::path::Path;
+use std::sync::atomic::{AtomicBool, Ordering};
+
+use anyhow::{Context, Result};
+use log::info;
+use serde::{Deserialize, Serialize};
+
+use super::level_monitor::calculate_levels;
+
+/// File name of the timeline inside the meeting folder
+pub const LEVEL_TIMELINE_FILE: &str = "levels.json";
+
+static RECORD_LEVEL_TIMELINE: AtomicBool = AtomicBool::new(false);
+
+pub fn is_level_timeline_enabled() -> bool {
+    RECORD_LEVEL_TIMELINE.load(Ordering::SeqCst)
+}
+
+pub fn set_level_timeline_enabled(enabled: bool) {
+    let previous = RECORD_LEVEL_TIMELINE.swap(enabled, Ordering::SeqCst);
+    if previous != enabled {
+        info!("📈 Level timeline recording {}", if enabled { "ENABLED" } else { "DISABLED" });
+    }
+}
+
+/// RMS and peak level for one second of audio (0.0 to 1.0)
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct LevelPoint {
+    pub second: u32,
+    pub rms: f32,
+    pub peak: f32,
+}
+
+/// Level history for a whole recording
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct LevelTimeline {
+    pub version: String,
+    pub resolution_seconds: f32,
+    pub points: Vec<LevelPoint>,
+}
+
+impl LevelTimeline {
+    /// Write the timeline to the meeting folder (atomic write with temp file)
+    pub fn save(&self, folder: &Path) -> Result<()> {
+        let path = folder.join(LEVEL_TIMELINE_FILE);
+        let temp_path = folder.join(format!(".{}.tmp", LEVEL_TIMELINE_FILE));
+
+        let json = serde_json::to_string(self).context("Failed to serialize level timeline")?;
+        std::fs::write(&temp_path, json).context("Failed to write level timeline")?;
+        std::fs::rename(&temp_path, &path).context("Failed to rename level timeline file")?;
+        Ok(())
+    }
+
+    /// Load the timeline from a meeting folder, if one was recorded
+    pub fn load(folder: &Path) -> Result<Option<Self>> {
+        let path = folder.join(LEVEL_TIMELINE_FILE);
+        if !path.exists() {
+            return Ok(None);
+        }
+
+        let json = std::fs::read_to_string(&path)
+            .with_context(|| format!("Failed to read {}", path.display()))?;
+        let timeline = serde_json::from_str(&json).context("Failed to parse level timeline")?;
+        Ok(Some(timeline))
+    }
+}
+
+/// Downsamples a stream of mono samples to one level point per second
+pub struct LevelTimelineRecorder {
+    samples_per_bucket: usize,
+    bucket_len: usize,
+    bucket_sum_squares: f64,
+    bucket_peak: f32,
+    points: Vec<LevelPoint>,
+}
+
+impl LevelTimelineRecorder {
+    pub fn new(sample_rate: u32) -> Self {
+        Self {
+            samples_per_bucket: sample_rate.max(1) as usize,
+            bucket_len: 0,
+            bucket_sum_squares: 0.0,
+            bucket_peak: 0.0,
+            points: Vec::new(),
+        }
+    }
+
+    pub fn add_samples(&mut self, mut samples: &[f32]) {
+        while !samples.is_empty() {
+            let take = (self.samples_per_bucket - self.bucket_len).min(samples.len());
+            let (rms, peak) = calculate_levels(&samples[..take]);
+
+            self.bucket_sum_squares += (rms as f64).powi(2) * take as f64;
+            self.bucket_peak = self.bucket_peak.max(peak);
+            self.bucket_len += take;
+            samples = &samples[take..];
+
+            if self.bucket_len == self.samples_per_bucket {
+                self.push_bucket();
+            }
+        }
+    }
+
+    /// Close the last partial second and return the timeline
+    pub fn finish(mut self) -> LevelTimeline {
+        if self.bucket_len > 0 {
+            self.push_bucket();
+        }
+
+        LevelTimeline {
+            version: "1.0".to_string(),
+            resolution_seconds: 1.0,
+            points: self.points,
+        }
+    }
+
+    fn push_bucket(&mut self) {
+        let rms = (self.bucket_sum_squares / self.bucket_len as f64).sqrt() as f32;
+        self.points.push(LevelPoint {
+            second: self.points.len() as u32,
+            rms: rms.min(1.0),
+            peak: self.bucket_peak.min(1.0),
+        });
+
+        self.bucket_len = 0;
+        self.bucket_sum_squares = 0.0;
+        self.bucket_peak = 0.0;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::tempdir;
+
+    #[test]
+    fn test_recorder_buckets_per_second() {
+        let mut recorder = LevelTimelineRecorder::new(4);
+
+        // Chunks that straddle bucket boundaries
+        recorder.add_samples(&[0.5, -0.5, 0.5]);
+        recorder.add_samples(&[-0.5, 0.0, 0.0, 0.9, 0.0, 0.1]);
+
+        let timeline = recorder.finish();
+        assert_eq!(timeline.points.len(), 3);
+
+        assert_eq!(timeline.points[0].second, 0);
+        assert!((timeline.points[0].rms - 0.5).abs() < 1e-6);
+        assert!((timeline.points[0].peak - 0.5).abs() < 1e-6);
+
+        assert!((timeline.points[1].peak - 0.9).abs() < 1e-6);
+        assert!((timeline.points[1].rms - 0.45).abs() < 1e-6);
+
+        // Trailing partial second is averaged over its own length
+        assert_eq!(timeline.points[2].second, 2);
+        assert!((timeline.points[2].rms - 0.1).abs() < 1e-6);
+    }
+
+    #[test]
+    fn test_save_and_load_roundtrip() {
+        let dir = tempdir().unwrap();
+        assert!(LevelTimeline::load(dir.path()).unwrap().is_none());
+
+        let mut recorder = LevelTimelineRecorder::new(2);
+        recorder.add_samples(&[0.2, 0.2, 0.4]);
+        let timeline = recorder.finish();
+
+        timeline.save(dir.path()).unwrap();
+        assert_eq!(LevelTimeline::load(dir.path()).unwrap(), Some(timeline));
+    }
+}
@@ -0,0 +1,188 @@
+//! Microphone test
+//!
+//! `test_microphone` records a few seconds from a mic through the same capture
+//! path as dictation, then reports the levels and whether VAD heard speech,
+//! so a muted or wrongly selected mic is caught before a meeting starts.
+
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use log::info;
+use serde::Serialize;
+
+use super::analysis::{count_clipped, to_dbfs};
+use super::dictation::{is_dictating, spawn_capture_thread};
+use super::processing::resample_audio;
+use super::vad::get_speech_chunks;
+
+const DEFAULT_TEST_SECONDS: f32 = 5.0;
+const MIN_TEST_SECONDS: f32 = 1.0;
+const MAX_TEST_SECONDS: f32 = 15.0;
+
+/// VAD's expected sample rate
+const VAD_SAMPLE_RATE: u32 = 16000;
+const VAD_REDEMPTION_MS: u32 = 400;
+/// Less speech than this is treated as a click or noise burst
+const MIN_SPEECH_SECONDS: f64 = 0.3;
+
+/// Peak level below which the mic is probably muted or the wrong device
+const QUIET_PEAK_DBFS: f64 = -50.0;
+
+static MIC_TEST_ACTIVE: AtomicBool = AtomicBool::new(false);
+
+/// Levels and speech detection for a short test capture
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct MicTestResult {
+    pub sample_rate: u32,
+    pub duration_seconds: f64,
+    /// RMS level over the whole capture
+    pub rms_dbfs: f64,
+    pub peak_dbfs: f64,
+    pub clipped_samples: u64,
+    pub speech_detected: bool,
+    pub speech_seconds: f64,
+    /// Human-readable problems found, empty if the mic sounds fine
+    pub warnings: Vec<String>,
+}
+
+/// RMS and peak levels (linear) of mono samples
+fn levels(samples: &[f32]) -> (f64, f64) {
+    if samples.is_empty() {
+        return (0.0, 0.0);
+    }
+    let mean_square = samples.iter().map(|&x| (x as f64).powi(2)).sum::<f64>() / samples.len() as f64;
+    let peak = samples.iter().fold(0.0f64, |peak, &x| peak.max(x.abs() as f64));
+    (mean_square.sqrt(), peak)
+}
+
+fn summarize(samples: &[f32], sample_rate: u32, speech_seconds: f64) -> MicTestResult {
+    let (rms, peak) = levels(samples);
+    let peak_dbfs = to_dbfs(peak);
+    let clipped_samples = count_clipped(samples);
+    let speech_detected = speech_seconds >= MIN_SPEECH_SECONDS;
+
+    let mut warnings = Vec::new();
+    if peak_dbfs < QUIET_PEAK_DBFS {
+        warnings.push("No sound picked up; check that the microphone is unmuted and selected".to_string());
+    } else if !speech_detected {
+        warnings.push("No speech detected; try speaking during the test".to_string());
+    }
+    if clipped_samples > 0 {
+        warnings.push(format!("{} samples clipped; lower the input gain", clipped_samples));
+    }
+
+    MicTestResult {
+        sample_rate,
+        duration_seconds: samples.len() as f64 / sample_rate.max(1) as f64,
+        rms_dbfs: to_dbfs(rms),
+        peak_dbfs,
+        clipped_samples,
+        speech_detected,
+        speech_seconds,
+        warnings,
+    }
+}
+
+/// Clears the active flag however the test ends
+struct ActiveGuard;
+
+impl Drop for ActiveGuard {
+    fn drop(&mut self) {
+        MIC_TEST_ACTIVE.store(false, Ordering::SeqCst);
+    }
+}
+
+/// Record `seconds` (default 5) from a mic, or the default input, and report
+/// its levels and whether speech was heard
+#[tauri::command]
+pub async fn test_microphone(
+    device_name: Option<String>,
+    seconds: Option<f32>,
+) -> Result<MicTestResult, String> {
+    if super::recording::state::is_recording() {
+        return Err("Cannot test the microphone while a recording is in progress".to_string());
+    }
+    if is_dictating() {
+        return Err("Cannot test the microphone while dictating".to_string());
+    }
+    if MIC_TEST_ACTIVE.swap(true, Ordering::SeqCst) {
+        return Err("A microphone test is already running".to_string());
+    }
+    let _active = ActiveGuard;
+
+    let seconds = seconds
+        .filter(|s| s.is_finite())
+        .unwrap_or(DEFAULT_TEST_SECONDS)
+        .clamp(MIN_TEST_SECONDS, MAX_TEST_SECONDS);
+
+    let buffer = Arc::new(Mutex::new(Vec::new()));
+    let stop_flag = Arc::new(AtomicBool::new(false));
+    let (ready_tx, ready_rx) = std::sync::mpsc::channel();
+
+    let capture_thread = spawn_capture_thread(device_name.clone(), buffer.clone(), stop_flag.clone(), ready_tx);
+    let sample_rate = tokio::task::spawn_blocking(move || ready_rx.recv())
+        .await
+        .map_err(|e| e.to_string())?
+        .map_err(|_| "Microphone capture thread exited unexpectedly".to_string())??;
+
+    info!("🎤 Testing microphone '{}' for {:.1}s",
+          device_name.as_deref().unwrap_or("default"), seconds);
+    tokio::time::sleep(Duration::from_secs_f32(seconds)).await;
+
+    stop_flag.store(true, Ordering::SeqCst);
+    tokio::task::spawn_blocking(move || capture_thread.join())
+        .await
+        .map_err(|e| e.to_string())?
+        .map_err(|_| "Microphone capture thread panicked".to_string())?;
+
+    let samples = std::mem::take(&mut *buffer.lock().unwrap());
+    let result = tokio::task::spawn_blocking(move || {
+        let audio = resample_audio(&samples, sample_rate, VAD_SAMPLE_RATE);
+        let speech_seconds: f64 = get_speech_chunks(&audio, VAD_REDEMPTION_MS)?
+            .iter()
+            .map(|segment| (segment.end_timestamp_ms - segment.start_timestamp_ms) / 1000.0)
+            .sum();
+        Ok::<_, anyhow::Error>(summarize(&samples, sample_rate, speech_seconds))
+    })
+    .await
+    .map_err(|e| e.to_string())?
+    .map_err(|e| e.to_string())?;
+
+    info!("🎤 Microphone test: rms {:.1} dBFS, peak {:.1} dBFS, speech {:.1}s",
+          result.rms_dbfs, result.peak_dbfs, result.speech_seconds);
+    Ok(result)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_levels() {
+        let (rms, peak) = levels(&[0.5, -0.5, 0.5, -0.5]);
+        assert!((rms - 0.5).abs() < 1e-9);
+        assert!((peak - 0.5).abs() < 1e-9);
+        assert_eq!(levels(&[]), (0.0, 0.0));
+    }
+
+    #[test]
+    fn test_summarize_warnings() {
+        let silent = summarize(&vec![0.0; 16000], 16000, 0.0);
+        assert_eq!(silent.duration_seconds, 1.0);
+        assert!(!silent.speech_detected);
+        assert!(silent.warnings[0].contains("No sound"));
+
+        let noise = summarize(&vec![0.1; 16000], 16000, 0.1);
+        assert!(!noise.speech_detected);
+        assert!(noise.warnings[0].contains("No speech"));
+
+        let speech = summarize(&vec![0.1; 16000], 16000, 0.8);
+        assert!(speech.speech_detected);
+        assert!(speech.warnings.is_empty());
+
+        let clipped = summarize(&[1.0, -1.0, 0.1], 16000, 1.0);
+        assert_eq!(clipped.clipped_samples, 2);
+        assert_eq!(clipped.warnings.len(), 1);
+    }
+}
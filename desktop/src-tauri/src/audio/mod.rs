@@ -2,6 +2,7 @@
 pub mod audio_processing;
 pub mod encode;
 pub mod ffmpeg;
+pub mod ffmpeg_download;
 pub mod vad;
 
 // Modularized audio processing (split from audio_processing.rs)
@@ -28,6 +29,8 @@ pub mod recording_preferences;
 pub mod recording_saver;
 pub mod incremental_saver;  // NEW: Incremental audio saving with checkpoints
 pub mod level_monitor;
+pub mod level_timeline;
+pub mod analysis;  // Offline quality analysis of saved recordings
 pub mod simple_level_monitor;
 pub mod buffer_pool;
 pub mod post_processor;
@@ -40,6 +43,9 @@ pub mod system_audio_commands;
 pub mod device_monitor;  // NEW: Device disconnect/reconnect monitoring
 pub mod playback_monitor; // NEW: Playback device detection for BT warnings
 pub mod retranscription;  // NEW: Batch retranscription of audio files
+pub mod reprocess;  // One-call retranscribe + diarize + speaker match
+pub mod dictation;  // Mic-only quick dictation mode
+pub mod mic_test;  // Short test capture with level stats and VAD
 
 // Transcription module (provider abstraction, engine management, worker pool)
 pub mod transcription;
@@ -93,10 +99,11 @@ pub use recording_preferences::{
 };
 pub use recording_saver::RecordingSaver;
 pub use level_monitor::{AudioLevelMonitor, AudioLevelData, AudioLevelUpdate};
+pub use level_timeline::{LevelTimeline, LevelPoint};
 pub use buffer_pool::{AudioBufferPool, PooledBuffer};
 pub use post_processor::{PostProcessor, PostProcessRequest, PostProcessResponse};
-pub use hardware_detector::{HardwareProfile, AdaptiveWhisperConfig, PerformanceTier, GpuType};
-pub use model_recommendations::{HardwareRecommendations, ModelRecommendation, RecommendationLevel, HardwareProfileInfo};
+pub use hardware_detector::{HardwareProfile, AdaptiveWhisperConfig, PerformanceTier, GpuType, GpuMemoryInfo};
+pub use model_recommendations::{HardwareRecommendations, LlmModelRecommendations, ModelRecommendation, RecommendationLevel, HardwareProfileInfo};
 pub use encode::{
     encode_single_audio, AudioInput
 };
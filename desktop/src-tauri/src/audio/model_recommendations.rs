@@ -4,7 +4,8 @@
 //! on the user's current hardware configuration.
 
 use serde::Serialize;
-use super::hardware_detector::{HardwareProfile, PerformanceTier, GpuType};
+use super::hardware_detector::{GpuMemoryInfo, HardwareProfile, PerformanceTier, GpuType};
+use crate::llm_engine::model_manager::available_models;
 
 /// Recommendation level for a model
 #[derive(Debug, Clone, PartialEq, Serialize)]
@@ -36,6 +37,9 @@ pub struct HardwareProfileInfo {
     pub memory_gb: u8,
     pub performance_tier: String,
     pub tier_description: String,
+    /// Largest total VRAM across GPUs in MB (None if not measured)
+    pub total_vram_mb: Option<u64>,
+    pub gpus: Vec<GpuMemoryInfo>,
 }
 
 /// Complete recommendations response
@@ -48,6 +52,14 @@ pub struct HardwareRecommendations {
     pub best_llm_model: Option<String>,
 }
 
+/// LLM-only recommendations response
+#[derive(Debug, Clone, Serialize)]
+pub struct LlmModelRecommendations {
+    pub hardware: HardwareProfileInfo,
+    pub models: Vec<ModelRecommendation>,
+    pub best_model: Option<String>,
+}
+
 impl HardwareProfile {
     /// Get model recommendations based on detected hardware
     pub fn get_model_recommendations(&self) -> HardwareRecommendations {
@@ -61,10 +73,7 @@ impl HardwareProfile {
             .map(|m| m.model_name.clone())
             .unwrap_or_else(|| "base-q5_1".to_string());
 
-        let best_llm = llm_models
-            .iter()
-            .find(|m| m.recommendation == RecommendationLevel::Recommended)
-            .map(|m| m.model_name.clone());
+        let best_llm = Self::best_llm_model(&llm_models);
 
         HardwareRecommendations {
             hardware: hardware_info,
@@ -98,6 +107,8 @@ impl HardwareProfile {
             memory_gb: self.memory_gb,
             performance_tier: tier_str.to_string(),
             tier_description: tier_desc.to_string(),
+            total_vram_mb: self.total_vram_mb(),
+            gpus: self.gpus.clone(),
         }
     }
 
@@ -131,6 +142,7 @@ impl HardwareProfile {
 
         models.iter().map(|(name, size_mb)| {
             let (recommendation, reason) = self.recommend_whisper_model(name, *size_mb);
+            let (recommendation, reason) = self.check_whisper_vram(*size_mb, recommendation, reason);
             ModelRecommendation {
                 model_name: name.to_string(),
                 recommendation,
@@ -198,66 +210,119 @@ impl HardwareProfile {
         }
     }
 
+    /// Downgrade Whisper models that won't fit in dedicated GPU memory
+    fn check_whisper_vram(
+        &self,
+        size_mb: u32,
+        recommendation: RecommendationLevel,
+        reason: String,
+    ) -> (RecommendationLevel, String) {
+        let Some(vram_mb) = self.dedicated_vram_mb() else {
+            return (recommendation, reason);
+        };
+
+        // Weights plus encoder/decoder buffers
+        let required_mb = size_mb as u64 * 3 / 2 + 300;
+        if required_mb > vram_mb
+            && matches!(recommendation, RecommendationLevel::Recommended | RecommendationLevel::Compatible)
+        {
+            (
+                RecommendationLevel::NotRecommended,
+                format!("Needs ~{}MB of GPU memory (you have {}MB)", required_mb, vram_mb),
+            )
+        } else {
+            (recommendation, reason)
+        }
+    }
+
     fn get_llm_recommendations(&self) -> Vec<ModelRecommendation> {
-        // LLM models with their size in GB
-        let models: Vec<(&str, f32)> = vec![
-            ("llama-3.2-1b-instruct", 0.8),
-            ("llama-3.2-3b-instruct", 2.0),
-            ("phi-3.5-mini", 2.2),
-            ("mistral-7b-instruct", 4.4),
-            ("qwen-2.5-7b-instruct", 4.5),
-        ];
+        // Curated GGUF models from the LLM registry, smallest first
+        let mut models: Vec<(String, f32)> = available_models()
+            .into_iter()
+            .map(|m| (m.id, m.size_bytes as f32 / 1_000_000_000.0))
+            .collect();
+        models.sort_by(|a, b| a.1.partial_cmp(&b.1).unwrap_or(std::cmp::Ordering::Equal));
 
         models.iter().map(|(name, size_gb)| {
-            let (recommendation, reason) = self.recommend_llm_model(name, *size_gb);
+            let (recommendation, reason) = self.recommend_llm_model(*size_gb);
             ModelRecommendation {
-                model_name: name.to_string(),
+                model_name: name.clone(),
                 recommendation,
                 reason,
             }
         }).collect()
     }
 
-    fn recommend_llm_model(&self, name: &str, size_gb: f32) -> (RecommendationLevel, String) {
+    /// Memory available for holding LLM weights, in GB
+    /// On unified memory the GPU working set limit is the real cap.
+    fn llm_memory_budget_gb(&self) -> f32 {
         let ram_gb = self.memory_gb as f32;
+        match self.gpus.iter().find(|g| g.unified_memory) {
+            Some(gpu) => ram_gb.min(gpu.total_vram_mb as f32 / 1024.0),
+            None => ram_gb,
+        }
+    }
 
-        match self.performance_tier {
-            PerformanceTier::Low => {
-                // Low tier: Only smallest model
-                if name.contains("1b") {
-                    (RecommendationLevel::Recommended, "Lightweight model for basic hardware".to_string())
-                } else if size_gb <= 2.5 {
-                    (RecommendationLevel::NotRecommended, "May be slow on basic hardware".to_string())
-                } else {
-                    (RecommendationLevel::TooHeavy, "Too demanding for this hardware".to_string())
-                }
-            }
-            PerformanceTier::Medium => {
-                // Medium tier: Up to 3B models
-                if name.contains("1b") || name.contains("3b") {
-                    (RecommendationLevel::Recommended, "Good fit for your hardware".to_string())
-                } else if name.contains("phi-3.5") {
-                    (RecommendationLevel::Compatible, "Works but may be slower".to_string())
-                } else {
-                    (RecommendationLevel::TooHeavy, format!("Requires more RAM (you have {}GB)", ram_gb as u8))
-                }
-            }
-            PerformanceTier::High => {
-                // High tier: Most models up to ~4GB
-                if size_gb <= 4.0 {
-                    (RecommendationLevel::Recommended, "Great choice for your hardware".to_string())
-                } else {
-                    (RecommendationLevel::Compatible, "Will work but may be slower".to_string())
-                }
-            }
-            PerformanceTier::Ultra => {
-                // Ultra tier: All models
-                if size_gb >= 4.0 {
-                    (RecommendationLevel::Recommended, "Best quality, your hardware handles it well".to_string())
-                } else {
-                    (RecommendationLevel::Recommended, "Fast and efficient on your hardware".to_string())
-                }
+    fn recommend_llm_model(&self, size_gb: f32) -> (RecommendationLevel, String) {
+        let budget_gb = self.llm_memory_budget_gb();
+        // Weights plus KV cache/runtime overhead, leaving room for the OS,
+        // the app itself and a loaded Whisper model
+        let comfortable_gb = size_gb * 1.5 + 2.5;
+        let minimum_gb = size_gb + 2.0;
+
+        // A dedicated GPU only accelerates the model if the weights fit in VRAM
+        let fits_dedicated_vram = self
+            .dedicated_vram_mb()
+            .map(|vram_mb| size_gb * 1.2 <= vram_mb as f32 / 1024.0);
+
+        if budget_gb >= comfortable_gb {
+            if !self.has_gpu_acceleration && size_gb > 4.0 {
+                (RecommendationLevel::Compatible, "Fits in memory, but will be slow without GPU acceleration".to_string())
+            } else if fits_dedicated_vram == Some(false) && size_gb > 4.0 {
+                (RecommendationLevel::Compatible, "Exceeds GPU memory, will partly run on CPU".to_string())
+            } else {
+                (RecommendationLevel::Recommended, "Good fit for your hardware".to_string())
             }
+        } else if budget_gb >= size_gb * 1.5 {
+            (RecommendationLevel::Compatible, "Fits, but leaves little headroom for other apps".to_string())
+        } else if budget_gb >= minimum_gb {
+            (RecommendationLevel::NotRecommended, format!("Tight fit for {:.0}GB of memory, may swap", budget_gb))
+        } else {
+            (RecommendationLevel::TooHeavy, format!("Requires more memory (you have {:.0}GB)", budget_gb))
+        }
+    }
+
+    /// Pick the default LLM: the largest recommended general-purpose (chat)
+    /// model, falling back to the largest recommended model of any kind
+    fn best_llm_model(models: &[ModelRecommendation]) -> Option<String> {
+        let chat_models: Vec<String> = available_models()
+            .into_iter()
+            .filter(|m| m.recommended_for.iter().any(|r| r == "chat"))
+            .map(|m| m.id)
+            .collect();
+
+        // Recommendations are sorted smallest first
+        let recommended = models
+            .iter()
+            .rev()
+            .filter(|m| m.recommendation == RecommendationLevel::Recommended);
+
+        recommended
+            .clone()
+            .find(|m| chat_models.contains(&m.model_name))
+            .or_else(|| recommended.clone().next())
+            .map(|m| m.model_name.clone())
+    }
+
+    /// Get LLM model recommendations only (used by onboarding to suggest a default)
+    pub fn get_llm_model_recommendations(&self) -> LlmModelRecommendations {
+        let models = self.get_llm_recommendations();
+        let best_model = Self::best_llm_model(&models);
+
+        LlmModelRecommendations {
+            hardware: self.to_info(),
+            models,
+            best_model,
         }
     }
 }
@@ -274,6 +339,7 @@ mod tests {
             gpu_type: GpuType::None,
             memory_gb: 8,
             performance_tier: PerformanceTier::Low,
+            gpus: Vec::new(),
         };
 
         let recommendations = profile.get_model_recommendations();
@@ -300,6 +366,7 @@ mod tests {
             gpu_type: GpuType::Cuda,
             memory_gb: 32,
             performance_tier: PerformanceTier::Ultra,
+            gpus: Vec::new(),
         };
 
         let recommendations = profile.get_model_recommendations();
@@ -311,4 +378,84 @@ mod tests {
             .unwrap();
         assert_eq!(large.recommendation, RecommendationLevel::Recommended);
     }
+
+    #[test]
+    fn test_llm_recommendation_8gb_cpu() {
+        let profile = HardwareProfile {
+            cpu_cores: 4,
+            has_gpu_acceleration: false,
+            gpu_type: GpuType::None,
+            memory_gb: 8,
+            performance_tier: PerformanceTier::Low,
+            gpus: Vec::new(),
+        };
+
+        let recommendations = profile.get_llm_model_recommendations();
+        assert_eq!(recommendations.best_model.as_deref(), Some("llama-3.2-3b-instruct"));
+
+        let mistral = recommendations.models.iter()
+            .find(|m| m.model_name == "mistral-7b-instruct")
+            .unwrap();
+        assert_ne!(mistral.recommendation, RecommendationLevel::Recommended);
+    }
+
+    #[test]
+    fn test_llm_recommendation_16gb_gpu() {
+        let profile = HardwareProfile {
+            cpu_cores: 8,
+            has_gpu_acceleration: true,
+            gpu_type: GpuType::Cuda,
+            memory_gb: 16,
+            performance_tier: PerformanceTier::Ultra,
+            gpus: Vec::new(),
+        };
+
+        let recommendations = profile.get_llm_model_recommendations();
+        assert_eq!(recommendations.best_model.as_deref(), Some("qwen-2.5-7b-instruct"));
+    }
+
+    #[test]
+    fn test_llm_recommendation_low_memory() {
+        let profile = HardwareProfile {
+            cpu_cores: 2,
+            has_gpu_acceleration: false,
+            gpu_type: GpuType::None,
+            memory_gb: 4,
+            performance_tier: PerformanceTier::Low,
+            gpus: Vec::new(),
+        };
+
+        let recommendations = profile.get_llm_model_recommendations();
+        assert_eq!(recommendations.best_model.as_deref(), Some("llama-3.2-1b-instruct"));
+    }
+
+    #[test]
+    fn test_vram_limits_recommendations() {
+        let profile = HardwareProfile {
+            cpu_cores: 8,
+            has_gpu_acceleration: true,
+            gpu_type: GpuType::Cuda,
+            memory_gb: 32,
+            performance_tier: PerformanceTier::Ultra,
+            gpus: vec![GpuMemoryInfo {
+                name: "Small GPU".to_string(),
+                total_vram_mb: 2048,
+                available_vram_mb: 2048,
+                unified_memory: false,
+            }],
+        };
+
+        let recommendations = profile.get_model_recommendations();
+        assert_eq!(recommendations.hardware.total_vram_mb, Some(2048));
+
+        let large = recommendations.whisper_models.iter()
+            .find(|m| m.model_name == "large-v3")
+            .unwrap();
+        assert_eq!(large.recommendation, RecommendationLevel::NotRecommended);
+
+        let qwen = recommendations.llm_models.iter()
+            .find(|m| m.model_name == "qwen-2.5-7b-instruct")
+            .unwrap();
+        assert_eq!(qwen.recommendation, RecommendationLevel::Compatible);
+    }
 }
@@ -76,6 +76,48 @@ pub fn ensure_screen_recording_permission() -> bool {
     false // Permission will be granted after restart
 }
 
+/// Event emitted when a recording needs system audio but the permission is missing
+pub const SYSTEM_AUDIO_PERMISSION_REQUIRED_EVENT: &str = "system-audio-permission-required";
+
+#[cfg(target_os = "macos")]
+#[link(name = "CoreGraphics", kind = "framework")]
+extern "C" {
+    fn CGPreflightScreenCaptureAccess() -> bool;
+}
+
+/// Whether system audio can be captured with the current backend, without prompting.
+///
+/// ScreenCaptureKit needs Screen Recording permission, which can be preflighted.
+/// Core Audio taps prompt for Audio Capture permission on first use and there is
+/// no API to check it beforehand, so they are assumed to be allowed.
+#[cfg(target_os = "macos")]
+pub fn has_system_audio_permission() -> bool {
+    use crate::audio::capture::AudioCaptureBackend;
+
+    match crate::audio::capture::get_current_backend() {
+        AudioCaptureBackend::ScreenCaptureKit => unsafe { CGPreflightScreenCaptureAccess() },
+        AudioCaptureBackend::CoreAudio => true,
+    }
+}
+
+#[cfg(not(target_os = "macos"))]
+pub fn has_system_audio_permission() -> bool {
+    true // Not required on other platforms
+}
+
+/// Settings page where system audio capture is granted, if the OS has one
+pub fn system_audio_settings_url() -> Option<&'static str> {
+    #[cfg(target_os = "macos")]
+    {
+        Some("x-apple.systempreferences:com.apple.preference.security?Privacy_ScreenCapture")
+    }
+
+    #[cfg(not(target_os = "macos"))]
+    {
+        None
+    }
+}
+
 /// Tauri command to check Screen Recording permission
 #[tauri::command]
 pub async fn check_screen_recording_permission_command() -> bool {
@@ -48,6 +48,7 @@ impl AudioPipelineManager {
         target_chunk_duration_ms: u32,
         sample_rate: u32,
         recording_sender: Option<mpsc::UnboundedSender<AudioChunk>>,
+        save_channel_layout: super::mixer::SaveChannelLayout,
         mic_device_name: String,
         mic_device_kind: super::super::device_detection::InputDeviceKind,
         system_device_name: String,
@@ -80,6 +81,7 @@ impl AudioPipelineManager {
         // CRITICAL FIX: Connect recording sender to receive pre-mixed audio
         // This ensures both mic AND system audio are captured in recordings
         pipeline.recording_sender_for_mixed = recording_sender;
+        pipeline.save_channel_layout = save_channel_layout;
 
         // WARM-UP GATE: Capture reference to transcription gate before spawning
         // This allows the recording manager to enable transcription after warm-up
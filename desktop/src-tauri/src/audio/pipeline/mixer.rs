@@ -1,5 +1,66 @@
 //! Professional audio mixer without aggressive ducking
 //! Combines mic + system audio with basic clipping prevention
+//! and, for stereo-split saving, interleaves them onto separate channels
+
+use std::sync::atomic::{AtomicU8, Ordering};
+
+use log::info;
+use serde::{Deserialize, Serialize};
+
+/// Channel layout of the saved audio file.
+/// Transcription always uses the mono mix regardless of this setting.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SaveChannelLayout {
+    /// Mic and system audio mixed to one channel
+    #[default]
+    MonoMix,
+    /// Mic on the left channel, system audio on the right
+    StereoSplit,
+}
+
+impl SaveChannelLayout {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::MonoMix => "mono_mix",
+            Self::StereoSplit => "stereo_split",
+        }
+    }
+
+    pub fn parse(value: &str) -> Option<Self> {
+        match value {
+            "mono_mix" => Some(Self::MonoMix),
+            "stereo_split" => Some(Self::StereoSplit),
+            _ => None,
+        }
+    }
+
+    pub fn channels(self) -> u16 {
+        match self {
+            Self::MonoMix => 1,
+            Self::StereoSplit => 2,
+        }
+    }
+}
+
+static SAVE_CHANNEL_LAYOUT: AtomicU8 = AtomicU8::new(0); // MonoMix
+
+pub fn get_save_channel_layout() -> SaveChannelLayout {
+    match SAVE_CHANNEL_LAYOUT.load(Ordering::SeqCst) {
+        1 => SaveChannelLayout::StereoSplit,
+        _ => SaveChannelLayout::MonoMix,
+    }
+}
+
+pub fn set_save_channel_layout(layout: SaveChannelLayout) {
+    let value = match layout {
+        SaveChannelLayout::MonoMix => 0,
+        SaveChannelLayout::StereoSplit => 1,
+    };
+    if SAVE_CHANNEL_LAYOUT.swap(value, Ordering::SeqCst) != value {
+        info!("🎧 Saved audio channel layout set to {}", layout.as_str());
+    }
+}
 
 /// Simple audio mixer without aggressive ducking
 /// Combines mic + system audio with basic clipping prevention
@@ -46,4 +107,38 @@ impl ProfessionalAudioMixer {
 
         mixed
     }
+
+    /// Interleave mic (left) and system audio (right) into one stereo buffer
+    pub fn split_window(&self, mic_window: &[f32], sys_window: &[f32]) -> Vec<f32> {
+        let max_len = mic_window.len().max(sys_window.len());
+        let mut stereo = Vec::with_capacity(max_len * 2);
+
+        for i in 0..max_len {
+            stereo.push(mic_window.get(i).copied().unwrap_or(0.0).clamp(-1.0, 1.0));
+            stereo.push(sys_window.get(i).copied().unwrap_or(0.0).clamp(-1.0, 1.0));
+        }
+
+        stereo
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_split_window_interleaves_mic_left_system_right() {
+        let mixer = ProfessionalAudioMixer::new(48000);
+        let stereo = mixer.split_window(&[0.1, 0.2, 1.5], &[-0.3, -0.4]);
+        assert_eq!(stereo, vec![0.1, -0.3, 0.2, -0.4, 1.0, 0.0]);
+    }
+
+    #[test]
+    fn test_channel_layout_parse_roundtrip() {
+        for layout in [SaveChannelLayout::MonoMix, SaveChannelLayout::StereoSplit] {
+            assert_eq!(SaveChannelLayout::parse(layout.as_str()), Some(layout));
+        }
+        assert_eq!(SaveChannelLayout::parse("surround"), None);
+        assert_eq!(SaveChannelLayout::StereoSplit.channels(), 2);
+    }
 }
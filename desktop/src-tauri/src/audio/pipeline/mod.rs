@@ -9,6 +9,7 @@
 
 pub mod ring_buffer;
 pub mod mixer;
+pub mod source_activity;
 pub mod capture;
 pub mod processor;
 pub mod manager;
@@ -17,3 +18,4 @@ pub mod manager;
 pub use capture::AudioCapture;
 pub use processor::AudioPipeline;
 pub use manager::AudioPipelineManager;
+pub use mixer::{SaveChannelLayout, get_save_channel_layout, set_save_channel_layout};
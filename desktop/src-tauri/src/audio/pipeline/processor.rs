@@ -10,9 +10,10 @@ use log::{debug, error, info, warn};
 use crate::{perf_debug, batch_audio_metric};
 use super::super::batch_processor::AudioMetricsBatcher;
 use super::super::recording_state::{AudioChunk, DeviceType};
-use super::super::vad::ContinuousVadProcessor;
+use super::super::vad::{live_chunk_seconds, ContinuousVadProcessor};
 use super::ring_buffer::AudioMixerRingBuffer;
-use super::mixer::ProfessionalAudioMixer;
+use super::mixer::{ProfessionalAudioMixer, SaveChannelLayout};
+use super::source_activity::SourceActivityTracker;
 
 /// VAD-driven audio processing pipeline
 /// Uses Voice Activity Detection to segment speech in real-time and send only speech to Whisper
@@ -31,8 +32,12 @@ pub struct AudioPipeline {
     // PROFESSIONAL AUDIO MIXING: Ring buffer + RMS-based mixer
     ring_buffer: AudioMixerRingBuffer,
     mixer: ProfessionalAudioMixer,
+    // Mic vs system energy per mix window, used to tag VAD segments by source
+    source_activity: SourceActivityTracker,
     // Recording sender for pre-mixed audio
     pub recording_sender_for_mixed: Option<mpsc::UnboundedSender<AudioChunk>>,
+    // Layout of the chunks sent to the recording sender (interleaved stereo for StereoSplit)
+    pub save_channel_layout: SaveChannelLayout,
     // WARM-UP GATE: Controls when transcription starts
     // During warm-up phase, audio is processed (for calibration) but not sent to Whisper
     transcription_enabled: Arc<AtomicBool>,
@@ -68,10 +73,15 @@ impl AudioPipeline {
 
         let redemption_time = if cfg!(target_os = "macos") { 400 } else { 400 };
 
+        // Long stretches of speech are cut at the live chunk length, if one is set
+        let live_chunk_seconds = live_chunk_seconds();
         let vad_processor = match ContinuousVadProcessor::new(sample_rate, redemption_time) {
             Ok(processor) => {
-                info!("VAD-driven pipeline: VAD segments will be sent directly to Whisper (no time-based accumulation)");
-                processor
+                match live_chunk_seconds {
+                    Some(seconds) => info!("VAD-driven pipeline: VAD segments sent to Whisper, split at {}s of continuous speech", seconds),
+                    None => info!("VAD-driven pipeline: VAD segments will be sent directly to Whisper (no time-based accumulation)"),
+                }
+                processor.with_max_segment_seconds(live_chunk_seconds)
             }
             Err(e) => {
                 error!("Failed to create VAD processor: {}", e);
@@ -82,6 +92,7 @@ impl AudioPipeline {
         // Initialize professional audio mixing components
         let ring_buffer = AudioMixerRingBuffer::new(sample_rate);
         let mixer = ProfessionalAudioMixer::new(sample_rate);
+        let source_activity = SourceActivityTracker::new(sample_rate);
 
         // Note: target_chunk_duration_ms is ignored - VAD controls segmentation now
         let _ = target_chunk_duration_ms;
@@ -101,7 +112,9 @@ impl AudioPipeline {
             // Initialize professional audio mixing
             ring_buffer,
             mixer,
+            source_activity,
             recording_sender_for_mixed: None,  // Will be set by manager
+            save_channel_layout: SaveChannelLayout::MonoMix,  // Will be set by manager
             // WARM-UP GATE: Starts disabled, enabled after warm-up completes
             transcription_enabled: Arc::new(AtomicBool::new(false)),
         }
@@ -180,6 +193,7 @@ impl AudioPipeline {
                             // System audio at natural levels
                             // Previous 2x gain was causing excessive limiting/distortion
                             let mixed_with_gain = mixed_clean;
+                            self.source_activity.record_window(&mic_window, &sys_window);
 
                             // STEP 3: Send mixed audio for transcription (VAD + Whisper)
                             match self.vad_processor.process_audio(&mixed_with_gain) {
@@ -193,12 +207,16 @@ impl AudioPipeline {
                                                 info!("📤 Sending VAD segment: {:.1}ms, {} samples",
                                                       duration_ms, segment.samples.len());
 
+                                                // Mixed audio, tagged with the source that dominated the segment
                                                 let transcription_chunk = AudioChunk {
                                                     data: segment.samples,
                                                     sample_rate: 16000,
                                                     timestamp: segment.start_timestamp_ms / 1000.0,
                                                     chunk_id: self.chunk_id_counter,
-                                                    device_type: DeviceType::Microphone,  // Mixed audio
+                                                    device_type: self.source_activity.dominant_source(
+                                                        segment.start_timestamp_ms,
+                                                        segment.end_timestamp_ms,
+                                                    ),
                                                 };
 
                                                 if let Err(e) = self.transcription_sender.send(transcription_chunk) {
@@ -221,9 +239,14 @@ impl AudioPipeline {
                             }
 
                             // STEP 4: Send mixed audio for recording (WAV file)
+                            // Stereo-split recordings keep mic and system on separate channels
                             if let Some(ref sender) = self.recording_sender_for_mixed {
+                                let data = match self.save_channel_layout {
+                                    SaveChannelLayout::MonoMix => mixed_with_gain.clone(),
+                                    SaveChannelLayout::StereoSplit => self.mixer.split_window(&mic_window, &sys_window),
+                                };
                                 let recording_chunk = AudioChunk {
-                                    data: mixed_with_gain.clone(),
+                                    data,
                                     sample_rate: self.sample_rate,
                                     timestamp: chunk.timestamp,
                                     chunk_id: self.chunk_id_counter,
@@ -273,7 +296,10 @@ impl AudioPipeline {
                                 sample_rate: 16000,
                                 timestamp: segment.start_timestamp_ms / 1000.0,
                                 chunk_id: self.chunk_id_counter,
-                                device_type: DeviceType::Microphone,
+                                device_type: self.source_activity.dominant_source(
+                                    segment.start_timestamp_ms,
+                                    segment.end_timestamp_ms,
+                                ),
                             };
 
                             if let Err(e) = self.transcription_sender.send(transcription_chunk) {
@@ -0,0 +1,105 @@
+//! Tracks which capture source (mic or system audio) is active over time
+//! Transcription runs on the mixed signal, so each VAD segment is attributed
+//! to the source that carried most of the energy during that segment
+
+use std::collections::VecDeque;
+
+use super::super::recording_state::DeviceType;
+
+/// How much mix-window history to keep, in milliseconds.
+/// VAD segments are much shorter than this.
+const HISTORY_MS: f64 = 120_000.0;
+
+struct WindowEnergy {
+    start_ms: f64,
+    end_ms: f64,
+    mic: f64,
+    system: f64,
+}
+
+pub struct SourceActivityTracker {
+    sample_rate: u32,
+    elapsed_ms: f64,
+    windows: VecDeque<WindowEnergy>,
+}
+
+fn energy(samples: &[f32]) -> f64 {
+    samples.iter().map(|&s| (s as f64) * (s as f64)).sum()
+}
+
+impl SourceActivityTracker {
+    pub fn new(sample_rate: u32) -> Self {
+        Self {
+            sample_rate,
+            elapsed_ms: 0.0,
+            windows: VecDeque::new(),
+        }
+    }
+
+    /// Record the next mix window; call once per window, in the order they are mixed
+    pub fn record_window(&mut self, mic_window: &[f32], sys_window: &[f32]) {
+        let len = mic_window.len().max(sys_window.len());
+        let start_ms = self.elapsed_ms;
+        self.elapsed_ms += len as f64 / self.sample_rate as f64 * 1000.0;
+
+        self.windows.push_back(WindowEnergy {
+            start_ms,
+            end_ms: self.elapsed_ms,
+            mic: energy(mic_window),
+            system: energy(sys_window),
+        });
+
+        while self.windows.front().is_some_and(|w| w.end_ms < self.elapsed_ms - HISTORY_MS) {
+            self.windows.pop_front();
+        }
+    }
+
+    /// Source with the most energy between `start_ms` and `end_ms` (mixed-stream time).
+    /// Falls back to the microphone when neither source has energy in that range.
+    pub fn dominant_source(&self, start_ms: f64, end_ms: f64) -> DeviceType {
+        let (mut mic, mut system) = (0.0, 0.0);
+        for window in &self.windows {
+            let overlap = end_ms.min(window.end_ms) - start_ms.max(window.start_ms);
+            if overlap <= 0.0 {
+                continue;
+            }
+            let fraction = overlap / (window.end_ms - window.start_ms);
+            mic += window.mic * fraction;
+            system += window.system * fraction;
+        }
+
+        if system > mic { DeviceType::System } else { DeviceType::Microphone }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_dominant_source_follows_energy() {
+        // 100ms windows at 1kHz: mic talks first, then system audio
+        let mut tracker = SourceActivityTracker::new(1000);
+        let loud = vec![0.5; 100];
+        let quiet = vec![0.01; 100];
+        for _ in 0..3 {
+            tracker.record_window(&loud, &quiet);
+        }
+        for _ in 0..3 {
+            tracker.record_window(&quiet, &loud);
+        }
+
+        assert_eq!(tracker.dominant_source(0.0, 300.0), DeviceType::Microphone);
+        assert_eq!(tracker.dominant_source(300.0, 600.0), DeviceType::System);
+        // Mostly system audio, with one mic window
+        assert_eq!(tracker.dominant_source(250.0, 600.0), DeviceType::System);
+    }
+
+    #[test]
+    fn test_silence_defaults_to_microphone() {
+        let mut tracker = SourceActivityTracker::new(1000);
+        tracker.record_window(&[0.0; 100], &[0.0; 100]);
+        assert_eq!(tracker.dominant_source(0.0, 100.0), DeviceType::Microphone);
+        assert_eq!(tracker.dominant_source(500.0, 900.0), DeviceType::Microphone);
+    }
+}
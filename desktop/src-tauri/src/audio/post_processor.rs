@@ -1,7 +1,22 @@
+use std::path::Path;
+use std::process::Command;
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
 use std::sync::Arc;
 use tokio::sync::mpsc;
-use anyhow::Result;
-use log::{info, warn, error};
+use anyhow::{anyhow, Result};
+use log::{debug, info, warn, error};
+use serde::{Deserialize, Serialize};
+
+use super::ffmpeg::find_ffmpeg_path;
+use super::level_timeline::LevelTimeline;
+use crate::database::{DatabaseManager, TranscriptSegment};
+
+#[cfg(target_os = "windows")]
+use std::os::windows::process::CommandExt;
+
+/// Windows flag to prevent console window from appearing
+#[cfg(target_os = "windows")]
+const CREATE_NO_WINDOW: u32 = 0x08000000;
 
 /// Post-processing request for transcript text
 #[derive(Debug, Clone)]
@@ -273,4 +288,940 @@ impl Default for PostProcessor {
     fn default() -> Self {
         Self::new()
     }
-}
\ No newline at end of file
+}
+
+// ============== Final-Pass Audio Normalization ==============
+// Live per-chunk loudness normalization can drift over a long recording.
+// This optional pass re-normalizes the complete saved file with FFmpeg's
+// two-pass loudnorm during the save phase of stop_recording.
+
+/// Integrated loudness target (EBU R128 / speech)
+const LOUDNORM_TARGET_I: f64 = -16.0;
+/// True peak ceiling
+const LOUDNORM_TARGET_TP: f64 = -1.5;
+/// Loudness range target
+const LOUDNORM_TARGET_LRA: f64 = 11.0;
+
+static FINAL_LOUDNORM_ENABLED: AtomicBool = AtomicBool::new(false);
+
+pub fn is_final_loudnorm_enabled() -> bool {
+    FINAL_LOUDNORM_ENABLED.load(Ordering::SeqCst)
+}
+
+pub fn set_final_loudnorm_enabled(enabled: bool) {
+    let previous = FINAL_LOUDNORM_ENABLED.swap(enabled, Ordering::SeqCst);
+    if previous != enabled {
+        info!("🔊 Final-pass loudness normalization {} (was {})",
+              if enabled { "ENABLED" } else { "DISABLED" },
+              if previous { "enabled" } else { "disabled" });
+    }
+}
+
+/// Loudness measured by the first loudnorm pass
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct LoudnessMeasurement {
+    /// Integrated loudness (LUFS)
+    pub input_i: f64,
+    /// True peak (dBTP)
+    pub input_tp: f64,
+    /// Loudness range (LU)
+    pub input_lra: f64,
+    /// Gating threshold (LUFS)
+    pub input_thresh: f64,
+    pub target_offset: f64,
+    /// Sample rate of the input, so the output keeps it (loudnorm resamples to 192kHz)
+    pub sample_rate: Option<u32>,
+}
+
+/// Parse the JSON block that `loudnorm=print_format=json` writes to stderr
+fn parse_loudnorm_output(stderr: &str) -> Result<LoudnessMeasurement> {
+    let start = stderr
+        .rfind("{")
+        .ok_or_else(|| anyhow!("loudnorm output did not contain a JSON block"))?;
+    let end = stderr[start..]
+        .find('}')
+        .map(|i| start + i + 1)
+        .ok_or_else(|| anyhow!("loudnorm JSON block is not terminated"))?;
+
+    let json: serde_json::Value = serde_json::from_str(&stderr[start..end])
+        .map_err(|e| anyhow!("Failed to parse loudnorm JSON: {}", e))?;
+
+    // loudnorm reports every value as a string, e.g. "input_i" : "-23.54"
+    let field = |name: &str| -> Result<f64> {
+        json.get(name)
+            .and_then(|v| v.as_str())
+            .and_then(|v| v.trim().parse::<f64>().ok())
+            .ok_or_else(|| anyhow!("loudnorm output missing '{}'", name))
+    };
+
+    Ok(LoudnessMeasurement {
+        input_i: field("input_i")?,
+        input_tp: field("input_tp")?,
+        input_lra: field("input_lra")?,
+        input_thresh: field("input_thresh")?,
+        target_offset: field("target_offset")?,
+        sample_rate: parse_input_sample_rate(stderr),
+    })
+}
+
+/// Find the input sample rate in FFmpeg's stream info ("..., 48000 Hz, mono, ...")
+fn parse_input_sample_rate(stderr: &str) -> Option<u32> {
+    stderr
+        .lines()
+        .filter(|line| line.contains("Audio:"))
+        .flat_map(|line| line.split(','))
+        .find_map(|part| part.trim().strip_suffix(" Hz").and_then(|hz| hz.trim().parse().ok()))
+}
+
+fn ffmpeg_command() -> Result<Command> {
+    let ffmpeg_path = find_ffmpeg_path()
+        .ok_or_else(|| anyhow!("FFmpeg not found. Please install FFmpeg."))?;
+    #[allow(unused_mut)]
+    let mut command = Command::new(ffmpeg_path);
+    #[cfg(target_os = "windows")]
+    command.creation_flags(CREATE_NO_WINDOW);
+    Ok(command)
+}
+
+/// First pass: measure the loudness of an audio file
+pub fn measure_loudness(path: &Path) -> Result<LoudnessMeasurement> {
+    let filter = format!(
+        "loudnorm=I={}:TP={}:LRA={}:print_format=json",
+        LOUDNORM_TARGET_I, LOUDNORM_TARGET_TP, LOUDNORM_TARGET_LRA
+    );
+
+    let output = ffmpeg_command()?
+        .arg("-hide_banner")
+        .arg("-i")
+        .arg(path)
+        .args(["-af", &filter, "-f", "null", "-"])
+        .output()?;
+
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    if !output.status.success() {
+        return Err(anyhow!("FFmpeg loudness measurement failed: {}", stderr));
+    }
+
+    parse_loudnorm_output(&stderr)
+}
+
+/// Normalize an audio file in place with two-pass loudnorm.
+/// Writes to a temporary file next to the original and only replaces it once
+/// FFmpeg succeeds, so a failure leaves the original untouched.
+pub fn normalize_audio_file(path: &Path) -> Result<LoudnessMeasurement> {
+    info!("🔊 Measuring loudness of {}", path.display());
+    let measured = measure_loudness(path)?;
+    debug!("Loudness measurement: {:?}", measured);
+
+    let filter = format!(
+        "loudnorm=I={}:TP={}:LRA={}:measured_I={}:measured_TP={}:measured_LRA={}:measured_thresh={}:offset={}:linear=true",
+        LOUDNORM_TARGET_I,
+        LOUDNORM_TARGET_TP,
+        LOUDNORM_TARGET_LRA,
+        measured.input_i,
+        measured.input_tp,
+        measured.input_lra,
+        measured.input_thresh,
+        measured.target_offset,
+    );
+    let sample_rate = measured.sample_rate.unwrap_or(48000).to_string();
+
+    let extension = path.extension().and_then(|e| e.to_str()).unwrap_or("mp4");
+    let temp_path = path.with_extension(format!("normalized.{}", extension));
+
+    let output = ffmpeg_command()?
+        .arg("-hide_banner")
+        .arg("-y")
+        .arg("-i")
+        .arg(path)
+        .args([
+            "-af", &filter,
+            "-ar", &sample_rate,
+            "-c:a", "aac",
+            "-b:a", "192k",
+            "-movflags", "+faststart",
+        ])
+        .arg(&temp_path)
+        .output()?;
+
+    if !output.status.success() || !temp_path.exists() {
+        let _ = std::fs::remove_file(&temp_path);
+        return Err(anyhow!(
+            "FFmpeg loudness normalization failed: {}",
+            String::from_utf8_lossy(&output.stderr)
+        ));
+    }
+
+    std::fs::rename(&temp_path, path)?;
+    info!("✅ Normalized {} from {:.1} LUFS to {:.1} LUFS",
+          path.display(), measured.input_i, LOUDNORM_TARGET_I);
+
+    Ok(measured)
+}
+
+// ============== Silence Trimming ==============
+// Removes long pauses from the saved file. The removed ranges are written to
+// trim_map.json in the meeting folder so transcript timings captured during
+// the recording can be shifted to match the trimmed audio.
+
+/// File name of the trim map inside the meeting folder
+pub const TRIM_MAP_FILE: &str = "trim_map.json";
+/// Level below which audio counts as silence
+const SILENCE_NOISE_FLOOR: &str = "-45dB";
+/// Each removed pause is shortened to this much silence rather than cut entirely
+const KEPT_SILENCE_SECONDS: f64 = 0.5;
+const DEFAULT_MIN_SILENCE_MS: u64 = 3000;
+
+static TRIM_SILENCE_ON_SAVE: AtomicBool = AtomicBool::new(false);
+static KEEP_UNTRIMMED_ORIGINAL: AtomicBool = AtomicBool::new(true);
+static TRIM_MIN_SILENCE_MS: AtomicU64 = AtomicU64::new(DEFAULT_MIN_SILENCE_MS);
+
+pub fn is_trim_silence_enabled() -> bool {
+    TRIM_SILENCE_ON_SAVE.load(Ordering::SeqCst)
+}
+
+pub fn set_trim_silence_enabled(enabled: bool) {
+    let previous = TRIM_SILENCE_ON_SAVE.swap(enabled, Ordering::SeqCst);
+    if previous != enabled {
+        info!("✂️ Silence trimming on save {} (was {})",
+              if enabled { "ENABLED" } else { "DISABLED" },
+              if previous { "enabled" } else { "disabled" });
+    }
+}
+
+pub fn is_keep_untrimmed_original_enabled() -> bool {
+    KEEP_UNTRIMMED_ORIGINAL.load(Ordering::SeqCst)
+}
+
+pub fn set_keep_untrimmed_original_enabled(enabled: bool) {
+    KEEP_UNTRIMMED_ORIGINAL.store(enabled, Ordering::SeqCst);
+}
+
+/// Minimum pause length (seconds) that gets trimmed
+pub fn get_trim_min_silence_seconds() -> f64 {
+    TRIM_MIN_SILENCE_MS.load(Ordering::SeqCst) as f64 / 1000.0
+}
+
+pub fn set_trim_min_silence_seconds(seconds: f64) {
+    // Must leave room for the silence kept at each cut
+    let ms = (seconds.max(KEPT_SILENCE_SECONDS + 0.5) * 1000.0).round() as u64;
+    TRIM_MIN_SILENCE_MS.store(ms, Ordering::SeqCst);
+}
+
+/// A range of the original audio that was removed (seconds)
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TrimmedRange {
+    pub start: f64,
+    pub end: f64,
+}
+
+/// Ranges removed from a recording by silence trimming
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TrimMap {
+    pub removed: Vec<TrimmedRange>,
+    /// File name of the untrimmed copy, if one was kept
+    pub original_file: Option<String>,
+    /// Set once the database transcript segments have been shifted
+    #[serde(default)]
+    pub applied_to_transcripts: bool,
+}
+
+impl TrimMap {
+    /// Total seconds removed from the recording
+    pub fn removed_seconds(&self) -> f64 {
+        self.removed.iter().map(|r| r.end - r.start).sum()
+    }
+
+    /// Map a time in the original audio to the trimmed audio.
+    /// Times inside a removed range snap to the cut.
+    pub fn map_time(&self, time: f64) -> f64 {
+        let mut shift = 0.0;
+        for range in &self.removed {
+            if time >= range.end {
+                shift += range.end - range.start;
+            } else if time > range.start {
+                return range.start - shift;
+            } else {
+                break;
+            }
+        }
+        time - shift
+    }
+
+    /// Drop the level points of removed audio and renumber the rest so the
+    /// timeline lines up with the trimmed audio
+    pub fn apply_to_level_timeline(&self, timeline: &mut LevelTimeline) {
+        let seconds = timeline.resolution_seconds as f64;
+        timeline.points.retain(|point| {
+            let middle = (point.second as f64 + 0.5) * seconds;
+            !self.removed.iter().any(|r| middle > r.start && middle < r.end)
+        });
+        for (index, point) in timeline.points.iter_mut().enumerate() {
+            point.second = index as u32;
+        }
+    }
+
+    pub fn load(folder: &Path) -> Result<Option<Self>> {
+        let path = folder.join(TRIM_MAP_FILE);
+        if !path.exists() {
+            return Ok(None);
+        }
+        let json = std::fs::read_to_string(&path)?;
+        Ok(Some(serde_json::from_str(&json)?))
+    }
+
+    pub fn save(&self, folder: &Path) -> Result<()> {
+        let path = folder.join(TRIM_MAP_FILE);
+        let temp_path = folder.join(format!(".{}.tmp", TRIM_MAP_FILE));
+        std::fs::write(&temp_path, serde_json::to_string_pretty(self)?)?;
+        std::fs::rename(&temp_path, &path)?;
+        Ok(())
+    }
+}
+
+/// Save the live transcript of a finished recording. If silence was trimmed
+/// on save, the timings are shifted to the trimmed audio and the recording's
+/// duration is shortened in the same transaction.
+pub fn save_live_transcript(db: &DatabaseManager, segments: &mut [TranscriptSegment]) -> Result<()> {
+    let Some(recording_id) = segments.first().map(|s| s.recording_id.clone()) else {
+        return Ok(());
+    };
+    let recording = db.get_recording(&recording_id)?;
+    let folder = recording.as_ref().and_then(|r| r.folder_path());
+    let trim_map = folder
+        .as_deref()
+        .and_then(|f| TrimMap::load(f).ok().flatten())
+        .filter(|map| !map.applied_to_transcripts);
+
+    let (Some(mut map), Some(folder), Some(recording)) = (trim_map, folder, recording) else {
+        return db.save_transcript_segments_batch(segments);
+    };
+
+    for segment in segments.iter_mut() {
+        segment.audio_start_time = map.map_time(segment.audio_start_time);
+        segment.audio_end_time = map.map_time(segment.audio_end_time);
+        segment.duration = segment.audio_end_time - segment.audio_start_time;
+    }
+    let duration = recording
+        .duration_seconds
+        .map(|d| (d - map.removed_seconds()).max(0.0));
+    db.save_trimmed_transcript_segments(segments, &recording.id, duration)?;
+
+    map.applied_to_transcripts = true;
+    if let Err(e) = map.save(&folder) {
+        warn!("Failed to mark trim map as applied: {}", e);
+    }
+    Ok(())
+}
+
+/// Parse (start, end) silences from `silencedetect` output on stderr.
+/// A silence still open at the end of the file has no end and is ignored.
+fn parse_silencedetect_output(stderr: &str) -> Vec<(f64, f64)> {
+    let mut silences = Vec::new();
+    let mut current_start = None;
+
+    for line in stderr.lines() {
+        if let Some(rest) = line.split("silence_start:").nth(1) {
+            current_start = rest.trim().parse::<f64>().ok();
+        } else if let Some(rest) = line.split("silence_end:").nth(1) {
+            let end = rest.split('|').next().and_then(|v| v.trim().parse::<f64>().ok());
+            if let (Some(start), Some(end)) = (current_start.take(), end) {
+                silences.push((start.max(0.0), end));
+            }
+        }
+    }
+
+    silences
+}
+
+/// Ranges to cut for silences of at least `min_seconds`, keeping a little silence at each cut
+fn silence_removal_ranges(silences: &[(f64, f64)], min_seconds: f64) -> Vec<TrimmedRange> {
+    let pad = KEPT_SILENCE_SECONDS / 2.0;
+    silences
+        .iter()
+        .filter(|(start, end)| end - start >= min_seconds)
+        .map(|&(start, end)| TrimmedRange { start: start + pad, end: end - pad })
+        .filter(|range| range.end > range.start)
+        .collect()
+}
+
+/// Remove silences longer than `min_seconds` from an audio file in place.
+/// Returns the trim map (also saved next to the file), or None if nothing was trimmed.
+pub fn trim_silence_in_file(path: &Path, min_seconds: f64, keep_original: bool) -> Result<Option<TrimMap>> {
+    let folder = path.parent().ok_or_else(|| anyhow!("Audio file has no parent folder"))?;
+
+    info!("✂️ Detecting silences longer than {:.1}s in {}", min_seconds, path.display());
+    let detect = ffmpeg_command()?
+        .arg("-hide_banner")
+        .arg("-i")
+        .arg(path)
+        .args([
+            "-af", &format!("silencedetect=noise={}:d={}", SILENCE_NOISE_FLOOR, min_seconds),
+            "-f", "null", "-",
+        ])
+        .output()?;
+
+    let stderr = String::from_utf8_lossy(&detect.stderr);
+    if !detect.status.success() {
+        return Err(anyhow!("FFmpeg silence detection failed: {}", stderr));
+    }
+
+    let removed = silence_removal_ranges(&parse_silencedetect_output(&stderr), min_seconds);
+    if removed.is_empty() {
+        info!("No long silences found, keeping audio as is");
+        return Ok(None);
+    }
+
+    let keep_expr = removed
+        .iter()
+        .map(|r| format!("between(t,{:.3},{:.3})", r.start, r.end))
+        .collect::<Vec<_>>()
+        .join("+");
+    let filter = format!("aselect='not({})',asetpts=N/SR/TB", keep_expr);
+    let sample_rate = parse_input_sample_rate(&stderr).unwrap_or(48000).to_string();
+
+    let extension = path.extension().and_then(|e| e.to_str()).unwrap_or("mp4");
+    let temp_path = path.with_extension(format!("trimmed.{}", extension));
+
+    let output = ffmpeg_command()?
+        .arg("-hide_banner")
+        .arg("-y")
+        .arg("-i")
+        .arg(path)
+        .args([
+            "-af", &filter,
+            "-ar", &sample_rate,
+            "-c:a", "aac",
+            "-b:a", "192k",
+            "-movflags", "+faststart",
+        ])
+        .arg(&temp_path)
+        .output()?;
+
+    if !output.status.success() || !temp_path.exists() {
+        let _ = std::fs::remove_file(&temp_path);
+        return Err(anyhow!(
+            "FFmpeg silence trimming failed: {}",
+            String::from_utf8_lossy(&output.stderr)
+        ));
+    }
+
+    let original_file = if keep_original {
+        let original_path = path.with_extension(format!("untrimmed.{}", extension));
+        std::fs::rename(path, &original_path)?;
+        original_path.file_name().map(|n| n.to_string_lossy().to_string())
+    } else {
+        None
+    };
+    std::fs::rename(&temp_path, path)?;
+
+    let map = TrimMap { removed, original_file, applied_to_transcripts: false };
+    map.save(folder)?;
+
+    info!("✅ Trimmed {:.1}s of silence from {} ({} cuts)",
+          map.removed_seconds(), path.display(), map.removed.len());
+    Ok(Some(map))
+}
+
+// ============== Remixing Stereo-Split Recordings ==============
+// Recordings saved with the stereo_split layout keep the mic on the left
+// channel and system audio on the right, so they can be mixed down again
+// later with different per-source gains.
+
+/// Highest gain accepted for either source
+const MAX_REMIX_GAIN: f64 = 4.0;
+
+fn validate_remix_gain(name: &str, gain: f64) -> Result<()> {
+    if !gain.is_finite() || !(0.0..=MAX_REMIX_GAIN).contains(&gain) {
+        return Err(anyhow!("{} must be between 0 and {}", name, MAX_REMIX_GAIN));
+    }
+    Ok(())
+}
+
+/// FFmpeg filter mixing left (mic) and right (system) into mono, limited to avoid clipping
+fn remix_filter(gain_mic: f64, gain_system: f64) -> String {
+    format!(
+        "pan=mono|c0={:.3}*c0+{:.3}*c1,alimiter=limit=0.98:level=false",
+        gain_mic, gain_system
+    )
+}
+
+/// Mix a stereo-split file down to mono next to the original ("audio.mixed.mp4").
+/// The stereo file is kept so the recording can be remixed again.
+pub fn remix_stereo_split_file(path: &Path, gain_mic: f64, gain_system: f64) -> Result<std::path::PathBuf> {
+    validate_remix_gain("gain_mic", gain_mic)?;
+    validate_remix_gain("gain_system", gain_system)?;
+
+    let extension = path.extension().and_then(|e| e.to_str()).unwrap_or("mp4");
+    let output_path = path.with_extension(format!("mixed.{}", extension));
+    let temp_path = path.with_extension(format!("mixed.tmp.{}", extension));
+
+    let output = ffmpeg_command()?
+        .arg("-hide_banner")
+        .arg("-y")
+        .arg("-i")
+        .arg(path)
+        .args([
+            "-vn",
+            "-af", &remix_filter(gain_mic, gain_system),
+            "-c:a", "aac",
+            "-b:a", "192k",
+            "-movflags", "+faststart",
+        ])
+        .arg(&temp_path)
+        .output()?;
+
+    if !output.status.success() || !temp_path.exists() {
+        let _ = std::fs::remove_file(&temp_path);
+        return Err(anyhow!(
+            "FFmpeg remix failed: {}",
+            String::from_utf8_lossy(&output.stderr)
+        ));
+    }
+
+    std::fs::rename(&temp_path, &output_path)?;
+    info!("🎚️ Remixed {} (mic x{:.2}, system x{:.2}) → {}",
+          path.display(), gain_mic, gain_system, output_path.display());
+    Ok(output_path)
+}
+
+/// Produce a mono mix of a stereo-split recording with adjustable per-source gains.
+/// Returns the path of the mixed file; the recording keeps pointing at the stereo file.
+#[tauri::command]
+pub async fn remix_recording(
+    state: tauri::State<'_, crate::state::AppState>,
+    recording_id: String,
+    gain_mic: f64,
+    gain_system: f64,
+) -> Result<String, String> {
+    validate_remix_gain("gain_mic", gain_mic).map_err(|e| e.to_string())?;
+    validate_remix_gain("gain_system", gain_system).map_err(|e| e.to_string())?;
+
+    let recording = state
+        .db()
+        .await
+        .get_recording(&recording_id)
+        .map_err(|e| e.to_string())?
+        .ok_or_else(|| format!("Recording not found: {}", recording_id))?;
+    let audio_path = recording
+        .audio_file_path
+        .clone()
+        .ok_or_else(|| "Recording has no audio file".to_string())?;
+
+    // Only the saver's metadata knows whether the channels hold separate sources
+    let layout = recording
+        .folder_path()
+        .and_then(|folder| std::fs::read_to_string(folder.join("metadata.json")).ok())
+        .and_then(|json| serde_json::from_str::<super::recording_saver::MeetingMetadata>(&json).ok())
+        .map(|metadata| metadata.channel_layout)
+        .unwrap_or_default();
+    if layout != super::pipeline::SaveChannelLayout::StereoSplit {
+        return Err("Recording was not saved with separate mic and system channels".to_string());
+    }
+
+    let path = std::path::PathBuf::from(audio_path);
+    let mixed = tokio::task::spawn_blocking(move || remix_stereo_split_file(&path, gain_mic, gain_system))
+        .await
+        .map_err(|e| e.to_string())?
+        .map_err(|e| e.to_string())?;
+
+    Ok(mixed.to_string_lossy().to_string())
+}
+
+// ============== Bulk Re-encoding ==============
+// Re-encodes saved recordings to a compressed format to reclaim disk space.
+
+/// Event emitted for each recording processed by `compress_recordings`
+const COMPRESS_PROGRESS_EVENT: &str = "compress-recordings-progress";
+
+/// FFmpeg codec, bitrate and file extension for a target format/quality
+fn compression_params(format: &str, quality: &str) -> Result<(&'static str, &'static str, &'static str)> {
+    let (codec, extension, bitrates) = match format.to_lowercase().as_str() {
+        "opus" => ("libopus", "ogg", ["24k", "32k", "64k"]),
+        "mp3" => ("libmp3lame", "mp3", ["64k", "96k", "128k"]),
+        "aac" | "m4a" => ("aac", "m4a", ["64k", "96k", "160k"]),
+        other => return Err(anyhow!("Unsupported format '{}'. Use opus, mp3 or aac", other)),
+    };
+
+    let bitrate = match quality.to_lowercase().as_str() {
+        "low" => bitrates[0],
+        "medium" => bitrates[1],
+        "high" => bitrates[2],
+        other => return Err(anyhow!("Unsupported quality '{}'. Use low, medium or high", other)),
+    };
+
+    Ok((codec, bitrate, extension))
+}
+
+/// Check that FFmpeg can decode the whole file without errors
+pub fn verify_audio_decodes(path: &Path) -> Result<()> {
+    let output = ffmpeg_command()?
+        .args(["-v", "error", "-i"])
+        .arg(path)
+        .args(["-f", "null", "-"])
+        .output()?;
+
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    if !output.status.success() || !stderr.trim().is_empty() {
+        return Err(anyhow!("Decoding {} failed: {}", path.display(), stderr.trim()));
+    }
+    Ok(())
+}
+
+/// Path for the compressed copy of `path`: the same name with `extension`, or
+/// `<name>.compressed[-N].<extension>` if that is the original or already exists
+fn compressed_output_path(path: &Path, extension: &str) -> std::path::PathBuf {
+    let output_path = path.with_extension(extension);
+    if output_path != path && !output_path.exists() {
+        return output_path;
+    }
+    (1..)
+        .map(|n| match n {
+            1 => path.with_extension(format!("compressed.{}", extension)),
+            n => path.with_extension(format!("compressed-{}.{}", n, extension)),
+        })
+        .find(|candidate| !candidate.exists())
+        .expect("unbounded candidates")
+}
+
+/// Re-encode an audio file to a compressed format next to the original.
+/// The original and any other existing file are left in place; returns the
+/// path of the compressed file.
+pub fn compress_audio_file(path: &Path, format: &str, quality: &str) -> Result<std::path::PathBuf> {
+    let (codec, bitrate, extension) = compression_params(format, quality)?;
+
+    let output_path = compressed_output_path(path, extension);
+
+    let output = ffmpeg_command()?
+        .arg("-hide_banner")
+        .arg("-n")
+        .arg("-i")
+        .arg(path)
+        .args(["-vn", "-c:a", codec, "-b:a", bitrate])
+        .arg(&output_path)
+        .output()?;
+
+    if !output.status.success() || !output_path.exists() {
+        let _ = std::fs::remove_file(&output_path);
+        return Err(anyhow!(
+            "FFmpeg re-encode failed: {}",
+            String::from_utf8_lossy(&output.stderr)
+        ));
+    }
+
+    Ok(output_path)
+}
+
+/// Outcome of compressing a single recording
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CompressionResult {
+    pub recording_id: String,
+    pub success: bool,
+    pub original_path: Option<String>,
+    pub new_path: Option<String>,
+    pub original_bytes: u64,
+    pub compressed_bytes: u64,
+    pub error: Option<String>,
+}
+
+/// Summary of a bulk compression run
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CompressionReport {
+    pub results: Vec<CompressionResult>,
+    pub succeeded: usize,
+    pub failed: usize,
+    /// Total bytes freed across all successful recordings
+    pub bytes_saved: u64,
+}
+
+/// Compress one recording: re-encode, verify, update the DB, then delete the original
+async fn compress_recording(
+    state: &crate::state::AppState,
+    recording_id: &str,
+    format: &str,
+    quality: &str,
+) -> Result<CompressionResult> {
+    let recording = state
+        .db()
+        .await
+        .get_recording(recording_id)?
+        .ok_or_else(|| anyhow!("Recording not found: {}", recording_id))?;
+
+    let original_path = recording
+        .audio_file_path
+        .ok_or_else(|| anyhow!("Recording has no audio file"))?;
+    let original = std::path::PathBuf::from(&original_path);
+    let original_bytes = std::fs::metadata(&original)
+        .map_err(|e| anyhow!("Audio file not accessible: {}", e))?
+        .len();
+
+    let (format, quality) = (format.to_string(), quality.to_string());
+    let source = original.clone();
+    let compressed = tokio::task::spawn_blocking(move || -> Result<std::path::PathBuf> {
+        let compressed = compress_audio_file(&source, &format, &quality)?;
+        if let Err(e) = verify_audio_decodes(&compressed) {
+            let _ = std::fs::remove_file(&compressed);
+            return Err(e);
+        }
+        Ok(compressed)
+    })
+    .await
+    .map_err(|e| anyhow!("Compression task failed: {}", e))??;
+
+    let compressed_bytes = std::fs::metadata(&compressed)?.len();
+    let new_path = compressed.to_string_lossy().to_string();
+
+    let update = crate::database::RecordingUpdate {
+        audio_file_path: Some(new_path.clone()),
+        ..Default::default()
+    };
+    if let Err(e) = state.db().await.update_recording(recording_id, &update) {
+        // Keep the original referenced in the DB; drop the orphaned copy
+        let _ = std::fs::remove_file(&compressed);
+        return Err(e);
+    }
+
+    if let Err(e) = std::fs::remove_file(&original) {
+        warn!("Compressed {} but failed to delete original {}: {}", recording_id, original_path, e);
+    }
+
+    Ok(CompressionResult {
+        recording_id: recording_id.to_string(),
+        success: true,
+        original_path: Some(original_path),
+        new_path: Some(new_path),
+        original_bytes,
+        compressed_bytes,
+        error: None,
+    })
+}
+
+/// Re-encode recordings' audio files to a compressed format.
+/// The original file is only deleted after the compressed one decodes cleanly
+/// and the recording's audio_file_path has been updated.
+/// Emits `compress-recordings-progress` after each recording.
+#[tauri::command]
+pub async fn compress_recordings<R: tauri::Runtime>(
+    app: tauri::AppHandle<R>,
+    state: tauri::State<'_, crate::state::AppState>,
+    recording_ids: Vec<String>,
+    format: String,
+    quality: String,
+) -> Result<CompressionReport, String> {
+    use tauri::Emitter;
+
+    // Fail fast on bad parameters rather than once per recording
+    compression_params(&format, &quality).map_err(|e| e.to_string())?;
+
+    let total = recording_ids.len();
+    let mut results = Vec::with_capacity(total);
+
+    for (index, recording_id) in recording_ids.iter().enumerate() {
+        let result = match compress_recording(&state, recording_id, &format, &quality).await {
+            Ok(result) => {
+                info!("🗜️ Compressed recording {}: {} → {} bytes",
+                      recording_id, result.original_bytes, result.compressed_bytes);
+                result
+            }
+            Err(e) => {
+                warn!("Failed to compress recording {}: {}", recording_id, e);
+                CompressionResult {
+                    recording_id: recording_id.clone(),
+                    success: false,
+                    original_path: None,
+                    new_path: None,
+                    original_bytes: 0,
+                    compressed_bytes: 0,
+                    error: Some(e.to_string()),
+                }
+            }
+        };
+
+        let _ = app.emit(COMPRESS_PROGRESS_EVENT, serde_json::json!({
+            "recording_id": recording_id,
+            "current": index + 1,
+            "total": total,
+            "success": result.success,
+            "error": result.error,
+        }));
+        results.push(result);
+    }
+
+    let succeeded = results.iter().filter(|r| r.success).count();
+    let bytes_saved = results
+        .iter()
+        .filter(|r| r.success)
+        .map(|r| r.original_bytes.saturating_sub(r.compressed_bytes))
+        .sum();
+
+    Ok(CompressionReport {
+        failed: results.len() - succeeded,
+        succeeded,
+        bytes_saved,
+        results,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_compressed_output_path_avoids_existing_files() {
+        let dir = tempfile::tempdir().unwrap();
+        let original = dir.path().join("audio.mp4");
+        std::fs::write(&original, b"original").unwrap();
+        assert_eq!(compressed_output_path(&original, "m4a"), dir.path().join("audio.m4a"));
+
+        std::fs::write(dir.path().join("audio.m4a"), b"sibling").unwrap();
+        assert_eq!(compressed_output_path(&original, "m4a"), dir.path().join("audio.compressed.m4a"));
+
+        let original = dir.path().join("audio.ogg");
+        std::fs::write(dir.path().join("audio.compressed.ogg"), b"earlier").unwrap();
+        assert_eq!(compressed_output_path(&original, "ogg"), dir.path().join("audio.compressed-2.ogg"));
+    }
+
+    #[test]
+    fn test_remix_filter_and_gain_validation() {
+        assert_eq!(
+            remix_filter(1.0, 0.5),
+            "pan=mono|c0=1.000*c0+0.500*c1,alimiter=limit=0.98:level=false"
+        );
+        assert!(validate_remix_gain("gain_mic", 0.0).is_ok());
+        assert!(validate_remix_gain("gain_mic", MAX_REMIX_GAIN).is_ok());
+        assert!(validate_remix_gain("gain_mic", -0.1).is_err());
+        assert!(validate_remix_gain("gain_mic", f64::NAN).is_err());
+        assert!(validate_remix_gain("gain_mic", MAX_REMIX_GAIN + 0.1).is_err());
+    }
+
+    #[test]
+    fn test_parse_loudnorm_output() {
+        let stderr = r#"
+Input #0, mov,mp4,m4a,3gp,3g2,mj2, from 'audio.mp4':
+  Stream #0:0[0x1](und): Audio: aac (LC) (mp4a / 0x6134706D), 48000 Hz, mono, fltp, 192 kb/s (default)
+[Parsed_loudnorm_0 @ 0x600000c4c000]
+{
+	"input_i" : "-27.61",
+	"input_tp" : "-4.47",
+	"input_lra" : "18.06",
+	"input_thresh" : "-39.20",
+	"output_i" : "-16.58",
+	"output_tp" : "-1.50",
+	"output_lra" : "14.78",
+	"output_thresh" : "-27.71",
+	"normalization_type" : "dynamic",
+	"target_offset" : "0.58"
+}
+"#;
+        let measured = parse_loudnorm_output(stderr).unwrap();
+        assert_eq!(measured.input_i, -27.61);
+        assert_eq!(measured.input_tp, -4.47);
+        assert_eq!(measured.input_lra, 18.06);
+        assert_eq!(measured.input_thresh, -39.20);
+        assert_eq!(measured.target_offset, 0.58);
+        assert_eq!(measured.sample_rate, Some(48000));
+    }
+
+    #[test]
+    fn test_parse_silencedetect_output() {
+        let stderr = r#"
+[silencedetect @ 0x7f8] silence_start: 0
+[silencedetect @ 0x7f8] silence_end: 1.5 | silence_duration: 1.5
+size=N/A time=00:00:20.00 bitrate=N/A speed= 600x
+[silencedetect @ 0x7f8] silence_start: 10.25
+[silencedetect @ 0x7f8] silence_end: 15.75 | silence_duration: 5.5
+[silencedetect @ 0x7f8] silence_start: 19.5
+"#;
+        assert_eq!(parse_silencedetect_output(stderr), vec![(0.0, 1.5), (10.25, 15.75)]);
+    }
+
+    #[test]
+    fn test_silence_removal_ranges_keep_padding() {
+        let ranges = silence_removal_ranges(&[(0.0, 1.5), (10.0, 16.0)], 3.0);
+        assert_eq!(ranges, vec![TrimmedRange { start: 10.25, end: 15.75 }]);
+    }
+
+    #[test]
+    fn test_trim_map_time_mapping() {
+        let map = TrimMap {
+            removed: vec![
+                TrimmedRange { start: 10.0, end: 15.0 },
+                TrimmedRange { start: 30.0, end: 40.0 },
+            ],
+            original_file: None,
+            applied_to_transcripts: false,
+        };
+
+        assert_eq!(map.removed_seconds(), 15.0);
+        assert_eq!(map.map_time(5.0), 5.0);
+        assert_eq!(map.map_time(12.0), 10.0);   // inside a cut snaps to it
+        assert_eq!(map.map_time(20.0), 15.0);
+        assert_eq!(map.map_time(45.0), 30.0);
+    }
+
+    #[test]
+    fn test_trim_map_level_timeline() {
+        let map = TrimMap {
+            removed: vec![TrimmedRange { start: 2.0, end: 4.0 }],
+            original_file: None,
+            applied_to_transcripts: false,
+        };
+        let mut timeline = LevelTimeline {
+            version: "1.0".to_string(),
+            resolution_seconds: 1.0,
+            points: (0..6)
+                .map(|second| crate::audio::LevelPoint { second, rms: second as f32 / 10.0, peak: 0.0 })
+                .collect(),
+        };
+
+        map.apply_to_level_timeline(&mut timeline);
+        let points: Vec<(u32, f32)> = timeline.points.iter().map(|p| (p.second, p.rms)).collect();
+        assert_eq!(points, vec![(0, 0.0), (1, 0.1), (2, 0.4), (3, 0.5)]);
+    }
+
+    #[test]
+    fn test_save_live_transcript_applies_trim_once() {
+        let dir = tempfile::tempdir().unwrap();
+        let db = DatabaseManager::new(dir.path().join("test.db")).unwrap();
+
+        let mut recording = crate::database::Recording::new("rec".to_string(), "Trimmed".to_string());
+        recording.meeting_folder_path = Some(dir.path().to_string_lossy().to_string());
+        db.create_recording(&recording).unwrap();
+        db.complete_recording("rec", 60.0).unwrap();
+
+        let map = TrimMap {
+            removed: vec![TrimmedRange { start: 10.0, end: 20.0 }],
+            original_file: None,
+            applied_to_transcripts: false,
+        };
+        map.save(dir.path()).unwrap();
+
+        let mut segments = vec![TranscriptSegment::test_segment(25.0, 30.0, "After the pause")];
+        save_live_transcript(&db, &mut segments).unwrap();
+
+        let saved = db.get_transcript_segments("rec").unwrap();
+        assert_eq!((saved[0].audio_start_time, saved[0].audio_end_time), (15.0, 20.0));
+        assert_eq!(db.get_recording("rec").unwrap().unwrap().duration_seconds, Some(50.0));
+        assert!(TrimMap::load(dir.path()).unwrap().unwrap().applied_to_transcripts);
+
+        // A second save (e.g. from the post-recording modal) isn't shifted again
+        segments[0].audio_start_time = 15.0;
+        save_live_transcript(&db, &mut segments).unwrap();
+        assert_eq!(db.get_recording("rec").unwrap().unwrap().duration_seconds, Some(50.0));
+        assert_eq!(db.get_transcript_segments("rec").unwrap()[0].audio_start_time, 15.0);
+    }
+
+    #[test]
+    fn test_parse_loudnorm_output_missing_json() {
+        assert!(parse_loudnorm_output("no json here").is_err());
+    }
+
+    #[test]
+    fn test_compression_params() {
+        assert_eq!(compression_params("opus", "low").unwrap(), ("libopus", "24k", "ogg"));
+        assert_eq!(compression_params("MP3", "high").unwrap(), ("libmp3lame", "128k", "mp3"));
+        assert_eq!(compression_params("aac", "medium").unwrap(), ("aac", "96k", "m4a"));
+        assert!(compression_params("flac", "low").is_err());
+        assert!(compression_params("opus", "ultra").is_err());
+    }
+}
@@ -14,7 +14,7 @@ pub mod resampling;
 pub mod spectral;
 
 // Re-export for backwards compatibility
-pub use normalizer::{normalize_v2, LoudnessNormalizer, TruePeakLimiter};
+pub use normalizer::{normalize_v2, measure_loudness, LoudnessMeasurement, LoudnessNormalizer, TruePeakLimiter};
 pub use noise_suppression::NoiseSuppressionProcessor;
 pub use filters::HighPassFilter;
 pub use resampling::{resample, resample_audio};
@@ -142,3 +142,35 @@ impl LoudnessNormalizer {
         normalized_samples
     }
 }
+
+/// Integrated loudness and true peak of a finished block of audio
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct LoudnessMeasurement {
+    /// Integrated loudness in LUFS (None if the audio is entirely below the gate)
+    pub integrated_lufs: Option<f64>,
+    /// Highest true peak in dBTP (None for digital silence)
+    pub true_peak_dbtp: Option<f64>,
+}
+
+/// Measure a whole mono signal with the same EBU R128 meter the normalizer uses
+pub fn measure_loudness(samples: &[f32], sample_rate: u32) -> Result<LoudnessMeasurement> {
+    const ANALYZE_CHUNK_SIZE: usize = 512;
+
+    let mut ebur128 = ebur128::EbuR128::new(1, sample_rate, ebur128::Mode::I | ebur128::Mode::TRUE_PEAK)
+        .map_err(|e| anyhow::anyhow!("Failed to create EBU R128 meter: {}", e))?;
+
+    for chunk in samples.chunks(ANALYZE_CHUNK_SIZE) {
+        ebur128
+            .add_frames_f32(chunk)
+            .map_err(|e| anyhow::anyhow!("Failed to add frames to EBU R128: {}", e))?;
+    }
+
+    let integrated_lufs = ebur128.loudness_global().ok().filter(|lufs| lufs.is_finite());
+    let true_peak_dbtp = ebur128
+        .true_peak(0)
+        .ok()
+        .filter(|&peak| peak > 0.0)
+        .map(|peak| 20.0 * peak.log10());
+
+    Ok(LoudnessMeasurement { integrated_lufs, true_peak_dbtp })
+}
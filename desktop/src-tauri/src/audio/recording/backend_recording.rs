@@ -0,0 +1,153 @@
+//! Database rows for recordings started by the backend
+//!
+//! Recordings started from the UI get their row from the frontend, which
+//! saves it when the post-recording modal closes. Recordings started by the
+//! global hotkey or a schedule have no frontend owner, so the row is created
+//! here when they start and completed by `stop_recording`, whatever stops them.
+
+use std::path::Path;
+use std::sync::{Mutex, PoisonError};
+use std::time::Instant;
+
+use log::{info, warn};
+use tauri::{AppHandle, Manager, Runtime};
+
+use crate::audio::{post_processor, recording_saver};
+use crate::database::{Recording, RecordingUpdate, TranscriptSegment};
+use crate::state::AppState;
+use crate::auto_summary;
+
+struct BackendRecording {
+    id: String,
+    started_at: Instant,
+}
+
+/// Row of the active backend-started recording
+static ACTIVE: Mutex<Option<BackendRecording>> = Mutex::new(None);
+
+/// Create the row of a recording the backend just started and remember it
+/// so `stop_recording` completes it. Returns the recording id.
+pub async fn create_recording_row<R: Runtime>(
+    app: &AppHandle<R>,
+    title: Option<String>,
+    microphone_device: Option<String>,
+    system_audio_device: Option<String>,
+) -> Result<String, String> {
+    let id = format!("rec-{}", uuid::Uuid::new_v4());
+    let title = title.unwrap_or_else(|| {
+        format!(
+            "Recording {}",
+            chrono::Local::now().format("%Y-%m-%d %H:%M:%S")
+        )
+    });
+
+    let mut recording = Recording::new(id, title);
+    recording.microphone_device = microphone_device;
+    recording.system_audio_device = system_audio_device;
+
+    let id = {
+        let state = app.state::<AppState>();
+        let db = state.db().await;
+        db.create_recording(&recording)
+            .map_err(|e| format!("Failed to create recording in database: {}", e))?
+    };
+
+    info!(
+        "Created database recording {} for backend-started recording",
+        id
+    );
+    *ACTIVE.lock().unwrap_or_else(PoisonError::into_inner) = Some(BackendRecording {
+        id: id.clone(),
+        started_at: Instant::now(),
+    });
+    Ok(id)
+}
+
+/// Complete the row of the backend-started recording that just stopped:
+/// store its paths, duration and transcript. Returns the recording id, or
+/// None when the recording was started from the UI.
+pub async fn complete_recording_row<R: Runtime>(
+    app: &AppHandle<R>,
+    meeting_folder: Option<&Path>,
+    segments: &[recording_saver::TranscriptSegment],
+) -> Option<String> {
+    let active = ACTIVE
+        .lock()
+        .unwrap_or_else(PoisonError::into_inner)
+        .take()?;
+    let duration_seconds = active.started_at.elapsed().as_secs_f64().floor();
+
+    let updates = RecordingUpdate {
+        audio_file_path: meeting_folder.map(|f| f.join("audio.mp4").to_string_lossy().to_string()),
+        meeting_folder_path: meeting_folder.map(|f| f.to_string_lossy().to_string()),
+        ..Default::default()
+    };
+    let mut segments = database_segments(&active.id, segments);
+
+    {
+        let state = app.state::<AppState>();
+        let db = state.db().await;
+        let result = db
+            .update_recording(&active.id, &updates)
+            .and_then(|_| db.complete_recording(&active.id, duration_seconds))
+            .and_then(|_| post_processor::save_live_transcript(&db, &mut segments));
+
+        match result {
+            Ok(()) => info!(
+                "Completed database recording {} ({}s, {} segments)",
+                active.id,
+                duration_seconds,
+                segments.len()
+            ),
+            // Recovery marks the row interrupted on the next start
+            Err(e) => {
+                warn!("Failed to complete database recording {}: {}", active.id, e);
+                return Some(active.id);
+            }
+        }
+    }
+
+    super::completion::recording_completed(app, &active.id).await;
+    if !segments.is_empty() {
+        auto_summary::summarize_if_enabled(app, &active.id).await;
+    }
+    Some(active.id)
+}
+
+/// Drop the remembered row without completing it (recovery marks it
+/// interrupted on the next start)
+pub fn forget_recording_row() {
+    if let Some(stale) = ACTIVE.lock().unwrap_or_else(PoisonError::into_inner).take() {
+        warn!(
+            "Forgetting database recording {} that was never completed",
+            stale.id
+        );
+    }
+}
+
+/// Convert the recorder's transcript to database segments, skipping empty text
+fn database_segments(
+    recording_id: &str,
+    segments: &[recording_saver::TranscriptSegment],
+) -> Vec<TranscriptSegment> {
+    segments
+        .iter()
+        .filter(|s| !s.text.trim().is_empty())
+        .enumerate()
+        .map(|(index, s)| TranscriptSegment {
+            id: format!("{}-seg-{}", recording_id, index),
+            recording_id: recording_id.to_string(),
+            text: s.text.trim().to_string(),
+            audio_start_time: s.audio_start_time,
+            audio_end_time: s.audio_end_time,
+            duration: s.audio_end_time - s.audio_start_time,
+            display_time: s.display_time.clone(),
+            confidence: s.confidence,
+            sequence_id: s.sequence_id as i64,
+            speaker_id: s.speaker_id.clone(),
+            speaker_label: s.speaker_label.clone(),
+            is_registered_speaker: s.is_registered_speaker,
+            low_confidence: false,
+        })
+        .collect()
+}
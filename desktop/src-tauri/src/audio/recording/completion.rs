@@ -0,0 +1,27 @@
+//! Work that follows marking a recording completed
+//!
+//! Recordings are marked completed by the frontend (`db_complete_recording`),
+//! by the backend for hotkey and scheduled recordings
+//! (`backend_recording::complete_recording_row`, also on exit) and by crash
+//! recovery. Each then calls `recording_completed` so every completed
+//! recording is scored and announced the same way.
+
+use log::warn;
+use tauri::{AppHandle, Manager, Runtime};
+
+use crate::state::AppState;
+use crate::{transcription_quality, webhook};
+
+/// Score the transcript of a recording that was just marked completed and
+/// fire the completion webhook
+pub async fn recording_completed<R: Runtime>(app: &AppHandle<R>, recording_id: &str) {
+    {
+        let state = app.state::<AppState>();
+        let db = state.db().await;
+        if let Err(e) = transcription_quality::update_transcription_quality(&db, recording_id) {
+            warn!("Failed to score transcription quality of {}: {}", recording_id, e);
+        }
+    }
+
+    webhook::notify_recording_completed(app, recording_id).await;
+}
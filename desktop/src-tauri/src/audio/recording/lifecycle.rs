@@ -5,21 +5,65 @@ use log::{error, info, warn};
 use tauri::{AppHandle, Emitter, Manager, Runtime};
 
 use super::super::devices::{AudioDevice, DeviceType};
+use super::super::permissions;
 use super::super::RecordingManager;
 use super::super::transcription::{
     self,
+    reset_rolling_context,
+    reset_live_diarization,
     reset_speech_detected_flag,
 };
 use super::state::{
     IS_RECORDING, RECORDING_MANAGER, TRANSCRIPTION_TASK,
-    is_recording, set_recording, set_recording_manager, take_recording_manager,
+    bump_recording_generation, is_recording, set_recording, set_recording_manager, take_recording_manager,
     set_transcription_task, take_transcription_task,
 };
 use super::types::{RecordingArgs, TranscriptionStatus};
+use super::model_retention;
+use super::backend_recording;
 
 // Re-export TranscriptUpdate for backward compatibility
 pub use super::super::transcription::TranscriptUpdate;
 
+/// Check the permission when system audio is requested; without it system audio
+/// would record silence. Returns whether system audio can be recorded: with
+/// mic-only fallback allowed a missing permission gives false, so the recording
+/// continues with the microphone, otherwise it refuses to start. Emits an event
+/// so the UI can send the user to the right settings page.
+fn preflight_system_audio_permission<R: Runtime>(app: &AppHandle<R>) -> Result<bool, String> {
+    if permissions::has_system_audio_permission() {
+        return Ok(true);
+    }
+
+    if super::super::recording_preferences::allow_mic_only_fallback() {
+        warn!("⚠️ System audio permission missing, recording the microphone only");
+        return Ok(false);
+    }
+
+    let settings_url = permissions::system_audio_settings_url();
+    let error = "System audio permission denied: grant Screen Recording permission to the app \
+                 in System Settings → Privacy & Security → Screen Recording, then restart the app"
+        .to_string();
+    warn!("⚠️ Refusing to record system audio without permission");
+
+    let _ = app.emit(permissions::SYSTEM_AUDIO_PERMISSION_REQUIRED_EVENT, serde_json::json!({
+        "error": error,
+        "userMessage": "Recording system audio needs your permission. Grant it in System Settings, then restart the app.",
+        "settingsUrl": settings_url,
+        "actionable": true
+    }));
+
+    Err(error)
+}
+
+/// Tell the UI that system audio failed to start and only the microphone is recorded
+fn emit_mic_only_warning<R: Runtime>(app: &AppHandle<R>) {
+    let _ = app.emit("recording-warning", serde_json::json!({
+        "warning": "mic_only_fallback",
+        "userMessage": "System audio could not be started. Recording continues with the microphone only."
+    }));
+}
+
 /// Start recording with default devices
 pub async fn start_recording<R: Runtime>(app: AppHandle<R>) -> Result<(), String> {
     start_recording_with_meeting_name(app, None).await
@@ -41,8 +85,17 @@ pub async fn start_recording_with_meeting_name<R: Runtime>(
     if current_recording_state {
         return Err("Recording already in progress".to_string());
     }
+    if crate::audio::dictation::is_dictating() {
+        return Err("Cannot start a recording while dictation is in progress".to_string());
+    }
+    // A row left behind by a recording whose stop failed must not be
+    // completed with this recording's data
+    backend_recording::forget_recording_row();
 
     // Validate that transcription models are available before starting recording
+    // A retained model is about to be reused, don't let the idle timer unload it
+    model_retention::cancel_idle_unload();
+
     info!("🔍 Validating transcription model availability before starting recording...");
     if let Err(validation_error) = transcription::validate_transcription_model_ready(&app).await {
         error!("Model validation failed: {}", validation_error);
@@ -58,6 +111,9 @@ pub async fn start_recording_with_meeting_name<R: Runtime>(
     }
     info!("✅ Transcription model validation passed");
 
+    // Default devices include system audio
+    let system_audio_allowed = preflight_system_audio_permission(&app)?;
+
     // Async-first approach - no more blocking operations!
     info!("🚀 Starting async recording initialization");
 
@@ -74,6 +130,9 @@ pub async fn start_recording_with_meeting_name<R: Runtime>(
         )
     });
     manager.set_meeting_name(Some(effective_meeting_name));
+    if !system_audio_allowed {
+        manager.skip_system_audio();
+    }
 
     // Set up error callback
     let app_for_error = app.clone();
@@ -87,13 +146,20 @@ pub async fn start_recording_with_meeting_name<R: Runtime>(
         .await
         .map_err(|e| format!("Failed to start recording: {}", e))?;
 
+    if manager.is_mic_only_fallback() {
+        emit_mic_only_warning(&app);
+    }
+
     // Store the manager globally to keep it alive
     set_recording_manager(Some(manager));
 
     // Set recording flag and reset speech detection flag
     info!("🔍 Setting IS_RECORDING to true and resetting SPEECH_DETECTED_EMITTED");
+    bump_recording_generation();
     set_recording(true);
     reset_speech_detected_flag(); // Reset for new recording session
+    reset_rolling_context();
+    reset_live_diarization();
 
     // Start optimized parallel transcription task and store handle
     let task_handle = transcription::start_transcription_task(app.clone(), transcription_receiver);
@@ -118,6 +184,9 @@ pub async fn start_recording_with_meeting_name<R: Runtime>(
                     display_time: update.timestamp.clone(), // Use wall-clock timestamp for display
                     confidence: update.confidence,
                     sequence_id: update.sequence_id,
+                    speaker_id: update.speaker_id.clone(),
+                    speaker_label: update.speaker_label.clone(),
+                    is_registered_speaker: update.is_registered_speaker,
                 };
 
                 // Save to recording manager
@@ -174,8 +243,17 @@ pub async fn start_recording_with_devices_and_meeting<R: Runtime>(
     if current_recording_state {
         return Err("Recording already in progress".to_string());
     }
+    if crate::audio::dictation::is_dictating() {
+        return Err("Cannot start a recording while dictation is in progress".to_string());
+    }
+    // A row left behind by a recording whose stop failed must not be
+    // completed with this recording's data
+    backend_recording::forget_recording_row();
 
     // Validate that transcription models are available before starting recording
+    // A retained model is about to be reused, don't let the idle timer unload it
+    model_retention::cancel_idle_unload();
+
     info!("🔍 Validating transcription model availability before starting recording...");
     if let Err(validation_error) = transcription::validate_transcription_model_ready(&app).await {
         error!("Model validation failed: {}", validation_error);
@@ -191,6 +269,8 @@ pub async fn start_recording_with_devices_and_meeting<R: Runtime>(
     }
     info!("✅ Transcription model validation passed");
 
+    let system_audio_allowed = system_device_name.is_none() || preflight_system_audio_permission(&app)?;
+
     // DEBUG: Log what device names we receive from frontend
     info!("🔍 DEBUG: mic_device_name = {:?}", mic_device_name);
     info!("🔍 DEBUG: system_device_name = {:?}", system_device_name);
@@ -224,6 +304,9 @@ pub async fn start_recording_with_devices_and_meeting<R: Runtime>(
         )
     });
     manager.set_meeting_name(Some(effective_meeting_name));
+    if !system_audio_allowed {
+        manager.skip_system_audio();
+    }
 
     // Set up error callback
     let app_for_error = app.clone();
@@ -237,13 +320,20 @@ pub async fn start_recording_with_devices_and_meeting<R: Runtime>(
         .await
         .map_err(|e| format!("Failed to start recording: {}", e))?;
 
+    if manager.is_mic_only_fallback() {
+        emit_mic_only_warning(&app);
+    }
+
     // Store the manager globally to keep it alive
     set_recording_manager(Some(manager));
 
     // Set recording flag and reset speech detection flag
     info!("🔍 Setting IS_RECORDING to true and resetting SPEECH_DETECTED_EMITTED");
+    bump_recording_generation();
     set_recording(true);
     reset_speech_detected_flag(); // Reset for new recording session
+    reset_rolling_context();
+    reset_live_diarization();
 
     // Start optimized parallel transcription task and store handle
     let task_handle = transcription::start_transcription_task(app.clone(), transcription_receiver);
@@ -268,6 +358,9 @@ pub async fn start_recording_with_devices_and_meeting<R: Runtime>(
                     display_time: update.timestamp.clone(), // Use wall-clock timestamp for display
                     confidence: update.confidence,
                     sequence_id: update.sequence_id,
+                    speaker_id: update.speaker_id.clone(),
+                    speaker_label: update.speaker_label.clone(),
+                    is_registered_speaker: update.is_registered_speaker,
                 };
 
                 // Save to recording manager
@@ -409,18 +502,7 @@ pub async fn stop_recording<R: Runtime>(
     }
 
     // Step 3: Now safely unload Whisper model after ALL chunks are processed
-    let _ = app.emit(
-        "recording-shutdown-progress",
-        serde_json::json!({
-            "stage": "unloading_model",
-            "message": "Unloading speech recognition model...",
-            "progress": 70
-        }),
-    );
-
-    info!("🧠 All transcript chunks processed. Now safely unloading transcription model...");
-
-    // Determine which provider was used and unload the appropriate model
+    // Determine which provider was used so the right model gets unloaded
     let config = match crate::api::api::api_get_transcript_config(
         app.clone(),
         app.clone().state(),
@@ -432,58 +514,21 @@ pub async fn stop_recording<R: Runtime>(
         _ => None,
     };
 
-    match config.as_deref() {
-        Some("parakeet") => {
-            info!("🦜 Unloading Parakeet model...");
-            let engine_clone = {
-                let engine_guard = crate::parakeet_engine::commands::PARAKEET_ENGINE
-                    .lock()
-                    .unwrap();
-                engine_guard.as_ref().cloned()
-            };
-
-            if let Some(engine) = engine_clone {
-                let current_model = engine
-                    .get_current_model()
-                    .await
-                    .unwrap_or_else(|| "unknown".to_string());
-                info!("Current Parakeet model before unload: '{}'", current_model);
-
-                if engine.unload_model().await {
-                    info!("✅ Parakeet model '{}' unloaded successfully", current_model);
-                } else {
-                    warn!("⚠️ Failed to unload Parakeet model '{}'", current_model);
-                }
-            } else {
-                warn!("⚠️ No Parakeet engine found to unload model");
-            }
-        }
-        _ => {
-            // Default to Whisper
-            info!("🎤 Unloading Whisper model...");
-            let engine_clone = {
-                let engine_guard = crate::whisper_engine::commands::WHISPER_ENGINE
-                    .lock()
-                    .unwrap();
-                engine_guard.as_ref().cloned()
-            };
-
-            if let Some(engine) = engine_clone {
-                let current_model = engine
-                    .get_current_model()
-                    .await
-                    .unwrap_or_else(|| "unknown".to_string());
-                info!("Current Whisper model before unload: '{}'", current_model);
-
-                if engine.unload_model().await {
-                    info!("✅ Whisper model '{}' unloaded successfully", current_model);
-                } else {
-                    warn!("⚠️ Failed to unload Whisper model '{}'", current_model);
-                }
-            } else {
-                warn!("⚠️ No Whisper engine found to unload model");
-            }
-        }
+    if model_retention::is_keep_model_loaded_enabled() {
+        info!("🧠 Keeping transcription model loaded for the next recording");
+        model_retention::schedule_idle_unload(config);
+    } else {
+        let _ = app.emit(
+            "recording-shutdown-progress",
+            serde_json::json!({
+                "stage": "unloading_model",
+                "message": "Unloading speech recognition model...",
+                "progress": 70
+            }),
+        );
+
+        info!("🧠 All transcript chunks processed. Now safely unloading transcription model...");
+        model_retention::unload_transcription_model(config.as_deref()).await;
     }
 
     // Step 3.5: Track meeting ended analytics with privacy-safe metadata
@@ -600,12 +645,13 @@ pub async fn stop_recording<R: Runtime>(
     );
 
     // Perform final cleanup with the manager if available
-    let (meeting_folder, meeting_name) = if let Some(mut manager) = manager_for_cleanup {
+    let (meeting_folder, meeting_name, transcript_segments) = if let Some(mut manager) = manager_for_cleanup {
         info!("🧹 Performing final cleanup and saving recording data");
 
         // Extract meeting info BEFORE async operations
         let meeting_folder = manager.get_meeting_folder();
         let meeting_name = manager.get_meeting_name();
+        let transcript_segments = manager.get_transcript_segments();
 
         match manager.save_recording_only(&app).await {
             Ok(_) => {
@@ -620,10 +666,10 @@ pub async fn stop_recording<R: Runtime>(
             }
         }
 
-        (meeting_folder, meeting_name)
+        (meeting_folder, meeting_name, transcript_segments)
     } else {
         info!("ℹ️ No recording manager available for cleanup");
-        (None, None)
+        (None, None, Vec::new())
     };
 
     // Set recording flag to false
@@ -645,8 +691,17 @@ pub async fn stop_recording<R: Runtime>(
     info!("   folder_path: {:?}", folder_path_str);
     info!("   meeting_name: {:?}", meeting_name_str);
 
-    // Database save removed - frontend will handle this after receiving all transcripts
-    info!("ℹ️ Skipping database save in Rust - frontend will save after all transcripts received");
+    // Recordings started by the hotkey or a schedule are saved here; the
+    // frontend saves the ones it started after all transcripts are received
+    let backend_recording_id = backend_recording::complete_recording_row(
+        &app,
+        meeting_folder.as_deref(),
+        &transcript_segments,
+    )
+    .await;
+    if backend_recording_id.is_none() {
+        info!("ℹ️ Skipping database save in Rust - frontend will save after all transcripts received");
+    }
 
     // Step 5: Complete shutdown
     let _ = app.emit(
@@ -664,7 +719,9 @@ pub async fn stop_recording<R: Runtime>(
         serde_json::json!({
             "message": "Recording stopped - frontend will save after all transcripts received",
             "folder_path": folder_path_str,
-            "meeting_name": meeting_name_str
+            "meeting_name": meeting_name_str,
+            // Set when the recording was already saved to the database here
+            "recording_id": backend_recording_id
         }),
     )
     .map_err(|e| e.to_string())?;
@@ -5,12 +5,22 @@
 //! - Pause/resume functionality
 //! - Device monitoring and reconnection
 //! - Global recording state management
+//! - Transcription model retention between recordings
+//! - Scheduled (auto-start) recordings
+//! - Recovery of recordings interrupted by a crash or forced quit
+//! - Database rows for recordings started by the hotkey or a schedule
+//! - Scoring and announcing completed recordings
 
 pub mod types;
 pub mod state;
 pub mod lifecycle;
 pub mod pause_resume;
 pub mod device_events;
+pub mod model_retention;
+pub mod scheduler;
+pub mod recovery;
+pub mod backend_recording;
+pub mod completion;
 
 // Re-export types
 pub use types::{
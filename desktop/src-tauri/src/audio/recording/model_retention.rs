@@ -0,0 +1,191 @@
+//! Transcription model retention between recordings
+//!
+//! By default the Whisper/Parakeet model is unloaded when a recording stops.
+//! With `keep_model_loaded` enabled, the model stays resident and is only
+//! unloaded after an idle timeout, so back-to-back recordings skip the load cost.
+
+use std::sync::atomic::{AtomicBool, AtomicU64, AtomicUsize, Ordering};
+use std::time::Duration;
+use log::{info, warn};
+
+use super::state::is_recording;
+
+/// Default idle time before a retained model is unloaded
+pub const DEFAULT_IDLE_TIMEOUT_MINUTES: u64 = 15;
+
+static KEEP_MODEL_LOADED: AtomicBool = AtomicBool::new(false);
+static IDLE_TIMEOUT_MINUTES: AtomicU64 = AtomicU64::new(DEFAULT_IDLE_TIMEOUT_MINUTES);
+
+/// Bumped whenever the model is used, so stale idle timers know to bail out
+static IDLE_GENERATION: AtomicU64 = AtomicU64::new(0);
+
+/// Number of non-recording users of the model (e.g. retranscription)
+static ACTIVE_MODEL_USERS: AtomicUsize = AtomicUsize::new(0);
+
+pub fn is_keep_model_loaded_enabled() -> bool {
+    KEEP_MODEL_LOADED.load(Ordering::SeqCst)
+}
+
+pub fn set_keep_model_loaded_enabled(enabled: bool) {
+    let previous = KEEP_MODEL_LOADED.swap(enabled, Ordering::SeqCst);
+    if previous != enabled {
+        info!("🧠 Keep transcription model loaded {} (was {})",
+              if enabled { "ENABLED" } else { "DISABLED" },
+              if previous { "enabled" } else { "disabled" });
+    }
+    if !enabled {
+        // Invalidate any pending idle timer; the next stop unloads immediately
+        cancel_idle_unload();
+    }
+}
+
+pub fn get_idle_timeout_minutes() -> u64 {
+    IDLE_TIMEOUT_MINUTES.load(Ordering::SeqCst)
+}
+
+pub fn set_idle_timeout_minutes(minutes: u64) {
+    IDLE_TIMEOUT_MINUTES.store(minutes.max(1), Ordering::SeqCst);
+}
+
+/// Cancel any pending idle unload (called when the model is used again)
+pub fn cancel_idle_unload() {
+    IDLE_GENERATION.fetch_add(1, Ordering::SeqCst);
+}
+
+/// Whether a `ModelUseGuard` is alive, i.e. the model is in use outside of a recording
+pub fn is_model_in_use() -> bool {
+    ACTIVE_MODEL_USERS.load(Ordering::SeqCst) > 0
+}
+
+/// Marks the model as in use outside of a recording. While any guard is
+/// alive the idle timer won't unload the model; dropping the last guard
+/// re-arms the timer when retention is enabled.
+pub struct ModelUseGuard;
+
+impl ModelUseGuard {
+    pub fn acquire() -> Self {
+        ACTIVE_MODEL_USERS.fetch_add(1, Ordering::SeqCst);
+        cancel_idle_unload();
+        Self
+    }
+}
+
+impl Drop for ModelUseGuard {
+    fn drop(&mut self) {
+        let remaining = ACTIVE_MODEL_USERS.fetch_sub(1, Ordering::SeqCst) - 1;
+        if remaining == 0 && is_keep_model_loaded_enabled() && !is_recording() {
+            schedule_idle_unload(None);
+        }
+    }
+}
+
+/// Unload the transcription model for the given provider ("parakeet" or Whisper by default)
+pub async fn unload_transcription_model(provider: Option<&str>) {
+    match provider {
+        Some("parakeet") => {
+            info!("🦜 Unloading Parakeet model...");
+            let engine_clone = {
+                let engine_guard = crate::parakeet_engine::commands::PARAKEET_ENGINE
+                    .lock()
+                    .unwrap();
+                engine_guard.as_ref().cloned()
+            };
+
+            if let Some(engine) = engine_clone {
+                let current_model = engine
+                    .get_current_model()
+                    .await
+                    .unwrap_or_else(|| "unknown".to_string());
+                info!("Current Parakeet model before unload: '{}'", current_model);
+
+                if engine.unload_model().await {
+                    info!("✅ Parakeet model '{}' unloaded successfully", current_model);
+                } else {
+                    warn!("⚠️ Failed to unload Parakeet model '{}'", current_model);
+                }
+            } else {
+                warn!("⚠️ No Parakeet engine found to unload model");
+            }
+        }
+        _ => {
+            // Default to Whisper
+            info!("🎤 Unloading Whisper model...");
+            let engine_clone = {
+                let engine_guard = crate::whisper_engine::commands::WHISPER_ENGINE
+                    .lock()
+                    .unwrap();
+                engine_guard.as_ref().cloned()
+            };
+
+            if let Some(engine) = engine_clone {
+                let current_model = engine
+                    .get_current_model()
+                    .await
+                    .unwrap_or_else(|| "unknown".to_string());
+                info!("Current Whisper model before unload: '{}'", current_model);
+
+                if engine.unload_model().await {
+                    info!("✅ Whisper model '{}' unloaded successfully", current_model);
+                } else {
+                    warn!("⚠️ Failed to unload Whisper model '{}'", current_model);
+                }
+            } else {
+                warn!("⚠️ No Whisper engine found to unload model");
+            }
+        }
+    }
+}
+
+/// Unload the model after the idle timeout unless it gets used again first.
+/// `provider` is None when the caller doesn't know it; both engines are unloaded then.
+pub fn schedule_idle_unload(provider: Option<String>) {
+    let generation = IDLE_GENERATION.fetch_add(1, Ordering::SeqCst) + 1;
+    let timeout_minutes = get_idle_timeout_minutes();
+    info!("⏲️ Transcription model will be unloaded after {} idle minutes", timeout_minutes);
+
+    tauri::async_runtime::spawn(async move {
+        tokio::time::sleep(Duration::from_secs(timeout_minutes * 60)).await;
+
+        if IDLE_GENERATION.load(Ordering::SeqCst) != generation {
+            // Model was used (or retention toggled) since this timer was armed
+            return;
+        }
+        if is_recording() || ACTIVE_MODEL_USERS.load(Ordering::SeqCst) > 0 {
+            return;
+        }
+
+        info!("💤 Transcription model idle for {} minutes, unloading", timeout_minutes);
+        match provider.as_deref() {
+            Some(p) => unload_transcription_model(Some(p)).await,
+            None => {
+                unload_transcription_model(Some("parakeet")).await;
+                unload_transcription_model(None).await;
+            }
+        }
+    });
+}
+
+#[tauri::command]
+pub fn get_keep_model_loaded() -> bool {
+    is_keep_model_loaded_enabled()
+}
+
+#[tauri::command]
+pub fn set_keep_model_loaded(enabled: bool) -> Result<(), String> {
+    set_keep_model_loaded_enabled(enabled);
+    Ok(())
+}
+
+#[tauri::command]
+pub fn get_model_idle_timeout_minutes() -> u64 {
+    get_idle_timeout_minutes()
+}
+
+#[tauri::command]
+pub fn set_model_idle_timeout_minutes(minutes: u64) -> Result<(), String> {
+    if minutes == 0 {
+        return Err("Idle timeout must be at least 1 minute".to_string());
+    }
+    set_idle_timeout_minutes(minutes);
+    Ok(())
+}
@@ -0,0 +1,120 @@
+//! Crash recovery for interrupted recordings
+//!
+//! A recording whose app quit or crashed before `stop_recording` finished is
+//! left in the "recording" state, with its audio still split into the
+//! incremental saver's 30s checkpoints. On startup such recordings are marked
+//! "interrupted", and the ones with checkpoints on disk are announced with a
+//! `recovery-available` event so the UI can offer to recover them.
+
+use log::{info, warn};
+use serde::Serialize;
+use tauri::{AppHandle, Emitter, Manager, Runtime};
+
+use crate::audio::incremental_saver::{count_checkpoints, recover_from_checkpoints};
+use crate::database::{DatabaseManager, Recording, RecordingUpdate};
+use crate::state::AppState;
+
+/// An interrupted recording whose audio can be stitched back together
+#[derive(Debug, Clone, Serialize)]
+pub struct RecoverableRecording {
+    pub recording_id: String,
+    pub title: String,
+    pub created_at: String,
+    pub meeting_folder: String,
+    pub checkpoint_count: u32,
+}
+
+fn find_recoverable_recordings(db: &DatabaseManager) -> anyhow::Result<Vec<RecoverableRecording>> {
+    let recordings = db.get_recordings_with_status("interrupted")?;
+
+    Ok(recordings
+        .into_iter()
+        .filter_map(|recording| {
+            let folder = recording.folder_path()?;
+            let checkpoint_count = count_checkpoints(&folder);
+            (checkpoint_count > 0).then(|| RecoverableRecording {
+                recording_id: recording.id,
+                title: recording.title,
+                created_at: recording.created_at,
+                meeting_folder: folder.to_string_lossy().to_string(),
+                checkpoint_count,
+            })
+        })
+        .collect())
+}
+
+/// Mark recordings left unfinished by the previous run as interrupted and emit
+/// `recovery-available` with the ones that can be recovered
+pub async fn check_interrupted_recordings<R: Runtime>(app: &AppHandle<R>) {
+    let recoverable = {
+        let state = app.state::<AppState>();
+        let db = state.db().await;
+        match db.mark_unfinished_recordings_interrupted() {
+            Ok(0) => {}
+            Ok(count) => info!("Marked {} unfinished recordings as interrupted", count),
+            Err(e) => warn!("Failed to mark unfinished recordings as interrupted: {}", e),
+        }
+        find_recoverable_recordings(&db)
+    };
+
+    match recoverable {
+        Ok(recordings) if !recordings.is_empty() => {
+            info!("🩹 {} interrupted recordings can be recovered", recordings.len());
+            let _ = app.emit("recovery-available", serde_json::json!({ "recordings": recordings }));
+        }
+        Ok(_) => {}
+        Err(e) => warn!("Failed to look for recoverable recordings: {}", e),
+    }
+}
+
+/// Interrupted recordings with audio checkpoints on disk
+#[tauri::command]
+pub async fn get_recoverable_recordings(
+    state: tauri::State<'_, AppState>,
+) -> Result<Vec<RecoverableRecording>, String> {
+    let db = state.db().await;
+    find_recoverable_recordings(&db).map_err(|e| e.to_string())
+}
+
+/// Stitch an interrupted recording's checkpoints into its audio file and mark it completed
+#[tauri::command]
+pub async fn recover_interrupted_recording(
+    app: AppHandle,
+    state: tauri::State<'_, AppState>,
+    recording_id: String,
+) -> Result<Recording, String> {
+    let recording = state
+        .db()
+        .await
+        .get_recording(&recording_id)
+        .map_err(|e| e.to_string())?
+        .ok_or_else(|| format!("Recording {} not found", recording_id))?;
+
+    if recording.status != "interrupted" {
+        return Err(format!("Recording {} was not interrupted", recording_id));
+    }
+    let folder = recording
+        .folder_path()
+        .ok_or_else(|| format!("Recording {} has no meeting folder", recording_id))?;
+
+    let audio_path = recover_from_checkpoints(&folder)
+        .await
+        .map_err(|e| format!("Failed to recover recording: {}", e))?;
+    info!("🩹 Recovered recording {} → {}", recording_id, audio_path.display());
+
+    state.db().await.update_recording(&recording_id, &RecordingUpdate {
+        status: Some("completed".to_string()),
+        completed_at: Some(chrono::Utc::now().to_rfc3339()),
+        audio_file_path: Some(audio_path.to_string_lossy().to_string()),
+        ..Default::default()
+    }).map_err(|e| e.to_string())?;
+
+    super::completion::recording_completed(&app, &recording_id).await;
+
+    state
+        .db()
+        .await
+        .get_recording(&recording_id)
+        .map_err(|e| e.to_string())?
+        .ok_or_else(|| format!("Recording {} not found", recording_id))
+}
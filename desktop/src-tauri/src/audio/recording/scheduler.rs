@@ -0,0 +1,325 @@
+//! Scheduled recordings
+//!
+//! A background task checks the `scheduled_recordings` table and starts a
+//! recording with the preset devices and meeting name when an occurrence is
+//! due. Occurrences missed while the app was closed are skipped with a warning.
+
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::Duration;
+
+use chrono::{DateTime, Datelike, Local, TimeZone, Weekday};
+use log::{error, info, warn};
+use tauri::{AppHandle, Emitter, Manager, Runtime};
+
+use super::state::{is_recording, recording_generation};
+use crate::database::{
+    CreateScheduledRecording, ScheduleRecurrence, ScheduledRecording, UpdateScheduledRecording,
+};
+use crate::state::AppState;
+
+/// How often the scheduler checks for due recordings
+const CHECK_INTERVAL_SECS: u64 = 30;
+
+/// An occurrence older than this is considered missed (app was closed) and skipped
+const START_GRACE_MINUTES: i64 = 5;
+
+static SCHEDULER_STARTED: AtomicBool = AtomicBool::new(false);
+
+/// Latest occurrence of a schedule at or before `now`, or None if it hasn't started yet
+pub fn latest_occurrence<Tz: TimeZone>(
+    start: &DateTime<Tz>,
+    recurrence: ScheduleRecurrence,
+    now: &DateTime<Tz>,
+) -> Option<DateTime<Tz>> {
+    if start > now {
+        return None;
+    }
+    if recurrence == ScheduleRecurrence::Once {
+        return Some(start.clone());
+    }
+
+    let tz = start.timezone();
+    let start_date = start.naive_local().date();
+    let start_time = start.naive_local().time();
+    let today = now.naive_local().date();
+
+    // A matching day is always found within the last week
+    for days_back in 0..=7 {
+        let date = today - chrono::Duration::days(days_back);
+        if date < start_date {
+            break;
+        }
+
+        let matches = match recurrence {
+            ScheduleRecurrence::Daily => true,
+            ScheduleRecurrence::Weekdays => !matches!(date.weekday(), Weekday::Sat | Weekday::Sun),
+            ScheduleRecurrence::Weekly => date.weekday() == start_date.weekday(),
+            ScheduleRecurrence::Once => unreachable!(),
+        };
+        if !matches {
+            continue;
+        }
+
+        if let Some(occurrence) = tz.from_local_datetime(&date.and_time(start_time)).earliest() {
+            if occurrence <= *now {
+                return Some(occurrence);
+            }
+        }
+    }
+
+    None
+}
+
+/// Start the background scheduler (once per app run)
+pub fn start_scheduler<R: Runtime>(app: AppHandle<R>) {
+    if SCHEDULER_STARTED.swap(true, Ordering::SeqCst) {
+        return;
+    }
+
+    info!("📅 Recording scheduler started");
+    tauri::async_runtime::spawn(async move {
+        loop {
+            check_schedules(&app).await;
+            tokio::time::sleep(Duration::from_secs(CHECK_INTERVAL_SECS)).await;
+        }
+    });
+}
+
+async fn check_schedules<R: Runtime>(app: &AppHandle<R>) {
+    let schedules = {
+        let state = app.state::<AppState>();
+        let db = state.db().await;
+        match db.get_enabled_scheduled_recordings() {
+            Ok(schedules) => schedules,
+            Err(e) => {
+                error!("Failed to load scheduled recordings: {}", e);
+                return;
+            }
+        }
+    };
+
+    let now = Local::now();
+    for schedule in schedules {
+        let Some(occurrence) = due_occurrence(&schedule, &now) else {
+            continue;
+        };
+
+        // Mark first so a failed start isn't retried every tick
+        {
+            let state = app.state::<AppState>();
+            let db = state.db().await;
+            if let Err(e) = db.mark_scheduled_recording_triggered(&schedule.id, &occurrence.to_rfc3339()) {
+                error!("Failed to update scheduled recording '{}': {}", schedule.name, e);
+                continue;
+            }
+        }
+
+        if now - occurrence > chrono::Duration::minutes(START_GRACE_MINUTES) {
+            warn!("📅 Skipping scheduled recording '{}' at {}: the app was not running at the scheduled time",
+                  schedule.name, occurrence.format("%Y-%m-%d %H:%M"));
+            continue;
+        }
+        if is_recording() {
+            warn!("📅 Skipping scheduled recording '{}': a recording is already in progress", schedule.name);
+            continue;
+        }
+
+        start_scheduled_recording(app, &schedule).await;
+    }
+}
+
+/// The occurrence that should fire now, if it hasn't been handled yet
+fn due_occurrence(schedule: &ScheduledRecording, now: &DateTime<Local>) -> Option<DateTime<Local>> {
+    let start = match DateTime::parse_from_rfc3339(&schedule.start_time) {
+        Ok(start) => start.with_timezone(&Local),
+        Err(e) => {
+            warn!("Scheduled recording '{}' has an invalid start time: {}", schedule.name, e);
+            return None;
+        }
+    };
+
+    let occurrence = latest_occurrence(&start, schedule.recurrence, now)?;
+
+    let already_handled = schedule
+        .last_triggered_at
+        .as_deref()
+        .and_then(|t| DateTime::parse_from_rfc3339(t).ok())
+        .map(|t| t.with_timezone(&Local))
+        .is_some_and(|last| last >= occurrence);
+
+    if already_handled { None } else { Some(occurrence) }
+}
+
+async fn start_scheduled_recording<R: Runtime>(app: &AppHandle<R>, schedule: &ScheduledRecording) {
+    info!("📅 Starting scheduled recording '{}'", schedule.name);
+
+    let result = super::lifecycle::start_recording_with_devices_and_meeting(
+        app.clone(),
+        schedule.mic_device.clone(),
+        schedule.system_device.clone(),
+        Some(schedule.name.clone()),
+    )
+    .await;
+
+    if let Err(e) = result {
+        error!("Failed to start scheduled recording '{}': {}", schedule.name, e);
+        let _ = app.emit("scheduled-recording-failed", serde_json::json!({
+            "scheduleId": schedule.id,
+            "name": schedule.name,
+            "error": e,
+        }));
+        return;
+    }
+
+    crate::globals::set_recording_flag(true);
+    let generation = recording_generation();
+
+    // No frontend owns this recording, so its database row is created here
+    // and completed when the recording stops
+    let recording_id = match super::backend_recording::create_recording_row(
+        app,
+        Some(schedule.name.clone()),
+        schedule.mic_device.clone(),
+        schedule.system_device.clone(),
+    )
+    .await
+    {
+        Ok(id) => Some(id),
+        Err(e) => {
+            warn!("📅 Scheduled recording '{}' started without a database row: {}", schedule.name, e);
+            None
+        }
+    };
+
+    let _ = app.emit("scheduled-recording-started", serde_json::json!({
+        "scheduleId": schedule.id,
+        "name": schedule.name,
+        "durationMinutes": schedule.duration_minutes,
+        "recordingId": recording_id,
+    }));
+
+    if let Some(minutes) = schedule.duration_minutes.filter(|m| *m > 0) {
+        schedule_auto_stop(app.clone(), schedule.name.clone(), generation, minutes as u64);
+    }
+}
+
+/// Stop the scheduled recording after `minutes`, unless it was stopped manually first
+fn schedule_auto_stop<R: Runtime>(app: AppHandle<R>, name: String, generation: u64, minutes: u64) {
+    tauri::async_runtime::spawn(async move {
+        let deadline = tokio::time::Instant::now() + Duration::from_secs(minutes * 60);
+
+        // Poll so a manual stop ends the timer and a later recording of any kind isn't cut short
+        while tokio::time::Instant::now() < deadline {
+            tokio::time::sleep(Duration::from_secs(5)).await;
+            if !is_recording() || recording_generation() != generation {
+                info!("📅 Scheduled recording '{}' was stopped manually", name);
+                return;
+            }
+        }
+
+        info!("📅 Scheduled recording '{}' reached its {} minute duration, stopping", name, minutes);
+        let result = super::lifecycle::stop_recording(
+            app.clone(),
+            super::types::RecordingArgs { save_path: String::new() },
+        )
+        .await;
+        crate::globals::set_recording_flag(false);
+
+        if let Err(e) = result {
+            error!("Failed to stop scheduled recording '{}': {}", name, e);
+        }
+        let _ = app.emit("scheduled-recording-stopped", serde_json::json!({ "name": name }));
+    });
+}
+
+// ============== Commands ==============
+
+#[tauri::command]
+pub async fn get_scheduled_recordings(
+    state: tauri::State<'_, AppState>,
+) -> Result<Vec<ScheduledRecording>, String> {
+    let db = state.db().await;
+    db.get_all_scheduled_recordings().map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub async fn create_scheduled_recording(
+    schedule: CreateScheduledRecording,
+    state: tauri::State<'_, AppState>,
+) -> Result<String, String> {
+    let db = state.db().await;
+    db.create_scheduled_recording(&schedule).map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub async fn update_scheduled_recording(
+    id: String,
+    update: UpdateScheduledRecording,
+    state: tauri::State<'_, AppState>,
+) -> Result<(), String> {
+    let db = state.db().await;
+    db.update_scheduled_recording(&id, &update).map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub async fn delete_scheduled_recording(
+    id: String,
+    state: tauri::State<'_, AppState>,
+) -> Result<(), String> {
+    let db = state.db().await;
+    db.delete_scheduled_recording(&id).map_err(|e| e.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::FixedOffset;
+
+    fn at(s: &str) -> DateTime<FixedOffset> {
+        DateTime::parse_from_rfc3339(s).unwrap()
+    }
+
+    #[test]
+    fn test_once_occurrence() {
+        let start = at("2025-01-06T09:30:00+00:00");
+        assert!(latest_occurrence(&start, ScheduleRecurrence::Once, &at("2025-01-06T09:00:00+00:00")).is_none());
+        assert_eq!(
+            latest_occurrence(&start, ScheduleRecurrence::Once, &at("2025-01-08T12:00:00+00:00")),
+            Some(start)
+        );
+    }
+
+    #[test]
+    fn test_daily_occurrence() {
+        let start = at("2025-01-06T09:30:00+00:00");
+        // Before today's slot -> yesterday's
+        assert_eq!(
+            latest_occurrence(&start, ScheduleRecurrence::Daily, &at("2025-01-10T09:00:00+00:00")),
+            Some(at("2025-01-09T09:30:00+00:00"))
+        );
+        // After today's slot -> today's
+        assert_eq!(
+            latest_occurrence(&start, ScheduleRecurrence::Daily, &at("2025-01-10T09:31:00+00:00")),
+            Some(at("2025-01-10T09:30:00+00:00"))
+        );
+    }
+
+    #[test]
+    fn test_weekdays_occurrence_skips_weekend() {
+        // 2025-01-06 is a Monday; 2025-01-12 is a Sunday
+        let start = at("2025-01-06T09:30:00+00:00");
+        assert_eq!(
+            latest_occurrence(&start, ScheduleRecurrence::Weekdays, &at("2025-01-12T10:00:00+00:00")),
+            Some(at("2025-01-10T09:30:00+00:00"))
+        );
+    }
+
+    #[test]
+    fn test_weekly_occurrence() {
+        let start = at("2025-01-06T09:30:00+00:00");
+        assert_eq!(
+            latest_occurrence(&start, ScheduleRecurrence::Weekly, &at("2025-01-15T10:00:00+00:00")),
+            Some(at("2025-01-13T09:30:00+00:00"))
+        );
+    }
+}
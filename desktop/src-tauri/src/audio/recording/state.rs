@@ -1,7 +1,7 @@
 //! Global recording state management
 
 use std::sync::{
-    atomic::{AtomicBool, Ordering},
+    atomic::{AtomicBool, AtomicU64, Ordering},
     Mutex,
 };
 use tokio::task::JoinHandle;
@@ -11,6 +11,9 @@ use super::super::RecordingManager;
 // Simple recording state tracking
 pub static IS_RECORDING: AtomicBool = AtomicBool::new(false);
 
+// Bumped on every recording start so timers tied to one recording can tell it apart from a later one
+static RECORDING_GENERATION: AtomicU64 = AtomicU64::new(0);
+
 // Global recording manager and transcription task to keep them alive during recording
 pub static RECORDING_MANAGER: Mutex<Option<RecordingManager>> = Mutex::new(None);
 pub static TRANSCRIPTION_TASK: Mutex<Option<JoinHandle<()>>> = Mutex::new(None);
@@ -25,6 +28,16 @@ pub fn set_recording(value: bool) {
     IS_RECORDING.store(value, Ordering::SeqCst);
 }
 
+/// Generation of the current (or most recent) recording
+pub fn recording_generation() -> u64 {
+    RECORDING_GENERATION.load(Ordering::SeqCst)
+}
+
+/// Start a new recording generation
+pub fn bump_recording_generation() -> u64 {
+    RECORDING_GENERATION.fetch_add(1, Ordering::SeqCst) + 1
+}
+
 /// Get a reference to the recording manager (takes the lock)
 pub fn with_recording_manager<T, F: FnOnce(Option<&RecordingManager>) -> T>(f: F) -> T {
     let guard = RECORDING_MANAGER.lock().unwrap();
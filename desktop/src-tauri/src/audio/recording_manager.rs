@@ -29,6 +29,10 @@ pub struct RecordingManager {
     recording_saver: RecordingSaver,
     device_monitor: Option<AudioDeviceMonitor>,
     device_event_receiver: Option<mpsc::UnboundedReceiver<DeviceEvent>>,
+    /// System audio was requested but failed to start, recording the mic only
+    mic_only_fallback: bool,
+    /// Leave out system audio even when a device is given
+    skip_system_audio: bool,
 }
 
 // SAFETY: RecordingManager contains types that we've marked as Send
@@ -49,6 +53,8 @@ impl RecordingManager {
             recording_saver: RecordingSaver::new(),
             device_monitor: Some(device_monitor),
             device_event_receiver: Some(device_event_receiver),
+            mic_only_fallback: false,
+            skip_system_audio: false,
         }
     }
 
@@ -62,6 +68,14 @@ impl RecordingManager {
     ) -> Result<mpsc::UnboundedReceiver<AudioChunk>> {
         info!("Starting recording manager");
 
+        let system_device = if self.skip_system_audio && system_device.is_some() {
+            warn!("⚠️ Leaving out system audio, recording the microphone only");
+            self.mic_only_fallback = true;
+            None
+        } else {
+            system_device
+        };
+
         // Set up transcription channel
         let (transcription_sender, transcription_receiver) = mpsc::unbounded_channel::<AudioChunk>();
 
@@ -105,6 +119,7 @@ impl RecordingManager {
             0, // Ignored - using dynamic sizing internally
             48000, // 48kHz sample rate
             Some(recording_sender), // CRITICAL: Pass recording sender to receive pre-mixed audio
+            self.recording_saver.channel_layout(), // Must match what the saver encodes
             mic_name,
             mic_kind,
             sys_name,
@@ -118,6 +133,24 @@ impl RecordingManager {
         // Pipeline handles mixing and distribution to both recording and transcription
         self.stream_manager.start_streams(microphone_device.clone(), system_device.clone(), None).await?;
 
+        // A system stream that failed to start leaves only the microphone
+        let system_device = if system_device.is_some() && !self.stream_manager.has_system_stream() {
+            if !super::recording_preferences::allow_mic_only_fallback() {
+                return Err(anyhow::anyhow!(
+                    "System audio could not be started and mic-only fallback is disabled"
+                ));
+            }
+            warn!("⚠️ System audio could not be started, continuing with the microphone only");
+            self.mic_only_fallback = true;
+            self.recording_saver.set_device_info(
+                microphone_device.as_ref().map(|d| d.name.clone()),
+                None,
+            );
+            None
+        } else {
+            system_device
+        };
+
         // WARM-UP PHASE: Allow audio processors to calibrate before transcription
         // - EBU R128 normalizer needs ~500ms-1s to learn correct gain
         // - RNNoise neural network needs audio context for effective denoising
@@ -407,6 +440,17 @@ impl RecordingManager {
         self.stream_manager.active_stream_count()
     }
 
+    /// Check if system audio failed to start and only the microphone is recorded
+    pub fn is_mic_only_fallback(&self) -> bool {
+        self.mic_only_fallback
+    }
+
+    /// Record the microphone only, as a mic-only fallback, even if a system
+    /// audio device is requested (e.g. its permission is missing)
+    pub fn skip_system_audio(&mut self) {
+        self.skip_system_audio = true;
+    }
+
     /// Set error callback for handling errors
     pub fn set_error_callback<F>(&self, callback: F)
     where
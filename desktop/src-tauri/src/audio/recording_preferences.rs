@@ -1,6 +1,7 @@
 use serde::{Deserialize, Serialize};
 use std::path::PathBuf;
-use tauri::{AppHandle, Runtime};
+use std::sync::atomic::{AtomicBool, Ordering};
+use tauri::{AppHandle, Emitter, Runtime};
 use log::{info, warn};
 
 #[cfg(target_os = "macos")]
@@ -18,6 +19,27 @@ pub struct RecordingPreferences {
     #[cfg(target_os = "macos")]
     #[serde(default)]
     pub system_audio_backend: Option<String>,
+    /// Keep recording the microphone when system audio can't be started
+    #[serde(default = "default_allow_mic_only_fallback")]
+    pub allow_mic_only_fallback: bool,
+}
+
+fn default_allow_mic_only_fallback() -> bool {
+    true
+}
+
+/// Mic-only fallback flag - controlled via preferences and the `allow_mic_only_fallback` setting
+static ALLOW_MIC_ONLY_FALLBACK: AtomicBool = AtomicBool::new(true);
+
+/// Allow or refuse recording with the microphone only when the system audio stream fails to start
+pub fn set_allow_mic_only_fallback(allowed: bool) {
+    ALLOW_MIC_ONLY_FALLBACK.store(allowed, Ordering::SeqCst);
+    info!("Mic-only fallback {}", if allowed { "allowed" } else { "disabled" });
+}
+
+/// Check if recording falls back to the microphone only when system audio fails
+pub fn allow_mic_only_fallback() -> bool {
+    ALLOW_MIC_ONLY_FALLBACK.load(Ordering::SeqCst)
 }
 
 impl Default for RecordingPreferences {
@@ -28,6 +50,7 @@ impl Default for RecordingPreferences {
             file_format: "mp4".to_string(),
             #[cfg(target_os = "macos")]
             system_audio_backend: Some("coreaudio".to_string()),
+            allow_mic_only_fallback: default_allow_mic_only_fallback(),
         }
     }
 }
@@ -93,7 +116,7 @@ pub async fn load_recording_preferences<R: Runtime>(
     // Try to load from Tauri store, fallback to defaults
     // For now, return defaults - can be enhanced to use tauri-plugin-store
     #[cfg(target_os = "macos")]
-    let prefs = {
+    let mut prefs = {
         let mut p = RecordingPreferences::default();
         let backend = crate::audio::capture::get_current_backend();
         p.system_audio_backend = Some(backend.to_string());
@@ -101,7 +124,9 @@ pub async fn load_recording_preferences<R: Runtime>(
     };
 
     #[cfg(not(target_os = "macos"))]
-    let prefs = RecordingPreferences::default();
+    let mut prefs = RecordingPreferences::default();
+
+    prefs.allow_mic_only_fallback = allow_mic_only_fallback();
 
     info!("Loaded recording preferences: save_folder={:?}, auto_save={}, format={}",
           prefs.save_folder, prefs.auto_save, prefs.file_format);
@@ -117,6 +142,8 @@ pub async fn save_recording_preferences<R: Runtime>(
     info!("Saving recording preferences: save_folder={:?}, auto_save={}, format={}",
           preferences.save_folder, preferences.auto_save, preferences.file_format);
 
+    set_allow_mic_only_fallback(preferences.allow_mic_only_fallback);
+
     // Save backend preference to global config
     #[cfg(target_os = "macos")]
     if let Some(backend_str) = &preferences.system_audio_backend {
@@ -284,12 +311,41 @@ pub async fn get_current_audio_backend() -> Result<String, String> {
     }
 }
 
+/// Event emitted after the capture backend changes; devices can differ per backend
+pub const AUDIO_BACKEND_CHANGED_EVENT: &str = "audio-backend-changed";
+
+/// Canonical id for a backend name, accepting aliases like "core_audio"
+fn normalize_backend_id(backend: &str) -> String {
+    #[cfg(target_os = "macos")]
+    if let Some(backend_enum) = AudioCaptureBackend::from_string(backend) {
+        return backend_enum.to_string();
+    }
+    backend.trim().to_lowercase()
+}
+
 /// Set audio capture backend
 #[tauri::command]
-pub async fn set_audio_backend(backend: String) -> Result<(), String> {
+pub async fn set_audio_backend<R: Runtime>(app: AppHandle<R>, backend: String) -> Result<(), String> {
+    let backend = normalize_backend_id(&backend);
+    let available = get_available_audio_backends().await?;
+    if !available.contains(&backend) {
+        return Err(format!(
+            "Audio backend '{}' is not available on this platform (available: {})",
+            backend,
+            available.join(", ")
+        ));
+    }
+
+    let previous = get_current_audio_backend().await?;
+    if backend == previous {
+        return Ok(());
+    }
+    if crate::audio::recording::state::is_recording() {
+        return Err("Cannot switch the audio backend while recording. Stop the recording first.".to_string());
+    }
+
     #[cfg(target_os = "macos")]
     {
-        use crate::audio::capture::AudioCaptureBackend;
         use crate::audio::permissions::{check_screen_recording_permission, request_screen_recording_permission};
 
         let backend_enum = AudioCaptureBackend::from_string(&backend)
@@ -322,16 +378,16 @@ pub async fn set_audio_backend(backend: String) -> Result<(), String> {
 
         info!("Setting audio backend to: {:?}", backend_enum);
         crate::audio::capture::set_current_backend(backend_enum);
-        Ok(())
     }
 
-    #[cfg(not(target_os = "macos"))]
-    {
-        if backend != "screencapturekit" {
-            return Err(format!("Backend {} not available on this platform", backend));
-        }
-        Ok(())
+    info!("Audio backend changed from {} to {}", previous, backend);
+    if let Err(e) = app.emit(
+        AUDIO_BACKEND_CHANGED_EVENT,
+        serde_json::json!({ "backend": backend, "previous": previous }),
+    ) {
+        warn!("Failed to emit {}: {}", AUDIO_BACKEND_CHANGED_EVENT, e);
     }
+    Ok(())
 }
 
 /// Get backend information (name and description)
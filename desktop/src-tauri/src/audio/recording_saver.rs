@@ -11,6 +11,8 @@ use super::recording_state::AudioChunk;
 use super::recording_preferences::load_recording_preferences;
 use super::audio_processing::create_meeting_folder;
 use super::incremental_saver::IncrementalAudioSaver;
+use super::level_timeline::{self, LevelTimelineRecorder};
+use super::pipeline::{get_save_channel_layout, SaveChannelLayout};
 
 /// Structured transcript segment for JSON export
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -23,6 +25,13 @@ pub struct TranscriptSegment {
     pub display_time: String,   // Formatted time for display like "[02:15]"
     pub confidence: f32,
     pub sequence_id: u64,
+    /// Speaker from live diarization or source labels (missing in older transcripts)
+    #[serde(default)]
+    pub speaker_id: Option<String>,
+    #[serde(default)]
+    pub speaker_label: Option<String>,
+    #[serde(default)]
+    pub is_registered_speaker: bool,
 }
 
 /// Meeting metadata structure
@@ -39,6 +48,9 @@ pub struct MeetingMetadata {
     pub transcript_file: String,
     pub sample_rate: u32,
     pub status: String,  // "recording", "completed", "error"
+    /// Missing in metadata written before stereo-split saving existed
+    #[serde(default)]
+    pub channel_layout: SaveChannelLayout,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -56,6 +68,8 @@ pub struct RecordingSaver {
     transcript_segments: Arc<Mutex<Vec<TranscriptSegment>>>,
     chunk_receiver: Option<mpsc::UnboundedReceiver<AudioChunk>>,
     is_saving: Arc<Mutex<bool>>,
+    level_timeline: Option<Arc<Mutex<LevelTimelineRecorder>>>,
+    channel_layout: SaveChannelLayout,
 }
 
 impl RecordingSaver {
@@ -68,6 +82,8 @@ impl RecordingSaver {
             transcript_segments: Arc::new(Mutex::new(Vec::new())),
             chunk_receiver: None,
             is_saving: Arc::new(Mutex::new(false)),
+            level_timeline: None,
+            channel_layout: SaveChannelLayout::MonoMix,
         }
     }
 
@@ -130,6 +146,9 @@ impl RecordingSaver {
             display_time: "[00:00]".to_string(),
             confidence: 1.0,
             sequence_id: 0,
+            speaker_id: None,
+            speaker_label: None,
+            is_registered_speaker: false,
         };
         self.add_transcript_segment(segment);
     }
@@ -142,6 +161,9 @@ impl RecordingSaver {
         let (sender, receiver) = mpsc::unbounded_channel::<AudioChunk>();
         self.chunk_receiver = Some(receiver);
 
+        // Fixed for the whole recording; the pipeline is started with the same layout
+        self.channel_layout = get_save_channel_layout();
+
         // Initialize meeting folder and incremental saver if meeting name provided
         if let Some(name) = self.meeting_name.clone() {
             match self.initialize_meeting_folder(&name) {
@@ -153,9 +175,18 @@ impl RecordingSaver {
             }
         }
 
+        // Optional per-second level history of the mixed audio
+        self.level_timeline = if level_timeline::is_level_timeline_enabled() {
+            Some(Arc::new(Mutex::new(LevelTimelineRecorder::new(48000))))
+        } else {
+            None
+        };
+
         // Start accumulation task
         let is_saving_clone = self.is_saving.clone();
         let incremental_saver_arc = self.incremental_saver.clone();
+        let level_timeline_arc = self.level_timeline.clone();
+        let channel_layout = self.channel_layout;
 
         if let Some(mut receiver) = self.chunk_receiver.take() {
             tokio::spawn(async move {
@@ -173,6 +204,22 @@ impl RecordingSaver {
                         break;
                     }
 
+                    if let Some(recorder) = &level_timeline_arc {
+                        if let Ok(mut recorder) = recorder.lock() {
+                            match channel_layout {
+                                SaveChannelLayout::MonoMix => recorder.add_samples(&chunk.data),
+                                SaveChannelLayout::StereoSplit => {
+                                    // Timeline tracks the combined level, like the mono mix
+                                    let mono: Vec<f32> = chunk.data
+                                        .chunks_exact(2)
+                                        .map(|frame| (frame[0] + frame[1]).clamp(-1.0, 1.0))
+                                        .collect();
+                                    recorder.add_samples(&mono);
+                                }
+                            }
+                        }
+                    }
+
                     // Add chunk to incremental saver
                     if let Some(saver_arc) = &incremental_saver_arc {
                         let mut saver_guard = saver_arc.lock().await;
@@ -205,7 +252,11 @@ impl RecordingSaver {
         let meeting_folder = create_meeting_folder(&base_folder, meeting_name)?;
 
         // Initialize incremental saver
-        let incremental_saver = IncrementalAudioSaver::new(meeting_folder.clone(), 48000)?;
+        let incremental_saver = IncrementalAudioSaver::with_channels(
+            meeting_folder.clone(),
+            48000,
+            self.channel_layout.channels(),
+        )?;
 
         // Create initial metadata
         let metadata = MeetingMetadata {
@@ -223,6 +274,7 @@ impl RecordingSaver {
             transcript_file: "transcripts.json".to_string(),
             sample_rate: 48000,
             status: "recording".to_string(),
+            channel_layout: self.channel_layout,
         };
 
         // Write initial metadata.json
@@ -367,6 +419,59 @@ impl RecordingSaver {
             return Err("No incremental saver initialized".to_string());
         };
 
+        // Optional final-pass loudness normalization over the whole file
+        if super::post_processor::is_final_loudnorm_enabled() {
+            let _ = app.emit(
+                "recording-shutdown-progress",
+                serde_json::json!({
+                    "stage": "normalizing_audio",
+                    "message": "Normalizing audio loudness...",
+                    "progress": 92
+                }),
+            );
+
+            let path = final_audio_path.clone();
+            match tokio::task::spawn_blocking(move || super::post_processor::normalize_audio_file(&path)).await {
+                Ok(Ok(_)) => info!("✅ Final-pass loudness normalization complete"),
+                Ok(Err(e)) => warn!("⚠️ Final-pass loudness normalization failed, keeping original audio: {}", e),
+                Err(e) => warn!("⚠️ Loudness normalization task panicked, keeping original audio: {}", e),
+            }
+        }
+
+        // Optional removal of long pauses; transcript timings are shifted to match
+        let mut trim_map = None;
+        if super::post_processor::is_trim_silence_enabled() {
+            let _ = app.emit(
+                "recording-shutdown-progress",
+                serde_json::json!({
+                    "stage": "trimming_silence",
+                    "message": "Trimming long silences...",
+                    "progress": 94
+                }),
+            );
+
+            let path = final_audio_path.clone();
+            let min_seconds = super::post_processor::get_trim_min_silence_seconds();
+            let keep_original = super::post_processor::is_keep_untrimmed_original_enabled();
+            match tokio::task::spawn_blocking(move || {
+                super::post_processor::trim_silence_in_file(&path, min_seconds, keep_original)
+            }).await {
+                Ok(Ok(map)) => trim_map = map,
+                Ok(Err(e)) => warn!("⚠️ Silence trimming failed, keeping untrimmed audio: {}", e),
+                Err(e) => warn!("⚠️ Silence trimming task panicked, keeping untrimmed audio: {}", e),
+            }
+        }
+
+        if let Some(map) = &trim_map {
+            if let Ok(mut segments) = self.transcript_segments.lock() {
+                for segment in segments.iter_mut() {
+                    segment.audio_start_time = map.map_time(segment.audio_start_time);
+                    segment.audio_end_time = map.map_time(segment.audio_end_time);
+                    segment.duration = segment.audio_end_time - segment.audio_start_time;
+                }
+            }
+        }
+
         // Save final transcripts.json with validation
         if let Some(folder) = &self.meeting_folder {
             if let Err(e) = self.write_transcripts_json(folder) {
@@ -381,6 +486,25 @@ impl RecordingSaver {
                 return Err("Transcript file verification failed".to_string());
             }
             info!("✅ Transcripts saved and verified at: {}", transcript_path.display());
+
+            // Level timeline is optional, so a failure here doesn't fail the save
+            if let Some(recorder) = self.level_timeline.take() {
+                // The accumulation task may still hold a reference, so swap the recorder out
+                let timeline = recorder.lock()
+                    .map(|mut r| std::mem::replace(&mut *r, LevelTimelineRecorder::new(48000)).finish());
+                match timeline {
+                    Ok(mut timeline) => {
+                        if let Some(map) = &trim_map {
+                            map.apply_to_level_timeline(&mut timeline);
+                        }
+                        match timeline.save(folder) {
+                            Ok(()) => info!("✅ Level timeline saved ({} points)", timeline.points.len()),
+                            Err(e) => warn!("⚠️ Failed to save level timeline: {}", e),
+                        }
+                    }
+                    Err(e) => warn!("⚠️ Failed to lock level timeline: {}", e),
+                }
+            }
         }
 
         // Update metadata to completed status with actual recording duration
@@ -397,6 +521,9 @@ impl RecordingSaver {
                     None
                 }
             });
+            if let (Some(map), Some(duration)) = (&trim_map, recording_duration) {
+                metadata.duration_seconds = Some((duration - map.removed_seconds()).max(0.0));
+            }
 
             if let Err(e) = self.write_metadata(folder, &metadata) {
                 error!("❌ Failed to update metadata to completed: {}", e);
@@ -442,6 +569,11 @@ impl RecordingSaver {
         }
     }
 
+    /// Channel layout of the audio being saved
+    pub fn channel_layout(&self) -> SaveChannelLayout {
+        self.channel_layout
+    }
+
     /// Get meeting name (for reload sync)
     pub fn get_meeting_name(&self) -> Option<String> {
         self.meeting_name.clone()
@@ -0,0 +1,367 @@
+//! One-call reprocessing of a recording
+//!
+//! `reprocess_recording` re-transcribes a recording's audio with the chosen
+//! model, diarizes it, relabels detected speakers that match a registered
+//! voice and saves the new transcript. Everything is computed before anything
+//! is written: the transcript, speaker embeddings and recording details are
+//! saved together at the end, so a failure or cancellation at any step leaves
+//! the saved recording untouched. Progress from all steps is reported as a
+//! single `reprocess-progress` stream.
+
+use std::collections::HashSet;
+use std::sync::Mutex;
+
+use log::{info, warn};
+use once_cell::sync::Lazy;
+use serde::{Deserialize, Serialize};
+use tauri::{AppHandle, Emitter, Listener, Runtime};
+
+use super::retranscription::{
+    save_retranscription_result, transcribe_recording, used_diarization_provider, RetranscriptionOptions,
+    RetranscriptionProgress, TranscriptSegment,
+};
+use crate::database::RecordingSpeakerEmbedding;
+use crate::diarization::speaker_db::cosine_similarity;
+use crate::diarization::DIARIZATION_ENGINE;
+use crate::state::AppState;
+
+/// Share of the progress bar used by retranscription and diarization
+const RETRANSCRIPTION_PROGRESS_SPAN: f64 = 90.0;
+const MATCHING_PROGRESS: u32 = 92;
+const SAVING_PROGRESS: u32 = 96;
+
+/// Recordings being reprocessed
+static REPROCESSING: Lazy<Mutex<HashSet<String>>> = Lazy::new(|| Mutex::new(HashSet::new()));
+
+/// Options for `reprocess_recording`: the retranscription options plus speaker matching
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct ReprocessOptions {
+    #[serde(flatten)]
+    pub retranscription: RetranscriptionOptions,
+    /// Relabel detected speakers that match a registered voice (default true)
+    #[serde(default)]
+    pub match_registered_speakers: Option<bool>,
+}
+
+/// Progress emitted as `reprocess-progress`
+#[derive(Debug, Clone, Serialize)]
+pub struct ReprocessProgress {
+    pub recording_id: String,
+    /// "loading" | "processing" | "diarizing" | "matching" | "saving" | "completed" | "failed"
+    pub stage: String,
+    pub progress_percent: u32,
+    pub message: String,
+}
+
+/// A detected speaker relabeled as a registered voice
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct MatchedSpeaker {
+    pub previous_speaker_id: String,
+    pub registered_speaker_id: String,
+    pub name: String,
+    pub similarity: f32,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ReprocessResult {
+    pub recording_id: String,
+    pub model_used: String,
+    pub segment_count: usize,
+    pub speaker_count: usize,
+    pub matched_speakers: Vec<MatchedSpeaker>,
+    /// Chunks that could not be transcribed; the transcript has placeholder gaps for them
+    pub failed_chunks: Vec<u32>,
+}
+
+fn emit_progress<R: Runtime>(app: &AppHandle<R>, recording_id: &str, stage: &str, percent: u32, message: &str) {
+    let progress = ReprocessProgress {
+        recording_id: recording_id.to_string(),
+        stage: stage.to_string(),
+        progress_percent: percent,
+        message: message.to_string(),
+    };
+    if let Err(e) = app.emit("reprocess-progress", &progress) {
+        warn!("Failed to emit reprocess progress: {}", e);
+    }
+}
+
+/// Pair detected speakers with registered voices, most similar pairs first.
+/// Each detected speaker and each registered voice is used at most once.
+fn match_registered_speakers(
+    detected: &[RecordingSpeakerEmbedding],
+    registered: &[(String, String, Vec<f32>)],
+    threshold: f32,
+) -> Vec<MatchedSpeaker> {
+    let mut candidates: Vec<(f32, &RecordingSpeakerEmbedding, &(String, String, Vec<f32>))> = detected
+        .iter()
+        .filter(|d| d.registered_speaker_id.is_none())
+        .flat_map(|d| registered.iter().map(move |r| (cosine_similarity(&r.2, &d.embedding), d, r)))
+        .filter(|(similarity, _, _)| *similarity >= threshold)
+        .collect();
+    candidates.sort_by(|a, b| b.0.total_cmp(&a.0));
+
+    let mut used_detected = HashSet::new();
+    let mut used_registered = HashSet::new();
+    let mut matches = Vec::new();
+    for (similarity, detected, (id, name, _)) in candidates {
+        if used_detected.contains(&detected.speaker_id) || used_registered.contains(id) {
+            continue;
+        }
+        used_detected.insert(detected.speaker_id.clone());
+        used_registered.insert(id.clone());
+        matches.push(MatchedSpeaker {
+            previous_speaker_id: detected.speaker_id.clone(),
+            registered_speaker_id: id.clone(),
+            name: name.clone(),
+            similarity,
+        });
+    }
+    matches
+}
+
+/// Relabel transcript segments of matched speakers, using the diarization
+/// engine's ID scheme for registered voices
+fn relabel_matched_speakers(transcripts: &mut [TranscriptSegment], matches: &[MatchedSpeaker]) {
+    for segment in transcripts.iter_mut() {
+        let Some(matched) = matches
+            .iter()
+            .find(|m| segment.speaker_id.as_deref() == Some(m.previous_speaker_id.as_str()))
+        else {
+            continue;
+        };
+        segment.speaker_id = Some(format!("registered_{}", matched.registered_speaker_id));
+        segment.speaker_label = Some(matched.name.clone());
+        segment.is_registered_speaker = true;
+    }
+}
+
+/// Record matches on the detected speakers' embeddings, as
+/// `assign_registered_speaker` does for a saved recording
+fn relabel_matched_embeddings(embeddings: &mut [RecordingSpeakerEmbedding], matches: &[MatchedSpeaker]) {
+    for embedding in embeddings.iter_mut() {
+        let Some(matched) = matches.iter().find(|m| m.previous_speaker_id == embedding.speaker_id) else {
+            continue;
+        };
+        embedding.speaker_id = format!("registered_{}", matched.registered_speaker_id);
+        embedding.speaker_label = matched.name.clone();
+        embedding.registered_speaker_id = Some(matched.registered_speaker_id.clone());
+    }
+}
+
+/// Registered voices as (id, name, embedding) and the engine's similarity threshold,
+/// or None if the diarization engine isn't initialized
+async fn registered_voices() -> Option<(Vec<(String, String, Vec<f32>)>, f32)> {
+    let guard = DIARIZATION_ENGINE.read().await;
+    let engine = guard.as_ref()?;
+    let speakers = match engine.get_registered_speakers() {
+        Ok(speakers) => speakers,
+        Err(e) => {
+            warn!("Failed to read registered speakers: {}", e);
+            return None;
+        }
+    };
+    Some((
+        speakers.into_iter().map(|s| (s.id, s.name, s.embedding)).collect(),
+        engine.similarity_threshold(),
+    ))
+}
+
+/// Removes the recording from the in-progress set however reprocessing ends
+struct ReprocessingGuard(String);
+
+impl Drop for ReprocessingGuard {
+    fn drop(&mut self) {
+        if let Ok(mut set) = REPROCESSING.lock() {
+            set.remove(&self.0);
+        }
+    }
+}
+
+async fn reprocess<R: Runtime>(
+    app: &AppHandle<R>,
+    state: &AppState,
+    recording_id: &str,
+    options: ReprocessOptions,
+) -> Result<ReprocessResult, String> {
+    let recording = state
+        .db()
+        .await
+        .get_recording(recording_id)
+        .map_err(|e| e.to_string())?
+        .ok_or_else(|| format!("Recording {} not found", recording_id))?;
+    let audio_path = recording
+        .audio_file_path
+        .ok_or_else(|| format!("Recording {} has no audio file", recording_id))?;
+
+    let mut retranscription = options.retranscription;
+    retranscription.enable_diarization.get_or_insert(true);
+    retranscription.validate()?;
+    let similarity_threshold = retranscription.similarity_threshold;
+    let diarization_provider = used_diarization_provider(&retranscription);
+
+    // Relay retranscription progress for this recording onto the combined stream
+    let relay_app = app.clone();
+    let relay_id = recording_id.to_string();
+    let listener = app.listen("retranscription-progress", move |event| {
+        let Ok(progress) = serde_json::from_str::<RetranscriptionProgress>(event.payload()) else {
+            return;
+        };
+        if progress.recording_id != relay_id || progress.status == "completed" {
+            return;
+        }
+        let percent = (progress.progress_percent as f64 * RETRANSCRIPTION_PROGRESS_SPAN / 100.0) as u32;
+        emit_progress(&relay_app, &relay_id, &progress.status, percent, &progress.message);
+    });
+    let outcome = transcribe_recording(app, recording_id.to_string(), audio_path, retranscription).await;
+    app.unlisten(listener);
+
+    let mut transcription = outcome?.ok_or_else(|| "Reprocessing was cancelled".to_string())?;
+    if !transcription.result.success {
+        return Err(transcription.result.error.take().unwrap_or_else(|| "Retranscription failed".to_string()));
+    }
+
+    let mut matched_speakers = Vec::new();
+    if options.match_registered_speakers.unwrap_or(true) {
+        emit_progress(app, recording_id, "matching", MATCHING_PROGRESS, "Matching registered speakers...");
+        match registered_voices().await {
+            Some((registered, default_threshold)) if !registered.is_empty() => {
+                if let Some(detected) = transcription.speaker_embeddings.as_mut() {
+                    matched_speakers = match_registered_speakers(
+                        detected,
+                        &registered,
+                        similarity_threshold.unwrap_or(default_threshold),
+                    );
+                    relabel_matched_speakers(&mut transcription.result.transcripts, &matched_speakers);
+                    relabel_matched_embeddings(detected, &matched_speakers);
+                } else {
+                    info!("No speaker embeddings from diarization, skipping speaker matching");
+                }
+            }
+            Some(_) => info!("No registered speakers to match"),
+            None => info!("Diarization engine not initialized, skipping speaker matching"),
+        }
+    }
+
+    emit_progress(app, recording_id, "saving", SAVING_PROGRESS, "Saving transcript...");
+    save_retranscription_result(&state.db().await, &transcription, diarization_provider.as_deref())
+        .map_err(|e| format!("Failed to save transcript: {}", e))?;
+
+    let result = transcription.result;
+    let speaker_count = result
+        .transcripts
+        .iter()
+        .filter_map(|t| t.speaker_id.as_deref())
+        .collect::<HashSet<_>>()
+        .len();
+    Ok(ReprocessResult {
+        recording_id: recording_id.to_string(),
+        model_used: result.model_used,
+        segment_count: result.transcripts.len(),
+        speaker_count,
+        matched_speakers,
+        failed_chunks: result.failed_chunks,
+    })
+}
+
+/// Re-transcribe, diarize, match registered speakers and save a recording's
+/// transcript in one call. Emits `reprocess-progress`; nothing is saved until
+/// every step has succeeded.
+#[tauri::command]
+pub async fn reprocess_recording<R: Runtime>(
+    app: AppHandle<R>,
+    state: tauri::State<'_, AppState>,
+    recording_id: String,
+    options: Option<ReprocessOptions>,
+) -> Result<ReprocessResult, String> {
+    if !REPROCESSING.lock().unwrap().insert(recording_id.clone()) {
+        return Err(format!("Recording {} is already being reprocessed", recording_id));
+    }
+    let _guard = ReprocessingGuard(recording_id.clone());
+
+    info!("Reprocessing recording {}", recording_id);
+    match reprocess(&app, &state, &recording_id, options.unwrap_or_default()).await {
+        Ok(result) => {
+            info!("Reprocessed recording {}: {} segments, {} speakers, {} matched",
+                  recording_id, result.segment_count, result.speaker_count, result.matched_speakers.len());
+            emit_progress(&app, &recording_id, "completed", 100, "Reprocessing complete!");
+            Ok(result)
+        }
+        Err(e) => {
+            warn!("Reprocessing recording {} failed: {}", recording_id, e);
+            emit_progress(&app, &recording_id, "failed", 100, &e);
+            Err(e)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn detected(speaker_id: &str, embedding: Vec<f32>) -> RecordingSpeakerEmbedding {
+        RecordingSpeakerEmbedding {
+            recording_id: "rec".to_string(),
+            speaker_id: speaker_id.to_string(),
+            speaker_label: speaker_id.to_string(),
+            embedding,
+            segment_count: 1,
+            registered_speaker_id: None,
+        }
+    }
+
+    #[test]
+    fn test_match_registered_speakers_pairs_best_first() {
+        let detected = vec![
+            detected("speaker_0", vec![1.0, 0.0]),
+            detected("speaker_1", vec![0.9, 0.1]),
+            detected("speaker_2", vec![0.0, 1.0]),
+        ];
+        let registered = vec![
+            ("alice".to_string(), "Alice".to_string(), vec![1.0, 0.0]),
+            ("bob".to_string(), "Bob".to_string(), vec![-1.0, 0.0]),
+        ];
+
+        let matches = match_registered_speakers(&detected, &registered, 0.5);
+        // Alice goes to the closest speaker only; Bob matches nobody
+        assert_eq!(matches.len(), 1);
+        assert_eq!(matches[0].previous_speaker_id, "speaker_0");
+        assert_eq!(matches[0].registered_speaker_id, "alice");
+    }
+
+    #[test]
+    fn test_relabel_matched_speakers() {
+        let segment = |speaker: &str| TranscriptSegment {
+            speaker_id: Some(speaker.to_string()),
+            speaker_label: Some(speaker.to_string()),
+            ..TranscriptSegment::test_segment("hi", 0.9)
+        };
+        let mut transcripts = vec![segment("speaker_0"), segment("speaker_1")];
+        relabel_matched_speakers(&mut transcripts, &[MatchedSpeaker {
+            previous_speaker_id: "speaker_0".to_string(),
+            registered_speaker_id: "alice".to_string(),
+            name: "Alice".to_string(),
+            similarity: 0.9,
+        }]);
+
+        assert_eq!(transcripts[0].speaker_id.as_deref(), Some("registered_alice"));
+        assert_eq!(transcripts[0].speaker_label.as_deref(), Some("Alice"));
+        assert!(transcripts[0].is_registered_speaker);
+        assert_eq!(transcripts[1].speaker_id.as_deref(), Some("speaker_1"));
+    }
+
+    #[test]
+    fn test_relabel_matched_embeddings() {
+        let mut embeddings = vec![detected("speaker_0", vec![1.0]), detected("speaker_1", vec![0.0])];
+        relabel_matched_embeddings(&mut embeddings, &[MatchedSpeaker {
+            previous_speaker_id: "speaker_1".to_string(),
+            registered_speaker_id: "bob".to_string(),
+            name: "Bob".to_string(),
+            similarity: 0.8,
+        }]);
+
+        assert_eq!(embeddings[0].registered_speaker_id, None);
+        assert_eq!(embeddings[1].speaker_id, "registered_bob");
+        assert_eq!(embeddings[1].speaker_label, "Bob");
+        assert_eq!(embeddings[1].registered_speaker_id.as_deref(), Some("bob"));
+    }
+}
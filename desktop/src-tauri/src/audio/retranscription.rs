@@ -5,8 +5,9 @@ use std::path::Path;
 use std::process::{Command, Stdio};
 use std::io::Read;
 use std::sync::Mutex;
+use std::sync::atomic::{AtomicBool, AtomicU64, AtomicUsize, Ordering};
 use std::collections::HashSet;
-use tauri::{AppHandle, Emitter, Runtime};
+use tauri::{AppHandle, Emitter, Manager, Runtime};
 use serde::{Deserialize, Serialize};
 use log::{info, error, debug, warn};
 use anyhow::{Result, anyhow};
@@ -14,6 +15,9 @@ use once_cell::sync::Lazy;
 
 use super::ffmpeg::find_ffmpeg_path;
 use crate::whisper_engine::parallel_processor::AudioChunk;
+use crate::database::RetranscriptionJob;
+use crate::state::AppState;
+use crate::task_scheduler::{self, TaskKind};
 
 #[cfg(target_os = "windows")]
 use std::os::windows::process::CommandExt;
@@ -22,9 +26,34 @@ use std::os::windows::process::CommandExt;
 #[cfg(target_os = "windows")]
 const CREATE_NO_WINDOW: u32 = 0x08000000;
 
+/// Attempts per chunk before it's recorded as a gap
+const CHUNK_MAX_ATTEMPTS: u32 = 3;
+
+/// Delay before the first retry; doubles with each attempt
+const CHUNK_RETRY_BASE_DELAY_MS: u64 = 500;
+
+/// Confidence given to retranscribed segments; Whisper's own confidence isn't
+/// extracted yet, so quality scoring treats this value as unmeasured
+pub const PLACEHOLDER_CONFIDENCE: f32 = 0.95;
+
+/// Text of the placeholder segment inserted for a chunk that couldn't be transcribed
+pub const FAILED_CHUNK_PLACEHOLDER: &str = "[Transcription failed for this section]";
+
 /// Global set of recording IDs that should be cancelled
 static CANCELLED_RECORDINGS: Lazy<Mutex<HashSet<String>>> = Lazy::new(|| Mutex::new(HashSet::new()));
 
+/// Recording IDs with a retranscription running or waiting for a task slot
+static ACTIVE_RETRANSCRIPTIONS: Lazy<Mutex<HashSet<String>>> = Lazy::new(|| Mutex::new(HashSet::new()));
+
+/// Bumped to stop every running batch transcription after its current file
+static BATCH_GENERATION: AtomicU64 = AtomicU64::new(0);
+
+/// Bumped to stop running quick transcriptions and speaker re-clusterings
+static STANDALONE_GENERATION: AtomicU64 = AtomicU64::new(0);
+
+/// Quick transcriptions and speaker re-clusterings running or waiting for a slot
+static ACTIVE_STANDALONE_JOBS: AtomicUsize = AtomicUsize::new(0);
+
 /// Progress information for retranscription
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct RetranscriptionProgress {
@@ -44,6 +73,17 @@ pub struct RetranscriptionResult {
     pub transcripts: Vec<TranscriptSegment>,
     pub error: Option<String>,
     pub model_used: String,
+    /// Chunks that still failed after retries; the transcript has placeholder gaps for them
+    #[serde(default)]
+    pub failed_chunks: Vec<u32>,
+}
+
+/// A finished transcription that hasn't been saved yet
+#[derive(Debug, Clone)]
+pub(crate) struct Transcription {
+    pub result: RetranscriptionResult,
+    /// Speaker centroids from PyAnnote diarization, saved with the transcript
+    pub speaker_embeddings: Option<Vec<crate::database::RecordingSpeakerEmbedding>>,
 }
 
 /// A transcript segment from retranscription
@@ -61,6 +101,27 @@ pub struct TranscriptSegment {
     pub speaker_label: Option<String>,
     #[serde(default)]
     pub is_registered_speaker: bool,
+    /// Confidence is below the requested `min_confidence` (text is kept, just flagged)
+    #[serde(default)]
+    pub low_confidence: bool,
+}
+
+#[cfg(test)]
+impl TranscriptSegment {
+    /// One-second segment for tests, with no speaker
+    pub fn test_segment(text: &str, confidence: f32) -> Self {
+        Self {
+            text: text.to_string(),
+            audio_start_time: 0.0,
+            audio_end_time: 1.0,
+            confidence,
+            sequence_id: 0,
+            speaker_id: None,
+            speaker_label: None,
+            is_registered_speaker: false,
+            low_confidence: false,
+        }
+    }
 }
 
 /// Emit retranscription progress to frontend
@@ -87,6 +148,36 @@ pub fn emit_progress<R: Runtime>(
     }
 }
 
+/// A chunk's transcript, emitted as `retranscription-segment` as soon as the
+/// chunk is transcribed. Speakers are not assigned yet; the final
+/// `retranscription-complete` result carries the full, diarized transcript.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RetranscriptionSegmentEvent {
+    pub recording_id: String,
+    pub chunk_index: u32,
+    pub total_chunks: u32,
+    pub segment: TranscriptSegment,
+}
+
+/// Emit a completed chunk's transcript segment to the frontend
+fn emit_segment<R: Runtime>(
+    app: &AppHandle<R>,
+    recording_id: &str,
+    total_chunks: u32,
+    segment: &TranscriptSegment,
+) {
+    let event = RetranscriptionSegmentEvent {
+        recording_id: recording_id.to_string(),
+        chunk_index: segment.sequence_id,
+        total_chunks,
+        segment: segment.clone(),
+    };
+
+    if let Err(e) = app.emit("retranscription-segment", &event) {
+        warn!("Failed to emit retranscription segment: {}", e);
+    }
+}
+
 /// Emit retranscription completion
 pub fn emit_complete<R: Runtime>(
     app: &AppHandle<R>,
@@ -119,34 +210,121 @@ fn clear_cancelled(recording_id: &str) {
     }
 }
 
-/// Tauri command to cancel a retranscription in progress
-#[tauri::command]
-pub async fn cancel_retranscription<R: Runtime>(
-    app: AppHandle<R>,
-    recording_id: String,
-) -> Result<(), String> {
-    info!("Cancelling retranscription for recording: {}", recording_id);
+/// Marks a recording's retranscription as active until dropped
+struct ActiveRetranscription(String);
+
+impl ActiveRetranscription {
+    fn start(recording_id: &str) -> Self {
+        if let Ok(mut set) = ACTIVE_RETRANSCRIPTIONS.lock() {
+            set.insert(recording_id.to_string());
+        }
+        Self(recording_id.to_string())
+    }
+}
+
+impl Drop for ActiveRetranscription {
+    fn drop(&mut self) {
+        if let Ok(mut set) = ACTIVE_RETRANSCRIPTIONS.lock() {
+            set.remove(&self.0);
+        }
+    }
+}
+
+/// A quick transcription or speaker re-clustering, counted as active until dropped
+struct StandaloneJob {
+    generation: u64,
+}
+
+impl StandaloneJob {
+    fn start() -> Self {
+        ACTIVE_STANDALONE_JOBS.fetch_add(1, Ordering::SeqCst);
+        Self { generation: STANDALONE_GENERATION.load(Ordering::SeqCst) }
+    }
+
+    /// Fails once `cancel_standalone_jobs` was called after the job started
+    fn check_cancelled(&self) -> Result<(), String> {
+        if STANDALONE_GENERATION.load(Ordering::SeqCst) != self.generation {
+            return Err("Cancelled".to_string());
+        }
+        Ok(())
+    }
+}
+
+impl Drop for StandaloneJob {
+    fn drop(&mut self) {
+        ACTIVE_STANDALONE_JOBS.fetch_sub(1, Ordering::SeqCst);
+    }
+}
 
+/// Stop quick transcriptions and speaker re-clusterings at their next check;
+/// returns how many were running or waiting for a slot
+pub fn cancel_standalone_jobs() -> usize {
+    STANDALONE_GENERATION.fetch_add(1, Ordering::SeqCst);
+    ACTIVE_STANDALONE_JOBS.load(Ordering::SeqCst)
+}
+
+/// Mark a retranscription cancelled and tell the frontend
+fn cancel_and_notify<R: Runtime>(app: &AppHandle<R>, recording_id: &str) {
     // Mark for cancellation
-    mark_cancelled(&recording_id);
+    mark_cancelled(recording_id);
 
     // Emit cancelled status
-    emit_progress(&app, &recording_id, "cancelled", 0, 0, 0, "Retranscription cancelled by user");
+    emit_progress(app, recording_id, "cancelled", 0, 0, 0, "Retranscription cancelled by user");
 
-    emit_complete(&app, &RetranscriptionResult {
-        recording_id: recording_id.clone(),
+    emit_complete(app, &RetranscriptionResult {
+        recording_id: recording_id.to_string(),
         success: false,
         transcripts: vec![],
         error: Some("Cancelled by user".to_string()),
         model_used: String::new(),
+        failed_chunks: vec![],
     });
+}
 
+/// Tauri command to cancel a retranscription in progress
+#[tauri::command]
+pub async fn cancel_retranscription<R: Runtime>(
+    app: AppHandle<R>,
+    recording_id: String,
+) -> Result<(), String> {
+    info!("Cancelling retranscription for recording: {}", recording_id);
+    cancel_and_notify(&app, &recording_id);
     Ok(())
 }
 
+/// Cancel every running or slot-waiting retranscription and stop running
+/// batch transcriptions; returns how many retranscriptions were cancelled
+pub fn cancel_all_retranscriptions<R: Runtime>(app: &AppHandle<R>) -> usize {
+    BATCH_GENERATION.fetch_add(1, Ordering::SeqCst);
+
+    let active: Vec<String> = ACTIVE_RETRANSCRIPTIONS
+        .lock()
+        .map(|set| set.iter().cloned().collect())
+        .unwrap_or_default();
+    for recording_id in &active {
+        info!("Cancelling retranscription for recording: {}", recording_id);
+        cancel_and_notify(app, recording_id);
+    }
+    active.len()
+}
+
 /// Decode audio file to raw f32 samples using FFmpeg
 /// Returns mono 16kHz audio samples suitable for Whisper
 pub fn decode_audio_file(audio_path: &str) -> Result<(Vec<f32>, u32)> {
+    decode_audio_file_at_rate(audio_path, 16000)
+}
+
+/// Decode audio file to mono f32 samples at the given sample rate
+pub fn decode_audio_file_at_rate(audio_path: &str, sample_rate: u32) -> Result<(Vec<f32>, u32)> {
+    decode_with_ffmpeg(audio_path, sample_rate, None)
+}
+
+/// Decode only the first `max_seconds` of an audio file, as mono 16kHz samples
+pub fn decode_audio_file_head(audio_path: &str, max_seconds: f64) -> Result<(Vec<f32>, u32)> {
+    decode_with_ffmpeg(audio_path, 16000, Some(max_seconds))
+}
+
+fn decode_with_ffmpeg(audio_path: &str, sample_rate: u32, max_seconds: Option<f64>) -> Result<(Vec<f32>, u32)> {
     let path = Path::new(audio_path);
 
     if !path.exists() {
@@ -159,7 +337,7 @@ pub fn decode_audio_file(audio_path: &str) -> Result<(Vec<f32>, u32)> {
     info!("Decoding audio file: {}", audio_path);
     debug!("Using FFmpeg at: {:?}", ffmpeg_path);
 
-    // Use FFmpeg to decode audio to raw PCM f32le at 16kHz mono (Whisper's expected format)
+    // Use FFmpeg to decode audio to raw PCM f32le mono
     let mut command = Command::new(&ffmpeg_path);
     
     #[cfg(target_os = "windows")]
@@ -173,9 +351,15 @@ pub fn decode_audio_file(audio_path: &str) -> Result<(Vec<f32>, u32)> {
         .arg("-acodec")
         .arg("pcm_f32le")       // Audio codec
         .arg("-ar")
-        .arg("16000")           // Sample rate: 16kHz (Whisper's expected rate)
+        .arg(sample_rate.to_string())
         .arg("-ac")
-        .arg("1")               // Mono
+        .arg("1");              // Mono
+
+    if let Some(max_seconds) = max_seconds {
+        command.arg("-t").arg(format!("{:.3}", max_seconds));
+    }
+
+    command
         .arg("-")               // Output to stdout
         .stdout(Stdio::piped())
         .stderr(Stdio::piped());
@@ -210,10 +394,10 @@ pub fn decode_audio_file(audio_path: &str) -> Result<(Vec<f32>, u32)> {
         .map(|chunk| f32::from_le_bytes([chunk[0], chunk[1], chunk[2], chunk[3]]))
         .collect();
 
-    let duration_seconds = samples.len() as f32 / 16000.0;
+    let duration_seconds = samples.len() as f32 / sample_rate as f32;
     info!("Decoded {} samples ({:.2} seconds) from {}", samples.len(), duration_seconds, audio_path);
 
-    Ok((samples, 16000)) // Return samples and sample rate
+    Ok((samples, sample_rate))
 }
 
 /// Prepare audio samples into chunks for parallel processing
@@ -313,39 +497,72 @@ fn align_speakers_with_transcripts(
     transcripts
 }
 
+/// Speaker segment overlapping the most of [start, end], with the overlap ratio
+fn best_speaker_match(
+    start: f64,
+    end: f64,
+    speaker_segments: &[crate::diarization::SpeakerSegment],
+) -> Option<(&crate::diarization::SpeakerSegment, f64)> {
+    let mut best_match: Option<(&crate::diarization::SpeakerSegment, f64)> = None;
+
+    for speaker_seg in speaker_segments {
+        // Calculate overlap between transcript and speaker segment
+        let overlap_start = start.max(speaker_seg.start_time);
+        let overlap_end = end.min(speaker_seg.end_time);
+        let overlap = (overlap_end - overlap_start).max(0.0);
+
+        if overlap > 0.0 {
+            let transcript_duration = end - start;
+            let overlap_ratio = if transcript_duration > 0.0 {
+                overlap / transcript_duration
+            } else {
+                0.0
+            };
+
+            if let Some((_, best_ratio)) = best_match {
+                if overlap_ratio > best_ratio {
+                    best_match = Some((speaker_seg, overlap_ratio));
+                }
+            } else {
+                best_match = Some((speaker_seg, overlap_ratio));
+            }
+        }
+    }
+
+    best_match
+}
+
+/// Default largest silence (seconds) between same-speaker segments that are merged
+pub const DEFAULT_SPEAKER_MERGE_GAP_SECONDS: f64 = 2.0;
+
 /// Assign speakers to transcripts and merge consecutive same-speaker segments
+/// separated by less than `merge_gap` seconds.
 /// This preserves all original text while adding speaker labels
 fn assign_and_merge_speakers(
     mut transcripts: Vec<TranscriptSegment>,
     speaker_segments: &[crate::diarization::SpeakerSegment],
+    merge_gap: f64,
 ) -> Vec<TranscriptSegment> {
-    // Phase 1: Assign speaker to each transcript based on majority overlap
-    for transcript in &mut transcripts {
-        let mut best_match: Option<(&crate::diarization::SpeakerSegment, f64)> = None;
+    let original_count = transcripts.len();
+    assign_speakers(&mut transcripts, speaker_segments);
+    let mut merged = merge_speaker_turns(transcripts, merge_gap);
 
-        for speaker_seg in speaker_segments {
-            // Calculate overlap between transcript and speaker segment
-            let overlap_start = transcript.audio_start_time.max(speaker_seg.start_time);
-            let overlap_end = transcript.audio_end_time.min(speaker_seg.end_time);
-            let overlap = (overlap_end - overlap_start).max(0.0);
+    // Renumber sequence_ids
+    for (i, seg) in merged.iter_mut().enumerate() {
+        seg.sequence_id = i as u32;
+    }
 
-            if overlap > 0.0 {
-                let transcript_duration = transcript.audio_end_time - transcript.audio_start_time;
-                let overlap_ratio = if transcript_duration > 0.0 {
-                    overlap / transcript_duration
-                } else {
-                    0.0
-                };
+    info!("Assigned speakers and merged {} segments into {} segments",
+          original_count, merged.len());
 
-                if let Some((_, best_ratio)) = best_match {
-                    if overlap_ratio > best_ratio {
-                        best_match = Some((speaker_seg, overlap_ratio));
-                    }
-                } else {
-                    best_match = Some((speaker_seg, overlap_ratio));
-                }
-            }
-        }
+    merged
+}
+
+/// Assign a speaker to each transcript based on majority overlap; transcripts
+/// without any overlap keep their speaker
+fn assign_speakers(transcripts: &mut [TranscriptSegment], speaker_segments: &[crate::diarization::SpeakerSegment]) {
+    for transcript in transcripts {
+        let best_match = best_speaker_match(transcript.audio_start_time, transcript.audio_end_time, speaker_segments);
 
         // Assign speaker if we found any overlap
         if let Some((speaker_seg, ratio)) = best_match {
@@ -357,18 +574,22 @@ fn assign_and_merge_speakers(
                    speaker_seg.speaker_label, ratio * 100.0);
         }
     }
+}
 
-    // Phase 2: Merge consecutive segments with same speaker
-    let original_count = transcripts.len();
+/// Merge consecutive segments of the same speaker separated by less than
+/// `merge_gap` seconds. A merged segment keeps the sequence_id of its first part.
+fn merge_speaker_turns(transcripts: Vec<TranscriptSegment>, merge_gap: f64) -> Vec<TranscriptSegment> {
     let mut merged: Vec<TranscriptSegment> = Vec::new();
 
     for segment in transcripts {
         if let Some(last) = merged.last_mut() {
-            // Same speaker and close in time (< 2 seconds gap)? Merge text
+            // Same speaker and close in time (gap below merge_gap)? Merge text
             let same_speaker = last.speaker_id == segment.speaker_id;
             let time_gap = segment.audio_start_time - last.audio_end_time;
+            // Keep low-confidence text in its own segment so it stays flagged precisely
+            let same_confidence_flag = last.low_confidence == segment.low_confidence;
 
-            if same_speaker && time_gap < 2.0 {
+            if same_speaker && same_confidence_flag && time_gap < merge_gap {
                 // Merge: append text with space, extend end time
                 last.text.push(' ');
                 last.text.push_str(&segment.text);
@@ -378,16 +599,54 @@ fn assign_and_merge_speakers(
         }
         merged.push(segment);
     }
+    merged
+}
 
-    // Phase 3: Renumber sequence_ids
-    for (i, seg) in merged.iter_mut().enumerate() {
-        seg.sequence_id = i as u32;
-    }
+/// Per-speaker centroid embeddings in their stored form
+fn speaker_embeddings_from_centroids(
+    recording_id: &str,
+    centroids: Vec<crate::diarization::SpeakerCentroid>,
+) -> Vec<crate::database::RecordingSpeakerEmbedding> {
+    centroids
+        .into_iter()
+        .map(|c| crate::database::RecordingSpeakerEmbedding {
+            recording_id: recording_id.to_string(),
+            speaker_id: c.speaker_id,
+            speaker_label: c.speaker_label,
+            embedding: c.embedding,
+            segment_count: c.segment_count as i64,
+            registered_speaker_id: c.registered_speaker_id,
+        })
+        .collect()
+}
 
-    info!("Assigned speakers and merged {} segments into {} segments",
-          original_count, merged.len());
+/// Persist the per-speaker centroid embeddings for later cross-recording matching
+async fn save_speaker_embeddings<R: Runtime>(
+    app: &AppHandle<R>,
+    recording_id: &str,
+    centroids: Vec<crate::diarization::SpeakerCentroid>,
+) {
+    let embeddings = speaker_embeddings_from_centroids(recording_id, centroids);
 
-    merged
+    let state = app.state::<AppState>();
+    let db = state.db().await;
+    match db.save_recording_speaker_embeddings(recording_id, &embeddings) {
+        Ok(()) => info!("Saved {} speaker embeddings for recording {}", embeddings.len(), recording_id),
+        Err(e) => warn!("Failed to save speaker embeddings for {}: {}", recording_id, e),
+    }
+}
+
+/// Flag (never remove) segments whose confidence is below `min_confidence`.
+/// Returns the number of flagged segments.
+fn flag_low_confidence(transcripts: &mut [TranscriptSegment], min_confidence: f32) -> usize {
+    let mut flagged = 0;
+    for segment in transcripts.iter_mut() {
+        segment.low_confidence = segment.confidence < min_confidence;
+        if segment.low_confidence {
+            flagged += 1;
+        }
+    }
+    flagged
 }
 
 /// Split transcript segments at speaker boundaries
@@ -469,6 +728,7 @@ fn split_transcripts_by_speakers(
                 speaker_id: Some(speaker.speaker_id.clone()),
                 speaker_label: Some(speaker.speaker_label.clone()),
                 is_registered_speaker: speaker.is_registered,
+                low_confidence: transcript.low_confidence,
             });
             sequence_id += 1;
         }
@@ -557,6 +817,144 @@ pub fn get_audio_duration(audio_path: &str) -> Result<f64> {
     Err(anyhow!("Could not determine audio duration"))
 }
 
+/// Options for a retranscription job (also persisted with queued jobs)
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct RetranscriptionOptions {
+    #[serde(default)]
+    pub model_name: Option<String>,
+    #[serde(default)]
+    pub language: Option<String>,
+    #[serde(default)]
+    pub enable_diarization: Option<bool>,
+    #[serde(default)]
+    pub diarization_provider: Option<String>,
+    #[serde(default)]
+    pub max_speakers: Option<usize>,
+    /// Estimate the speaker count (up to max_speakers) and use it as the limit (pyannote only)
+    #[serde(default)]
+    pub auto_speakers: Option<bool>,
+    #[serde(default)]
+    pub similarity_threshold: Option<f32>,
+    /// Speaker segments shorter than this (seconds) are absorbed into the surrounding speaker; 0 disables
+    #[serde(default)]
+    pub min_speaker_segment_seconds: Option<f64>,
+    /// Consecutive segments of the same speaker separated by less than this
+    /// (seconds) are merged into one. Lower values keep quick exchanges as
+    /// separate segments, higher values join a speaker's turn across pauses.
+    /// Defaults to DEFAULT_SPEAKER_MERGE_GAP_SECONDS; 0 only merges overlapping segments
+    #[serde(default)]
+    pub speaker_merge_gap_seconds: Option<f64>,
+    /// Segments below this confidence (0.0-1.0) are flagged as low confidence
+    #[serde(default)]
+    pub min_confidence: Option<f32>,
+}
+
+impl RetranscriptionOptions {
+    pub fn validate(&self) -> Result<(), String> {
+        if self.max_speakers == Some(0) {
+            return Err("max_speakers must be at least 1".to_string());
+        }
+        if let Some(min) = self.min_speaker_segment_seconds {
+            if !min.is_finite() || min < 0.0 {
+                return Err("min_speaker_segment_seconds must be zero or more".to_string());
+            }
+        }
+        if let Some(gap) = self.speaker_merge_gap_seconds {
+            if !gap.is_finite() || gap < 0.0 {
+                return Err("speaker_merge_gap_seconds must be zero or more".to_string());
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Progress where speaker detection starts when diarization is enabled;
+/// transcription uses 5% up to here
+const DIARIZATION_PROGRESS_START: u32 = 75;
+/// Progress where speaker detection ends, before speakers are assigned to the transcript
+const DIARIZATION_PROGRESS_END: u32 = 97;
+
+/// Progress callback for the diarization engines: maps the fraction of audio
+/// processed onto the diarization range, emitting only when the percentage changes
+fn diarization_progress_reporter<'a, R: Runtime>(
+    app: &'a AppHandle<R>,
+    recording_id: &'a str,
+    total_chunks: u32,
+) -> impl FnMut(f64) + 'a {
+    let mut last_percent = 0;
+    move |fraction: f64| {
+        let fraction = fraction.clamp(0.0, 1.0);
+        let span = (DIARIZATION_PROGRESS_END - DIARIZATION_PROGRESS_START - 1) as f64;
+        let percent = DIARIZATION_PROGRESS_START + 1 + (fraction * span) as u32;
+        if percent != last_percent {
+            last_percent = percent;
+            emit_progress(app, recording_id, "diarizing", percent, total_chunks, total_chunks,
+                          &format!("Detecting speakers in audio... {:.0}%", fraction * 100.0));
+        }
+    }
+}
+
+/// Create the pyannote engine from the models in the diarization models directory.
+/// Returns None (after logging why) if the models are missing or fail to load.
+fn create_pyannote_engine<R: Runtime>(
+    app: &AppHandle<R>,
+    max_speakers: usize,
+    similarity_threshold: f32,
+) -> Option<crate::diarization::DiarizationEngine> {
+    info!("Diarization engine not initialized, attempting auto-initialization...");
+
+    let models_dir = crate::model_directories::models_dir(app, crate::model_directories::ModelKind::Diarization).ok()?;
+    let seg_path = models_dir.join(crate::diarization::SEGMENTATION_MODEL_NAME);
+    let emb_path = models_dir.join(crate::diarization::EMBEDDING_MODEL_NAME);
+
+    if !(seg_path.exists() && emb_path.exists()) {
+        warn!("Diarization models not found at {:?}", models_dir);
+        return None;
+    }
+
+    info!("Found diarization models, initializing engine...");
+    match crate::diarization::DiarizationEngine::new(
+        crate::diarization::DiarizationConfig {
+            segmentation_model_path: seg_path,
+            embedding_model_path: emb_path,
+            max_speakers,
+            similarity_threshold,
+        }
+    ) {
+        Ok(engine) => {
+            info!("Diarization engine initialized successfully");
+            Some(engine)
+        }
+        Err(e) => {
+            warn!("Failed to initialize diarization engine: {}", e);
+            None
+        }
+    }
+}
+
+/// Transcribe one chunk, retrying with exponential backoff before giving up
+async fn transcribe_chunk_with_retry(
+    engine: &crate::whisper_engine::WhisperEngine,
+    data: Vec<f32>,
+    language: Option<String>,
+    chunk_idx: usize,
+) -> Result<String> {
+    let mut attempt = 1;
+    loop {
+        match engine.transcribe_audio(data.clone(), language.clone()).await {
+            Ok(text) => return Ok(text),
+            Err(e) if attempt < CHUNK_MAX_ATTEMPTS => {
+                let delay_ms = CHUNK_RETRY_BASE_DELAY_MS * 2u64.pow(attempt - 1);
+                warn!("Failed to transcribe chunk {} (attempt {}/{}): {} - retrying in {}ms",
+                      chunk_idx, attempt, CHUNK_MAX_ATTEMPTS, e, delay_ms);
+                tokio::time::sleep(std::time::Duration::from_millis(delay_ms)).await;
+                attempt += 1;
+            }
+            Err(e) => return Err(e),
+        }
+    }
+}
+
 /// Tauri command to start retranscription of a recording
 /// This runs in the background and emits progress events
 #[tauri::command]
@@ -569,29 +967,273 @@ pub async fn retranscribe_recording<R: Runtime>(
     enable_diarization: Option<bool>,
     diarization_provider: Option<String>,
     max_speakers: Option<usize>,
+    auto_speakers: Option<bool>,
     similarity_threshold: Option<f32>,
+    min_confidence: Option<f32>,
+    min_speaker_segment_seconds: Option<f64>,
+    speaker_merge_gap_seconds: Option<f64>,
 ) -> Result<(), String> {
+    let options = RetranscriptionOptions {
+        model_name,
+        language,
+        enable_diarization,
+        diarization_provider,
+        max_speakers,
+        auto_speakers,
+        similarity_threshold,
+        min_confidence,
+        min_speaker_segment_seconds,
+        speaker_merge_gap_seconds,
+    };
+    options.validate()?;
+
+    run_retranscription(&app, recording_id, audio_file_path, options).await?;
+    Ok(())
+}
+
+/// Transcribe a short audio file and return its text.
+/// Unlike retranscription nothing is saved and no events are emitted; the
+/// audio is still split into 30s chunks for Whisper. A model loaded for the
+/// job is swapped back for the previous one afterwards. Refused while
+/// recording, as the live transcription shares the Whisper engine.
+#[tauri::command]
+pub async fn transcribe_file_quick(
+    audio_path: String,
+    model: Option<String>,
+    language: Option<String>,
+) -> Result<String, String> {
+    use crate::whisper_engine::commands::WHISPER_ENGINE;
+
+    if !Path::new(&audio_path).is_file() {
+        return Err(format!("Audio file not found: {}", audio_path));
+    }
+    if super::recording::state::is_recording() {
+        return Err("Cannot transcribe a file while a recording is in progress".to_string());
+    }
+
+    let engine = WHISPER_ENGINE
+        .lock()
+        .unwrap()
+        .clone()
+        .ok_or_else(|| "Whisper engine not initialized".to_string())?;
+
+    let job = StandaloneJob::start();
+    let _permit = task_scheduler::acquire(TaskKind::Transcription, format!("Transcribe {}", audio_path)).await?;
+    job.check_cancelled()?;
+    let _model_guard = crate::audio::recording::model_retention::ModelUseGuard::acquire();
+
+    let mut previous_model = None;
+    if let Some(model) = model.filter(|m| m != "current") {
+        let current = engine.get_current_model().await;
+        if current.as_deref() != Some(model.as_str()) {
+            info!("Loading model '{}' for quick transcription", model);
+            let was_loaded = engine.is_model_loaded().await;
+            engine
+                .load_model(&model)
+                .await
+                .map_err(|e| format!("Failed to load model '{}': {}", model, e))?;
+            previous_model = current.filter(|_| was_loaded);
+        }
+    }
+
+    let text = transcribe_file_text(&engine, &audio_path, language, &job).await;
+
+    if let Some(previous) = previous_model {
+        info!("Restoring model '{}' after quick transcription", previous);
+        if let Err(e) = engine.load_model(&previous).await {
+            warn!("Failed to restore model '{}': {}", previous, e);
+        }
+    }
+    text
+}
+
+/// Decode a file and transcribe it chunk by chunk with the loaded model
+async fn transcribe_file_text(
+    engine: &crate::whisper_engine::WhisperEngine,
+    audio_path: &str,
+    language: Option<String>,
+    job: &StandaloneJob,
+) -> Result<String, String> {
+    if !engine.is_model_loaded().await {
+        return Err("No Whisper model loaded. Please load a model first.".to_string());
+    }
+
+    let path = audio_path.to_string();
+    let (samples, sample_rate) = tokio::task::spawn_blocking(move || decode_audio_file(&path))
+        .await
+        .map_err(|e| e.to_string())?
+        .map_err(|e| format!("Failed to decode audio: {}", e))?;
+
+    let chunks = prepare_chunks(samples, sample_rate, 30000.0);
+    let mut text = String::new();
+    for (idx, chunk) in chunks.into_iter().enumerate() {
+        job.check_cancelled()?;
+        let chunk_text = transcribe_chunk_with_retry(engine, chunk.data, language.clone(), idx)
+            .await
+            .map_err(|e| format!("Failed to transcribe {}: {}", audio_path, e))?;
+        let chunk_text = chunk_text.trim();
+        if !chunk_text.is_empty() {
+            if !text.is_empty() {
+                text.push(' ');
+            }
+            text.push_str(chunk_text);
+        }
+    }
+
+    Ok(text)
+}
+
+/// Seconds of audio decoded for language detection (Whisper's window)
+const LANGUAGE_DETECTION_SECONDS: f64 = 30.0;
+
+/// Language detected from the start of an audio file
+#[derive(Debug, Clone, Serialize)]
+pub struct LanguageDetection {
+    /// Whisper language code, e.g. "en"
+    pub language: String,
+    /// Probability of the detected language (0.0-1.0)
+    pub confidence: f32,
+}
+
+/// Detect the spoken language from the first ~30s of an audio file, without transcribing it.
+/// Uses the currently loaded Whisper model, which must be multilingual.
+#[tauri::command]
+pub async fn detect_audio_language(audio_path: String) -> Result<LanguageDetection, String> {
+    use crate::whisper_engine::commands::WHISPER_ENGINE;
+
+    let engine = WHISPER_ENGINE
+        .lock()
+        .unwrap()
+        .clone()
+        .ok_or_else(|| "Whisper engine not initialized".to_string())?;
+
+    let _model_guard = crate::audio::recording::model_retention::ModelUseGuard::acquire();
+
+    let path = audio_path.clone();
+    let (samples, _) = tokio::task::spawn_blocking(move || decode_audio_file_head(&path, LANGUAGE_DETECTION_SECONDS))
+        .await
+        .map_err(|e| e.to_string())?
+        .map_err(|e| format!("Failed to decode audio: {}", e))?;
+
+    if samples.is_empty() {
+        return Err(format!("No audio found in {}", audio_path));
+    }
+
+    let (language, confidence) = engine
+        .detect_language(&samples)
+        .await
+        .map_err(|e| format!("Failed to detect language: {}", e))?;
+    info!("Detected language '{}' ({:.0}%) for {}", language, confidence * 100.0, audio_path);
+
+    Ok(LanguageDetection { language, confidence })
+}
+
+/// Retranscribe a single recording, save the new transcript and emit the
+/// completion event once it is saved. Listeners of `retranscription-complete`
+/// only display the result; the backend owns persistence.
+/// Returns None if the job was cancelled.
+pub async fn run_retranscription<R: Runtime>(
+    app: &AppHandle<R>,
+    recording_id: String,
+    audio_file_path: String,
+    options: RetranscriptionOptions,
+) -> Result<Option<RetranscriptionResult>, String> {
+    let diarization_provider = used_diarization_provider(&options);
+    let Some(transcription) = transcribe_recording(app, recording_id, audio_file_path, options).await? else {
+        return Ok(None);
+    };
+
+    let saved = {
+        let state = app.state::<AppState>();
+        let db = state.db().await;
+        save_retranscription_result(&db, &transcription, diarization_provider.as_deref())
+    };
+    let mut result = transcription.result;
+    if let Err(e) = saved {
+        let error_msg = format!("Failed to save transcript: {}", e);
+        error!("{} for recording {}", error_msg, result.recording_id);
+        result.success = false;
+        result.error = Some(error_msg.clone());
+        emit_complete(app, &result);
+        return Err(error_msg);
+    }
+
+    emit_complete(app, &result);
+    Ok(Some(result))
+}
+
+/// Diarization provider to store on the recording: None when diarization is off
+pub(crate) fn used_diarization_provider(options: &RetranscriptionOptions) -> Option<String> {
+    options
+        .enable_diarization
+        .unwrap_or(false)
+        .then(|| options.diarization_provider.clone().unwrap_or_else(|| "pyannote".to_string()))
+}
+
+/// Transcribe (and diarize) a recording's audio without saving anything,
+/// emitting progress and segment events. A failure is reported with a
+/// `retranscription-complete` event; on success the caller decides what to
+/// save and when to announce it. Returns None if the job was cancelled.
+pub(crate) async fn transcribe_recording<R: Runtime>(
+    app: &AppHandle<R>,
+    recording_id: String,
+    audio_file_path: String,
+    options: RetranscriptionOptions,
+) -> Result<Option<Transcription>, String> {
     use crate::whisper_engine::commands::WHISPER_ENGINE;
     use crate::diarization::DIARIZATION_ENGINE;
     use crate::diarization::sortformer_provider::SORTFORMER_ENGINE;
 
+    let RetranscriptionOptions {
+        model_name,
+        language,
+        enable_diarization,
+        diarization_provider,
+        max_speakers,
+        auto_speakers,
+        similarity_threshold,
+        min_confidence,
+        min_speaker_segment_seconds,
+        speaker_merge_gap_seconds,
+    } = options;
+
     let diarization_enabled = enable_diarization.unwrap_or(false);
     let provider = diarization_provider.as_deref().unwrap_or("pyannote");
 
     // Use provided values or defaults for pyannote settings
-    let max_spk = max_speakers.unwrap_or(10);
+    let max_spk = max_speakers.unwrap_or(10).max(1);
+    let auto_spk = auto_speakers.unwrap_or(false);
     let sim_threshold = similarity_threshold.unwrap_or(0.4);
+    let min_speaker_segment = min_speaker_segment_seconds
+        .unwrap_or(crate::diarization::DEFAULT_MIN_SPEAKER_SEGMENT_SECONDS);
+    let merge_gap = speaker_merge_gap_seconds.unwrap_or(DEFAULT_SPEAKER_MERGE_GAP_SECONDS);
 
     info!("Starting retranscription for recording: {}", recording_id);
     info!("Audio file: {}", audio_file_path);
-    info!("Model: {:?}, Language: {:?}, Diarization: {} (provider: {}, max_speakers: {}, threshold: {:.2})",
-          model_name, language, diarization_enabled, provider, max_spk, sim_threshold);
+    info!("Model: {:?}, Language: {:?}, Diarization: {} (provider: {}, max_speakers: {}{}, threshold: {:.2})",
+          model_name, language, diarization_enabled, provider, max_spk,
+          if auto_spk { " auto" } else { "" }, sim_threshold);
 
     // Clear any previous cancellation flag for this recording
     clear_cancelled(&recording_id);
+    let _active = ActiveRetranscription::start(&recording_id);
+
+    // Wait for a background task slot; diarization runs under the same slot
+    if task_scheduler::is_busy() {
+        emit_progress(app, &recording_id, "loading", 0, 0, 0, "Waiting for other background tasks...");
+    }
+    let permit = task_scheduler::acquire(TaskKind::Transcription, format!("Retranscribe {}", recording_id)).await;
+    if permit.is_err() || is_cancelled(&recording_id) {
+        info!("Retranscription cancelled while queued for recording: {}", recording_id);
+        clear_cancelled(&recording_id);
+        return Ok(None);
+    }
+
+    // Keep a retained model from being idle-unloaded mid-job
+    let _model_guard = crate::audio::recording::model_retention::ModelUseGuard::acquire();
 
     // Emit initial progress
-    emit_progress(&app, &recording_id, "loading", 0, 0, 0, "Loading audio file...");
+    emit_progress(app, &recording_id, "loading", 0, 0, 0, "Loading audio file...");
 
     // Decode the audio file
     let (samples, sample_rate) = match decode_audio_file(&audio_file_path) {
@@ -599,12 +1241,13 @@ pub async fn retranscribe_recording<R: Runtime>(
         Err(e) => {
             let error_msg = format!("Failed to decode audio: {}", e);
             error!("{}", error_msg);
-            emit_complete(&app, &RetranscriptionResult {
+            emit_complete(app, &RetranscriptionResult {
                 recording_id: recording_id.clone(),
                 success: false,
                 transcripts: vec![],
                 error: Some(error_msg.clone()),
                 model_used: model_name.clone().unwrap_or_default(),
+                failed_chunks: vec![],
             });
             return Err(error_msg);
         }
@@ -618,7 +1261,7 @@ pub async fn retranscribe_recording<R: Runtime>(
     let chunks = prepare_chunks(samples, sample_rate, chunk_duration_ms);
     let total_chunks = chunks.len() as u32;
 
-    emit_progress(&app, &recording_id, "processing", 5, 0, total_chunks,
+    emit_progress(app, &recording_id, "processing", 5, 0, total_chunks,
                   &format!("Processing {} chunks...", total_chunks));
 
     // Get Whisper engine
@@ -629,12 +1272,13 @@ pub async fn retranscribe_recording<R: Runtime>(
             None => {
                 let error_msg = "Whisper engine not initialized".to_string();
                 error!("{}", error_msg);
-                emit_complete(&app, &RetranscriptionResult {
+                emit_complete(app, &RetranscriptionResult {
                     recording_id: recording_id.clone(),
                     success: false,
                     transcripts: vec![],
                     error: Some(error_msg.clone()),
                     model_used: model_name.clone().unwrap_or_default(),
+                    failed_chunks: vec![],
                 });
                 return Err(error_msg);
             }
@@ -648,18 +1292,19 @@ pub async fn retranscribe_recording<R: Runtime>(
         let current_model = engine.get_current_model().await;
         if current_model.as_deref() != Some(model.as_str()) {
             info!("Loading model '{}' for retranscription (current: {:?})", model, current_model);
-            emit_progress(&app, &recording_id, "loading", 2, 0, 0,
+            emit_progress(app, &recording_id, "loading", 2, 0, 0,
                           &format!("Loading model '{}'...", model));
 
             if let Err(e) = engine.load_model(&model).await {
                 let error_msg = format!("Failed to load model '{}': {}", model, e);
                 error!("{}", error_msg);
-                emit_complete(&app, &RetranscriptionResult {
+                emit_complete(app, &RetranscriptionResult {
                     recording_id: recording_id.clone(),
                     success: false,
                     transcripts: vec![],
                     error: Some(error_msg.clone()),
                     model_used: model.clone(),
+                    failed_chunks: vec![],
                 });
                 return Err(error_msg);
             }
@@ -671,42 +1316,69 @@ pub async fn retranscribe_recording<R: Runtime>(
         debug!("Using currently loaded model for retranscription");
     }
 
+    // Leave room in the progress bar for diarization when it will run
+    let transcription_span = if diarization_enabled {
+        (DIARIZATION_PROGRESS_START - 5) as f64
+    } else {
+        90.0
+    };
+
     // Process each chunk
     let mut transcripts: Vec<TranscriptSegment> = Vec::new();
+    let mut failed_chunks: Vec<u32> = Vec::new();
 
     for (idx, chunk) in chunks.iter().enumerate() {
         // Check for cancellation before processing each chunk
         if is_cancelled(&recording_id) {
             info!("Retranscription cancelled for recording: {}", recording_id);
             clear_cancelled(&recording_id);
-            return Ok(()); // Exit gracefully - cancellation event already emitted
+            return Ok(None); // Exit gracefully - cancellation event already emitted
         }
 
-        let progress_percent = ((idx as f64 / total_chunks as f64) * 90.0 + 5.0) as u32;
-        emit_progress(&app, &recording_id, "processing", progress_percent,
+        let progress_percent = ((idx as f64 / total_chunks as f64) * transcription_span + 5.0) as u32;
+        emit_progress(app, &recording_id, "processing", progress_percent,
                       idx as u32 + 1, total_chunks,
                       &format!("Transcribing chunk {} of {}...", idx + 1, total_chunks));
 
-        // Transcribe the chunk
-        match engine.transcribe_audio(chunk.data.clone(), language.clone()).await {
+        // Transcribe the chunk, retrying transient failures
+        let chunk_start = chunk.start_time_ms / 1000.0; // Convert to seconds
+        let chunk_end = (chunk.start_time_ms + chunk.duration_ms) / 1000.0;
+        match transcribe_chunk_with_retry(&engine, chunk.data.clone(), language.clone(), idx).await {
             Ok(text) => {
                 if !text.trim().is_empty() {
-                    transcripts.push(TranscriptSegment {
+                    let segment = TranscriptSegment {
                         text: text.trim().to_string(),
-                        audio_start_time: chunk.start_time_ms / 1000.0, // Convert to seconds
-                        audio_end_time: (chunk.start_time_ms + chunk.duration_ms) / 1000.0,
-                        confidence: 0.95, // Placeholder - could be extracted from Whisper
+                        audio_start_time: chunk_start,
+                        audio_end_time: chunk_end,
+                        confidence: PLACEHOLDER_CONFIDENCE,
                         sequence_id: idx as u32,
                         // Speaker info will be added after diarization if enabled
                         speaker_id: None,
                         speaker_label: None,
                         is_registered_speaker: false,
-                    });
+                        low_confidence: false,
+                    };
+                    emit_segment(app, &recording_id, total_chunks, &segment);
+                    transcripts.push(segment);
                 }
             }
             Err(e) => {
-                warn!("Failed to transcribe chunk {}: {}", idx, e);
-                // Continue with other chunks even if one fails
+                error!("Giving up on chunk {} after {} attempts: {}", idx, CHUNK_MAX_ATTEMPTS, e);
+                failed_chunks.push(idx as u32);
+                // Mark the gap instead of silently dropping it
+                let segment = TranscriptSegment {
+                    text: FAILED_CHUNK_PLACEHOLDER.to_string(),
+                    audio_start_time: chunk_start,
+                    audio_end_time: chunk_end,
+                    confidence: 0.0,
+                    sequence_id: idx as u32,
+                    speaker_id: None,
+                    speaker_label: None,
+                    is_registered_speaker: false,
+                    low_confidence: false,
+                };
+                emit_segment(app, &recording_id, total_chunks, &segment);
+                transcripts.push(segment);
             }
         }
 
@@ -714,17 +1386,23 @@ pub async fn retranscribe_recording<R: Runtime>(
         if is_cancelled(&recording_id) {
             info!("Retranscription cancelled after chunk {} for recording: {}", idx, recording_id);
             clear_cancelled(&recording_id);
-            return Ok(()); // Exit gracefully - cancellation event already emitted
+            return Ok(None); // Exit gracefully - cancellation event already emitted
         }
     }
 
     info!("Transcription complete: {} segments", transcripts.len());
 
+    if let Some(threshold) = min_confidence {
+        let flagged = flag_low_confidence(&mut transcripts, threshold);
+        info!("Flagged {} of {} segments below confidence {:.2}", flagged, transcripts.len(), threshold);
+    }
+
     // Run diarization if enabled
+    let mut speaker_embeddings = None;
     if diarization_enabled && !transcripts.is_empty() {
         let provider_name = if provider == "sortformer" { "Sortformer" } else { "PyAnnote" };
 
-        emit_progress(&app, &recording_id, "diarizing", 95, total_chunks, total_chunks,
+        emit_progress(app, &recording_id, "diarizing", DIARIZATION_PROGRESS_START, total_chunks, total_chunks,
                       &format!("Loading {} diarization model...", provider_name));
 
         // Re-decode audio for diarization (need fresh samples)
@@ -739,9 +1417,7 @@ pub async fn retranscribe_recording<R: Runtime>(
                     // Auto-initialize if not already initialized
                     if guard.is_none() {
                         info!("Sortformer engine not initialized, attempting auto-initialization...");
-                        use tauri::Manager;
-                        if let Ok(app_data_dir) = app.path().app_data_dir() {
-                            let models_dir = app_data_dir.join("models");
+                        if let Ok(models_dir) = crate::model_directories::models_dir(&app, crate::model_directories::ModelKind::Diarization) {
                             let model_path = models_dir.join(crate::diarization::SORTFORMER_MODEL_NAME);
 
                             if model_path.exists() {
@@ -764,10 +1440,10 @@ pub async fn retranscribe_recording<R: Runtime>(
                     if let Some(sortformer_engine) = guard.as_mut() {
                         sortformer_engine.reset();
 
-                        emit_progress(&app, &recording_id, "diarizing", 96, total_chunks, total_chunks,
-                                      "Detecting speakers in audio...");
+                        let mut report_progress = diarization_progress_reporter(app, &recording_id, total_chunks);
+                        report_progress(0.0);
 
-                        match sortformer_engine.diarize(diarization_samples, diarization_rate) {
+                        match sortformer_engine.diarize_with_progress(diarization_samples, diarization_rate, &mut report_progress) {
                             Ok(segments) => {
                                 info!("Sortformer diarization found {} speaker segments", segments.len());
                                 // Convert Sortformer segments to our format
@@ -798,50 +1474,41 @@ pub async fn retranscribe_recording<R: Runtime>(
 
                     // Auto-initialize if not already initialized
                     if guard.is_none() {
-                        info!("Diarization engine not initialized, attempting auto-initialization...");
-
-                        // Get models directory from app handle
-                        use tauri::Manager;
-                        if let Ok(app_data_dir) = app.path().app_data_dir() {
-                            let models_dir = app_data_dir.join("models");
-                            let seg_path = models_dir.join(crate::diarization::SEGMENTATION_MODEL_NAME);
-                            let emb_path = models_dir.join(crate::diarization::EMBEDDING_MODEL_NAME);
-
-                            if seg_path.exists() && emb_path.exists() {
-                                info!("Found diarization models, initializing engine...");
-                                match crate::diarization::DiarizationEngine::new(
-                                    crate::diarization::DiarizationConfig {
-                                        segmentation_model_path: seg_path,
-                                        embedding_model_path: emb_path,
-                                        max_speakers: max_spk,
-                                        similarity_threshold: sim_threshold,
-                                    }
-                                ) {
-                                    Ok(engine) => {
-                                        *guard = Some(engine);
-                                        info!("Diarization engine initialized successfully");
-                                    }
-                                    Err(e) => {
-                                        warn!("Failed to initialize diarization engine: {}", e);
-                                    }
-                                }
-                            } else {
-                                warn!("Diarization models not found at {:?}", models_dir);
-                            }
-                        }
+                        *guard = create_pyannote_engine(app, max_spk, sim_threshold);
                     }
 
                     if let Some(diarization_engine) = guard.as_mut() {
                         // Update configuration with user-specified values
                         diarization_engine.update_config(Some(max_spk), Some(sim_threshold));
 
-                        emit_progress(&app, &recording_id, "diarizing", 96, total_chunks, total_chunks,
-                                      "Detecting speakers in audio...");
-
-                        // Run diarization on the full audio
-                        match diarization_engine.diarize(&diarization_samples, diarization_rate) {
+                        let mut report_progress = diarization_progress_reporter(app, &recording_id, total_chunks);
+                        report_progress(0.0);
+
+                        // Run diarization on the full audio, caching the embeddings
+                        // so speakers can be re-clustered later without the models
+                        let result = diarization_engine
+                            .extract_segment_embeddings_with_progress(&diarization_samples, diarization_rate, &mut report_progress)
+                            .and_then(|embedded| {
+                                if let Some(folder) = Path::new(&audio_file_path).parent() {
+                                    if let Err(e) = crate::diarization::save_segment_embeddings(folder, &embedded) {
+                                        warn!("Failed to cache diarization embeddings: {}", e);
+                                    }
+                                }
+                                if auto_spk {
+                                    diarization_engine
+                                        .assign_speakers_auto(&embedded, max_spk)
+                                        .map(|(segments, _)| segments)
+                                } else {
+                                    diarization_engine.assign_speakers(&embedded)
+                                }
+                            });
+                        match result {
                             Ok(segments) => {
                                 info!("PyAnnote diarization found {} speaker segments", segments.len());
+                                speaker_embeddings = Some(speaker_embeddings_from_centroids(
+                                    &recording_id,
+                                    diarization_engine.speaker_centroids(),
+                                ));
                                 Some(segments)
                             }
                             Err(e) => {
@@ -857,10 +1524,15 @@ pub async fn retranscribe_recording<R: Runtime>(
 
                 // Apply speaker segments to transcripts if diarization succeeded
                 if let Some(segments) = speaker_segments {
-                    emit_progress(&app, &recording_id, "diarizing", 98, total_chunks, total_chunks,
+                    emit_progress(app, &recording_id, "diarizing", 98, total_chunks, total_chunks,
                                   "Assigning speakers to transcript...");
 
-                    transcripts = assign_and_merge_speakers(transcripts, &segments);
+                    let segment_count = segments.len();
+                    let segments = crate::diarization::smooth_speaker_segments(segments, min_speaker_segment);
+                    debug!("Speaker smoothing: {} -> {} segments (min {:.2}s)",
+                           segment_count, segments.len(), min_speaker_segment);
+
+                    transcripts = assign_and_merge_speakers(transcripts, &segments, merge_gap);
                 }
             }
             Err(e) => {
@@ -872,22 +1544,701 @@ pub async fn retranscribe_recording<R: Runtime>(
     info!("Retranscription complete: {} segments", transcripts.len());
 
     // Emit completion
-    emit_progress(&app, &recording_id, "completed", 100, total_chunks, total_chunks,
-                  "Retranscription complete!");
+    let completion_message = if failed_chunks.is_empty() {
+        "Retranscription complete!".to_string()
+    } else {
+        warn!("Retranscription partial: {} of {} chunks failed", failed_chunks.len(), total_chunks);
+        format!("Retranscription complete, but {} of {} chunks could not be transcribed",
+                failed_chunks.len(), total_chunks)
+    };
+    emit_progress(app, &recording_id, "completed", 100, total_chunks, total_chunks,
+                  &completion_message);
+
+    Ok(Some(Transcription {
+        result: RetranscriptionResult {
+            recording_id: recording_id.clone(),
+            success: true,
+            transcripts,
+            error: None,
+            model_used: model,
+            failed_chunks,
+        },
+        speaker_embeddings,
+    }))
+}
+
+/// Relabel a recording's saved transcript from a speaker timeline, using the
+/// same majority-overlap matching as retranscription. Segments no speaker
+/// overlaps lose their label. Returns how many segments changed speaker.
+pub(crate) fn apply_speakers_to_saved_transcript(
+    db: &crate::database::DatabaseManager,
+    recording_id: &str,
+    speaker_segments: &[crate::diarization::SpeakerSegment],
+) -> Result<usize> {
+    let mut transcripts = db.get_transcript_segments(recording_id)?;
+    let mut segments_updated = 0;
+    for transcript in &mut transcripts {
+        let (speaker_id, speaker_label, is_registered) =
+            match best_speaker_match(transcript.audio_start_time, transcript.audio_end_time, speaker_segments) {
+                Some((seg, _)) => (Some(seg.speaker_id.clone()), Some(seg.speaker_label.clone()), seg.is_registered),
+                None => (None, None, false),
+            };
+        if transcript.speaker_id != speaker_id || transcript.speaker_label != speaker_label {
+            segments_updated += 1;
+        }
+        transcript.speaker_id = speaker_id;
+        transcript.speaker_label = speaker_label;
+        transcript.is_registered_speaker = is_registered;
+    }
+    db.replace_transcripts(recording_id, &transcripts)?;
+    Ok(segments_updated)
+}
+
+/// Re-assign speakers to a recording's saved transcript and merge consecutive
+/// same-speaker segments closer than `merge_gap` seconds, as a retranscription
+/// does. A merged segment keeps the id and display time of its first part.
+/// Returns the number of saved segments whose speaker changed.
+fn apply_and_merge_speakers_in_saved_transcript(
+    db: &crate::database::DatabaseManager,
+    recording_id: &str,
+    speaker_segments: &[crate::diarization::SpeakerSegment],
+    merge_gap: f64,
+) -> Result<usize> {
+    let saved = db.get_transcript_segments(recording_id)?;
+    // The sequence_id is the saved segment's index until the merge
+    let mut transcripts: Vec<TranscriptSegment> = saved
+        .iter()
+        .enumerate()
+        .map(|(index, s)| TranscriptSegment {
+            text: s.text.clone(),
+            audio_start_time: s.audio_start_time,
+            audio_end_time: s.audio_end_time,
+            confidence: s.confidence,
+            sequence_id: index as u32,
+            speaker_id: None,
+            speaker_label: None,
+            is_registered_speaker: false,
+            low_confidence: s.low_confidence,
+        })
+        .collect();
+    assign_speakers(&mut transcripts, speaker_segments);
+
+    let segments_updated = saved
+        .iter()
+        .zip(&transcripts)
+        .filter(|(before, after)| before.speaker_id != after.speaker_id || before.speaker_label != after.speaker_label)
+        .count();
+
+    let merged: Vec<crate::database::TranscriptSegment> = merge_speaker_turns(transcripts, merge_gap)
+        .into_iter()
+        .enumerate()
+        .map(|(sequence_id, t)| {
+            let first = &saved[t.sequence_id as usize];
+            crate::database::TranscriptSegment {
+                text: t.text,
+                audio_end_time: t.audio_end_time,
+                duration: t.audio_end_time - first.audio_start_time,
+                sequence_id: sequence_id as i64,
+                speaker_id: t.speaker_id,
+                speaker_label: t.speaker_label,
+                is_registered_speaker: t.is_registered_speaker,
+                ..first.clone()
+            }
+        })
+        .collect();
+    db.replace_transcripts(recording_id, &merged)?;
+    Ok(segments_updated)
+}
+
+/// Result of re-clustering a recording's speakers
+#[derive(Debug, Clone, Serialize)]
+pub struct ReclusterResult {
+    pub recording_id: String,
+    pub speaker_count: usize,
+    /// Transcript segments whose speaker changed
+    pub segments_updated: usize,
+}
+
+/// Re-cluster a recording's speakers with new parameters and update the saved
+/// transcript. Uses the embeddings cached by the last PyAnnote retranscription,
+/// so the audio isn't decoded and the segmentation model isn't run again.
+/// Consecutive same-speaker segments closer than `speaker_merge_gap_seconds`
+/// (default DEFAULT_SPEAKER_MERGE_GAP_SECONDS) are merged.
+#[tauri::command]
+pub async fn recluster_speakers<R: Runtime>(
+    app: AppHandle<R>,
+    state: tauri::State<'_, AppState>,
+    recording_id: String,
+    similarity_threshold: f32,
+    max_speakers: Option<usize>,
+    auto_speakers: Option<bool>,
+    speaker_merge_gap_seconds: Option<f64>,
+) -> Result<ReclusterResult, String> {
+    use crate::diarization::DIARIZATION_ENGINE;
+
+    if !(0.0..=1.0).contains(&similarity_threshold) {
+        return Err("similarity_threshold must be between 0 and 1".to_string());
+    }
+    let merge_gap = speaker_merge_gap_seconds.unwrap_or(DEFAULT_SPEAKER_MERGE_GAP_SECONDS);
+    if !merge_gap.is_finite() || merge_gap < 0.0 {
+        return Err("speaker_merge_gap_seconds must be zero or more".to_string());
+    }
+    let max_spk = max_speakers.unwrap_or(10);
+    if max_spk == 0 {
+        return Err("max_speakers must be at least 1".to_string());
+    }
+
+    let audio_path = {
+        let db = state.db().await;
+        db.get_recording(&recording_id)
+            .map_err(|e| e.to_string())?
+            .ok_or_else(|| format!("Recording not found: {}", recording_id))?
+            .audio_file_path
+            .ok_or_else(|| format!("Recording {} has no audio file", recording_id))?
+    };
+    let folder = Path::new(&audio_path)
+        .parent()
+        .ok_or_else(|| format!("Invalid audio path: {}", audio_path))?;
+    let embedded = crate::diarization::load_segment_embeddings(folder)
+        .map_err(|e| e.to_string())?
+        .ok_or_else(|| "No cached diarization data for this recording. Retranscribe it with PyAnnote diarization first.".to_string())?;
+
+    let job = StandaloneJob::start();
+    let _permit = task_scheduler::acquire(TaskKind::Diarization, format!("Re-cluster speakers of {}", recording_id)).await?;
+    job.check_cancelled()?;
+    let (speaker_segments, centroids) = {
+        let mut guard = DIARIZATION_ENGINE.write().await;
+        if guard.is_none() {
+            *guard = create_pyannote_engine(&app, max_spk, similarity_threshold);
+        }
+        let engine = guard
+            .as_mut()
+            .ok_or_else(|| "Diarization engine not available. Download the diarization models first.".to_string())?;
 
-    let result = RetranscriptionResult {
-        recording_id: recording_id.clone(),
-        success: true,
-        transcripts,
+        engine.update_config(Some(max_spk), Some(similarity_threshold));
+        let segments = if auto_speakers.unwrap_or(false) {
+            engine.assign_speakers_auto(&embedded, max_spk).map(|(segments, _)| segments)
+        } else {
+            engine.assign_speakers(&embedded)
+        }
+        .map_err(|e| e.to_string())?;
+        (segments, engine.speaker_centroids())
+    };
+
+    // Leave the saved speakers untouched if cancelled while clustering
+    job.check_cancelled()?;
+
+    let speaker_segments = crate::diarization::smooth_speaker_segments(
+        speaker_segments,
+        crate::diarization::DEFAULT_MIN_SPEAKER_SEGMENT_SECONDS,
+    );
+    let speaker_count = speaker_segments
+        .iter()
+        .map(|s| s.speaker_id.as_str())
+        .collect::<HashSet<_>>()
+        .len();
+    save_speaker_embeddings(&app, &recording_id, centroids).await;
+
+    let segments_updated = {
+        let db = state.db().await;
+        apply_and_merge_speakers_in_saved_transcript(&db, &recording_id, &speaker_segments, merge_gap)
+            .map_err(|e| e.to_string())?
+    };
+
+    info!("Re-clustered recording {}: {} speakers, {} segments changed (threshold {:.2})",
+          recording_id, speaker_count, segments_updated, similarity_threshold);
+
+    Ok(ReclusterResult {
+        recording_id,
+        speaker_count,
+        segments_updated,
+    })
+}
+
+// ============ Batch Transcription ============
+// Imports a list of audio files as new recordings and transcribes them one
+// after another with `run_retranscription`.
+
+/// Outcome for one file of a batch
+#[derive(Debug, Clone, Serialize)]
+pub struct BatchFileResult {
+    pub path: String,
+    pub success: bool,
+    /// Set once the recording was created, even if transcription then failed
+    pub recording_id: Option<String>,
+    pub segment_count: usize,
+    pub error: Option<String>,
+}
+
+/// Outcome of a batch transcription
+#[derive(Debug, Clone, Serialize)]
+pub struct BatchTranscriptionSummary {
+    pub total: usize,
+    pub succeeded: usize,
+    pub failed: usize,
+    pub files: Vec<BatchFileResult>,
+}
+
+/// Copy an audio file into a new meeting folder and create its recording
+async fn import_audio_file<R: Runtime>(
+    app: &AppHandle<R>,
+    path: &str,
+    model: Option<&str>,
+    language: Option<&str>,
+    diarization_provider: Option<&str>,
+) -> Result<crate::database::Recording, String> {
+    let source = Path::new(path);
+    if !source.is_file() {
+        return Err(format!("Audio file not found: {}", path));
+    }
+    let title = source
+        .file_stem()
+        .map(|stem| stem.to_string_lossy().to_string())
+        .filter(|stem| !stem.trim().is_empty())
+        .unwrap_or_else(|| "Imported recording".to_string());
+    let file_name = source
+        .extension()
+        .map(|ext| format!("audio.{}", ext.to_string_lossy()))
+        .unwrap_or_else(|| "audio".to_string());
+
+    let base_folder = super::recording_preferences::get_default_recordings_folder();
+    let source = source.to_path_buf();
+    let folder_name = title.clone();
+    let (folder, audio_path, duration) = tokio::task::spawn_blocking(move || {
+        let folder = super::file_io::create_meeting_folder(&base_folder, &folder_name)?;
+        let audio_path = folder.join(&file_name);
+        std::fs::copy(&source, &audio_path)?;
+        let duration = get_audio_duration(&audio_path.to_string_lossy()).ok();
+        Ok::<_, anyhow::Error>((folder, audio_path, duration))
+    })
+    .await
+    .map_err(|e| e.to_string())?
+    .map_err(|e| format!("Failed to import {}: {}", path, e))?;
+
+    // Completed (or failed) by transcribe_batch_file once transcription ends
+    let mut recording = crate::database::Recording::new(format!("rec-{}", uuid::Uuid::new_v4()), title);
+    recording.status = "transcribing".to_string();
+    recording.duration_seconds = duration;
+    recording.audio_file_path = Some(audio_path.to_string_lossy().to_string());
+    recording.meeting_folder_path = Some(folder.to_string_lossy().to_string());
+    recording.transcription_model = model.map(str::to_string);
+    recording.language = language.map(str::to_string);
+    recording.diarization_provider = diarization_provider.map(str::to_string);
+
+    let state = app.state::<AppState>();
+    state.db().await.create_recording(&recording).map_err(|e| e.to_string())?;
+    Ok(recording)
+}
+
+/// Import and transcribe one file of a batch
+async fn transcribe_batch_file<R: Runtime>(
+    app: &AppHandle<R>,
+    path: &str,
+    options: &RetranscriptionOptions,
+) -> BatchFileResult {
+    let mut result = BatchFileResult {
+        path: path.to_string(),
+        success: false,
+        recording_id: None,
+        segment_count: 0,
         error: None,
-        model_used: model,
     };
 
-    emit_complete(&app, &result);
+    let diarization_provider = used_diarization_provider(options);
+    let recording = match import_audio_file(
+        app,
+        path,
+        options.model_name.as_deref(),
+        options.language.as_deref(),
+        diarization_provider.as_deref(),
+    )
+    .await
+    {
+        Ok(recording) => recording,
+        Err(e) => {
+            result.error = Some(e);
+            return result;
+        }
+    };
+    result.recording_id = Some(recording.id.clone());
+    let audio_path = recording.audio_file_path.clone().unwrap_or_default();
+
+    match run_retranscription(app, recording.id.clone(), audio_path, options.clone()).await {
+        Ok(Some(transcription)) => {
+            result.success = true;
+            result.segment_count = transcription.transcripts.len();
+            if !transcription.failed_chunks.is_empty() {
+                result.error = Some(format!(
+                    "{} chunks could not be transcribed",
+                    transcription.failed_chunks.len()
+                ));
+            }
+        }
+        Ok(None) => result.error = Some("Cancelled".to_string()),
+        Err(e) => result.error = Some(e),
+    }
 
+    let updates = crate::database::RecordingUpdate {
+        status: Some(if result.success { "completed" } else { "failed" }.to_string()),
+        completed_at: result.success.then(|| chrono::Utc::now().to_rfc3339()),
+        ..Default::default()
+    };
+    let state = app.state::<AppState>();
+    if let Err(e) = state.db().await.update_recording(&recording.id, &updates) {
+        warn!("Failed to update status of imported recording {}: {}", recording.id, e);
+    }
+    result
+}
+
+/// Import a list of audio files as new recordings and transcribe them one at
+/// a time. Each file gets the usual retranscription events under its new
+/// recording id, plus `batch-transcription-progress` when it finishes.
+/// Failures are collected in the summary rather than stopping the batch.
+#[tauri::command]
+pub async fn transcribe_files_batch<R: Runtime>(
+    app: AppHandle<R>,
+    paths: Vec<String>,
+    model: Option<String>,
+    language: Option<String>,
+    enable_diarization: Option<bool>,
+    diarization_provider: Option<String>,
+) -> Result<BatchTranscriptionSummary, String> {
+    if paths.is_empty() {
+        return Err("No files to transcribe".to_string());
+    }
+    // Without an explicit provider, use the one chosen in settings
+    let diarization_provider = match diarization_provider {
+        Some(provider) => Some(provider),
+        None => {
+            let state = app.state::<AppState>();
+            let db = state.db().await;
+            db.get_setting("diarization_provider").ok().flatten().filter(|p| !p.is_empty())
+        }
+    };
+    let options = RetranscriptionOptions {
+        model_name: model,
+        language,
+        enable_diarization,
+        diarization_provider,
+        ..Default::default()
+    };
+
+    let total = paths.len();
+    info!("Batch transcribing {} files", total);
+    let mut files = Vec::with_capacity(total);
+    let generation = BATCH_GENERATION.load(Ordering::SeqCst);
+
+    for (index, path) in paths.iter().enumerate() {
+        if BATCH_GENERATION.load(Ordering::SeqCst) != generation {
+            info!("Batch transcription cancelled, skipping {} remaining files", total - index);
+            files.extend(paths[index..].iter().map(|path| BatchFileResult {
+                path: path.clone(),
+                success: false,
+                recording_id: None,
+                segment_count: 0,
+                error: Some("Cancelled".to_string()),
+            }));
+            break;
+        }
+
+        let _ = app.emit("batch-transcription-progress", serde_json::json!({
+            "current": index + 1,
+            "total": total,
+            "path": path,
+            "status": "processing",
+        }));
+
+        let result = transcribe_batch_file(&app, path, &options).await;
+        if result.success {
+            info!("Batch file {}/{} transcribed: {}", index + 1, total, path);
+        } else {
+            warn!("Batch file {}/{} failed: {}: {:?}", index + 1, total, path, result.error);
+        }
+
+        let _ = app.emit("batch-transcription-progress", serde_json::json!({
+            "current": index + 1,
+            "total": total,
+            "path": path,
+            "status": if result.success { "completed" } else { "failed" },
+            "recordingId": result.recording_id,
+            "error": result.error,
+        }));
+        files.push(result);
+    }
+
+    let succeeded = files.iter().filter(|f| f.success).count();
+    info!("Batch transcription finished: {} succeeded, {} failed", succeeded, total - succeeded);
+    Ok(BatchTranscriptionSummary {
+        total,
+        succeeded,
+        failed: total - succeeded,
+        files,
+    })
+}
+
+// ============ Retranscription Queue ============
+// Jobs are persisted in the `retranscription_queue` table and processed one
+// at a time by a background worker using `run_retranscription`.
+
+/// Set while the queue worker task is running
+static QUEUE_WORKER_RUNNING: AtomicBool = AtomicBool::new(false);
+
+fn emit_queue_updated<R: Runtime>(app: &AppHandle<R>) {
+    if let Err(e) = app.emit("retranscription-queue-updated", ()) {
+        warn!("Failed to emit retranscription queue update: {}", e);
+    }
+}
+
+/// Start the queue worker if it isn't already running
+fn ensure_queue_worker<R: Runtime>(app: &AppHandle<R>) {
+    if QUEUE_WORKER_RUNNING.swap(true, Ordering::SeqCst) {
+        return;
+    }
+
+    let app = app.clone();
+    tauri::async_runtime::spawn(async move {
+        loop {
+            let next = {
+                let state = app.state::<AppState>();
+                let db = state.db().await;
+                db.next_pending_retranscription_job()
+            };
+
+            match next {
+                Ok(Some(job)) => process_queued_job(&app, job).await,
+                Ok(None) => {
+                    QUEUE_WORKER_RUNNING.store(false, Ordering::SeqCst);
+                    // A job may have been enqueued between the check and clearing the flag
+                    let state = app.state::<AppState>();
+                    let has_pending = matches!(state.db().await.next_pending_retranscription_job(), Ok(Some(_)));
+                    if has_pending && !QUEUE_WORKER_RUNNING.swap(true, Ordering::SeqCst) {
+                        continue;
+                    }
+                    info!("Retranscription queue is empty");
+                    break;
+                }
+                Err(e) => {
+                    error!("Failed to read retranscription queue: {}", e);
+                    QUEUE_WORKER_RUNNING.store(false, Ordering::SeqCst);
+                    break;
+                }
+            }
+        }
+    });
+}
+
+/// Run one queued job. Its transcript is saved by `run_retranscription`; a
+/// failed job stays in the queue marked "failed" with its error until cleared.
+async fn process_queued_job<R: Runtime>(app: &AppHandle<R>, job: RetranscriptionJob) {
+    info!("Processing queued retranscription job {} for recording {}", job.id, job.recording_id);
+
+    let state = app.state::<AppState>();
+    let recording = {
+        let db = state.db().await;
+        if let Err(e) = db.set_retranscription_job_status(&job.id, "running", None) {
+            error!("Failed to mark retranscription job {} running: {}", job.id, e);
+        }
+        db.get_recording(&job.recording_id)
+    };
+    emit_queue_updated(app);
+
+    let outcome = match recording {
+        Ok(Some(recording)) => match recording.audio_file_path {
+            Some(audio_path) => {
+                let options: RetranscriptionOptions = serde_json::from_str(&job.options).unwrap_or_default();
+                run_retranscription(app, job.recording_id.clone(), audio_path, options).await
+            }
+            None => Err("Recording has no audio file".to_string()),
+        },
+        Ok(None) => Err("Recording not found".to_string()),
+        Err(e) => Err(e.to_string()),
+    };
+
+    let db = state.db().await;
+    match outcome {
+        Ok(_) => {
+            // Saved by run_retranscription, or cancelled while running
+            let _ = db.complete_retranscription_job(&job.id);
+        }
+        Err(e) => {
+            warn!("Queued retranscription job {} failed: {}", job.id, e);
+            let _ = db.set_retranscription_job_status(&job.id, "failed", Some(&e));
+        }
+    }
+    drop(db);
+    emit_queue_updated(app);
+}
+
+/// Replace the recording's transcript and speaker embeddings with the
+/// transcription and record the model and diarization provider used (None
+/// clears the provider), all in one database transaction
+pub(crate) fn save_retranscription_result(
+    db: &crate::database::DatabaseManager,
+    transcription: &Transcription,
+    diarization_provider: Option<&str>,
+) -> Result<()> {
+    let result = &transcription.result;
+    if !result.success || result.transcripts.is_empty() {
+        return Ok(());
+    }
+
+    let timestamp = chrono::Utc::now().timestamp_millis();
+    let segments: Vec<crate::database::TranscriptSegment> = result
+        .transcripts
+        .iter()
+        .enumerate()
+        .map(|(idx, t)| {
+            let start = t.audio_start_time.max(0.0) as u64;
+            crate::database::TranscriptSegment {
+                id: format!("retrans-{}-{}-{}", result.recording_id, idx, timestamp),
+                recording_id: result.recording_id.clone(),
+                text: t.text.clone(),
+                audio_start_time: t.audio_start_time,
+                audio_end_time: t.audio_end_time,
+                duration: t.audio_end_time - t.audio_start_time,
+                display_time: format!("[{:02}:{:02}]", start / 60, start % 60),
+                confidence: t.confidence,
+                sequence_id: t.sequence_id as i64,
+                speaker_id: t.speaker_id.clone(),
+                speaker_label: t.speaker_label.clone(),
+                is_registered_speaker: t.is_registered_speaker,
+                low_confidence: t.low_confidence,
+            }
+        })
+        .collect();
+
+    // Embeddings of the previous run don't match the new speakers, so they are cleared when none are produced
+    let embeddings = transcription.speaker_embeddings.as_deref().unwrap_or_default();
+    db.save_retranscription(&result.recording_id, &segments, embeddings, &crate::database::RecordingUpdate {
+        transcription_model: Some(result.model_used.clone()),
+        // An empty string clears the provider
+        diarization_provider: Some(diarization_provider.unwrap_or_default().to_string()),
+        ..Default::default()
+    })?;
+    info!("Saved {} segments and {} speaker embeddings for recording {}",
+          segments.len(), embeddings.len(), result.recording_id);
+    if let Err(e) = crate::transcription_quality::update_transcription_quality(db, &result.recording_id) {
+        warn!("Failed to score transcription quality of {}: {}", result.recording_id, e);
+    }
+    Ok(())
+}
+
+/// Resume the persisted queue on startup (jobs interrupted by a restart run again)
+pub async fn resume_retranscription_queue<R: Runtime>(app: &AppHandle<R>) {
+    let pending = {
+        let state = app.state::<AppState>();
+        let db = state.db().await;
+        if let Err(e) = db.reset_running_retranscription_jobs() {
+            warn!("Failed to reset interrupted retranscription jobs: {}", e);
+        }
+        db.next_pending_retranscription_job()
+    };
+
+    if let Ok(Some(_)) = pending {
+        info!("Resuming retranscription queue");
+        ensure_queue_worker(app);
+    }
+}
+
+/// Remove every pending job from the queue; returns how many were removed.
+/// The running job is left to `cancel_all_retranscriptions`.
+pub(crate) fn clear_pending_retranscription_jobs<R: Runtime>(
+    app: &AppHandle<R>,
+    db: &crate::database::DatabaseManager,
+) -> Result<usize> {
+    let mut removed = 0;
+    for job in db.get_retranscription_queue()? {
+        if job.status == "pending" && db.delete_retranscription_job(&job.id)? {
+            removed += 1;
+        }
+    }
+    if removed > 0 {
+        emit_queue_updated(app);
+    }
+    Ok(removed)
+}
+
+/// Add a recording to the retranscription queue
+#[tauri::command]
+pub async fn enqueue_retranscription<R: Runtime>(
+    app: AppHandle<R>,
+    state: tauri::State<'_, AppState>,
+    recording_id: String,
+    options: Option<RetranscriptionOptions>,
+) -> Result<String, String> {
+    let options = options.unwrap_or_default();
+    options.validate()?;
+    let options_json = serde_json::to_string(&options)
+        .map_err(|e| e.to_string())?;
+
+    let job_id = {
+        let db = state.db().await;
+        db.enqueue_retranscription_job(&recording_id, &options_json)
+            .map_err(|e| e.to_string())?
+    };
+
+    info!("Queued retranscription for recording {} (job {})", recording_id, job_id);
+    emit_queue_updated(&app);
+    ensure_queue_worker(&app);
+    Ok(job_id)
+}
+
+/// Queued jobs in order, including failed ones with their error
+#[tauri::command]
+pub async fn get_retranscription_queue(
+    state: tauri::State<'_, AppState>,
+) -> Result<Vec<RetranscriptionJob>, String> {
+    let db = state.db().await;
+    db.get_retranscription_queue().map_err(|e| e.to_string())
+}
+
+/// Reorder queued jobs; `job_ids` lists jobs in the desired order
+#[tauri::command]
+pub async fn reorder_retranscription_queue<R: Runtime>(
+    app: AppHandle<R>,
+    state: tauri::State<'_, AppState>,
+    job_ids: Vec<String>,
+) -> Result<(), String> {
+    state.db().await
+        .reorder_retranscription_queue(&job_ids)
+        .map_err(|e| e.to_string())?;
+    emit_queue_updated(&app);
+    Ok(())
+}
+
+/// Remove a pending (or failed) job from the queue.
+/// The running job is cancelled with `cancel_retranscription` instead.
+#[tauri::command]
+pub async fn cancel_queued_retranscription<R: Runtime>(
+    app: AppHandle<R>,
+    state: tauri::State<'_, AppState>,
+    job_id: String,
+) -> Result<(), String> {
+    let removed = state.db().await
+        .delete_retranscription_job(&job_id)
+        .map_err(|e| e.to_string())?;
+    if !removed {
+        return Err("Job is running or no longer in the queue".to_string());
+    }
+    emit_queue_updated(&app);
     Ok(())
 }
 
+/// Remove every failed job from the queue; returns how many were removed
+#[tauri::command]
+pub async fn clear_failed_retranscription_jobs<R: Runtime>(
+    app: AppHandle<R>,
+    state: tauri::State<'_, AppState>,
+) -> Result<usize, String> {
+    let removed = state.db().await
+        .delete_failed_retranscription_jobs()
+        .map_err(|e| e.to_string())?;
+    if removed > 0 {
+        emit_queue_updated(&app);
+    }
+    Ok(removed)
+}
+
 /// Get status of a retranscription job (placeholder for future job tracking)
 #[tauri::command]
 pub async fn get_retranscription_status(
@@ -920,4 +2271,66 @@ mod tests {
         assert_eq!(chunks[0].start_time_ms, 0.0);
         assert_eq!(chunks[4].start_time_ms, 4000.0);
     }
+
+    #[test]
+    fn test_flag_low_confidence_keeps_text() {
+        let mut transcripts = vec![
+            TranscriptSegment::test_segment("clear", 0.9),
+            TranscriptSegment::test_segment("mumbled", 0.3),
+        ];
+
+        assert_eq!(flag_low_confidence(&mut transcripts, 0.5), 1);
+        assert_eq!(transcripts.len(), 2);
+        assert!(!transcripts[0].low_confidence);
+        assert!(transcripts[1].low_confidence);
+        assert_eq!(transcripts[1].text, "mumbled");
+    }
+
+    /// One speaker's turns at 0-2s and 3.5-5s, 1.5s apart
+    fn turns_with_gap() -> (Vec<TranscriptSegment>, Vec<crate::diarization::SpeakerSegment>) {
+        let transcripts = vec![
+            TranscriptSegment {
+                audio_start_time: 0.0,
+                audio_end_time: 2.0,
+                ..TranscriptSegment::test_segment("first", 0.9)
+            },
+            TranscriptSegment {
+                audio_start_time: 3.5,
+                audio_end_time: 5.0,
+                sequence_id: 1,
+                ..TranscriptSegment::test_segment("second", 0.9)
+            },
+        ];
+        let speakers = vec![crate::diarization::SpeakerSegment {
+            start_time: 0.0,
+            end_time: 5.0,
+            speaker_id: "speaker_0".to_string(),
+            speaker_label: "Speaker 1".to_string(),
+            confidence: 1.0,
+            is_registered: false,
+            registered_speaker_id: None,
+        }];
+        (transcripts, speakers)
+    }
+
+    #[test]
+    fn test_assign_and_merge_speakers_merges_within_default_gap() {
+        let (transcripts, speakers) = turns_with_gap();
+        let merged = assign_and_merge_speakers(transcripts, &speakers, DEFAULT_SPEAKER_MERGE_GAP_SECONDS);
+
+        assert_eq!(merged.len(), 1);
+        assert_eq!(merged[0].text, "first second");
+        assert_eq!((merged[0].audio_start_time, merged[0].audio_end_time), (0.0, 5.0));
+        assert_eq!(merged[0].speaker_label.as_deref(), Some("Speaker 1"));
+    }
+
+    #[test]
+    fn test_assign_and_merge_speakers_keeps_turns_beyond_gap() {
+        let (transcripts, speakers) = turns_with_gap();
+        let merged = assign_and_merge_speakers(transcripts, &speakers, 1.0);
+
+        assert_eq!(merged.len(), 2);
+        assert!(merged.iter().all(|s| s.speaker_id.as_deref() == Some("speaker_0")));
+        assert_eq!(merged.iter().map(|s| s.sequence_id).collect::<Vec<_>>(), vec![0, 1]);
+    }
 }
@@ -5,7 +5,7 @@ use cpal::{Device, Stream, SupportedStreamConfig};
 use log::{error, info, warn};
 use tokio::sync::mpsc;
 
-use super::devices::{AudioDevice, get_device_and_config};
+use super::devices::{AudioDevice, get_device_and_config, select_stream_config};
 use super::pipeline::AudioCapture;
 use super::recording_state::{RecordingState, DeviceType};
 use super::capture::{AudioCaptureBackend, get_current_backend};
@@ -113,6 +113,7 @@ impl AudioStream {
 
         // Get the underlying cpal device and config
         let (cpal_device, config) = get_device_and_config(&device).await?;
+        let config = select_stream_config(&cpal_device, &device.name, config);
 
         info!("Audio config - Sample rate: {}, Channels: {}, Format: {:?}",
               config.sample_rate().0, config.channels(), config.sample_format());
@@ -454,6 +455,11 @@ impl AudioStreamManager {
         count
     }
 
+    /// Check if the system audio stream is running
+    pub fn has_system_stream(&self) -> bool {
+        self.system_stream.is_some()
+    }
+
     /// Check if any streams are active
     pub fn has_active_streams(&self) -> bool {
         self.microphone_stream.is_some() || self.system_stream.is_some()
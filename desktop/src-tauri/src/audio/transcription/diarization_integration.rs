@@ -1,14 +1,169 @@
 // audio/transcription/diarization_integration.rs
 //
 // Live diarization support for transcription worker.
+//
+// Live chunks are diarized one at a time, so speakers are clustered online:
+// each speech segment's embedding is compared against the running centroid of
+// every speaker seen so far in the session and joins the closest one, or starts
+// a new speaker. IDs are handed out in order of first appearance and never
+// reassigned, so a speaker keeps the same label for the whole recording.
+
+use std::collections::HashMap;
+use std::sync::Mutex;
 
-use crate::diarization::{DIARIZATION_ENGINE, SpeakerSegment};
-use log::debug;
+use crate::diarization::speaker_db::cosine_similarity;
+use crate::diarization::{SegmentEmbedding, DIARIZATION_ENGINE};
+use log::{debug, info};
 
 use super::globals::is_live_diarization_enabled;
 
+/// Segments shorter than this give noisy embeddings; they can join an
+/// existing speaker but never start a new one
+const MIN_NEW_SPEAKER_SECONDS: f64 = 1.0;
+
+/// A speaker found during the live session
+#[derive(Debug, Clone)]
+struct LiveSpeaker {
+    speaker_id: String,
+    speaker_label: String,
+    /// Sum of the speaker's segment embeddings (cosine ignores the scale)
+    centroid: Vec<f32>,
+    is_registered: bool,
+}
+
+/// Online speaker clustering with stable IDs for one recording session
+#[derive(Debug, Default)]
+pub struct OnlineSpeakerClusterer {
+    speakers: Vec<LiveSpeaker>,
+    /// Session speakers created so far, used for "Speaker N" numbering
+    anonymous_count: usize,
+}
+
+impl OnlineSpeakerClusterer {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Index of the speaker most similar to `embedding`, with the similarity
+    fn closest(&self, embedding: &[f32]) -> Option<(usize, f32)> {
+        self.speakers
+            .iter()
+            .enumerate()
+            .map(|(index, speaker)| (index, cosine_similarity(embedding, &speaker.centroid)))
+            .max_by(|a, b| a.1.total_cmp(&b.1))
+    }
+
+    fn add_to_centroid(&mut self, index: usize, embedding: &[f32]) {
+        let centroid = &mut self.speakers[index].centroid;
+        if centroid.len() == embedding.len() {
+            for (sum, value) in centroid.iter_mut().zip(embedding) {
+                *sum += value;
+            }
+        }
+    }
+
+    /// Assign a segment to a speaker, creating one if no existing speaker is
+    /// within `threshold` and there is room. `registered` is the matching
+    /// registered voice (id, name), if any. Returns the speaker's index.
+    pub fn assign(
+        &mut self,
+        embedding: &[f32],
+        duration: f64,
+        threshold: f32,
+        max_speakers: usize,
+        registered: Option<(String, String)>,
+    ) -> Option<usize> {
+        if let Some((registered_id, name)) = registered {
+            let speaker_id = format!("registered_{}", registered_id);
+            let index = match self.speakers.iter().position(|s| s.speaker_id == speaker_id) {
+                Some(index) => index,
+                None => {
+                    self.speakers.push(LiveSpeaker {
+                        speaker_id,
+                        speaker_label: name,
+                        centroid: vec![0.0; embedding.len()],
+                        is_registered: true,
+                    });
+                    self.speakers.len() - 1
+                }
+            };
+            self.add_to_centroid(index, embedding);
+            return Some(index);
+        }
+
+        let closest = self.closest(embedding);
+        if let Some((index, similarity)) = closest {
+            if similarity >= threshold {
+                self.add_to_centroid(index, embedding);
+                return Some(index);
+            }
+        }
+
+        let anonymous = self.speakers.iter().filter(|s| !s.is_registered).count();
+        if duration >= MIN_NEW_SPEAKER_SECONDS && anonymous < max_speakers.max(1) {
+            let speaker_index = self.anonymous_count;
+            self.anonymous_count += 1;
+            self.speakers.push(LiveSpeaker {
+                speaker_id: format!("speaker_{}", speaker_index),
+                speaker_label: format!("Speaker {}", speaker_index + 1),
+                centroid: embedding.to_vec(),
+                is_registered: false,
+            });
+            return Some(self.speakers.len() - 1);
+        }
+
+        // Too short to trust or no room left: go with the closest speaker without
+        // moving its centroid
+        closest.map(|(index, _)| index)
+    }
+
+    /// Speaker ID, label and registered flag of the speaker at `index`
+    pub fn speaker(&self, index: usize) -> Option<(String, String, bool)> {
+        self.speakers
+            .get(index)
+            .map(|s| (s.speaker_id.clone(), s.speaker_label.clone(), s.is_registered))
+    }
+
+    /// Speaker talking the longest within `start_time..end_time`, assigning each
+    /// segment in order
+    pub fn assign_dominant(
+        &mut self,
+        segments: &[(SegmentEmbedding, Option<(String, String)>)],
+        start_time: f64,
+        end_time: f64,
+        threshold: f32,
+        max_speakers: usize,
+    ) -> Option<usize> {
+        let mut talk_time: HashMap<usize, f64> = HashMap::new();
+        for (segment, registered) in segments {
+            let overlap = (end_time.min(segment.end_time) - start_time.max(segment.start_time)).max(0.0);
+            if overlap <= 0.0 {
+                continue;
+            }
+            let duration = segment.end_time - segment.start_time;
+            if let Some(index) = self.assign(&segment.embedding, duration, threshold, max_speakers, registered.clone()) {
+                *talk_time.entry(index).or_default() += overlap;
+            }
+        }
+
+        talk_time
+            .into_iter()
+            .max_by(|a, b| a.1.total_cmp(&b.1).then(b.0.cmp(&a.0)))
+            .map(|(index, _)| index)
+    }
+}
+
+/// Speakers of the current recording session
+static LIVE_SPEAKERS: Mutex<Option<OnlineSpeakerClusterer>> = Mutex::new(None);
+
+/// Forget the live speakers for a new recording session
+pub fn reset_live_diarization() {
+    if let Ok(mut speakers) = LIVE_SPEAKERS.lock() {
+        *speakers = None;
+    }
+}
+
 /// Run diarization on audio samples and return speaker info for the given time range
-#[allow(dead_code)]
 pub async fn get_speaker_for_segment(
     samples: &[f32],
     sample_rate: u32,
@@ -21,51 +176,95 @@ pub async fn get_speaker_for_segment(
 
     // Try to get diarization engine
     let mut guard = DIARIZATION_ENGINE.write().await;
-    let engine = match guard.as_mut() {
-        Some(e) => e,
-        None => return None,
-    };
+    let engine = guard.as_mut()?;
 
-    // Run diarization on this segment
-    match engine.diarize(samples, sample_rate) {
-        Ok(segments) => {
-            // Find the best matching speaker segment by time overlap
-            let best_segment = find_best_speaker_segment(&segments, start_time, end_time);
-            best_segment.map(|seg| (
-                seg.speaker_id.clone(),
-                seg.speaker_label.clone(),
-                seg.is_registered,
-            ))
-        }
+    // Embed this chunk's speech segments; the engine's own session state is
+    // left alone so retranscription can't disturb the live speakers
+    let segments = match engine.extract_segment_embeddings(samples, sample_rate) {
+        Ok(segments) => segments,
         Err(e) => {
             debug!("Diarization failed for segment: {}", e);
-            None
+            return None;
         }
-    }
+    };
+    let threshold = engine.similarity_threshold();
+    let max_speakers = engine.max_speakers();
+    let segments: Vec<(SegmentEmbedding, Option<(String, String)>)> = segments
+        .into_iter()
+        .map(|segment| {
+            let registered = engine
+                .match_registered_speaker(&segment.embedding)
+                .ok()
+                .flatten()
+                .map(|(id, name, _)| (id, name));
+            (segment, registered)
+        })
+        .collect();
+    drop(guard);
+
+    let mut live = LIVE_SPEAKERS.lock().ok()?;
+    let clusterer = live.get_or_insert_with(|| {
+        info!("Starting live diarization session");
+        OnlineSpeakerClusterer::new()
+    });
+    let index = clusterer.assign_dominant(&segments, start_time, end_time, threshold, max_speakers)?;
+    clusterer.speaker(index)
 }
 
-/// Find the speaker segment with the most overlap with the given time range
-pub fn find_best_speaker_segment(
-    segments: &[SpeakerSegment],
-    start_time: f64,
-    end_time: f64,
-) -> Option<&SpeakerSegment> {
-    if segments.is_empty() {
-        return None;
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn segment(start: f64, end: f64, embedding: &[f32]) -> (SegmentEmbedding, Option<(String, String)>) {
+        (SegmentEmbedding { start_time: start, end_time: end, embedding: embedding.to_vec() }, None)
     }
 
-    // Find segment with maximum overlap
-    segments.iter()
-        .filter_map(|seg| {
-            let overlap_start = start_time.max(seg.start_time);
-            let overlap_end = end_time.min(seg.end_time);
-            let overlap = (overlap_end - overlap_start).max(0.0);
-            if overlap > 0.0 {
-                Some((seg, overlap))
-            } else {
-                None
-            }
-        })
-        .max_by(|a, b| a.1.partial_cmp(&b.1).unwrap_or(std::cmp::Ordering::Equal))
-        .map(|(seg, _)| seg)
+    #[test]
+    fn test_speaker_ids_are_stable() {
+        let mut clusterer = OnlineSpeakerClusterer::new();
+        let alice = [1.0, 0.0, 0.0];
+        let bob = [0.0, 1.0, 0.0];
+
+        assert_eq!(clusterer.assign(&alice, 2.0, 0.8, 4, None), Some(0));
+        assert_eq!(clusterer.assign(&bob, 2.0, 0.8, 4, None), Some(1));
+        // A slightly different take of the first voice keeps its ID
+        assert_eq!(clusterer.assign(&[0.9, 0.1, 0.0], 2.0, 0.8, 4, None), Some(0));
+        assert_eq!(clusterer.assign(&bob, 2.0, 0.8, 4, None), Some(1));
+        assert_eq!(clusterer.speaker(0).unwrap().1, "Speaker 1");
+        assert_eq!(clusterer.speaker(1).unwrap().1, "Speaker 2");
+    }
+
+    #[test]
+    fn test_short_or_overflow_segments_join_closest() {
+        let mut clusterer = OnlineSpeakerClusterer::new();
+        assert_eq!(clusterer.assign(&[1.0, 0.0], 2.0, 0.8, 1, None), Some(0));
+        // Too short to start a new speaker
+        assert_eq!(clusterer.assign(&[0.0, 1.0], 0.4, 0.8, 4, None), Some(0));
+        // Max speakers reached
+        assert_eq!(clusterer.assign(&[0.0, 1.0], 2.0, 0.8, 1, None), Some(0));
+    }
+
+    #[test]
+    fn test_registered_speaker() {
+        let mut clusterer = OnlineSpeakerClusterer::new();
+        let registered = Some(("abc".to_string(), "Alice".to_string()));
+        let index = clusterer.assign(&[1.0, 0.0], 2.0, 0.8, 4, registered.clone()).unwrap();
+        assert_eq!(clusterer.speaker(index), Some(("registered_abc".to_string(), "Alice".to_string(), true)));
+        assert_eq!(clusterer.assign(&[1.0, 0.0], 2.0, 0.8, 4, registered), Some(index));
+        // Anonymous numbering is unaffected by registered speakers
+        let other = clusterer.assign(&[0.0, 1.0], 2.0, 0.8, 4, None).unwrap();
+        assert_eq!(clusterer.speaker(other).unwrap().1, "Speaker 1");
+    }
+
+    #[test]
+    fn test_assign_dominant() {
+        let mut clusterer = OnlineSpeakerClusterer::new();
+        let segments = vec![
+            segment(0.0, 1.5, &[1.0, 0.0]),
+            segment(1.5, 5.0, &[0.0, 1.0]),
+            segment(6.0, 9.0, &[1.0, 0.0]),
+        ];
+        assert_eq!(clusterer.assign_dominant(&segments, 0.0, 5.0, 0.8, 4), Some(1));
+        assert!(clusterer.assign_dominant(&[], 0.0, 5.0, 0.8, 4).is_none());
+    }
 }
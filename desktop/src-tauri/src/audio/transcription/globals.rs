@@ -3,7 +3,11 @@
 // Global state for transcription: counters, flags, and settings.
 
 use log::info;
+use once_cell::sync::Lazy;
 use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::RwLock;
+
+use crate::audio::recording_state::DeviceType;
 
 /// Sequence counter for transcript updates (monotonically increasing)
 pub static SEQUENCE_COUNTER: AtomicU64 = AtomicU64::new(0);
@@ -14,7 +18,12 @@ pub static SPEECH_DETECTED_EMITTED: AtomicBool = AtomicBool::new(false);
 /// Live diarization enabled flag - controlled via settings
 pub static LIVE_DIARIZATION_ENABLED: AtomicBool = AtomicBool::new(false);
 
-/// Enable or disable live speaker diarization
+/// Enable or disable live speaker diarization.
+///
+/// Each transcribed chunk is also run through the diarization models
+/// (segmentation plus a speaker embedding), which roughly doubles the CPU
+/// time spent per chunk during recording. Keep it off on slower machines;
+/// speakers can still be identified after the meeting by retranscription.
 pub fn set_live_diarization_enabled(enabled: bool) {
     LIVE_DIARIZATION_ENABLED.store(enabled, Ordering::SeqCst);
     info!("Live diarization {}", if enabled { "enabled" } else { "disabled" });
@@ -25,6 +34,67 @@ pub fn is_live_diarization_enabled() -> bool {
     LIVE_DIARIZATION_ENABLED.load(Ordering::SeqCst)
 }
 
+/// Default speaker label for microphone speech
+pub const DEFAULT_MIC_SPEAKER_LABEL: &str = "You";
+
+/// Default speaker label for system audio speech
+pub const DEFAULT_SYSTEM_SPEAKER_LABEL: &str = "Remote";
+
+/// Label live transcripts by audio source flag - controlled via settings
+pub static SOURCE_SPEAKER_LABELS_ENABLED: AtomicBool = AtomicBool::new(false);
+
+/// Speaker labels for (microphone, system audio)
+static SOURCE_SPEAKER_LABELS: Lazy<RwLock<(String, String)>> = Lazy::new(|| {
+    RwLock::new((DEFAULT_MIC_SPEAKER_LABEL.to_string(), DEFAULT_SYSTEM_SPEAKER_LABEL.to_string()))
+});
+
+/// Enable or disable labelling live transcript segments by audio source
+pub fn set_source_speaker_labels_enabled(enabled: bool) {
+    SOURCE_SPEAKER_LABELS_ENABLED.store(enabled, Ordering::SeqCst);
+    info!("Source speaker labels {}", if enabled { "enabled" } else { "disabled" });
+}
+
+/// Check if live transcript segments are labelled by audio source
+pub fn is_source_speaker_labels_enabled() -> bool {
+    SOURCE_SPEAKER_LABELS_ENABLED.load(Ordering::SeqCst)
+}
+
+/// Set the speaker labels for microphone and system audio; empty labels fall back to the defaults
+pub fn set_source_speaker_labels(mic_label: &str, system_label: &str) {
+    let label = |value: &str, default: &str| {
+        let value = value.trim();
+        if value.is_empty() { default.to_string() } else { value.to_string() }
+    };
+    let labels = (
+        label(mic_label, DEFAULT_MIC_SPEAKER_LABEL),
+        label(system_label, DEFAULT_SYSTEM_SPEAKER_LABEL),
+    );
+    info!("Source speaker labels set to mic='{}', system='{}'", labels.0, labels.1);
+    if let Ok(mut guard) = SOURCE_SPEAKER_LABELS.write() {
+        *guard = labels;
+    }
+}
+
+/// Get the speaker labels for (microphone, system audio)
+pub fn get_source_speaker_labels() -> (String, String) {
+    SOURCE_SPEAKER_LABELS
+        .read()
+        .map(|guard| guard.clone())
+        .unwrap_or_else(|_| (DEFAULT_MIC_SPEAKER_LABEL.to_string(), DEFAULT_SYSTEM_SPEAKER_LABEL.to_string()))
+}
+
+/// Speaker id and label for speech from `source`, None when labelling by source is disabled
+pub fn speaker_for_source(source: &DeviceType) -> Option<(String, String)> {
+    if !is_source_speaker_labels_enabled() {
+        return None;
+    }
+    let (mic_label, system_label) = get_source_speaker_labels();
+    Some(match source {
+        DeviceType::Microphone => ("source_mic".to_string(), mic_label),
+        DeviceType::System => ("source_system".to_string(), system_label),
+    })
+}
+
 /// Reset the speech detected flag for a new recording session
 pub fn reset_speech_detected_flag() {
     SPEECH_DETECTED_EMITTED.store(false, Ordering::SeqCst);
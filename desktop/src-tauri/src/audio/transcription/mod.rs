@@ -11,6 +11,7 @@
 // - types.rs: TranscriptUpdate struct, formatting utilities
 // - diarization_integration.rs: Live speaker diarization support
 // - transcriber.rs: Provider-agnostic chunk transcription
+// - rolling_context.rs: Previous chunk text as Whisper prompt, overlap de-duplication
 // - worker.rs: Parallel worker pool and main task loop
 
 pub mod provider;
@@ -21,6 +22,7 @@ pub mod globals;
 pub mod types;
 pub mod diarization_integration;
 pub mod transcriber;
+pub mod rolling_context;
 pub mod worker;
 
 // Re-export commonly used types
@@ -46,3 +48,19 @@ pub use types::TranscriptUpdate;
 
 // Re-export diarization check (for backwards compatibility)
 pub use globals::is_live_diarization_enabled;
+pub use diarization_integration::reset_live_diarization;
+
+// Re-export rolling context settings
+pub use rolling_context::{
+    is_rolling_context_enabled,
+    reset_rolling_context,
+    set_rolling_context_enabled,
+};
+
+// Re-export source speaker label settings
+pub use globals::{
+    get_source_speaker_labels,
+    is_source_speaker_labels_enabled,
+    set_source_speaker_labels,
+    set_source_speaker_labels_enabled,
+};
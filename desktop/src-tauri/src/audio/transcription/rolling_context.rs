@@ -0,0 +1,120 @@
+// audio/transcription/rolling_context.rs
+//
+// Rolling context between live transcription chunks. When enabled, the tail
+// of the previous chunk's text is given to Whisper as the initial prompt, so
+// words cut at a chunk boundary are recognised in context. Whisper sometimes
+// repeats the end of the prompt, so the repeated words are stripped from the
+// start of the new chunk's text before it is emitted.
+
+use log::info;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Mutex;
+
+/// Words of previous text kept as context for the next chunk
+const CONTEXT_WORDS: usize = 32;
+
+/// Shortest repeat that is stripped; a single shared word is usually a real repeat
+const MIN_REPEATED_WORDS: usize = 2;
+
+/// Rolling context enabled flag - controlled via settings
+pub static ROLLING_CONTEXT_ENABLED: AtomicBool = AtomicBool::new(false);
+
+/// Tail of the text transcribed so far in this recording session
+static PREVIOUS_TEXT: Mutex<String> = Mutex::new(String::new());
+
+/// Enable or disable prompting each live chunk with the previous chunk's text
+pub fn set_rolling_context_enabled(enabled: bool) {
+    ROLLING_CONTEXT_ENABLED.store(enabled, Ordering::SeqCst);
+    info!("Live rolling context {}", if enabled { "enabled" } else { "disabled" });
+}
+
+/// Check if live chunks are prompted with the previous chunk's text
+pub fn is_rolling_context_enabled() -> bool {
+    ROLLING_CONTEXT_ENABLED.load(Ordering::SeqCst)
+}
+
+/// Forget the previous text for a new recording session
+pub fn reset_rolling_context() {
+    if let Ok(mut previous) = PREVIOUS_TEXT.lock() {
+        previous.clear();
+    }
+}
+
+/// Last `count` words of `text`
+fn tail_words(text: &str, count: usize) -> String {
+    let words: Vec<&str> = text.split_whitespace().collect();
+    words[words.len().saturating_sub(count)..].join(" ")
+}
+
+/// Word compared case- and punctuation-insensitively
+fn normalize_word(word: &str) -> String {
+    word.chars()
+        .filter(|c| c.is_alphanumeric())
+        .flat_map(char::to_lowercase)
+        .collect()
+}
+
+/// Remove the longest run of words at the start of `text` that repeats the end of `previous`
+pub fn strip_repeated_prefix(previous: &str, text: &str) -> String {
+    let previous: Vec<String> = previous.split_whitespace().map(normalize_word).collect();
+    let words: Vec<&str> = text.split_whitespace().collect();
+    let normalized: Vec<String> = words.iter().map(|w| normalize_word(w)).collect();
+
+    let longest = previous.len().min(words.len()).min(CONTEXT_WORDS);
+    let repeated = (MIN_REPEATED_WORDS..=longest)
+        .rev()
+        .find(|&count| previous[previous.len() - count..] == normalized[..count])
+        .unwrap_or(0);
+
+    words[repeated..].join(" ")
+}
+
+/// Initial prompt for the next chunk, if rolling context is enabled and there is previous text
+pub fn context_prompt() -> Option<String> {
+    if !is_rolling_context_enabled() {
+        return None;
+    }
+    let previous = PREVIOUS_TEXT.lock().ok()?;
+    (!previous.is_empty()).then(|| previous.clone())
+}
+
+/// De-duplicate a chunk's text against the previous chunk and remember it as
+/// context for the next one; returns the text to emit
+pub fn apply_rolling_context(text: &str) -> String {
+    if !is_rolling_context_enabled() {
+        return text.to_string();
+    }
+    let Ok(mut previous) = PREVIOUS_TEXT.lock() else {
+        return text.to_string();
+    };
+
+    let text = strip_repeated_prefix(&previous, text);
+    if !text.is_empty() {
+        *previous = tail_words(&format!("{} {}", previous, text), CONTEXT_WORDS);
+    }
+    text
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_strip_repeated_prefix() {
+        let previous = "so the budget for next quarter is";
+        assert_eq!(
+            strip_repeated_prefix(previous, "Next quarter is, around two million."),
+            "around two million."
+        );
+        // A single shared word is kept
+        assert_eq!(strip_repeated_prefix(previous, "is it final?"), "is it final?");
+        assert_eq!(strip_repeated_prefix("", "hello there"), "hello there");
+        assert_eq!(strip_repeated_prefix(previous, "quarter is"), "");
+    }
+
+    #[test]
+    fn test_tail_words() {
+        assert_eq!(tail_words("one two three four", 2), "three four");
+        assert_eq!(tail_words("one two", 5), "one two");
+    }
+}
@@ -69,13 +69,15 @@ async fn transcribe_with_whisper<R: Runtime>(
 ) -> std::result::Result<(String, Option<f32>, bool), TranscriptionError> {
     // Get language preference from global state
     let language = crate::get_language_preference_internal();
+    // Tail of the previous chunk's text, when rolling context is enabled
+    let prompt = super::rolling_context::context_prompt();
 
     match whisper_engine
-        .transcribe_audio_with_confidence(speech_samples, language)
+        .transcribe_audio_with_prompt(speech_samples, language, prompt)
         .await
     {
         Ok((text, confidence, is_partial)) => {
-            let cleaned_text = text.trim().to_string();
+            let cleaned_text = super::rolling_context::apply_rolling_context(text.trim());
             if cleaned_text.is_empty() {
                 return Ok((String::new(), Some(confidence), is_partial));
             }
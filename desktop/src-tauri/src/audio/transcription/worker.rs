@@ -4,10 +4,12 @@
 
 use super::engine::TranscriptionEngine;
 use super::provider::TranscriptionError;
-use super::globals::{is_live_diarization_enabled, mark_speech_detected, next_sequence_id, SPEECH_DETECTED_EMITTED};
+use super::globals::{is_live_diarization_enabled, mark_speech_detected, next_sequence_id, speaker_for_source, SPEECH_DETECTED_EMITTED};
+use super::diarization_integration::get_speaker_for_segment;
 use super::types::{TranscriptUpdate, format_current_timestamp};
 use super::transcriber::transcribe_chunk_with_provider;
 use crate::audio::AudioChunk;
+use crate::audio::recording_state::DeviceType;
 use log::{error, info, warn};
 use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
 use std::sync::Arc;
@@ -87,6 +89,7 @@ pub fn start_transcription_task<R: Runtime>(
         let mut receiver = transcription_receiver;
         while let Some(chunk) = receiver.recv().await {
             let queued = chunks_queued.fetch_add(1, Ordering::SeqCst) + 1;
+            crate::metrics::record_transcription_queued();
             info!(
                 "📥 Dispatching chunk {} to workers (total queued: {})",
                 chunk.chunk_id, queued
@@ -228,11 +231,16 @@ async fn process_chunk<R: Runtime>(
     if !engine_clone.is_model_loaded().await {
         warn!("⚠️ Worker {}: Model unloaded, but continuing to preserve chunk {}", worker_id, chunk.chunk_id);
         chunks_completed_clone.fetch_add(1, Ordering::SeqCst);
+        crate::metrics::record_transcription_completed();
         return;
     }
 
     let chunk_timestamp = chunk.timestamp;
     let chunk_duration = chunk.data.len() as f64 / chunk.sample_rate as f64;
+    let chunk_source = chunk.device_type.clone();
+    // Keep the audio for live diarization; source labels take precedence
+    let diarization_audio = (is_live_diarization_enabled() && speaker_for_source(&chunk_source).is_none())
+        .then(|| (chunk.data.clone(), chunk.sample_rate));
 
     // Transcribe with provider-agnostic approach
     match transcribe_chunk_with_provider(engine_clone, chunk, app_clone).await {
@@ -244,6 +252,8 @@ async fn process_chunk<R: Runtime>(
                 is_partial,
                 chunk_timestamp,
                 chunk_duration,
+                &chunk_source,
+                diarization_audio,
                 engine_clone,
                 app_clone,
                 should_log_this_chunk,
@@ -267,6 +277,8 @@ async fn handle_transcription_result<R: Runtime>(
     is_partial: bool,
     chunk_timestamp: f64,
     chunk_duration: f64,
+    chunk_source: &DeviceType,
+    diarization_audio: Option<(Vec<f32>, u32)>,
     engine_clone: &TranscriptionEngine,
     app_clone: &AppHandle<R>,
     should_log_this_chunk: bool,
@@ -312,11 +324,16 @@ async fn handle_transcription_result<R: Runtime>(
         let audio_start_time = chunk_timestamp;
         let audio_end_time = chunk_timestamp + chunk_duration;
 
-        // Get speaker info if diarization is enabled
+        // Get speaker info: by audio source if enabled, otherwise from diarization
         let (speaker_id, speaker_label, is_registered_speaker) =
-            if is_live_diarization_enabled() {
-                // For now, just return None - live diarization done on full mixed audio in pipeline
-                (None, None, false)
+            if let Some((id, label)) = speaker_for_source(chunk_source) {
+                (Some(id), Some(label), false)
+            } else if let Some((samples, sample_rate)) = diarization_audio {
+                // Segment times from diarization are relative to the chunk
+                match get_speaker_for_segment(&samples, sample_rate, 0.0, chunk_duration).await {
+                    Some((id, label, registered)) => (Some(id), Some(label), registered),
+                    None => (None, None, false),
+                }
             } else {
                 (None, None, false)
             };
@@ -372,6 +389,8 @@ async fn handle_transcription_error<R: Runtime>(
             let _ = app_clone.emit("transcription-warning", e.to_string());
         }
     }
+    // The chunk left the queue whatever the outcome
+    crate::metrics::record_transcription_completed();
 }
 
 /// Emit progress update
@@ -383,6 +402,7 @@ async fn emit_progress<R: Runtime>(
     should_log_this_chunk: bool,
 ) {
     let completed = chunks_completed_clone.fetch_add(1, Ordering::SeqCst) + 1;
+    crate::metrics::record_transcription_completed();
     let queued = chunks_queued_clone.load(Ordering::SeqCst);
 
     if completed % 5 == 0 || should_log_this_chunk {
@@ -1,9 +1,197 @@
 use anyhow::{anyhow, Result};
 use silero_rs::{VadConfig, VadSession, VadTransition};
 use log::{debug, info};
+use serde::{Deserialize, Serialize};
 use std::collections::VecDeque;
+use std::sync::atomic::{AtomicU32, AtomicU8, Ordering};
 use std::time::Duration;
 
+/// How strictly the VAD rejects non-speech audio.
+/// Higher levels filter more noise but may clip quiet speech.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum VadAggressiveness {
+    Relaxed,
+    Balanced,
+    Aggressive,
+    VeryAggressive,
+    /// Use the numeric threshold set with `set_vad_settings`
+    Custom,
+}
+
+impl VadAggressiveness {
+    /// Silero positive speech threshold for the preset (None for Custom)
+    pub fn preset_threshold(self) -> Option<f32> {
+        match self {
+            Self::Relaxed => Some(0.35),
+            Self::Balanced => Some(0.50),
+            Self::Aggressive => Some(0.65),
+            Self::VeryAggressive => Some(0.80),
+            Self::Custom => None,
+        }
+    }
+
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Relaxed => "relaxed",
+            Self::Balanced => "balanced",
+            Self::Aggressive => "aggressive",
+            Self::VeryAggressive => "very_aggressive",
+            Self::Custom => "custom",
+        }
+    }
+
+    pub fn parse(value: &str) -> Option<Self> {
+        match value {
+            "relaxed" => Some(Self::Relaxed),
+            "balanced" => Some(Self::Balanced),
+            "aggressive" => Some(Self::Aggressive),
+            "very_aggressive" => Some(Self::VeryAggressive),
+            "custom" => Some(Self::Custom),
+            _ => None,
+        }
+    }
+
+    fn from_u8(value: u8) -> Self {
+        match value {
+            0 => Self::Relaxed,
+            2 => Self::Aggressive,
+            3 => Self::VeryAggressive,
+            4 => Self::Custom,
+            _ => Self::Balanced,
+        }
+    }
+
+    fn to_u8(self) -> u8 {
+        match self {
+            Self::Relaxed => 0,
+            Self::Balanced => 1,
+            Self::Aggressive => 2,
+            Self::VeryAggressive => 3,
+            Self::Custom => 4,
+        }
+    }
+}
+
+/// Allowed range for the custom positive speech threshold
+const MIN_VAD_THRESHOLD: f32 = 0.05;
+const MAX_VAD_THRESHOLD: f32 = 0.95;
+/// Hysteresis between the positive and negative thresholds (Silero default: 0.50 / 0.35)
+const VAD_THRESHOLD_HYSTERESIS: f32 = 0.15;
+
+static VAD_AGGRESSIVENESS: AtomicU8 = AtomicU8::new(1); // Balanced
+static VAD_CUSTOM_THRESHOLD: AtomicU32 = AtomicU32::new(0x3F00_0000); // 0.5f32
+
+/// Current VAD settings, as exposed to the frontend
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct VadSettings {
+    pub aggressiveness: VadAggressiveness,
+    /// Custom positive speech threshold (used when aggressiveness is Custom)
+    pub threshold: f32,
+}
+
+pub fn get_vad_aggressiveness() -> VadAggressiveness {
+    VadAggressiveness::from_u8(VAD_AGGRESSIVENESS.load(Ordering::SeqCst))
+}
+
+pub fn set_vad_aggressiveness(aggressiveness: VadAggressiveness) {
+    let previous = VadAggressiveness::from_u8(VAD_AGGRESSIVENESS.swap(aggressiveness.to_u8(), Ordering::SeqCst));
+    if previous != aggressiveness {
+        info!("🎚️ VAD aggressiveness set to {} (was {})", aggressiveness.as_str(), previous.as_str());
+    }
+}
+
+pub fn get_vad_custom_threshold() -> f32 {
+    f32::from_bits(VAD_CUSTOM_THRESHOLD.load(Ordering::SeqCst))
+}
+
+pub fn set_vad_custom_threshold(threshold: f32) {
+    let threshold = if threshold.is_finite() {
+        threshold.clamp(MIN_VAD_THRESHOLD, MAX_VAD_THRESHOLD)
+    } else {
+        0.5
+    };
+    VAD_CUSTOM_THRESHOLD.store(threshold.to_bits(), Ordering::SeqCst);
+}
+
+/// (positive, negative) Silero speech thresholds for the current settings
+pub fn current_vad_thresholds() -> (f32, f32) {
+    vad_thresholds(get_vad_aggressiveness(), get_vad_custom_threshold())
+}
+
+fn vad_thresholds(aggressiveness: VadAggressiveness, custom_threshold: f32) -> (f32, f32) {
+    let positive = aggressiveness
+        .preset_threshold()
+        .unwrap_or(custom_threshold)
+        .clamp(MIN_VAD_THRESHOLD, MAX_VAD_THRESHOLD);
+    let negative = (positive - VAD_THRESHOLD_HYSTERESIS).max(0.01);
+    (positive, negative)
+}
+
+#[tauri::command]
+pub fn get_vad_settings() -> VadSettings {
+    VadSettings {
+        aggressiveness: get_vad_aggressiveness(),
+        threshold: get_vad_custom_threshold(),
+    }
+}
+
+/// Takes effect for the next recording (the VAD is created at recording start)
+#[tauri::command]
+pub fn set_vad_settings(aggressiveness: VadAggressiveness, threshold: Option<f32>) -> Result<(), String> {
+    if let Some(threshold) = threshold {
+        set_vad_custom_threshold(threshold);
+    }
+    set_vad_aggressiveness(aggressiveness);
+    Ok(())
+}
+
+/// Allowed range for the live transcription chunk length
+pub const MIN_LIVE_CHUNK_SECONDS: u32 = 3;
+pub const MAX_LIVE_CHUNK_SECONDS: u32 = 15;
+
+/// Longest live speech segment sent for transcription, in seconds (0 = no limit).
+/// Shorter chunks show text sooner during long stretches of speech; longer
+/// chunks give Whisper more context per chunk and transcribe more accurately.
+static LIVE_CHUNK_SECONDS: AtomicU32 = AtomicU32::new(0);
+
+fn clamp_live_chunk_seconds(seconds: u32) -> u32 {
+    seconds.clamp(MIN_LIVE_CHUNK_SECONDS, MAX_LIVE_CHUNK_SECONDS)
+}
+
+pub fn live_chunk_seconds() -> Option<u32> {
+    match LIVE_CHUNK_SECONDS.load(Ordering::SeqCst) {
+        0 => None,
+        seconds => Some(seconds),
+    }
+}
+
+/// Set the live chunk length (clamped to the allowed range), or None for
+/// chunks that end only when the VAD hears a pause
+pub fn apply_live_chunk_seconds(seconds: Option<u32>) {
+    let seconds = seconds.map(clamp_live_chunk_seconds).unwrap_or(0);
+    LIVE_CHUNK_SECONDS.store(seconds, Ordering::SeqCst);
+}
+
+#[tauri::command]
+pub fn get_live_chunk_seconds() -> Option<u32> {
+    live_chunk_seconds()
+}
+
+/// Takes effect for the next recording (the VAD is created at recording start)
+#[tauri::command]
+pub fn set_live_chunk_seconds(seconds: Option<u32>) -> Result<(), String> {
+    if seconds.is_some_and(|s| !(MIN_LIVE_CHUNK_SECONDS..=MAX_LIVE_CHUNK_SECONDS).contains(&s)) {
+        return Err(format!(
+            "Live chunk length must be between {} and {} seconds",
+            MIN_LIVE_CHUNK_SECONDS, MAX_LIVE_CHUNK_SECONDS
+        ));
+    }
+    apply_live_chunk_seconds(seconds);
+    info!("🎚️ Live transcription chunk length set to {:?}s", seconds);
+    Ok(())
+}
+
 /// Represents a complete speech segment detected by VAD
 #[derive(Debug, Clone)]
 pub struct SpeechSegment {
@@ -24,6 +212,10 @@ pub struct ContinuousVadProcessor {
     in_speech: bool,
     processed_samples: usize,
     speech_start_sample: usize,
+    /// Split ongoing speech into segments of at most this many 16kHz samples
+    max_segment_samples: Option<usize>,
+    /// Start of the current segment (ms) once ongoing speech has been split
+    split_start_ms: Option<f64>,
     // State tracking for smart logging
     last_logged_state: bool,
 }
@@ -39,9 +231,11 @@ impl ContinuousVadProcessor {
 
         // CONTINUOUS SPEECH FIX: Tuned for capturing complete 5+ second utterances
         // Previous: 0.55/0.40 with 400ms redemption was fragmenting speech into 40ms segments
-        // New: More lenient thresholds + longer redemption for continuous speech
-        config.positive_speech_threshold = 0.50;  // Silero default - good for continuous speech
-        config.negative_speech_threshold = 0.35;  // Silero default - allows natural pauses
+        // Thresholds come from the VAD aggressiveness setting; "balanced" is the
+        // Silero default of 0.50/0.35, which allows natural pauses
+        let (positive_threshold, negative_threshold) = current_vad_thresholds();
+        config.positive_speech_threshold = positive_threshold;
+        config.negative_speech_threshold = negative_threshold;
 
         // CRITICAL FIX: Removed redemption_time capping to support long continuous speech
         // Previous: capped at 400ms, causing VAD to fragment 5-second speech into 40ms segments
@@ -55,8 +249,8 @@ impl ContinuousVadProcessor {
         // New: 250ms ensures segments are substantial enough for Whisper (>100ms requirement)
         config.min_speech_time = Duration::from_millis(250);  // Prevent tiny fragments
 
-        debug!("Creating VAD session with: sample_rate={}Hz, redemption={}ms, min_speech={}ms, input_rate={}Hz",
-               VAD_SAMPLE_RATE, redemption_time_ms, 250, input_sample_rate);
+        debug!("Creating VAD session with: sample_rate={}Hz, redemption={}ms, min_speech={}ms, input_rate={}Hz, thresholds={:.2}/{:.2}",
+               VAD_SAMPLE_RATE, redemption_time_ms, 250, input_sample_rate, positive_threshold, negative_threshold);
 
         let session = VadSession::new(config)
             .map_err(|e| anyhow!("Failed to create VAD session: {:?}", e))?;
@@ -77,11 +271,20 @@ impl ContinuousVadProcessor {
             in_speech: false,
             processed_samples: 0,
             speech_start_sample: 0,
+            max_segment_samples: None,
+            split_start_ms: None,
             // Initialize state tracking
             last_logged_state: false,
         })
     }
 
+    /// Cut speech longer than `seconds` into consecutive segments instead of
+    /// waiting for a pause (None keeps whole utterances together)
+    pub fn with_max_segment_seconds(mut self, seconds: Option<u32>) -> Self {
+        self.max_segment_samples = seconds.map(|s| s as usize * 16000);
+        self
+    }
+
     /// Process incoming audio samples and return any complete speech segments
     /// Handles resampling from input sample rate to 16kHz for VAD processing
     pub fn process_audio(&mut self, samples: &[f32]) -> Result<Vec<SpeechSegment>> {
@@ -179,7 +382,9 @@ impl ContinuousVadProcessor {
 
         // Force end any ongoing speech
         if self.in_speech && !self.current_speech.is_empty() {
-            let start_ms = (self.speech_start_sample as f64 / self.sample_rate as f64) * 1000.0;
+            let start_ms = self.split_start_ms.take().unwrap_or(
+                (self.speech_start_sample as f64 / self.sample_rate as f64) * 1000.0
+            );
             let end_ms = (self.processed_samples as f64 / self.sample_rate as f64) * 1000.0;
 
             let segment = SpeechSegment {
@@ -202,6 +407,24 @@ impl ContinuousVadProcessor {
         Ok(completed_segments)
     }
 
+    /// Emit the speech accumulated so far as a segment while speech continues
+    fn split_current_speech(&mut self) {
+        let start_ms = self.split_start_ms.unwrap_or_else(|| {
+            // Accumulated audio ends at the current position
+            (self.processed_samples + self.chunk_size) as f64 / 16.0 - self.current_speech.len() as f64 / 16.0
+        });
+        let end_ms = start_ms + self.current_speech.len() as f64 / 16.0;
+
+        debug!("VAD: Splitting ongoing speech at {:.1}ms ({:.1}ms segment)", end_ms, end_ms - start_ms);
+        self.speech_segments.push_back(SpeechSegment {
+            samples: std::mem::take(&mut self.current_speech),
+            start_timestamp_ms: start_ms,
+            end_timestamp_ms: end_ms,
+            confidence: 0.9,
+        });
+        self.split_start_ms = Some(end_ms);
+    }
+
     fn process_chunk(&mut self, chunk: &[f32]) -> Result<()> {
         let transitions = self.session.process(chunk)
             .map_err(|e| anyhow!("VAD processing failed: {}", e))?;
@@ -218,6 +441,7 @@ impl ContinuousVadProcessor {
                     self.in_speech = true;
                     self.speech_start_sample = self.processed_samples + (timestamp_ms * self.sample_rate as usize / 1000);
                     self.current_speech.clear();
+                    self.split_start_ms = None;
                 }
                 VadTransition::SpeechEnd { start_timestamp_ms, end_timestamp_ms, samples } => {
                     // Only log if we were previously in speech state
@@ -227,17 +451,19 @@ impl ContinuousVadProcessor {
                     }
                     self.in_speech = false;
 
-                    // Use samples from VAD transition if available, otherwise use accumulated samples
-                    let speech_samples = if !samples.is_empty() {
-                        samples
-                    } else {
-                        self.current_speech.clone()
+                    // Use samples from VAD transition if available, otherwise use accumulated samples.
+                    // After a split the transition's samples repeat audio already sent,
+                    // so only the remainder since the split is used.
+                    let (speech_samples, start_ms) = match self.split_start_ms.take() {
+                        Some(split_start_ms) => (self.current_speech.clone(), split_start_ms),
+                        None if !samples.is_empty() => (samples, start_timestamp_ms as f64),
+                        None => (self.current_speech.clone(), start_timestamp_ms as f64),
                     };
 
                     if !speech_samples.is_empty() {
                         let segment = SpeechSegment {
                             samples: speech_samples,
-                            start_timestamp_ms: start_timestamp_ms as f64,
+                            start_timestamp_ms: start_ms,
                             end_timestamp_ms: end_timestamp_ms as f64,
                             confidence: 0.9, // VAD confidence
                         };
@@ -256,6 +482,12 @@ impl ContinuousVadProcessor {
         // Accumulate speech if we're currently in a speech state
         if self.in_speech {
             self.current_speech.extend_from_slice(chunk);
+
+            if let Some(max_samples) = self.max_segment_samples {
+                if self.current_speech.len() >= max_samples {
+                    self.split_current_speech();
+                }
+            }
         }
 
         self.processed_samples += chunk.len();
@@ -316,4 +548,46 @@ pub fn get_speech_chunks(samples_mono_16k: &[f32], redemption_time_ms: u32) -> R
     Ok(segments)
 }
 
- 
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_balanced_preset_matches_silero_defaults() {
+        let (positive, negative) = vad_thresholds(VadAggressiveness::Balanced, 0.9);
+        assert!((positive - 0.50).abs() < 1e-6);
+        assert!((negative - 0.35).abs() < 1e-6);
+    }
+
+    #[test]
+    fn test_custom_threshold_is_clamped() {
+        let (positive, negative) = vad_thresholds(VadAggressiveness::Custom, 0.7);
+        assert!((positive - 0.7).abs() < 1e-6);
+        assert!((negative - 0.55).abs() < 1e-6);
+
+        let (positive, negative) = vad_thresholds(VadAggressiveness::Custom, 0.0);
+        assert_eq!(positive, MIN_VAD_THRESHOLD);
+        assert_eq!(negative, 0.01);
+    }
+
+    #[test]
+    fn test_live_chunk_seconds_is_clamped() {
+        assert_eq!(clamp_live_chunk_seconds(1), MIN_LIVE_CHUNK_SECONDS);
+        assert_eq!(clamp_live_chunk_seconds(8), 8);
+        assert_eq!(clamp_live_chunk_seconds(60), MAX_LIVE_CHUNK_SECONDS);
+    }
+
+    #[test]
+    fn test_aggressiveness_string_roundtrip() {
+        for level in [
+            VadAggressiveness::Relaxed,
+            VadAggressiveness::Balanced,
+            VadAggressiveness::Aggressive,
+            VadAggressiveness::VeryAggressive,
+            VadAggressiveness::Custom,
+        ] {
+            assert_eq!(VadAggressiveness::parse(level.as_str()), Some(level));
+            assert_eq!(VadAggressiveness::from_u8(level.to_u8()), level);
+        }
+    }
+}
@@ -0,0 +1,197 @@
+//! Automatic meeting summaries
+//!
+//! When `auto_summarize_on_complete` is enabled, the summary template is run
+//! through the LLM engine once a completed recording's transcripts are saved.
+//! The response is streamed straight into `summary.md` in the meeting folder
+//! and `auto-summary-complete` (or `auto-summary-failed`) is emitted.
+
+use std::collections::HashSet;
+use std::io::Write;
+use std::path::Path;
+use std::sync::{Arc, Mutex};
+
+use log::{error, info, warn};
+use once_cell::sync::Lazy;
+use tauri::{AppHandle, Emitter, Manager, Runtime};
+
+use crate::llm_engine::provider::{CompletionRequest, StreamCallback};
+use crate::state::AppState;
+use crate::task_scheduler::{self, TaskKind};
+
+/// Settings key for the auto-summary preference
+pub const AUTO_SUMMARIZE_SETTING: &str = "auto_summarize_on_complete";
+
+/// Built-in template used for the summary prompt
+const SUMMARY_TEMPLATE_ID: &str = "builtin_summarize";
+
+/// Prompt used if the built-in template was deleted
+const FALLBACK_SUMMARY_PROMPT: &str = "Please provide a concise summary of this meeting, \
+     highlighting the main topics discussed and any conclusions reached.";
+
+const SUMMARY_FILE_NAME: &str = "summary.md";
+
+/// Recordings currently being summarized, so a repeated save doesn't start a second run
+static IN_PROGRESS: Lazy<Mutex<HashSet<String>>> = Lazy::new(|| Mutex::new(HashSet::new()));
+
+/// Start an auto-summary for a recording if the preference is on and it has completed
+pub async fn summarize_if_enabled<R: Runtime>(app: &AppHandle<R>, recording_id: &str) {
+    let recording = {
+        let state = app.state::<AppState>();
+        let db = state.db().await;
+
+        if !db.get_bool_setting(AUTO_SUMMARIZE_SETTING, false).unwrap_or(false) {
+            return;
+        }
+
+        match db.get_recording(recording_id) {
+            Ok(Some(recording)) => recording,
+            Ok(None) => return,
+            Err(e) => {
+                error!("Auto-summary: failed to load recording {}: {}", recording_id, e);
+                return;
+            }
+        }
+    };
+
+    if recording.status != "completed" {
+        return;
+    }
+
+    let Some(folder) = recording.folder_path() else {
+        info!("Auto-summary: recording {} has no meeting folder, skipping", recording_id);
+        return;
+    };
+
+    if !IN_PROGRESS.lock().unwrap().insert(recording_id.to_string()) {
+        return;
+    }
+
+    let app = app.clone();
+    let recording_id = recording_id.to_string();
+    let title = recording.title;
+    tauri::async_runtime::spawn(async move {
+        let path = folder.join(SUMMARY_FILE_NAME);
+        let outcome = match task_scheduler::acquire(TaskKind::Llm, format!("Summarize {}", recording_id)).await {
+            Ok(_permit) => generate_summary(&app, &recording_id, &title, &path).await,
+            Err(e) => Err(e),
+        };
+        match outcome {
+            Ok(true) => {
+                info!("📝 Auto-summary saved to {}", path.display());
+                let _ = app.emit("auto-summary-complete", serde_json::json!({
+                    "recordingId": recording_id,
+                    "path": path.to_string_lossy(),
+                }));
+            }
+            Ok(false) => {}
+            Err(e) => {
+                error!("Auto-summary for recording {} failed: {}", recording_id, e);
+                let _ = std::fs::remove_file(&path);
+                let _ = app.emit("auto-summary-failed", serde_json::json!({
+                    "recordingId": recording_id,
+                    "error": e,
+                }));
+            }
+        }
+        IN_PROGRESS.lock().unwrap().remove(&recording_id);
+    });
+}
+
+/// Generate the summary into `path`. Returns Ok(false) if it was skipped.
+async fn generate_summary<R: Runtime>(
+    app: &AppHandle<R>,
+    recording_id: &str,
+    title: &str,
+    path: &Path,
+) -> Result<bool, String> {
+    let state = app.state::<AppState>();
+
+    let (segments, prompt) = {
+        let db = state.db().await;
+        let segments = db.get_transcript_segments(recording_id).map_err(|e| e.to_string())?;
+        let prompt = db
+            .get_template(SUMMARY_TEMPLATE_ID)
+            .ok()
+            .flatten()
+            .map(|t| t.prompt)
+            .unwrap_or_else(|| FALLBACK_SUMMARY_PROMPT.to_string());
+        (segments, prompt)
+    };
+
+    if segments.is_empty() {
+        info!("Auto-summary: recording {} has no transcript, skipping", recording_id);
+        return Ok(false);
+    }
+
+    let transcript = segments
+        .iter()
+        .map(|s| {
+            let speaker = s.speaker_label.as_deref().unwrap_or("Unknown");
+            format!("[{}] {}: {}", s.display_time, speaker, s.text)
+        })
+        .collect::<Vec<_>>()
+        .join("\n");
+
+    let engine = state.llm_engine.read().await;
+    if !engine.is_ready().await {
+        warn!("Auto-summary: no LLM provider ready, skipping summary for recording {}", recording_id);
+        return Ok(false);
+    }
+
+    let system = format!(
+        "You are a helpful assistant analyzing a meeting transcript. \
+        Answer based on the transcript below.\n\n\
+        TRANSCRIPT:\n{}",
+        transcript
+    );
+    let request = CompletionRequest {
+        max_tokens: Some(2048),
+        stream: true,
+        ..CompletionRequest::with_system_and_user(system, prompt)
+    };
+
+    // Stream tokens straight into the file
+    let mut file = std::fs::File::create(path)
+        .map_err(|e| format!("Failed to create {}: {}", path.display(), e))?;
+    writeln!(file, "# {}\n", title).map_err(|e| e.to_string())?;
+    let file = Arc::new(Mutex::new(file));
+    let write_error: Arc<Mutex<Option<String>>> = Arc::new(Mutex::new(None));
+
+    let file_for_callback = file.clone();
+    let error_for_callback = write_error.clone();
+    let callback: StreamCallback = Box::new(move |token: String| {
+        if let Err(e) = file_for_callback.lock().unwrap().write_all(token.as_bytes()) {
+            error_for_callback.lock().unwrap().get_or_insert_with(|| e.to_string());
+        }
+    });
+
+    engine
+        .complete_streaming(request, callback, None)
+        .await
+        .map_err(|e| e.to_string())?;
+
+    if let Some(e) = write_error.lock().unwrap().take() {
+        return Err(format!("Failed to write summary: {}", e));
+    }
+    let mut file = file.lock().unwrap();
+    writeln!(file).and_then(|_| file.flush()).map_err(|e| e.to_string())?;
+
+    Ok(true)
+}
+
+#[tauri::command]
+pub async fn get_auto_summarize_on_complete(
+    state: tauri::State<'_, AppState>,
+) -> Result<bool, String> {
+    let db = state.db().await;
+    db.get_bool_setting(AUTO_SUMMARIZE_SETTING, false).map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub async fn set_auto_summarize_on_complete(
+    state: tauri::State<'_, AppState>,
+    enabled: bool,
+) -> Result<(), String> {
+    let db = state.db().await;
+    db.set_bool_setting(AUTO_SUMMARIZE_SETTING, enabled).map_err(|e| e.to_string())
+}
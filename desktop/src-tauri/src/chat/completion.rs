@@ -4,37 +4,58 @@ use std::sync::Arc;
 use tokio_util::sync::CancellationToken;
 use tauri::Emitter;
 
-use crate::database::{ChatMessageStatus, ChatRole};
+use crate::database::{ChatMessageStatus, ChatRole, DatabaseManager, Tool};
+use crate::llm_engine::commands::TOOL_PROMPT_TEMPLATE_SETTING;
 use crate::llm_engine::model_manager::has_native_tool_support_with_override;
-use crate::llm_engine::provider::{CompletionRequest, Message, MessageRole, ToolDefinition};
+use crate::llm_engine::provider::{CompletionRequest, Message, MessageRole, ToolCallDelta, ToolDefinition};
 use crate::tools::executor::{execute_tool, ToolContext};
+use crate::chat::types::TranscriptScope;
 use crate::chat::tool_orchestration::{
     build_tool_system_prompt, run_simulated_tool_loop, SimulatedToolConfig,
 };
 
-/// Run the actual chat completion in background
-pub async fn run_chat_completion(
-    app_handle: tauri::AppHandle,
-    llm_engine: Arc<tokio::sync::RwLock<crate::llm_engine::engine::LlmEngine>>,
-    database: Arc<tokio::sync::RwLock<Option<crate::state::DbWrapper>>>,
-    mcp_manager: Arc<tokio::sync::RwLock<Option<crate::mcp::McpManager>>>,
-    session_id: String,
-    recording_id: String,
-    message_id: String,
-    cancel_token: CancellationToken,
-    _tool_ids: Option<Vec<String>>, // Now unused - tools are loaded from session DB
-) -> Result<(), String> {
-    // Get database - hold reference within scope
-    let db_guard = database.read().await;
-    let db_wrapper = db_guard.as_ref().ok_or("Database not initialized")?;
-    let db = db_wrapper.inner();
+/// Maximum tokens generated for a chat answer
+pub(crate) const CHAT_MAX_TOKENS: u32 = 2048;
 
-    // Update status to streaming
-    db.update_chat_message_status(&message_id, ChatMessageStatus::Streaming, None)
-        .map_err(|e| e.to_string())?;
+/// Everything sent to the LLM for a chat turn
+pub(crate) struct ChatContext {
+    /// System prompt with the transcript, followed by the session history
+    pub messages: Vec<Message>,
+    pub tool_definitions: Option<Vec<ToolDefinition>>,
+    pub tools: Vec<Tool>,
+}
 
+/// Convert session tools to the definitions sent to the LLM (None if there are no tools)
+pub(crate) fn to_tool_definitions(tools: &[Tool]) -> Option<Vec<ToolDefinition>> {
+    if tools.is_empty() {
+        return None;
+    }
+
+    Some(tools.iter().map(|t| {
+        let schema: serde_json::Value = serde_json::from_str(&t.function_schema)
+            .unwrap_or_else(|_| serde_json::json!({}));
+        let parameters = schema.get("parameters")
+            .cloned()
+            .unwrap_or_else(|| serde_json::json!({"type": "object", "properties": {}}));
+
+        ToolDefinition {
+            name: t.name.clone(),
+            description: t.description.clone().unwrap_or_default(),
+            parameters,
+        }
+    }).collect())
+}
+
+/// Build the LLM context for a chat session: the session's tools, the
+/// (optionally scoped) transcript system prompt and the finished messages
+pub(crate) fn build_chat_context(
+    db: &DatabaseManager,
+    session_id: &str,
+    recording_id: &str,
+    transcript_scope: Option<TranscriptScope>,
+) -> Result<ChatContext, String> {
     // Load tools from session database (user's current selection)
-    let session_tools = db.get_session_tools(&session_id).map_err(|e| e.to_string())?;
+    let session_tools = db.get_session_tools(session_id).map_err(|e| e.to_string())?;
 
     log::info!(
         "Session {} has {} tools selected: {:?}",
@@ -46,32 +67,24 @@ pub async fn run_chat_completion(
     let tools = session_tools;
 
     // Convert tools to ToolDefinition format
-    let tool_definitions: Option<Vec<ToolDefinition>> = if tools.is_empty() {
-        None
-    } else {
-        Some(tools.iter().map(|t| {
-            let schema: serde_json::Value = serde_json::from_str(&t.function_schema)
-                .unwrap_or_else(|_| serde_json::json!({}));
-            let parameters = schema.get("parameters")
-                .cloned()
-                .unwrap_or_else(|| serde_json::json!({"type": "object", "properties": {}}));
-
-            ToolDefinition {
-                name: t.name.clone(),
-                description: t.description.clone().unwrap_or_default(),
-                parameters,
-            }
-        }).collect())
-    };
+    let tool_definitions = to_tool_definitions(&tools);
 
-    // Load transcript for context
-    let segments = db
-        .get_transcript_segments(&recording_id)
+    // Load transcript for context, limited to the requested scope
+    let mut segments = db
+        .get_transcript_segments(recording_id)
         .map_err(|e| e.to_string())?;
+    let scope = transcript_scope.filter(|scope| scope.is_limited());
+    if let Some(scope) = &scope {
+        segments.retain(|s| scope.includes(s));
+    }
 
     // Build transcript text for context
     let transcript_text = if segments.is_empty() {
-        "No transcript available for this recording.".to_string()
+        if scope.is_some() {
+            "No transcript segments match the selected part of this recording.".to_string()
+        } else {
+            "No transcript available for this recording.".to_string()
+        }
     } else {
         segments
             .iter()
@@ -85,20 +98,37 @@ pub async fn run_chat_completion(
 
     // Load chat history for this session
     let chat_messages = db
-        .get_chat_messages_by_session(&session_id)
+        .get_chat_messages_by_session(session_id)
         .map_err(|e| e.to_string())?;
 
     // Build messages for LLM (excluding the pending assistant message)
     let mut messages: Vec<Message> = Vec::new();
 
     // System message with transcript context
-    let system_content = format!(
+    let transcript_heading = if scope.is_some() {
+        "TRANSCRIPT (excerpt selected by the user):"
+    } else {
+        "TRANSCRIPT:"
+    };
+    let mut system_content = format!(
         "You are a helpful assistant analyzing a meeting transcript. \
         Answer questions about the meeting based on the transcript below.\n\n\
-        TRANSCRIPT:\n{}\n\n\
+        {}\n{}\n\n\
         Provide clear, concise answers based on the transcript content.",
+        transcript_heading,
         transcript_text
     );
+
+    // System messages in the history are summaries of compacted earlier turns
+    let summaries: Vec<&str> = chat_messages
+        .iter()
+        .filter(|m| m.role == ChatRole::System && !m.content.is_empty())
+        .map(|m| m.content.as_str())
+        .collect();
+    if !summaries.is_empty() {
+        system_content.push_str("\n\nSUMMARY OF THE EARLIER CONVERSATION:\n");
+        system_content.push_str(&summaries.join("\n\n"));
+    }
     messages.push(Message {
         role: MessageRole::System,
         content: system_content,
@@ -124,6 +154,38 @@ pub async fn run_chat_completion(
         });
     }
 
+    Ok(ChatContext {
+        messages,
+        tool_definitions,
+        tools,
+    })
+}
+
+/// Run the actual chat completion in background
+pub async fn run_chat_completion(
+    app_handle: tauri::AppHandle,
+    llm_engine: Arc<tokio::sync::RwLock<crate::llm_engine::engine::LlmEngine>>,
+    database: Arc<tokio::sync::RwLock<Option<crate::state::DbWrapper>>>,
+    mcp_manager: Arc<tokio::sync::RwLock<Option<crate::mcp::McpManager>>>,
+    session_id: String,
+    recording_id: String,
+    message_id: String,
+    cancel_token: CancellationToken,
+    _tool_ids: Option<Vec<String>>, // Now unused - tools are loaded from session DB
+    transcript_scope: Option<TranscriptScope>,
+) -> Result<(), String> {
+    // Get database - hold reference within scope
+    let db_guard = database.read().await;
+    let db_wrapper = db_guard.as_ref().ok_or("Database not initialized")?;
+    let db = db_wrapper.inner();
+
+    // Update status to streaming
+    db.update_chat_message_status(&message_id, ChatMessageStatus::Streaming, None)
+        .map_err(|e| e.to_string())?;
+
+    let ChatContext { messages, tool_definitions, tools } =
+        build_chat_context(db, &session_id, &recording_id, transcript_scope)?;
+
     // Drop the database lock before the long-running operation
     drop(db_guard);
 
@@ -152,17 +214,25 @@ pub async fn run_chat_completion(
         return Err("LLM engine not ready".to_string());
     }
 
+    // Wall time of the whole generation, including tool calls
+    let started = std::time::Instant::now();
+
     // Determine if model has native tool support
     let model_id = engine.current_model().await.unwrap_or_default();
 
-    // Check for user-defined tool support override from database
-    let user_tool_support_override = {
+    // Check for user-defined tool support override and prompt template from database
+    let (user_tool_support_override, tool_prompt_template) = {
         let db_lock = database.read().await;
-        db_lock.as_ref()
+        let tool_support = db_lock.as_ref()
             .and_then(|db| db.get_model_tool_support(&model_id).ok())
-            .flatten()
+            .flatten();
+        let template = db_lock.as_ref()
+            .and_then(|db| db.get_setting(TOOL_PROMPT_TEMPLATE_SETTING).ok())
+            .flatten();
+        (tool_support, template)
     };
 
+    // Models with native tool support also keep a real system role
     let use_native_tools = has_native_tool_support_with_override(&model_id, user_tool_support_override);
 
     log::info!(
@@ -180,6 +250,7 @@ pub async fn run_chat_completion(
         let tool_system_prompt = build_tool_system_prompt(
             &messages[0].content,
             tool_definitions.as_ref().unwrap(),
+            tool_prompt_template.as_deref(),
         );
         let mut sim_messages = messages.clone();
         sim_messages[0].content = tool_system_prompt;
@@ -205,6 +276,13 @@ pub async fn run_chat_completion(
                 if let Some(db) = db_lock.as_ref() {
                     db.update_chat_message_content(&message_id, &final_answer)
                         .map_err(|e| e.to_string())?;
+                    // The simulated loop doesn't report token usage
+                    let _ = db.update_chat_message_metrics(
+                        &message_id,
+                        None,
+                        None,
+                        started.elapsed().as_millis() as i64,
+                    );
                     db.update_chat_message_status(&message_id, ChatMessageStatus::Complete, None)
                         .map_err(|e| e.to_string())?;
                 }
@@ -221,6 +299,9 @@ pub async fn run_chat_completion(
 
                 Ok(())
             }
+            Err(_) if cancel_token.is_cancelled() => {
+                stop_cancelled_message(&database, &message_id, None).await
+            }
             Err(e) => {
                 let db_lock = database.read().await;
                 if let Some(db) = db_lock.as_ref() {
@@ -238,11 +319,14 @@ pub async fn run_chat_completion(
     // Native tool support or no tools - use existing streaming flow
     let request = CompletionRequest {
         messages,
-        max_tokens: Some(2048),
+        max_tokens: Some(CHAT_MAX_TOKENS),
         temperature: Some(0.7),
         stream: true,
         tools: tool_definitions.clone(),
         tool_choice: if tool_definitions.is_some() { Some("auto".to_string()) } else { None },
+        session_id: Some(session_id.clone()),
+        native_system_role: Some(use_native_tools),
+        tool_prompt_template: tool_prompt_template.clone(),
         ..Default::default()
     };
 
@@ -287,14 +371,45 @@ pub async fn run_chat_completion(
         });
     });
 
+    // Stream tool calls as they generate so the UI can show them live
+    let message_id_for_tools = message_id.clone();
+    let app_handle_for_tools = app_handle.clone();
+    let session_id_for_tools = session_id.clone();
+    let tool_callback = Box::new(move |delta: ToolCallDelta| {
+        let _ = app_handle_for_tools.emit(
+            &format!("chat-tool-call-{}", session_id_for_tools),
+            serde_json::json!({
+                "message_id": message_id_for_tools,
+                "index": delta.index,
+                "id": delta.id,
+                "name": delta.name,
+                "arguments": delta.arguments
+            }),
+        );
+    });
+
     // Run completion with streaming
-    let result = engine.complete_streaming(request.clone(), callback, Some(cancel_token.clone())).await;
+    let result = engine
+        .complete_streaming_with_tool_calls(request.clone(), callback, tool_callback, Some(cancel_token.clone()))
+        .await;
+
+    // Keep whatever was streamed before a cancel as the final message. Providers
+    // that can't abort generation return the full response, so use the
+    // accumulated tokens rather than the response content.
+    if cancel_token.is_cancelled() {
+        let partial = accumulated_content.lock().await.clone();
+        return stop_cancelled_message(&database, &message_id, Some(&partial)).await;
+    }
 
     // Handle result, including tool call loop
     match result {
         Ok(mut response) => {
             let mut current_messages = request.messages.clone();
 
+            // Prompt size of the first request; generated tokens summed over tool iterations
+            let prompt_tokens = response.prompt_tokens;
+            let mut completion_tokens = response.completion_tokens;
+
             // Tool call loop
             const MAX_TOOL_ITERATIONS: usize = 10;
             let mut iteration = 0;
@@ -360,25 +475,27 @@ pub async fn run_chat_completion(
 
                 // Check for cancellation
                 if cancel_token.is_cancelled() {
-                    let db_lock = database.read().await;
-                    if let Some(db) = db_lock.as_ref() {
-                        let _ = db.update_chat_message_status(&message_id, ChatMessageStatus::Cancelled, None);
-                    }
-                    return Err("Cancelled".to_string());
+                    return stop_cancelled_message(&database, &message_id, Some(&response.content)).await;
                 }
 
                 // Run another completion
                 let next_request = CompletionRequest {
                     messages: current_messages.clone(),
-                    max_tokens: Some(2048),
+                    max_tokens: Some(CHAT_MAX_TOKENS),
                     temperature: Some(0.7),
                     stream: false,
                     tools: tool_definitions.clone(),
                     tool_choice: Some("auto".to_string()),
+                    session_id: Some(session_id.clone()),
+                    native_system_role: Some(use_native_tools),
+                    tool_prompt_template: tool_prompt_template.clone(),
                     ..Default::default()
                 };
 
                 response = engine.complete(next_request).await.map_err(|e| e.to_string())?;
+                if let Some(tokens) = response.completion_tokens {
+                    *completion_tokens.get_or_insert(0) += tokens;
+                }
 
                 {
                     let db_lock = database.read().await;
@@ -402,6 +519,12 @@ pub async fn run_chat_completion(
             if let Some(db) = db_lock.as_ref() {
                 db.update_chat_message_content(&message_id, &response.content)
                     .map_err(|e| e.to_string())?;
+                let _ = db.update_chat_message_metrics(
+                    &message_id,
+                    completion_tokens.map(i64::from),
+                    prompt_tokens.map(i64::from),
+                    started.elapsed().as_millis() as i64,
+                );
                 db.update_chat_message_status(&message_id, ChatMessageStatus::Complete, None)
                     .map_err(|e| e.to_string())?;
             }
@@ -420,3 +543,17 @@ pub async fn run_chat_completion(
         }
     }
 }
+
+/// Finalize a cancelled completion, keeping `partial` output (if any) as the
+/// message content with the `Stopped` status
+async fn stop_cancelled_message(
+    database: &Arc<tokio::sync::RwLock<Option<crate::state::DbWrapper>>>,
+    message_id: &str,
+    partial: Option<&str>,
+) -> Result<(), String> {
+    let db_lock = database.read().await;
+    if let Some(db) = db_lock.as_ref() {
+        let _ = db.stop_chat_message(message_id, partial);
+    }
+    Err("Cancelled".to_string())
+}
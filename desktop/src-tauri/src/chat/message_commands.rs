@@ -4,15 +4,27 @@ use std::sync::Arc;
 use tauri::{Emitter, State};
 use tokio_util::sync::CancellationToken;
 
-use crate::database::{ChatMessage, ChatMessageStatus};
+use crate::database::{ChatMessage, ChatMessageStatus, ChatRole};
+use crate::llm_engine::commands::TOOL_PROMPT_TEMPLATE_SETTING;
+use crate::llm_engine::model_manager::{has_native_tool_support_with_override, NATIVE_TOOL_MODELS};
+use crate::llm_engine::provider::{CompletionRequest, Message, ProviderType};
 use crate::state::AppState;
-use super::types::{SendMessageResponse, ChatMessageStatus2};
+use crate::task_scheduler::{self, TaskKind};
+use super::types::{
+    SendMessageResponse, ChatMessageStatus2, TranscriptScope, ContextTokenEstimate,
+    ToolCallingMode, PreviewTool, ToolPreview,
+};
 use super::task_registry::{
     register_task, remove_task, cancel_task, cancel_session_tasks, is_session_processing,
+    try_register_session_task,
+};
+use super::completion::{
+    build_chat_context, run_chat_completion, to_tool_definitions, ChatContext, CHAT_MAX_TOKENS,
 };
-use super::completion::run_chat_completion;
+use super::tool_orchestration::{build_tool_system_prompt, extract_json_object};
 
-/// Send a chat message and start background completion
+/// Send a chat message and start background completion.
+/// `transcript_scope` limits the transcript context to a time range and/or selected segments.
 #[tauri::command]
 pub async fn chat_send_message(
     app_handle: tauri::AppHandle,
@@ -22,6 +34,7 @@ pub async fn chat_send_message(
     provider_type: Option<String>,
     model_id: Option<String>,
     tool_ids: Option<Vec<String>>,
+    transcript_scope: Option<TranscriptScope>,
 ) -> Result<SendMessageResponse, String> {
     let db = state.db().await;
 
@@ -89,6 +102,14 @@ pub async fn chat_send_message(
 
     // Spawn background task
     tokio::spawn(async move {
+        // Wait for a background task slot; a cancel while waiting is handled
+        // by the completion itself
+        let _permit = task_scheduler::acquire_or_cancel(
+            TaskKind::Llm,
+            format!("Chat {}", session_id_clone),
+            &cancel_token,
+        )
+        .await;
         let result = run_chat_completion(
             app_handle_clone.clone(),
             state_llm_engine,
@@ -99,6 +120,7 @@ pub async fn chat_send_message(
             assistant_message_id_clone.clone(),
             cancel_token,
             tool_ids_clone,
+            transcript_scope,
         )
         .await;
 
@@ -106,27 +128,7 @@ pub async fn chat_send_message(
         remove_task(&assistant_message_id_clone);
 
         // Emit completion event
-        match result {
-            Ok(_) => {
-                let _ = app_handle_clone.emit(
-                    &format!("chat-complete-{}", session_id_clone),
-                    serde_json::json!({
-                        "message_id": assistant_message_id_clone,
-                        "status": "complete"
-                    }),
-                );
-            }
-            Err(e) => {
-                let _ = app_handle_clone.emit(
-                    &format!("chat-complete-{}", session_id_clone),
-                    serde_json::json!({
-                        "message_id": assistant_message_id_clone,
-                        "status": "error",
-                        "error": e
-                    }),
-                );
-            }
-        }
+        emit_completion_result(&app_handle_clone, &session_id_clone, &assistant_message_id_clone, result);
     });
 
     Ok(SendMessageResponse {
@@ -135,6 +137,232 @@ pub async fn chat_send_message(
     })
 }
 
+/// Emit the chat-complete event for a finished completion
+fn emit_completion_result(
+    app_handle: &tauri::AppHandle,
+    session_id: &str,
+    message_id: &str,
+    result: Result<(), String>,
+) {
+    match result {
+        Ok(_) => {
+            let _ = app_handle.emit(
+                &format!("chat-complete-{}", session_id),
+                serde_json::json!({
+                    "message_id": message_id,
+                    "status": "complete"
+                }),
+            );
+        }
+        Err(e) if e == "Cancelled" => {
+            let _ = app_handle.emit(
+                &format!("chat-complete-{}", session_id),
+                serde_json::json!({
+                    "message_id": message_id,
+                    "status": "stopped"
+                }),
+            );
+        }
+        Err(e) => {
+            let _ = app_handle.emit(
+                &format!("chat-complete-{}", session_id),
+                serde_json::json!({
+                    "message_id": message_id,
+                    "status": "error",
+                    "error": e
+                }),
+            );
+        }
+    }
+}
+
+/// Re-run the latest assistant message with a different provider/model.
+///
+/// The message is replaced in place. The engine is switched to the requested
+/// provider/model (loading it if needed) for this completion only, then
+/// restored to the provider/model that was active before. Other LLM tasks
+/// wait while the engine is switched, so they keep running on their model.
+#[tauri::command]
+pub async fn chat_regenerate_with_model(
+    app_handle: tauri::AppHandle,
+    state: State<'_, AppState>,
+    message_id: String,
+    provider_type: ProviderType,
+    model_id: String,
+) -> Result<(), String> {
+    let (session_id, recording_id) = {
+        let db = state.db().await;
+        let message = db
+            .get_chat_message(&message_id)
+            .map_err(|e| e.to_string())?
+            .ok_or("Message not found")?;
+
+        if message.role != ChatRole::Assistant {
+            return Err("Only assistant messages can be regenerated".to_string());
+        }
+        let session_id = message.session_id.clone().ok_or("Message has no chat session")?;
+
+        // Earlier turns are the context, so only the latest answer can be replaced
+        let messages = db
+            .get_chat_messages_by_session(&session_id)
+            .map_err(|e| e.to_string())?;
+        if messages.last().map(|m| m.id.as_str()) != Some(message_id.as_str()) {
+            return Err("Only the latest message in a chat can be regenerated".to_string());
+        }
+
+        (session_id, message.recording_id)
+    };
+
+    // Claim the session; the guard releases it and switches the model back
+    // however this ends
+    let cancel_token = CancellationToken::new();
+    if !try_register_session_task(message_id.clone(), session_id.clone(), cancel_token.clone()) {
+        return Err("A response is already being generated for this chat".to_string());
+    }
+    let mut regeneration = RegenerationGuard {
+        message_id: message_id.clone(),
+        llm_engine: state.llm_engine.clone(),
+        previous: None,
+        finished: false,
+    };
+
+    {
+        let db = state.db().await;
+        let provider_str = serde_json::to_value(&provider_type)
+            .ok()
+            .and_then(|v| v.as_str().map(String::from));
+        db.reset_chat_message_for_regeneration(&message_id, provider_str.as_deref(), Some(&model_id))
+            .map_err(|e| e.to_string())?;
+    }
+
+    let state_llm_engine = state.llm_engine.clone();
+    let state_db = state.database_arc();
+    let state_mcp = state.mcp_manager_arc();
+
+    tokio::spawn(async move {
+        // The engine is shared, so switching it would move every running LLM
+        // task onto the requested model. The switch, completion and restore
+        // run while no other LLM task does; a cancel while waiting is handled
+        // by the completion itself.
+        let _permit = task_scheduler::acquire_exclusive_or_cancel(
+            TaskKind::Llm,
+            format!("Regenerate in chat {}", session_id),
+            &cancel_token,
+        )
+        .await;
+        let switched = if cancel_token.is_cancelled() {
+            Ok(())
+        } else {
+            regeneration.switch_to(provider_type, &model_id).await
+        };
+
+        let result = match switched {
+            Ok(()) => {
+                run_chat_completion(
+                    app_handle.clone(),
+                    state_llm_engine,
+                    state_db,
+                    state_mcp,
+                    session_id.clone(),
+                    recording_id,
+                    message_id.clone(),
+                    cancel_token,
+                    None,
+                    None,
+                )
+                .await
+            }
+            Err(e) => {
+                if let Some(db) = state_db.read().await.as_ref() {
+                    let _ = db.update_chat_message_status(&message_id, ChatMessageStatus::Error, Some(e.as_str()));
+                }
+                Err(e)
+            }
+        };
+
+        regeneration.finish().await;
+
+        emit_completion_result(&app_handle, &session_id, &message_id, result);
+    });
+
+    Ok(())
+}
+
+/// Releases a regeneration's chat task and switches the engine back to the
+/// previous provider/model. `finish` waits for the switch; dropping the guard
+/// (an early return) runs it in the background.
+struct RegenerationGuard {
+    message_id: String,
+    llm_engine: Arc<tokio::sync::RwLock<crate::llm_engine::engine::LlmEngine>>,
+    /// Provider/model to restore, once the engine was switched
+    previous: Option<(Option<ProviderType>, Option<String>)>,
+    finished: bool,
+}
+
+impl RegenerationGuard {
+    /// Switch the engine to the requested provider/model (loading it if
+    /// needed), remembering the active ones to restore
+    async fn switch_to(&mut self, provider_type: ProviderType, model_id: &str) -> Result<(), String> {
+        let engine = self.llm_engine.read().await;
+        self.previous = Some((engine.active_provider_type().await, engine.current_model().await));
+        let switched = match engine.set_active_provider(provider_type).await {
+            Ok(()) if engine.current_model().await.as_deref() != Some(model_id) => {
+                engine.initialize(model_id).await
+            }
+            other => other,
+        };
+        switched.map_err(|e| e.to_string())
+    }
+
+    async fn finish(mut self) {
+        self.finished = true;
+        remove_task(&self.message_id);
+        if let Some((provider_type, model_id)) = self.previous.take() {
+            restore_llm_model(&self.llm_engine, provider_type, model_id).await;
+        }
+    }
+}
+
+impl Drop for RegenerationGuard {
+    fn drop(&mut self) {
+        if self.finished {
+            return;
+        }
+        remove_task(&self.message_id);
+        if let Some((provider_type, model_id)) = self.previous.take() {
+            let llm_engine = self.llm_engine.clone();
+            tauri::async_runtime::spawn(async move {
+                restore_llm_model(&llm_engine, provider_type, model_id).await;
+            });
+        }
+    }
+}
+
+/// Switch the engine back to a previously active provider/model
+async fn restore_llm_model(
+    llm_engine: &Arc<tokio::sync::RwLock<crate::llm_engine::engine::LlmEngine>>,
+    provider_type: Option<ProviderType>,
+    model_id: Option<String>,
+) {
+    let Some(provider_type) = provider_type else {
+        return;
+    };
+
+    let engine = llm_engine.read().await;
+    if let Err(e) = engine.set_active_provider(provider_type).await {
+        log::warn!("Failed to restore LLM provider after regeneration: {}", e);
+        return;
+    }
+
+    if let Some(model_id) = model_id {
+        if engine.current_model().await.as_deref() != Some(model_id.as_str()) {
+            if let Err(e) = engine.initialize(&model_id).await {
+                log::warn!("Failed to restore LLM model {} after regeneration: {}", model_id, e);
+            }
+        }
+    }
+}
+
 /// Get all chat messages for a session
 #[tauri::command]
 pub async fn chat_get_messages(
@@ -173,15 +401,259 @@ pub async fn chat_cancel_message(
 ) -> Result<(), String> {
     // Find and cancel the task
     if cancel_task(&message_id).is_some() {
-        // Update status in database
+        // Keep the output streamed so far; the completion task writes its
+        // final buffer once it notices the cancellation
         let db = state.db().await;
-        db.update_chat_message_status(&message_id, ChatMessageStatus::Cancelled, None)
+        db.stop_chat_message(&message_id, None)
             .map_err(|e| e.to_string())?;
     }
 
     Ok(())
 }
 
+/// Prompt used to condense older chat turns when compacting a session
+const COMPACT_SYSTEM_PROMPT: &str = "You condense chat history. Summarize the conversation below \
+    between a user and an assistant about a meeting. Keep the questions asked, the answers and \
+    facts given, and any decisions or open follow-ups, so the conversation can continue from the \
+    summary alone. Reply with the summary only.";
+
+/// Summarize all but the last `keep_last_n` messages of a session into a single
+/// summary message so long chats fit the context window. The replaced messages
+/// are moved to the archive, not deleted. Returns the summary message, or None
+/// if there was nothing to compact.
+#[tauri::command]
+pub async fn chat_compact_session(
+    state: State<'_, AppState>,
+    session_id: String,
+    keep_last_n: usize,
+) -> Result<Option<ChatMessage>, String> {
+    if is_session_processing(&session_id) {
+        return Err("A response is being generated for this chat".to_string());
+    }
+
+    let (recording_id, messages) = {
+        let db = state.db().await;
+        let session = db
+            .get_chat_session(&session_id)
+            .map_err(|e| e.to_string())?
+            .ok_or("Session not found")?;
+        let messages = db
+            .get_chat_messages_by_session(&session_id)
+            .map_err(|e| e.to_string())?;
+        (session.recording_id, messages)
+    };
+
+    let compact_count = messages.len().saturating_sub(keep_last_n);
+    // A lone summary (or single message) gains nothing from another pass
+    if compact_count < 2 {
+        return Ok(None);
+    }
+    let to_compact = &messages[..compact_count];
+
+    let conversation = to_compact
+        .iter()
+        .filter(|m| !m.content.is_empty())
+        .map(|m| {
+            let label = match m.role {
+                ChatRole::System => "Earlier summary",
+                ChatRole::User => "User",
+                ChatRole::Assistant => "Assistant",
+            };
+            format!("{}: {}", label, m.content)
+        })
+        .collect::<Vec<_>>()
+        .join("\n\n");
+
+    let summary_text = {
+        let _permit = task_scheduler::acquire(TaskKind::Llm, format!("Compact chat {}", session_id)).await?;
+        let engine = state.llm_engine.read().await;
+        if !engine.is_ready().await {
+            return Err("LLM engine not ready. Please configure an LLM provider in settings.".to_string());
+        }
+        let request = CompletionRequest {
+            max_tokens: Some(1024),
+            temperature: Some(0.3),
+            ..CompletionRequest::with_system_and_user(COMPACT_SYSTEM_PROMPT, conversation)
+        };
+        let response = engine.complete(request).await.map_err(|e| e.to_string())?;
+        // The cached prompt prefix no longer matches the session's history
+        let _ = engine.end_session(&session_id).await;
+        response.content.trim().to_string()
+    };
+    if summary_text.is_empty() {
+        return Err("The model returned an empty summary".to_string());
+    }
+
+    // The summary takes the place (and sequence position) of the first compacted message
+    let summary = ChatMessage {
+        sequence_id: to_compact[0].sequence_id,
+        ..ChatMessage::system(&session_id, &recording_id, &summary_text)
+    };
+    let message_ids: Vec<String> = to_compact.iter().map(|m| m.id.clone()).collect();
+
+    let db = state.db().await;
+    db.compact_chat_messages(&message_ids, &summary)
+        .map_err(|e| e.to_string())?;
+
+    log::info!(
+        "Compacted {} messages of chat session {} into a summary",
+        message_ids.len(),
+        session_id
+    );
+    Ok(Some(summary))
+}
+
+/// Prompt asking for follow-up questions about the meeting and conversation
+const FOLLOWUP_SYSTEM_PROMPT: &str = "You suggest follow-up questions. Given a meeting transcript \
+    and a conversation about it, suggest 3 short questions the user could ask next to learn more \
+    about the meeting. Don't repeat questions already asked. Reply with JSON only, in exactly this \
+    shape: {\"questions\": [\"...\", \"...\", \"...\"]}";
+
+const FOLLOWUP_COUNT: usize = 3;
+/// Most recent chat messages sent as context for follow-up suggestions
+const FOLLOWUP_RECENT_MESSAGES: usize = 6;
+/// Transcript characters sent, keeping the end of long transcripts
+const FOLLOWUP_MAX_TRANSCRIPT_CHARS: usize = 8000;
+
+/// A line without a leading list marker such as "-", "*" or "1."
+fn strip_list_marker(line: &str) -> &str {
+    let line = line.trim_start().trim_start_matches(['-', '*', '•']).trim_start();
+    let digits = line.len() - line.trim_start_matches(|c: char| c.is_ascii_digit()).len();
+    match line[digits..].chars().next() {
+        Some('.') | Some(')') if digits > 0 => line[digits + 1..].trim_start(),
+        _ => line,
+    }
+}
+
+/// Questions from the model's reply: the `questions` list of a JSON object,
+/// or lines ending in '?' when the model ignored the format
+fn parse_followup_questions(output: &str) -> Vec<String> {
+    let from_json = output
+        .find('{')
+        .and_then(|start| extract_json_object(&output[start..]))
+        .and_then(|json| serde_json::from_str::<serde_json::Value>(&json).ok())
+        .and_then(|value| value.get("questions").and_then(|q| q.as_array()).cloned())
+        .map(|items| {
+            items
+                .iter()
+                .filter_map(|item| item.as_str().map(str::to_string))
+                .collect::<Vec<_>>()
+        });
+
+    let candidates = from_json.unwrap_or_else(|| {
+        output
+            .lines()
+            .filter(|line| line.trim_end().ends_with('?'))
+            .map(|line| strip_list_marker(line).to_string())
+            .collect()
+    });
+
+    let mut questions: Vec<String> = Vec::new();
+    for candidate in candidates {
+        let question = candidate.trim().trim_matches('"').trim().to_string();
+        if !question.is_empty() && !questions.iter().any(|q| q.eq_ignore_ascii_case(&question)) {
+            questions.push(question);
+        }
+    }
+    questions.truncate(FOLLOWUP_COUNT);
+    questions
+}
+
+/// Ask the LLM for follow-up questions based on the session's transcript and
+/// recent messages. The questions are returned for the UI to offer, not sent.
+#[tauri::command]
+pub async fn chat_suggest_followups(
+    state: State<'_, AppState>,
+    session_id: String,
+) -> Result<Vec<String>, String> {
+    let (segments, messages) = {
+        let db = state.db().await;
+        let session = db
+            .get_chat_session(&session_id)
+            .map_err(|e| e.to_string())?
+            .ok_or("Session not found")?;
+        let segments = db
+            .get_transcript_segments(&session.recording_id)
+            .map_err(|e| e.to_string())?;
+        let messages = db
+            .get_chat_messages_by_session(&session_id)
+            .map_err(|e| e.to_string())?;
+        (segments, messages)
+    };
+
+    let transcript = segments
+        .iter()
+        .map(|s| format!("{}: {}", s.speaker_label.as_deref().unwrap_or("Unknown"), s.text.trim()))
+        .collect::<Vec<_>>()
+        .join("\n");
+    let transcript = if transcript.len() > FOLLOWUP_MAX_TRANSCRIPT_CHARS {
+        let mut start = transcript.len() - FOLLOWUP_MAX_TRANSCRIPT_CHARS;
+        while !transcript.is_char_boundary(start) {
+            start += 1;
+        }
+        format!("...{}", &transcript[start..])
+    } else {
+        transcript
+    };
+
+    let recent: Vec<&ChatMessage> = messages
+        .iter()
+        .filter(|m| !m.content.is_empty())
+        .collect();
+    let conversation = recent[recent.len().saturating_sub(FOLLOWUP_RECENT_MESSAGES)..]
+        .iter()
+        .map(|m| {
+            let label = match m.role {
+                ChatRole::System => "Earlier summary",
+                ChatRole::User => "User",
+                ChatRole::Assistant => "Assistant",
+            };
+            format!("{}: {}", label, m.content)
+        })
+        .collect::<Vec<_>>()
+        .join("\n\n");
+
+    if transcript.is_empty() && conversation.is_empty() {
+        return Err("Nothing to suggest follow-ups for yet".to_string());
+    }
+    let prompt = format!(
+        "TRANSCRIPT:\n{}\n\nCONVERSATION:\n{}",
+        if transcript.is_empty() { "No transcript available." } else { transcript.as_str() },
+        if conversation.is_empty() { "No messages yet." } else { conversation.as_str() },
+    );
+
+    let output = {
+        let _permit = task_scheduler::acquire(TaskKind::Llm, format!("Suggest follow-ups for {}", session_id)).await?;
+        let engine = state.llm_engine.read().await;
+        if !engine.is_ready().await {
+            return Err("LLM engine not ready. Please configure an LLM provider in settings.".to_string());
+        }
+        let request = CompletionRequest {
+            max_tokens: Some(512),
+            temperature: Some(0.5),
+            ..CompletionRequest::with_system_and_user(FOLLOWUP_SYSTEM_PROMPT, prompt)
+        };
+        engine.complete(request).await.map_err(|e| e.to_string())?.content
+    };
+
+    let questions = parse_followup_questions(&output);
+    if questions.is_empty() {
+        return Err("The model did not suggest any questions".to_string());
+    }
+    Ok(questions)
+}
+
+/// Get the messages of a session that were replaced by a summary
+#[tauri::command]
+pub async fn chat_get_archived_messages(
+    state: State<'_, AppState>,
+    session_id: String,
+) -> Result<Vec<ChatMessage>, String> {
+    let db = state.db().await;
+    db.get_archived_chat_messages(&session_id)
+        .map_err(|e| e.to_string())
+}
+
 /// Delete all chat messages for a session (but keep the session)
 #[tauri::command]
 pub async fn chat_clear_session(
@@ -234,3 +706,189 @@ pub async fn chat_get_pending_messages(
     let db = state.db().await;
     db.get_pending_chat_messages().map_err(|e| e.to_string())
 }
+
+/// Count the tokens `chat_send_message` would send for `user_message`, using
+/// the same context (transcript, history and tools) as the completion.
+#[tauri::command]
+pub async fn chat_estimate_context_tokens(
+    state: State<'_, AppState>,
+    session_id: String,
+    user_message: String,
+    transcript_scope: Option<TranscriptScope>,
+) -> Result<ContextTokenEstimate, String> {
+    let ChatContext { mut messages, tool_definitions, .. } = {
+        let db = state.db().await;
+        let session = db
+            .get_chat_session(&session_id)
+            .map_err(|e| e.to_string())?
+            .ok_or("Session not found")?;
+        build_chat_context(&db, &session_id, &session.recording_id, transcript_scope)?
+    };
+    messages.push(Message::user(user_message));
+
+    let engine = state.llm_engine.read().await;
+
+    // Same system-role and tool handling as the completion
+    let model_id = engine.current_model().await.unwrap_or_default();
+    let (user_tool_support_override, tool_prompt_template) = {
+        let db = state.db().await;
+        (
+            db.get_model_tool_support(&model_id).ok().flatten(),
+            db.get_setting(TOOL_PROMPT_TEMPLATE_SETTING).ok().flatten(),
+        )
+    };
+    let use_native_tools = has_native_tool_support_with_override(&model_id, user_tool_support_override);
+
+    // Models without native tools get the tool instructions in the system prompt
+    let tool_definitions = match tool_definitions {
+        Some(tools) if !use_native_tools => {
+            messages[0].content =
+                build_tool_system_prompt(&messages[0].content, &tools, tool_prompt_template.as_deref());
+            None
+        }
+        tools => tools,
+    };
+
+    let request = CompletionRequest {
+        messages,
+        max_tokens: Some(CHAT_MAX_TOKENS),
+        tool_choice: tool_definitions.as_ref().map(|_| "auto".to_string()),
+        tools: tool_definitions,
+        session_id: Some(session_id),
+        native_system_role: Some(use_native_tools),
+        tool_prompt_template,
+        ..Default::default()
+    };
+
+    let count = engine.count_tokens(&request).await.map_err(|e| e.to_string())?;
+
+    Ok(ContextTokenEstimate {
+        prompt_tokens: count.prompt_tokens,
+        max_completion_tokens: CHAT_MAX_TOKENS,
+        context_size: count.context_size,
+        fits: count
+            .context_size
+            .map(|size| count.prompt_tokens + CHAT_MAX_TOKENS as usize <= size),
+        exact: count.exact,
+    })
+}
+
+/// Show which tools a chat session would send to the active model and how:
+/// natively as definitions, or as instructions injected into the system prompt.
+#[tauri::command]
+pub async fn chat_preview_tools(
+    state: State<'_, AppState>,
+    session_id: String,
+) -> Result<ToolPreview, String> {
+    let session_tools = {
+        let db = state.db().await;
+        db.get_session_tools(&session_id).map_err(|e| e.to_string())?
+    };
+
+    let (model_id, provider_type) = {
+        let engine = state.llm_engine.read().await;
+        (engine.current_model().await, engine.active_provider_type().await)
+    };
+    let model_name = model_id.clone().unwrap_or_default();
+    let (tool_support_override, tool_prompt_template) = {
+        let db = state.db().await;
+        (
+            db.get_model_tool_support(&model_name).ok().flatten(),
+            db.get_setting(TOOL_PROMPT_TEMPLATE_SETTING).ok().flatten(),
+        )
+    };
+    let native_tool_support = has_native_tool_support_with_override(&model_name, tool_support_override);
+
+    // MCP tools only work while their server is running
+    let mut tools = Vec::with_capacity(session_tools.len());
+    {
+        let mcp_manager = state.mcp_manager_arc();
+        let mcp_guard = mcp_manager.read().await;
+        for tool in &session_tools {
+            let available = match (&tool.mcp_server_id, mcp_guard.as_ref()) {
+                (Some(server_id), Some(mcp)) => mcp.is_server_running(server_id).await,
+                (Some(_), None) => false,
+                (None, _) => true,
+            };
+            tools.push(PreviewTool {
+                id: tool.id.clone(),
+                name: tool.name.clone(),
+                tool_type: tool.tool_type.clone(),
+                mcp_server_name: tool.mcp_server_name.clone(),
+                available,
+            });
+        }
+    }
+
+    let tool_definitions = to_tool_definitions(&session_tools).unwrap_or_default();
+    let (mode, tool_prompt) = if tool_definitions.is_empty() {
+        (ToolCallingMode::None, None)
+    } else if !native_tool_support {
+        // Same instructions run_chat_completion adds for the simulated tool loop
+        let prompt = build_tool_system_prompt("", &tool_definitions, tool_prompt_template.as_deref());
+        (ToolCallingMode::PromptInjection, Some(prompt.trim_start().to_string()))
+    } else if provider_type == Some(ProviderType::Embedded)
+        && !NATIVE_TOOL_MODELS.iter().any(|m| model_name.to_lowercase().contains(m))
+    {
+        // The sidecar only checks the model name before falling back to injection
+        (ToolCallingMode::SidecarPromptInjection, None)
+    } else {
+        (ToolCallingMode::Native, None)
+    };
+
+    Ok(ToolPreview {
+        model_id,
+        mode,
+        native_tool_support,
+        tool_support_override,
+        tools,
+        tool_definitions,
+        tool_prompt,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_strip_list_marker() {
+        assert_eq!(strip_list_marker("- What was decided?"), "What was decided?");
+        assert_eq!(strip_list_marker("  * Who owns it?"), "Who owns it?");
+        assert_eq!(strip_list_marker("• When is it due?"), "When is it due?");
+        assert_eq!(strip_list_marker("2. Why the delay?"), "Why the delay?");
+        assert_eq!(strip_list_marker("10) What changed?"), "What changed?");
+        assert_eq!(strip_list_marker("2024 budget?"), "2024 budget?");
+    }
+
+    #[test]
+    fn test_parse_followup_questions_json() {
+        let output = r#"{"questions": ["What was decided?", "Who owns the rollout?"]}"#;
+        assert_eq!(
+            parse_followup_questions(output),
+            vec!["What was decided?", "Who owns the rollout?"]
+        );
+
+        let fenced = "Here you go:\n```json\n{\"questions\": [\"When is the deadline?\"]}\n```";
+        assert_eq!(parse_followup_questions(fenced), vec!["When is the deadline?"]);
+    }
+
+    #[test]
+    fn test_parse_followup_questions_line_fallback() {
+        let output = "Some questions you could ask:\n1. What was decided?\n- Who owns the rollout?\nThat's all.";
+        assert_eq!(
+            parse_followup_questions(output),
+            vec!["What was decided?", "Who owns the rollout?"]
+        );
+        assert!(parse_followup_questions("No questions here.").is_empty());
+    }
+
+    #[test]
+    fn test_parse_followup_questions_dedup_and_truncate() {
+        let output = r#"{"questions": ["What was decided?", "what was decided?", " ", "Who owns it?", "When is it due?", "Why the delay?"]}"#;
+        assert_eq!(
+            parse_followup_questions(output),
+            vec!["What was decided?", "Who owns it?", "When is it due?"]
+        );
+    }
+}
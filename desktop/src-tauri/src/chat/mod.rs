@@ -22,7 +22,10 @@ pub mod commands;
 pub mod tool_orchestration;
 
 // Re-export types
-pub use types::{SendMessageResponse, ChatMessageStatus2};
+pub use types::{
+    SendMessageResponse, ChatMessageStatus2, TranscriptScope, ContextTokenEstimate,
+    ToolCallingMode, PreviewTool, ToolPreview,
+};
 
 // Re-export session commands
 pub use session_commands::{
@@ -46,4 +49,10 @@ pub use message_commands::{
     chat_delete_history,
     chat_is_processing,
     chat_get_pending_messages,
+    chat_regenerate_with_model,
+    chat_estimate_context_tokens,
+    chat_compact_session,
+    chat_suggest_followups,
+    chat_get_archived_messages,
+    chat_preview_tools,
 };
@@ -115,6 +115,11 @@ pub async fn chat_delete_session(
     // Cancel any active tasks for this session
     cancel_session_tasks(&session_id);
 
+    // Let the LLM provider drop any cached prompt for this conversation
+    if let Err(e) = state.llm_engine.read().await.end_session(&session_id).await {
+        log::warn!("Failed to end LLM session {}: {}", session_id, e);
+    }
+
     let db = state.db().await;
     db.delete_chat_session(&session_id)
         .map_err(|e| e.to_string())
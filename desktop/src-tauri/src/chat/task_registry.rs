@@ -1,5 +1,7 @@
 //! Chat task registry - tracks active chat completion tasks
 
+use std::sync::{Mutex, PoisonError};
+
 use dashmap::DashMap;
 use once_cell::sync::Lazy;
 use tokio_util::sync::CancellationToken;
@@ -14,8 +16,32 @@ pub struct ChatTask {
 /// Global registry of active chat tasks (keyed by message_id)
 pub static ACTIVE_CHAT_TASKS: Lazy<DashMap<String, ChatTask>> = Lazy::new(DashMap::new);
 
+/// Held while registering, so a session check and the insert that follows it
+/// can't interleave with another registration
+static REGISTRATION_LOCK: Lazy<Mutex<()>> = Lazy::new(|| Mutex::new(()));
+
 /// Register a new chat task
 pub fn register_task(message_id: String, session_id: String, cancel_token: CancellationToken) {
+    let _lock = REGISTRATION_LOCK.lock().unwrap_or_else(PoisonError::into_inner);
+    insert_task(message_id, session_id, cancel_token);
+}
+
+/// Register a chat task unless the session already has one.
+/// Returns false (registering nothing) if the session is busy.
+pub fn try_register_session_task(
+    message_id: String,
+    session_id: String,
+    cancel_token: CancellationToken,
+) -> bool {
+    let _lock = REGISTRATION_LOCK.lock().unwrap_or_else(PoisonError::into_inner);
+    if is_session_processing(&session_id) {
+        return false;
+    }
+    insert_task(message_id, session_id, cancel_token);
+    true
+}
+
+fn insert_task(message_id: String, session_id: String, cancel_token: CancellationToken) {
     ACTIVE_CHAT_TASKS.insert(
         message_id.clone(),
         ChatTask {
@@ -46,6 +72,19 @@ pub fn cancel_session_tasks(session_id: &str) {
     }
 }
 
+/// Cancel and remove every active task; returns how many were cancelled
+pub fn cancel_all_tasks() -> usize {
+    let message_ids: Vec<String> = ACTIVE_CHAT_TASKS
+        .iter()
+        .map(|entry| entry.message_id.clone())
+        .collect();
+
+    message_ids
+        .iter()
+        .filter(|message_id| cancel_task(message_id).is_some())
+        .count()
+}
+
 /// Check if there's an active task for a session
 pub fn is_session_processing(session_id: &str) -> bool {
     ACTIVE_CHAT_TASKS
@@ -62,3 +101,55 @@ pub fn cancel_task(message_id: &str) -> Option<CancellationToken> {
             task.cancel_token
         })
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// The registry is global and `cancel_all_tasks` clears all of it, so
+    /// these tests must not overlap
+    static SERIAL: Mutex<()> = Mutex::new(());
+
+    #[test]
+    fn test_try_register_session_task() {
+        let _serial = SERIAL.lock().unwrap_or_else(PoisonError::into_inner);
+        let session_id = "session_try_register".to_string();
+        assert!(try_register_session_task(
+            "msg_try_register_1".to_string(),
+            session_id.clone(),
+            CancellationToken::new(),
+        ));
+        // The session is claimed until its task is removed
+        assert!(!try_register_session_task(
+            "msg_try_register_2".to_string(),
+            session_id.clone(),
+            CancellationToken::new(),
+        ));
+        assert!(!ACTIVE_CHAT_TASKS.contains_key("msg_try_register_2"));
+
+        remove_task("msg_try_register_1");
+        assert!(try_register_session_task(
+            "msg_try_register_2".to_string(),
+            session_id.clone(),
+            CancellationToken::new(),
+        ));
+        remove_task("msg_try_register_2");
+        assert!(!is_session_processing(&session_id));
+    }
+
+    #[test]
+    fn test_cancel_all_tasks() {
+        let _serial = SERIAL.lock().unwrap_or_else(PoisonError::into_inner);
+        let first = CancellationToken::new();
+        let second = CancellationToken::new();
+        register_task("msg_cancel_all_1".to_string(), "session_cancel_all_1".to_string(), first.clone());
+        register_task("msg_cancel_all_2".to_string(), "session_cancel_all_2".to_string(), second.clone());
+
+        assert_eq!(cancel_all_tasks(), 2);
+        assert!(first.is_cancelled() && second.is_cancelled());
+        assert!(!is_session_processing("session_cancel_all_1"));
+        assert!(!is_session_processing("session_cancel_all_2"));
+
+        assert_eq!(cancel_all_tasks(), 0);
+    }
+}
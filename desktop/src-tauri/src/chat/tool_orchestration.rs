@@ -52,13 +52,34 @@ static JSON_BLOCK_RE: Lazy<Regex> = Lazy::new(|| {
 });
 
 static BARE_JSON_RE: Lazy<Regex> = Lazy::new(|| {
-    Regex::new(r#"\{"tool"\s*:\s*"[^"]+""#).expect("Invalid regex")
+    Regex::new(r#"\{"tool"\s*:\s*"[^"]+"|\{"tool_call"\s*:\s*\{"#).expect("Invalid regex")
 });
 
-/// Build system prompt with tool definitions embedded
-pub fn build_tool_system_prompt(base_prompt: &str, tools: &[ToolDefinition]) -> String {
+/// Build system prompt with tool definitions embedded.
+/// A custom `template` (the `tool_prompt_template` setting) replaces the
+/// built-in instructions: `{tools}` becomes the tool list and `{tool_names}`
+/// the tool names, as with the sidecar's prompt injection.
+pub fn build_tool_system_prompt(
+    base_prompt: &str,
+    tools: &[ToolDefinition],
+    template: Option<&str>,
+) -> String {
     let mut prompt = base_prompt.to_string();
 
+    if let Some(template) = template.filter(|t| !t.trim().is_empty()) {
+        let tool_list = format_tool_list(tools);
+        let tool_names = tools.iter().map(|t| t.name.as_str()).collect::<Vec<_>>().join(", ");
+        let instructions = template.replace("{tool_names}", &tool_names);
+        prompt.push_str("\n\n");
+        if instructions.contains("{tools}") {
+            prompt.push_str(&instructions.replace("{tools}", &tool_list));
+        } else {
+            // Templates without the placeholder still need the tool list
+            prompt.push_str(&format!("{}\n\n{}", instructions, tool_list.trim_end()));
+        }
+        return prompt;
+    }
+
     prompt.push_str("\n\n## Available Tools\n\n");
     prompt.push_str("You have access to tools. To use a tool, you MUST respond with ONLY a JSON code block in this exact format:\n");
     prompt.push_str("```json\n{\"tool\": \"<tool_name>\", \"arguments\": {...}}\n```\n\n");
@@ -72,8 +93,16 @@ pub fn build_tool_system_prompt(base_prompt: &str, tools: &[ToolDefinition]) ->
     prompt.push_str("```json\n{\"tool\": \"get_current_time\", \"arguments\": {}}\n```\n\n");
     prompt.push_str("### Tools:\n\n");
 
+    prompt.push_str(&format_tool_list(tools));
+    prompt
+}
+
+/// Tool names, descriptions and parameters in the format the built-in
+/// instructions use
+fn format_tool_list(tools: &[ToolDefinition]) -> String {
+    let mut list = String::new();
     for tool in tools {
-        prompt.push_str(&format!("**{}**: {}\n", tool.name, tool.description));
+        list.push_str(&format!("**{}**: {}\n", tool.name, tool.description));
 
         // Add parameters from JSON schema
         if let Some(props) = tool.parameters.get("properties") {
@@ -84,7 +113,7 @@ pub fn build_tool_system_prompt(base_prompt: &str, tools: &[ToolDefinition]) ->
                 .map(|arr| arr.iter().filter_map(|v| v.as_str()).collect())
                 .unwrap_or_default();
 
-            prompt.push_str("Parameters:\n");
+            list.push_str("Parameters:\n");
             if let Some(obj) = props.as_object() {
                 for (name, schema) in obj {
                     let typ = schema.get("type").and_then(|t| t.as_str()).unwrap_or("any");
@@ -97,14 +126,14 @@ pub fn build_tool_system_prompt(base_prompt: &str, tools: &[ToolDefinition]) ->
                     } else {
                         "optional"
                     };
-                    prompt.push_str(&format!("- {} ({}, {}): {}\n", name, typ, req, desc));
+                    list.push_str(&format!("- {} ({}, {}): {}\n", name, typ, req, desc));
                 }
             }
         }
-        prompt.push('\n');
+        list.push('\n');
     }
 
-    prompt
+    list
 }
 
 /// Parse model output for tool calls
@@ -115,7 +144,7 @@ pub fn parse_tool_call(output: &str) -> ParsedToolCall {
         return parse_json_tool_call(json_str);
     }
 
-    // Try bare JSON object starting with {"tool":
+    // Try bare JSON object starting with {"tool": or {"tool_call":
     if let Some(m) = BARE_JSON_RE.find(output) {
         if let Some(json_str) = extract_json_object(&output[m.start()..]) {
             return parse_json_tool_call(&json_str);
@@ -126,13 +155,24 @@ pub fn parse_tool_call(output: &str) -> ParsedToolCall {
     ParsedToolCall::FinalAnswer(output.to_string())
 }
 
-/// Parse a JSON string as a tool call
+/// Parse a JSON string as a tool call. Besides the built-in
+/// `{"tool": ..., "arguments": ...}` format this accepts the
+/// `{"tool_call": {"name": ..., "arguments": ...}}` format custom tool prompt
+/// templates describe.
 fn parse_json_tool_call(json_str: &str) -> ParsedToolCall {
     match serde_json::from_str::<serde_json::Value>(json_str) {
         Ok(val) => {
+            let val = match val.get("tool_call") {
+                Some(inner) if inner.is_object() => serde_json::json!({
+                    "tool": inner.get("name"),
+                    "arguments": inner.get("arguments"),
+                }),
+                _ => val,
+            };
             let tool = val.get("tool").and_then(|t| t.as_str());
             let args = val
                 .get("arguments")
+                .filter(|a| !a.is_null())
                 .cloned()
                 .unwrap_or_else(|| serde_json::json!({}));
 
@@ -155,7 +195,7 @@ fn parse_json_tool_call(json_str: &str) -> ParsedToolCall {
 }
 
 /// Extract a complete JSON object from a string (balance braces)
-fn extract_json_object(s: &str) -> Option<String> {
+pub(crate) fn extract_json_object(s: &str) -> Option<String> {
     let mut depth = 0;
     let mut in_string = false;
     let mut escape = false;
@@ -235,6 +275,7 @@ pub async fn run_simulated_tool_loop(
             stream: false,
             tools: None, // Don't pass tools to non-native model
             tool_choice: None,
+            native_system_role: Some(false),
             ..Default::default()
         };
 
@@ -467,10 +508,29 @@ mod tests {
             }),
         }];
 
-        let result = build_tool_system_prompt(base, &tools);
+        let result = build_tool_system_prompt(base, &tools, None);
         assert!(result.contains("You are a helpful assistant"));
         assert!(result.contains("## Available Tools"));
         assert!(result.contains("**search**"));
         assert!(result.contains("query (string, required)"));
+
+        let template = "Call one of {tool_names} with {\"tool_call\": ...}.\n{tools}";
+        let result = build_tool_system_prompt(base, &tools, Some(template));
+        assert!(result.starts_with("You are a helpful assistant.\n\nCall one of search with"));
+        assert!(!result.contains("## Available Tools"));
+        assert!(result.contains("**search**: Search for text"));
+        assert!(result.contains("query (string, required)"));
+    }
+
+    #[test]
+    fn test_parse_tool_call_template_format() {
+        let output = r#"{"tool_call": {"name": "search_transcript", "arguments": {"query": "budget"}}}"#;
+        match parse_tool_call(output) {
+            ParsedToolCall::ToolRequest { tool, arguments } => {
+                assert_eq!(tool, "search_transcript");
+                assert_eq!(arguments["query"], "budget");
+            }
+            _ => panic!("Expected ToolRequest"),
+        }
     }
 }
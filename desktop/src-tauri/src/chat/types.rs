@@ -2,6 +2,9 @@
 
 use serde::{Deserialize, Serialize};
 
+use crate::database::TranscriptSegment;
+use crate::llm_engine::provider::ToolDefinition;
+
 /// Response when sending a chat message
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct SendMessageResponse {
@@ -9,6 +12,92 @@ pub struct SendMessageResponse {
     pub assistant_message_id: String,
 }
 
+/// Limits the transcript included as chat context to a time range and/or
+/// specific segments. With both set, a segment must match both.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct TranscriptScope {
+    /// Start of the range in seconds from the start of the recording
+    pub start_time: Option<f64>,
+    /// End of the range in seconds from the start of the recording
+    pub end_time: Option<f64>,
+    pub segment_ids: Option<Vec<String>>,
+}
+
+impl TranscriptScope {
+    /// Whether a segment falls inside the scope (segments overlapping the range count)
+    pub fn includes(&self, segment: &TranscriptSegment) -> bool {
+        let after_start = self.start_time.map_or(true, |start| segment.audio_end_time >= start);
+        let before_end = self.end_time.map_or(true, |end| segment.audio_start_time <= end);
+        let selected = self
+            .segment_ids
+            .as_ref()
+            .map_or(true, |ids| ids.iter().any(|id| *id == segment.id));
+        after_start && before_end && selected
+    }
+
+    /// Whether the scope restricts anything
+    pub fn is_limited(&self) -> bool {
+        self.start_time.is_some() || self.end_time.is_some() || self.segment_ids.is_some()
+    }
+}
+
+/// Size of the context a chat message would send to the LLM
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ContextTokenEstimate {
+    /// Tokens in the prompt (transcript, history and the new message)
+    pub prompt_tokens: usize,
+    /// Tokens reserved for the answer
+    pub max_completion_tokens: u32,
+    /// Context window of the loaded model, if the provider reports it
+    pub context_size: Option<usize>,
+    /// Whether prompt and answer fit in the context window (None if unknown)
+    pub fits: Option<bool>,
+    /// False when the count is a character-based estimate
+    pub exact: bool,
+}
+
+/// How tools are presented to the model for a chat session
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ToolCallingMode {
+    /// No tools are enabled for the session
+    None,
+    /// Tool definitions are sent natively with the request
+    Native,
+    /// Tools are described in the system prompt and calls parsed from the reply
+    PromptInjection,
+    /// Tools are sent natively but the embedded sidecar doesn't recognise the
+    /// model as tool-capable, so it injects them using the tool prompt template
+    SidecarPromptInjection,
+}
+
+/// A tool enabled for a chat session, as seen by the preview
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PreviewTool {
+    pub id: String,
+    pub name: String,
+    pub tool_type: String,
+    pub mcp_server_name: Option<String>,
+    /// False for MCP tools whose server isn't running
+    pub available: bool,
+}
+
+/// What the model would receive about tools for a chat session
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ToolPreview {
+    pub model_id: Option<String>,
+    pub mode: ToolCallingMode,
+    /// Result of tool support detection, including the user override
+    pub native_tool_support: bool,
+    /// User override for the model's tool support, if set
+    pub tool_support_override: Option<bool>,
+    pub tools: Vec<PreviewTool>,
+    /// Definitions sent with the request in native mode
+    pub tool_definitions: Vec<ToolDefinition>,
+    /// Tool instructions appended to the system prompt in prompt-injection mode
+    pub tool_prompt: Option<String>,
+}
+
 /// Chat message status for frontend
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ChatMessageStatus2 {
@@ -5,7 +5,7 @@ use anyhow::{Context, Result};
 use rusqlite::{Connection, params};
 use uuid::Uuid;
 
-use super::models::{Category, Tag};
+use super::models::{Category, CategoryWithCount, Tag, UNCATEGORIZED_CATEGORY_ID};
 use super::DatabaseManager;
 
 impl DatabaseManager {
@@ -18,6 +18,13 @@ impl DatabaseManager {
         })
     }
 
+    /// Get all categories with their recording counts, plus an "Uncategorized" bucket
+    pub fn get_categories_with_counts(&self) -> Result<Vec<CategoryWithCount>> {
+        self.with_connection(|conn| {
+            get_categories_with_counts_impl(conn)
+        })
+    }
+
     /// Get a category by ID
     pub fn get_category(&self, id: &str) -> Result<Option<Category>> {
         self.with_connection(|conn| {
@@ -97,6 +104,21 @@ impl DatabaseManager {
         })
     }
 
+    /// Delete tags that aren't assigned to any recording, returning how many were removed.
+    /// Only tags are affected; categories (including system ones) are never touched.
+    pub fn delete_unused_tags(&self) -> Result<usize> {
+        self.with_connection(|conn| {
+            delete_unused_tags_impl(conn)
+        })
+    }
+
+    /// Get the most used tags, ordered by usage count
+    pub fn get_popular_tags(&self, limit: usize) -> Result<Vec<Tag>> {
+        self.with_connection(|conn| {
+            get_popular_tags_impl(conn, limit)
+        })
+    }
+
     /// Get or create a tag by name
     pub fn get_or_create_tag(&self, name: &str, color: Option<&str>) -> Result<String> {
         self.with_connection(|conn| {
@@ -125,6 +147,49 @@ fn get_all_categories_impl(conn: &Connection) -> Result<Vec<Category>> {
         .context("Failed to collect categories")
 }
 
+fn get_categories_with_counts_impl(conn: &Connection) -> Result<Vec<CategoryWithCount>> {
+    let mut stmt = conn.prepare(
+        r#"SELECT c.id, c.name, c.color, c.is_system, COUNT(rc.recording_id)
+           FROM categories c
+           LEFT JOIN recording_categories rc ON rc.category_id = c.id
+           GROUP BY c.id
+           ORDER BY c.is_system DESC, c.name ASC"#
+    ).context("Failed to prepare get_categories_with_counts query")?;
+
+    let mut categories = stmt.query_map([], |row| {
+        Ok(CategoryWithCount {
+            category: Category {
+                id: row.get(0)?,
+                name: row.get(1)?,
+                color: row.get(2)?,
+                is_system: row.get::<_, i32>(3)? == 1,
+            },
+            recording_count: row.get(4)?,
+        })
+    }).context("Failed to query categories with counts")?
+        .collect::<std::result::Result<Vec<_>, _>>()
+        .context("Failed to collect categories with counts")?;
+
+    let uncategorized: i64 = conn.query_row(
+        r#"SELECT COUNT(*) FROM recordings r
+           WHERE NOT EXISTS (SELECT 1 FROM recording_categories rc WHERE rc.recording_id = r.id)"#,
+        [],
+        |row| row.get(0),
+    ).context("Failed to count uncategorized recordings")?;
+
+    categories.push(CategoryWithCount {
+        category: Category {
+            id: UNCATEGORIZED_CATEGORY_ID.to_string(),
+            name: "Uncategorized".to_string(),
+            color: None,
+            is_system: true,
+        },
+        recording_count: uncategorized,
+    });
+
+    Ok(categories)
+}
+
 fn get_category_impl(conn: &Connection, id: &str) -> Result<Option<Category>> {
     let mut stmt = conn.prepare(
         "SELECT id, name, color, is_system FROM categories WHERE id = ?"
@@ -245,38 +310,54 @@ fn delete_tag_impl(conn: &Connection, id: &str) -> Result<()> {
 }
 
 fn assign_tag_impl(conn: &Connection, recording_id: &str, tag_id: &str) -> Result<()> {
-    // Insert the association
+    // usage_count is maintained by the recording_tags triggers (migration v12)
     conn.execute(
         "INSERT OR IGNORE INTO recording_tags (recording_id, tag_id) VALUES (?1, ?2)",
         params![recording_id, tag_id],
     ).context("Failed to assign tag")?;
 
-    // Increment usage count
-    conn.execute(
-        "UPDATE tags SET usage_count = usage_count + 1 WHERE id = ?",
-        params![tag_id],
-    ).context("Failed to update tag usage count")?;
-
     Ok(())
 }
 
 fn remove_tag_impl(conn: &Connection, recording_id: &str, tag_id: &str) -> Result<()> {
-    let changes = conn.execute(
+    // usage_count is maintained by the recording_tags triggers (migration v12)
+    conn.execute(
         "DELETE FROM recording_tags WHERE recording_id = ? AND tag_id = ?",
         params![recording_id, tag_id],
     ).context("Failed to remove tag")?;
 
-    // Decrement usage count if we actually removed something
-    if changes > 0 {
-        conn.execute(
-            "UPDATE tags SET usage_count = MAX(0, usage_count - 1) WHERE id = ?",
-            params![tag_id],
-        ).context("Failed to update tag usage count")?;
-    }
-
     Ok(())
 }
 
+fn delete_unused_tags_impl(conn: &Connection) -> Result<usize> {
+    let deleted = conn.execute(
+        r#"DELETE FROM tags
+           WHERE usage_count = 0
+             AND NOT EXISTS (SELECT 1 FROM recording_tags rt WHERE rt.tag_id = tags.id)"#,
+        [],
+    ).context("Failed to delete unused tags")?;
+
+    Ok(deleted)
+}
+
+fn get_popular_tags_impl(conn: &Connection, limit: usize) -> Result<Vec<Tag>> {
+    let mut stmt = conn.prepare(
+        "SELECT id, name, color, usage_count FROM tags WHERE usage_count > 0 ORDER BY usage_count DESC, name ASC LIMIT ?"
+    ).context("Failed to prepare get_popular_tags query")?;
+
+    let tags = stmt.query_map(params![limit as i64], |row| {
+        Ok(Tag {
+            id: row.get(0)?,
+            name: row.get(1)?,
+            color: row.get(2)?,
+            usage_count: row.get(3)?,
+        })
+    }).context("Failed to query popular tags")?;
+
+    tags.collect::<std::result::Result<Vec<_>, _>>()
+        .context("Failed to collect popular tags")
+}
+
 fn get_or_create_tag_impl(conn: &Connection, name: &str, color: Option<&str>) -> Result<String> {
     // Try to find existing tag
     let mut stmt = conn.prepare(
@@ -330,6 +411,29 @@ mod tests {
         assert!(!category.is_system);
     }
 
+    #[test]
+    fn test_get_categories_with_counts() {
+        let db = create_test_db();
+
+        let category_id = db.create_category("Counted", None).unwrap();
+        for id in ["rec_count_1", "rec_count_2", "rec_count_3"] {
+            db.create_recording(&Recording::new(id.to_string(), id.to_string())).unwrap();
+        }
+        db.assign_category("rec_count_1", &category_id).unwrap();
+        db.assign_category("rec_count_2", &category_id).unwrap();
+
+        let counts = db.get_categories_with_counts().unwrap();
+
+        let counted = counts.iter().find(|c| c.category.id == category_id).unwrap();
+        assert_eq!(counted.recording_count, 2);
+
+        let daily = counts.iter().find(|c| c.category.name == "Daily").unwrap();
+        assert_eq!(daily.recording_count, 0);
+
+        let uncategorized = counts.iter().find(|c| c.category.id == UNCATEGORIZED_CATEGORY_ID).unwrap();
+        assert_eq!(uncategorized.recording_count, 1);
+    }
+
     #[test]
     fn test_create_and_assign_tag() {
         let db = create_test_db();
@@ -348,6 +452,58 @@ mod tests {
         assert_eq!(tag.usage_count, 1);
     }
 
+    #[test]
+    fn test_tag_usage_count_maintenance() {
+        let db = create_test_db();
+
+        for id in ["rec_usage_1", "rec_usage_2"] {
+            db.create_recording(&Recording::new(id.to_string(), id.to_string())).unwrap();
+        }
+        let popular = db.create_tag("Popular", None).unwrap();
+        let rare = db.create_tag("Rare", None).unwrap();
+        let unused = db.create_tag("Unused", None).unwrap();
+
+        db.assign_tag("rec_usage_1", &popular).unwrap();
+        db.assign_tag("rec_usage_1", &popular).unwrap(); // duplicate is ignored
+        db.assign_tag("rec_usage_2", &popular).unwrap();
+        db.assign_tag("rec_usage_1", &rare).unwrap();
+
+        let tags = db.get_popular_tags(10).unwrap();
+        assert_eq!(tags.len(), 2);
+        assert_eq!(tags[0].id, popular);
+        assert_eq!(tags[0].usage_count, 2);
+        assert!(tags.iter().all(|t| t.id != unused));
+
+        // Removing a tag and deleting a recording both decrement
+        db.remove_tag("rec_usage_1", &rare).unwrap();
+        db.delete_recording("rec_usage_2").unwrap();
+        assert_eq!(db.get_tag(&rare).unwrap().unwrap().usage_count, 0);
+        assert_eq!(db.get_tag(&popular).unwrap().unwrap().usage_count, 1);
+
+        assert_eq!(db.get_popular_tags(1).unwrap().len(), 1);
+    }
+
+    #[test]
+    fn test_delete_unused_tags() {
+        let db = create_test_db();
+
+        db.create_recording(&Recording::new("rec_cleanup".to_string(), "Cleanup".to_string())).unwrap();
+        let used = db.create_tag("Used", None).unwrap();
+        let orphan = db.create_tag("Orphan", None).unwrap();
+        db.assign_tag("rec_cleanup", &used).unwrap();
+        let categories_before = db.get_all_categories().unwrap().len();
+
+        assert_eq!(db.delete_unused_tags().unwrap(), 1);
+        assert!(db.get_tag(&orphan).unwrap().is_none());
+        assert!(db.get_tag(&used).unwrap().is_some());
+        assert_eq!(db.get_all_categories().unwrap().len(), categories_before);
+
+        // Deleting the recording orphans the remaining tag
+        db.delete_recording("rec_cleanup").unwrap();
+        assert_eq!(db.delete_unused_tags().unwrap(), 1);
+        assert!(db.get_all_tags().unwrap().is_empty());
+    }
+
     #[test]
     fn test_get_or_create_tag() {
         let db = create_test_db();
@@ -57,6 +57,28 @@ impl DatabaseManager {
         })
     }
 
+    /// Record token counts and generation time for a message
+    pub fn update_chat_message_metrics(
+        &self,
+        message_id: &str,
+        token_count: Option<i64>,
+        prompt_tokens: Option<i64>,
+        generation_ms: i64,
+    ) -> Result<()> {
+        self.with_connection(|conn| {
+            update_chat_message_metrics_impl(conn, message_id, token_count, prompt_tokens, generation_ms)
+        })
+    }
+
+    /// Finalize a cancelled in-progress message. `partial` (if given) replaces
+    /// the content; the message becomes `Stopped` if it has any content and
+    /// `Cancelled` otherwise. Finished messages are left untouched.
+    pub fn stop_chat_message(&self, message_id: &str, partial: Option<&str>) -> Result<()> {
+        self.with_connection(|conn| {
+            stop_chat_message_impl(conn, message_id, partial)
+        })
+    }
+
     /// Update the status of a chat message
     pub fn update_chat_message_status(
         &self,
@@ -69,6 +91,39 @@ impl DatabaseManager {
         })
     }
 
+    /// Clear an assistant message so it can be regenerated with the given provider/model
+    pub fn reset_chat_message_for_regeneration(
+        &self,
+        message_id: &str,
+        provider_type: Option<&str>,
+        model_id: Option<&str>,
+    ) -> Result<()> {
+        self.with_connection(|conn| {
+            conn.execute(
+                r#"UPDATE chat_messages
+                   SET content = '', status = ?, error_message = NULL, provider_type = ?, model_id = ?,
+                       token_count = NULL, prompt_tokens = NULL, generation_ms = NULL
+                   WHERE id = ?"#,
+                params![ChatMessageStatus::Pending.as_str(), provider_type, model_id, message_id],
+            ).context("Failed to reset chat message for regeneration")?;
+            Ok(())
+        })
+    }
+
+    /// Replace messages with a summary message, moving the originals to the archive
+    pub fn compact_chat_messages(&self, message_ids: &[String], summary: &ChatMessage) -> Result<()> {
+        self.with_connection(|conn| {
+            compact_chat_messages_impl(conn, message_ids, summary)
+        })
+    }
+
+    /// Get the archived (compacted) messages of a session
+    pub fn get_archived_chat_messages(&self, session_id: &str) -> Result<Vec<ChatMessage>> {
+        self.with_connection(|conn| {
+            get_archived_chat_messages_impl(conn, session_id)
+        })
+    }
+
     /// Delete all chat messages for a session
     pub fn delete_chat_messages_by_session(&self, session_id: &str) -> Result<()> {
         self.with_connection(|conn| {
@@ -110,14 +165,18 @@ fn save_chat_message_impl(conn: &Connection, message: &ChatMessage) -> Result<()
         r#"
         INSERT INTO chat_messages (
             id, recording_id, session_id, role, content, created_at,
-            sequence_id, status, error_message, provider_type, model_id
-        ) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11)
+            sequence_id, status, error_message, provider_type, model_id,
+            token_count, prompt_tokens, generation_ms
+        ) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13, ?14)
         ON CONFLICT(id) DO UPDATE SET
             content = excluded.content,
             status = excluded.status,
             error_message = excluded.error_message,
             provider_type = excluded.provider_type,
-            model_id = excluded.model_id
+            model_id = excluded.model_id,
+            token_count = excluded.token_count,
+            prompt_tokens = excluded.prompt_tokens,
+            generation_ms = excluded.generation_ms
         "#,
         params![
             message.id,
@@ -131,6 +190,9 @@ fn save_chat_message_impl(conn: &Connection, message: &ChatMessage) -> Result<()
             message.error_message,
             message.provider_type,
             message.model_id,
+            message.token_count,
+            message.prompt_tokens,
+            message.generation_ms,
         ],
     ).context("Failed to save chat message")?;
 
@@ -141,7 +203,8 @@ fn get_chat_messages_by_session_impl(conn: &Connection, session_id: &str) -> Res
     let mut stmt = conn.prepare(
         r#"
         SELECT id, recording_id, session_id, role, content, created_at,
-               sequence_id, status, error_message, provider_type, model_id
+               sequence_id, status, error_message, provider_type, model_id,
+               token_count, prompt_tokens, generation_ms
         FROM chat_messages
         WHERE session_id = ?
         ORDER BY sequence_id ASC
@@ -161,6 +224,9 @@ fn get_chat_messages_by_session_impl(conn: &Connection, session_id: &str) -> Res
             error_message: row.get(8)?,
             provider_type: row.get(9)?,
             model_id: row.get(10)?,
+            token_count: row.get(11)?,
+            prompt_tokens: row.get(12)?,
+            generation_ms: row.get(13)?,
         })
     }).context("Failed to query chat messages")?;
 
@@ -172,7 +238,8 @@ fn get_chat_messages_impl(conn: &Connection, recording_id: &str) -> Result<Vec<C
     let mut stmt = conn.prepare(
         r#"
         SELECT id, recording_id, session_id, role, content, created_at,
-               sequence_id, status, error_message, provider_type, model_id
+               sequence_id, status, error_message, provider_type, model_id,
+               token_count, prompt_tokens, generation_ms
         FROM chat_messages
         WHERE recording_id = ?
         ORDER BY sequence_id ASC
@@ -192,6 +259,9 @@ fn get_chat_messages_impl(conn: &Connection, recording_id: &str) -> Result<Vec<C
             error_message: row.get(8)?,
             provider_type: row.get(9)?,
             model_id: row.get(10)?,
+            token_count: row.get(11)?,
+            prompt_tokens: row.get(12)?,
+            generation_ms: row.get(13)?,
         })
     }).context("Failed to query chat messages")?;
 
@@ -203,7 +273,8 @@ fn get_chat_message_impl(conn: &Connection, message_id: &str) -> Result<Option<C
     let mut stmt = conn.prepare(
         r#"
         SELECT id, recording_id, session_id, role, content, created_at,
-               sequence_id, status, error_message, provider_type, model_id
+               sequence_id, status, error_message, provider_type, model_id,
+               token_count, prompt_tokens, generation_ms
         FROM chat_messages
         WHERE id = ?
         "#
@@ -222,6 +293,9 @@ fn get_chat_message_impl(conn: &Connection, message_id: &str) -> Result<Option<C
             error_message: row.get(8)?,
             provider_type: row.get(9)?,
             model_id: row.get(10)?,
+            token_count: row.get(11)?,
+            prompt_tokens: row.get(12)?,
+            generation_ms: row.get(13)?,
         })
     });
 
@@ -261,6 +335,34 @@ fn update_chat_message_content_impl(conn: &Connection, message_id: &str, content
     Ok(())
 }
 
+fn update_chat_message_metrics_impl(
+    conn: &Connection,
+    message_id: &str,
+    token_count: Option<i64>,
+    prompt_tokens: Option<i64>,
+    generation_ms: i64,
+) -> Result<()> {
+    conn.execute(
+        "UPDATE chat_messages SET token_count = ?, prompt_tokens = ?, generation_ms = ? WHERE id = ?",
+        params![token_count, prompt_tokens, generation_ms, message_id],
+    ).context("Failed to update chat message metrics")?;
+
+    Ok(())
+}
+
+fn stop_chat_message_impl(conn: &Connection, message_id: &str, partial: Option<&str>) -> Result<()> {
+    conn.execute(
+        "UPDATE chat_messages
+         SET content = COALESCE(?2, content),
+             status = CASE WHEN COALESCE(?2, content) <> '' THEN 'stopped' ELSE 'cancelled' END,
+             error_message = NULL
+         WHERE id = ?1 AND status IN ('pending', 'streaming', 'cancelled', 'stopped')",
+        params![message_id, partial],
+    ).context("Failed to stop chat message")?;
+
+    Ok(())
+}
+
 fn update_chat_message_status_impl(
     conn: &Connection,
     message_id: &str,
@@ -281,9 +383,82 @@ fn delete_chat_messages_by_session_impl(conn: &Connection, session_id: &str) ->
         params![session_id],
     ).context("Failed to delete chat messages for session")?;
 
+    conn.execute(
+        "DELETE FROM chat_message_archive WHERE session_id = ?",
+        params![session_id],
+    ).context("Failed to delete archived chat messages for session")?;
+
+    Ok(())
+}
+
+fn compact_chat_messages_impl(conn: &Connection, message_ids: &[String], summary: &ChatMessage) -> Result<()> {
+    let tx = conn.unchecked_transaction()
+        .context("Failed to start compaction transaction")?;
+
+    for message_id in message_ids {
+        tx.execute(
+            r#"
+            INSERT INTO chat_message_archive (
+                id, recording_id, session_id, role, content, created_at,
+                sequence_id, status, error_message, provider_type, model_id,
+                token_count, prompt_tokens, generation_ms, summary_message_id
+            )
+            SELECT id, recording_id, session_id, role, content, created_at,
+                   sequence_id, status, error_message, provider_type, model_id,
+                   token_count, prompt_tokens, generation_ms, ?2
+            FROM chat_messages
+            WHERE id = ?1
+            "#,
+            params![message_id, summary.id],
+        ).context("Failed to archive chat message")?;
+
+        tx.execute(
+            "DELETE FROM chat_messages WHERE id = ?",
+            params![message_id],
+        ).context("Failed to remove archived chat message")?;
+    }
+
+    save_chat_message_impl(&tx, summary)?;
+
+    tx.commit().context("Failed to commit compaction")?;
     Ok(())
 }
 
+fn get_archived_chat_messages_impl(conn: &Connection, session_id: &str) -> Result<Vec<ChatMessage>> {
+    let mut stmt = conn.prepare(
+        r#"
+        SELECT id, recording_id, session_id, role, content, created_at,
+               sequence_id, status, error_message, provider_type, model_id,
+               token_count, prompt_tokens, generation_ms
+        FROM chat_message_archive
+        WHERE session_id = ?
+        ORDER BY sequence_id ASC
+        "#
+    ).context("Failed to prepare get_archived_chat_messages query")?;
+
+    let messages = stmt.query_map(params![session_id], |row| {
+        Ok(ChatMessage {
+            id: row.get(0)?,
+            recording_id: row.get(1)?,
+            session_id: row.get(2)?,
+            role: ChatRole::from_str(&row.get::<_, String>(3)?),
+            content: row.get(4)?,
+            created_at: row.get(5)?,
+            sequence_id: row.get(6)?,
+            status: ChatMessageStatus::from_str(&row.get::<_, String>(7)?),
+            error_message: row.get(8)?,
+            provider_type: row.get(9)?,
+            model_id: row.get(10)?,
+            token_count: row.get(11)?,
+            prompt_tokens: row.get(12)?,
+            generation_ms: row.get(13)?,
+        })
+    }).context("Failed to query archived chat messages")?;
+
+    messages.collect::<std::result::Result<Vec<_>, _>>()
+        .context("Failed to collect archived chat messages")
+}
+
 fn delete_chat_messages_impl(conn: &Connection, recording_id: &str) -> Result<()> {
     conn.execute(
         "DELETE FROM chat_messages WHERE recording_id = ?",
@@ -297,7 +472,8 @@ fn get_pending_chat_messages_impl(conn: &Connection) -> Result<Vec<ChatMessage>>
     let mut stmt = conn.prepare(
         r#"
         SELECT id, recording_id, session_id, role, content, created_at,
-               sequence_id, status, error_message, provider_type, model_id
+               sequence_id, status, error_message, provider_type, model_id,
+               token_count, prompt_tokens, generation_ms
         FROM chat_messages
         WHERE status IN ('pending', 'streaming')
         ORDER BY created_at ASC
@@ -317,6 +493,9 @@ fn get_pending_chat_messages_impl(conn: &Connection) -> Result<Vec<ChatMessage>>
             error_message: row.get(8)?,
             provider_type: row.get(9)?,
             model_id: row.get(10)?,
+            token_count: row.get(11)?,
+            prompt_tokens: row.get(12)?,
+            generation_ms: row.get(13)?,
         })
     }).context("Failed to query pending chat messages")?;
 
@@ -0,0 +1,106 @@
+// Recording insights repository for Meeting-Local
+// Stores the decisions, risks and open questions extracted from each recording
+
+use anyhow::{Context, Result};
+use rusqlite::{params, Row};
+
+use super::models::RecordingInsights;
+use super::DatabaseManager;
+
+impl DatabaseManager {
+    /// Save the insights for a recording, replacing any previous ones
+    pub fn save_recording_insights(&self, insights: &RecordingInsights) -> Result<()> {
+        self.with_connection(|conn| {
+            conn.execute(
+                r#"INSERT OR REPLACE INTO recording_insights
+                   (recording_id, decisions, risks, open_questions, model_id, created_at)
+                   VALUES (?1, ?2, ?3, ?4, ?5, ?6)"#,
+                params![
+                    insights.recording_id,
+                    serde_json::to_string(&insights.decisions)?,
+                    serde_json::to_string(&insights.risks)?,
+                    serde_json::to_string(&insights.open_questions)?,
+                    insights.model_id,
+                    insights.created_at,
+                ],
+            ).context("Failed to save recording insights")?;
+            Ok(())
+        })
+    }
+
+    /// Get the stored insights for a recording
+    pub fn get_recording_insights(&self, recording_id: &str) -> Result<Option<RecordingInsights>> {
+        self.with_connection(|conn| {
+            let result = conn.query_row(
+                r#"SELECT recording_id, decisions, risks, open_questions, model_id, created_at
+                   FROM recording_insights WHERE recording_id = ?"#,
+                params![recording_id],
+                row_to_insights,
+            );
+
+            match result {
+                Ok(insights) => Ok(Some(insights)),
+                Err(rusqlite::Error::QueryReturnedNoRows) => Ok(None),
+                Err(e) => Err(e).context("Failed to get recording insights"),
+            }
+        })
+    }
+}
+
+/// Lists are JSON arrays; unreadable ones come back empty
+fn json_list(row: &Row, index: usize) -> rusqlite::Result<Vec<String>> {
+    let json: String = row.get(index)?;
+    Ok(serde_json::from_str(&json).unwrap_or_default())
+}
+
+fn row_to_insights(row: &Row) -> rusqlite::Result<RecordingInsights> {
+    Ok(RecordingInsights {
+        recording_id: row.get(0)?,
+        decisions: json_list(row, 1)?,
+        risks: json_list(row, 2)?,
+        open_questions: json_list(row, 3)?,
+        model_id: row.get(4)?,
+        created_at: row.get(5)?,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::database::models::Recording;
+    use tempfile::tempdir;
+
+    fn create_test_db() -> DatabaseManager {
+        let dir = tempdir().unwrap();
+        let db_path = dir.path().join("test.db");
+        DatabaseManager::new(db_path).unwrap()
+    }
+
+    #[test]
+    fn test_save_and_get_insights() {
+        let db = create_test_db();
+        db.create_recording(&Recording::new("rec_ins".to_string(), "Insights".to_string())).unwrap();
+        assert!(db.get_recording_insights("rec_ins").unwrap().is_none());
+
+        let mut insights = RecordingInsights {
+            recording_id: "rec_ins".to_string(),
+            decisions: vec!["Ship on Friday".to_string()],
+            risks: vec!["QA is understaffed".to_string()],
+            open_questions: vec![],
+            model_id: Some("test-model".to_string()),
+            created_at: "2024-01-01T00:00:00Z".to_string(),
+        };
+        db.save_recording_insights(&insights).unwrap();
+
+        let stored = db.get_recording_insights("rec_ins").unwrap().unwrap();
+        assert_eq!(stored.decisions, vec!["Ship on Friday".to_string()]);
+        assert_eq!(stored.risks.len(), 1);
+        assert!(stored.open_questions.is_empty());
+        assert_eq!(stored.model_id.as_deref(), Some("test-model"));
+
+        // Saving again replaces the previous insights
+        insights.decisions.clear();
+        db.save_recording_insights(&insights).unwrap();
+        assert!(db.get_recording_insights("rec_ins").unwrap().unwrap().decisions.is_empty());
+    }
+}
@@ -69,6 +69,11 @@ impl DatabaseManager {
     pub fn db_path(&self) -> &PathBuf {
         &self.db_path
     }
+
+    /// Get the schema version the database has been migrated to
+    pub fn schema_version(&self) -> Result<i32> {
+        self.with_connection(migrations::get_schema_version)
+    }
 }
 
 #[cfg(test)]
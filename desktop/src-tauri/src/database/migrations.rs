@@ -5,7 +5,7 @@ use anyhow::{Context, Result};
 use rusqlite::Connection;
 
 /// Current schema version
-const SCHEMA_VERSION: i32 = 10;
+const SCHEMA_VERSION: i32 = 22;
 
 /// Run all necessary migrations to bring the database up to date
 pub fn run_migrations(conn: &Connection) -> Result<()> {
@@ -51,11 +51,59 @@ pub fn run_migrations(conn: &Connection) -> Result<()> {
         migrate_v10(conn)?;
     }
 
+    if current_version < 11 {
+        migrate_v11(conn)?;
+    }
+
+    if current_version < 12 {
+        migrate_v12(conn)?;
+    }
+
+    if current_version < 13 {
+        migrate_v13(conn)?;
+    }
+
+    if current_version < 14 {
+        migrate_v14(conn)?;
+    }
+
+    if current_version < 15 {
+        migrate_v15(conn)?;
+    }
+
+    if current_version < 16 {
+        migrate_v16(conn)?;
+    }
+
+    if current_version < 17 {
+        migrate_v17(conn)?;
+    }
+
+    if current_version < 18 {
+        migrate_v18(conn)?;
+    }
+
+    if current_version < 19 {
+        migrate_v19(conn)?;
+    }
+
+    if current_version < 20 {
+        migrate_v20(conn)?;
+    }
+
+    if current_version < 21 {
+        migrate_v21(conn)?;
+    }
+
+    if current_version < 22 {
+        migrate_v22(conn)?;
+    }
+
     Ok(())
 }
 
 /// Get the current schema version from the database
-fn get_schema_version(conn: &Connection) -> Result<i32> {
+pub(crate) fn get_schema_version(conn: &Connection) -> Result<i32> {
     // Check if schema_version table exists
     let table_exists: bool = conn.query_row(
         "SELECT COUNT(*) > 0 FROM sqlite_master WHERE type='table' AND name='schema_version'",
@@ -564,6 +612,338 @@ fn migrate_v10(conn: &Connection) -> Result<()> {
     Ok(())
 }
 
+/// Scheduled recordings schema (version 11) - Auto-start recordings for recurring meetings
+fn migrate_v11(conn: &Connection) -> Result<()> {
+    log::info!("Running database migration v11 - Scheduled recordings");
+
+    conn.execute_batch(r#"
+        -- Scheduled recordings table: Recordings started automatically by the scheduler
+        CREATE TABLE IF NOT EXISTS scheduled_recordings (
+            id TEXT PRIMARY KEY NOT NULL,
+            name TEXT NOT NULL,
+            start_time TEXT NOT NULL,
+            recurrence TEXT NOT NULL DEFAULT 'once',
+            duration_minutes INTEGER,
+            mic_device TEXT,
+            system_device TEXT,
+            enabled INTEGER DEFAULT 1,
+            last_triggered_at TEXT,
+            created_at TEXT NOT NULL DEFAULT (datetime('now'))
+        );
+
+        -- Index for the scheduler's enabled-schedules scan
+        CREATE INDEX IF NOT EXISTS idx_scheduled_recordings_enabled
+        ON scheduled_recordings(enabled) WHERE enabled = 1;
+
+        -- Record migration
+        INSERT INTO schema_version (version) VALUES (11);
+    "#).context("Failed to run migration v11")?;
+
+    log::info!("Migration v11 completed successfully");
+    Ok(())
+}
+
+/// Tag usage counts (version 12) - Keep tags.usage_count in sync with recording_tags
+fn migrate_v12(conn: &Connection) -> Result<()> {
+    log::info!("Running database migration v12 - Tag usage count maintenance");
+
+    conn.execute_batch(r#"
+        -- Triggers also fire for rows removed by ON DELETE CASCADE (recording deletion)
+        CREATE TRIGGER IF NOT EXISTS trg_recording_tags_insert
+        AFTER INSERT ON recording_tags
+        BEGIN
+            UPDATE tags SET usage_count = usage_count + 1 WHERE id = NEW.tag_id;
+        END;
+
+        CREATE TRIGGER IF NOT EXISTS trg_recording_tags_delete
+        AFTER DELETE ON recording_tags
+        BEGIN
+            UPDATE tags SET usage_count = MAX(0, usage_count - 1) WHERE id = OLD.tag_id;
+        END;
+
+        -- Backfill: correct counts that drifted before the triggers existed
+        UPDATE tags SET usage_count = (
+            SELECT COUNT(*) FROM recording_tags WHERE recording_tags.tag_id = tags.id
+        );
+
+        -- Index for popular tag queries
+        CREATE INDEX IF NOT EXISTS idx_tags_usage_count ON tags(usage_count DESC);
+
+        -- Record migration
+        INSERT INTO schema_version (version) VALUES (12);
+    "#).context("Failed to run migration v12")?;
+
+    log::info!("Migration v12 completed successfully");
+    Ok(())
+}
+
+/// Retranscription queue schema (version 13) - Persistent batch retranscription jobs
+fn migrate_v13(conn: &Connection) -> Result<()> {
+    log::info!("Running database migration v13 - Retranscription queue");
+
+    conn.execute_batch(r#"
+        -- Retranscription queue: Jobs processed sequentially by the background worker
+        CREATE TABLE IF NOT EXISTS retranscription_queue (
+            id TEXT PRIMARY KEY NOT NULL,
+            recording_id TEXT NOT NULL,
+            options TEXT NOT NULL DEFAULT '{}',
+            position INTEGER NOT NULL,
+            status TEXT NOT NULL DEFAULT 'pending',
+            error TEXT,
+            created_at TEXT NOT NULL DEFAULT (datetime('now')),
+            FOREIGN KEY (recording_id) REFERENCES recordings(id) ON DELETE CASCADE
+        );
+
+        -- Index for picking the next pending job
+        CREATE INDEX IF NOT EXISTS idx_retranscription_queue_position
+        ON retranscription_queue(status, position);
+
+        -- Record migration
+        INSERT INTO schema_version (version) VALUES (13);
+    "#).context("Failed to run migration v13")?;
+
+    log::info!("Migration v13 completed successfully");
+    Ok(())
+}
+
+/// Speaker embeddings schema (version 14) - Per-recording speaker centroids for later matching
+fn migrate_v14(conn: &Connection) -> Result<()> {
+    log::info!("Running database migration v14 - Recording speaker embeddings");
+
+    conn.execute_batch(r#"
+        -- Centroid embedding of each speaker detected in a recording
+        CREATE TABLE IF NOT EXISTS recording_speaker_embeddings (
+            recording_id TEXT NOT NULL,
+            speaker_id TEXT NOT NULL,
+            speaker_label TEXT NOT NULL,
+            embedding BLOB NOT NULL,
+            segment_count INTEGER DEFAULT 0,
+            registered_speaker_id TEXT,
+            created_at TEXT NOT NULL DEFAULT (datetime('now')),
+            PRIMARY KEY (recording_id, speaker_id),
+            FOREIGN KEY (recording_id) REFERENCES recordings(id) ON DELETE CASCADE
+        );
+
+        -- Record migration
+        INSERT INTO schema_version (version) VALUES (14);
+    "#).context("Failed to run migration v14")?;
+
+    log::info!("Migration v14 completed successfully");
+    Ok(())
+}
+
+/// Chat message metrics (version 15) - Token counts and generation time per message
+fn migrate_v15(conn: &Connection) -> Result<()> {
+    log::info!("Running database migration v15 - Chat message metrics");
+
+    conn.execute_batch(r#"
+        -- Tokens generated, prompt tokens and wall time of assistant messages
+        ALTER TABLE chat_messages ADD COLUMN token_count INTEGER;
+        ALTER TABLE chat_messages ADD COLUMN prompt_tokens INTEGER;
+        ALTER TABLE chat_messages ADD COLUMN generation_ms INTEGER;
+
+        -- Record migration
+        INSERT INTO schema_version (version) VALUES (15);
+    "#).context("Failed to run migration v15")?;
+
+    log::info!("Migration v15 completed successfully");
+    Ok(())
+}
+
+/// Chat message archive (version 16) - Messages replaced by a summary when a session is compacted
+fn migrate_v16(conn: &Connection) -> Result<()> {
+    log::info!("Running database migration v16 - Chat message archive");
+
+    conn.execute_batch(r#"
+        -- Original messages of compacted chat sessions
+        CREATE TABLE IF NOT EXISTS chat_message_archive (
+            id TEXT PRIMARY KEY NOT NULL,
+            recording_id TEXT NOT NULL,
+            session_id TEXT NOT NULL,
+            role TEXT NOT NULL,
+            content TEXT NOT NULL,
+            created_at TEXT NOT NULL,
+            sequence_id INTEGER NOT NULL,
+            status TEXT NOT NULL,
+            error_message TEXT,
+            provider_type TEXT,
+            model_id TEXT,
+            token_count INTEGER,
+            prompt_tokens INTEGER,
+            generation_ms INTEGER,
+            summary_message_id TEXT NOT NULL,
+            archived_at TEXT NOT NULL DEFAULT (datetime('now')),
+            FOREIGN KEY (recording_id) REFERENCES recordings(id) ON DELETE CASCADE,
+            FOREIGN KEY (session_id) REFERENCES chat_sessions(id) ON DELETE CASCADE
+        );
+
+        -- Index for listing a session's archived messages in order
+        CREATE INDEX IF NOT EXISTS idx_chat_message_archive_session
+        ON chat_message_archive(session_id, sequence_id);
+
+        -- Record migration
+        INSERT INTO schema_version (version) VALUES (16);
+    "#).context("Failed to run migration v16")?;
+
+    log::info!("Migration v16 completed successfully");
+    Ok(())
+}
+
+/// Recording insights (version 17) - LLM-extracted decisions, risks and open questions
+fn migrate_v17(conn: &Connection) -> Result<()> {
+    log::info!("Running database migration v17 - Recording insights");
+
+    conn.execute_batch(r#"
+        -- One set of insights per recording; lists are stored as JSON arrays
+        CREATE TABLE IF NOT EXISTS recording_insights (
+            recording_id TEXT PRIMARY KEY NOT NULL,
+            decisions TEXT NOT NULL DEFAULT '[]',
+            risks TEXT NOT NULL DEFAULT '[]',
+            open_questions TEXT NOT NULL DEFAULT '[]',
+            model_id TEXT,
+            created_at TEXT NOT NULL DEFAULT (datetime('now')),
+            FOREIGN KEY (recording_id) REFERENCES recordings(id) ON DELETE CASCADE
+        );
+
+        -- Record migration
+        INSERT INTO schema_version (version) VALUES (17);
+    "#).context("Failed to run migration v17")?;
+
+    log::info!("Migration v17 completed successfully");
+    Ok(())
+}
+
+/// Chat message search (version 18) - Full-text index over chat message content
+fn migrate_v18(conn: &Connection) -> Result<()> {
+    log::info!("Running database migration v18 - Chat message search");
+
+    conn.execute_batch(r#"
+        -- Full-text search virtual table for chat message content
+        CREATE VIRTUAL TABLE IF NOT EXISTS chat_messages_fts USING fts5(
+            content,
+            content='chat_messages',
+            content_rowid='rowid'
+        );
+
+        -- Triggers to keep FTS in sync with chat_messages
+        CREATE TRIGGER IF NOT EXISTS chat_messages_fts_insert AFTER INSERT ON chat_messages BEGIN
+            INSERT INTO chat_messages_fts(rowid, content)
+            VALUES (new.rowid, new.content);
+        END;
+
+        CREATE TRIGGER IF NOT EXISTS chat_messages_fts_delete AFTER DELETE ON chat_messages BEGIN
+            INSERT INTO chat_messages_fts(chat_messages_fts, rowid, content)
+            VALUES('delete', old.rowid, old.content);
+        END;
+
+        CREATE TRIGGER IF NOT EXISTS chat_messages_fts_update AFTER UPDATE OF content ON chat_messages BEGIN
+            INSERT INTO chat_messages_fts(chat_messages_fts, rowid, content)
+            VALUES('delete', old.rowid, old.content);
+            INSERT INTO chat_messages_fts(rowid, content)
+            VALUES (new.rowid, new.content);
+        END;
+
+        -- Index the messages that already exist
+        INSERT INTO chat_messages_fts(chat_messages_fts) VALUES('rebuild');
+
+        -- Record migration
+        INSERT INTO schema_version (version) VALUES (18);
+    "#).context("Failed to run migration v18")?;
+
+    log::info!("Migration v18 completed successfully");
+    Ok(())
+}
+
+/// Transcript edit history (version 19) - Before/after values of manual edits for undo
+fn migrate_v19(conn: &Connection) -> Result<()> {
+    log::info!("Running database migration v19 - Transcript edit history");
+
+    conn.execute_batch(r#"
+        -- One row per segment changed; rows of the same edit share edit_id
+        CREATE TABLE IF NOT EXISTS transcript_edits (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            edit_id TEXT NOT NULL,
+            recording_id TEXT NOT NULL,
+            segment_id TEXT NOT NULL,
+            field TEXT NOT NULL,
+            before_value TEXT,
+            after_value TEXT,
+            created_at TEXT NOT NULL DEFAULT (datetime('now')),
+            FOREIGN KEY (recording_id) REFERENCES recordings(id) ON DELETE CASCADE
+        );
+
+        -- Index for finding a recording's latest edits
+        CREATE INDEX IF NOT EXISTS idx_transcript_edits_recording
+        ON transcript_edits(recording_id, id);
+
+        -- Record migration
+        INSERT INTO schema_version (version) VALUES (19);
+    "#).context("Failed to run migration v19")?;
+
+    log::info!("Migration v19 completed successfully");
+    Ok(())
+}
+
+/// Transcript versions (version 20) - Derived transcripts kept next to the original
+fn migrate_v20(conn: &Connection) -> Result<()> {
+    log::info!("Running database migration v20 - Transcript versions");
+
+    conn.execute_batch(r#"
+        -- Segments are stored as a JSON array with the original ids and timing
+        CREATE TABLE IF NOT EXISTS transcript_versions (
+            id TEXT PRIMARY KEY NOT NULL,
+            recording_id TEXT NOT NULL,
+            kind TEXT NOT NULL,
+            segments TEXT NOT NULL DEFAULT '[]',
+            model_id TEXT,
+            created_at TEXT NOT NULL DEFAULT (datetime('now')),
+            FOREIGN KEY (recording_id) REFERENCES recordings(id) ON DELETE CASCADE
+        );
+
+        -- Index for listing a recording's versions
+        CREATE INDEX IF NOT EXISTS idx_transcript_versions_recording
+        ON transcript_versions(recording_id, created_at);
+
+        -- Record migration
+        INSERT INTO schema_version (version) VALUES (20);
+    "#).context("Failed to run migration v20")?;
+
+    log::info!("Migration v20 completed successfully");
+    Ok(())
+}
+
+/// Transcription quality (version 21) - Score flagging recordings worth re-transcribing
+fn migrate_v21(conn: &Connection) -> Result<()> {
+    log::info!("Running database migration v21 - Transcription quality");
+
+    conn.execute_batch(r#"
+        -- Score from 0 to 1, NULL until the recording is transcribed
+        ALTER TABLE recordings ADD COLUMN transcription_quality REAL;
+
+        -- Record migration
+        INSERT INTO schema_version (version) VALUES (21);
+    "#).context("Failed to run migration v21")?;
+
+    log::info!("Migration v21 completed successfully");
+    Ok(())
+}
+
+/// Low-confidence segments (version 22) - Flag set by retranscription's min_confidence
+fn migrate_v22(conn: &Connection) -> Result<()> {
+    log::info!("Running database migration v22 - Low-confidence segments");
+
+    conn.execute_batch(r#"
+        -- 1 when the segment's confidence is below the requested minimum
+        ALTER TABLE transcript_segments ADD COLUMN low_confidence INTEGER NOT NULL DEFAULT 0;
+
+        -- Record migration
+        INSERT INTO schema_version (version) VALUES (22);
+    "#).context("Failed to run migration v22")?;
+
+    log::info!("Migration v22 completed successfully");
+    Ok(())
+}
+
 /// Seed the built-in tools that come with the app
 fn seed_builtin_tools(conn: &Connection) -> Result<()> {
     log::info!("Seeding built-in tools...");
@@ -15,6 +15,11 @@ pub mod template_repo;
 pub mod tools_repo;
 pub mod mcp_repo;
 pub mod model_config_repo;
+pub mod scheduled_recordings_repo;
+pub mod retranscription_queue_repo;
+pub mod speaker_embeddings_repo;
+pub mod insights_repo;
+pub mod transcript_versions_repo;
 
 pub use manager::DatabaseManager;
 pub use models::*;
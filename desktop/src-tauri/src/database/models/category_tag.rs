@@ -11,6 +11,17 @@ pub struct Category {
     pub is_system: bool,
 }
 
+/// Pseudo-category ID for recordings with no category assigned
+pub const UNCATEGORIZED_CATEGORY_ID: &str = "uncategorized";
+
+/// A category with the number of recordings assigned to it
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CategoryWithCount {
+    #[serde(flatten)]
+    pub category: Category,
+    pub recording_count: i64,
+}
+
 /// A user-defined tag
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Tag {
@@ -29,6 +40,34 @@ pub struct SearchResult {
     pub tags: Vec<Tag>,
 }
 
+/// What a global search result points to
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum GlobalSearchKind {
+    Recording,
+    Transcript,
+    ChatMessage,
+    Template,
+}
+
+/// One entry of a global (command palette) search, ranked by `score`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GlobalSearchResult {
+    pub kind: GlobalSearchKind,
+    /// ID of the recording, transcript segment, chat message or template
+    pub id: String,
+    /// Recording the result belongs to (None for templates)
+    pub recording_id: Option<String>,
+    /// Recording title, or template name
+    pub title: String,
+    /// Excerpt with the matches wrapped in <mark> tags
+    pub snippet: Option<String>,
+    /// Position in the recording in seconds (transcript results only)
+    pub audio_start_time: Option<f64>,
+    /// Relevance from 0.0 to 1.0, higher is better
+    pub score: f64,
+}
+
 /// Search filters
 #[derive(Debug, Clone, Serialize, Deserialize, Default)]
 pub struct SearchFilters {
@@ -37,4 +76,8 @@ pub struct SearchFilters {
     pub date_from: Option<String>,
     pub date_to: Option<String>,
     pub search_transcripts: bool,
+    /// Tokens of context in transcript match snippets (1-64, default 32)
+    pub snippet_tokens: Option<usize>,
+    /// Most transcript matches returned (1-500, default 50)
+    pub result_limit: Option<usize>,
 }
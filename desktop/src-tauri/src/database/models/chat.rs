@@ -37,6 +37,8 @@ pub enum ChatMessageStatus {
     Streaming,
     Complete,
     Cancelled,
+    /// Cancelled after producing output; the partial content is kept
+    Stopped,
     Error,
 }
 
@@ -47,6 +49,7 @@ impl ChatMessageStatus {
             ChatMessageStatus::Streaming => "streaming",
             ChatMessageStatus::Complete => "complete",
             ChatMessageStatus::Cancelled => "cancelled",
+            ChatMessageStatus::Stopped => "stopped",
             ChatMessageStatus::Error => "error",
         }
     }
@@ -57,6 +60,7 @@ impl ChatMessageStatus {
             "streaming" => ChatMessageStatus::Streaming,
             "complete" => ChatMessageStatus::Complete,
             "cancelled" => ChatMessageStatus::Cancelled,
+            "stopped" => ChatMessageStatus::Stopped,
             "error" => ChatMessageStatus::Error,
             _ => ChatMessageStatus::Complete,
         }
@@ -83,6 +87,15 @@ pub struct ChatMessage {
     /// The model ID used (e.g., "llama3.2", "mistral-7b")
     #[serde(default)]
     pub model_id: Option<String>,
+    /// Tokens generated for this message (assistant messages only)
+    #[serde(default)]
+    pub token_count: Option<i64>,
+    /// Tokens in the prompt that produced this message
+    #[serde(default)]
+    pub prompt_tokens: Option<i64>,
+    /// Wall time spent generating this message, in milliseconds
+    #[serde(default)]
+    pub generation_ms: Option<i64>,
 }
 
 impl ChatMessage {
@@ -100,6 +113,9 @@ impl ChatMessage {
             error_message: None,
             provider_type: None,
             model_id: None,
+            token_count: None,
+            prompt_tokens: None,
+            generation_ms: None,
         }
     }
 
@@ -123,10 +139,13 @@ impl ChatMessage {
             error_message: None,
             provider_type,
             model_id,
+            token_count: None,
+            prompt_tokens: None,
+            generation_ms: None,
         }
     }
 
-    /// Create a system message (for transcript context)
+    /// Create a system message (e.g. the summary of a compacted chat history)
     pub fn system(session_id: &str, recording_id: &str, content: &str) -> Self {
         Self {
             id: uuid::Uuid::new_v4().to_string(),
@@ -140,6 +159,9 @@ impl ChatMessage {
             error_message: None,
             provider_type: None,
             model_id: None,
+            token_count: None,
+            prompt_tokens: None,
+            generation_ms: None,
         }
     }
 }
@@ -162,6 +184,27 @@ pub struct ChatSession {
     pub model_id: Option<String>,
 }
 
+/// A chat message matched by full-text search, with the session and recording it belongs to
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ChatMessageSearchResult {
+    pub message_id: String,
+    pub session_id: Option<String>,
+    pub session_title: Option<String>,
+    pub recording_id: String,
+    pub recording_title: String,
+    pub role: ChatRole,
+    /// Excerpt of the message with the matches wrapped in <mark> tags
+    pub matched_text: String,
+    pub created_at: String,
+}
+
+/// A chat session together with its messages
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ChatSessionWithMessages {
+    pub session: ChatSession,
+    pub messages: Vec<ChatMessage>,
+}
+
 impl ChatSession {
     /// Create a new chat session with a default title
     pub fn new(recording_id: &str, title: &str) -> Self {
@@ -0,0 +1,16 @@
+// Recording insights models
+
+use serde::{Deserialize, Serialize};
+
+/// Decisions, risks and open questions the LLM extracted from a recording
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct RecordingInsights {
+    pub recording_id: String,
+    pub decisions: Vec<String>,
+    /// Risks and blockers raised in the meeting
+    pub risks: Vec<String>,
+    pub open_questions: Vec<String>,
+    /// Model that produced the insights
+    pub model_id: Option<String>,
+    pub created_at: String,
+}
@@ -9,6 +9,11 @@
 // - template.rs: Prompt templates
 // - tool.rs: AI tools
 // - mcp.rs: MCP server configuration
+// - scheduled_recording.rs: Scheduled (auto-start) recordings
+// - retranscription_job.rs: Persistent retranscription queue
+// - speaker_embedding.rs: Per-recording speaker embeddings
+// - insights.rs: LLM-extracted recording insights
+// - transcript_version.rs: Derived transcript versions (cleanup, redaction)
 
 mod settings;
 mod recording;
@@ -19,14 +24,23 @@ mod template;
 mod tool;
 mod mcp;
 mod model_config;
+mod scheduled_recording;
+mod retranscription_job;
+mod speaker_embedding;
+mod insights;
+mod transcript_version;
 
 // Re-export all public types for backwards compatibility
 pub use settings::{Setting, AllSettings};
-pub use recording::{Recording, RecordingUpdate, RecordingWithMetadata};
-pub use transcript::{TranscriptSegment, RegisteredSpeakerDb, SpeakerLabel};
-pub use category_tag::{Category, Tag, SearchResult, SearchFilters};
+pub use recording::{Recording, RecordingUpdate, RecordingWithMetadata, RecordingFull};
+pub use transcript::{TranscriptSegment, RegisteredSpeakerDb, SpeakerLabel, SpeakerSummary};
+pub use category_tag::{
+    Category, CategoryWithCount, Tag, SearchResult, SearchFilters, UNCATEGORIZED_CATEGORY_ID,
+    GlobalSearchKind, GlobalSearchResult,
+};
 pub use chat::{
-    ChatRole, ChatMessageStatus, ChatMessage, ChatConfig, ChatSession, DefaultLlmConfig,
+    ChatRole, ChatMessageStatus, ChatMessage, ChatConfig, ChatSession, ChatSessionWithMessages,
+    ChatMessageSearchResult, DefaultLlmConfig,
 };
 pub use template::{PromptTemplate, CreatePromptTemplate, UpdatePromptTemplate};
 pub use tool::{
@@ -38,3 +52,10 @@ pub use mcp::{
     McpServerConfig, McpServerWithTools,
 };
 pub use model_config::{ModelConfig, UpsertModelConfig};
+pub use scheduled_recording::{
+    ScheduleRecurrence, ScheduledRecording, CreateScheduledRecording, UpdateScheduledRecording,
+};
+pub use retranscription_job::RetranscriptionJob;
+pub use speaker_embedding::RecordingSpeakerEmbedding;
+pub use insights::RecordingInsights;
+pub use transcript_version::{TranscriptVersion, TRANSCRIPT_VERSION_CLEANUP, TRANSCRIPT_VERSION_REDACTED};
@@ -1,6 +1,6 @@
 // Database models - Recording
 use serde::{Deserialize, Serialize};
-use super::{Category, Tag};
+use super::{Category, ChatSessionWithMessages, Tag, TranscriptSegment};
 
 /// A recording (meeting) entry
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -19,6 +19,9 @@ pub struct Recording {
     pub transcription_model: Option<String>,
     pub language: Option<String>,
     pub diarization_provider: Option<String>,
+    /// Transcription quality score from 0 to 1, computed after transcription
+    #[serde(default)]
+    pub transcription_quality: Option<f64>,
 }
 
 impl Recording {
@@ -38,8 +41,21 @@ impl Recording {
             transcription_model: None,
             language: None,
             diarization_provider: None,
+            transcription_quality: None,
         }
     }
+
+    /// Meeting folder, falling back to the folder containing the audio file
+    pub fn folder_path(&self) -> Option<std::path::PathBuf> {
+        self.meeting_folder_path
+            .as_ref()
+            .map(std::path::PathBuf::from)
+            .or_else(|| {
+                self.audio_file_path
+                    .as_ref()
+                    .and_then(|p| std::path::Path::new(p).parent().map(|d| d.to_path_buf()))
+            })
+    }
 }
 
 /// Updates that can be applied to a recording
@@ -53,6 +69,11 @@ pub struct RecordingUpdate {
     pub meeting_folder_path: Option<String>,
     pub transcription_model: Option<String>,
     pub diarization_provider: Option<String>,
+    /// Empty string clears the field (also for the fields below)
+    pub microphone_device: Option<String>,
+    pub system_audio_device: Option<String>,
+    /// Language code such as "en" or "pt-BR", or "auto"
+    pub language: Option<String>,
 }
 
 /// A recording with its associated categories and tags
@@ -63,3 +84,13 @@ pub struct RecordingWithMetadata {
     pub tags: Vec<Tag>,
     pub transcript_count: i32,
 }
+
+/// Everything stored about a recording, for programmatic export
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RecordingFull {
+    pub recording: Recording,
+    pub categories: Vec<Category>,
+    pub tags: Vec<Tag>,
+    pub transcript_segments: Vec<TranscriptSegment>,
+    pub chat_sessions: Vec<ChatSessionWithMessages>,
+}
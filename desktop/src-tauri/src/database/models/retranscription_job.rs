@@ -0,0 +1,18 @@
+// Retranscription queue models
+
+use serde::{Deserialize, Serialize};
+
+/// A queued retranscription job
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RetranscriptionJob {
+    pub id: String,
+    pub recording_id: String,
+    /// JSON-encoded retranscription options (model, language, diarization, ...)
+    pub options: String,
+    /// Order in the queue (lower runs first)
+    pub position: i64,
+    /// "pending" | "running" | "failed"
+    pub status: String,
+    pub error: Option<String>,
+    pub created_at: String,
+}
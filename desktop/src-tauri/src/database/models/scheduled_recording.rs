@@ -0,0 +1,89 @@
+// Scheduled recording models
+
+use serde::{Deserialize, Deserializer, Serialize};
+
+/// How often a scheduled recording repeats
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum ScheduleRecurrence {
+    Once,
+    Daily,
+    Weekdays,
+    Weekly,
+}
+
+impl ScheduleRecurrence {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ScheduleRecurrence::Once => "once",
+            ScheduleRecurrence::Daily => "daily",
+            ScheduleRecurrence::Weekdays => "weekdays",
+            ScheduleRecurrence::Weekly => "weekly",
+        }
+    }
+
+    pub fn from_str(s: &str) -> Self {
+        match s.to_lowercase().as_str() {
+            "daily" => ScheduleRecurrence::Daily,
+            "weekdays" => ScheduleRecurrence::Weekdays,
+            "weekly" => ScheduleRecurrence::Weekly,
+            _ => ScheduleRecurrence::Once,
+        }
+    }
+}
+
+/// A recording that starts automatically at a scheduled time
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ScheduledRecording {
+    pub id: String,
+    /// Meeting name used for the recording
+    pub name: String,
+    /// First occurrence (RFC 3339, local offset); later occurrences follow `recurrence`
+    pub start_time: String,
+    pub recurrence: ScheduleRecurrence,
+    /// Stop automatically after this many minutes (None = until manually stopped)
+    pub duration_minutes: Option<i64>,
+    pub mic_device: Option<String>,
+    pub system_device: Option<String>,
+    pub enabled: bool,
+    /// Last occurrence that was started or skipped (RFC 3339)
+    pub last_triggered_at: Option<String>,
+    pub created_at: String,
+}
+
+/// Input for creating a scheduled recording
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CreateScheduledRecording {
+    pub name: String,
+    pub start_time: String,
+    pub recurrence: ScheduleRecurrence,
+    pub duration_minutes: Option<i64>,
+    pub mic_device: Option<String>,
+    pub system_device: Option<String>,
+}
+
+/// Input for updating a scheduled recording (None = keep current value).
+/// The optional fields are cleared with `Some(None)`, sent as `null`.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct UpdateScheduledRecording {
+    pub name: Option<String>,
+    pub start_time: Option<String>,
+    pub recurrence: Option<ScheduleRecurrence>,
+    #[serde(default, deserialize_with = "explicit_null", skip_serializing_if = "Option::is_none")]
+    pub duration_minutes: Option<Option<i64>>,
+    #[serde(default, deserialize_with = "explicit_null", skip_serializing_if = "Option::is_none")]
+    pub mic_device: Option<Option<String>>,
+    #[serde(default, deserialize_with = "explicit_null", skip_serializing_if = "Option::is_none")]
+    pub system_device: Option<Option<String>>,
+    pub enabled: Option<bool>,
+}
+
+/// Deserialize a present field, including `null`, as `Some`; missing
+/// fields fall back to `None` through `#[serde(default)]`
+fn explicit_null<'de, D, T>(deserializer: D) -> Result<Option<Option<T>>, D::Error>
+where
+    D: Deserializer<'de>,
+    T: Deserialize<'de>,
+{
+    Option::<T>::deserialize(deserializer).map(Some)
+}
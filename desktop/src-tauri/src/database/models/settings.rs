@@ -24,4 +24,45 @@ pub struct AllSettings {
     pub last_system_audio: Option<String>,
     pub recordings_folder: Option<String>,
     pub current_model: Option<String>,
+    /// JSON object of custom whisper model name -> file path
+    pub custom_whisper_models: Option<String>,
+    /// Keep recording the microphone when system audio fails to start (default true)
+    pub allow_mic_only_fallback: Option<bool>,
+    pub keep_model_loaded: bool,
+    pub final_loudnorm: bool,
+    pub record_level_timeline: bool,
+    pub trim_silence_on_save: bool,
+    pub trim_min_silence_seconds: Option<f64>,
+    pub keep_untrimmed_original: Option<bool>,
+    pub vad_aggressiveness: Option<String>,
+    pub vad_threshold: Option<f32>,
+    /// Longest live transcription chunk in seconds (None: split only at pauses)
+    pub live_chunk_seconds: Option<u32>,
+    pub save_channel_layout: Option<String>,
+    pub label_speakers_by_source: bool,
+    pub live_rolling_context: bool,
+    /// Run speaker diarization on live chunks during recording (CPU heavy)
+    pub live_diarization: bool,
+    /// Diarization provider used when a transcription doesn't name one ("pyannote" or "sortformer")
+    pub diarization_provider: Option<String>,
+    pub mic_speaker_label: Option<String>,
+    pub system_speaker_label: Option<String>,
+    pub model_idle_timeout_minutes: Option<u64>,
+    pub recording_hotkey: Option<String>,
+    pub auto_cleanup_unused_tags: bool,
+    pub auto_summarize_on_complete: bool,
+    pub completion_webhook_url: Option<String>,
+    pub completion_webhook_allow_private: bool,
+    pub tool_prompt_template: Option<String>,
+    pub metrics_endpoint_enabled: bool,
+    pub metrics_endpoint_port: Option<u16>,
+    pub file_logging_enabled: bool,
+    /// JSON object of device name -> requested capture sample rate
+    pub capture_sample_rates: Option<String>,
+    /// Heavy background tasks (transcription, diarization, LLM) run at once
+    pub max_concurrent_tasks: Option<usize>,
+    /// Folders used instead of the default model folders
+    pub whisper_models_dir: Option<String>,
+    pub llm_models_dir: Option<String>,
+    pub diarization_models_dir: Option<String>,
 }
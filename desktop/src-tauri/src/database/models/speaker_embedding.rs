@@ -0,0 +1,19 @@
+// Per-recording speaker embedding models
+
+use serde::{Deserialize, Serialize};
+
+/// Centroid voice embedding of a speaker detected in a recording
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RecordingSpeakerEmbedding {
+    pub recording_id: String,
+    /// Speaker ID as used in transcript_segments (e.g. "speaker_0")
+    pub speaker_id: String,
+    pub speaker_label: String,
+    /// Centroid embedding, stored as a BLOB and not exposed to the frontend
+    #[serde(skip_serializing, default)]
+    pub embedding: Vec<f32>,
+    /// Number of diarization segments averaged into the centroid
+    pub segment_count: i64,
+    /// Set once the speaker has been matched to a registered voice
+    pub registered_speaker_id: Option<String>,
+}
@@ -20,6 +20,9 @@ pub struct TranscriptSegment {
     pub speaker_label: Option<String>,
     #[serde(default)]
     pub is_registered_speaker: bool,
+    /// Confidence is below the retranscription's `min_confidence`, so the UI greys it out
+    #[serde(default)]
+    pub low_confidence: bool,
 }
 
 /// A registered speaker with voice profile
@@ -40,3 +43,55 @@ pub struct SpeakerLabel {
     pub speaker_id: String,
     pub custom_label: String,
 }
+
+/// A speaker aggregated across all recordings (for the people directory)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SpeakerSummary {
+    /// Display label, the registered name for registered speakers
+    pub speaker_label: String,
+    pub registered_speaker_id: Option<String>,
+    pub registered_name: Option<String>,
+    pub recording_count: i64,
+    pub segment_count: i64,
+    /// Total talk time in seconds
+    pub total_duration: f64,
+}
+
+#[cfg(test)]
+impl TranscriptSegment {
+    /// Segment of recording "rec" for tests, with no speaker and confidence 0.9
+    pub fn test_segment(start: f64, end: f64, text: &str) -> Self {
+        Self {
+            id: format!("seg-{}", start),
+            recording_id: "rec".to_string(),
+            text: text.to_string(),
+            audio_start_time: start,
+            audio_end_time: end,
+            duration: end - start,
+            display_time: String::new(),
+            confidence: 0.9,
+            sequence_id: 0,
+            speaker_id: None,
+            speaker_label: None,
+            is_registered_speaker: false,
+            low_confidence: false,
+        }
+    }
+
+    pub fn with_ids(mut self, id: &str, recording_id: &str) -> Self {
+        self.id = id.to_string();
+        self.recording_id = recording_id.to_string();
+        self
+    }
+
+    pub fn with_speaker(mut self, speaker_id: &str, speaker_label: &str) -> Self {
+        self.speaker_id = Some(speaker_id.to_string());
+        self.speaker_label = Some(speaker_label.to_string());
+        self
+    }
+
+    pub fn with_confidence(mut self, confidence: f32) -> Self {
+        self.confidence = confidence;
+        self
+    }
+}
@@ -0,0 +1,24 @@
+// Transcript version models
+
+use serde::{Deserialize, Serialize};
+
+use super::TranscriptSegment;
+
+/// Kind of derived transcript: LLM cleanup of punctuation and errors
+pub const TRANSCRIPT_VERSION_CLEANUP: &str = "cleanup";
+/// Kind of derived transcript: personal information redacted for sharing
+pub const TRANSCRIPT_VERSION_REDACTED: &str = "redacted";
+
+/// An alternative transcript of a recording, derived from the original
+/// segments with the same ids and timing. The original is never modified.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TranscriptVersion {
+    pub id: String,
+    pub recording_id: String,
+    /// What produced this version, e.g. "cleanup" or "redacted"
+    pub kind: String,
+    pub segments: Vec<TranscriptSegment>,
+    /// Model that produced the version, if an LLM was used
+    pub model_id: Option<String>,
+    pub created_at: String,
+}
@@ -1,10 +1,12 @@
 // Recordings repository for Meeting-Local
 // Handles CRUD operations for recordings/meetings
 
-use anyhow::{Context, Result};
+use anyhow::{bail, Context, Result};
 use rusqlite::{Connection, params};
 
-use super::models::{Recording, RecordingUpdate, RecordingWithMetadata, Category, Tag};
+use super::models::{
+    Recording, RecordingUpdate, RecordingWithMetadata, RecordingFull, ChatSessionWithMessages, Category, Tag,
+};
 use super::DatabaseManager;
 
 impl DatabaseManager {
@@ -29,6 +31,30 @@ impl DatabaseManager {
         })
     }
 
+    /// Get a recording with its categories, tags, transcript segments and all chat sessions
+    pub fn get_recording_full(&self, id: &str) -> Result<Option<RecordingFull>> {
+        let Some(with_metadata) = self.get_recording_with_metadata(id)? else {
+            return Ok(None);
+        };
+
+        let transcript_segments = self.get_transcript_segments(id)?;
+        let chat_sessions = self.get_chat_sessions(id)?
+            .into_iter()
+            .map(|session| {
+                let messages = self.get_chat_messages_by_session(&session.id)?;
+                Ok(ChatSessionWithMessages { session, messages })
+            })
+            .collect::<Result<Vec<_>>>()?;
+
+        Ok(Some(RecordingFull {
+            recording: with_metadata.recording,
+            categories: with_metadata.categories,
+            tags: with_metadata.tags,
+            transcript_segments,
+            chat_sessions,
+        }))
+    }
+
     /// Get all recordings (most recent first)
     pub fn get_all_recordings(&self) -> Result<Vec<RecordingWithMetadata>> {
         self.with_connection(|conn| {
@@ -57,12 +83,41 @@ impl DatabaseManager {
         })
     }
 
+    /// Mark recordings still in the "recording" state as "interrupted".
+    /// Called on startup, when no recording can be in progress; returns how many were marked.
+    pub fn mark_unfinished_recordings_interrupted(&self) -> Result<usize> {
+        self.with_connection(|conn| {
+            conn.execute(
+                "UPDATE recordings SET status = 'interrupted', updated_at = datetime('now') WHERE status = 'recording'",
+                [],
+            ).context("Failed to mark unfinished recordings as interrupted")
+        })
+    }
+
+    /// Get recordings with the given status (most recent first)
+    pub fn get_recordings_with_status(&self, status: &str) -> Result<Vec<Recording>> {
+        self.with_connection(|conn| {
+            get_recordings_with_status_impl(conn, status)
+        })
+    }
+
     /// Mark a recording as completed
     pub fn complete_recording(&self, id: &str, duration_seconds: f64) -> Result<()> {
         self.with_connection(|conn| {
             complete_recording_impl(conn, id, duration_seconds)
         })
     }
+
+    /// Store the transcription quality score of a recording
+    pub fn set_transcription_quality(&self, id: &str, score: f64) -> Result<()> {
+        self.with_connection(|conn| {
+            conn.execute(
+                "UPDATE recordings SET transcription_quality = ? WHERE id = ?",
+                params![score, id],
+            ).context("Failed to set transcription quality")?;
+            Ok(())
+        })
+    }
 }
 
 fn create_recording_impl(conn: &Connection, recording: &Recording) -> Result<String> {
@@ -99,7 +154,7 @@ fn get_recording_impl(conn: &Connection, id: &str) -> Result<Option<Recording>>
         r#"
         SELECT id, title, created_at, completed_at, duration_seconds, status,
                audio_file_path, meeting_folder_path, microphone_device, system_audio_device,
-               sample_rate, transcription_model, language, diarization_provider
+               sample_rate, transcription_model, language, diarization_provider, transcription_quality
         FROM recordings WHERE id = ?
         "#
     ).context("Failed to prepare get_recording query")?;
@@ -120,6 +175,7 @@ fn get_recording_impl(conn: &Connection, id: &str) -> Result<Option<Recording>>
             transcription_model: row.get(11)?,
             language: row.get(12)?,
             diarization_provider: row.get(13)?,
+            transcription_quality: row.get(14)?,
         })
     });
 
@@ -154,7 +210,7 @@ fn get_all_recordings_impl(conn: &Connection, limit: Option<i32>) -> Result<Vec<
             r#"
             SELECT id, title, created_at, completed_at, duration_seconds, status,
                    audio_file_path, meeting_folder_path, microphone_device, system_audio_device,
-                   sample_rate, transcription_model, language, diarization_provider
+                   sample_rate, transcription_model, language, diarization_provider, transcription_quality
             FROM recordings
             ORDER BY created_at DESC
             LIMIT {}
@@ -163,7 +219,7 @@ fn get_all_recordings_impl(conn: &Connection, limit: Option<i32>) -> Result<Vec<
         None => r#"
             SELECT id, title, created_at, completed_at, duration_seconds, status,
                    audio_file_path, meeting_folder_path, microphone_device, system_audio_device,
-                   sample_rate, transcription_model, language, diarization_provider
+                   sample_rate, transcription_model, language, diarization_provider, transcription_quality
             FROM recordings
             ORDER BY created_at DESC
             "#.to_string(),
@@ -187,6 +243,7 @@ fn get_all_recordings_impl(conn: &Connection, limit: Option<i32>) -> Result<Vec<
             transcription_model: row.get(11)?,
             language: row.get(12)?,
             diarization_provider: row.get(13)?,
+            transcription_quality: row.get(14)?,
         })
     }).context("Failed to query recordings")?;
 
@@ -210,7 +267,83 @@ fn get_all_recordings_impl(conn: &Connection, limit: Option<i32>) -> Result<Vec<
     Ok(results)
 }
 
-fn update_recording_impl(conn: &Connection, id: &str, updates: &RecordingUpdate) -> Result<()> {
+fn get_recordings_with_status_impl(conn: &Connection, status: &str) -> Result<Vec<Recording>> {
+    let mut stmt = conn.prepare(
+        r#"
+        SELECT id, title, created_at, completed_at, duration_seconds, status,
+               audio_file_path, meeting_folder_path, microphone_device, system_audio_device,
+               sample_rate, transcription_model, language, diarization_provider, transcription_quality
+        FROM recordings
+        WHERE status = ?
+        ORDER BY created_at DESC
+        "#
+    ).context("Failed to prepare get_recordings_with_status query")?;
+
+    let recordings = stmt.query_map(params![status], |row| {
+        Ok(Recording {
+            id: row.get(0)?,
+            title: row.get(1)?,
+            created_at: row.get(2)?,
+            completed_at: row.get(3)?,
+            duration_seconds: row.get(4)?,
+            status: row.get(5)?,
+            audio_file_path: row.get(6)?,
+            meeting_folder_path: row.get(7)?,
+            microphone_device: row.get(8)?,
+            system_audio_device: row.get(9)?,
+            sample_rate: row.get(10)?,
+            transcription_model: row.get(11)?,
+            language: row.get(12)?,
+            diarization_provider: row.get(13)?,
+            transcription_quality: row.get(14)?,
+        })
+    }).context("Failed to query recordings by status")?;
+
+    recordings.collect::<std::result::Result<Vec<_>, _>>()
+        .context("Failed to collect recordings by status")
+}
+
+/// Maximum length of free-text metadata such as device names
+const MAX_METADATA_LEN: usize = 256;
+
+/// Trimmed value, or None for an empty string (clears the column)
+fn non_empty(value: &str) -> Option<String> {
+    let value = value.trim();
+    (!value.is_empty()).then(|| value.to_string())
+}
+
+fn validate_metadata_text(field: &str, value: &str) -> Result<()> {
+    if value.trim().chars().count() > MAX_METADATA_LEN {
+        bail!("{} must be at most {} characters", field, MAX_METADATA_LEN);
+    }
+    if value.chars().any(|c| c.is_control()) {
+        bail!("{} must not contain control characters", field);
+    }
+    Ok(())
+}
+
+/// Accept "auto" or a language code like "en", "yue" or "pt-BR"
+fn validate_language(language: &str) -> Result<()> {
+    let language = language.trim();
+    if language.is_empty() || language == "auto" {
+        return Ok(());
+    }
+
+    let mut parts = language.split('-');
+    let primary = parts.next().unwrap_or_default();
+    let region = parts.next();
+    let valid = (2..=3).contains(&primary.len())
+        && primary.chars().all(|c| c.is_ascii_lowercase())
+        && region.map_or(true, |r| (2..=4).contains(&r.len()) && r.chars().all(|c| c.is_ascii_alphanumeric()))
+        && parts.next().is_none();
+
+    if !valid {
+        bail!("Invalid language code: {}", language);
+    }
+    Ok(())
+}
+
+pub(super) fn update_recording_impl(conn: &Connection, id: &str, updates: &RecordingUpdate) -> Result<()> {
     let mut set_clauses = Vec::new();
     let mut params_vec: Vec<Box<dyn rusqlite::ToSql>> = Vec::new();
 
@@ -239,8 +372,9 @@ fn update_recording_impl(conn: &Connection, id: &str, updates: &RecordingUpdate)
         params_vec.push(Box::new(meeting_folder_path.clone()));
     }
     if let Some(ref transcription_model) = updates.transcription_model {
+        validate_metadata_text("transcription_model", transcription_model)?;
         set_clauses.push("transcription_model = ?");
-        params_vec.push(Box::new(transcription_model.clone()));
+        params_vec.push(Box::new(non_empty(transcription_model)));
     }
     if let Some(ref diarization_provider) = updates.diarization_provider {
         set_clauses.push("diarization_provider = ?");
@@ -252,6 +386,22 @@ fn update_recording_impl(conn: &Connection, id: &str, updates: &RecordingUpdate)
         }
     }
 
+    if let Some(ref microphone_device) = updates.microphone_device {
+        validate_metadata_text("microphone_device", microphone_device)?;
+        set_clauses.push("microphone_device = ?");
+        params_vec.push(Box::new(non_empty(microphone_device)));
+    }
+    if let Some(ref system_audio_device) = updates.system_audio_device {
+        validate_metadata_text("system_audio_device", system_audio_device)?;
+        set_clauses.push("system_audio_device = ?");
+        params_vec.push(Box::new(non_empty(system_audio_device)));
+    }
+    if let Some(ref language) = updates.language {
+        validate_language(language)?;
+        set_clauses.push("language = ?");
+        params_vec.push(Box::new(non_empty(language)));
+    }
+
     if set_clauses.is_empty() {
         return Ok(());
     }
@@ -386,4 +536,94 @@ mod tests {
         assert_eq!(retrieved.status, "completed");
         assert_eq!(retrieved.duration_seconds, Some(120.5));
     }
+
+    #[test]
+    fn test_set_transcription_quality() {
+        let db = create_test_db();
+
+        let recording = Recording::new("rec_quality".to_string(), "Quiet Meeting".to_string());
+        db.create_recording(&recording).unwrap();
+        assert_eq!(db.get_recording("rec_quality").unwrap().unwrap().transcription_quality, None);
+
+        db.set_transcription_quality("rec_quality", 0.42).unwrap();
+        let retrieved = db.get_recording("rec_quality").unwrap().unwrap();
+        assert_eq!(retrieved.transcription_quality, Some(0.42));
+    }
+
+    #[test]
+    fn test_mark_unfinished_recordings_interrupted() {
+        let db = create_test_db();
+
+        db.create_recording(&Recording::new("rec_live".to_string(), "Crashed".to_string())).unwrap();
+        db.create_recording(&Recording::new("rec_done".to_string(), "Finished".to_string())).unwrap();
+        db.complete_recording("rec_done", 60.0).unwrap();
+
+        assert_eq!(db.mark_unfinished_recordings_interrupted().unwrap(), 1);
+        let interrupted = db.get_recordings_with_status("interrupted").unwrap();
+        assert_eq!(interrupted.len(), 1);
+        assert_eq!(interrupted[0].id, "rec_live");
+        assert!(db.get_recordings_with_status("recording").unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_update_recording_metadata() {
+        let db = create_test_db();
+
+        let recording = Recording::new("rec_789".to_string(), "Metadata".to_string());
+        db.create_recording(&recording).unwrap();
+
+        db.update_recording("rec_789", &RecordingUpdate {
+            microphone_device: Some(" USB Mic ".to_string()),
+            system_audio_device: Some("Speakers".to_string()),
+            language: Some("pt-BR".to_string()),
+            ..Default::default()
+        }).unwrap();
+
+        let retrieved = db.get_recording("rec_789").unwrap().unwrap();
+        assert_eq!(retrieved.microphone_device.as_deref(), Some("USB Mic"));
+        assert_eq!(retrieved.system_audio_device.as_deref(), Some("Speakers"));
+        assert_eq!(retrieved.language.as_deref(), Some("pt-BR"));
+
+        // Empty string clears the field
+        db.update_recording("rec_789", &RecordingUpdate {
+            system_audio_device: Some(String::new()),
+            ..Default::default()
+        }).unwrap();
+        let retrieved = db.get_recording("rec_789").unwrap().unwrap();
+        assert_eq!(retrieved.system_audio_device, None);
+
+        for language in ["english", "EN", "en-", "en-US-x"] {
+            let result = db.update_recording("rec_789", &RecordingUpdate {
+                language: Some(language.to_string()),
+                ..Default::default()
+            });
+            assert!(result.is_err(), "{} should be rejected", language);
+        }
+        assert!(db.update_recording("rec_789", &RecordingUpdate {
+            microphone_device: Some("Mic\n".to_string()),
+            ..Default::default()
+        }).is_err());
+        assert!(db.update_recording("rec_789", &RecordingUpdate {
+            microphone_device: Some("m".repeat(MAX_METADATA_LEN + 1)),
+            ..Default::default()
+        }).is_err());
+    }
+
+    #[test]
+    fn test_get_recording_full() {
+        let db = create_test_db();
+        assert!(db.get_recording_full("missing").unwrap().is_none());
+
+        db.create_recording(&Recording::new("rec_full".to_string(), "Full".to_string())).unwrap();
+        let tag_id = db.create_tag("planning", None).unwrap();
+        db.assign_tag("rec_full", &tag_id).unwrap();
+        let session = db.get_or_create_chat_session("rec_full").unwrap();
+
+        let full = db.get_recording_full("rec_full").unwrap().unwrap();
+        assert_eq!(full.recording.title, "Full");
+        assert_eq!(full.tags.len(), 1);
+        assert!(full.transcript_segments.is_empty());
+        assert_eq!(full.chat_sessions.len(), 1);
+        assert_eq!(full.chat_sessions[0].session.id, session.id);
+    }
 }
@@ -0,0 +1,255 @@
+// Retranscription queue repository for Meeting-Local
+// Persists queued retranscription jobs so the queue survives restarts
+
+use anyhow::{Context, Result};
+use rusqlite::{Connection, params, Row};
+
+use super::models::RetranscriptionJob;
+use super::DatabaseManager;
+
+const SELECT_COLUMNS: &str = "id, recording_id, options, position, status, error, created_at";
+
+impl DatabaseManager {
+    /// Append a job to the end of the queue, returning its ID
+    pub fn enqueue_retranscription_job(&self, recording_id: &str, options: &str) -> Result<String> {
+        self.with_connection(|conn| {
+            enqueue_job_impl(conn, recording_id, options)
+        })
+    }
+
+    /// Get all queued jobs in queue order
+    pub fn get_retranscription_queue(&self) -> Result<Vec<RetranscriptionJob>> {
+        self.with_connection(|conn| {
+            let mut stmt = conn.prepare(
+                &format!("SELECT {} FROM retranscription_queue ORDER BY position", SELECT_COLUMNS)
+            ).context("Failed to prepare get_retranscription_queue query")?;
+
+            let jobs = stmt.query_map([], row_to_job)
+                .context("Failed to query retranscription queue")?;
+
+            jobs.collect::<std::result::Result<Vec<_>, _>>()
+                .context("Failed to collect retranscription jobs")
+        })
+    }
+
+    /// Get the next pending job, if any
+    pub fn next_pending_retranscription_job(&self) -> Result<Option<RetranscriptionJob>> {
+        self.with_connection(|conn| {
+            let result = conn.query_row(
+                &format!(
+                    "SELECT {} FROM retranscription_queue WHERE status = 'pending' ORDER BY position LIMIT 1",
+                    SELECT_COLUMNS
+                ),
+                [],
+                row_to_job,
+            );
+
+            match result {
+                Ok(job) => Ok(Some(job)),
+                Err(rusqlite::Error::QueryReturnedNoRows) => Ok(None),
+                Err(e) => Err(e).context("Failed to get next retranscription job"),
+            }
+        })
+    }
+
+    /// Update a job's status (and error message for failed jobs)
+    pub fn set_retranscription_job_status(&self, id: &str, status: &str, error: Option<&str>) -> Result<()> {
+        self.with_connection(|conn| {
+            conn.execute(
+                "UPDATE retranscription_queue SET status = ?, error = ? WHERE id = ?",
+                params![status, error, id],
+            ).context("Failed to update retranscription job status")?;
+            Ok(())
+        })
+    }
+
+    /// Reorder the queue; jobs are placed in the order of `job_ids`,
+    /// any jobs not listed keep their relative order after them
+    pub fn reorder_retranscription_queue(&self, job_ids: &[String]) -> Result<()> {
+        self.with_connection(|conn| {
+            reorder_queue_impl(conn, job_ids)
+        })
+    }
+
+    /// Remove a job that isn't running. Returns false if it's running or doesn't exist.
+    pub fn delete_retranscription_job(&self, id: &str) -> Result<bool> {
+        self.with_connection(|conn| {
+            let deleted = conn.execute(
+                "DELETE FROM retranscription_queue WHERE id = ? AND status != 'running'",
+                params![id],
+            ).context("Failed to delete retranscription job")?;
+            Ok(deleted > 0)
+        })
+    }
+
+    /// Remove a finished job regardless of status
+    pub fn complete_retranscription_job(&self, id: &str) -> Result<()> {
+        self.with_connection(|conn| {
+            conn.execute(
+                "DELETE FROM retranscription_queue WHERE id = ?",
+                params![id],
+            ).context("Failed to remove completed retranscription job")?;
+            Ok(())
+        })
+    }
+
+    /// Remove every failed job, returning how many were removed
+    pub fn delete_failed_retranscription_jobs(&self) -> Result<usize> {
+        self.with_connection(|conn| {
+            conn.execute(
+                "DELETE FROM retranscription_queue WHERE status = 'failed'",
+                [],
+            ).context("Failed to delete failed retranscription jobs")
+        })
+    }
+
+    /// Return jobs left "running" by a previous app session to the pending state
+    pub fn reset_running_retranscription_jobs(&self) -> Result<usize> {
+        self.with_connection(|conn| {
+            conn.execute(
+                "UPDATE retranscription_queue SET status = 'pending' WHERE status = 'running'",
+                [],
+            ).context("Failed to reset running retranscription jobs")
+        })
+    }
+}
+
+fn row_to_job(row: &Row) -> rusqlite::Result<RetranscriptionJob> {
+    Ok(RetranscriptionJob {
+        id: row.get(0)?,
+        recording_id: row.get(1)?,
+        options: row.get(2)?,
+        position: row.get(3)?,
+        status: row.get(4)?,
+        error: row.get(5)?,
+        created_at: row.get(6)?,
+    })
+}
+
+fn enqueue_job_impl(conn: &Connection, recording_id: &str, options: &str) -> Result<String> {
+    let id = format!("rtjob_{}", uuid::Uuid::new_v4());
+    let now = chrono::Utc::now().to_rfc3339();
+
+    conn.execute(
+        r#"INSERT INTO retranscription_queue (id, recording_id, options, position, status, created_at)
+           VALUES (?1, ?2, ?3, (SELECT COALESCE(MAX(position), 0) + 1 FROM retranscription_queue), 'pending', ?4)"#,
+        params![id, recording_id, options, now],
+    ).context("Failed to enqueue retranscription job")?;
+
+    Ok(id)
+}
+
+fn reorder_queue_impl(conn: &Connection, job_ids: &[String]) -> Result<()> {
+    let tx = conn.unchecked_transaction()
+        .context("Failed to start transaction for reorder_retranscription_queue")?;
+
+    let mut ordered: Vec<String> = job_ids.to_vec();
+    {
+        let mut stmt = tx.prepare("SELECT id FROM retranscription_queue ORDER BY position")
+            .context("Failed to prepare queue order query")?;
+        let existing = stmt.query_map([], |row| row.get::<_, String>(0))
+            .context("Failed to query queue order")?
+            .collect::<std::result::Result<Vec<_>, _>>()
+            .context("Failed to collect queue order")?;
+
+        ordered.retain(|id| existing.contains(id));
+        for id in existing {
+            if !ordered.contains(&id) {
+                ordered.push(id);
+            }
+        }
+    }
+
+    for (position, id) in ordered.iter().enumerate() {
+        tx.execute(
+            "UPDATE retranscription_queue SET position = ? WHERE id = ?",
+            params![position as i64 + 1, id],
+        ).context("Failed to update job position")?;
+    }
+
+    tx.commit().context("Failed to commit queue reorder")?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::database::models::Recording;
+    use tempfile::tempdir;
+
+    fn create_test_db() -> DatabaseManager {
+        let dir = tempdir().unwrap();
+        let db_path = dir.path().join("test.db");
+        DatabaseManager::new(db_path).unwrap()
+    }
+
+    fn create_recordings(db: &DatabaseManager, ids: &[&str]) {
+        for id in ids {
+            db.create_recording(&Recording::new(id.to_string(), id.to_string())).unwrap();
+        }
+    }
+
+    #[test]
+    fn test_enqueue_and_next_pending() {
+        let db = create_test_db();
+        create_recordings(&db, &["rec_q1", "rec_q2"]);
+
+        let first = db.enqueue_retranscription_job("rec_q1", "{}").unwrap();
+        let second = db.enqueue_retranscription_job("rec_q2", "{}").unwrap();
+
+        assert_eq!(db.next_pending_retranscription_job().unwrap().unwrap().id, first);
+
+        db.set_retranscription_job_status(&first, "running", None).unwrap();
+        assert_eq!(db.next_pending_retranscription_job().unwrap().unwrap().id, second);
+
+        // Running jobs can't be deleted, and come back as pending after a restart
+        assert!(!db.delete_retranscription_job(&first).unwrap());
+        assert_eq!(db.reset_running_retranscription_jobs().unwrap(), 1);
+        assert_eq!(db.next_pending_retranscription_job().unwrap().unwrap().id, first);
+    }
+
+    #[test]
+    fn test_reorder_queue() {
+        let db = create_test_db();
+        create_recordings(&db, &["rec_r1", "rec_r2", "rec_r3"]);
+
+        let a = db.enqueue_retranscription_job("rec_r1", "{}").unwrap();
+        let b = db.enqueue_retranscription_job("rec_r2", "{}").unwrap();
+        let c = db.enqueue_retranscription_job("rec_r3", "{}").unwrap();
+
+        // Unknown IDs are ignored, unlisted jobs keep their order at the end
+        db.reorder_retranscription_queue(&[c.clone(), "missing".to_string(), a.clone()]).unwrap();
+
+        let order: Vec<String> = db.get_retranscription_queue().unwrap().into_iter().map(|j| j.id).collect();
+        assert_eq!(order, vec![c, a, b]);
+    }
+
+    #[test]
+    fn test_delete_failed_jobs() {
+        let db = create_test_db();
+        create_recordings(&db, &["rec_f1", "rec_f2"]);
+
+        let failed = db.enqueue_retranscription_job("rec_f1", "{}").unwrap();
+        let pending = db.enqueue_retranscription_job("rec_f2", "{}").unwrap();
+        db.set_retranscription_job_status(&failed, "failed", Some("Recording has no audio file")).unwrap();
+
+        // Failed jobs stay visible with their error until cleared
+        let queue = db.get_retranscription_queue().unwrap();
+        assert_eq!(queue[0].error.as_deref(), Some("Recording has no audio file"));
+
+        assert_eq!(db.delete_failed_retranscription_jobs().unwrap(), 1);
+        let order: Vec<String> = db.get_retranscription_queue().unwrap().into_iter().map(|j| j.id).collect();
+        assert_eq!(order, vec![pending]);
+    }
+
+    #[test]
+    fn test_jobs_removed_with_recording() {
+        let db = create_test_db();
+        create_recordings(&db, &["rec_gone"]);
+
+        db.enqueue_retranscription_job("rec_gone", "{}").unwrap();
+        db.delete_recording("rec_gone").unwrap();
+
+        assert!(db.get_retranscription_queue().unwrap().is_empty());
+    }
+}
@@ -0,0 +1,286 @@
+// Scheduled recordings repository for Meeting-Local
+// Handles CRUD operations for recordings started automatically by the scheduler
+
+use anyhow::{Context, Result};
+use rusqlite::{Connection, params, Row};
+
+use super::models::{
+    CreateScheduledRecording, ScheduleRecurrence, ScheduledRecording, UpdateScheduledRecording,
+};
+use super::DatabaseManager;
+
+const SELECT_COLUMNS: &str = "id, name, start_time, recurrence, duration_minutes, mic_device, \
+     system_device, enabled, last_triggered_at, created_at";
+
+impl DatabaseManager {
+    /// Create a scheduled recording, returning its ID
+    pub fn create_scheduled_recording(&self, schedule: &CreateScheduledRecording) -> Result<String> {
+        self.with_connection(|conn| {
+            create_scheduled_recording_impl(conn, schedule)
+        })
+    }
+
+    /// Get a scheduled recording by ID
+    pub fn get_scheduled_recording(&self, id: &str) -> Result<Option<ScheduledRecording>> {
+        self.with_connection(|conn| {
+            get_scheduled_recording_impl(conn, id)
+        })
+    }
+
+    /// Get all scheduled recordings ordered by start time
+    pub fn get_all_scheduled_recordings(&self) -> Result<Vec<ScheduledRecording>> {
+        self.with_connection(|conn| {
+            get_scheduled_recordings_impl(conn, false)
+        })
+    }
+
+    /// Get enabled scheduled recordings (used by the scheduler)
+    pub fn get_enabled_scheduled_recordings(&self) -> Result<Vec<ScheduledRecording>> {
+        self.with_connection(|conn| {
+            get_scheduled_recordings_impl(conn, true)
+        })
+    }
+
+    /// Update a scheduled recording
+    pub fn update_scheduled_recording(&self, id: &str, update: &UpdateScheduledRecording) -> Result<()> {
+        self.with_connection(|conn| {
+            update_scheduled_recording_impl(conn, id, update)
+        })
+    }
+
+    /// Record that an occurrence was started or skipped
+    pub fn mark_scheduled_recording_triggered(&self, id: &str, occurrence: &str) -> Result<()> {
+        self.with_connection(|conn| {
+            conn.execute(
+                "UPDATE scheduled_recordings SET last_triggered_at = ? WHERE id = ?",
+                params![occurrence, id],
+            ).context("Failed to mark scheduled recording triggered")?;
+            Ok(())
+        })
+    }
+
+    /// Delete a scheduled recording
+    pub fn delete_scheduled_recording(&self, id: &str) -> Result<()> {
+        self.with_connection(|conn| {
+            conn.execute(
+                "DELETE FROM scheduled_recordings WHERE id = ?",
+                params![id],
+            ).context("Failed to delete scheduled recording")?;
+            Ok(())
+        })
+    }
+}
+
+fn row_to_schedule(row: &Row) -> rusqlite::Result<ScheduledRecording> {
+    Ok(ScheduledRecording {
+        id: row.get(0)?,
+        name: row.get(1)?,
+        start_time: row.get(2)?,
+        recurrence: ScheduleRecurrence::from_str(&row.get::<_, String>(3)?),
+        duration_minutes: row.get(4)?,
+        mic_device: row.get(5)?,
+        system_device: row.get(6)?,
+        enabled: row.get::<_, i32>(7)? != 0,
+        last_triggered_at: row.get(8)?,
+        created_at: row.get(9)?,
+    })
+}
+
+fn validate_start_time(start_time: &str) -> Result<()> {
+    chrono::DateTime::parse_from_rfc3339(start_time)
+        .with_context(|| format!("Invalid start time '{}', expected RFC 3339", start_time))?;
+    Ok(())
+}
+
+fn create_scheduled_recording_impl(conn: &Connection, schedule: &CreateScheduledRecording) -> Result<String> {
+    validate_start_time(&schedule.start_time)?;
+
+    let id = format!("schedule_{}", uuid::Uuid::new_v4());
+    let now = chrono::Utc::now().to_rfc3339();
+
+    conn.execute(
+        r#"INSERT INTO scheduled_recordings
+           (id, name, start_time, recurrence, duration_minutes, mic_device, system_device, enabled, created_at)
+           VALUES (?, ?, ?, ?, ?, ?, ?, 1, ?)"#,
+        params![
+            id,
+            schedule.name,
+            schedule.start_time,
+            schedule.recurrence.as_str(),
+            schedule.duration_minutes,
+            schedule.mic_device,
+            schedule.system_device,
+            now,
+        ],
+    ).context("Failed to create scheduled recording")?;
+
+    Ok(id)
+}
+
+fn get_scheduled_recording_impl(conn: &Connection, id: &str) -> Result<Option<ScheduledRecording>> {
+    let mut stmt = conn.prepare(
+        &format!("SELECT {} FROM scheduled_recordings WHERE id = ?", SELECT_COLUMNS)
+    ).context("Failed to prepare get_scheduled_recording query")?;
+
+    match stmt.query_row(params![id], row_to_schedule) {
+        Ok(schedule) => Ok(Some(schedule)),
+        Err(rusqlite::Error::QueryReturnedNoRows) => Ok(None),
+        Err(e) => Err(e).context("Failed to get scheduled recording"),
+    }
+}
+
+fn get_scheduled_recordings_impl(conn: &Connection, enabled_only: bool) -> Result<Vec<ScheduledRecording>> {
+    let filter = if enabled_only { "WHERE enabled = 1" } else { "" };
+    let mut stmt = conn.prepare(
+        &format!("SELECT {} FROM scheduled_recordings {} ORDER BY start_time", SELECT_COLUMNS, filter)
+    ).context("Failed to prepare get_scheduled_recordings query")?;
+
+    let schedules = stmt.query_map([], row_to_schedule)
+        .context("Failed to query scheduled recordings")?;
+
+    schedules.collect::<std::result::Result<Vec<_>, _>>()
+        .context("Failed to collect scheduled recordings")
+}
+
+fn update_scheduled_recording_impl(conn: &Connection, id: &str, update: &UpdateScheduledRecording) -> Result<()> {
+    let existing = get_scheduled_recording_impl(conn, id)?
+        .with_context(|| format!("Scheduled recording not found: {}", id))?;
+
+    if let Some(ref start_time) = update.start_time {
+        validate_start_time(start_time)?;
+    }
+
+    // Changing when the schedule fires resets the trigger history
+    let timing_changed = update.start_time.as_ref().is_some_and(|t| *t != existing.start_time)
+        || update.recurrence.is_some_and(|r| r != existing.recurrence);
+    let last_triggered_at = if timing_changed { None } else { existing.last_triggered_at };
+
+    conn.execute(
+        r#"UPDATE scheduled_recordings SET
+           name = ?, start_time = ?, recurrence = ?, duration_minutes = ?,
+           mic_device = ?, system_device = ?, enabled = ?, last_triggered_at = ?
+           WHERE id = ?"#,
+        params![
+            update.name.as_ref().unwrap_or(&existing.name),
+            update.start_time.as_ref().unwrap_or(&existing.start_time),
+            update.recurrence.unwrap_or(existing.recurrence).as_str(),
+            update.duration_minutes.unwrap_or(existing.duration_minutes),
+            update.mic_device.as_ref().unwrap_or(&existing.mic_device),
+            update.system_device.as_ref().unwrap_or(&existing.system_device),
+            update.enabled.unwrap_or(existing.enabled) as i32,
+            last_triggered_at,
+            id,
+        ],
+    ).context("Failed to update scheduled recording")?;
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::tempdir;
+
+    fn create_test_db() -> DatabaseManager {
+        let dir = tempdir().unwrap();
+        let db_path = dir.path().join("test.db");
+        DatabaseManager::new(db_path).unwrap()
+    }
+
+    fn standup() -> CreateScheduledRecording {
+        CreateScheduledRecording {
+            name: "Daily standup".to_string(),
+            start_time: "2025-01-06T09:30:00+01:00".to_string(),
+            recurrence: ScheduleRecurrence::Weekdays,
+            duration_minutes: Some(15),
+            mic_device: Some("MacBook Pro Microphone".to_string()),
+            system_device: None,
+        }
+    }
+
+    #[test]
+    fn test_create_and_get_scheduled_recording() {
+        let db = create_test_db();
+
+        let id = db.create_scheduled_recording(&standup()).unwrap();
+        let schedule = db.get_scheduled_recording(&id).unwrap().unwrap();
+
+        assert_eq!(schedule.name, "Daily standup");
+        assert_eq!(schedule.recurrence, ScheduleRecurrence::Weekdays);
+        assert_eq!(schedule.duration_minutes, Some(15));
+        assert!(schedule.enabled);
+        assert!(schedule.last_triggered_at.is_none());
+    }
+
+    #[test]
+    fn test_create_rejects_invalid_start_time() {
+        let db = create_test_db();
+
+        let mut schedule = standup();
+        schedule.start_time = "tomorrow at 9".to_string();
+        assert!(db.create_scheduled_recording(&schedule).is_err());
+    }
+
+    #[test]
+    fn test_update_resets_trigger_history_when_timing_changes() {
+        let db = create_test_db();
+
+        let id = db.create_scheduled_recording(&standup()).unwrap();
+        db.mark_scheduled_recording_triggered(&id, "2025-01-06T09:30:00+01:00").unwrap();
+
+        // Disabling keeps the history
+        db.update_scheduled_recording(&id, &UpdateScheduledRecording {
+            enabled: Some(false),
+            ..Default::default()
+        }).unwrap();
+        let schedule = db.get_scheduled_recording(&id).unwrap().unwrap();
+        assert!(!schedule.enabled);
+        assert!(schedule.last_triggered_at.is_some());
+        assert!(db.get_enabled_scheduled_recordings().unwrap().is_empty());
+
+        // Moving the start time clears it
+        db.update_scheduled_recording(&id, &UpdateScheduledRecording {
+            start_time: Some("2025-01-06T10:00:00+01:00".to_string()),
+            ..Default::default()
+        }).unwrap();
+        let schedule = db.get_scheduled_recording(&id).unwrap().unwrap();
+        assert!(schedule.last_triggered_at.is_none());
+        assert_eq!(schedule.name, "Daily standup");
+    }
+
+    #[test]
+    fn test_update_clears_optional_fields() {
+        let db = create_test_db();
+
+        let id = db.create_scheduled_recording(&standup()).unwrap();
+
+        // Missing fields keep their value, null clears it
+        let update: UpdateScheduledRecording =
+            serde_json::from_str(r#"{"duration_minutes": null, "name": "Standup"}"#).unwrap();
+        db.update_scheduled_recording(&id, &update).unwrap();
+        let schedule = db.get_scheduled_recording(&id).unwrap().unwrap();
+        assert_eq!(schedule.duration_minutes, None);
+        assert_eq!(schedule.mic_device.as_deref(), Some("MacBook Pro Microphone"));
+
+        db.update_scheduled_recording(&id, &UpdateScheduledRecording {
+            mic_device: Some(None),
+            system_device: Some(Some("BlackHole 2ch".to_string())),
+            ..Default::default()
+        }).unwrap();
+        let schedule = db.get_scheduled_recording(&id).unwrap().unwrap();
+        assert_eq!(schedule.mic_device, None);
+        assert_eq!(schedule.system_device.as_deref(), Some("BlackHole 2ch"));
+        assert_eq!(schedule.name, "Standup");
+    }
+
+    #[test]
+    fn test_delete_scheduled_recording() {
+        let db = create_test_db();
+
+        let id = db.create_scheduled_recording(&standup()).unwrap();
+        db.delete_scheduled_recording(&id).unwrap();
+
+        assert!(db.get_scheduled_recording(&id).unwrap().is_none());
+        assert!(db.get_all_scheduled_recordings().unwrap().is_empty());
+    }
+}
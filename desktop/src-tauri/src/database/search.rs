@@ -1,12 +1,27 @@
 // Search functionality for Meeting-Local
-// Full-text search across recordings and transcripts
+// Full-text search across recordings, transcripts and chat messages
 
-use anyhow::{Context, Result};
+use anyhow::{bail, Context, Result};
 use rusqlite::{params, Connection};
 
-use super::models::{Recording, SearchResult, SearchFilters, Category, Tag};
+use super::models::{
+    Recording, SearchResult, SearchFilters, Category, Tag, ChatMessageSearchResult, ChatRole,
+    GlobalSearchKind, GlobalSearchResult,
+};
 use super::DatabaseManager;
 
+/// Score range of full-text matches; exact and prefix name matches rank above it
+const MIN_FTS_SCORE: f64 = 0.3;
+const MAX_FTS_SCORE: f64 = 0.8;
+
+/// Transcript match snippet length in tokens; FTS5 allows at most 64
+const DEFAULT_SNIPPET_TOKENS: usize = 32;
+const MAX_SNIPPET_TOKENS: usize = 64;
+
+/// Transcript matches returned per search
+const DEFAULT_RESULT_LIMIT: usize = 50;
+const MAX_RESULT_LIMIT: usize = 500;
+
 impl DatabaseManager {
     /// Search recordings by query and filters
     pub fn search_recordings(&self, query: &str, filters: &SearchFilters) -> Result<Vec<SearchResult>> {
@@ -14,6 +29,27 @@ impl DatabaseManager {
             search_recordings_impl(conn, query, filters)
         })
     }
+
+    /// Search chat message content across all recordings, newest first.
+    /// `snippet_tokens` is clamped to 1..=64 (default 32), as for transcripts.
+    pub fn search_chat_messages(
+        &self,
+        query: &str,
+        limit: usize,
+        snippet_tokens: Option<usize>,
+    ) -> Result<Vec<ChatMessageSearchResult>> {
+        self.with_connection(|conn| {
+            search_chat_messages_impl(conn, query, limit, snippet_tokens)
+        })
+    }
+
+    /// Search recording titles, transcripts, chat messages and template names
+    /// and return one list ranked by relevance
+    pub fn global_search(&self, query: &str, limit: usize) -> Result<Vec<GlobalSearchResult>> {
+        self.with_connection(|conn| {
+            global_search_impl(conn, query, limit)
+        })
+    }
 }
 
 /// Search recordings by title, transcript content, categories, and tags
@@ -22,6 +58,7 @@ fn search_recordings_impl(
     query: &str,
     filters: &SearchFilters,
 ) -> Result<Vec<SearchResult>> {
+    validate_filters(filters)?;
     let mut results = Vec::new();
 
     // If we have a text query, search both title and transcripts
@@ -65,21 +102,40 @@ fn search_recordings_impl(
     Ok(results)
 }
 
+/// Snippet length in tokens for FTS5 `snippet()`, defaulted and clamped to the allowed range
+fn snippet_token_count(requested: Option<usize>) -> i64 {
+    requested.unwrap_or(DEFAULT_SNIPPET_TOKENS).clamp(1, MAX_SNIPPET_TOKENS) as i64
+}
+
+fn validate_filters(filters: &SearchFilters) -> Result<()> {
+    if let Some(tokens) = filters.snippet_tokens {
+        if !(1..=MAX_SNIPPET_TOKENS).contains(&tokens) {
+            bail!("Snippet length must be between 1 and {} tokens", MAX_SNIPPET_TOKENS);
+        }
+    }
+    if let Some(limit) = filters.result_limit {
+        if !(1..=MAX_RESULT_LIMIT).contains(&limit) {
+            bail!("Result limit must be between 1 and {}", MAX_RESULT_LIMIT);
+        }
+    }
+    Ok(())
+}
+
 /// Search recordings by title
 fn search_by_title(
     conn: &Connection,
     query: &str,
     filters: &SearchFilters,
 ) -> Result<Vec<SearchResult>> {
-    let search_pattern = format!("%{}%", query);
+    let search_pattern = like_pattern(query);
 
     let mut sql = String::from(
         r#"
         SELECT DISTINCT r.id, r.title, r.created_at, r.completed_at, r.duration_seconds,
                r.status, r.audio_file_path, r.meeting_folder_path, r.microphone_device,
-               r.system_audio_device, r.sample_rate, r.transcription_model, r.language, r.diarization_provider
+               r.system_audio_device, r.sample_rate, r.transcription_model, r.language, r.diarization_provider, r.transcription_quality
         FROM recordings r
-        WHERE r.title LIKE ?1
+        WHERE r.title LIKE ?1 ESCAPE '\'
         "#
     );
 
@@ -152,6 +208,7 @@ fn search_by_title(
             transcription_model: row.get(11)?,
             language: row.get(12)?,
             diarization_provider: row.get(13)?,
+            transcription_quality: row.get(14)?,
         })
     }).context("Failed to execute search query")?;
 
@@ -230,21 +287,26 @@ fn search_transcripts_fts(
 ) -> Result<Vec<SearchResult>> {
     // FTS5 query - escape special characters
     let fts_query = query.replace("\"", "\"\"");
+    let snippet_tokens = snippet_token_count(filters.snippet_tokens);
+    let result_limit = filters.result_limit.unwrap_or(DEFAULT_RESULT_LIMIT);
 
     let mut sql = String::from(
         r#"
         SELECT DISTINCT r.id, r.title, r.created_at, r.completed_at, r.duration_seconds,
                r.status, r.audio_file_path, r.meeting_folder_path, r.microphone_device,
-               r.system_audio_device, r.sample_rate, r.transcription_model, r.language, r.diarization_provider,
-               snippet(transcript_fts, 1, '<mark>', '</mark>', '...', 32) as matched_text
+               r.system_audio_device, r.sample_rate, r.transcription_model, r.language, r.diarization_provider, r.transcription_quality,
+               snippet(transcript_fts, 1, '<mark>', '</mark>', '...', ?2) as matched_text
         FROM recordings r
         INNER JOIN transcript_fts fts ON r.id = fts.recording_id
         WHERE transcript_fts MATCH ?1
         "#
     );
 
-    let mut param_count = 1;
-    let mut params_vec: Vec<Box<dyn rusqlite::ToSql>> = vec![Box::new(format!("\"{}\"", fts_query))];
+    let mut param_count = 2;
+    let mut params_vec: Vec<Box<dyn rusqlite::ToSql>> = vec![
+        Box::new(format!("\"{}\"", fts_query)),
+        Box::new(snippet_tokens),
+    ];
 
     // Add date filters
     if let Some(ref date_from) = filters.date_from {
@@ -286,12 +348,15 @@ fn search_transcripts_fts(
                 placeholders.join(", ")
             ));
             for id in tag_ids {
+                param_count += 1;
                 params_vec.push(Box::new(id.clone()));
             }
         }
     }
 
-    sql.push_str(" ORDER BY r.created_at DESC LIMIT 50");
+    param_count += 1;
+    sql.push_str(&format!(" ORDER BY r.created_at DESC LIMIT ?{}", param_count));
+    params_vec.push(Box::new(result_limit as i64));
 
     let params_refs: Vec<&dyn rusqlite::ToSql> = params_vec.iter().map(|p| p.as_ref()).collect();
 
@@ -313,8 +378,9 @@ fn search_transcripts_fts(
                 transcription_model: row.get(11)?,
                 language: row.get(12)?,
                 diarization_provider: row.get(13)?,
+                transcription_quality: row.get(14)?,
             },
-            row.get::<_, String>(14)?,
+            row.get::<_, String>(15)?,
         ))
     }).context("Failed to execute FTS query")?;
 
@@ -337,6 +403,193 @@ fn search_transcripts_fts(
     Ok(results)
 }
 
+fn search_chat_messages_impl(
+    conn: &Connection,
+    query: &str,
+    limit: usize,
+    snippet_tokens: Option<usize>,
+) -> Result<Vec<ChatMessageSearchResult>> {
+    if query.trim().is_empty() {
+        return Ok(Vec::new());
+    }
+
+    // FTS5 query - escape special characters
+    let fts_query = format!("\"{}\"", query.replace("\"", "\"\""));
+
+    let mut stmt = conn.prepare(
+        r#"
+        SELECT m.id, m.session_id, s.title, m.recording_id, r.title, m.role,
+               snippet(chat_messages_fts, 0, '<mark>', '</mark>', '...', ?3) as matched_text,
+               m.created_at
+        FROM chat_messages_fts fts
+        INNER JOIN chat_messages m ON m.rowid = fts.rowid
+        INNER JOIN recordings r ON r.id = m.recording_id
+        LEFT JOIN chat_sessions s ON s.id = m.session_id
+        WHERE chat_messages_fts MATCH ?1
+        ORDER BY m.created_at DESC
+        LIMIT ?2
+        "#
+    ).context("Failed to prepare chat message search query")?;
+
+    let results = stmt.query_map(params![fts_query, limit as i64, snippet_token_count(snippet_tokens)], |row| {
+        Ok(ChatMessageSearchResult {
+            message_id: row.get(0)?,
+            session_id: row.get(1)?,
+            session_title: row.get(2)?,
+            recording_id: row.get(3)?,
+            recording_title: row.get(4)?,
+            role: ChatRole::from_str(&row.get::<_, String>(5)?),
+            matched_text: row.get(6)?,
+            created_at: row.get(7)?,
+        })
+    }).context("Failed to execute chat message search query")?;
+
+    results.collect::<std::result::Result<Vec<_>, _>>()
+        .context("Failed to read chat message search results")
+}
+
+/// LIKE pattern matching `query` as a literal substring (used with `ESCAPE '\'`)
+fn like_pattern(query: &str) -> String {
+    let escaped = query
+        .replace('\\', "\\\\")
+        .replace('%', "\\%")
+        .replace('_', "\\_");
+    format!("%{}%", escaped)
+}
+
+/// Relevance of a name match: exact, prefix, then substring
+fn name_match_score(name: &str, query: &str) -> f64 {
+    let name = name.to_lowercase();
+    let query = query.to_lowercase();
+    if name == query {
+        1.0
+    } else if name.starts_with(&query) {
+        0.9
+    } else {
+        0.75
+    }
+}
+
+/// Map an FTS5 bm25 rank (negative, lower is better) to MIN_FTS_SCORE - MAX_FTS_SCORE
+fn fts_rank_score(rank: f64) -> f64 {
+    let relevance = (-rank).max(0.0);
+    MIN_FTS_SCORE + (MAX_FTS_SCORE - MIN_FTS_SCORE) * relevance / (relevance + 1.0)
+}
+
+fn global_search_impl(conn: &Connection, query: &str, limit: usize) -> Result<Vec<GlobalSearchResult>> {
+    let query = query.trim();
+    if query.is_empty() || limit == 0 {
+        return Ok(Vec::new());
+    }
+
+    let like_pattern = like_pattern(query);
+    let fts_query = format!("\"{}\"", query.replace("\"", "\"\""));
+    let mut results = Vec::new();
+
+    // Recording titles
+    let mut stmt = conn.prepare(
+        "SELECT id, title FROM recordings WHERE title LIKE ?1 ESCAPE '\\' ORDER BY created_at DESC LIMIT ?2"
+    ).context("Failed to prepare recording title search")?;
+    let rows = stmt.query_map(params![like_pattern, limit as i64], |row| {
+        Ok((row.get::<_, String>(0)?, row.get::<_, String>(1)?))
+    }).context("Failed to search recording titles")?;
+    for row in rows {
+        let (id, title) = row.context("Failed to read recording title result")?;
+        results.push(GlobalSearchResult {
+            kind: GlobalSearchKind::Recording,
+            score: name_match_score(&title, query),
+            recording_id: Some(id.clone()),
+            id,
+            title,
+            snippet: None,
+            audio_start_time: None,
+        });
+    }
+
+    // Transcript segments
+    let mut stmt = conn.prepare(
+        r#"
+        SELECT t.id, t.recording_id, r.title, t.audio_start_time,
+               snippet(transcript_fts, 1, '<mark>', '</mark>', '...', 16),
+               bm25(transcript_fts)
+        FROM transcript_fts fts
+        INNER JOIN transcript_segments t ON t.rowid = fts.rowid
+        INNER JOIN recordings r ON r.id = t.recording_id
+        WHERE transcript_fts MATCH ?1
+        ORDER BY bm25(transcript_fts)
+        LIMIT ?2
+        "#
+    ).context("Failed to prepare transcript search")?;
+    let rows = stmt.query_map(params![fts_query, limit as i64], |row| {
+        Ok(GlobalSearchResult {
+            kind: GlobalSearchKind::Transcript,
+            id: row.get(0)?,
+            recording_id: Some(row.get(1)?),
+            title: row.get(2)?,
+            audio_start_time: Some(row.get(3)?),
+            snippet: Some(row.get(4)?),
+            score: fts_rank_score(row.get(5)?),
+        })
+    }).context("Failed to search transcripts")?;
+    for row in rows {
+        results.push(row.context("Failed to read transcript result")?);
+    }
+
+    // Chat messages
+    let mut stmt = conn.prepare(
+        r#"
+        SELECT m.id, m.recording_id, r.title,
+               snippet(chat_messages_fts, 0, '<mark>', '</mark>', '...', 16),
+               bm25(chat_messages_fts)
+        FROM chat_messages_fts fts
+        INNER JOIN chat_messages m ON m.rowid = fts.rowid
+        INNER JOIN recordings r ON r.id = m.recording_id
+        WHERE chat_messages_fts MATCH ?1
+        ORDER BY bm25(chat_messages_fts)
+        LIMIT ?2
+        "#
+    ).context("Failed to prepare chat message search")?;
+    let rows = stmt.query_map(params![fts_query, limit as i64], |row| {
+        Ok(GlobalSearchResult {
+            kind: GlobalSearchKind::ChatMessage,
+            id: row.get(0)?,
+            recording_id: Some(row.get(1)?),
+            title: row.get(2)?,
+            snippet: Some(row.get(3)?),
+            audio_start_time: None,
+            score: fts_rank_score(row.get(4)?),
+        })
+    }).context("Failed to search chat messages")?;
+    for row in rows {
+        results.push(row.context("Failed to read chat message result")?);
+    }
+
+    // Template names
+    let mut stmt = conn.prepare(
+        "SELECT id, name, description FROM prompt_templates WHERE name LIKE ?1 ESCAPE '\\' ORDER BY sort_order LIMIT ?2"
+    ).context("Failed to prepare template search")?;
+    let rows = stmt.query_map(params![like_pattern, limit as i64], |row| {
+        Ok((row.get::<_, String>(0)?, row.get::<_, String>(1)?, row.get::<_, Option<String>>(2)?))
+    }).context("Failed to search templates")?;
+    for row in rows {
+        let (id, name, description) = row.context("Failed to read template result")?;
+        results.push(GlobalSearchResult {
+            kind: GlobalSearchKind::Template,
+            score: name_match_score(&name, query),
+            id,
+            recording_id: None,
+            title: name,
+            snippet: description,
+            audio_start_time: None,
+        });
+    }
+
+    // Stable sort keeps the per-kind order (recency / rank) among equal scores
+    results.sort_by(|a, b| b.score.total_cmp(&a.score));
+    results.truncate(limit);
+    Ok(results)
+}
+
 /// Filter recordings without text search
 fn filter_recordings(
     conn: &Connection,
@@ -346,7 +599,7 @@ fn filter_recordings(
         r#"
         SELECT r.id, r.title, r.created_at, r.completed_at, r.duration_seconds,
                r.status, r.audio_file_path, r.meeting_folder_path, r.microphone_device,
-               r.system_audio_device, r.sample_rate, r.transcription_model, r.language, r.diarization_provider
+               r.system_audio_device, r.sample_rate, r.transcription_model, r.language, r.diarization_provider, r.transcription_quality
         FROM recordings r
         WHERE 1=1
         "#
@@ -421,6 +674,7 @@ fn filter_recordings(
             transcription_model: row.get(11)?,
             language: row.get(12)?,
             diarization_provider: row.get(13)?,
+            transcription_quality: row.get(14)?,
         })
     }).context("Failed to execute filter query")?;
 
@@ -449,18 +703,18 @@ fn search_by_category_name(
     query: &str,
     filters: &SearchFilters,
 ) -> Result<Vec<SearchResult>> {
-    let search_pattern = format!("%{}%", query);
+    let search_pattern = like_pattern(query);
 
     let mut sql = String::from(
         r#"
         SELECT DISTINCT r.id, r.title, r.created_at, r.completed_at, r.duration_seconds,
                r.status, r.audio_file_path, r.meeting_folder_path, r.microphone_device,
-               r.system_audio_device, r.sample_rate, r.transcription_model, r.language, r.diarization_provider,
+               r.system_audio_device, r.sample_rate, r.transcription_model, r.language, r.diarization_provider, r.transcription_quality,
                c.name as category_name
         FROM recordings r
         INNER JOIN recording_categories rc ON r.id = rc.recording_id
         INNER JOIN categories c ON rc.category_id = c.id
-        WHERE c.name LIKE ?1
+        WHERE c.name LIKE ?1 ESCAPE '\'
         "#
     );
 
@@ -501,8 +755,9 @@ fn search_by_category_name(
                 transcription_model: row.get(11)?,
                 language: row.get(12)?,
                 diarization_provider: row.get(13)?,
+                transcription_quality: row.get(14)?,
             },
-            row.get::<_, String>(14)?,
+            row.get::<_, String>(15)?,
         ))
     }).context("Failed to execute category name search query")?;
 
@@ -531,18 +786,18 @@ fn search_by_tag_name(
     query: &str,
     filters: &SearchFilters,
 ) -> Result<Vec<SearchResult>> {
-    let search_pattern = format!("%{}%", query);
+    let search_pattern = like_pattern(query);
 
     let mut sql = String::from(
         r#"
         SELECT DISTINCT r.id, r.title, r.created_at, r.completed_at, r.duration_seconds,
                r.status, r.audio_file_path, r.meeting_folder_path, r.microphone_device,
-               r.system_audio_device, r.sample_rate, r.transcription_model, r.language, r.diarization_provider,
+               r.system_audio_device, r.sample_rate, r.transcription_model, r.language, r.diarization_provider, r.transcription_quality,
                t.name as tag_name
         FROM recordings r
         INNER JOIN recording_tags rt ON r.id = rt.recording_id
         INNER JOIN tags t ON rt.tag_id = t.id
-        WHERE t.name LIKE ?1
+        WHERE t.name LIKE ?1 ESCAPE '\'
         "#
     );
 
@@ -583,8 +838,9 @@ fn search_by_tag_name(
                 transcription_model: row.get(11)?,
                 language: row.get(12)?,
                 diarization_provider: row.get(13)?,
+                transcription_quality: row.get(14)?,
             },
-            row.get::<_, String>(14)?,
+            row.get::<_, String>(15)?,
         ))
     }).context("Failed to execute tag name search query")?;
 
@@ -638,4 +894,138 @@ mod tests {
 
         assert_eq!(results.len(), 0);
     }
+
+    #[test]
+    fn test_search_chat_messages() {
+        let conn = setup_test_db();
+        conn.execute_batch(r#"
+            INSERT INTO recordings (id, title, created_at) VALUES ('rec_1', 'Planning', '2024-01-01T10:00:00Z');
+            INSERT INTO chat_sessions (id, recording_id, title) VALUES ('ses_1', 'rec_1', 'Budget questions');
+            INSERT INTO chat_messages (id, recording_id, session_id, role, content, sequence_id)
+            VALUES ('msg_1', 'rec_1', 'ses_1', 'user', 'What was the marketing budget?', 1),
+                   ('msg_2', 'rec_1', 'ses_1', 'assistant', 'The team agreed on a launch date.', 2);
+        "#).unwrap();
+
+        let results = search_chat_messages_impl(&conn, "budget", 10, None).unwrap();
+        assert_eq!(results.len(), 1);
+        assert_eq!(results[0].message_id, "msg_1");
+        assert_eq!(results[0].session_title.as_deref(), Some("Budget questions"));
+        assert_eq!(results[0].recording_title, "Planning");
+        assert!(results[0].matched_text.contains("<mark>budget</mark>"));
+
+        // Edited content is re-indexed
+        conn.execute("UPDATE chat_messages SET content = 'Launch budget is fixed' WHERE id = 'msg_2'", []).unwrap();
+        assert_eq!(search_chat_messages_impl(&conn, "budget", 10, None).unwrap().len(), 2);
+        assert!(search_chat_messages_impl(&conn, "launch date", 10, None).unwrap().is_empty());
+
+        assert!(search_chat_messages_impl(&conn, "  ", 10, None).unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_chat_message_search_snippet_length() {
+        let conn = setup_test_db();
+        conn.execute_batch(r#"
+            INSERT INTO recordings (id, title, created_at) VALUES ('rec_1', 'Planning', '2024-01-01T10:00:00Z');
+            INSERT INTO chat_messages (id, recording_id, role, content, sequence_id)
+            VALUES ('msg_1', 'rec_1', 'user', 'We spent most of the meeting on the marketing budget for the spring launch', 1);
+        "#).unwrap();
+
+        let snippet = |tokens: Option<usize>| {
+            search_chat_messages_impl(&conn, "budget", 10, tokens).unwrap().remove(0).matched_text
+        };
+        let short = snippet(Some(3));
+        assert!(short.contains("<mark>budget</mark>"));
+        assert!(short.split_whitespace().count() <= 3);
+
+        // Out-of-range lengths are clamped instead of reaching FTS5
+        assert!(snippet(Some(0)).contains("<mark>budget</mark>"));
+        assert_eq!(snippet(Some(1000)), snippet(None));
+    }
+
+    #[test]
+    fn test_global_search_ranks_mixed_results() {
+        let conn = setup_test_db();
+        conn.execute_batch(r#"
+            INSERT INTO recordings (id, title, created_at) VALUES
+                ('rec_1', 'Roadmap', '2024-01-01T10:00:00Z'),
+                ('rec_2', 'Weekly sync', '2024-01-02T10:00:00Z');
+            INSERT INTO transcript_segments (id, recording_id, text, audio_start_time, audio_end_time, duration, display_time, sequence_id)
+            VALUES ('seg_1', 'rec_2', 'Next we go through the roadmap for Q3', 12.5, 15.0, 2.5, '00:12', 1);
+            INSERT INTO chat_sessions (id, recording_id, title) VALUES ('ses_1', 'rec_2', 'Chat');
+            INSERT INTO chat_messages (id, recording_id, session_id, role, content, sequence_id)
+            VALUES ('msg_1', 'rec_2', 'ses_1', 'user', 'Summarize the roadmap discussion', 1);
+        "#).unwrap();
+
+        let results = global_search_impl(&conn, "roadmap", 10).unwrap();
+        assert_eq!(results.len(), 3);
+        // Exact title match ranks first
+        assert_eq!(results[0].kind, GlobalSearchKind::Recording);
+        assert_eq!(results[0].id, "rec_1");
+        assert!(results.windows(2).all(|w| w[0].score >= w[1].score));
+
+        let transcript = results.iter().find(|r| r.kind == GlobalSearchKind::Transcript).unwrap();
+        assert_eq!(transcript.recording_id.as_deref(), Some("rec_2"));
+        assert_eq!(transcript.audio_start_time, Some(12.5));
+        assert!(results.iter().any(|r| r.kind == GlobalSearchKind::ChatMessage));
+
+        // Built-in templates are searchable by name
+        let results = global_search_impl(&conn, "action items", 10).unwrap();
+        assert!(results.iter().any(|r| r.kind == GlobalSearchKind::Template));
+
+        assert_eq!(global_search_impl(&conn, "roadmap", 1).unwrap().len(), 1);
+    }
+
+    #[test]
+    fn test_like_wildcards_match_literally() {
+        let conn = setup_test_db();
+        conn.execute_batch(r#"
+            INSERT INTO recordings (id, title, created_at) VALUES
+                ('rec_1', 'Q3 at 100% capacity', '2024-01-01T10:00:00Z'),
+                ('rec_2', 'Q3 at 1000 capacity', '2024-01-02T10:00:00Z'),
+                ('rec_3', 'file_name review', '2024-01-03T10:00:00Z'),
+                ('rec_4', 'filexname review', '2024-01-04T10:00:00Z');
+        "#).unwrap();
+
+        let filters = SearchFilters::default();
+        let ids = |query: &str| -> Vec<String> {
+            search_recordings_impl(&conn, query, &filters).unwrap().into_iter().map(|r| r.recording.id).collect()
+        };
+        assert_eq!(ids("100%"), vec!["rec_1"]);
+        assert_eq!(ids("file_name"), vec!["rec_3"]);
+
+        let results = global_search_impl(&conn, "100%", 10).unwrap();
+        assert_eq!(results.len(), 1);
+        assert_eq!(results[0].id, "rec_1");
+    }
+
+    #[test]
+    fn test_transcript_search_snippet_length_and_limit() {
+        let conn = setup_test_db();
+        conn.execute_batch(r#"
+            INSERT INTO recordings (id, title, created_at) VALUES
+                ('rec_1', 'Planning', '2024-01-01T10:00:00Z'),
+                ('rec_2', 'Weekly sync', '2024-01-02T10:00:00Z');
+            INSERT INTO transcript_segments (id, recording_id, text, audio_start_time, audio_end_time, duration, display_time, sequence_id)
+            VALUES ('seg_1', 'rec_1', 'We spent most of the meeting on the marketing budget for the spring launch', 0.0, 5.0, 5.0, '00:00', 1),
+                   ('seg_2', 'rec_2', 'The budget stays the same', 0.0, 2.0, 2.0, '00:00', 1);
+        "#).unwrap();
+
+        let mut filters = SearchFilters { search_transcripts: true, ..Default::default() };
+        assert_eq!(search_recordings_impl(&conn, "budget", &filters).unwrap().len(), 2);
+
+        filters.result_limit = Some(1);
+        filters.snippet_tokens = Some(3);
+        let results = search_recordings_impl(&conn, "budget", &filters).unwrap();
+        assert_eq!(results.len(), 1);
+        assert_eq!(results[0].recording.id, "rec_2");
+        let snippet = &results[0].matched_text;
+        assert!(snippet.contains("<mark>budget</mark>"));
+        assert!(snippet.split_whitespace().count() <= 3);
+
+        filters.snippet_tokens = Some(65);
+        assert!(search_recordings_impl(&conn, "budget", &filters).is_err());
+        filters.snippet_tokens = None;
+        filters.result_limit = Some(0);
+        assert!(search_recordings_impl(&conn, "budget", &filters).is_err());
+    }
 }
@@ -135,6 +135,39 @@ fn load_all_settings_impl(conn: &Connection) -> Result<AllSettings> {
             "last_system_audio" => settings.last_system_audio = Some(value),
             "recordings_folder" => settings.recordings_folder = Some(value),
             "current_model" => settings.current_model = Some(value),
+            "custom_whisper_models" => settings.custom_whisper_models = Some(value),
+            "allow_mic_only_fallback" => settings.allow_mic_only_fallback = Some(value == "true"),
+            "final_loudnorm" => settings.final_loudnorm = value == "true",
+            "record_level_timeline" => settings.record_level_timeline = value == "true",
+            "trim_silence_on_save" => settings.trim_silence_on_save = value == "true",
+            "trim_min_silence_seconds" => settings.trim_min_silence_seconds = value.parse().ok(),
+            "keep_untrimmed_original" => settings.keep_untrimmed_original = Some(value == "true"),
+            "vad_aggressiveness" => settings.vad_aggressiveness = Some(value),
+            "vad_threshold" => settings.vad_threshold = value.parse().ok(),
+            "live_chunk_seconds" => settings.live_chunk_seconds = value.parse().ok(),
+            "save_channel_layout" => settings.save_channel_layout = Some(value),
+            "label_speakers_by_source" => settings.label_speakers_by_source = value == "true",
+            "live_rolling_context" => settings.live_rolling_context = value == "true",
+            "live_diarization" => settings.live_diarization = value == "true",
+            "diarization_provider" => settings.diarization_provider = Some(value),
+            "mic_speaker_label" => settings.mic_speaker_label = Some(value),
+            "system_speaker_label" => settings.system_speaker_label = Some(value),
+            "keep_model_loaded" => settings.keep_model_loaded = value == "true",
+            "model_idle_timeout_minutes" => settings.model_idle_timeout_minutes = value.parse().ok(),
+            "recording_hotkey" => settings.recording_hotkey = Some(value),
+            "auto_cleanup_unused_tags" => settings.auto_cleanup_unused_tags = value == "true",
+            "auto_summarize_on_complete" => settings.auto_summarize_on_complete = value == "true",
+            "completion_webhook_url" => settings.completion_webhook_url = Some(value),
+            "completion_webhook_allow_private" => settings.completion_webhook_allow_private = value == "true",
+            "tool_prompt_template" => settings.tool_prompt_template = Some(value),
+            "metrics_endpoint_enabled" => settings.metrics_endpoint_enabled = value == "true",
+            "metrics_endpoint_port" => settings.metrics_endpoint_port = value.parse().ok(),
+            "file_logging_enabled" => settings.file_logging_enabled = value == "true",
+            "capture_sample_rates" => settings.capture_sample_rates = Some(value),
+            "max_concurrent_tasks" => settings.max_concurrent_tasks = value.parse().ok(),
+            "whisper_models_dir" => settings.whisper_models_dir = Some(value),
+            "llm_models_dir" => settings.llm_models_dir = Some(value),
+            "diarization_models_dir" => settings.diarization_models_dir = Some(value),
             _ => {
                 log::debug!("Unknown setting key: {}", key);
             }
@@ -0,0 +1,228 @@
+// Speaker embeddings repository for Meeting-Local
+// Stores per-recording speaker centroid embeddings for retroactive speaker matching
+
+use anyhow::{Context, Result};
+use rusqlite::{Connection, params, Row};
+
+use super::models::RecordingSpeakerEmbedding;
+use super::DatabaseManager;
+
+const SELECT_COLUMNS: &str =
+    "recording_id, speaker_id, speaker_label, embedding, segment_count, registered_speaker_id";
+
+impl DatabaseManager {
+    /// Replace the stored speaker embeddings for a recording
+    pub fn save_recording_speaker_embeddings(
+        &self,
+        recording_id: &str,
+        embeddings: &[RecordingSpeakerEmbedding],
+    ) -> Result<()> {
+        self.with_connection(|conn| {
+            save_embeddings_impl(conn, recording_id, embeddings)
+        })
+    }
+
+    /// Get the speaker embeddings stored for a recording
+    pub fn get_recording_speaker_embeddings(&self, recording_id: &str) -> Result<Vec<RecordingSpeakerEmbedding>> {
+        self.with_connection(|conn| {
+            let mut stmt = conn.prepare(
+                &format!("SELECT {} FROM recording_speaker_embeddings WHERE recording_id = ? ORDER BY speaker_id", SELECT_COLUMNS)
+            ).context("Failed to prepare get_recording_speaker_embeddings query")?;
+
+            let embeddings = stmt.query_map(params![recording_id], row_to_embedding)
+                .context("Failed to query speaker embeddings")?;
+
+            embeddings.collect::<std::result::Result<Vec<_>, _>>()
+                .context("Failed to collect speaker embeddings")
+        })
+    }
+
+    /// Get the IDs of all recordings that have stored speaker embeddings
+    pub fn get_recordings_with_speaker_embeddings(&self) -> Result<Vec<String>> {
+        self.with_connection(|conn| {
+            let mut stmt = conn.prepare(
+                "SELECT DISTINCT recording_id FROM recording_speaker_embeddings ORDER BY recording_id"
+            ).context("Failed to prepare get_recordings_with_speaker_embeddings query")?;
+
+            let ids = stmt.query_map([], |row| row.get::<_, String>(0))
+                .context("Failed to query recordings with speaker embeddings")?;
+
+            ids.collect::<std::result::Result<Vec<_>, _>>()
+                .context("Failed to collect recording IDs")
+        })
+    }
+
+    /// Relabel a detected speaker as a registered speaker in one recording.
+    /// Updates the transcript segments and the stored embedding; returns segments updated.
+    pub fn assign_registered_speaker(
+        &self,
+        recording_id: &str,
+        speaker_id: &str,
+        registered_speaker_id: &str,
+        registered_name: &str,
+    ) -> Result<usize> {
+        self.with_connection(|conn| {
+            assign_registered_speaker_impl(conn, recording_id, speaker_id, registered_speaker_id, registered_name)
+        })
+    }
+}
+
+fn embedding_to_blob(embedding: &[f32]) -> Vec<u8> {
+    embedding.iter().flat_map(|v| v.to_le_bytes()).collect()
+}
+
+fn blob_to_embedding(blob: &[u8]) -> Vec<f32> {
+    blob.chunks_exact(4)
+        .map(|chunk| f32::from_le_bytes([chunk[0], chunk[1], chunk[2], chunk[3]]))
+        .collect()
+}
+
+fn row_to_embedding(row: &Row) -> rusqlite::Result<RecordingSpeakerEmbedding> {
+    let blob: Vec<u8> = row.get(3)?;
+    Ok(RecordingSpeakerEmbedding {
+        recording_id: row.get(0)?,
+        speaker_id: row.get(1)?,
+        speaker_label: row.get(2)?,
+        embedding: blob_to_embedding(&blob),
+        segment_count: row.get(4)?,
+        registered_speaker_id: row.get(5)?,
+    })
+}
+
+fn save_embeddings_impl(
+    conn: &Connection,
+    recording_id: &str,
+    embeddings: &[RecordingSpeakerEmbedding],
+) -> Result<()> {
+    let tx = conn.unchecked_transaction()
+        .context("Failed to start transaction for save_recording_speaker_embeddings")?;
+    write_embeddings(&tx, recording_id, embeddings)?;
+    tx.commit().context("Failed to commit speaker embeddings")?;
+    Ok(())
+}
+
+/// Replace a recording's speaker embeddings; the caller owns the transaction
+pub(super) fn write_embeddings(
+    tx: &Connection,
+    recording_id: &str,
+    embeddings: &[RecordingSpeakerEmbedding],
+) -> Result<()> {
+    tx.execute(
+        "DELETE FROM recording_speaker_embeddings WHERE recording_id = ?",
+        params![recording_id],
+    ).context("Failed to clear speaker embeddings")?;
+
+    for embedding in embeddings {
+        tx.execute(
+            r#"INSERT INTO recording_speaker_embeddings
+               (recording_id, speaker_id, speaker_label, embedding, segment_count, registered_speaker_id)
+               VALUES (?1, ?2, ?3, ?4, ?5, ?6)"#,
+            params![
+                recording_id,
+                embedding.speaker_id,
+                embedding.speaker_label,
+                embedding_to_blob(&embedding.embedding),
+                embedding.segment_count,
+                embedding.registered_speaker_id,
+            ],
+        ).context("Failed to save speaker embedding")?;
+    }
+
+    Ok(())
+}
+
+fn assign_registered_speaker_impl(
+    conn: &Connection,
+    recording_id: &str,
+    speaker_id: &str,
+    registered_speaker_id: &str,
+    registered_name: &str,
+) -> Result<usize> {
+    let tx = conn.unchecked_transaction()
+        .context("Failed to start transaction for assign_registered_speaker")?;
+
+    // Same ID scheme the diarization engine uses for registered voices
+    let new_speaker_id = format!("registered_{}", registered_speaker_id);
+
+    let updated = tx.execute(
+        r#"UPDATE transcript_segments
+           SET speaker_id = ?1, speaker_label = ?2, is_registered_speaker = 1
+           WHERE recording_id = ?3 AND speaker_id = ?4"#,
+        params![new_speaker_id, registered_name, recording_id, speaker_id],
+    ).context("Failed to relabel transcript segments")?;
+
+    tx.execute(
+        r#"UPDATE recording_speaker_embeddings
+           SET speaker_id = ?1, speaker_label = ?2, registered_speaker_id = ?3
+           WHERE recording_id = ?4 AND speaker_id = ?5"#,
+        params![new_speaker_id, registered_name, registered_speaker_id, recording_id, speaker_id],
+    ).context("Failed to update speaker embedding")?;
+
+    tx.commit().context("Failed to commit speaker relabel")?;
+    Ok(updated)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::database::models::{Recording, TranscriptSegment};
+    use tempfile::tempdir;
+
+    fn create_test_db() -> DatabaseManager {
+        let dir = tempdir().unwrap();
+        let db_path = dir.path().join("test.db");
+        DatabaseManager::new(db_path).unwrap()
+    }
+
+    fn embedding(speaker_id: &str, values: Vec<f32>) -> RecordingSpeakerEmbedding {
+        RecordingSpeakerEmbedding {
+            recording_id: "rec_spk".to_string(),
+            speaker_id: speaker_id.to_string(),
+            speaker_label: speaker_id.replace("speaker_", "Speaker "),
+            embedding: values,
+            segment_count: 3,
+            registered_speaker_id: None,
+        }
+    }
+
+    #[test]
+    fn test_save_and_get_embeddings_roundtrip() {
+        let db = create_test_db();
+        db.create_recording(&Recording::new("rec_spk".to_string(), "Speakers".to_string())).unwrap();
+
+        db.save_recording_speaker_embeddings("rec_spk", &[
+            embedding("speaker_0", vec![0.1, -0.2, 0.3]),
+            embedding("speaker_1", vec![0.5, 0.5, 0.0]),
+        ]).unwrap();
+
+        let stored = db.get_recording_speaker_embeddings("rec_spk").unwrap();
+        assert_eq!(stored.len(), 2);
+        assert_eq!(stored[0].embedding, vec![0.1, -0.2, 0.3]);
+        assert_eq!(db.get_recordings_with_speaker_embeddings().unwrap(), vec!["rec_spk".to_string()]);
+
+        // Saving again replaces the previous set
+        db.save_recording_speaker_embeddings("rec_spk", &[embedding("speaker_0", vec![1.0])]).unwrap();
+        assert_eq!(db.get_recording_speaker_embeddings("rec_spk").unwrap().len(), 1);
+    }
+
+    #[test]
+    fn test_assign_registered_speaker() {
+        let db = create_test_db();
+        db.create_recording(&Recording::new("rec_spk".to_string(), "Speakers".to_string())).unwrap();
+        db.save_recording_speaker_embeddings("rec_spk", &[embedding("speaker_0", vec![0.1, 0.2])]).unwrap();
+        let segment = TranscriptSegment::test_segment(0.0, 1.0, "Hello")
+            .with_ids("seg_spk_1", "rec_spk")
+            .with_speaker("speaker_0", "Speaker 1");
+        db.save_transcript_segment(&segment).unwrap();
+
+        let updated = db.assign_registered_speaker("rec_spk", "speaker_0", "spk_0001", "Alice").unwrap();
+        assert_eq!(updated, 1);
+
+        let segments = db.get_transcript_segments("rec_spk").unwrap();
+        assert_eq!(segments[0].speaker_label.as_deref(), Some("Alice"));
+        assert!(segments[0].is_registered_speaker);
+
+        let stored = db.get_recording_speaker_embeddings("rec_spk").unwrap();
+        assert_eq!(stored[0].registered_speaker_id.as_deref(), Some("spk_0001"));
+    }
+}
@@ -0,0 +1,159 @@
+// Transcript versions repository for Meeting-Local
+// Stores derived transcripts (e.g. LLM cleanup) next to the original segments
+
+use anyhow::{Context, Result};
+use rusqlite::{params, Row};
+
+use super::models::TranscriptVersion;
+use super::DatabaseManager;
+
+impl DatabaseManager {
+    /// Save a transcript version, replacing one with the same id
+    pub fn save_transcript_version(&self, version: &TranscriptVersion) -> Result<()> {
+        self.with_connection(|conn| {
+            conn.execute(
+                r#"INSERT OR REPLACE INTO transcript_versions
+                   (id, recording_id, kind, segments, model_id, created_at)
+                   VALUES (?1, ?2, ?3, ?4, ?5, ?6)"#,
+                params![
+                    version.id,
+                    version.recording_id,
+                    version.kind,
+                    serde_json::to_string(&version.segments)?,
+                    version.model_id,
+                    version.created_at,
+                ],
+            ).context("Failed to save transcript version")?;
+            Ok(())
+        })
+    }
+
+    /// Get all transcript versions of a recording, newest first
+    pub fn get_transcript_versions(&self, recording_id: &str) -> Result<Vec<TranscriptVersion>> {
+        self.with_connection(|conn| {
+            let mut stmt = conn.prepare(
+                r#"SELECT id, recording_id, kind, segments, model_id, created_at
+                   FROM transcript_versions WHERE recording_id = ?
+                   ORDER BY created_at DESC, rowid DESC"#
+            ).context("Failed to prepare get_transcript_versions query")?;
+
+            let versions = stmt.query_map(params![recording_id], row_to_version)
+                .context("Failed to query transcript versions")?;
+            versions.collect::<std::result::Result<Vec<_>, _>>()
+                .context("Failed to collect transcript versions")
+        })
+    }
+
+    /// Get a transcript version by id
+    pub fn get_transcript_version(&self, version_id: &str) -> Result<Option<TranscriptVersion>> {
+        self.with_connection(|conn| {
+            let result = conn.query_row(
+                r#"SELECT id, recording_id, kind, segments, model_id, created_at
+                   FROM transcript_versions WHERE id = ?"#,
+                params![version_id],
+                row_to_version,
+            );
+
+            match result {
+                Ok(version) => Ok(Some(version)),
+                Err(rusqlite::Error::QueryReturnedNoRows) => Ok(None),
+                Err(e) => Err(e).context("Failed to get transcript version"),
+            }
+        })
+    }
+
+    /// Get the newest transcript version of a kind for a recording
+    pub fn get_latest_transcript_version(&self, recording_id: &str, kind: &str) -> Result<Option<TranscriptVersion>> {
+        self.with_connection(|conn| {
+            let result = conn.query_row(
+                r#"SELECT id, recording_id, kind, segments, model_id, created_at
+                   FROM transcript_versions WHERE recording_id = ? AND kind = ?
+                   ORDER BY created_at DESC, rowid DESC LIMIT 1"#,
+                params![recording_id, kind],
+                row_to_version,
+            );
+
+            match result {
+                Ok(version) => Ok(Some(version)),
+                Err(rusqlite::Error::QueryReturnedNoRows) => Ok(None),
+                Err(e) => Err(e).context("Failed to get latest transcript version"),
+            }
+        })
+    }
+
+    /// Delete a transcript version
+    pub fn delete_transcript_version(&self, version_id: &str) -> Result<bool> {
+        self.with_connection(|conn| {
+            let deleted = conn.execute(
+                "DELETE FROM transcript_versions WHERE id = ?",
+                params![version_id],
+            ).context("Failed to delete transcript version")?;
+            Ok(deleted > 0)
+        })
+    }
+}
+
+fn row_to_version(row: &Row) -> rusqlite::Result<TranscriptVersion> {
+    let segments: String = row.get(3)?;
+    Ok(TranscriptVersion {
+        id: row.get(0)?,
+        recording_id: row.get(1)?,
+        kind: row.get(2)?,
+        // Unreadable segments come back empty
+        segments: serde_json::from_str(&segments).unwrap_or_default(),
+        model_id: row.get(4)?,
+        created_at: row.get(5)?,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::database::models::{Recording, TranscriptSegment};
+    use tempfile::tempdir;
+
+    fn create_test_db() -> DatabaseManager {
+        let dir = tempdir().unwrap();
+        let db_path = dir.path().join("test.db");
+        DatabaseManager::new(db_path).unwrap()
+    }
+
+    fn version(id: &str, created_at: &str, text: &str) -> TranscriptVersion {
+        TranscriptVersion {
+            id: id.to_string(),
+            recording_id: "rec_ver".to_string(),
+            kind: "cleanup".to_string(),
+            segments: vec![TranscriptSegment {
+                sequence_id: 1,
+                ..TranscriptSegment::test_segment(1.0, 2.5, text).with_ids("seg_1", "rec_ver")
+            }],
+            model_id: Some("test-model".to_string()),
+            created_at: created_at.to_string(),
+        }
+    }
+
+    #[test]
+    fn test_save_and_get_transcript_versions() {
+        let db = create_test_db();
+        db.create_recording(&Recording::new("rec_ver".to_string(), "Versions".to_string())).unwrap();
+        assert!(db.get_transcript_versions("rec_ver").unwrap().is_empty());
+
+        db.save_transcript_version(&version("tv_1", "2024-01-01T00:00:00Z", "First.")).unwrap();
+        db.save_transcript_version(&version("tv_2", "2024-01-02T00:00:00Z", "Second.")).unwrap();
+
+        let versions = db.get_transcript_versions("rec_ver").unwrap();
+        assert_eq!(versions.len(), 2);
+        assert_eq!(versions[0].id, "tv_2");
+        assert_eq!(versions[0].segments[0].text, "Second.");
+        assert_eq!(versions[0].segments[0].audio_end_time, 2.5);
+
+        let latest = db.get_latest_transcript_version("rec_ver", "cleanup").unwrap().unwrap();
+        assert_eq!(latest.id, "tv_2");
+        assert!(db.get_latest_transcript_version("rec_ver", "redacted").unwrap().is_none());
+
+        let stored = db.get_transcript_version("tv_1").unwrap().unwrap();
+        assert_eq!(stored.kind, "cleanup");
+        assert!(db.delete_transcript_version("tv_1").unwrap());
+        assert!(db.get_transcript_version("tv_1").unwrap().is_none());
+    }
+}
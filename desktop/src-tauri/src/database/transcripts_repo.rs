@@ -1,10 +1,10 @@
 // Transcripts repository for Meeting-Local
 // Handles CRUD operations for transcript segments
 
-use anyhow::{Context, Result};
-use rusqlite::{Connection, params};
+use anyhow::{bail, Context, Result};
+use rusqlite::{Connection, OptionalExtension, params};
 
-use super::models::TranscriptSegment;
+use super::models::{RecordingSpeakerEmbedding, RecordingUpdate, SpeakerSummary, TranscriptSegment};
 use super::DatabaseManager;
 
 impl DatabaseManager {
@@ -18,14 +18,36 @@ impl DatabaseManager {
     /// Save multiple transcript segments in a batch
     pub fn save_transcript_segments_batch(&self, segments: &[TranscriptSegment]) -> Result<()> {
         self.with_connection(|conn| {
-            save_transcript_segments_batch_impl(conn, segments)
+            save_transcript_segments_batch_impl(conn, segments, None)
+        })
+    }
+
+    /// Save segments shifted to silence-trimmed audio and, in the same
+    /// transaction, set the recording's trimmed duration
+    pub fn save_trimmed_transcript_segments(
+        &self,
+        segments: &[TranscriptSegment],
+        recording_id: &str,
+        duration_seconds: Option<f64>,
+    ) -> Result<()> {
+        self.with_connection(|conn| {
+            let duration = duration_seconds.map(|d| (recording_id, d));
+            save_transcript_segments_batch_impl(conn, segments, duration)
         })
     }
 
     /// Get all transcript segments for a recording
     pub fn get_transcript_segments(&self, recording_id: &str) -> Result<Vec<TranscriptSegment>> {
         self.with_connection(|conn| {
-            get_transcript_segments_impl(conn, recording_id)
+            get_transcript_segments_impl(conn, recording_id, None)
+        })
+    }
+
+    /// Get transcript segments with a sequence_id greater than `after_sequence_id`,
+    /// for polling a growing transcript
+    pub fn get_transcript_segments_since(&self, recording_id: &str, after_sequence_id: i64) -> Result<Vec<TranscriptSegment>> {
+        self.with_connection(|conn| {
+            get_transcript_segments_impl(conn, recording_id, Some(after_sequence_id))
         })
     }
 
@@ -44,13 +66,29 @@ impl DatabaseManager {
     }
 
     /// Replace all transcript segments for a recording with new ones
-    /// This is used when retranscription is complete
+    /// This is used when retranscription is complete. The recording's edit
+    /// history refers to the old segments, so it is cleared as well.
     pub fn replace_transcripts(&self, recording_id: &str, segments: &[TranscriptSegment]) -> Result<()> {
         self.with_connection(|conn| {
             replace_transcripts_impl(conn, recording_id, segments)
         })
     }
 
+    /// Save a finished retranscription: the new segments, the speaker
+    /// embeddings (replacing any old ones, even with none) and the recording's
+    /// metadata are written together or not at all.
+    pub fn save_retranscription(
+        &self,
+        recording_id: &str,
+        segments: &[TranscriptSegment],
+        embeddings: &[RecordingSpeakerEmbedding],
+        recording_update: &RecordingUpdate,
+    ) -> Result<()> {
+        self.with_connection(|conn| {
+            save_retranscription_impl(conn, recording_id, segments, embeddings, recording_update)
+        })
+    }
+
     /// Update speaker label for all segments with a given speaker_id
     /// This is used when renaming a speaker
     pub fn update_speaker_label(&self, speaker_id: &str, new_label: &str) -> Result<usize> {
@@ -59,12 +97,97 @@ impl DatabaseManager {
         })
     }
 
+    /// Assign a speaker to everything said between `start_time` and `end_time`
+    /// (seconds) in a recording. Segments crossing a boundary are split there,
+    /// dividing their words in proportion to time. Returns the number of
+    /// segments assigned.
+    pub fn assign_speaker_range(
+        &self,
+        recording_id: &str,
+        start_time: f64,
+        end_time: f64,
+        speaker_id: &str,
+        speaker_label: &str,
+    ) -> Result<usize> {
+        self.with_connection(|conn| {
+            assign_speaker_range_impl(conn, recording_id, start_time, end_time, speaker_id, speaker_label)
+        })
+    }
+
+    /// Get every distinct speaker across all recordings with recording count and talk time.
+    /// Registered speakers that don't appear in any transcript are included with zero counts.
+    pub fn get_all_speakers_summary(&self) -> Result<Vec<SpeakerSummary>> {
+        self.with_connection(|conn| {
+            get_all_speakers_summary_impl(conn)
+        })
+    }
+
+    /// Regenerate `display_time` for every segment of a recording as "[HH:MM:SS]".
+    /// Relative times count from the start of the audio; absolute times are the
+    /// local wall-clock time, based on the recording's `created_at`.
+    /// Returns the number of segments updated.
+    pub fn recompute_display_times(&self, recording_id: &str, absolute: bool) -> Result<usize> {
+        self.with_connection(|conn| {
+            recompute_display_times_impl(conn, recording_id, absolute)
+        })
+    }
+
     /// Update the text content of a transcript segment
     pub fn update_transcript_text(&self, segment_id: &str, new_text: &str) -> Result<()> {
         self.with_connection(|conn| {
             update_transcript_text_impl(conn, segment_id, new_text)
         })
     }
+
+    /// Revert the most recent text edit or speaker rename in a recording.
+    /// Returns the number of segments reverted, 0 if there is nothing to undo.
+    pub fn undo_last_transcript_edit(&self, recording_id: &str) -> Result<usize> {
+        self.with_connection(|conn| {
+            undo_last_transcript_edit_impl(conn, recording_id)
+        })
+    }
+}
+
+/// Edits kept for undo per recording; older ones are dropped
+const MAX_TRANSCRIPT_EDITS_PER_RECORDING: i64 = 50;
+
+const EDIT_FIELD_TEXT: &str = "text";
+const EDIT_FIELD_SPEAKER_LABEL: &str = "speaker_label";
+
+/// Record one changed value of an edit
+fn record_transcript_edit(
+    conn: &Connection,
+    edit_id: &str,
+    recording_id: &str,
+    segment_id: &str,
+    field: &str,
+    before_value: Option<&str>,
+    after_value: Option<&str>,
+) -> Result<()> {
+    conn.execute(
+        "INSERT INTO transcript_edits (edit_id, recording_id, segment_id, field, before_value, after_value)
+         VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
+        params![edit_id, recording_id, segment_id, field, before_value, after_value],
+    ).context("Failed to record transcript edit")?;
+
+    Ok(())
+}
+
+/// Drop a recording's edits beyond the newest MAX_TRANSCRIPT_EDITS_PER_RECORDING
+fn prune_transcript_edits(conn: &Connection, recording_id: &str) -> Result<()> {
+    conn.execute(
+        r#"DELETE FROM transcript_edits
+           WHERE recording_id = ?1 AND edit_id NOT IN (
+               SELECT edit_id FROM transcript_edits
+               WHERE recording_id = ?1
+               GROUP BY edit_id
+               ORDER BY MAX(id) DESC
+               LIMIT ?2
+           )"#,
+        params![recording_id, MAX_TRANSCRIPT_EDITS_PER_RECORDING],
+    ).context("Failed to prune transcript edits")?;
+
+    Ok(())
 }
 
 fn save_transcript_segment_impl(conn: &Connection, segment: &TranscriptSegment) -> Result<()> {
@@ -73,8 +196,8 @@ fn save_transcript_segment_impl(conn: &Connection, segment: &TranscriptSegment)
         INSERT INTO transcript_segments (
             id, recording_id, text, audio_start_time, audio_end_time,
             duration, display_time, confidence, sequence_id,
-            speaker_id, speaker_label, is_registered_speaker
-        ) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12)
+            speaker_id, speaker_label, is_registered_speaker, low_confidence
+        ) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13)
         ON CONFLICT(id) DO UPDATE SET
             text = excluded.text,
             audio_start_time = excluded.audio_start_time,
@@ -85,7 +208,8 @@ fn save_transcript_segment_impl(conn: &Connection, segment: &TranscriptSegment)
             sequence_id = excluded.sequence_id,
             speaker_id = excluded.speaker_id,
             speaker_label = excluded.speaker_label,
-            is_registered_speaker = excluded.is_registered_speaker
+            is_registered_speaker = excluded.is_registered_speaker,
+            low_confidence = excluded.low_confidence
         "#,
         params![
             segment.id,
@@ -100,13 +224,18 @@ fn save_transcript_segment_impl(conn: &Connection, segment: &TranscriptSegment)
             segment.speaker_id,
             segment.speaker_label,
             segment.is_registered_speaker as i32,
+            segment.low_confidence as i32,
         ],
     ).context("Failed to save transcript segment")?;
 
     Ok(())
 }
 
-fn save_transcript_segments_batch_impl(conn: &Connection, segments: &[TranscriptSegment]) -> Result<()> {
+fn save_transcript_segments_batch_impl(
+    conn: &Connection,
+    segments: &[TranscriptSegment],
+    recording_duration: Option<(&str, f64)>,
+) -> Result<()> {
     let tx = conn.unchecked_transaction()
         .context("Failed to start transaction")?;
 
@@ -116,8 +245,8 @@ fn save_transcript_segments_batch_impl(conn: &Connection, segments: &[Transcript
             INSERT INTO transcript_segments (
                 id, recording_id, text, audio_start_time, audio_end_time,
                 duration, display_time, confidence, sequence_id,
-                speaker_id, speaker_label, is_registered_speaker
-            ) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12)
+                speaker_id, speaker_label, is_registered_speaker, low_confidence
+            ) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13)
             ON CONFLICT(id) DO UPDATE SET
                 text = excluded.text,
                 audio_start_time = excluded.audio_start_time,
@@ -128,7 +257,8 @@ fn save_transcript_segments_batch_impl(conn: &Connection, segments: &[Transcript
                 sequence_id = excluded.sequence_id,
                 speaker_id = excluded.speaker_id,
                 speaker_label = excluded.speaker_label,
-                is_registered_speaker = excluded.is_registered_speaker
+                is_registered_speaker = excluded.is_registered_speaker,
+                low_confidence = excluded.low_confidence
             "#,
             params![
                 segment.id,
@@ -143,27 +273,39 @@ fn save_transcript_segments_batch_impl(conn: &Connection, segments: &[Transcript
                 segment.speaker_id,
                 segment.speaker_label,
                 segment.is_registered_speaker as i32,
+                segment.low_confidence as i32,
             ],
         ).context("Failed to save transcript segment in batch")?;
     }
 
+    if let Some((recording_id, duration_seconds)) = recording_duration {
+        tx.execute(
+            "UPDATE recordings SET duration_seconds = ? WHERE id = ?",
+            params![duration_seconds, recording_id],
+        ).context("Failed to update recording duration")?;
+    }
+
     tx.commit().context("Failed to commit transcript batch")?;
     Ok(())
 }
 
-fn get_transcript_segments_impl(conn: &Connection, recording_id: &str) -> Result<Vec<TranscriptSegment>> {
+fn get_transcript_segments_impl(
+    conn: &Connection,
+    recording_id: &str,
+    after_sequence_id: Option<i64>,
+) -> Result<Vec<TranscriptSegment>> {
     let mut stmt = conn.prepare(
         r#"
         SELECT id, recording_id, text, audio_start_time, audio_end_time,
                duration, display_time, confidence, sequence_id,
-               speaker_id, speaker_label, is_registered_speaker
+               speaker_id, speaker_label, is_registered_speaker, low_confidence
         FROM transcript_segments
-        WHERE recording_id = ?
+        WHERE recording_id = ?1 AND (?2 IS NULL OR sequence_id > ?2)
         ORDER BY sequence_id ASC
         "#
     ).context("Failed to prepare get_transcript_segments query")?;
 
-    let segments = stmt.query_map(params![recording_id], |row| {
+    let segments = stmt.query_map(params![recording_id, after_sequence_id], |row| {
         Ok(TranscriptSegment {
             id: row.get(0)?,
             recording_id: row.get(1)?,
@@ -177,6 +319,7 @@ fn get_transcript_segments_impl(conn: &Connection, recording_id: &str) -> Result
             speaker_id: row.get(9)?,
             speaker_label: row.get(10)?,
             is_registered_speaker: row.get::<_, Option<i32>>(11)?.map_or(false, |v| v != 0),
+            low_confidence: row.get::<_, i32>(12)? != 0,
         })
     }).context("Failed to query transcript segments")?;
 
@@ -194,7 +337,7 @@ fn delete_transcript_segments_impl(conn: &Connection, recording_id: &str) -> Res
 }
 
 fn get_full_transcript_impl(conn: &Connection, recording_id: &str) -> Result<String> {
-    let segments = get_transcript_segments_impl(conn, recording_id)?;
+    let segments = get_transcript_segments_impl(conn, recording_id, None)?;
     let texts: Vec<&str> = segments.iter().map(|s| s.text.as_str()).collect();
     Ok(texts.join(" "))
 }
@@ -202,13 +345,41 @@ fn get_full_transcript_impl(conn: &Connection, recording_id: &str) -> Result<Str
 fn replace_transcripts_impl(conn: &Connection, recording_id: &str, segments: &[TranscriptSegment]) -> Result<()> {
     let tx = conn.unchecked_transaction()
         .context("Failed to start transaction for replace_transcripts")?;
+    write_transcripts(&tx, recording_id, segments)?;
+    tx.commit().context("Failed to commit replace_transcripts")?;
+    Ok(())
+}
 
+fn save_retranscription_impl(
+    conn: &Connection,
+    recording_id: &str,
+    segments: &[TranscriptSegment],
+    embeddings: &[RecordingSpeakerEmbedding],
+    recording_update: &RecordingUpdate,
+) -> Result<()> {
+    let tx = conn.unchecked_transaction()
+        .context("Failed to start transaction for save_retranscription")?;
+    write_transcripts(&tx, recording_id, segments)?;
+    super::speaker_embeddings_repo::write_embeddings(&tx, recording_id, embeddings)?;
+    super::recordings_repo::update_recording_impl(&tx, recording_id, recording_update)?;
+    tx.commit().context("Failed to commit save_retranscription")?;
+    Ok(())
+}
+
+/// Replace a recording's segments; the caller owns the transaction
+fn write_transcripts(tx: &Connection, recording_id: &str, segments: &[TranscriptSegment]) -> Result<()> {
     // First, delete all existing segments for this recording
     tx.execute(
         "DELETE FROM transcript_segments WHERE recording_id = ?",
         params![recording_id],
     ).context("Failed to delete old transcript segments")?;
 
+    // Edits of the old segments can't be undone on the new ones
+    tx.execute(
+        "DELETE FROM transcript_edits WHERE recording_id = ?",
+        params![recording_id],
+    ).context("Failed to clear transcript edit history")?;
+
     // Then insert all new segments
     for segment in segments {
         tx.execute(
@@ -216,8 +387,8 @@ fn replace_transcripts_impl(conn: &Connection, recording_id: &str, segments: &[T
             INSERT INTO transcript_segments (
                 id, recording_id, text, audio_start_time, audio_end_time,
                 duration, display_time, confidence, sequence_id,
-                speaker_id, speaker_label, is_registered_speaker
-            ) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12)
+                speaker_id, speaker_label, is_registered_speaker, low_confidence
+            ) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13)
             "#,
             params![
                 segment.id,
@@ -232,32 +403,345 @@ fn replace_transcripts_impl(conn: &Connection, recording_id: &str, segments: &[T
                 segment.speaker_id,
                 segment.speaker_label,
                 segment.is_registered_speaker as i32,
+                segment.low_confidence as i32,
             ],
         ).context("Failed to insert new transcript segment")?;
     }
 
-    tx.commit().context("Failed to commit replace_transcripts")?;
     Ok(())
 }
 
 fn update_speaker_label_impl(conn: &Connection, speaker_id: &str, new_label: &str) -> Result<usize> {
-    let rows_updated = conn.execute(
+    let tx = conn.unchecked_transaction()
+        .context("Failed to start transaction for update_speaker_label")?;
+
+    let previous: Vec<(String, String, Option<String>)> = {
+        let mut stmt = tx.prepare(
+            "SELECT id, recording_id, speaker_label FROM transcript_segments
+             WHERE speaker_id = ? AND speaker_label IS NOT ?"
+        ).context("Failed to prepare speaker label query")?;
+        let rows = stmt.query_map(params![speaker_id, new_label], |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?)))
+            .context("Failed to query speaker labels")?;
+        rows.collect::<std::result::Result<Vec<_>, _>>()
+            .context("Failed to collect speaker labels")?
+    };
+
+    let rows_updated = tx.execute(
         "UPDATE transcript_segments SET speaker_label = ? WHERE speaker_id = ?",
         params![new_label, speaker_id],
     ).context("Failed to update speaker label")?;
 
+    let edit_id = uuid::Uuid::new_v4().to_string();
+    let mut recordings: Vec<&str> = Vec::new();
+    for (segment_id, recording_id, label) in &previous {
+        record_transcript_edit(
+            &tx, &edit_id, recording_id, segment_id,
+            EDIT_FIELD_SPEAKER_LABEL, label.as_deref(), Some(new_label),
+        )?;
+        if !recordings.contains(&recording_id.as_str()) {
+            recordings.push(recording_id);
+        }
+    }
+    for recording_id in recordings {
+        prune_transcript_edits(&tx, recording_id)?;
+    }
+
+    tx.commit().context("Failed to commit speaker label update")?;
     Ok(rows_updated)
 }
 
+/// Seconds of a segment within `start..end`
+fn overlap_seconds(segment: &TranscriptSegment, start: f64, end: f64) -> f64 {
+    (segment.audio_end_time.min(end) - segment.audio_start_time.max(start)).max(0.0)
+}
+
+/// Split a segment at `cuts` (times inside it), giving each part the words
+/// that fall in its time span. None if any part would get no words.
+fn split_segment(segment: &TranscriptSegment, cuts: &[f64]) -> Option<Vec<TranscriptSegment>> {
+    let words: Vec<&str> = segment.text.split_whitespace().collect();
+    let span = segment.audio_end_time - segment.audio_start_time;
+    if span <= 0.0 {
+        return None;
+    }
+    let word_index = |time: f64| {
+        (((time - segment.audio_start_time) / span) * words.len() as f64).round() as usize
+    };
+
+    let mut bounds = vec![segment.audio_start_time];
+    bounds.extend_from_slice(cuts);
+    bounds.push(segment.audio_end_time);
+
+    let mut parts = Vec::with_capacity(bounds.len() - 1);
+    for (index, pair) in bounds.windows(2).enumerate() {
+        let (from, to) = (word_index(pair[0]), word_index(pair[1]).min(words.len()));
+        if from >= to {
+            return None;
+        }
+        parts.push(TranscriptSegment {
+            id: if index == 0 { segment.id.clone() } else { format!("seg_{}", uuid::Uuid::new_v4()) },
+            text: words[from..to].join(" "),
+            audio_start_time: pair[0],
+            audio_end_time: pair[1],
+            duration: pair[1] - pair[0],
+            display_time: if index == 0 {
+                segment.display_time.clone()
+            } else {
+                format_relative_display_time(pair[0])
+            },
+            ..segment.clone()
+        });
+    }
+    Some(parts)
+}
+
+/// Assign a speaker to the segments overlapping `start..end`, splitting those
+/// that cross a boundary. Segments too short to split go to the range if most
+/// of their time is inside it. Returns the segments and how many were assigned.
+fn assign_speaker_range_segments(
+    segments: Vec<TranscriptSegment>,
+    start: f64,
+    end: f64,
+    speaker_id: &str,
+    speaker_label: &str,
+) -> (Vec<TranscriptSegment>, usize) {
+    let assign = |segment: &mut TranscriptSegment| {
+        segment.speaker_id = Some(speaker_id.to_string());
+        segment.speaker_label = Some(speaker_label.to_string());
+        segment.is_registered_speaker = speaker_id.starts_with("registered_");
+    };
+
+    let mut result = Vec::with_capacity(segments.len());
+    let mut assigned = 0;
+    let mut split = false;
+
+    for mut segment in segments {
+        let overlap = overlap_seconds(&segment, start, end);
+        if overlap <= 0.0 {
+            result.push(segment);
+            continue;
+        }
+
+        let cuts: Vec<f64> = [start, end]
+            .into_iter()
+            .filter(|&t| t > segment.audio_start_time && t < segment.audio_end_time)
+            .collect();
+        let parts = if cuts.is_empty() { None } else { split_segment(&segment, &cuts) };
+
+        match parts {
+            Some(parts) => {
+                split = true;
+                for mut part in parts {
+                    if overlap_seconds(&part, start, end) > 0.0 {
+                        assign(&mut part);
+                        assigned += 1;
+                    }
+                    result.push(part);
+                }
+            }
+            None => {
+                if cuts.is_empty() || overlap * 2.0 >= segment.audio_end_time - segment.audio_start_time {
+                    assign(&mut segment);
+                    assigned += 1;
+                }
+                result.push(segment);
+            }
+        }
+    }
+
+    // Keep sequence order unique after inserting split parts
+    if split {
+        let base = result.first().map_or(0, |s| s.sequence_id);
+        for (index, segment) in result.iter_mut().enumerate() {
+            segment.sequence_id = base + index as i64;
+        }
+    }
+
+    (result, assigned)
+}
+
+fn assign_speaker_range_impl(
+    conn: &Connection,
+    recording_id: &str,
+    start_time: f64,
+    end_time: f64,
+    speaker_id: &str,
+    speaker_label: &str,
+) -> Result<usize> {
+    if !start_time.is_finite() || !end_time.is_finite() || start_time >= end_time {
+        bail!("Invalid time range: {} to {}", start_time, end_time);
+    }
+
+    let segments = get_transcript_segments_impl(conn, recording_id, None)?;
+    let (segments, assigned) =
+        assign_speaker_range_segments(segments, start_time, end_time, speaker_id, speaker_label);
+    if assigned > 0 {
+        replace_transcripts_impl(conn, recording_id, &segments)?;
+    }
+    Ok(assigned)
+}
+
+fn get_all_speakers_summary_impl(conn: &Connection) -> Result<Vec<SpeakerSummary>> {
+    // Registered voices use "registered_{id}" as their speaker_id in transcripts
+    let mut stmt = conn.prepare(
+        r#"SELECT COALESCE(rs.name, ts.speaker_label, ts.speaker_id) AS label,
+                  MAX(rs.id),
+                  MAX(rs.name),
+                  COUNT(DISTINCT ts.recording_id),
+                  COUNT(*),
+                  COALESCE(SUM(ts.duration), 0.0) AS total_duration
+           FROM transcript_segments ts
+           LEFT JOIN registered_speakers rs ON ts.speaker_id = 'registered_' || rs.id
+           WHERE ts.speaker_id IS NOT NULL
+           GROUP BY label
+           UNION ALL
+           SELECT rs.name, rs.id, rs.name, 0, 0, 0.0
+           FROM registered_speakers rs
+           WHERE NOT EXISTS (
+               SELECT 1 FROM transcript_segments ts WHERE ts.speaker_id = 'registered_' || rs.id
+           )
+           ORDER BY total_duration DESC, label"#
+    ).context("Failed to prepare get_all_speakers_summary query")?;
+
+    let speakers = stmt.query_map([], |row| {
+        Ok(SpeakerSummary {
+            speaker_label: row.get(0)?,
+            registered_speaker_id: row.get(1)?,
+            registered_name: row.get(2)?,
+            recording_count: row.get(3)?,
+            segment_count: row.get(4)?,
+            total_duration: row.get(5)?,
+        })
+    }).context("Failed to query speakers summary")?;
+
+    speakers.collect::<std::result::Result<Vec<_>, _>>()
+        .context("Failed to collect speakers summary")
+}
+
+/// Format seconds from the start of the recording as "[HH:MM:SS]"
+fn format_relative_display_time(seconds: f64) -> String {
+    let total = seconds.max(0.0).floor() as u64;
+    format!("[{:02}:{:02}:{:02}]", total / 3600, (total % 3600) / 60, total % 60)
+}
+
+fn recompute_display_times_impl(conn: &Connection, recording_id: &str, absolute: bool) -> Result<usize> {
+    let start = if absolute {
+        let created_at: String = conn.query_row(
+            "SELECT created_at FROM recordings WHERE id = ?",
+            params![recording_id],
+            |row| row.get(0),
+        ).context("Failed to get recording start time")?;
+
+        let start = chrono::DateTime::parse_from_rfc3339(&created_at)
+            .with_context(|| format!("Invalid recording created_at: {}", created_at))?;
+        Some(start.with_timezone(&chrono::Local))
+    } else {
+        None
+    };
+
+    let segments: Vec<(String, f64)> = {
+        let mut stmt = conn.prepare(
+            "SELECT id, audio_start_time FROM transcript_segments WHERE recording_id = ?"
+        ).context("Failed to prepare display time query")?;
+        let rows = stmt.query_map(params![recording_id], |row| Ok((row.get(0)?, row.get(1)?)))
+            .context("Failed to query segment start times")?;
+        rows.collect::<std::result::Result<Vec<_>, _>>()
+            .context("Failed to collect segment start times")?
+    };
+
+    let tx = conn.unchecked_transaction()
+        .context("Failed to start transaction for recompute_display_times")?;
+
+    for (id, audio_start_time) in &segments {
+        let display_time = match &start {
+            Some(start) => {
+                let offset = chrono::Duration::milliseconds((audio_start_time.max(0.0) * 1000.0) as i64);
+                (*start + offset).format("[%H:%M:%S]").to_string()
+            }
+            None => format_relative_display_time(*audio_start_time),
+        };
+
+        tx.execute(
+            "UPDATE transcript_segments SET display_time = ? WHERE id = ?",
+            params![display_time, id],
+        ).context("Failed to update display time")?;
+    }
+
+    tx.commit().context("Failed to commit display times")?;
+    Ok(segments.len())
+}
+
 fn update_transcript_text_impl(conn: &Connection, segment_id: &str, new_text: &str) -> Result<()> {
-    conn.execute(
+    let tx = conn.unchecked_transaction()
+        .context("Failed to start transaction for update_transcript_text")?;
+
+    let previous: Option<(String, String)> = tx.query_row(
+        "SELECT recording_id, text FROM transcript_segments WHERE id = ?",
+        params![segment_id],
+        |row| Ok((row.get(0)?, row.get(1)?)),
+    ).optional().context("Failed to get transcript text")?;
+
+    tx.execute(
         "UPDATE transcript_segments SET text = ? WHERE id = ?",
         params![new_text, segment_id],
     ).context("Failed to update transcript text")?;
 
+    if let Some((recording_id, text)) = previous.filter(|(_, text)| text != new_text) {
+        let edit_id = uuid::Uuid::new_v4().to_string();
+        record_transcript_edit(
+            &tx, &edit_id, &recording_id, segment_id,
+            EDIT_FIELD_TEXT, Some(&text), Some(new_text),
+        )?;
+        prune_transcript_edits(&tx, &recording_id)?;
+    }
+
+    tx.commit().context("Failed to commit transcript text update")?;
     Ok(())
 }
 
+fn undo_last_transcript_edit_impl(conn: &Connection, recording_id: &str) -> Result<usize> {
+    let tx = conn.unchecked_transaction()
+        .context("Failed to start transaction for undo_last_transcript_edit")?;
+
+    let edit_id: Option<String> = tx.query_row(
+        "SELECT edit_id FROM transcript_edits WHERE recording_id = ? ORDER BY id DESC LIMIT 1",
+        params![recording_id],
+        |row| row.get(0),
+    ).optional().context("Failed to get last transcript edit")?;
+    let Some(edit_id) = edit_id else {
+        return Ok(0);
+    };
+
+    let changes: Vec<(String, String, Option<String>)> = {
+        let mut stmt = tx.prepare(
+            "SELECT segment_id, field, before_value FROM transcript_edits
+             WHERE recording_id = ? AND edit_id = ? ORDER BY id DESC"
+        ).context("Failed to prepare transcript edit query")?;
+        let rows = stmt.query_map(params![recording_id, edit_id], |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?)))
+            .context("Failed to query transcript edit")?;
+        rows.collect::<std::result::Result<Vec<_>, _>>()
+            .context("Failed to collect transcript edit")?
+    };
+
+    let mut reverted = 0;
+    for (segment_id, field, before_value) in &changes {
+        let sql = match field.as_str() {
+            EDIT_FIELD_TEXT => "UPDATE transcript_segments SET text = ? WHERE id = ?",
+            EDIT_FIELD_SPEAKER_LABEL => "UPDATE transcript_segments SET speaker_label = ? WHERE id = ?",
+            other => bail!("Unknown transcript edit field: {}", other),
+        };
+        reverted += tx.execute(sql, params![before_value, segment_id])
+            .context("Failed to revert transcript edit")?;
+    }
+
+    tx.execute(
+        "DELETE FROM transcript_edits WHERE recording_id = ? AND edit_id = ?",
+        params![recording_id, edit_id],
+    ).context("Failed to delete undone transcript edit")?;
+
+    tx.commit().context("Failed to commit transcript undo")?;
+    Ok(reverted)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -293,6 +777,7 @@ mod tests {
                 speaker_id: Some("speaker_0".to_string()),
                 speaker_label: Some("Speaker 1".to_string()),
                 is_registered_speaker: false,
+                low_confidence: false,
             },
             TranscriptSegment {
                 id: "seg_2".to_string(),
@@ -307,6 +792,7 @@ mod tests {
                 speaker_id: Some("speaker_1".to_string()),
                 speaker_label: Some("Speaker 2".to_string()),
                 is_registered_speaker: false,
+                low_confidence: false,
             },
         ];
 
@@ -316,6 +802,32 @@ mod tests {
         assert_eq!(retrieved.len(), 2);
         assert_eq!(retrieved[0].text, "Hello world");
         assert_eq!(retrieved[1].text, "This is a test");
+
+        let since = db.get_transcript_segments_since("rec_test", 1).unwrap();
+        assert_eq!(since.len(), 1);
+        assert_eq!(since[0].id, "seg_2");
+        assert!(db.get_transcript_segments_since("rec_test", 2).unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_low_confidence_round_trip() {
+        let db = create_test_db();
+        db.create_recording(&Recording::new("rec_low".to_string(), "Low".to_string())).unwrap();
+
+        let segment = |id: &str, start: f64, low_confidence: bool| TranscriptSegment {
+            sequence_id: start as i64,
+            low_confidence,
+            ..TranscriptSegment::test_segment(start, start + 1.0, "Text").with_ids(id, "rec_low")
+        };
+        db.replace_transcripts("rec_low", &[segment("seg_l1", 0.0, false), segment("seg_l2", 1.0, true)]).unwrap();
+
+        let flags: Vec<bool> = db
+            .get_transcript_segments("rec_low")
+            .unwrap()
+            .iter()
+            .map(|s| s.low_confidence)
+            .collect();
+        assert_eq!(flags, vec![false, true]);
     }
 
     #[test]
@@ -339,6 +851,7 @@ mod tests {
                 speaker_id: None,
                 speaker_label: None,
                 is_registered_speaker: false,
+                low_confidence: false,
             },
             TranscriptSegment {
                 id: "seg_b".to_string(),
@@ -353,6 +866,7 @@ mod tests {
                 speaker_id: None,
                 speaker_label: None,
                 is_registered_speaker: false,
+                low_confidence: false,
             },
         ];
 
@@ -361,4 +875,213 @@ mod tests {
         let full = db.get_full_transcript("rec_full").unwrap();
         assert_eq!(full, "First Second");
     }
+
+    fn speaker_segment(id: &str, recording_id: &str, speaker_id: &str, label: &str, duration: f64) -> TranscriptSegment {
+        TranscriptSegment {
+            sequence_id: 1,
+            is_registered_speaker: speaker_id.starts_with("registered_"),
+            ..TranscriptSegment::test_segment(0.0, duration, "Text")
+                .with_ids(id, recording_id)
+                .with_speaker(speaker_id, label)
+        }
+    }
+
+    #[test]
+    fn test_get_all_speakers_summary() {
+        let db = create_test_db();
+        db.create_recording(&Recording::new("rec_s1".to_string(), "One".to_string())).unwrap();
+        db.create_recording(&Recording::new("rec_s2".to_string(), "Two".to_string())).unwrap();
+
+        db.with_connection(|conn| {
+            conn.execute_batch(
+                "INSERT INTO registered_speakers (id, name, embedding) VALUES ('spk_1', 'Alice', x'00'), ('spk_2', 'Bob', x'00');"
+            )?;
+            Ok(())
+        }).unwrap();
+
+        db.save_transcript_segments_batch(&[
+            speaker_segment("seg_s1", "rec_s1", "registered_spk_1", "Alice", 10.0),
+            speaker_segment("seg_s2", "rec_s2", "registered_spk_1", "Alice", 5.0),
+            speaker_segment("seg_s3", "rec_s2", "speaker_0", "Speaker 1", 3.0),
+        ]).unwrap();
+
+        let speakers = db.get_all_speakers_summary().unwrap();
+        assert_eq!(speakers.len(), 3);
+
+        assert_eq!(speakers[0].speaker_label, "Alice");
+        assert_eq!(speakers[0].registered_speaker_id.as_deref(), Some("spk_1"));
+        assert_eq!(speakers[0].recording_count, 2);
+        assert_eq!(speakers[0].total_duration, 15.0);
+
+        assert_eq!(speakers[1].speaker_label, "Speaker 1");
+        assert!(speakers[1].registered_name.is_none());
+
+        // Registered but never heard
+        assert_eq!(speakers[2].registered_name.as_deref(), Some("Bob"));
+        assert_eq!(speakers[2].recording_count, 0);
+    }
+
+    #[test]
+    fn test_assign_speaker_range() {
+        let db = create_test_db();
+        db.create_recording(&Recording::new("rec_range".to_string(), "Range".to_string())).unwrap();
+
+        let segment = |id: &str, start: f64, end: f64, text: &str, sequence_id: i64| TranscriptSegment {
+            text: text.to_string(),
+            audio_start_time: start,
+            audio_end_time: end,
+            duration: end - start,
+            sequence_id,
+            ..speaker_segment(id, "rec_range", "speaker_0", "Speaker 1", 0.0)
+        };
+        db.save_transcript_segments_batch(&[
+            segment("seg_r1", 0.0, 4.0, "one two three four", 1),
+            segment("seg_r2", 4.0, 6.0, "five six", 2),
+            segment("seg_r3", 6.0, 10.0, "seven eight nine ten", 3),
+        ]).unwrap();
+
+        // Splits seg_r1 at 2s, takes seg_r2 whole, leaves seg_r3 alone
+        let assigned = db.assign_speaker_range("rec_range", 2.0, 6.0, "speaker_1", "Speaker 2").unwrap();
+        assert_eq!(assigned, 2);
+
+        let segments = db.get_transcript_segments("rec_range").unwrap();
+        let summary: Vec<(&str, Option<&str>, f64)> = segments
+            .iter()
+            .map(|s| (s.text.as_str(), s.speaker_label.as_deref(), s.audio_start_time))
+            .collect();
+        assert_eq!(summary, vec![
+            ("one two", Some("Speaker 1"), 0.0),
+            ("three four", Some("Speaker 2"), 2.0),
+            ("five six", Some("Speaker 2"), 4.0),
+            ("seven eight nine ten", Some("Speaker 1"), 6.0),
+        ]);
+        assert_eq!(segments[0].id, "seg_r1");
+
+        // A range too short to hold a word doesn't split; the segment stays with its speaker
+        assert_eq!(db.assign_speaker_range("rec_range", 6.5, 7.0, "speaker_2", "Speaker 3").unwrap(), 0);
+        assert!(db.assign_speaker_range("rec_range", 5.0, 5.0, "speaker_2", "Speaker 3").is_err());
+    }
+
+    #[test]
+    fn test_undo_last_transcript_edit() {
+        let db = create_test_db();
+        db.create_recording(&Recording::new("rec_undo".to_string(), "Undo".to_string())).unwrap();
+        db.save_transcript_segments_batch(&[
+            speaker_segment("seg_u1", "rec_undo", "speaker_0", "Speaker 1", 1.0),
+            speaker_segment("seg_u2", "rec_undo", "speaker_0", "Speaker 1", 1.0),
+        ]).unwrap();
+
+        assert_eq!(db.undo_last_transcript_edit("rec_undo").unwrap(), 0);
+
+        db.update_transcript_text("seg_u1", "Edited").unwrap();
+        db.update_speaker_label("speaker_0", "Alice").unwrap();
+
+        // The rename is undone first, as one edit across both segments
+        assert_eq!(db.undo_last_transcript_edit("rec_undo").unwrap(), 2);
+        let segments = db.get_transcript_segments("rec_undo").unwrap();
+        assert!(segments.iter().all(|s| s.speaker_label.as_deref() == Some("Speaker 1")));
+        assert_eq!(segments[0].text, "Edited");
+
+        assert_eq!(db.undo_last_transcript_edit("rec_undo").unwrap(), 1);
+        assert_eq!(db.get_transcript_segments("rec_undo").unwrap()[0].text, "Text");
+        assert_eq!(db.undo_last_transcript_edit("rec_undo").unwrap(), 0);
+    }
+
+    #[test]
+    fn test_undo_after_replace_transcripts() {
+        let db = create_test_db();
+        db.create_recording(&Recording::new("rec_replace".to_string(), "Replace".to_string())).unwrap();
+        db.save_transcript_segment(&speaker_segment("seg_rp", "rec_replace", "speaker_0", "Speaker 1", 1.0)).unwrap();
+        db.update_transcript_text("seg_rp", "Edited").unwrap();
+
+        // The new transcript reuses the segment ID, as speaker range assignment does
+        db.replace_transcripts("rec_replace", &[TranscriptSegment {
+            text: "Retranscribed".to_string(),
+            ..speaker_segment("seg_rp", "rec_replace", "speaker_0", "Speaker 1", 1.0)
+        }]).unwrap();
+
+        // The edit of the replaced transcript is gone, so undo leaves the new one alone
+        assert_eq!(db.undo_last_transcript_edit("rec_replace").unwrap(), 0);
+        assert_eq!(db.get_transcript_segments("rec_replace").unwrap()[0].text, "Retranscribed");
+    }
+
+    #[test]
+    fn test_save_retranscription_is_atomic() {
+        let db = create_test_db();
+        db.create_recording(&Recording::new("rec_rt".to_string(), "Retranscribe".to_string())).unwrap();
+        db.save_transcript_segment(&speaker_segment("seg_old", "rec_rt", "speaker_0", "Speaker 1", 1.0)).unwrap();
+        db.save_recording_speaker_embeddings("rec_rt", &[RecordingSpeakerEmbedding {
+            recording_id: "rec_rt".to_string(),
+            speaker_id: "speaker_0".to_string(),
+            speaker_label: "Speaker 1".to_string(),
+            embedding: vec![0.1, 0.2],
+            segment_count: 1,
+            registered_speaker_id: None,
+        }]).unwrap();
+        let new_segments = [speaker_segment("seg_new", "rec_rt", "speaker_0", "Speaker 1", 2.0)];
+
+        // A failing metadata update leaves the old transcript and embeddings in place
+        let invalid = RecordingUpdate { transcription_model: Some("bad\nmodel".to_string()), ..Default::default() };
+        assert!(db.save_retranscription("rec_rt", &new_segments, &[], &invalid).is_err());
+        assert_eq!(db.get_transcript_segments("rec_rt").unwrap()[0].id, "seg_old");
+        assert_eq!(db.get_recording_speaker_embeddings("rec_rt").unwrap().len(), 1);
+
+        // Without new embeddings the old ones are cleared
+        let update = RecordingUpdate { transcription_model: Some("large-v3".to_string()), ..Default::default() };
+        db.save_retranscription("rec_rt", &new_segments, &[], &update).unwrap();
+        assert_eq!(db.get_transcript_segments("rec_rt").unwrap()[0].id, "seg_new");
+        assert!(db.get_recording_speaker_embeddings("rec_rt").unwrap().is_empty());
+        let recording = db.get_recording("rec_rt").unwrap().unwrap();
+        assert_eq!(recording.transcription_model.as_deref(), Some("large-v3"));
+    }
+
+    #[test]
+    fn test_transcript_edit_history_is_bounded() {
+        let db = create_test_db();
+        db.create_recording(&Recording::new("rec_bound".to_string(), "Bound".to_string())).unwrap();
+        db.save_transcript_segment(&speaker_segment("seg_b1", "rec_bound", "speaker_0", "Speaker 1", 1.0)).unwrap();
+
+        for i in 0..MAX_TRANSCRIPT_EDITS_PER_RECORDING + 5 {
+            db.update_transcript_text("seg_b1", &format!("Version {}", i)).unwrap();
+        }
+
+        let edits: i64 = db.with_connection(|conn| {
+            Ok(conn.query_row(
+                "SELECT COUNT(*) FROM transcript_edits WHERE recording_id = 'rec_bound'",
+                [],
+                |row| row.get(0),
+            )?)
+        }).unwrap();
+        assert_eq!(edits, MAX_TRANSCRIPT_EDITS_PER_RECORDING);
+    }
+
+    #[test]
+    fn test_recompute_display_times() {
+        let db = create_test_db();
+
+        let mut recording = Recording::new("rec_times".to_string(), "Times".to_string());
+        recording.created_at = "2024-03-01T09:30:00+00:00".to_string();
+        db.create_recording(&recording).unwrap();
+
+        let segment = |id: &str, start: f64| TranscriptSegment {
+            display_time: "10:42:17 AM".to_string(),
+            sequence_id: start as i64,
+            ..TranscriptSegment::test_segment(start, start + 1.0, "Text").with_ids(id, "rec_times")
+        };
+        db.save_transcript_segments_batch(&[segment("seg_t1", 5.4), segment("seg_t2", 3725.0)]).unwrap();
+
+        assert_eq!(db.recompute_display_times("rec_times", false).unwrap(), 2);
+        let segments = db.get_transcript_segments("rec_times").unwrap();
+        assert_eq!(segments[0].display_time, "[00:00:05]");
+        assert_eq!(segments[1].display_time, "[01:02:05]");
+
+        db.recompute_display_times("rec_times", true).unwrap();
+        let expected = chrono::DateTime::parse_from_rfc3339("2024-03-01T10:32:05+00:00")
+            .unwrap()
+            .with_timezone(&chrono::Local)
+            .format("[%H:%M:%S]")
+            .to_string();
+        let segments = db.get_transcript_segments("rec_times").unwrap();
+        assert_eq!(segments[1].display_time, expected);
+    }
 }
@@ -0,0 +1,188 @@
+//! Diagnostics bundle for support requests
+//!
+//! `export_diagnostics` writes a zip with:
+//! - `diagnostics.json`: app version, OS, schema version, hardware profile,
+//!   installed models and settings (secrets redacted)
+//! - `logs/`: the log files, when file logging has produced any
+
+use std::io::Write;
+use std::path::{Path, PathBuf};
+
+use anyhow::{Context, Result};
+use log::info;
+use serde::Serialize;
+use serde_json::Value;
+use tauri::{AppHandle, Manager};
+use zip::write::SimpleFileOptions;
+use zip::{CompressionMethod, ZipWriter};
+
+use crate::audio::hardware_detector::HardwareProfile;
+use crate::database::Setting;
+use crate::state::AppState;
+
+const DIAGNOSTICS_FILE_NAME: &str = "diagnostics.json";
+const REDACTED: &str = "[redacted]";
+
+/// Setting keys with any of these underscore-separated words hold credentials
+/// ("openai_api_key" does, "recording_hotkey" doesn't)
+const SECRET_KEY_WORDS: &[&str] = &["key", "apikey", "token", "secret", "password", "auth"];
+
+/// Settings holding private URLs that carry their own credentials
+const SECRET_SETTINGS: &[&str] = &["completion_webhook_url"];
+
+#[derive(Debug, Serialize)]
+struct InstalledModels {
+    whisper: Value,
+    llm: Value,
+    diarization: Value,
+}
+
+#[derive(Debug, Serialize)]
+struct DiagnosticsReport {
+    generated_at: String,
+    app_version: String,
+    os: String,
+    arch: String,
+    schema_version: Option<i32>,
+    hardware: HardwareProfile,
+    models: InstalledModels,
+    settings: Vec<(String, String)>,
+}
+
+fn is_secret_setting(key: &str) -> bool {
+    let key = key.to_lowercase();
+    SECRET_SETTINGS.contains(&key.as_str())
+        || key.split(['_', '-', '.']).any(|word| SECRET_KEY_WORDS.contains(&word))
+}
+
+/// Settings as key/value pairs with secret values replaced
+fn redact_settings(settings: Vec<Setting>) -> Vec<(String, String)> {
+    let mut settings: Vec<(String, String)> = settings
+        .into_iter()
+        .map(|setting| {
+            let value = if is_secret_setting(&setting.key) && !setting.value.is_empty() {
+                REDACTED.to_string()
+            } else {
+                setting.value
+            };
+            (setting.key, value)
+        })
+        .collect();
+    settings.sort();
+    settings
+}
+
+/// Model lists are best effort: a failure is reported in place of the list
+fn model_list<T: Serialize>(models: Result<T, String>) -> Value {
+    match models {
+        Ok(models) => serde_json::to_value(models).unwrap_or(Value::Null),
+        Err(e) => serde_json::json!({ "error": e }),
+    }
+}
+
+fn write_bundle(dest: &Path, report: &DiagnosticsReport, log_files: &[PathBuf]) -> Result<()> {
+    let file = std::fs::File::create(dest)
+        .with_context(|| format!("Failed to create {}", dest.display()))?;
+    let mut zip = ZipWriter::new(file);
+    let options = SimpleFileOptions::default().compression_method(CompressionMethod::Deflated);
+
+    zip.start_file(DIAGNOSTICS_FILE_NAME, options)?;
+    zip.write_all(serde_json::to_string_pretty(report)?.as_bytes())?;
+
+    for path in log_files {
+        let Some(name) = path.file_name().and_then(|n| n.to_str()) else {
+            continue;
+        };
+        let contents = std::fs::read(path)
+            .with_context(|| format!("Failed to read {}", path.display()))?;
+        zip.start_file(format!("logs/{}", name), options)?;
+        zip.write_all(&contents)?;
+    }
+
+    zip.finish().context("Failed to write diagnostics bundle")?;
+    Ok(())
+}
+
+/// Gather logs, schema version, hardware, installed models and redacted
+/// settings into a zip at `dest_path`; returns the path written
+#[tauri::command]
+pub async fn export_diagnostics(
+    app: AppHandle,
+    state: tauri::State<'_, AppState>,
+    dest_path: String,
+) -> Result<String, String> {
+    let (schema_version, settings) = {
+        let db = state.db().await;
+        (
+            db.schema_version().ok(),
+            db.get_all_settings_list().map_err(|e| e.to_string())?,
+        )
+    };
+
+    let whisper = crate::whisper_engine::commands::whisper_get_available_models().await;
+    let llm = state
+        .llm_model_manager
+        .read()
+        .await
+        .local_models()
+        .map_err(|e| e.to_string());
+    let diarization = crate::diarization::model_manager::check_diarization_models(app.clone()).await;
+
+    let report = DiagnosticsReport {
+        generated_at: chrono::Utc::now().to_rfc3339(),
+        app_version: app.package_info().version.to_string(),
+        os: std::env::consts::OS.to_string(),
+        arch: std::env::consts::ARCH.to_string(),
+        schema_version,
+        hardware: HardwareProfile::detect().clone(),
+        models: InstalledModels {
+            whisper: model_list(whisper),
+            llm: model_list(llm),
+            diarization: model_list(diarization),
+        },
+        settings: redact_settings(settings),
+    };
+
+    let log_files = crate::file_log::log_files(&app).unwrap_or_default();
+    let dest = PathBuf::from(&dest_path);
+    tokio::task::spawn_blocking(move || write_bundle(&dest, &report, &log_files))
+        .await
+        .map_err(|e| e.to_string())?
+        .map_err(|e| format!("{:#}", e))?;
+
+    info!("🩺 Diagnostics bundle written to {}", dest_path);
+    Ok(dest_path)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn setting(key: &str, value: &str) -> Setting {
+        Setting {
+            key: key.to_string(),
+            value: value.to_string(),
+            value_type: "string".to_string(),
+            updated_at: String::new(),
+        }
+    }
+
+    #[test]
+    fn test_redact_settings() {
+        let settings = redact_settings(vec![
+            setting("openai_api_key", "sk-123"),
+            setting("completion_webhook_url", "https://hooks.example.com/T0/B0/secret"),
+            setting("recording_hotkey", "CommandOrControl+Shift+R"),
+            setting("completion_webhook_allow_private", "false"),
+            setting("auth_token", ""),
+        ]);
+
+        assert_eq!(settings, vec![
+            ("auth_token".to_string(), String::new()),
+            ("completion_webhook_allow_private".to_string(), "false".to_string()),
+            ("completion_webhook_url".to_string(), REDACTED.to_string()),
+            ("openai_api_key".to_string(), REDACTED.to_string()),
+            ("recording_hotkey".to_string(), "CommandOrControl+Shift+R".to_string()),
+        ]);
+    }
+}
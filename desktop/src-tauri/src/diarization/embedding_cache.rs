@@ -0,0 +1,81 @@
+// Per-recording cache of segment embeddings
+// Stores the speech segments and voice embeddings computed during diarization
+// in the recording folder, so speakers can be re-clustered with different
+// parameters without running the ONNX models again
+
+use std::path::Path;
+
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+
+use super::engine::SegmentEmbedding;
+
+/// Cache file written next to the recording's audio
+pub const EMBEDDING_CACHE_FILE_NAME: &str = "diarization_embeddings.json";
+
+/// Bumped when the cached data can no longer be reused (e.g. a new embedding model)
+const CACHE_VERSION: u32 = 1;
+
+#[derive(Serialize, Deserialize)]
+struct EmbeddingCache {
+    version: u32,
+    segments: Vec<SegmentEmbedding>,
+}
+
+/// Write the segment embeddings for a recording folder (atomic write with temp file)
+pub fn save_segment_embeddings(folder: &Path, segments: &[SegmentEmbedding]) -> Result<()> {
+    let cache = EmbeddingCache {
+        version: CACHE_VERSION,
+        segments: segments.to_vec(),
+    };
+    let json = serde_json::to_string(&cache)?;
+
+    let path = folder.join(EMBEDDING_CACHE_FILE_NAME);
+    let temp_path = folder.join(format!(".{}.tmp", EMBEDDING_CACHE_FILE_NAME));
+    std::fs::write(&temp_path, json)
+        .with_context(|| format!("Failed to write {}", temp_path.display()))?;
+    std::fs::rename(&temp_path, &path)
+        .with_context(|| format!("Failed to write {}", path.display()))?;
+    Ok(())
+}
+
+/// Read the cached segment embeddings, None if there is no usable cache
+pub fn load_segment_embeddings(folder: &Path) -> Result<Option<Vec<SegmentEmbedding>>> {
+    let path = folder.join(EMBEDDING_CACHE_FILE_NAME);
+    if !path.exists() {
+        return Ok(None);
+    }
+
+    let json = std::fs::read_to_string(&path)
+        .with_context(|| format!("Failed to read {}", path.display()))?;
+    let cache: EmbeddingCache = serde_json::from_str(&json)
+        .with_context(|| format!("Invalid diarization cache {}", path.display()))?;
+
+    if cache.version != CACHE_VERSION {
+        return Ok(None);
+    }
+    Ok(Some(cache.segments))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::tempdir;
+
+    #[test]
+    fn test_cache_roundtrip() {
+        let dir = tempdir().unwrap();
+        assert!(load_segment_embeddings(dir.path()).unwrap().is_none());
+
+        let segments = vec![
+            SegmentEmbedding { start_time: 0.5, end_time: 2.0, embedding: vec![0.1, 0.2, 0.3] },
+            SegmentEmbedding { start_time: 2.5, end_time: 4.0, embedding: vec![0.3, 0.2, 0.1] },
+        ];
+        save_segment_embeddings(dir.path(), &segments).unwrap();
+
+        let loaded = load_segment_embeddings(dir.path()).unwrap().unwrap();
+        assert_eq!(loaded.len(), 2);
+        assert_eq!(loaded[1].start_time, 2.5);
+        assert_eq!(loaded[0].embedding, vec![0.1, 0.2, 0.3]);
+    }
+}
@@ -12,7 +12,8 @@ use log::{info, debug, warn};
 
 use pyannote_rs::{EmbeddingExtractor, EmbeddingManager, get_segments};
 
-use super::speaker_db::SpeakerDatabase;
+use super::speaker_db::{cosine_similarity, SpeakerDatabase};
+use crate::state::AppState;
 
 /// Global diarization engine instance
 pub static DIARIZATION_ENGINE: Lazy<Arc<RwLock<Option<DiarizationEngine>>>> =
@@ -61,6 +62,96 @@ pub struct SpeakerSegment {
     pub registered_speaker_id: Option<String>,
 }
 
+/// A speech segment and its voice embedding, before speakers are assigned
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SegmentEmbedding {
+    /// Start time in seconds
+    pub start_time: f64,
+    /// End time in seconds
+    pub end_time: f64,
+    pub embedding: Vec<f32>,
+}
+
+/// Embeddings sampled when estimating the speaker count; clustering is cubic in this
+const MAX_ESTIMATION_EMBEDDINGS: usize = 200;
+/// Clusters closer than this cosine distance are treated as a single speaker
+const SAME_SPEAKER_DISTANCE: f32 = 0.5;
+
+/// Estimate how many speakers the embeddings come from, at most `max_speakers`.
+///
+/// Clusters the embeddings bottom-up (average linkage on cosine distance) and
+/// picks the count at the elbow: where the next merge would join clusters
+/// much further apart than the previous merge did.
+pub fn estimate_speaker_count(embeddings: &[Vec<f32>], max_speakers: usize) -> usize {
+    let max_speakers = max_speakers.max(1);
+    if embeddings.len() < 2 {
+        return 1;
+    }
+
+    let stride = embeddings.len().div_ceil(MAX_ESTIMATION_EMBEDDINGS);
+    let sample: Vec<&Vec<f32>> = embeddings.iter().step_by(stride).collect();
+    let n = sample.len();
+
+    let mut distance = vec![vec![0.0f32; n]; n];
+    for i in 0..n {
+        for j in (i + 1)..n {
+            let d = 1.0 - cosine_similarity(sample[i], sample[j]);
+            distance[i][j] = d;
+            distance[j][i] = d;
+        }
+    }
+
+    // merge_distance[k] is the distance of the merge that takes k clusters down to k - 1
+    let mut merge_distance = vec![0.0f32; n + 2];
+    let mut sizes = vec![1usize; n];
+    let mut active = vec![true; n];
+
+    for clusters in (2..=n).rev() {
+        let mut closest = (0, 0, f32::INFINITY);
+        for a in (0..n).filter(|&a| active[a]) {
+            for b in ((a + 1)..n).filter(|&b| active[b]) {
+                if distance[a][b] < closest.2 {
+                    closest = (a, b, distance[a][b]);
+                }
+            }
+        }
+        let (a, b, d) = closest;
+        merge_distance[clusters] = d;
+
+        let total = (sizes[a] + sizes[b]) as f32;
+        for c in (0..n).filter(|&c| active[c] && c != a && c != b) {
+            let merged = (distance[a][c] * sizes[a] as f32 + distance[b][c] * sizes[b] as f32) / total;
+            distance[a][c] = merged;
+            distance[c][a] = merged;
+        }
+        sizes[a] += sizes[b];
+        active[b] = false;
+    }
+
+    if merge_distance[2] < SAME_SPEAKER_DISTANCE {
+        return 1;
+    }
+
+    let mut best = (1, f32::NEG_INFINITY);
+    for k in 2..=max_speakers.min(n) {
+        let gap = merge_distance[k] - merge_distance[k + 1];
+        if gap > best.1 {
+            best = (k, gap);
+        }
+    }
+    best.0
+}
+
+/// Average voice embedding of one speaker across the segments diarized so far
+#[derive(Debug, Clone)]
+pub struct SpeakerCentroid {
+    pub speaker_id: String,
+    pub speaker_label: String,
+    pub embedding: Vec<f32>,
+    pub segment_count: usize,
+    pub registered_speaker_id: Option<String>,
+}
+
 /// Diarization engine that identifies speakers in audio
 pub struct DiarizationEngine {
     config: DiarizationConfig,
@@ -71,6 +162,11 @@ pub struct DiarizationEngine {
     speaker_labels: HashMap<String, String>,
     /// Counter for assigning speaker IDs in a session
     speaker_counter: usize,
+    /// Running embedding sums per speaker in this session (for centroids)
+    speaker_embedding_sums: HashMap<String, SpeakerCentroid>,
+    /// Assign segments to the closest speaker once max_speakers is reached,
+    /// instead of "Unknown" (set when the speaker count was estimated)
+    assign_overflow_to_closest: bool,
 }
 
 impl DiarizationEngine {
@@ -113,6 +209,8 @@ impl DiarizationEngine {
             speaker_db,
             speaker_labels: HashMap::new(),
             speaker_counter: 0,
+            speaker_embedding_sums: HashMap::new(),
+            assign_overflow_to_closest: false,
         })
     }
 
@@ -121,6 +219,53 @@ impl DiarizationEngine {
     /// Takes f32 samples at any sample rate and returns speaker segments.
     /// Internally converts to i16 at 16kHz for pyannote-rs.
     pub fn diarize(&mut self, samples: &[f32], sample_rate: u32) -> Result<Vec<SpeakerSegment>> {
+        let segments = self.extract_segment_embeddings(samples, sample_rate)?;
+        self.assign_speakers(&segments)
+    }
+
+    /// Run diarization, first estimating the number of speakers (up to
+    /// `max_speakers`) and using that as the speaker limit.
+    /// Returns the segments and the estimated count.
+    pub fn diarize_auto(
+        &mut self,
+        samples: &[f32],
+        sample_rate: u32,
+        max_speakers: usize,
+    ) -> Result<(Vec<SpeakerSegment>, usize)> {
+        let segments = self.extract_segment_embeddings(samples, sample_rate)?;
+        self.assign_speakers_auto(&segments, max_speakers)
+    }
+
+    /// Assign speakers to embedded segments after estimating the speaker count
+    /// (up to `max_speakers`). Returns the segments and the estimated count.
+    pub fn assign_speakers_auto(
+        &mut self,
+        segments: &[SegmentEmbedding],
+        max_speakers: usize,
+    ) -> Result<(Vec<SpeakerSegment>, usize)> {
+        let embeddings: Vec<Vec<f32>> = segments.iter().map(|s| s.embedding.clone()).collect();
+        let estimated = estimate_speaker_count(&embeddings, max_speakers);
+        info!("Estimated {} speakers (limit {}) from {} segments", estimated, max_speakers, segments.len());
+
+        self.update_config(Some(estimated), None);
+        self.assign_overflow_to_closest = true;
+        let speaker_segments = self.assign_speakers(segments)?;
+        Ok((speaker_segments, estimated))
+    }
+
+    /// Find speech segments and compute a voice embedding for each
+    pub fn extract_segment_embeddings(&mut self, samples: &[f32], sample_rate: u32) -> Result<Vec<SegmentEmbedding>> {
+        self.extract_segment_embeddings_with_progress(samples, sample_rate, &mut |_| {})
+    }
+
+    /// Like `extract_segment_embeddings`, calling `on_progress` with the
+    /// fraction (0.0-1.0) of the audio processed so far
+    pub fn extract_segment_embeddings_with_progress(
+        &mut self,
+        samples: &[f32],
+        sample_rate: u32,
+        on_progress: &mut dyn FnMut(f64),
+    ) -> Result<Vec<SegmentEmbedding>> {
         info!("Running diarization on {} samples at {} Hz", samples.len(), sample_rate);
 
         // Convert f32 to i16 samples (pyannote-rs uses i16)
@@ -133,7 +278,8 @@ impl DiarizationEngine {
         let segments_iter = get_segments(&samples_i16, sample_rate, &self.config.segmentation_model_path)
             .map_err(|e| anyhow!("Failed to run segmentation: {}", e))?;
 
-        let mut speaker_segments = Vec::new();
+        let mut segments = Vec::new();
+        let total_seconds = samples.len() as f64 / sample_rate.max(1) as f64;
 
         // Process each detected speech segment
         for segment_result in segments_iter {
@@ -154,13 +300,35 @@ impl DiarizationEngine {
                 }
             };
 
+            segments.push(SegmentEmbedding {
+                start_time: segment.start,
+                end_time: segment.end,
+                embedding,
+            });
+
+            if total_seconds > 0.0 {
+                on_progress((segment.end / total_seconds).min(1.0));
+            }
+        }
+
+        on_progress(1.0);
+        Ok(segments)
+    }
+
+    /// Assign a speaker to each embedded segment
+    pub fn assign_speakers(&mut self, segments: &[SegmentEmbedding]) -> Result<Vec<SpeakerSegment>> {
+        let mut speaker_segments = Vec::with_capacity(segments.len());
+
+        for segment in segments {
             // Find or create speaker for this embedding
             let (speaker_id, speaker_label, confidence, is_registered, registered_id) =
-                self.identify_speaker(&embedding)?;
+                self.identify_speaker(&segment.embedding)?;
+
+            self.accumulate_embedding(&speaker_id, &speaker_label, registered_id.as_deref(), &segment.embedding);
 
             speaker_segments.push(SpeakerSegment {
-                start_time: segment.start,
-                end_time: segment.end,
+                start_time: segment.start_time,
+                end_time: segment.end_time,
                 speaker_id,
                 speaker_label,
                 confidence,
@@ -176,6 +344,48 @@ impl DiarizationEngine {
         Ok(speaker_segments)
     }
 
+    /// Add a segment embedding to its speaker's running sum
+    fn accumulate_embedding(
+        &mut self,
+        speaker_id: &str,
+        speaker_label: &str,
+        registered_id: Option<&str>,
+        embedding: &[f32],
+    ) {
+        let entry = self.speaker_embedding_sums
+            .entry(speaker_id.to_string())
+            .or_insert_with(|| SpeakerCentroid {
+                speaker_id: speaker_id.to_string(),
+                speaker_label: speaker_label.to_string(),
+                embedding: vec![0.0; embedding.len()],
+                segment_count: 0,
+                registered_speaker_id: registered_id.map(|s| s.to_string()),
+            });
+
+        if entry.embedding.len() != embedding.len() {
+            warn!("Embedding size mismatch for {}, skipping segment", speaker_id);
+            return;
+        }
+        for (sum, value) in entry.embedding.iter_mut().zip(embedding) {
+            *sum += value;
+        }
+        entry.segment_count += 1;
+    }
+
+    /// Centroid embedding of each speaker detected since the last session reset
+    pub fn speaker_centroids(&self) -> Vec<SpeakerCentroid> {
+        self.speaker_embedding_sums
+            .values()
+            .filter(|c| c.segment_count > 0)
+            .map(|c| SpeakerCentroid {
+                embedding: c.embedding.iter().map(|v| v / c.segment_count as f32).collect(),
+                // Labels can be renamed during the session
+                speaker_label: self.speaker_labels.get(&c.speaker_id).cloned().unwrap_or_else(|| c.speaker_label.clone()),
+                ..c.clone()
+            })
+            .collect()
+    }
+
     /// Identify speaker from embedding, checking registered voices first
     fn identify_speaker(&mut self, embedding: &[f32]) -> Result<(String, String, f32, bool, Option<String>)> {
         // First, check against registered speakers
@@ -220,11 +430,30 @@ impl DiarizationEngine {
             return Ok((speaker_id, speaker_label, 0.75, false, None));
         }
 
+        if self.assign_overflow_to_closest {
+            if let Some(closest) = self.closest_session_speaker(embedding) {
+                return Ok(closest);
+            }
+        }
+
         // Fallback: max speakers reached, assign to "Unknown"
         warn!("Max speakers ({}) reached, segment assigned to 'Unknown'", self.config.max_speakers);
         Ok(("unknown".to_string(), "Unknown".to_string(), 0.3, false, None))
     }
 
+    /// Session speaker whose centroid is most similar to the embedding
+    fn closest_session_speaker(&self, embedding: &[f32]) -> Option<(String, String, f32, bool, Option<String>)> {
+        self.speaker_centroids()
+            .into_iter()
+            .filter(|c| c.registered_speaker_id.is_none())
+            .map(|c| {
+                let similarity = cosine_similarity(embedding, &c.embedding);
+                (c, similarity)
+            })
+            .max_by(|a, b| a.1.total_cmp(&b.1))
+            .map(|(c, similarity)| (c.speaker_id, c.speaker_label, similarity.max(0.0), false, None))
+    }
+
     /// Register a new voice for future recognition
     pub fn register_voice(&mut self, name: &str, samples: &[f32]) -> Result<String> {
         info!("Registering voice for '{}'", name);
@@ -258,6 +487,28 @@ impl DiarizationEngine {
         self.speaker_db.get_all_speakers()
     }
 
+    /// Get a registered speaker's name and embedding
+    pub fn get_registered_embedding(&self, speaker_id: &str) -> Option<(String, Vec<f32>)> {
+        self.speaker_db
+            .get_speaker(speaker_id)
+            .map(|s| (s.name.clone(), s.embedding.clone()))
+    }
+
+    /// Current similarity threshold for speaker matching
+    pub fn similarity_threshold(&self) -> f32 {
+        self.config.similarity_threshold
+    }
+
+    /// Maximum number of session speakers
+    pub fn max_speakers(&self) -> usize {
+        self.config.max_speakers
+    }
+
+    /// Registered voice matching an embedding at the current threshold: (id, name, similarity)
+    pub fn match_registered_speaker(&self, embedding: &[f32]) -> Result<Option<(String, String, f32)>> {
+        self.speaker_db.find_matching_speaker(embedding, self.config.similarity_threshold)
+    }
+
     /// Rename a speaker label for a specific session
     pub fn rename_speaker(&mut self, speaker_id: &str, new_label: &str) {
         self.speaker_labels.insert(speaker_id.to_string(), new_label.to_string());
@@ -269,6 +520,7 @@ impl DiarizationEngine {
     pub fn reset_session(&mut self) {
         self.speaker_counter = 0;
         self.speaker_labels.clear();
+        self.speaker_embedding_sums.clear();
         self.embedding_manager = EmbeddingManager::new(self.config.max_speakers);
         info!("Diarization session reset");
     }
@@ -288,6 +540,8 @@ impl DiarizationEngine {
         // Reset session state when config changes
         self.speaker_counter = 0;
         self.speaker_labels.clear();
+        self.speaker_embedding_sums.clear();
+        self.assign_overflow_to_closest = false;
     }
 
     /// Check if the engine is ready
@@ -385,6 +639,173 @@ pub async fn rename_speaker(
     Ok(())
 }
 
+/// Upper bound on audio used to compute an enrollment embedding
+const MAX_ENROLLMENT_SECONDS: f64 = 60.0;
+
+/// Concatenate the audio of the given (start, end) ranges in seconds, up to `max_seconds`
+fn collect_speaker_audio(
+    samples: &[f32],
+    sample_rate: u32,
+    ranges: &[(f64, f64)],
+    max_seconds: f64,
+) -> Vec<f32> {
+    let max_samples = (max_seconds * sample_rate as f64) as usize;
+    let mut audio = Vec::new();
+
+    for &(start, end) in ranges {
+        let start_idx = ((start.max(0.0) * sample_rate as f64) as usize).min(samples.len());
+        let end_idx = ((end.max(0.0) * sample_rate as f64) as usize).min(samples.len());
+        if end_idx <= start_idx {
+            continue;
+        }
+
+        let remaining = max_samples.saturating_sub(audio.len());
+        if remaining == 0 {
+            break;
+        }
+        let take = (end_idx - start_idx).min(remaining);
+        audio.extend_from_slice(&samples[start_idx..start_idx + take]);
+    }
+
+    audio
+}
+
+/// Tauri command to enroll a speaker from an existing recording
+///
+/// Uses the audio of every transcript segment labeled `speaker_id` in the
+/// recording to compute a voice embedding, registers it under `name`, and
+/// relabels those segments as the registered speaker. Returns the new speaker ID.
+#[tauri::command]
+pub async fn enroll_speaker_from_recording(
+    state: tauri::State<'_, AppState>,
+    recording_id: String,
+    speaker_id: String,
+    name: String,
+) -> Result<String, String> {
+    let (audio_path, ranges) = {
+        let db = state.db().await;
+        let recording = db.get_recording(&recording_id)
+            .map_err(|e| e.to_string())?
+            .ok_or_else(|| format!("Recording not found: {}", recording_id))?;
+        let audio_path = recording.audio_file_path
+            .ok_or("Recording has no audio file")?;
+
+        let ranges: Vec<(f64, f64)> = db.get_transcript_segments(&recording_id)
+            .map_err(|e| e.to_string())?
+            .into_iter()
+            .filter(|s| s.speaker_id.as_deref() == Some(speaker_id.as_str()))
+            .map(|s| (s.audio_start_time, s.audio_end_time))
+            .collect();
+        (audio_path, ranges)
+    };
+
+    if ranges.is_empty() {
+        return Err(format!("No segments labeled '{}' in this recording", speaker_id));
+    }
+
+    let (samples, sample_rate) = tokio::task::spawn_blocking(move || {
+        crate::audio::retranscription::decode_audio_file(&audio_path)
+    })
+    .await
+    .map_err(|e| e.to_string())?
+    .map_err(|e| e.to_string())?;
+
+    let speaker_audio = collect_speaker_audio(&samples, sample_rate, &ranges, MAX_ENROLLMENT_SECONDS);
+    if speaker_audio.len() < sample_rate as usize {
+        return Err("Not enough audio for this speaker (need at least 1 second)".to_string());
+    }
+    info!("Enrolling '{}' from {} segments ({:.1}s of audio) in recording {}",
+          name, ranges.len(), speaker_audio.len() as f64 / sample_rate as f64, recording_id);
+
+    let registered_id = {
+        let mut guard = DIARIZATION_ENGINE.write().await;
+        let engine = guard.as_mut().ok_or("Diarization engine not initialized")?;
+        engine.register_voice(&name, &speaker_audio).map_err(|e| e.to_string())?
+    };
+
+    let db = state.db().await;
+    db.assign_registered_speaker(&recording_id, &speaker_id, &registered_id, &name)
+        .map_err(|e| e.to_string())?;
+
+    Ok(registered_id)
+}
+
+/// A detected speaker in a past recording that was relabeled as a registered speaker
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SpeakerRematch {
+    pub recording_id: String,
+    /// The generic speaker ID that matched (e.g. "speaker_1")
+    pub previous_speaker_id: String,
+    pub previous_label: String,
+    pub similarity: f32,
+    pub segments_updated: usize,
+}
+
+/// Tauri command to retroactively recognize a registered speaker in past recordings
+///
+/// Compares the registered voice against the speaker embeddings stored for each
+/// recording and relabels the best-matching generic speaker above the threshold.
+/// Recordings without stored embeddings are skipped.
+#[tauri::command]
+pub async fn rematch_speaker_across_recordings(
+    state: tauri::State<'_, AppState>,
+    speaker_id: String,
+    recording_ids: Option<Vec<String>>,
+    similarity_threshold: Option<f32>,
+) -> Result<Vec<SpeakerRematch>, String> {
+    let (name, registered_embedding, default_threshold) = {
+        let guard = DIARIZATION_ENGINE.read().await;
+        let engine = guard.as_ref().ok_or("Diarization engine not initialized")?;
+        let (name, embedding) = engine
+            .get_registered_embedding(&speaker_id)
+            .ok_or_else(|| format!("Registered speaker not found: {}", speaker_id))?;
+        (name, embedding, engine.similarity_threshold())
+    };
+    let threshold = similarity_threshold.unwrap_or(default_threshold);
+
+    let db = state.db().await;
+    let recording_ids = match recording_ids {
+        Some(ids) => ids,
+        None => db.get_recordings_with_speaker_embeddings().map_err(|e| e.to_string())?,
+    };
+
+    let mut matches = Vec::new();
+    for recording_id in recording_ids {
+        let embeddings = db
+            .get_recording_speaker_embeddings(&recording_id)
+            .map_err(|e| e.to_string())?;
+
+        // At most one detected speaker per recording can be this person
+        let best = embeddings
+            .iter()
+            .filter(|e| e.registered_speaker_id.is_none())
+            .map(|e| (e, cosine_similarity(&registered_embedding, &e.embedding)))
+            .filter(|(_, similarity)| *similarity >= threshold)
+            .max_by(|a, b| a.1.total_cmp(&b.1));
+
+        let Some((detected, similarity)) = best else {
+            continue;
+        };
+
+        let segments_updated = db
+            .assign_registered_speaker(&recording_id, &detected.speaker_id, &speaker_id, &name)
+            .map_err(|e| e.to_string())?;
+
+        info!("Matched '{}' to {} in recording {} (similarity {:.2}, {} segments)",
+              name, detected.speaker_label, recording_id, similarity, segments_updated);
+
+        matches.push(SpeakerRematch {
+            recording_id,
+            previous_speaker_id: detected.speaker_id.clone(),
+            previous_label: detected.speaker_label.clone(),
+            similarity,
+            segments_updated,
+        });
+    }
+
+    Ok(matches)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -395,4 +816,45 @@ mod tests {
         assert_eq!(config.max_speakers, 10);
         assert_eq!(config.similarity_threshold, 0.5);
     }
+
+    /// Noisy copies of a unit vector along `axis`
+    fn cluster(axis: usize, count: usize) -> Vec<Vec<f32>> {
+        (0..count)
+            .map(|i| {
+                let mut v = vec![0.05 * ((i % 3) as f32); 8];
+                v[axis] = 1.0;
+                v
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_estimate_speaker_count() {
+        let mut embeddings = cluster(0, 12);
+        assert_eq!(estimate_speaker_count(&embeddings, 10), 1);
+
+        embeddings.extend(cluster(3, 8));
+        embeddings.extend(cluster(6, 5));
+        assert_eq!(estimate_speaker_count(&embeddings, 10), 3);
+        // The limit still applies
+        assert_eq!(estimate_speaker_count(&embeddings, 2), 2);
+
+        assert_eq!(estimate_speaker_count(&[], 4), 1);
+    }
+
+    #[test]
+    fn test_collect_speaker_audio() {
+        // 10 seconds at 10 Hz, sample value = index
+        let samples: Vec<f32> = (0..100).map(|i| i as f32).collect();
+
+        let audio = collect_speaker_audio(&samples, 10, &[(1.0, 2.0), (5.0, 5.5)], 60.0);
+        assert_eq!(audio.len(), 15);
+        assert_eq!(audio[0], 10.0);
+        assert_eq!(audio[10], 50.0);
+
+        // Capped at max_seconds, ranges past the end are clamped
+        let audio = collect_speaker_audio(&samples, 10, &[(0.0, 3.0), (8.0, 20.0)], 4.0);
+        assert_eq!(audio.len(), 40);
+        assert_eq!(audio[30], 80.0);
+    }
 }
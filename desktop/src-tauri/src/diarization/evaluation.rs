@@ -0,0 +1,306 @@
+// Diarization Error Rate (DER) evaluation
+// Compares a recording's speaker labels against a reference RTTM timeline.
+// Scored frame by frame without a forgiveness collar, with overlapping speech
+// counted, and with hypothesis speakers mapped one-to-one onto reference
+// speakers so as to maximise the matched time (Hungarian algorithm).
+
+use std::collections::HashMap;
+
+use serde::Serialize;
+
+use super::rttm::{parse_rttm, read_rttm_file, RttmSegment};
+use crate::state::AppState;
+
+/// Frame length used for scoring, in seconds
+const FRAME_SECONDS: f64 = 0.01;
+
+/// A hypothesis speaker and the reference speaker it was mapped to
+#[derive(Debug, Clone, Serialize)]
+pub struct SpeakerMapping {
+    pub hypothesis_speaker: String,
+    pub reference_speaker: String,
+    /// Time both speakers are active together, in seconds
+    pub overlap_seconds: f64,
+}
+
+/// DER and its components, in seconds of speaker time
+#[derive(Debug, Clone, Serialize)]
+pub struct DerReport {
+    /// Total reference speaker time (overlapping speakers counted separately)
+    pub total_reference_seconds: f64,
+    /// Reference speech with no hypothesis speaker
+    pub missed_seconds: f64,
+    /// Hypothesis speech with no reference speaker
+    pub false_alarm_seconds: f64,
+    /// Speech attributed to the wrong speaker
+    pub confusion_seconds: f64,
+    /// (missed + false alarm + confusion) / total reference time
+    pub der: f64,
+    pub speaker_mapping: Vec<SpeakerMapping>,
+}
+
+/// Speaker index active in each frame
+fn frames_by_speaker(segments: &[RttmSegment], speakers: &[String], num_frames: usize) -> Vec<Vec<usize>> {
+    let mut frames = vec![Vec::new(); num_frames];
+    for segment in segments {
+        let speaker = speakers.iter().position(|s| *s == segment.speaker).unwrap();
+        let start = (segment.start_time / FRAME_SECONDS).round() as usize;
+        let end = ((segment.end_time / FRAME_SECONDS).round() as usize).min(num_frames);
+        for frame in frames.iter_mut().take(end).skip(start) {
+            if !frame.contains(&speaker) {
+                frame.push(speaker);
+            }
+        }
+    }
+    frames
+}
+
+fn unique_speakers(segments: &[RttmSegment]) -> Vec<String> {
+    let mut speakers: Vec<String> = Vec::new();
+    for segment in segments {
+        if !speakers.contains(&segment.speaker) {
+            speakers.push(segment.speaker.clone());
+        }
+    }
+    speakers
+}
+
+/// Assignment maximising the total weight; `weights[row][col]`, returns the column for each row
+fn max_weight_assignment(weights: &[Vec<f64>]) -> Vec<Option<usize>> {
+    let rows = weights.len();
+    let cols = weights.first().map_or(0, |r| r.len());
+    let n = rows.max(cols);
+    if n == 0 {
+        return Vec::new();
+    }
+
+    let max_weight = weights.iter().flatten().cloned().fold(0.0, f64::max);
+    // Square cost matrix, 1-indexed as in the classic formulation
+    let cost = |i: usize, j: usize| -> f64 {
+        if i <= rows && j <= cols { max_weight - weights[i - 1][j - 1] } else { max_weight }
+    };
+
+    let mut u = vec![0.0; n + 1];
+    let mut v = vec![0.0; n + 1];
+    let mut assigned_row = vec![0usize; n + 1];
+    let mut way = vec![0usize; n + 1];
+
+    for i in 1..=n {
+        assigned_row[0] = i;
+        let mut j0 = 0;
+        let mut min_v = vec![f64::INFINITY; n + 1];
+        let mut used = vec![false; n + 1];
+        loop {
+            used[j0] = true;
+            let i0 = assigned_row[j0];
+            let mut delta = f64::INFINITY;
+            let mut j1 = 0;
+            for j in 1..=n {
+                if !used[j] {
+                    let current = cost(i0, j) - u[i0] - v[j];
+                    if current < min_v[j] {
+                        min_v[j] = current;
+                        way[j] = j0;
+                    }
+                    if min_v[j] < delta {
+                        delta = min_v[j];
+                        j1 = j;
+                    }
+                }
+            }
+            for j in 0..=n {
+                if used[j] {
+                    u[assigned_row[j]] += delta;
+                    v[j] -= delta;
+                } else {
+                    min_v[j] -= delta;
+                }
+            }
+            j0 = j1;
+            if assigned_row[j0] == 0 {
+                break;
+            }
+        }
+        loop {
+            let j1 = way[j0];
+            assigned_row[j0] = assigned_row[j1];
+            j0 = j1;
+            if j0 == 0 {
+                break;
+            }
+        }
+    }
+
+    let mut result = vec![None; rows];
+    for j in 1..=n {
+        let i = assigned_row[j];
+        if (1..=rows).contains(&i) && j <= cols {
+            result[i - 1] = Some(j - 1);
+        }
+    }
+    result
+}
+
+/// Compute DER of `hypothesis` against `reference`
+pub fn compute_der(reference: &[RttmSegment], hypothesis: &[RttmSegment]) -> DerReport {
+    let end_time = reference
+        .iter()
+        .chain(hypothesis)
+        .map(|s| s.end_time)
+        .fold(0.0, f64::max);
+    let num_frames = (end_time / FRAME_SECONDS).round() as usize;
+
+    let ref_speakers = unique_speakers(reference);
+    let hyp_speakers = unique_speakers(hypothesis);
+    let ref_frames = frames_by_speaker(reference, &ref_speakers, num_frames);
+    let hyp_frames = frames_by_speaker(hypothesis, &hyp_speakers, num_frames);
+
+    let mut overlap = vec![vec![0.0; ref_speakers.len()]; hyp_speakers.len()];
+    let (mut total, mut missed, mut false_alarm, mut speaker_frames) = (0usize, 0usize, 0usize, 0usize);
+    for (ref_active, hyp_active) in ref_frames.iter().zip(&hyp_frames) {
+        total += ref_active.len();
+        missed += ref_active.len().saturating_sub(hyp_active.len());
+        false_alarm += hyp_active.len().saturating_sub(ref_active.len());
+        speaker_frames += ref_active.len().min(hyp_active.len());
+        for &h in hyp_active {
+            for &r in ref_active {
+                overlap[h][r] += FRAME_SECONDS;
+            }
+        }
+    }
+
+    let assignment = max_weight_assignment(&overlap);
+    let mut speaker_mapping = Vec::new();
+    let mut matched_seconds = 0.0;
+    for (h, r) in assignment.iter().enumerate() {
+        if let Some(r) = *r {
+            if overlap[h][r] > 0.0 {
+                matched_seconds += overlap[h][r];
+                speaker_mapping.push(SpeakerMapping {
+                    hypothesis_speaker: hyp_speakers[h].clone(),
+                    reference_speaker: ref_speakers[r].clone(),
+                    overlap_seconds: overlap[h][r],
+                });
+            }
+        }
+    }
+
+    let total_reference_seconds = total as f64 * FRAME_SECONDS;
+    let missed_seconds = missed as f64 * FRAME_SECONDS;
+    let false_alarm_seconds = false_alarm as f64 * FRAME_SECONDS;
+    let confusion_seconds = (speaker_frames as f64 * FRAME_SECONDS - matched_seconds).max(0.0);
+    let der = if total_reference_seconds > 0.0 {
+        (missed_seconds + false_alarm_seconds + confusion_seconds) / total_reference_seconds
+    } else {
+        0.0
+    };
+
+    DerReport {
+        total_reference_seconds,
+        missed_seconds,
+        false_alarm_seconds,
+        confusion_seconds,
+        der,
+        speaker_mapping,
+    }
+}
+
+/// Speaker timeline of a recording's saved transcript
+pub(crate) fn stored_speaker_segments(
+    segments: &[crate::database::TranscriptSegment],
+) -> Vec<RttmSegment> {
+    segments
+        .iter()
+        .filter(|s| s.audio_end_time > s.audio_start_time)
+        .filter_map(|s| {
+            s.speaker_id.as_ref().map(|speaker| RttmSegment {
+                start_time: s.audio_start_time.max(0.0),
+                end_time: s.audio_end_time,
+                speaker: speaker.clone(),
+            })
+        })
+        .collect()
+}
+
+/// Compare a recording's speaker labels with a reference RTTM file and report
+/// the Diarization Error Rate with its missed / false alarm / confusion parts
+#[tauri::command]
+pub async fn diarization_evaluate(
+    state: tauri::State<'_, AppState>,
+    recording_id: String,
+    reference_rttm_path: String,
+) -> Result<DerReport, String> {
+    let content = read_rttm_file(&reference_rttm_path)?;
+    let reference = parse_rttm(&content).map_err(|e| e.to_string())?;
+    if reference.is_empty() {
+        return Err("Reference RTTM has no speaker segments".to_string());
+    }
+
+    let transcripts = state
+        .db()
+        .await
+        .get_transcript_segments(&recording_id)
+        .map_err(|e| e.to_string())?;
+    let hypothesis = stored_speaker_segments(&transcripts);
+    if hypothesis.is_empty() {
+        return Err(format!("Recording {} has no speaker-labelled segments", recording_id));
+    }
+
+    // Use display labels in the mapping where the transcript has them
+    let labels: HashMap<&str, &str> = transcripts
+        .iter()
+        .filter_map(|s| Some((s.speaker_id.as_deref()?, s.speaker_label.as_deref()?)))
+        .collect();
+
+    let mut report = compute_der(&reference, &hypothesis);
+    for mapping in &mut report.speaker_mapping {
+        if let Some(label) = labels.get(mapping.hypothesis_speaker.as_str()) {
+            mapping.hypothesis_speaker = format!("{} ({})", label, mapping.hypothesis_speaker);
+        }
+    }
+    Ok(report)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn seg(start: f64, end: f64, speaker: &str) -> RttmSegment {
+        RttmSegment { start_time: start, end_time: end, speaker: speaker.to_string() }
+    }
+
+    #[test]
+    fn test_der_ignores_label_names() {
+        let reference = vec![seg(0.0, 5.0, "alice"), seg(5.0, 10.0, "bob")];
+        let hypothesis = vec![seg(0.0, 5.0, "speaker_1"), seg(5.0, 10.0, "speaker_0")];
+        let report = compute_der(&reference, &hypothesis);
+        assert!(report.der.abs() < 1e-9);
+        assert_eq!(report.speaker_mapping.len(), 2);
+    }
+
+    #[test]
+    fn test_der_components() {
+        // 10s of reference speech
+        let reference = vec![seg(0.0, 4.0, "alice"), seg(4.0, 10.0, "bob")];
+        // Misses 1s at the start, labels 2s of bob as alice, adds 1s after the end
+        let hypothesis = vec![seg(1.0, 6.0, "a"), seg(6.0, 11.0, "b")];
+        let report = compute_der(&reference, &hypothesis);
+
+        assert!((report.total_reference_seconds - 10.0).abs() < 1e-6);
+        assert!((report.missed_seconds - 1.0).abs() < 1e-6);
+        assert!((report.false_alarm_seconds - 1.0).abs() < 1e-6);
+        assert!((report.confusion_seconds - 2.0).abs() < 1e-6);
+        assert!((report.der - 0.4).abs() < 1e-6);
+    }
+
+    #[test]
+    fn test_assignment_picks_best_total() {
+        // Greedy would take (0,0)=5 and then (1,1)=1; the optimum is 4 + 4
+        let weights = vec![vec![5.0, 4.0], vec![4.0, 1.0]];
+        assert_eq!(max_weight_assignment(&weights), vec![Some(1), Some(0)]);
+
+        // More hypothesis speakers than reference speakers
+        let weights = vec![vec![1.0], vec![3.0], vec![2.0]];
+        assert_eq!(max_weight_assignment(&weights), vec![None, Some(0), None]);
+    }
+}
@@ -10,14 +10,29 @@ pub mod speaker_db;
 pub mod model_manager;
 pub mod sortformer;
 pub mod sortformer_provider;
+pub mod smoothing;
+pub mod embedding_cache;
+pub mod rttm;
+pub mod evaluation;
 
 // Re-export pyannote-rs based engine (default)
 pub use engine::{
-    DiarizationEngine, SpeakerSegment, DiarizationConfig,
+    DiarizationEngine, SpeakerSegment, DiarizationConfig, SpeakerRematch, SpeakerCentroid,
+    SegmentEmbedding, estimate_speaker_count,
     init_diarization_engine, get_diarization_engine,
     DIARIZATION_ENGINE,
 };
 
+// Re-export post-diarization smoothing
+pub use smoothing::{smooth_speaker_segments, DEFAULT_MIN_SPEAKER_SEGMENT_SECONDS};
+
+// Re-export the per-recording embedding cache
+pub use embedding_cache::{load_segment_embeddings, save_segment_embeddings, EMBEDDING_CACHE_FILE_NAME};
+
+// Re-export RTTM parsing and DER evaluation
+pub use rttm::{parse_rttm, to_rttm, RttmSegment};
+pub use evaluation::{compute_der, DerReport, SpeakerMapping};
+
 // Re-export speaker database
 pub use speaker_db::{
     RegisteredSpeaker, SpeakerDatabase,
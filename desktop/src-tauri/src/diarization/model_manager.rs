@@ -9,6 +9,8 @@ use tokio::fs;
 use tokio::io::AsyncWriteExt;
 use futures_util::StreamExt;
 
+use crate::downloads::{DownloadHandle, DownloadKind};
+
 /// Model URLs for pyannote diarization
 /// These models are the official pyannote-rs releases and are compatible with pyannote-rs 0.3.x
 const SEGMENTATION_MODEL_URL: &str =
@@ -95,6 +97,7 @@ async fn download_file(
 
     let total_size = response.content_length().unwrap_or(0);
     info!("Downloading {} ({:.1} MB)", model_name, total_size as f64 / (1024.0 * 1024.0));
+    let tracker = DownloadHandle::start(DownloadKind::Diarization, model_name);
 
     // Create temp file first
     let temp_path = dest_path.with_extension("tmp");
@@ -112,6 +115,7 @@ async fn download_file(
             .map_err(|e| anyhow!("Failed to write chunk: {}", e))?;
 
         downloaded += chunk.len() as u64;
+        tracker.update(downloaded, total_size);
 
         if total_size > 0 {
             let progress = ((downloaded as f64 / total_size as f64) * 100.0) as u8;
@@ -186,7 +190,7 @@ pub async fn download_diarization_models(
 ) -> Result<(), String> {
     use tauri::Emitter;
 
-    // Get models directory (same as Whisper models)
+    // Get models directory (same as Whisper models unless moved)
     let models_dir = get_models_directory(&app)?;
 
     info!("Downloading diarization models to {:?}", models_dir);
@@ -236,15 +240,9 @@ pub async fn are_diarization_models_ready(
     Ok(are_models_available(&models_dir))
 }
 
-/// Get the models directory path
+/// Get the models directory path (shared with Whisper models unless moved)
 fn get_models_directory(app: &tauri::AppHandle) -> Result<PathBuf, String> {
-    use tauri::Manager;
-
-    // Use the same directory as Whisper models
-    let app_data_dir = app.path().app_data_dir()
-        .map_err(|e| format!("Failed to get app data dir: {}", e))?;
-
-    Ok(app_data_dir.join("models"))
+    crate::model_directories::models_dir(app, crate::model_directories::ModelKind::Diarization)
 }
 
 #[cfg(test)]
@@ -0,0 +1,232 @@
+// RTTM (Rich Transcription Time Marked) speaker timelines
+// The standard format of diarization research tools: one line per speaker turn,
+// `SPEAKER <file> <channel> <start> <duration> <NA> <NA> <speaker> <NA> <NA>`
+
+use std::collections::HashMap;
+
+use anyhow::{anyhow, Result};
+
+use super::engine::SpeakerSegment;
+use crate::state::AppState;
+
+/// Largest RTTM file read
+const MAX_RTTM_FILE_BYTES: u64 = 16 * 1024 * 1024;
+/// Most speaker turns accepted from one RTTM file
+const MAX_RTTM_SEGMENTS: usize = 100_000;
+
+/// One speaker turn from an RTTM file
+#[derive(Debug, Clone, PartialEq)]
+pub struct RttmSegment {
+    /// Start time in seconds
+    pub start_time: f64,
+    /// End time in seconds
+    pub end_time: f64,
+    pub speaker: String,
+}
+
+/// Parse the SPEAKER lines of an RTTM file; other record types and comments are skipped
+pub fn parse_rttm(content: &str) -> Result<Vec<RttmSegment>> {
+    let mut segments = Vec::new();
+
+    for (line_no, line) in content.lines().enumerate() {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with(";;") || line.starts_with('#') {
+            continue;
+        }
+
+        let fields: Vec<&str> = line.split_whitespace().collect();
+        if fields[0] != "SPEAKER" {
+            continue;
+        }
+        if fields.len() < 8 {
+            return Err(anyhow!("RTTM line {}: expected at least 8 fields, found {}", line_no + 1, fields.len()));
+        }
+
+        let start: f64 = fields[3]
+            .parse()
+            .map_err(|_| anyhow!("RTTM line {}: invalid start time '{}'", line_no + 1, fields[3]))?;
+        let duration: f64 = fields[4]
+            .parse()
+            .map_err(|_| anyhow!("RTTM line {}: invalid duration '{}'", line_no + 1, fields[4]))?;
+        if !start.is_finite() || !duration.is_finite() || start < 0.0 || duration < 0.0 {
+            return Err(anyhow!("RTTM line {}: negative or invalid timing", line_no + 1));
+        }
+
+        if segments.len() == MAX_RTTM_SEGMENTS {
+            return Err(anyhow!("RTTM file has more than {} speaker segments", MAX_RTTM_SEGMENTS));
+        }
+        segments.push(RttmSegment {
+            start_time: start,
+            end_time: start + duration,
+            speaker: fields[7].to_string(),
+        });
+    }
+
+    segments.sort_by(|a, b| a.start_time.total_cmp(&b.start_time));
+    Ok(segments)
+}
+
+/// Read an RTTM file, refusing files larger than `MAX_RTTM_FILE_BYTES`
+pub fn read_rttm_file(path: &str) -> Result<String, String> {
+    let size = std::fs::metadata(path)
+        .map_err(|e| format!("Failed to read {}: {}", path, e))?
+        .len();
+    if size > MAX_RTTM_FILE_BYTES {
+        return Err(format!(
+            "{} is too large for an RTTM file ({} MB, at most {} MB)",
+            path,
+            size / (1024 * 1024),
+            MAX_RTTM_FILE_BYTES / (1024 * 1024)
+        ));
+    }
+    std::fs::read_to_string(path).map_err(|e| format!("Failed to read {}: {}", path, e))
+}
+
+/// RTTM fields can't contain whitespace
+fn rttm_token(value: &str) -> String {
+    let token: String = value
+        .chars()
+        .map(|c| if c.is_whitespace() { '_' } else { c })
+        .collect();
+    if token.is_empty() { "<NA>".to_string() } else { token }
+}
+
+/// Format speaker turns as RTTM SPEAKER lines for `file_id`
+pub fn to_rttm(file_id: &str, segments: &[RttmSegment]) -> String {
+    let file_id = rttm_token(file_id);
+    segments
+        .iter()
+        .map(|s| {
+            format!(
+                "SPEAKER {} 1 {:.3} {:.3} <NA> <NA> {} <NA> <NA>\n",
+                file_id,
+                s.start_time,
+                (s.end_time - s.start_time).max(0.0),
+                rttm_token(&s.speaker)
+            )
+        })
+        .collect()
+}
+
+/// Write a recording's speaker timeline (from its saved transcript) as RTTM.
+/// Returns the number of speaker turns written.
+#[tauri::command]
+pub async fn export_speaker_rttm(
+    state: tauri::State<'_, AppState>,
+    recording_id: String,
+    path: String,
+) -> Result<usize, String> {
+    let transcripts = state
+        .db()
+        .await
+        .get_transcript_segments(&recording_id)
+        .map_err(|e| e.to_string())?;
+
+    let mut turns = super::evaluation::stored_speaker_segments(&transcripts);
+    if turns.is_empty() {
+        return Err(format!("Recording {} has no speaker-labelled segments", recording_id));
+    }
+    turns.sort_by(|a, b| a.start_time.total_cmp(&b.start_time));
+
+    std::fs::write(&path, to_rttm(&recording_id, &turns))
+        .map_err(|e| format!("Failed to write {}: {}", path, e))?;
+    Ok(turns.len())
+}
+
+/// Replace a recording's speaker labels with the timeline from an RTTM file.
+/// Speakers are matched onto the existing transcript by time overlap; RTTM
+/// speakers that already exist in the transcript keep their display labels.
+/// Returns the number of transcript segments whose speaker changed.
+#[tauri::command]
+pub async fn import_speaker_rttm(
+    state: tauri::State<'_, AppState>,
+    recording_id: String,
+    path: String,
+) -> Result<usize, String> {
+    let content = read_rttm_file(&path)?;
+    let turns = parse_rttm(&content).map_err(|e| e.to_string())?;
+    if turns.is_empty() {
+        return Err("RTTM file has no speaker segments".to_string());
+    }
+
+    let db = state.db().await;
+    let existing = db.get_transcript_segments(&recording_id).map_err(|e| e.to_string())?;
+    if existing.is_empty() {
+        return Err(format!("Recording {} has no transcript", recording_id));
+    }
+    let labels: HashMap<String, (String, bool)> = existing
+        .iter()
+        .filter_map(|s| Some((s.speaker_id.clone()?, (s.speaker_label.clone()?, s.is_registered_speaker))))
+        .collect();
+
+    let speaker_segments: Vec<SpeakerSegment> = turns
+        .into_iter()
+        .map(|turn| {
+            let (speaker_label, is_registered) = labels
+                .get(&turn.speaker)
+                .cloned()
+                .unwrap_or_else(|| (turn.speaker.clone(), false));
+            SpeakerSegment {
+                start_time: turn.start_time,
+                end_time: turn.end_time,
+                speaker_id: turn.speaker,
+                speaker_label,
+                confidence: 1.0,
+                is_registered,
+                registered_speaker_id: None,
+            }
+        })
+        .collect();
+
+    crate::audio::retranscription::apply_speakers_to_saved_transcript(&db, &recording_id, &speaker_segments)
+        .map_err(|e| e.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_rttm() {
+        let content = "\
+;; comment line
+SPEAKER meeting 1 12.50 3.25 <NA> <NA> bob <NA> <NA>
+SPKR-INFO meeting 1 <NA> <NA> <NA> unknown bob <NA> <NA>
+
+SPEAKER meeting 1 0.00 10.00 <NA> <NA> alice <NA> <NA>
+";
+        let segments = parse_rttm(content).unwrap();
+        assert_eq!(segments.len(), 2);
+        assert_eq!(segments[0], RttmSegment { start_time: 0.0, end_time: 10.0, speaker: "alice".to_string() });
+        assert_eq!(segments[1].speaker, "bob");
+        assert!((segments[1].end_time - 15.75).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_rttm_roundtrip() {
+        let segments = vec![
+            RttmSegment { start_time: 0.0, end_time: 2.5, speaker: "speaker_0".to_string() },
+            RttmSegment { start_time: 2.5, end_time: 4.0, speaker: "Jane Doe".to_string() },
+        ];
+        let rttm = to_rttm("rec 1", &segments);
+        assert!(rttm.starts_with("SPEAKER rec_1 1 0.000 2.500 <NA> <NA> speaker_0 <NA> <NA>\n"));
+
+        let parsed = parse_rttm(&rttm).unwrap();
+        assert_eq!(parsed.len(), 2);
+        assert_eq!(parsed[1].speaker, "Jane_Doe");
+        assert!((parsed[1].end_time - 4.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_parse_rttm_rejects_bad_lines() {
+        assert!(parse_rttm("SPEAKER meeting 1 abc 1.0 <NA> <NA> a <NA> <NA>").is_err());
+        assert!(parse_rttm("SPEAKER meeting 1 1.0").is_err());
+    }
+
+    #[test]
+    fn test_parse_rttm_caps_segment_count() {
+        let line = "SPEAKER meeting 1 0.0 1.0 <NA> <NA> a <NA> <NA>\n";
+        assert_eq!(parse_rttm(&line.repeat(MAX_RTTM_SEGMENTS)).unwrap().len(), MAX_RTTM_SEGMENTS);
+        assert!(parse_rttm(&line.repeat(MAX_RTTM_SEGMENTS + 1)).is_err());
+    }
+}
@@ -0,0 +1,127 @@
+// Post-diarization smoothing
+// Removes short speaker segments that flicker between labels by absorbing
+// them into the surrounding dominant speaker
+
+use super::engine::SpeakerSegment;
+
+/// Default minimum speaker segment duration in seconds
+pub const DEFAULT_MIN_SPEAKER_SEGMENT_SECONDS: f64 = 0.5;
+
+fn duration(segment: &SpeakerSegment) -> f64 {
+    segment.end_time - segment.start_time
+}
+
+/// Give `segment` the speaker of `source`, keeping its own timing
+fn relabel(segment: &mut SpeakerSegment, source: &SpeakerSegment) {
+    segment.speaker_id = source.speaker_id.clone();
+    segment.speaker_label = source.speaker_label.clone();
+    segment.is_registered = source.is_registered;
+    segment.registered_speaker_id = source.registered_speaker_id.clone();
+    segment.confidence = segment.confidence.min(source.confidence);
+}
+
+/// Relabel segments shorter than `min_duration` seconds to the surrounding
+/// speaker, then merge consecutive segments of the same speaker.
+///
+/// A short segment takes the speaker on both sides when they agree, otherwise
+/// the speaker of the longer neighbouring segment. A `min_duration` of 0
+/// leaves the segments unchanged.
+pub fn smooth_speaker_segments(mut segments: Vec<SpeakerSegment>, min_duration: f64) -> Vec<SpeakerSegment> {
+    if min_duration <= 0.0 || segments.len() < 2 {
+        return segments;
+    }
+
+    segments.sort_by(|a, b| a.start_time.total_cmp(&b.start_time));
+
+    for i in 0..segments.len() {
+        if duration(&segments[i]) >= min_duration {
+            continue;
+        }
+
+        let prev = i.checked_sub(1).map(|p| &segments[p]);
+        let next = segments.get(i + 1);
+        let source = match (prev, next) {
+            (Some(p), Some(n)) if p.speaker_id == n.speaker_id => p,
+            (Some(p), Some(n)) => if duration(n) > duration(p) { n } else { p },
+            (Some(p), None) => p,
+            (None, Some(n)) => n,
+            (None, None) => continue,
+        };
+
+        if source.speaker_id != segments[i].speaker_id {
+            let source = source.clone();
+            relabel(&mut segments[i], &source);
+        }
+    }
+
+    let mut merged: Vec<SpeakerSegment> = Vec::with_capacity(segments.len());
+    for segment in segments {
+        if let Some(last) = merged.last_mut() {
+            if last.speaker_id == segment.speaker_id {
+                last.end_time = last.end_time.max(segment.end_time);
+                last.confidence = last.confidence.min(segment.confidence);
+                continue;
+            }
+        }
+        merged.push(segment);
+    }
+
+    merged
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn segment(start: f64, end: f64, speaker: usize) -> SpeakerSegment {
+        SpeakerSegment {
+            start_time: start,
+            end_time: end,
+            speaker_id: format!("speaker_{}", speaker),
+            speaker_label: format!("Speaker {}", speaker + 1),
+            confidence: 0.8,
+            is_registered: false,
+            registered_speaker_id: None,
+        }
+    }
+
+    fn speakers(segments: &[SpeakerSegment]) -> Vec<(f64, f64, &str)> {
+        segments.iter().map(|s| (s.start_time, s.end_time, s.speaker_id.as_str())).collect()
+    }
+
+    #[test]
+    fn test_flicker_is_absorbed() {
+        // A long turn from speaker 0 interrupted by short blips of 1 and 2,
+        // then a real turn from speaker 1
+        let segments = vec![
+            segment(0.0, 3.0, 0),
+            segment(3.0, 3.2, 1),
+            segment(3.2, 5.0, 0),
+            segment(5.0, 5.3, 2),
+            segment(5.3, 5.4, 0),
+            segment(5.4, 8.0, 1),
+        ];
+
+        let smoothed = smooth_speaker_segments(segments, 0.5);
+        // The last short piece of speaker 0 is next to the long turn of 1, so it joins 1
+        assert_eq!(speakers(&smoothed), vec![(0.0, 5.3, "speaker_0"), (5.3, 8.0, "speaker_1")]);
+    }
+
+    #[test]
+    fn test_short_segment_between_different_speakers_joins_longer_one() {
+        let segments = vec![
+            segment(0.0, 1.0, 0),
+            segment(1.0, 1.3, 2),
+            segment(1.3, 6.0, 1),
+        ];
+
+        let smoothed = smooth_speaker_segments(segments, 0.5);
+        assert_eq!(speakers(&smoothed), vec![(0.0, 1.0, "speaker_0"), (1.0, 6.0, "speaker_1")]);
+    }
+
+    #[test]
+    fn test_zero_minimum_keeps_segments() {
+        let segments = vec![segment(0.0, 1.0, 0), segment(1.0, 1.1, 1), segment(1.1, 2.0, 0)];
+        assert_eq!(smooth_speaker_segments(segments, 0.0).len(), 3);
+    }
+}
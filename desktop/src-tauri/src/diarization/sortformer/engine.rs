@@ -52,10 +52,21 @@ impl Sortformer {
 
     /// Main diarization entry point
     pub fn diarize(
+        &mut self,
+        audio: Vec<f32>,
+        sample_rate: u32,
+        channels: u16,
+    ) -> Result<Vec<SpeakerSegment>> {
+        self.diarize_with_progress(audio, sample_rate, channels, &mut |_| {})
+    }
+
+    /// Diarize, calling `on_progress` with the fraction (0.0-1.0) of chunks processed
+    pub fn diarize_with_progress(
         &mut self,
         mut audio: Vec<f32>,
         sample_rate: u32,
         channels: u16,
+        on_progress: &mut dyn FnMut(f64),
     ) -> Result<Vec<SpeakerSegment>> {
         if sample_rate != SAMPLE_RATE as u32 {
             return Err(anyhow!(
@@ -102,6 +113,7 @@ impl Sortformer {
                 current_len,
             )?;
             all_chunk_preds.push(chunk_preds);
+            on_progress((chunk_idx + 1) as f64 / num_chunks as f64);
         }
 
         let full_preds = concat_predictions(&all_chunk_preds);
@@ -48,6 +48,17 @@ impl SortformerEngine {
         self.sortformer.diarize(samples, sample_rate, 1)
     }
 
+    /// Run diarization, reporting the fraction (0.0-1.0) of audio processed
+    pub fn diarize_with_progress(
+        &mut self,
+        samples: Vec<f32>,
+        sample_rate: u32,
+        on_progress: &mut dyn FnMut(f64),
+    ) -> Result<Vec<SpeakerSegment>> {
+        debug!("Running Sortformer diarization on {} samples", samples.len());
+        self.sortformer.diarize_with_progress(samples, sample_rate, 1, on_progress)
+    }
+
     /// Reset the streaming state
     pub fn reset(&mut self) {
         self.sortformer.reset_state();
@@ -88,13 +99,7 @@ pub async fn init_sortformer(model_path: String) -> Result<(), String> {
 /// Check if Sortformer model is available
 #[tauri::command]
 pub async fn is_sortformer_model_available(app: tauri::AppHandle) -> Result<bool, String> {
-    use tauri::Manager;
-
-    let models_dir = app
-        .path()
-        .app_data_dir()
-        .map_err(|e| format!("Failed to get app data dir: {}", e))?
-        .join("models");
+    let models_dir = crate::model_directories::models_dir(&app, crate::model_directories::ModelKind::Diarization)?;
 
     Ok(SortformerEngine::is_model_available(&models_dir))
 }
@@ -102,16 +107,12 @@ pub async fn is_sortformer_model_available(app: tauri::AppHandle) -> Result<bool
 /// Download Sortformer model
 #[tauri::command]
 pub async fn download_sortformer_model(app: tauri::AppHandle) -> Result<(), String> {
-    use tauri::{Manager, Emitter};
+    use tauri::Emitter;
     use tokio::fs;
     use tokio::io::AsyncWriteExt;
     use futures_util::StreamExt;
 
-    let models_dir = app
-        .path()
-        .app_data_dir()
-        .map_err(|e| format!("Failed to get app data dir: {}", e))?
-        .join("models");
+    let models_dir = crate::model_directories::models_dir(&app, crate::model_directories::ModelKind::Diarization)?;
 
     if !models_dir.exists() {
         fs::create_dir_all(&models_dir)
@@ -145,6 +146,11 @@ pub async fn download_sortformer_model(app: tauri::AppHandle) -> Result<(), Stri
         total_size as f64 / (1024.0 * 1024.0)
     );
 
+    let tracker = crate::downloads::DownloadHandle::start(
+        crate::downloads::DownloadKind::Diarization,
+        SORTFORMER_MODEL_NAME,
+    );
+
     let temp_path = model_path.with_extension("tmp");
     let mut file = fs::File::create(&temp_path)
         .await
@@ -161,6 +167,7 @@ pub async fn download_sortformer_model(app: tauri::AppHandle) -> Result<(), Stri
             .map_err(|e| format!("Failed to write chunk: {}", e))?;
 
         downloaded += chunk.len() as u64;
+        tracker.update(downloaded, total_size);
 
         if total_size > 0 {
             let progress = ((downloaded as f64 / total_size as f64) * 100.0) as u8;
@@ -233,13 +240,7 @@ pub async fn sortformer_reset() -> Result<(), String> {
 /// Get Sortformer model info
 #[tauri::command]
 pub async fn get_sortformer_model_info(app: tauri::AppHandle) -> Result<serde_json::Value, String> {
-    use tauri::Manager;
-
-    let models_dir = app
-        .path()
-        .app_data_dir()
-        .map_err(|e| format!("Failed to get app data dir: {}", e))?
-        .join("models");
+    let models_dir = crate::model_directories::models_dir(&app, crate::model_directories::ModelKind::Diarization)?;
 
     let model_path = models_dir.join(SORTFORMER_MODEL_NAME);
     let available = model_path.exists();
@@ -0,0 +1,139 @@
+//! Registry of in-flight model downloads
+//!
+//! The Whisper, LLM and diarization downloaders each report their progress
+//! here while a download runs, so `get_all_download_progress` can return every
+//! active download from one place. Entries are removed when the download's
+//! `DownloadHandle` is dropped, whether it finished, failed or was cancelled.
+
+use std::collections::HashMap;
+use std::sync::Mutex;
+
+use once_cell::sync::Lazy;
+use serde::Serialize;
+
+/// Which model manager a download belongs to
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum DownloadKind {
+    Whisper,
+    Llm,
+    Diarization,
+}
+
+impl DownloadKind {
+    fn as_str(self) -> &'static str {
+        match self {
+            Self::Whisper => "whisper",
+            Self::Llm => "llm",
+            Self::Diarization => "diarization",
+        }
+    }
+}
+
+/// Progress of one active download
+#[derive(Debug, Clone, Serialize)]
+pub struct DownloadEntry {
+    /// "<kind>:<model>", unique across managers
+    pub id: String,
+    pub kind: DownloadKind,
+    pub model: String,
+    pub downloaded_bytes: u64,
+    /// None if the server didn't send a content length
+    pub total_bytes: Option<u64>,
+    pub percent: Option<f32>,
+    /// "starting", "downloading" or "verifying"
+    pub status: String,
+    pub started_at: String,
+}
+
+static ACTIVE_DOWNLOADS: Lazy<Mutex<HashMap<String, DownloadEntry>>> =
+    Lazy::new(|| Mutex::new(HashMap::new()));
+
+/// Handle for reporting a download's progress; removes the entry when dropped
+pub struct DownloadHandle {
+    id: String,
+}
+
+impl DownloadHandle {
+    /// Register a new download
+    pub fn start(kind: DownloadKind, model: &str) -> Self {
+        let id = format!("{}:{}", kind.as_str(), model);
+        let entry = DownloadEntry {
+            id: id.clone(),
+            kind,
+            model: model.to_string(),
+            downloaded_bytes: 0,
+            total_bytes: None,
+            percent: None,
+            status: "starting".to_string(),
+            started_at: chrono::Utc::now().to_rfc3339(),
+        };
+        ACTIVE_DOWNLOADS.lock().unwrap().insert(id.clone(), entry);
+        Self { id }
+    }
+
+    /// Record bytes received so far (total of 0 means unknown)
+    pub fn update(&self, downloaded_bytes: u64, total_bytes: u64) {
+        self.update_with_status(downloaded_bytes, total_bytes, "downloading");
+    }
+
+    pub fn update_with_status(&self, downloaded_bytes: u64, total_bytes: u64, status: &str) {
+        if let Some(entry) = ACTIVE_DOWNLOADS.lock().unwrap().get_mut(&self.id) {
+            let total = (total_bytes > 0).then_some(total_bytes);
+            entry.downloaded_bytes = downloaded_bytes;
+            entry.total_bytes = total;
+            entry.percent = total.map(|t| (downloaded_bytes as f32 / t as f32 * 100.0).min(100.0));
+            entry.status = status.to_string();
+        }
+    }
+}
+
+impl Drop for DownloadHandle {
+    fn drop(&mut self) {
+        ACTIVE_DOWNLOADS.lock().unwrap().remove(&self.id);
+    }
+}
+
+/// All downloads currently in progress, oldest first
+pub fn active_downloads() -> Vec<DownloadEntry> {
+    let mut downloads: Vec<DownloadEntry> = ACTIVE_DOWNLOADS.lock().unwrap().values().cloned().collect();
+    downloads.sort_by(|a, b| a.started_at.cmp(&b.started_at).then_with(|| a.id.cmp(&b.id)));
+    downloads
+}
+
+/// Progress of every active Whisper, LLM and diarization download
+#[tauri::command]
+pub async fn get_all_download_progress() -> Result<Vec<DownloadEntry>, String> {
+    Ok(active_downloads())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_handle_tracks_progress_until_dropped() {
+        let handle = DownloadHandle::start(DownloadKind::Llm, "test-tracker-model");
+        handle.update(50, 200);
+
+        let entry = active_downloads()
+            .into_iter()
+            .find(|d| d.id == "llm:test-tracker-model")
+            .unwrap();
+        assert_eq!(entry.downloaded_bytes, 50);
+        assert_eq!(entry.total_bytes, Some(200));
+        assert_eq!(entry.percent, Some(25.0));
+        assert_eq!(entry.status, "downloading");
+
+        handle.update(10, 0);
+        let entry = active_downloads()
+            .into_iter()
+            .find(|d| d.id == "llm:test-tracker-model")
+            .unwrap();
+        assert_eq!(entry.total_bytes, None);
+        assert_eq!(entry.percent, None);
+
+        drop(handle);
+        assert!(active_downloads().iter().all(|d| d.id != "llm:test-tracker-model"));
+    }
+}
@@ -0,0 +1,336 @@
+//! Recording export
+//!
+//! Writes recordings into a destination folder, one sub-folder per recording:
+//! - `transcript`: `transcript.md` (readable transcript) and `recording.json`
+//!   (recording, categories, tags, transcript segments and chat sessions)
+//! - `archive`: the above plus a copy of every file in the meeting folder,
+//!   including the recorder's own `metadata.json`
+//!
+//! `export_recordings_bulk` exports every recording matching a set of search
+//! filters, emitting `bulk-export-progress` after each one. It can export a
+//! derived transcript version (e.g. `redacted`) instead of the original.
+
+use std::fmt::Write as _;
+use std::path::{Path, PathBuf};
+
+use anyhow::{anyhow, Context, Result};
+use log::{info, warn};
+use serde::{Deserialize, Serialize};
+use tauri::{AppHandle, Emitter, Runtime};
+
+use crate::audio::file_io::sanitize_filename;
+use crate::database::{RecordingFull, SearchFilters, TranscriptVersion, TRANSCRIPT_VERSION_REDACTED};
+use crate::state::AppState;
+
+const TRANSCRIPT_FILE_NAME: &str = "transcript.md";
+/// Not `metadata.json`, which the recorder writes into the meeting folder
+const METADATA_FILE_NAME: &str = "recording.json";
+
+/// What to write for each recording
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum ExportFormat {
+    /// Transcript and metadata only
+    Transcript,
+    /// Transcript, metadata and the meeting folder (audio, summary, ...)
+    Archive,
+}
+
+/// A recording that could not be exported
+#[derive(Debug, Clone, Serialize)]
+pub struct BulkExportFailure {
+    pub recording_id: String,
+    pub title: String,
+    pub error: String,
+}
+
+/// Outcome of a bulk export
+#[derive(Debug, Clone, Serialize)]
+pub struct BulkExportSummary {
+    pub total: usize,
+    /// Folders written, one per exported recording
+    pub exported: Vec<String>,
+    pub failed: Vec<BulkExportFailure>,
+}
+
+fn format_timestamp(seconds: f64) -> String {
+    let total = seconds.max(0.0) as u64;
+    let (hours, minutes, secs) = (total / 3600, (total % 3600) / 60, total % 60);
+    if hours > 0 {
+        format!("{:02}:{:02}:{:02}", hours, minutes, secs)
+    } else {
+        format!("{:02}:{:02}", minutes, secs)
+    }
+}
+
+/// Readable markdown transcript with a metadata header
+pub fn format_transcript_markdown(full: &RecordingFull) -> String {
+    let recording = &full.recording;
+    let mut out = format!("# {}\n\n", recording.title);
+
+    let _ = writeln!(out, "- Date: {}", recording.created_at);
+    if let Some(duration) = recording.duration_seconds {
+        let _ = writeln!(out, "- Duration: {}", format_timestamp(duration));
+    }
+    if !full.categories.is_empty() {
+        let names: Vec<&str> = full.categories.iter().map(|c| c.name.as_str()).collect();
+        let _ = writeln!(out, "- Categories: {}", names.join(", "));
+    }
+    if !full.tags.is_empty() {
+        let names: Vec<&str> = full.tags.iter().map(|t| t.name.as_str()).collect();
+        let _ = writeln!(out, "- Tags: {}", names.join(", "));
+    }
+    out.push_str("\n## Transcript\n\n");
+
+    if full.transcript_segments.is_empty() {
+        out.push_str("_No transcript._\n");
+    }
+    for segment in &full.transcript_segments {
+        let speaker = segment
+            .speaker_label
+            .as_deref()
+            .map(|label| format!(" {}:", label))
+            .unwrap_or_default();
+        let _ = writeln!(
+            out,
+            "**[{}]{}** {}\n",
+            format_timestamp(segment.audio_start_time),
+            speaker,
+            segment.text.trim()
+        );
+    }
+
+    out
+}
+
+/// Folder name for a recording: its title plus the start of its ID, so
+/// recordings with the same title don't overwrite each other
+fn export_folder_name(full: &RecordingFull) -> String {
+    let title = sanitize_filename(&full.recording.title);
+    let title = if title.is_empty() { "Recording".to_string() } else { title };
+    let id_prefix: String = full.recording.id.chars().take(8).collect();
+    format!("{}_{}", title, id_prefix)
+}
+
+fn copy_dir_recursive(source: &Path, dest: &Path) -> Result<()> {
+    std::fs::create_dir_all(dest)
+        .with_context(|| format!("Failed to create {}", dest.display()))?;
+    for entry in std::fs::read_dir(source)
+        .with_context(|| format!("Failed to read {}", source.display()))?
+    {
+        let entry = entry?;
+        let target = dest.join(entry.file_name());
+        if entry.file_type()?.is_dir() {
+            copy_dir_recursive(&entry.path(), &target)?;
+        } else {
+            std::fs::copy(entry.path(), &target)
+                .with_context(|| format!("Failed to copy {}", entry.path().display()))?;
+        }
+    }
+    Ok(())
+}
+
+/// Export one recording into a new sub-folder of `dest_dir`; returns that folder
+pub fn export_recording(full: &RecordingFull, format: ExportFormat, dest_dir: &Path) -> Result<PathBuf> {
+    let folder = dest_dir.join(export_folder_name(full));
+
+    if format == ExportFormat::Archive {
+        let source = full
+            .recording
+            .folder_path()
+            .filter(|p| p.is_dir())
+            .ok_or_else(|| anyhow!("Meeting folder not found"))?;
+        copy_dir_recursive(&source, &folder)?;
+    } else {
+        std::fs::create_dir_all(&folder)
+            .with_context(|| format!("Failed to create {}", folder.display()))?;
+    }
+
+    std::fs::write(folder.join(TRANSCRIPT_FILE_NAME), format_transcript_markdown(full))
+        .context("Failed to write transcript")?;
+    std::fs::write(folder.join(METADATA_FILE_NAME), serde_json::to_string_pretty(full)?)
+        .context("Failed to write metadata")?;
+
+    Ok(folder)
+}
+
+/// Replace a recording's transcript with a derived version for export.
+/// Chat sessions are dropped from redacted exports since they can quote the
+/// original transcript.
+pub fn apply_transcript_version(full: &mut RecordingFull, version: TranscriptVersion) {
+    if version.kind == TRANSCRIPT_VERSION_REDACTED {
+        full.chat_sessions.clear();
+    }
+    full.transcript_segments = version.segments;
+}
+
+/// Export every recording matching `filter` into `dest_dir`.
+/// With `transcript_version` (a version kind such as "redacted"), each
+/// recording's newest version of that kind is exported instead of the original
+/// transcript; recordings without one fail rather than falling back.
+/// Failures are collected in the summary rather than stopping the export.
+#[tauri::command]
+pub async fn export_recordings_bulk<R: Runtime>(
+    app: AppHandle<R>,
+    state: tauri::State<'_, AppState>,
+    filter: SearchFilters,
+    format: ExportFormat,
+    dest_dir: String,
+    transcript_version: Option<String>,
+) -> Result<BulkExportSummary, String> {
+    if transcript_version.as_deref() == Some(TRANSCRIPT_VERSION_REDACTED) && format == ExportFormat::Archive {
+        return Err("Archives include the original transcript files; export redacted transcripts with the transcript format".to_string());
+    }
+
+    let dest = PathBuf::from(&dest_dir);
+    std::fs::create_dir_all(&dest)
+        .map_err(|e| format!("Failed to create {}: {}", dest_dir, e))?;
+
+    let recordings: Vec<_> = state
+        .db()
+        .await
+        .search_recordings("", &filter)
+        .map_err(|e| e.to_string())?
+        .into_iter()
+        .map(|result| result.recording)
+        .collect();
+
+    let total = recordings.len();
+    info!("📦 Exporting {} recordings to {} ({:?})", total, dest_dir, format);
+
+    let mut summary = BulkExportSummary { total, exported: Vec::new(), failed: Vec::new() };
+
+    for (index, recording) in recordings.into_iter().enumerate() {
+        let full = match &transcript_version {
+            Some(kind) => load_with_transcript_version(&state, &recording.id, kind).await,
+            None => state.db().await.get_recording_full(&recording.id).map_err(|e| e.to_string()),
+        };
+        let result = match full {
+            Ok(Some(full)) => {
+                let dest = dest.clone();
+                tokio::task::spawn_blocking(move || export_recording(&full, format, &dest))
+                    .await
+                    .map_err(|e| e.to_string())
+                    .and_then(|r| r.map_err(|e| format!("{:#}", e)))
+            }
+            Ok(None) => Err("Recording not found".to_string()),
+            Err(e) => Err(e),
+        };
+
+        match result {
+            Ok(folder) => summary.exported.push(folder.to_string_lossy().to_string()),
+            Err(error) => {
+                warn!("Export of recording {} failed: {}", recording.id, error);
+                summary.failed.push(BulkExportFailure {
+                    recording_id: recording.id.clone(),
+                    title: recording.title.clone(),
+                    error,
+                });
+            }
+        }
+
+        let _ = app.emit("bulk-export-progress", serde_json::json!({
+            "current": index + 1,
+            "total": total,
+            "recordingId": recording.id,
+            "title": recording.title,
+            "failed": summary.failed.len(),
+        }));
+    }
+
+    info!("📦 Bulk export finished: {} exported, {} failed", summary.exported.len(), summary.failed.len());
+    Ok(summary)
+}
+
+/// A recording with its newest transcript version of `kind` in place of the original
+async fn load_with_transcript_version(
+    state: &AppState,
+    recording_id: &str,
+    kind: &str,
+) -> Result<Option<RecordingFull>, String> {
+    let db = state.db().await;
+    let Some(mut full) = db.get_recording_full(recording_id).map_err(|e| e.to_string())? else {
+        return Ok(None);
+    };
+    let version = db
+        .get_latest_transcript_version(recording_id, kind)
+        .map_err(|e| e.to_string())?
+        .ok_or_else(|| format!("No {} transcript version", kind))?;
+    apply_transcript_version(&mut full, version);
+    Ok(Some(full))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::database::{Recording, TranscriptSegment};
+
+    fn recording_full(title: &str) -> RecordingFull {
+        let mut recording = Recording::new("0123456789abcdef".to_string(), title.to_string());
+        recording.duration_seconds = Some(3725.0);
+        RecordingFull {
+            recording,
+            categories: Vec::new(),
+            tags: Vec::new(),
+            transcript_segments: vec![TranscriptSegment::test_segment(65.0, 67.0, " Let's get started. ")
+                .with_speaker("speaker_0", "Alice")],
+            chat_sessions: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn test_format_transcript_markdown() {
+        let markdown = format_transcript_markdown(&recording_full("Weekly sync"));
+        assert!(markdown.starts_with("# Weekly sync\n"));
+        assert!(markdown.contains("- Duration: 01:02:05\n"));
+        assert!(markdown.contains("**[01:05] Alice:** Let's get started.\n"));
+    }
+
+    #[test]
+    fn test_export_transcript_format() {
+        let dir = tempfile::tempdir().unwrap();
+        let folder = export_recording(&recording_full("Q3: plan/review"), ExportFormat::Transcript, dir.path()).unwrap();
+
+        assert_eq!(folder.file_name().unwrap(), "Q3_ plan_review_01234567");
+        assert!(folder.join(TRANSCRIPT_FILE_NAME).is_file());
+        let metadata = std::fs::read_to_string(folder.join(METADATA_FILE_NAME)).unwrap();
+        assert!(metadata.contains("\"transcript_segments\""));
+
+        // Archives need the meeting folder
+        assert!(export_recording(&recording_full("No folder"), ExportFormat::Archive, dir.path()).is_err());
+    }
+
+    #[test]
+    fn test_export_archive_keeps_recorder_metadata() {
+        let meeting = tempfile::tempdir().unwrap();
+        std::fs::write(meeting.path().join("metadata.json"), "{\"status\":\"completed\"}").unwrap();
+        let mut full = recording_full("Weekly sync");
+        full.recording.meeting_folder_path = Some(meeting.path().to_string_lossy().to_string());
+
+        let dir = tempfile::tempdir().unwrap();
+        let folder = export_recording(&full, ExportFormat::Archive, dir.path()).unwrap();
+        assert_eq!(
+            std::fs::read_to_string(folder.join("metadata.json")).unwrap(),
+            "{\"status\":\"completed\"}"
+        );
+        assert!(folder.join(METADATA_FILE_NAME).is_file());
+    }
+
+    #[test]
+    fn test_apply_redacted_transcript_version() {
+        let mut full = recording_full("Weekly sync");
+        let mut segments = full.transcript_segments.clone();
+        segments[0].text = "Call [NAME] at [PHONE].".to_string();
+        apply_transcript_version(&mut full, TranscriptVersion {
+            id: "tv_1".to_string(),
+            recording_id: full.recording.id.clone(),
+            kind: TRANSCRIPT_VERSION_REDACTED.to_string(),
+            segments,
+            model_id: None,
+            created_at: "2024-01-01T00:00:00Z".to_string(),
+        });
+
+        assert!(format_transcript_markdown(&full).contains("Call [NAME] at [PHONE]."));
+        assert!(full.chat_sessions.is_empty());
+    }
+}
@@ -0,0 +1,239 @@
+//! Log file output
+//!
+//! env_logger writes every record through `LogWriter`, which always copies it
+//! to stderr and, when the `file_logging_enabled` setting is on, appends it to
+//! `logs/meeting-local.log` in the app data dir. The file is rotated when it
+//! grows past `MAX_LOG_FILE_BYTES` or the day changes, keeping the last
+//! `MAX_ROTATED_FILES` files as `meeting-local.1.log` (newest) and up.
+
+use std::fs::{File, OpenOptions};
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+
+use chrono::{Local, NaiveDate};
+use serde::Serialize;
+use tauri::{AppHandle, Manager, Runtime};
+
+use crate::state::AppState;
+
+/// Settings key enabling the log file
+pub const FILE_LOGGING_SETTING: &str = "file_logging_enabled";
+
+const LOG_DIR_NAME: &str = "logs";
+const LOG_FILE_STEM: &str = "meeting-local";
+const MAX_LOG_FILE_BYTES: u64 = 5 * 1024 * 1024;
+const MAX_ROTATED_FILES: usize = 5;
+
+/// Log file being written, if file logging is enabled
+static LOG_FILE: Mutex<Option<RotatingFile>> = Mutex::new(None);
+
+/// Append-only log file that rotates by size and date
+struct RotatingFile {
+    dir: PathBuf,
+    /// None only while rotating, so the file is closed before it is renamed
+    file: Option<File>,
+    size: u64,
+    opened_on: NaiveDate,
+}
+
+fn active_log_path(dir: &Path) -> PathBuf {
+    dir.join(format!("{}.log", LOG_FILE_STEM))
+}
+
+fn rotated_log_path(dir: &Path, index: usize) -> PathBuf {
+    dir.join(format!("{}.{}.log", LOG_FILE_STEM, index))
+}
+
+/// Shift `meeting-local.N.log` up by one (dropping the oldest) and move the
+/// active file to `meeting-local.1.log`
+fn rotate_files(dir: &Path) -> io::Result<()> {
+    let _ = std::fs::remove_file(rotated_log_path(dir, MAX_ROTATED_FILES));
+    for index in (1..MAX_ROTATED_FILES).rev() {
+        let from = rotated_log_path(dir, index);
+        if from.exists() {
+            std::fs::rename(&from, rotated_log_path(dir, index + 1))?;
+        }
+    }
+    let active = active_log_path(dir);
+    if active.exists() {
+        std::fs::rename(&active, rotated_log_path(dir, 1))?;
+    }
+    Ok(())
+}
+
+impl RotatingFile {
+    fn open(dir: &Path) -> io::Result<Self> {
+        std::fs::create_dir_all(dir)?;
+        let path = active_log_path(dir);
+
+        // A file left over from an earlier day starts the new day rotated
+        let today = Local::now().date_naive();
+        if let Ok(modified) = path.metadata().and_then(|m| m.modified()) {
+            if chrono::DateTime::<Local>::from(modified).date_naive() != today {
+                rotate_files(dir)?;
+            }
+        }
+
+        let file = OpenOptions::new().create(true).append(true).open(&path)?;
+        let size = file.metadata()?.len();
+        Ok(Self { dir: dir.to_path_buf(), file: Some(file), size, opened_on: today })
+    }
+
+    fn write_record(&mut self, record: &[u8]) -> io::Result<()> {
+        let today = Local::now().date_naive();
+        if today != self.opened_on || self.size + record.len() as u64 > MAX_LOG_FILE_BYTES {
+            self.file = None;
+            rotate_files(&self.dir)?;
+            self.file = Some(OpenOptions::new().create(true).append(true).open(active_log_path(&self.dir))?);
+            self.size = 0;
+            self.opened_on = today;
+        }
+
+        let file = self.file.as_mut().ok_or_else(|| io::Error::other("Log file is closed"))?;
+        file.write_all(record)?;
+        self.size += record.len() as u64;
+        Ok(())
+    }
+}
+
+/// env_logger target: stderr plus the log file when enabled
+pub struct LogWriter;
+
+impl Write for LogWriter {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        io::stderr().write_all(buf)?;
+        if let Ok(mut guard) = LOG_FILE.lock() {
+            if let Some(file) = guard.as_mut() {
+                // A failing log file must not break logging to stderr
+                let _ = file.write_record(buf);
+            }
+        }
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        if let Ok(mut guard) = LOG_FILE.lock() {
+            if let Some(file) = guard.as_mut().and_then(|f| f.file.as_mut()) {
+                let _ = file.flush();
+            }
+        }
+        io::stderr().flush()
+    }
+}
+
+/// Directory holding the log files
+pub fn log_dir<R: Runtime>(app: &AppHandle<R>) -> Result<PathBuf, String> {
+    app.path()
+        .app_data_dir()
+        .map(|dir| dir.join(LOG_DIR_NAME))
+        .map_err(|e| format!("Failed to get app data dir: {}", e))
+}
+
+/// Existing log files, newest first
+pub fn log_files<R: Runtime>(app: &AppHandle<R>) -> Result<Vec<PathBuf>, String> {
+    let dir = log_dir(app)?;
+    Ok(std::iter::once(active_log_path(&dir))
+        .chain((1..=MAX_ROTATED_FILES).map(|index| rotated_log_path(&dir, index)))
+        .filter(|path| path.is_file())
+        .collect())
+}
+
+/// Start or stop writing logs to the rotating file in `dir`
+pub fn set_file_logging(dir: Option<&Path>) -> io::Result<()> {
+    let file = dir.map(RotatingFile::open).transpose()?;
+    *LOG_FILE.lock().unwrap() = file;
+    Ok(())
+}
+
+/// Enable file logging in the app data dir, logging (not returning) failures
+pub fn enable_file_logging<R: Runtime>(app: &AppHandle<R>) {
+    let dir = match log_dir(app) {
+        Ok(dir) => dir,
+        Err(e) => {
+            log::error!("{}", e);
+            return;
+        }
+    };
+    match set_file_logging(Some(&dir)) {
+        Ok(()) => log::info!("📝 Writing logs to {}", active_log_path(&dir).display()),
+        Err(e) => log::error!("Failed to open log file: {}", e),
+    }
+}
+
+/// Log file location and whether logs are being written to it
+#[derive(Debug, Clone, Serialize)]
+pub struct LogFileInfo {
+    pub path: String,
+    pub enabled: bool,
+}
+
+/// Path of the current log file, for attaching to bug reports
+#[tauri::command]
+pub fn get_log_file_path<R: Runtime>(app: AppHandle<R>) -> Result<LogFileInfo, String> {
+    let path = active_log_path(&log_dir(&app)?);
+    Ok(LogFileInfo {
+        path: path.to_string_lossy().to_string(),
+        enabled: LOG_FILE.lock().unwrap().is_some(),
+    })
+}
+
+/// Enable or disable writing logs to the rotating file; applies immediately
+#[tauri::command]
+pub async fn set_file_logging_enabled<R: Runtime>(
+    app: AppHandle<R>,
+    state: tauri::State<'_, AppState>,
+    enabled: bool,
+) -> Result<(), String> {
+    if enabled {
+        let dir = log_dir(&app)?;
+        set_file_logging(Some(&dir)).map_err(|e| format!("Failed to open log file: {}", e))?;
+    } else {
+        set_file_logging(None).map_err(|e| e.to_string())?;
+    }
+
+    state
+        .db()
+        .await
+        .set_bool_setting(FILE_LOGGING_SETTING, enabled)
+        .map_err(|e| e.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_rotate_files_shifts_and_drops_oldest() {
+        let dir = tempfile::tempdir().unwrap();
+        let dir = dir.path();
+        std::fs::write(active_log_path(dir), "current").unwrap();
+        for index in 1..=MAX_ROTATED_FILES {
+            std::fs::write(rotated_log_path(dir, index), format!("old {}", index)).unwrap();
+        }
+
+        rotate_files(dir).unwrap();
+
+        assert!(!active_log_path(dir).exists());
+        assert_eq!(std::fs::read_to_string(rotated_log_path(dir, 1)).unwrap(), "current");
+        assert_eq!(std::fs::read_to_string(rotated_log_path(dir, 2)).unwrap(), "old 1");
+        assert_eq!(
+            std::fs::read_to_string(rotated_log_path(dir, MAX_ROTATED_FILES)).unwrap(),
+            format!("old {}", MAX_ROTATED_FILES - 1)
+        );
+        assert!(!rotated_log_path(dir, MAX_ROTATED_FILES + 1).exists());
+    }
+
+    #[test]
+    fn test_rotating_file_rotates_past_size_limit() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut file = RotatingFile::open(dir.path()).unwrap();
+        file.write_record(b"first\n").unwrap();
+
+        file.size = MAX_LOG_FILE_BYTES;
+        file.write_record(b"second\n").unwrap();
+
+        assert_eq!(std::fs::read_to_string(rotated_log_path(dir.path(), 1)).unwrap(), "first\n");
+        assert_eq!(std::fs::read_to_string(active_log_path(dir.path())).unwrap(), "second\n");
+    }
+}
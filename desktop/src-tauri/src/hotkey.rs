@@ -0,0 +1,201 @@
+//! Global hotkey for toggling recording (push-to-talk style quick notes)
+//!
+//! The shortcut is stored in the `recording_hotkey` setting and registered
+//! at startup. Pressing it stops an active recording or starts a new one
+//! with the last used devices, emitting `hotkey-recording-toggled`. Started
+//! recordings get a database row that is completed when they stop.
+
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Mutex;
+
+use log::{error, info, warn};
+use tauri::{AppHandle, Emitter, Manager, Runtime};
+use tauri_plugin_global_shortcut::{GlobalShortcutExt, Shortcut, ShortcutEvent, ShortcutState};
+
+use crate::globals::set_recording_flag;
+use crate::state::AppState;
+
+/// Settings key holding the accelerator string (e.g. "CommandOrControl+Shift+R")
+pub const RECORDING_HOTKEY_SETTING: &str = "recording_hotkey";
+
+/// Currently registered accelerator
+static CURRENT_HOTKEY: Mutex<Option<String>> = Mutex::new(None);
+
+/// Set while a start/stop triggered by the hotkey is running, to ignore repeat presses
+static TOGGLE_IN_PROGRESS: AtomicBool = AtomicBool::new(false);
+
+/// Global shortcut plugin handler - toggles recording on key press
+pub fn handle_shortcut<R: Runtime>(app: &AppHandle<R>, shortcut: &Shortcut, event: ShortcutEvent) {
+    if event.state() != ShortcutState::Pressed {
+        return;
+    }
+
+    let is_recording_hotkey = CURRENT_HOTKEY
+        .lock()
+        .unwrap()
+        .as_deref()
+        .and_then(|s| s.parse::<Shortcut>().ok())
+        .map(|registered| registered == *shortcut)
+        .unwrap_or(false);
+    if !is_recording_hotkey {
+        return;
+    }
+
+    if TOGGLE_IN_PROGRESS.swap(true, Ordering::SeqCst) {
+        info!("⌨️ Recording hotkey pressed while a toggle is in progress, ignoring");
+        return;
+    }
+
+    let app = app.clone();
+    tauri::async_runtime::spawn(async move {
+        toggle_recording(app).await;
+        TOGGLE_IN_PROGRESS.store(false, Ordering::SeqCst);
+    });
+}
+
+/// Stop the active recording, or start one with the last used devices
+async fn toggle_recording<R: Runtime>(app: AppHandle<R>) {
+    if crate::audio::recording::lifecycle::is_recording_async().await {
+        info!("⌨️ Recording hotkey: stopping recording");
+        let result = crate::audio::recording::lifecycle::stop_recording(
+            app.clone(),
+            crate::audio::recording::types::RecordingArgs {
+                save_path: String::new(),
+            },
+        )
+        .await;
+        set_recording_flag(false);
+        emit_toggled(&app, "stopped", None, result.err());
+        return;
+    }
+
+    info!("⌨️ Recording hotkey: starting recording");
+    let (mic, system) = {
+        let state = app.state::<AppState>();
+        let db = state.db().await;
+        (
+            db.get_setting("last_microphone").ok().flatten(),
+            db.get_setting("last_system_audio").ok().flatten(),
+        )
+    };
+
+    let result = crate::audio::recording::lifecycle::start_recording_with_devices_and_meeting(
+        app.clone(),
+        mic.clone(),
+        system.clone(),
+        None,
+    )
+    .await;
+    if let Err(e) = result {
+        emit_toggled(&app, "started", None, Some(e));
+        return;
+    }
+    set_recording_flag(true);
+
+    // No frontend owns this recording, so its database row is created here
+    // and completed when the recording stops
+    let recording_id = match crate::audio::recording::backend_recording::create_recording_row(
+        &app, None, mic, system,
+    )
+    .await
+    {
+        Ok(id) => Some(id),
+        Err(e) => {
+            warn!("⌨️ Recording started without a database row: {}", e);
+            None
+        }
+    };
+    emit_toggled(&app, "started", recording_id, None);
+}
+
+fn emit_toggled<R: Runtime>(
+    app: &AppHandle<R>,
+    action: &str,
+    recording_id: Option<String>,
+    error: Option<String>,
+) {
+    if let Some(ref e) = error {
+        error!("⌨️ Recording hotkey toggle ({}) failed: {}", action, e);
+    }
+    let _ = app.emit(
+        "hotkey-recording-toggled",
+        serde_json::json!({
+            "action": action,
+            "recordingId": recording_id,
+            "success": error.is_none(),
+            "error": error,
+        }),
+    );
+}
+
+/// Register `accelerator` as the recording hotkey, replacing any previous one
+pub fn register_hotkey<R: Runtime>(app: &AppHandle<R>, accelerator: &str) -> Result<(), String> {
+    let shortcut: Shortcut = accelerator
+        .parse()
+        .map_err(|e| format!("Invalid shortcut '{}': {}", accelerator, e))?;
+
+    unregister_hotkey(app)?;
+
+    app.global_shortcut()
+        .register(shortcut)
+        .map_err(|e| format!("Failed to register shortcut '{}': {}", accelerator, e))?;
+
+    *CURRENT_HOTKEY.lock().unwrap() = Some(accelerator.to_string());
+    info!("⌨️ Registered recording hotkey: {}", accelerator);
+    Ok(())
+}
+
+/// Unregister the current recording hotkey, if any
+pub fn unregister_hotkey<R: Runtime>(app: &AppHandle<R>) -> Result<(), String> {
+    let previous = CURRENT_HOTKEY.lock().unwrap().take();
+    if let Some(previous) = previous {
+        if let Ok(shortcut) = previous.parse::<Shortcut>() {
+            app.global_shortcut()
+                .unregister(shortcut)
+                .map_err(|e| format!("Failed to unregister shortcut '{}': {}", previous, e))?;
+            info!("⌨️ Unregistered recording hotkey: {}", previous);
+        }
+    }
+    Ok(())
+}
+
+/// Register the hotkey saved in settings (called during app setup)
+pub fn register_saved_hotkey<R: Runtime>(app: &AppHandle<R>, accelerator: Option<String>) {
+    if let Some(accelerator) = accelerator.filter(|a| !a.trim().is_empty()) {
+        if let Err(e) = register_hotkey(app, &accelerator) {
+            warn!("Failed to register saved recording hotkey: {}", e);
+        }
+    }
+}
+
+#[tauri::command]
+pub async fn get_recording_hotkey() -> Option<String> {
+    CURRENT_HOTKEY.lock().unwrap().clone()
+}
+
+/// Register and persist the recording hotkey
+#[tauri::command]
+pub async fn set_recording_hotkey<R: Runtime>(
+    app: AppHandle<R>,
+    state: tauri::State<'_, AppState>,
+    accelerator: String,
+) -> Result<(), String> {
+    register_hotkey(&app, &accelerator)?;
+
+    let db = state.db().await;
+    db.set_setting(RECORDING_HOTKEY_SETTING, &accelerator, "string")
+        .map_err(|e| e.to_string())
+}
+
+/// Unregister and forget the recording hotkey
+#[tauri::command]
+pub async fn clear_recording_hotkey<R: Runtime>(
+    app: AppHandle<R>,
+    state: tauri::State<'_, AppState>,
+) -> Result<(), String> {
+    unregister_hotkey(&app)?;
+
+    let db = state.db().await;
+    db.delete_setting(RECORDING_HOTKEY_SETTING)
+        .map_err(|e| e.to_string())
+}
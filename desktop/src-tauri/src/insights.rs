@@ -0,0 +1,183 @@
+//! Meeting insights
+//!
+//! Asks the LLM for the decisions, risks/blockers and open questions of a
+//! recording as structured JSON, and caches the result in the
+//! `recording_insights` table so it is only generated once per recording.
+
+use log::{info, warn};
+use serde_json::Value;
+
+use crate::chat::tool_orchestration::extract_json_object;
+use crate::database::RecordingInsights;
+use crate::llm_engine::provider::CompletionRequest;
+use crate::state::AppState;
+use crate::task_scheduler::{self, TaskKind};
+
+const INSIGHTS_PROMPT: &str = r#"Extract the following from this meeting and reply with JSON only, in exactly this shape:
+{"decisions": ["..."], "risks": ["..."], "open_questions": ["..."]}
+
+- decisions: decisions that were made or agreed on
+- risks: risks, blockers or concerns that were raised
+- open_questions: questions that were left unanswered or need follow-up
+
+Each entry is one short sentence. Use an empty list when there is nothing for a category."#;
+
+/// Fields of an entry object that hold its text, if the model didn't answer with plain strings
+const ENTRY_TEXT_FIELDS: &[&str] = &["text", "description", "title", "summary", "question", "decision", "risk"];
+
+#[derive(Debug, Default, PartialEq)]
+struct InsightLists {
+    decisions: Vec<String>,
+    risks: Vec<String>,
+    open_questions: Vec<String>,
+}
+
+/// Entries of a list field; plain strings or objects with a text field
+fn string_list(value: Option<&Value>) -> Vec<String> {
+    let Some(Value::Array(items)) = value else {
+        return Vec::new();
+    };
+
+    items
+        .iter()
+        .filter_map(|item| match item {
+            Value::String(text) => Some(text.trim().to_string()),
+            Value::Object(fields) => ENTRY_TEXT_FIELDS
+                .iter()
+                .find_map(|key| fields.get(*key).and_then(Value::as_str))
+                .map(|text| text.trim().to_string()),
+            _ => None,
+        })
+        .filter(|text| !text.is_empty())
+        .collect()
+}
+
+/// Parse the model output, tolerating code fences and text around the JSON object
+fn parse_insights(output: &str) -> Result<InsightLists, String> {
+    let start = output
+        .find('{')
+        .ok_or_else(|| "The model did not return JSON insights".to_string())?;
+    let json = extract_json_object(&output[start..])
+        .ok_or_else(|| "The model returned incomplete JSON insights".to_string())?;
+    let value: Value = serde_json::from_str(&json)
+        .map_err(|e| format!("The model returned invalid JSON insights: {}", e))?;
+
+    let field = |names: &[&str]| names.iter().find_map(|name| value.get(*name));
+    Ok(InsightLists {
+        decisions: string_list(field(&["decisions"])),
+        risks: string_list(field(&["risks", "blockers"])),
+        open_questions: string_list(field(&["open_questions", "questions"])),
+    })
+}
+
+/// Extract decisions, risks and open questions from a recording with the LLM.
+/// Returns the stored insights if they were already extracted, unless `force` is set.
+#[tauri::command]
+pub async fn extract_insights(
+    state: tauri::State<'_, AppState>,
+    recording_id: String,
+    force: Option<bool>,
+) -> Result<RecordingInsights, String> {
+    let segments = {
+        let db = state.db().await;
+        if !force.unwrap_or(false) {
+            if let Some(insights) = db.get_recording_insights(&recording_id).map_err(|e| e.to_string())? {
+                return Ok(insights);
+            }
+        }
+        db.get_transcript_segments(&recording_id).map_err(|e| e.to_string())?
+    };
+
+    if segments.is_empty() {
+        return Err(format!("Recording {} has no transcript", recording_id));
+    }
+
+    let transcript = segments
+        .iter()
+        .map(|s| {
+            let speaker = s.speaker_label.as_deref().unwrap_or("Unknown");
+            format!("[{}] {}: {}", s.display_time, speaker, s.text)
+        })
+        .collect::<Vec<_>>()
+        .join("\n");
+
+    let permit = task_scheduler::acquire(TaskKind::Llm, format!("Insights for {}", recording_id)).await?;
+    let engine = state.llm_engine.read().await;
+    if !engine.is_ready().await {
+        return Err("No LLM provider is ready".to_string());
+    }
+
+    let system = format!(
+        "You are a helpful assistant analyzing a meeting transcript. \
+        Answer based on the transcript below.\n\n\
+        TRANSCRIPT:\n{}",
+        transcript
+    );
+    let request = CompletionRequest {
+        max_tokens: Some(2048),
+        temperature: Some(0.2),
+        ..CompletionRequest::with_system_and_user(system, INSIGHTS_PROMPT)
+    };
+
+    let response = engine.complete(request).await.map_err(|e| e.to_string())?;
+    drop(engine);
+    drop(permit);
+
+    let lists = parse_insights(&response.content).inspect_err(|e| {
+        warn!("Insights for recording {}: {} - output: {}", recording_id, e, response.content);
+    })?;
+
+    let insights = RecordingInsights {
+        recording_id: recording_id.clone(),
+        decisions: lists.decisions,
+        risks: lists.risks,
+        open_questions: lists.open_questions,
+        model_id: Some(response.model),
+        created_at: chrono::Utc::now().to_rfc3339(),
+    };
+
+    state
+        .db()
+        .await
+        .save_recording_insights(&insights)
+        .map_err(|e| e.to_string())?;
+
+    info!(
+        "💡 Extracted insights for recording {}: {} decisions, {} risks, {} open questions",
+        recording_id,
+        insights.decisions.len(),
+        insights.risks.len(),
+        insights.open_questions.len()
+    );
+    Ok(insights)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_fenced_insights() {
+        let output = "Here you go:\n```json\n{\"decisions\": [\"Ship v2 on Friday\"], \
+                      \"risks\": [\"QA {capacity} is low\"], \"open_questions\": []}\n```";
+        let lists = parse_insights(output).unwrap();
+        assert_eq!(lists.decisions, vec!["Ship v2 on Friday".to_string()]);
+        assert_eq!(lists.risks, vec!["QA {capacity} is low".to_string()]);
+        assert!(lists.open_questions.is_empty());
+    }
+
+    #[test]
+    fn test_parse_insights_tolerates_object_entries_and_missing_fields() {
+        let output = r#"{"decisions": [{"text": "Hire a designer"}, "", 3], "blockers": ["Budget approval"]}"#;
+        let lists = parse_insights(output).unwrap();
+        assert_eq!(lists.decisions, vec!["Hire a designer".to_string()]);
+        assert_eq!(lists.risks, vec!["Budget approval".to_string()]);
+        assert!(lists.open_questions.is_empty());
+    }
+
+    #[test]
+    fn test_parse_insights_rejects_non_json() {
+        assert!(parse_insights("No decisions were made.").is_err());
+        assert!(parse_insights("{\"decisions\": [\"unterminated\"").is_err());
+    }
+}
@@ -6,7 +6,8 @@
 // - Basic device management
 
 use serde::{Deserialize, Serialize};
-use std::sync::atomic::Ordering;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::Duration;
 
 // Performance logging macros - exported for use by other modules
 #[macro_use]
@@ -27,6 +28,23 @@ pub mod chat;
 pub mod templates;
 pub mod tools;
 pub mod mcp;
+pub mod hotkey;
+pub mod auto_summary;
+pub mod insights;
+pub mod transcript_cleanup;
+pub mod transcript_redaction;
+pub mod webhook;
+pub mod onboarding;
+pub mod downloads;
+pub mod export;
+pub mod metrics;
+pub mod file_log;
+pub mod diagnostics;
+pub mod meeting_analytics;
+pub mod task_scheduler;
+pub mod transcript_keywords;
+pub mod model_directories;
+pub mod transcription_quality;
 
 // Stub modules for removed MeetLocal features
 pub mod stubs;
@@ -58,7 +76,7 @@ pub mod parakeet_engine {
 
 use audio::{list_audio_devices, AudioDevice};
 use log::{error as log_error, info as log_info};
-use tauri::{AppHandle, Manager, Runtime};
+use tauri::{AppHandle, Emitter, Manager, Runtime};
 
 // Re-export for backwards compatibility
 pub use globals::get_language_preference_internal;
@@ -150,6 +168,101 @@ fn set_sys_normalizer_enabled(enabled: bool) -> Result<(), String> {
     Ok(())
 }
 
+// --- Final-pass normalization of the saved file ---
+
+#[tauri::command]
+fn get_final_loudnorm_enabled() -> bool {
+    audio::post_processor::is_final_loudnorm_enabled()
+}
+
+#[tauri::command]
+fn set_final_loudnorm_enabled(enabled: bool) -> Result<(), String> {
+    audio::post_processor::set_final_loudnorm_enabled(enabled);
+    Ok(())
+}
+
+// --- Silence trimming of the saved file ---
+
+#[tauri::command]
+fn get_trim_silence_on_save() -> bool {
+    audio::post_processor::is_trim_silence_enabled()
+}
+
+#[tauri::command]
+fn set_trim_silence_on_save(enabled: bool) -> Result<(), String> {
+    audio::post_processor::set_trim_silence_enabled(enabled);
+    Ok(())
+}
+
+#[tauri::command]
+fn get_trim_min_silence_seconds() -> f64 {
+    audio::post_processor::get_trim_min_silence_seconds()
+}
+
+#[tauri::command]
+fn set_trim_min_silence_seconds(seconds: f64) -> Result<(), String> {
+    audio::post_processor::set_trim_min_silence_seconds(seconds);
+    Ok(())
+}
+
+#[tauri::command]
+fn get_keep_untrimmed_original() -> bool {
+    audio::post_processor::is_keep_untrimmed_original_enabled()
+}
+
+#[tauri::command]
+fn set_keep_untrimmed_original(enabled: bool) -> Result<(), String> {
+    audio::post_processor::set_keep_untrimmed_original_enabled(enabled);
+    Ok(())
+}
+
+// --- Per-second level timeline of the mixed recording ---
+
+#[tauri::command]
+fn get_record_level_timeline_enabled() -> bool {
+    audio::level_timeline::is_level_timeline_enabled()
+}
+
+#[tauri::command]
+fn set_record_level_timeline_enabled(enabled: bool) -> Result<(), String> {
+    audio::level_timeline::set_level_timeline_enabled(enabled);
+    Ok(())
+}
+
+/// Get the level timeline recorded during capture, or None if it wasn't enabled
+#[tauri::command]
+async fn get_recording_level_timeline(
+    recording_id: String,
+    state: tauri::State<'_, state::AppState>,
+) -> Result<Option<audio::LevelTimeline>, String> {
+    let recording = {
+        let db = state.db().await;
+        db.get_recording(&recording_id)
+            .map_err(|e| e.to_string())?
+            .ok_or_else(|| format!("Recording not found: {}", recording_id))?
+    };
+
+    let Some(folder) = recording.folder_path() else {
+        return Ok(None);
+    };
+
+    audio::LevelTimeline::load(&folder).map_err(|e| e.to_string())
+}
+
+// --- Channel layout of the saved file ---
+
+#[tauri::command]
+fn get_save_channel_layout() -> audio::pipeline::SaveChannelLayout {
+    audio::pipeline::get_save_channel_layout()
+}
+
+/// Takes effect for the next recording
+#[tauri::command]
+fn set_save_channel_layout(layout: audio::pipeline::SaveChannelLayout) -> Result<(), String> {
+    audio::pipeline::set_save_channel_layout(layout);
+    Ok(())
+}
+
 // --- Legacy commands (backward compatibility) ---
 
 #[tauri::command]
@@ -167,8 +280,8 @@ fn set_noise_suppression_enabled(enabled: bool) -> Result<(), String> {
 // ============== Database Commands ==============
 
 use database::{
-    AllSettings, Recording, RecordingUpdate, RecordingWithMetadata,
-    TranscriptSegment, Category, Tag, SearchResult, SearchFilters,
+    AllSettings, Recording, RecordingUpdate, RecordingWithMetadata, RecordingFull,
+    TranscriptSegment, TranscriptVersion, Category, CategoryWithCount, Tag, SearchResult, SearchFilters,
 };
 
 #[tauri::command]
@@ -226,6 +339,16 @@ async fn db_get_recording(
     db.get_recording_with_metadata(&id).map_err(|e| e.to_string())
 }
 
+/// Get a recording with its transcript, chat sessions, categories and tags as one object
+#[tauri::command]
+async fn db_get_recording_full(
+    recording_id: String,
+    state: tauri::State<'_, state::AppState>,
+) -> Result<Option<RecordingFull>, String> {
+    let db = state.db().await;
+    db.get_recording_full(&recording_id).map_err(|e| e.to_string())
+}
+
 #[tauri::command]
 async fn db_get_all_recordings(
     state: tauri::State<'_, state::AppState>,
@@ -291,18 +414,27 @@ async fn db_delete_recording(
         }
     }
 
+    cleanup_unused_tags_if_enabled(&db);
+
     log::info!("Successfully deleted recording: {}", id);
     Ok(())
 }
 
 #[tauri::command]
 async fn db_complete_recording(
+    app: tauri::AppHandle,
     id: String,
     duration: f64,
     state: tauri::State<'_, state::AppState>,
 ) -> Result<(), String> {
-    let db = state.db().await;
-    db.complete_recording(&id, duration).map_err(|e| e.to_string())
+    state
+        .db()
+        .await
+        .complete_recording(&id, duration)
+        .map_err(|e| e.to_string())?;
+
+    audio::recording::completion::recording_completed(&app, &id).await;
+    Ok(())
 }
 
 // Transcript commands
@@ -317,11 +449,28 @@ async fn db_save_transcript_segment(
 
 #[tauri::command]
 async fn db_save_transcript_segments_batch(
-    segments: Vec<TranscriptSegment>,
+    app: tauri::AppHandle,
+    mut segments: Vec<TranscriptSegment>,
     state: tauri::State<'_, state::AppState>,
 ) -> Result<(), String> {
-    let db = state.db().await;
-    db.save_transcript_segments_batch(&segments).map_err(|e| e.to_string())
+    {
+        let db = state.db().await;
+
+        audio::post_processor::save_live_transcript(&db, &mut segments).map_err(|e| e.to_string())?;
+
+        // The recording is completed before its transcript arrives, so score it now
+        if let Some(recording_id) = segments.first().map(|s| s.recording_id.as_str()) {
+            if let Err(e) = transcription_quality::update_transcription_quality(&db, recording_id) {
+                log::warn!("Failed to score transcription quality of {}: {}", recording_id, e);
+            }
+        }
+    }
+
+    // Transcripts are saved after the recording is marked completed
+    if let Some(recording_id) = segments.first().map(|s| s.recording_id.clone()) {
+        auto_summary::summarize_if_enabled(&app, &recording_id).await;
+    }
+    Ok(())
 }
 
 #[tauri::command]
@@ -340,7 +489,11 @@ async fn db_replace_transcripts(
     state: tauri::State<'_, state::AppState>,
 ) -> Result<(), String> {
     let db = state.db().await;
-    db.replace_transcripts(&recording_id, &segments).map_err(|e| e.to_string())
+    db.replace_transcripts(&recording_id, &segments).map_err(|e| e.to_string())?;
+    if let Err(e) = transcription_quality::update_transcription_quality(&db, &recording_id) {
+        log::warn!("Failed to score transcription quality of {}: {}", recording_id, e);
+    }
+    Ok(())
 }
 
 #[tauri::command]
@@ -353,6 +506,51 @@ async fn db_update_speaker_label(
     db.update_speaker_label(&speaker_id, &new_label).map_err(|e| e.to_string())
 }
 
+/// Derived transcript versions of a recording (e.g. LLM cleanup, redaction), newest first
+#[tauri::command]
+async fn db_get_transcript_versions(
+    recording_id: String,
+    state: tauri::State<'_, state::AppState>,
+) -> Result<Vec<TranscriptVersion>, String> {
+    let db = state.db().await;
+    db.get_transcript_versions(&recording_id).map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+async fn db_delete_transcript_version(
+    version_id: String,
+    state: tauri::State<'_, state::AppState>,
+) -> Result<bool, String> {
+    let db = state.db().await;
+    db.delete_transcript_version(&version_id).map_err(|e| e.to_string())
+}
+
+/// Revert the most recent transcript text edit or speaker rename in a recording.
+/// Returns the number of segments reverted (0 if there is nothing to undo).
+#[tauri::command]
+async fn db_undo_last_transcript_edit(
+    recording_id: String,
+    state: tauri::State<'_, state::AppState>,
+) -> Result<usize, String> {
+    let db = state.db().await;
+    db.undo_last_transcript_edit(&recording_id).map_err(|e| e.to_string())
+}
+
+/// Assign a speaker to a time range of a recording, splitting segments at the range boundaries
+#[tauri::command]
+async fn db_assign_speaker_range(
+    recording_id: String,
+    start_time: f64,
+    end_time: f64,
+    speaker_id: String,
+    speaker_label: String,
+    state: tauri::State<'_, state::AppState>,
+) -> Result<usize, String> {
+    let db = state.db().await;
+    db.assign_speaker_range(&recording_id, start_time, end_time, &speaker_id, &speaker_label)
+        .map_err(|e| e.to_string())
+}
+
 #[tauri::command]
 async fn db_update_transcript_text(
     segment_id: String,
@@ -363,6 +561,86 @@ async fn db_update_transcript_text(
     db.update_transcript_text(&segment_id, &new_text).map_err(|e| e.to_string())
 }
 
+/// Poll a recording's transcript for segments after `sequence_id` (all segments if omitted).
+/// Use "current" for the recording in progress; a saved recording whose meeting folder
+/// is the live one is also served from the in-memory transcript.
+#[tauri::command]
+async fn get_transcript_segments_since(
+    recording_id: String,
+    sequence_id: Option<i64>,
+    state: tauri::State<'_, state::AppState>,
+) -> Result<Vec<TranscriptSegment>, String> {
+    let after = sequence_id.unwrap_or(-1);
+    let (live_folder, live_segments) = audio::recording::state::with_recording_manager(|manager| {
+        manager
+            .map(|m| (m.get_meeting_folder(), m.get_transcript_segments()))
+            .unwrap_or_default()
+    });
+
+    let db = state.db().await;
+    let is_live = recording_id == "current"
+        || (live_folder.is_some()
+            && db.get_recording(&recording_id)
+                .map_err(|e| e.to_string())?
+                .and_then(|r| r.meeting_folder_path)
+                .map(std::path::PathBuf::from)
+                == live_folder);
+
+    if !is_live {
+        return db.get_transcript_segments_since(&recording_id, after).map_err(|e| e.to_string());
+    }
+
+    Ok(live_segments
+        .into_iter()
+        .filter(|s| s.sequence_id as i64 > after)
+        .map(|s| TranscriptSegment {
+            id: s.id,
+            recording_id: recording_id.clone(),
+            text: s.text,
+            audio_start_time: s.audio_start_time,
+            audio_end_time: s.audio_end_time,
+            duration: s.duration,
+            display_time: s.display_time,
+            confidence: s.confidence,
+            sequence_id: s.sequence_id as i64,
+            speaker_id: None,
+            speaker_label: None,
+            is_registered_speaker: false,
+            low_confidence: false,
+        })
+        .collect())
+}
+
+/// Regenerate display times from audio_start_time (relative, or absolute wall-clock)
+#[tauri::command]
+async fn db_recompute_display_times(
+    recording_id: String,
+    absolute: Option<bool>,
+    state: tauri::State<'_, state::AppState>,
+) -> Result<usize, String> {
+    let db = state.db().await;
+    db.recompute_display_times(&recording_id, absolute.unwrap_or(false))
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+async fn db_get_all_speakers_summary(
+    state: tauri::State<'_, state::AppState>,
+) -> Result<Vec<database::SpeakerSummary>, String> {
+    let db = state.db().await;
+    db.get_all_speakers_summary().map_err(|e| e.to_string())
+}
+
+// Speaker embedding commands
+#[tauri::command]
+async fn db_get_recording_speaker_embeddings(
+    recording_id: String,
+    state: tauri::State<'_, state::AppState>,
+) -> Result<Vec<database::RecordingSpeakerEmbedding>, String> {
+    let db = state.db().await;
+    db.get_recording_speaker_embeddings(&recording_id).map_err(|e| e.to_string())
+}
+
 // Category commands
 #[tauri::command]
 async fn db_get_all_categories(
@@ -372,6 +650,14 @@ async fn db_get_all_categories(
     db.get_all_categories().map_err(|e| e.to_string())
 }
 
+#[tauri::command]
+async fn db_get_categories_with_counts(
+    state: tauri::State<'_, state::AppState>,
+) -> Result<Vec<CategoryWithCount>, String> {
+    let db = state.db().await;
+    db.get_categories_with_counts().map_err(|e| e.to_string())
+}
+
 #[tauri::command]
 async fn db_create_category(
     name: String,
@@ -420,6 +706,15 @@ async fn db_get_all_tags(
     db.get_all_tags().map_err(|e| e.to_string())
 }
 
+#[tauri::command]
+async fn db_get_popular_tags(
+    limit: Option<usize>,
+    state: tauri::State<'_, state::AppState>,
+) -> Result<Vec<Tag>, String> {
+    let db = state.db().await;
+    db.get_popular_tags(limit.unwrap_or(10)).map_err(|e| e.to_string())
+}
+
 #[tauri::command]
 async fn db_create_tag(
     name: String,
@@ -459,6 +754,51 @@ async fn db_delete_tag(
     db.delete_tag(&tag_id).map_err(|e| e.to_string())
 }
 
+#[tauri::command]
+async fn db_delete_unused_tags(
+    state: tauri::State<'_, state::AppState>,
+) -> Result<usize, String> {
+    let db = state.db().await;
+    db.delete_unused_tags().map_err(|e| e.to_string())
+}
+
+/// Settings key for automatically deleting tags no recording uses
+const AUTO_CLEANUP_TAGS_SETTING: &str = "auto_cleanup_unused_tags";
+
+/// How often the background task cleans up unused tags
+const TAG_CLEANUP_INTERVAL_SECS: u64 = 24 * 60 * 60;
+
+/// Delete unused tags if the auto-cleanup preference is on
+fn cleanup_unused_tags_if_enabled(db: &database::DatabaseManager) {
+    if !db.get_bool_setting(AUTO_CLEANUP_TAGS_SETTING, false).unwrap_or(false) {
+        return;
+    }
+    match db.delete_unused_tags() {
+        Ok(0) => {}
+        Ok(count) => log::info!("Auto-cleanup removed {} unused tags", count),
+        Err(e) => log::warn!("Failed to clean up unused tags: {}", e),
+    }
+}
+
+#[tauri::command]
+async fn get_auto_cleanup_unused_tags(
+    state: tauri::State<'_, state::AppState>,
+) -> Result<bool, String> {
+    let db = state.db().await;
+    db.get_bool_setting(AUTO_CLEANUP_TAGS_SETTING, false).map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+async fn set_auto_cleanup_unused_tags(
+    enabled: bool,
+    state: tauri::State<'_, state::AppState>,
+) -> Result<(), String> {
+    let db = state.db().await;
+    db.set_bool_setting(AUTO_CLEANUP_TAGS_SETTING, enabled).map_err(|e| e.to_string())?;
+    cleanup_unused_tags_if_enabled(&db);
+    Ok(())
+}
+
 #[tauri::command]
 async fn db_get_or_create_tag(
     name: String,
@@ -480,6 +820,30 @@ async fn db_search_recordings(
     db.search_recordings(&query, &filters).map_err(|e| e.to_string())
 }
 
+/// Full-text search over chat messages in all recordings (default limit 50,
+/// default snippet length 32 tokens)
+#[tauri::command]
+async fn db_search_chat_messages(
+    query: String,
+    limit: Option<usize>,
+    snippet_tokens: Option<usize>,
+    state: tauri::State<'_, state::AppState>,
+) -> Result<Vec<database::ChatMessageSearchResult>, String> {
+    let db = state.db().await;
+    db.search_chat_messages(&query, limit.unwrap_or(50), snippet_tokens).map_err(|e| e.to_string())
+}
+
+/// Command palette search over recordings, transcripts, chat messages and templates (default limit 20)
+#[tauri::command]
+async fn db_global_search(
+    query: String,
+    limit: Option<usize>,
+    state: tauri::State<'_, state::AppState>,
+) -> Result<Vec<database::GlobalSearchResult>, String> {
+    let db = state.db().await;
+    db.global_search(&query, limit.unwrap_or(20)).map_err(|e| e.to_string())
+}
+
 #[derive(Debug, Deserialize)]
 struct RecordingArgs {
     save_path: String,
@@ -510,6 +874,12 @@ fn get_hardware_recommendations() -> audio::HardwareRecommendations {
     profile.get_model_recommendations()
 }
 
+#[tauri::command]
+fn get_llm_model_recommendations() -> audio::LlmModelRecommendations {
+    let profile = audio::HardwareProfile::detect();
+    profile.get_llm_model_recommendations()
+}
+
 // ============== Recording Commands ==============
 
 #[tauri::command]
@@ -574,6 +944,50 @@ async fn stop_recording<R: Runtime>(app: AppHandle<R>, args: RecordingArgs) -> R
     }
 }
 
+/// Longest the app waits on exit for an active recording to stop and flush its transcripts
+const SHUTDOWN_FLUSH_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// Longest the app waits on exit for the completion webhook of the stopped recording
+const SHUTDOWN_WEBHOOK_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Set while the exit handler is stopping a recording, so repeated exit requests are ignored
+static SHUTDOWN_IN_PROGRESS: AtomicBool = AtomicBool::new(false);
+
+/// Stop an active recording before the app exits, running the same stop-and-flush
+/// sequence as `stop_recording` with a bounded wait, then exit
+async fn stop_recording_before_exit<R: Runtime>(app: AppHandle<R>, exit_code: i32) {
+    log_info!("🛑 App is exiting during a recording - stopping and flushing transcripts first");
+    let _ = app.emit("app-shutdown-started", serde_json::json!({
+        "timeoutSeconds": SHUTDOWN_FLUSH_TIMEOUT.as_secs(),
+    }));
+
+    let stop = audio::recording::lifecycle::stop_recording(
+        app.clone(),
+        audio::recording::types::RecordingArgs { save_path: String::new() },
+    );
+    let (flushed, error) = match tokio::time::timeout(SHUTDOWN_FLUSH_TIMEOUT, stop).await {
+        Ok(Ok(())) => (true, None),
+        Ok(Err(e)) => {
+            log_error!("Failed to stop recording on exit: {}", e);
+            (false, Some(e))
+        }
+        Err(_) => {
+            log_error!("Recording did not stop within {}s, exiting anyway", SHUTDOWN_FLUSH_TIMEOUT.as_secs());
+            (false, Some("Timed out waiting for transcription to finish".to_string()))
+        }
+    };
+    RECORDING_FLAG.store(false, Ordering::SeqCst);
+
+    // Stopping completed the recording, which may have started its webhook
+    webhook::wait_for_pending_deliveries(SHUTDOWN_WEBHOOK_TIMEOUT).await;
+
+    let _ = app.emit("app-shutdown-complete", serde_json::json!({
+        "flushed": flushed,
+        "error": error,
+    }));
+    app.exit(exit_code);
+}
+
 #[tauri::command]
 async fn is_recording() -> bool {
     audio::recording::lifecycle::is_recording_async().await
@@ -590,6 +1004,8 @@ fn get_transcription_status() -> TranscriptionStatus {
 
 // ============== Live Diarization Commands ==============
 
+/// Enable or disable diarization of live chunks. Diarizing every chunk roughly
+/// doubles the CPU time per chunk while recording; persisted as `live_diarization`.
 #[tauri::command]
 fn set_live_diarization_enabled(enabled: bool) {
     audio::transcription::set_live_diarization_enabled(enabled);
@@ -600,6 +1016,43 @@ fn get_live_diarization_enabled() -> bool {
     audio::transcription::is_live_diarization_enabled()
 }
 
+#[derive(serde::Serialize, serde::Deserialize)]
+struct SourceSpeakerLabels {
+    enabled: bool,
+    mic_label: String,
+    system_label: String,
+}
+
+/// Labels used for live transcript segments dominated by the mic or by system audio
+#[tauri::command]
+fn get_source_speaker_labels() -> SourceSpeakerLabels {
+    let (mic_label, system_label) = audio::transcription::get_source_speaker_labels();
+    SourceSpeakerLabels {
+        enabled: audio::transcription::is_source_speaker_labels_enabled(),
+        mic_label,
+        system_label,
+    }
+}
+
+/// Takes effect for the next transcribed segment; empty labels fall back to the defaults
+#[tauri::command]
+fn set_source_speaker_labels(labels: SourceSpeakerLabels) {
+    audio::transcription::set_source_speaker_labels_enabled(labels.enabled);
+    audio::transcription::set_source_speaker_labels(&labels.mic_label, &labels.system_label);
+}
+
+#[tauri::command]
+fn get_live_rolling_context_enabled() -> bool {
+    audio::transcription::is_rolling_context_enabled()
+}
+
+/// Prompt each live chunk with the previous chunk's text; takes effect for the next chunk
+#[tauri::command]
+fn set_live_rolling_context_enabled(enabled: bool) -> Result<(), String> {
+    audio::transcription::set_rolling_context_enabled(enabled);
+    Ok(())
+}
+
 #[tauri::command]
 fn read_audio_file(file_path: String) -> Result<Vec<u8>, String> {
     std::fs::read(&file_path).map_err(|e| format!("Failed to read audio file: {}", e))
@@ -632,6 +1085,35 @@ async fn get_audio_devices() -> Result<Vec<AudioDevice>, String> {
         .map_err(|e| format!("Failed to list audio devices: {}", e))
 }
 
+/// Supported sample rates and channel counts of a device, with warnings about
+/// configurations that force resampling. Names without an "(input)"/"(output)"
+/// suffix are treated as input devices.
+#[tauri::command]
+async fn probe_device_capabilities(device_name: String) -> Result<audio::devices::DeviceCapabilities, String> {
+    let device = AudioDevice::from_name(&device_name)
+        .unwrap_or_else(|_| AudioDevice::new(device_name, audio::devices::DeviceType::Input));
+    audio::devices::probe_device_capabilities(&device)
+        .await
+        .map_err(|e| format!("Failed to probe device capabilities: {}", e))
+}
+
+/// Capture sample rates requested per device name
+#[tauri::command]
+fn get_capture_sample_rates() -> std::collections::HashMap<String, u32> {
+    audio::devices::get_preferred_sample_rates()
+}
+
+/// Request a capture sample rate for a device, or clear it with None.
+/// Takes effect for the next recording; unsupported rates fall back to the device default.
+#[tauri::command]
+fn set_capture_sample_rate(device_name: String, sample_rate: Option<u32>) -> Result<(), String> {
+    if sample_rate.is_some_and(|rate| !(8000..=192000).contains(&rate)) {
+        return Err("Sample rate must be between 8000 and 192000 Hz".to_string());
+    }
+    audio::devices::set_preferred_sample_rate(&device_name, sample_rate);
+    Ok(())
+}
+
 #[tauri::command]
 async fn start_recording_with_devices<R: Runtime>(
     app: AppHandle<R>,
@@ -674,13 +1156,20 @@ async fn is_audio_level_monitoring() -> bool {
 // ============== Main App Entry ==============
 
 pub fn run() {
-    // Initialize env_logger to output to stderr (reads RUST_LOG env var)
+    // Initialize env_logger to output to stderr (reads RUST_LOG env var),
+    // and to the log file once it is enabled from settings
     env_logger::Builder::from_env(env_logger::Env::default().default_filter_or("info"))
         .format_timestamp_millis()
+        .target(env_logger::Target::Pipe(Box::new(file_log::LogWriter)))
         .init();
 
     tauri::Builder::default()
         .plugin(tauri_plugin_dialog::init())
+        .plugin(
+            tauri_plugin_global_shortcut::Builder::new()
+                .with_handler(|app, shortcut, event| hotkey::handle_shortcut(app, shortcut, event))
+                .build(),
+        )
         .manage(whisper_engine::parallel_commands::ParallelProcessorState::new())
         .manage(audio::init_system_audio_state())
         .manage(state::AppState::new())
@@ -706,6 +1195,11 @@ pub fn run() {
             if let Ok(settings) = db.load_all_settings() {
                 log::info!("Applying settings from database...");
 
+                // Write logs to the rotating log file
+                if settings.file_logging_enabled {
+                    file_log::enable_file_logging(app.handle());
+                }
+
                 // Apply audio processing settings
                 audio::ffmpeg_mixer::set_mic_rnnoise_enabled(settings.mic_rnnoise);
                 audio::ffmpeg_mixer::set_mic_highpass_enabled(settings.mic_highpass);
@@ -713,6 +1207,94 @@ pub fn run() {
                 audio::ffmpeg_mixer::set_sys_rnnoise_enabled(settings.sys_rnnoise);
                 audio::ffmpeg_mixer::set_sys_highpass_enabled(settings.sys_highpass);
                 audio::ffmpeg_mixer::set_sys_normalizer_enabled(settings.sys_normalizer);
+                audio::post_processor::set_final_loudnorm_enabled(settings.final_loudnorm);
+                audio::level_timeline::set_level_timeline_enabled(settings.record_level_timeline);
+                audio::post_processor::set_trim_silence_enabled(settings.trim_silence_on_save);
+                if let Some(seconds) = settings.trim_min_silence_seconds {
+                    audio::post_processor::set_trim_min_silence_seconds(seconds);
+                }
+                if let Some(keep) = settings.keep_untrimmed_original {
+                    audio::post_processor::set_keep_untrimmed_original_enabled(keep);
+                }
+
+                if let Some(layout) = settings.save_channel_layout.as_deref().and_then(audio::pipeline::SaveChannelLayout::parse) {
+                    audio::pipeline::set_save_channel_layout(layout);
+                }
+
+                // Register custom whisper model files loaded by path
+                if let Some(models) = settings.custom_whisper_models.as_deref().and_then(|json| serde_json::from_str(json).ok()) {
+                    whisper_engine::model_registry::set_custom_models(models);
+                }
+
+                // Apply per-device capture sample rates
+                if let Some(rates) = settings.capture_sample_rates.as_deref().and_then(|json| serde_json::from_str(json).ok()) {
+                    audio::devices::set_preferred_sample_rates(rates);
+                }
+
+                // Apply speaker labels for source-tagged live transcripts
+                audio::transcription::set_source_speaker_labels_enabled(settings.label_speakers_by_source);
+                audio::transcription::set_source_speaker_labels(
+                    settings.mic_speaker_label.as_deref().unwrap_or_default(),
+                    settings.system_speaker_label.as_deref().unwrap_or_default(),
+                );
+
+                // Apply mic-only fallback when system audio fails to start
+                audio::recording_preferences::set_allow_mic_only_fallback(
+                    settings.allow_mic_only_fallback.unwrap_or(true),
+                );
+
+                // Apply live diarization (speaker labels on live transcripts)
+                audio::transcription::set_live_diarization_enabled(settings.live_diarization);
+
+                // Apply rolling context between live transcription chunks
+                audio::transcription::set_rolling_context_enabled(settings.live_rolling_context);
+
+                // Apply VAD aggressiveness (used when the next recording starts)
+                if let Some(threshold) = settings.vad_threshold {
+                    audio::vad::set_vad_custom_threshold(threshold);
+                }
+                if let Some(level) = settings.vad_aggressiveness.as_deref().and_then(audio::vad::VadAggressiveness::parse) {
+                    audio::vad::set_vad_aggressiveness(level);
+                }
+                audio::vad::apply_live_chunk_seconds(settings.live_chunk_seconds);
+
+                // Apply transcription model retention
+                audio::recording::model_retention::set_keep_model_loaded_enabled(settings.keep_model_loaded);
+                if let Some(minutes) = settings.model_idle_timeout_minutes {
+                    audio::recording::model_retention::set_idle_timeout_minutes(minutes);
+                }
+
+                // Apply the limit on heavy background tasks running at once
+                if let Some(max) = settings.max_concurrent_tasks {
+                    task_scheduler::set_max_concurrent_tasks(max);
+                }
+
+                // Use model folders moved out of their defaults
+                for (kind, dir) in [
+                    (model_directories::ModelKind::Whisper, &settings.whisper_models_dir),
+                    (model_directories::ModelKind::Llm, &settings.llm_models_dir),
+                    (model_directories::ModelKind::Diarization, &settings.diarization_models_dir),
+                ] {
+                    model_directories::set_custom_models_dir(kind, dir.as_deref().map(std::path::PathBuf::from));
+                }
+                if let Some(dir) = settings.llm_models_dir.as_deref() {
+                    let app_state: tauri::State<state::AppState> = app.state();
+                    tauri::async_runtime::block_on(app_state.set_llm_models_dir(std::path::PathBuf::from(dir)));
+                }
+
+                // Register the recording hotkey
+                hotkey::register_saved_hotkey(app.handle(), settings.recording_hotkey.clone());
+
+                // Start the local metrics endpoint
+                if settings.metrics_endpoint_enabled {
+                    let metrics_handle = app.handle().clone();
+                    let port = settings.metrics_endpoint_port.unwrap_or(metrics::DEFAULT_METRICS_PORT);
+                    tauri::async_runtime::spawn(async move {
+                        if let Err(e) = metrics::start_metrics_server(metrics_handle, port).await {
+                            log::error!("{}", e);
+                        }
+                    });
+                }
 
                 // Apply language preference
                 if let Some(lang) = settings.language {
@@ -765,13 +1347,71 @@ pub fn run() {
                 app_state.init_database(db_clone).await;
             });
 
+            // Periodically clean up unused tags (checks the preference each run)
+            let cleanup_handle = app.handle().clone();
+            tauri::async_runtime::spawn(async move {
+                loop {
+                    {
+                        let app_state = cleanup_handle.state::<state::AppState>();
+                        let db = app_state.db().await;
+                        cleanup_unused_tags_if_enabled(&db);
+                    }
+                    tokio::time::sleep(std::time::Duration::from_secs(TAG_CLEANUP_INTERVAL_SECS)).await;
+                }
+            });
+
+            // Resume retranscription jobs left in the persistent queue
+            let queue_handle = app.handle().clone();
+            tauri::async_runtime::spawn(async move {
+                audio::retranscription::resume_retranscription_queue(&queue_handle).await;
+            });
+
+            // Offer recovery for recordings interrupted by a crash or forced quit
+            let recovery_handle = app.handle().clone();
+            tauri::async_runtime::spawn(async move {
+                audio::recording::recovery::check_interrupted_recordings(&recovery_handle).await;
+            });
+
+            // Start the scheduled recordings background task
+            audio::recording::scheduler::start_scheduler(app.handle().clone());
+
             // Set models directory
             whisper_engine::commands::set_models_directory(&app.handle());
 
-            // Initialize Whisper engine on startup
-            tauri::async_runtime::spawn(async {
+            // Folder for an FFmpeg downloaded by the app, preferred over other installs
+            if let Ok(app_data_dir) = app.path().app_data_dir() {
+                audio::ffmpeg::set_bundled_ffmpeg_dir(app_data_dir.join("ffmpeg"));
+            }
+
+            // Initialize Whisper engine and warm up the configured model on startup.
+            // Runs after the settings above, so the saved language is used.
+            let warmup_handle = app.handle().clone();
+            tauri::async_runtime::spawn(async move {
                 if let Err(e) = whisper_engine::commands::whisper_init().await {
                     log::error!("Failed to initialize Whisper engine: {}", e);
+                    return;
+                }
+                let provider = api::api::api_get_transcript_config(
+                    warmup_handle.clone(),
+                    warmup_handle.state(),
+                    None,
+                )
+                .await
+                .ok()
+                .flatten()
+                .map(|config| config.provider);
+                match provider.as_deref() {
+                    None | Some("localWhisper") => {
+                        // Failure (e.g. no model downloaded yet) is reported via whisper-warmup-failed
+                        let _ = whisper_engine::commands::whisper_warmup(warmup_handle).await;
+                    }
+                    Some(provider) => {
+                        // Another engine transcribes; load its model instead of warming Whisper
+                        log::info!("Skipping Whisper warmup, transcription uses {}", provider);
+                        if let Err(e) = audio::transcription::validate_transcription_model_ready(&warmup_handle).await {
+                            log::warn!("Failed to load the {} model on startup: {}", provider, e);
+                        }
+                    }
                 }
             });
 
@@ -788,6 +1428,9 @@ pub fn run() {
             save_transcript,
             // Device commands
             get_audio_devices,
+            probe_device_capabilities,
+            get_capture_sample_rates,
+            set_capture_sample_rate,
             start_recording_with_devices,
             // Audio level monitoring
             start_audio_level_monitoring,
@@ -796,6 +1439,8 @@ pub fn run() {
             // Recording control - pause/resume
             audio::recording::pause_resume::pause_recording,
             audio::recording::pause_resume::resume_recording,
+            audio::recording::recovery::get_recoverable_recordings,
+            audio::recording::recovery::recover_interrupted_recording,
             audio::recording::pause_resume::is_recording_paused,
             audio::recording::pause_resume::get_recording_state,
             audio::recording::pause_resume::get_meeting_folder_path,
@@ -813,10 +1458,46 @@ pub fn run() {
             audio::recording_preferences::open_recordings_folder,
             audio::recording_preferences::open_folder,
             audio::recording_preferences::select_recording_folder,
+            // Transcription model retention
+            audio::recording::model_retention::get_keep_model_loaded,
+            audio::recording::model_retention::set_keep_model_loaded,
+            audio::recording::model_retention::get_model_idle_timeout_minutes,
+            audio::recording::model_retention::set_model_idle_timeout_minutes,
+            task_scheduler::get_task_scheduler_status,
+            task_scheduler::cancel_all_tasks,
+            task_scheduler::set_max_concurrent_background_tasks,
+            model_directories::get_model_directories,
+            model_directories::set_model_directory,
+            model_directories::get_all_installed_models,
+            transcription_quality::get_transcription_quality,
+            // Scheduled recordings
+            audio::recording::scheduler::get_scheduled_recordings,
+            audio::recording::scheduler::create_scheduled_recording,
+            audio::recording::scheduler::update_scheduled_recording,
+            audio::recording::scheduler::delete_scheduled_recording,
+            // Dictation (mic-only quick record)
+            audio::dictation::start_dictation,
+            audio::dictation::stop_dictation,
+            audio::dictation::is_dictation_active,
+            audio::mic_test::test_microphone,
+            // Recording hotkey
+            hotkey::get_recording_hotkey,
+            hotkey::set_recording_hotkey,
+            hotkey::clear_recording_hotkey,
             // Retranscription commands
             audio::retranscription::retranscribe_recording,
             audio::retranscription::cancel_retranscription,
+            audio::retranscription::transcribe_file_quick,
+            audio::retranscription::transcribe_files_batch,
+            audio::retranscription::detect_audio_language,
+            audio::retranscription::recluster_speakers,
             audio::retranscription::get_retranscription_status,
+            audio::retranscription::enqueue_retranscription,
+            audio::retranscription::get_retranscription_queue,
+            audio::retranscription::reorder_retranscription_queue,
+            audio::retranscription::cancel_queued_retranscription,
+            audio::retranscription::clear_failed_retranscription_jobs,
+            audio::reprocess::reprocess_recording,
             audio::recording_preferences::get_available_audio_backends,
             audio::recording_preferences::get_current_audio_backend,
             audio::recording_preferences::set_audio_backend,
@@ -825,14 +1506,17 @@ pub fn run() {
             whisper_engine::commands::whisper_init,
             whisper_engine::commands::whisper_get_available_models,
             whisper_engine::commands::whisper_load_model,
+            whisper_engine::commands::whisper_load_model_from_path,
             whisper_engine::commands::whisper_get_current_model,
             whisper_engine::commands::whisper_is_model_loaded,
             whisper_engine::commands::whisper_has_available_models,
             whisper_engine::commands::whisper_validate_model_ready,
+            whisper_engine::commands::whisper_warmup,
             whisper_engine::commands::whisper_transcribe_audio,
             whisper_engine::commands::whisper_get_models_directory,
             whisper_engine::commands::whisper_download_model,
             whisper_engine::commands::whisper_cancel_download,
+            downloads::get_all_download_progress,
             whisper_engine::commands::whisper_delete_model,
             whisper_engine::commands::open_models_folder,
             // Parallel processing
@@ -844,6 +1528,7 @@ pub fn run() {
             whisper_engine::parallel_commands::get_parallel_processing_status,
             whisper_engine::parallel_commands::get_system_resources,
             whisper_engine::parallel_commands::check_resource_constraints,
+            whisper_engine::parallel_commands::get_inference_resource_usage,
             whisper_engine::parallel_commands::calculate_optimal_workers,
             whisper_engine::parallel_commands::prepare_audio_chunks,
             whisper_engine::parallel_commands::test_parallel_processing_setup,
@@ -862,6 +1547,10 @@ pub fn run() {
             set_language_preference,
             // Hardware recommendations
             get_hardware_recommendations,
+            get_llm_model_recommendations,
+            // Onboarding model setup
+            onboarding::auto_setup_models,
+            onboarding::cancel_auto_setup_models,
             // Audio processing controls (per-source)
             get_mic_rnnoise_enabled,
             set_mic_rnnoise_enabled,
@@ -875,6 +1564,29 @@ pub fn run() {
             set_sys_highpass_enabled,
             get_sys_normalizer_enabled,
             set_sys_normalizer_enabled,
+            // Final-pass loudness normalization
+            get_final_loudnorm_enabled,
+            set_final_loudnorm_enabled,
+            get_trim_silence_on_save,
+            set_trim_silence_on_save,
+            get_trim_min_silence_seconds,
+            set_trim_min_silence_seconds,
+            get_keep_untrimmed_original,
+            set_keep_untrimmed_original,
+            get_record_level_timeline_enabled,
+            set_record_level_timeline_enabled,
+            get_recording_level_timeline,
+            get_save_channel_layout,
+            set_save_channel_layout,
+            audio::analysis::analyze_recording_audio,
+            audio::analysis::detect_silence_gaps,
+            audio::vad::get_vad_settings,
+            audio::vad::set_vad_settings,
+            audio::vad::get_live_chunk_seconds,
+            audio::vad::set_live_chunk_seconds,
+            // Bulk re-encoding of saved recordings
+            audio::post_processor::compress_recordings,
+            audio::post_processor::remix_recording,
             // Legacy noise suppression (backward compat)
             get_noise_suppression_enabled,
             set_noise_suppression_enabled,
@@ -886,6 +1598,7 @@ pub fn run() {
             // Database commands - Recordings
             db_create_recording,
             db_get_recording,
+            db_get_recording_full,
             db_get_all_recordings,
             db_get_recent_recordings,
             db_update_recording,
@@ -897,22 +1610,54 @@ pub fn run() {
             db_get_transcript_segments,
             db_replace_transcripts,
             db_update_speaker_label,
+            db_assign_speaker_range,
+            db_get_all_speakers_summary,
+            meeting_analytics::compute_meeting_analytics,
+            transcript_keywords::get_transcript_keywords,
+            insights::extract_insights,
+            transcript_cleanup::cleanup_transcript,
+            transcript_redaction::redact_transcript,
+            db_get_transcript_versions,
+            db_delete_transcript_version,
             db_update_transcript_text,
+            db_undo_last_transcript_edit,
+            db_recompute_display_times,
+            get_transcript_segments_since,
             // Database commands - Categories
+            db_get_recording_speaker_embeddings,
             db_get_all_categories,
+            db_get_categories_with_counts,
             db_create_category,
             db_assign_category,
             db_remove_category,
             db_delete_category,
             // Database commands - Tags
             db_get_all_tags,
+            db_get_popular_tags,
             db_create_tag,
             db_assign_tag,
             db_remove_tag,
             db_delete_tag,
             db_get_or_create_tag,
+            db_delete_unused_tags,
+            get_auto_cleanup_unused_tags,
+            set_auto_cleanup_unused_tags,
+            auto_summary::get_auto_summarize_on_complete,
+            auto_summary::set_auto_summarize_on_complete,
+            webhook::get_completion_webhook_url,
+            webhook::set_completion_webhook_url,
             // Database commands - Search
             db_search_recordings,
+            db_search_chat_messages,
+            db_global_search,
+            export::export_recordings_bulk,
+            metrics::get_metrics_endpoint,
+            metrics::set_metrics_endpoint,
+            file_log::get_log_file_path,
+            file_log::set_file_logging_enabled,
+            diagnostics::export_diagnostics,
+            audio::ffmpeg::check_ffmpeg,
+            audio::ffmpeg_download::download_ffmpeg,
             // Diarization commands
             diarization::engine::init_diarization,
             diarization::engine::diarize_audio,
@@ -920,6 +1665,11 @@ pub fn run() {
             diarization::engine::get_registered_speakers,
             diarization::engine::delete_registered_speaker,
             diarization::engine::rename_speaker,
+            diarization::engine::rematch_speaker_across_recordings,
+            diarization::engine::enroll_speaker_from_recording,
+            diarization::evaluation::diarization_evaluate,
+            diarization::rttm::export_speaker_rttm,
+            diarization::rttm::import_speaker_rttm,
             // Diarization model management
             diarization::model_manager::download_diarization_models,
             diarization::model_manager::check_diarization_models,
@@ -927,6 +1677,10 @@ pub fn run() {
             // Live diarization control
             set_live_diarization_enabled,
             get_live_diarization_enabled,
+            get_source_speaker_labels,
+            set_source_speaker_labels,
+            get_live_rolling_context_enabled,
+            set_live_rolling_context_enabled,
             // Sortformer diarization
             diarization::sortformer_provider::init_sortformer,
             diarization::sortformer_provider::is_sortformer_model_available,
@@ -946,6 +1700,9 @@ pub fn run() {
             llm_engine::commands::llm_is_ready,
             // LLM commands - Ollama specific
             llm_engine::commands::llm_ollama_check_connection,
+            // LLM commands - Diagnostics
+            llm_engine::commands::llm_test_provider,
+            llm_engine::commands::llm_warmup,
             // LLM commands - Completion
             llm_engine::commands::llm_complete,
             llm_engine::commands::llm_complete_streaming,
@@ -965,6 +1722,8 @@ pub fn run() {
             // LLM model tool support commands
             llm_engine::commands::llm_get_model_tool_support,
             llm_engine::commands::llm_set_model_tool_support,
+            llm_engine::commands::llm_get_tool_prompt_template,
+            llm_engine::commands::llm_set_tool_prompt_template,
             llm_engine::commands::llm_delete_model_tool_support,
             llm_engine::commands::llm_get_all_model_configs,
             llm_engine::commands::llm_get_effective_tool_support,
@@ -986,6 +1745,12 @@ pub fn run() {
             chat::message_commands::chat_delete_history,
             chat::message_commands::chat_is_processing,
             chat::message_commands::chat_get_pending_messages,
+            chat::message_commands::chat_regenerate_with_model,
+            chat::message_commands::chat_estimate_context_tokens,
+            chat::message_commands::chat_compact_session,
+            chat::message_commands::chat_suggest_followups,
+            chat::message_commands::chat_get_archived_messages,
+            chat::message_commands::chat_preview_tools,
             // Template commands
             templates::commands::template_list,
             templates::commands::template_get,
@@ -1022,6 +1787,19 @@ pub fn run() {
             mcp::commands::mcp_get_server_tools,
             mcp::commands::mcp_get_running_servers,
         ])
-        .run(tauri::generate_context!())
-        .expect("error while running tauri application");
+        .build(tauri::generate_context!())
+        .expect("error while building tauri application")
+        .run(|app_handle, event| {
+            // Closing the last window or quitting mid-recording would drop in-flight transcripts
+            if let tauri::RunEvent::ExitRequested { code, api, .. } = event {
+                if !RECORDING_FLAG.load(Ordering::SeqCst) {
+                    return;
+                }
+                api.prevent_exit();
+                if !SHUTDOWN_IN_PROGRESS.swap(true, Ordering::SeqCst) {
+                    let app = app_handle.clone();
+                    tauri::async_runtime::spawn(stop_recording_before_exit(app, code.unwrap_or(0)));
+                }
+            }
+        });
 }
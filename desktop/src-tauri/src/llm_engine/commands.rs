@@ -10,6 +10,7 @@ use crate::llm_engine::provider::{
     ProviderCapabilities, ProviderType,
 };
 use crate::state::AppState;
+use crate::task_scheduler::{self, TaskKind};
 
 /// Provider info for frontend
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -150,6 +151,7 @@ pub async fn llm_complete(
     state: State<'_, AppState>,
     request: CompletionRequestInput,
 ) -> Result<CompletionResponse, String> {
+    let _permit = task_scheduler::acquire(TaskKind::Llm, "LLM completion").await?;
     let engine = state.llm_engine.read().await;
 
     let completion_request = CompletionRequest {
@@ -172,6 +174,7 @@ pub async fn llm_complete_streaming(
     request: CompletionRequestInput,
     event_id: String,
 ) -> Result<CompletionResponse, String> {
+    let _permit = task_scheduler::acquire(TaskKind::Llm, format!("LLM completion {}", event_id)).await?;
     let engine = state.llm_engine.read().await;
 
     let completion_request = CompletionRequest {
@@ -225,11 +228,52 @@ pub async fn llm_is_model_downloaded(
     Ok(manager.is_downloaded(&model_id))
 }
 
-/// Delete a downloaded model
+/// Delete a downloaded model.
+/// If the sidecar has it loaded it is shut down first, which is refused while
+/// a chat or other LLM task may be using it. The default model setting is
+/// cleared when it points at the deleted model.
 #[tauri::command]
 pub async fn llm_delete_model(state: State<'_, AppState>, model_id: String) -> Result<(), String> {
-    let manager = state.llm_model_manager.read().await;
-    manager.delete_model(&model_id).map_err(|e| e.to_string())
+    {
+        let engine = state.llm_engine.read().await;
+        if let Some(provider) = engine.get_provider(&ProviderType::Embedded) {
+            if provider.current_model().await.as_deref() == Some(model_id.as_str()) {
+                if !crate::chat::task_registry::ACTIVE_CHAT_TASKS.is_empty()
+                    || crate::task_scheduler::has_tasks(crate::task_scheduler::TaskKind::Llm)
+                {
+                    return Err(format!(
+                        "Cannot delete model '{}' while it is generating. Try again when it has finished.",
+                        model_id
+                    ));
+                }
+                provider
+                    .shutdown()
+                    .await
+                    .map_err(|e| format!("Failed to unload model '{}' before deleting it: {}", model_id, e))?;
+                log::info!("Unloaded LLM model {} before deleting it", model_id);
+            }
+        }
+    }
+
+    {
+        let manager = state.llm_model_manager.read().await;
+        manager.delete_model(&model_id).map_err(|e| e.to_string())?;
+    }
+
+    let db = state.db().await;
+    let is_embedded_default = db
+        .get_setting("default_llm_provider")
+        .map_err(|e| e.to_string())?
+        .and_then(|p| serde_json::from_value::<ProviderType>(serde_json::Value::String(p)).ok())
+        .map(|p| p == ProviderType::Embedded)
+        .unwrap_or(false);
+    let default_model = db.get_setting("default_llm_model").map_err(|e| e.to_string())?;
+    if is_embedded_default && default_model.as_deref() == Some(model_id.as_str()) {
+        db.delete_setting("default_llm_provider").map_err(|e| e.to_string())?;
+        db.delete_setting("default_llm_model").map_err(|e| e.to_string())?;
+    }
+
+    Ok(())
 }
 
 /// Download a model with progress events
@@ -451,6 +495,40 @@ pub async fn llm_clear_default_model(state: State<'_, AppState>) -> Result<(), S
 
 // === Model Tool Support Commands ===
 
+/// Settings key for the prompt-injection template used with non-native tool models
+pub const TOOL_PROMPT_TEMPLATE_SETTING: &str = "tool_prompt_template";
+
+/// Get the custom tool prompt template (None = built-in template)
+#[tauri::command]
+pub async fn llm_get_tool_prompt_template(
+    state: State<'_, AppState>,
+) -> Result<Option<String>, String> {
+    let db = state.db().await;
+    db.get_setting(TOOL_PROMPT_TEMPLATE_SETTING).map_err(|e| e.to_string())
+}
+
+/// Set the instructions injected for models without native tool calling.
+/// `{tools}` is replaced with the tool list and `{tool_names}` with the tool
+/// names. The model must still answer with `{"tool_call": {"name": ..., "arguments": {...}}}`,
+/// so the template has to describe that format. None or empty restores the built-in template.
+#[tauri::command]
+pub async fn llm_set_tool_prompt_template(
+    state: State<'_, AppState>,
+    template: Option<String>,
+) -> Result<(), String> {
+    let db = state.db().await;
+    match template.filter(|t| !t.trim().is_empty()) {
+        Some(template) => {
+            if !template.contains("tool_call") {
+                return Err("Template must describe the {\"tool_call\": ...} JSON format so tool calls can be parsed".to_string());
+            }
+            db.set_setting(TOOL_PROMPT_TEMPLATE_SETTING, &template, "string")
+                .map_err(|e| e.to_string())
+        }
+        None => db.delete_setting(TOOL_PROMPT_TEMPLATE_SETTING).map_err(|e| e.to_string()),
+    }
+}
+
 /// Get whether a model has native tool support
 /// Returns: Option<bool> - None if no user override, Some(bool) if user has set a preference
 #[tauri::command]
@@ -506,3 +584,269 @@ pub async fn llm_get_effective_tool_support(
 
     Ok(has_native_tool_support_with_override(&model_id, user_override))
 }
+
+// === Diagnostics ===
+
+/// Result of an end-to-end provider test
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ProviderTestResult {
+    pub provider_type: ProviderType,
+    /// Model used for the test (None if no model could be resolved)
+    pub model_id: Option<String>,
+    /// Connection/initialization succeeded
+    pub connected: bool,
+    /// Latency of the plain completion in milliseconds
+    pub latency_ms: Option<u64>,
+    /// Text returned by the plain completion
+    pub response_preview: Option<String>,
+    /// At least one token arrived through the streaming callback
+    pub streaming_ok: bool,
+    /// Tool calling produced a tool call (None if the provider doesn't support tools)
+    pub tool_calling_ok: Option<bool>,
+    /// Errors encountered along the way, in order
+    pub errors: Vec<String>,
+}
+
+const TEST_PROMPT: &str = "Reply with the single word: OK";
+
+/// Test a provider end-to-end: connect/initialize, run a tiny completion,
+/// then check streaming and tool calling.
+///
+/// Uses the default model from settings when it belongs to this provider,
+/// otherwise the currently loaded model, otherwise the first local model.
+#[tauri::command]
+pub async fn llm_test_provider(
+    state: State<'_, AppState>,
+    provider_type: ProviderType,
+) -> Result<ProviderTestResult, String> {
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::sync::Arc;
+    use std::time::Instant;
+
+    use crate::llm_engine::provider::ToolDefinition;
+
+    let engine = state.llm_engine.read().await;
+    let provider = engine
+        .get_provider(&provider_type)
+        .ok_or_else(|| format!("Provider {:?} not registered", provider_type))?;
+
+    let mut result = ProviderTestResult {
+        provider_type: provider_type.clone(),
+        model_id: None,
+        connected: false,
+        latency_ms: None,
+        response_preview: None,
+        streaming_ok: false,
+        tool_calling_ok: None,
+        errors: Vec::new(),
+    };
+
+    // Ollama: make sure the server is reachable before anything else
+    if provider_type == ProviderType::Ollama {
+        if let Err(e) = engine.ollama_check_connection().await {
+            result.errors.push(format!("Connection check failed: {}", e));
+            return Ok(result);
+        }
+    }
+
+    // Resolve which model to test with
+    let default_model = {
+        let db = state.db().await;
+        let default_provider = db.get_setting("default_llm_provider").ok().flatten();
+        let matches_provider = default_provider
+            .and_then(|p| serde_json::from_value::<ProviderType>(serde_json::Value::String(p)).ok())
+            .map(|p| p == provider_type)
+            .unwrap_or(false);
+        if matches_provider {
+            db.get_setting("default_llm_model").ok().flatten()
+        } else {
+            None
+        }
+    };
+
+    let model_id = match default_model {
+        Some(m) => Some(m),
+        None => match provider.current_model().await {
+            Some(m) => Some(m),
+            None => provider
+                .list_models()
+                .await
+                .ok()
+                .and_then(|models| models.into_iter().find(|m| m.is_local).map(|m| m.id)),
+        },
+    };
+
+    let Some(model_id) = model_id else {
+        result
+            .errors
+            .push("No model available for this provider".to_string());
+        return Ok(result);
+    };
+    result.model_id = Some(model_id.clone());
+
+    // Load the model if it isn't already
+    if !provider.is_ready().await || provider.current_model().await.as_deref() != Some(&model_id) {
+        if let Err(e) = provider.initialize(&model_id).await {
+            result.errors.push(format!("Initialization failed: {}", e));
+            return Ok(result);
+        }
+    }
+    result.connected = true;
+
+    // Plain completion with latency
+    let request = CompletionRequest {
+        messages: vec![Message::user(TEST_PROMPT)],
+        max_tokens: Some(16),
+        temperature: Some(0.0),
+        ..Default::default()
+    };
+    let started = Instant::now();
+    match provider.complete(request.clone()).await {
+        Ok(response) => {
+            result.latency_ms = Some(started.elapsed().as_millis() as u64);
+            result.response_preview = Some(response.content.trim().chars().take(100).collect());
+        }
+        Err(e) => result.errors.push(format!("Completion failed: {}", e)),
+    }
+
+    // Streaming
+    let token_count = Arc::new(AtomicUsize::new(0));
+    let token_count_cb = token_count.clone();
+    let callback = Box::new(move |_token: String| {
+        token_count_cb.fetch_add(1, Ordering::Relaxed);
+    });
+    let streaming_request = CompletionRequest {
+        stream: true,
+        ..request
+    };
+    match provider.complete_streaming(streaming_request, callback, None).await {
+        Ok(_) => result.streaming_ok = token_count.load(Ordering::Relaxed) > 0,
+        Err(e) => result.errors.push(format!("Streaming failed: {}", e)),
+    }
+
+    // Tool calling
+    if provider.capabilities().function_calling {
+        let tool_request = CompletionRequest {
+            messages: vec![Message::user("What time is it? Use the available tool.")],
+            max_tokens: Some(128),
+            temperature: Some(0.0),
+            tools: Some(vec![ToolDefinition {
+                name: "get_current_time".to_string(),
+                description: "Get the current time".to_string(),
+                parameters: serde_json::json!({ "type": "object", "properties": {} }),
+            }]),
+            tool_choice: Some("required".to_string()),
+            ..Default::default()
+        };
+        match provider.complete(tool_request).await {
+            Ok(response) => {
+                let called = response
+                    .tool_calls
+                    .map(|calls| !calls.is_empty())
+                    .unwrap_or(false);
+                result.tool_calling_ok = Some(called);
+            }
+            Err(e) => {
+                result.tool_calling_ok = Some(false);
+                result.errors.push(format!("Tool calling failed: {}", e));
+            }
+        }
+    }
+
+    log::info!(
+        "LLM provider test for {:?} ({}): connected={}, latency={:?}ms, streaming={}, tools={:?}",
+        provider_type,
+        model_id,
+        result.connected,
+        result.latency_ms,
+        result.streaming_ok,
+        result.tool_calling_ok
+    );
+
+    Ok(result)
+}
+
+/// Result of an LLM warmup run
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LlmWarmupResult {
+    pub provider_type: ProviderType,
+    pub model_id: String,
+    /// Time spent loading the model (0 if it was already loaded)
+    pub load_ms: u64,
+    /// Time spent on the dummy completion
+    pub inference_ms: u64,
+}
+
+/// Load the default LLM model and run a one-token completion to prime it.
+/// Emits `llm-warmup-complete` or `llm-warmup-failed`.
+#[tauri::command]
+pub async fn llm_warmup(
+    app_handle: tauri::AppHandle,
+    state: State<'_, AppState>,
+) -> Result<LlmWarmupResult, String> {
+    let result = run_llm_warmup(&state).await;
+
+    match &result {
+        Ok(warmup) => {
+            log::info!(
+                "LLM warmup complete: {:?}/{}, load={}ms, inference={}ms",
+                warmup.provider_type, warmup.model_id, warmup.load_ms, warmup.inference_ms
+            );
+            let _ = app_handle.emit("llm-warmup-complete", warmup);
+        }
+        Err(error) => {
+            log::warn!("LLM warmup failed: {}", error);
+            let _ = app_handle.emit("llm-warmup-failed", serde_json::json!({ "error": error }));
+        }
+    }
+
+    result
+}
+
+async fn run_llm_warmup(state: &State<'_, AppState>) -> Result<LlmWarmupResult, String> {
+    use std::time::Instant;
+
+    let (provider, model) = {
+        let db = state.db().await;
+        (
+            db.get_setting("default_llm_provider").map_err(|e| e.to_string())?,
+            db.get_setting("default_llm_model").map_err(|e| e.to_string())?,
+        )
+    };
+
+    let provider_type = provider
+        .and_then(|p| serde_json::from_value::<ProviderType>(serde_json::Value::String(p)).ok())
+        .ok_or_else(|| "No default LLM provider configured".to_string())?;
+    let model_id = model.ok_or_else(|| "No default LLM model configured".to_string())?;
+
+    let engine = state.llm_engine.read().await;
+    engine
+        .set_active_provider(provider_type.clone())
+        .await
+        .map_err(|e| e.to_string())?;
+
+    let load_started = Instant::now();
+    let load_ms = if engine.is_ready().await && engine.current_model().await.as_deref() == Some(&model_id) {
+        0
+    } else {
+        engine.initialize(&model_id).await.map_err(|e| e.to_string())?;
+        load_started.elapsed().as_millis() as u64
+    };
+
+    let request = CompletionRequest {
+        messages: vec![Message::user("Hi")],
+        max_tokens: Some(1),
+        temperature: Some(0.0),
+        ..Default::default()
+    };
+    let inference_started = Instant::now();
+    engine.complete(request).await.map_err(|e| e.to_string())?;
+    let inference_ms = inference_started.elapsed().as_millis() as u64;
+
+    Ok(LlmWarmupResult {
+        provider_type,
+        model_id,
+        load_ms,
+        inference_ms,
+    })
+}
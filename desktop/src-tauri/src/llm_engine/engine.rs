@@ -10,7 +10,7 @@ use std::path::PathBuf;
 
 use crate::llm_engine::provider::{
     CompletionRequest, CompletionResponse, LlmError, LlmModelInfo, LlmProvider,
-    ProviderCapabilities, ProviderType, StreamCallback,
+    ProviderCapabilities, ProviderType, StreamCallback, TokenCount, ToolCallCallback,
 };
 use crate::llm_engine::providers::{OllamaProvider, SidecarProvider, SidecarConfig};
 
@@ -61,6 +61,24 @@ impl LlmEngine {
         }
     }
 
+    /// Load embedded models from another directory. The embedded provider is
+    /// shut down and replaced, so a model it had loaded must be loaded again;
+    /// the active provider stays selected.
+    pub async fn set_models_dir(&mut self, models_dir: PathBuf) {
+        if let Some(provider) = self.providers.get(&ProviderType::Embedded) {
+            if let Err(e) = provider.shutdown().await {
+                log::warn!("Failed to shut down embedded provider: {}", e);
+            }
+        }
+        self.providers.insert(
+            ProviderType::Embedded,
+            Arc::new(SidecarProvider::new(SidecarConfig {
+                models_dir,
+                ..SidecarConfig::default()
+            })),
+        );
+    }
+
     /// Get list of available provider types
     pub fn available_providers(&self) -> Vec<ProviderType> {
         self.providers.keys().cloned().collect()
@@ -157,7 +175,23 @@ impl LlmEngine {
     /// Run a completion request
     pub async fn complete(&self, request: CompletionRequest) -> Result<CompletionResponse, LlmError> {
         let provider = self.get_active_provider().await?;
-        provider.complete(request).await
+        let response = provider.complete(request).await?;
+        crate::metrics::record_llm_completion(response.completion_tokens);
+        Ok(response)
+    }
+
+    /// Run several independent completions, one result per request in order.
+    /// Providers that support it send them in a single round trip.
+    pub async fn complete_batch(
+        &self,
+        requests: Vec<CompletionRequest>,
+    ) -> Result<Vec<Result<CompletionResponse, LlmError>>, LlmError> {
+        let provider = self.get_active_provider().await?;
+        let responses = provider.complete_batch(requests).await?;
+        for response in responses.iter().flatten() {
+            crate::metrics::record_llm_completion(response.completion_tokens);
+        }
+        Ok(responses)
     }
 
     /// Run a streaming completion request
@@ -169,7 +203,39 @@ impl LlmEngine {
         cancel_token: Option<tokio_util::sync::CancellationToken>,
     ) -> Result<CompletionResponse, LlmError> {
         let provider = self.get_active_provider().await?;
-        provider.complete_streaming(request, callback, cancel_token).await
+        let response = provider.complete_streaming(request, callback, cancel_token).await?;
+        crate::metrics::record_llm_completion(response.completion_tokens);
+        Ok(response)
+    }
+
+    /// Run a streaming completion that also reports tool calls as they generate
+    pub async fn complete_streaming_with_tool_calls(
+        &self,
+        request: CompletionRequest,
+        callback: StreamCallback,
+        tool_callback: ToolCallCallback,
+        cancel_token: Option<tokio_util::sync::CancellationToken>,
+    ) -> Result<CompletionResponse, LlmError> {
+        let provider = self.get_active_provider().await?;
+        let response = provider
+            .complete_streaming_with_tool_calls(request, callback, tool_callback, cancel_token)
+            .await?;
+        crate::metrics::record_llm_completion(response.completion_tokens);
+        Ok(response)
+    }
+
+    /// Count the prompt tokens of a request with the active provider
+    pub async fn count_tokens(&self, request: &CompletionRequest) -> Result<TokenCount, LlmError> {
+        let provider = self.get_active_provider().await?;
+        provider.count_tokens(request).await
+    }
+
+    /// Release per-conversation state held by the active provider
+    pub async fn end_session(&self, session_id: &str) -> Result<(), LlmError> {
+        if let Ok(provider) = self.get_active_provider().await {
+            provider.end_session(session_id).await?;
+        }
+        Ok(())
     }
 
     /// Shutdown the active provider
@@ -5,6 +5,7 @@ use std::path::PathBuf;
 use crate::llm_engine::provider::LlmError;
 use super::types::{DownloadProgress, DownloadStatus};
 use super::registry::available_models;
+use crate::downloads::{DownloadHandle, DownloadKind};
 
 /// Download a model with progress callback
 /// Returns the path to the downloaded model
@@ -23,6 +24,7 @@ where
         .ok_or_else(|| LlmError::ModelNotFound(model_id.to_string()))?;
 
     let dest_path = models_dir.join(format!("{}.gguf", model_id));
+    let tracker = DownloadHandle::start(DownloadKind::Llm, model_id);
 
     // Report starting
     on_progress(DownloadProgress {
@@ -81,6 +83,7 @@ where
             .map_err(|e| LlmError::Other(format!("Failed to write chunk: {}", e)))?;
 
         downloaded += chunk.len() as u64;
+        tracker.update(downloaded, total_size);
         let percent = (downloaded as f32 / total_size as f32) * 100.0;
 
         on_progress(DownloadProgress {
@@ -99,6 +102,7 @@ where
     drop(file);
 
     // Verify download (basic size check)
+    tracker.update_with_status(downloaded, total_size, "verifying");
     on_progress(DownloadProgress {
         model_id: model_id.to_string(),
         downloaded_bytes: downloaded,
@@ -179,6 +183,8 @@ where
         )));
     }
 
+    let tracker = DownloadHandle::start(DownloadKind::Llm, &model_id);
+
     // Report starting
     on_progress(DownloadProgress {
         model_id: model_id.clone(),
@@ -233,6 +239,7 @@ where
             .map_err(|e| LlmError::Other(format!("Failed to write chunk: {}", e)))?;
 
         downloaded += chunk.len() as u64;
+        tracker.update(downloaded, total_size);
         let percent = if total_size > 0 {
             (downloaded as f32 / total_size as f32) * 100.0
         } else {
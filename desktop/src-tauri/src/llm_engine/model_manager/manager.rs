@@ -17,8 +17,11 @@ pub struct LlmModelManager {
 impl LlmModelManager {
     /// Create a new model manager
     pub fn new(app_data_dir: PathBuf) -> Self {
-        let models_dir = app_data_dir.join("llm_models");
+        Self::with_models_dir(app_data_dir.join("llm_models"))
+    }
 
+    /// Create a model manager storing models directly in `models_dir`
+    pub fn with_models_dir(models_dir: PathBuf) -> Self {
         // Ensure directory exists
         if !models_dir.exists() {
             std::fs::create_dir_all(&models_dir).ok();
@@ -25,6 +25,8 @@ pub enum LlmError {
     DownloadFailed(String),
     /// Inference/completion failed
     InferenceFailed(String),
+    /// Not enough (GPU) memory to load the model or run inference
+    OutOfMemory(String),
     /// Provider not initialized
     NotInitialized,
     /// Generic error
@@ -42,6 +44,7 @@ impl fmt::Display for LlmError {
             LlmError::InvalidRequest(msg) => write!(f, "Invalid request: {}", msg),
             LlmError::DownloadFailed(msg) => write!(f, "Download failed: {}", msg),
             LlmError::InferenceFailed(msg) => write!(f, "Inference failed: {}", msg),
+            LlmError::OutOfMemory(msg) => write!(f, "Out of memory (try a smaller model): {}", msg),
             LlmError::NotInitialized => write!(f, "Provider not initialized"),
             LlmError::Other(msg) => write!(f, "{}", msg),
         }
@@ -163,6 +166,19 @@ pub struct CompletionRequest {
     /// Tool choice: "auto", "none", or "required"
     #[serde(skip_serializing_if = "Option::is_none")]
     pub tool_choice: Option<String>,
+    /// Conversation this request belongs to. Embedded models use it to keep
+    /// the KV cache for earlier turns warm.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub session_id: Option<String>,
+    /// Whether the model handles the system role natively. When false, system
+    /// messages are merged into the first user message. None lets the
+    /// provider decide from the model name.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub native_system_role: Option<bool>,
+    /// Instructions used when a provider injects tools into the prompt for
+    /// models without native tool calling. None uses the built-in template.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub tool_prompt_template: Option<String>,
 }
 
 impl Default for CompletionRequest {
@@ -176,6 +192,9 @@ impl Default for CompletionRequest {
             stream: false,
             tools: None,
             tool_choice: None,
+            session_id: None,
+            native_system_role: None,
+            tool_prompt_template: None,
         }
     }
 }
@@ -213,6 +232,40 @@ pub struct CompletionResponse {
     pub tool_calls: Option<Vec<ToolCall>>,
 }
 
+/// Prompt size of a completion request
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TokenCount {
+    /// Number of tokens in the prompt
+    pub prompt_tokens: usize,
+    /// Context window of the loaded model, if known
+    pub context_size: Option<usize>,
+    /// Whether the count came from the model's tokenizer (false = estimate)
+    pub exact: bool,
+}
+
+impl TokenCount {
+    /// Rough estimate (~4 characters per token plus per-message overhead) for
+    /// providers that can't tokenize locally; tool definitions count as their JSON
+    pub fn estimate(request: &CompletionRequest) -> Self {
+        let message_tokens: usize = request
+            .messages
+            .iter()
+            .map(|m| m.content.chars().count().div_ceil(4) + 4)
+            .sum();
+        let tool_tokens = request
+            .tools
+            .as_ref()
+            .and_then(|tools| serde_json::to_string(tools).ok())
+            .map_or(0, |json| json.chars().count().div_ceil(4));
+        let prompt_tokens = message_tokens + tool_tokens;
+        Self {
+            prompt_tokens,
+            context_size: None,
+            exact: false,
+        }
+    }
+}
+
 /// Information about an available model
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct LlmModelInfo {
@@ -256,6 +309,24 @@ pub struct ProviderCapabilities {
 /// Callback for streaming responses
 pub type StreamCallback = Box<dyn Fn(String) + Send + Sync>;
 
+/// Incremental tool call update received while streaming
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ToolCallDelta {
+    /// Position of the call among the response's tool calls
+    pub index: usize,
+    #[serde(default)]
+    pub id: String,
+    /// Function name (may be empty until generated)
+    #[serde(default)]
+    pub name: String,
+    /// Argument text generated since the previous delta for this call
+    #[serde(default)]
+    pub arguments: String,
+}
+
+/// Callback for streamed tool call updates
+pub type ToolCallCallback = Box<dyn Fn(ToolCallDelta) + Send + Sync>;
+
 /// The main trait that all LLM providers must implement
 #[async_trait]
 pub trait LlmProvider: Send + Sync {
@@ -280,6 +351,20 @@ pub trait LlmProvider: Send + Sync {
     /// Run a completion request (non-streaming)
     async fn complete(&self, request: CompletionRequest) -> Result<CompletionResponse, LlmError>;
 
+    /// Run several independent non-streaming completions, returning one result
+    /// per request in order. Providers that can send them in one round trip
+    /// override this; the default runs them one at a time.
+    async fn complete_batch(
+        &self,
+        requests: Vec<CompletionRequest>,
+    ) -> Result<Vec<Result<CompletionResponse, LlmError>>, LlmError> {
+        let mut responses = Vec::with_capacity(requests.len());
+        for request in requests {
+            responses.push(self.complete(request).await);
+        }
+        Ok(responses)
+    }
+
     /// Run a completion request with streaming
     /// The callback is called for each token/chunk received
     /// Optional cancel_token allows cancelling the stream mid-generation
@@ -290,6 +375,30 @@ pub trait LlmProvider: Send + Sync {
         cancel_token: Option<tokio_util::sync::CancellationToken>,
     ) -> Result<CompletionResponse, LlmError>;
 
+    /// Streaming completion that also reports tool call names/arguments as they
+    /// are generated. The final assembled calls are still returned in the response.
+    /// Providers without incremental tool calls fall back to plain streaming.
+    async fn complete_streaming_with_tool_calls(
+        &self,
+        request: CompletionRequest,
+        callback: StreamCallback,
+        _tool_callback: ToolCallCallback,
+        cancel_token: Option<tokio_util::sync::CancellationToken>,
+    ) -> Result<CompletionResponse, LlmError> {
+        self.complete_streaming(request, callback, cancel_token).await
+    }
+
+    /// Count the prompt tokens of a request without running it.
+    /// Providers without a local tokenizer return an estimate.
+    async fn count_tokens(&self, request: &CompletionRequest) -> Result<TokenCount, LlmError> {
+        Ok(TokenCount::estimate(request))
+    }
+
+    /// Release any per-conversation state (e.g. cached prompt prefixes)
+    async fn end_session(&self, _session_id: &str) -> Result<(), LlmError> {
+        Ok(())
+    }
+
     /// Shutdown the provider and release resources
     async fn shutdown(&self) -> Result<(), LlmError>;
 }
@@ -22,7 +22,8 @@ const CREATE_NO_WINDOW: u32 = 0x08000000;
 
 use crate::llm_engine::provider::{
     CompletionRequest, CompletionResponse, FunctionCall, LlmError, LlmModelInfo, LlmProvider,
-    Message, MessageRole, ProviderCapabilities, StreamCallback, ToolCall,
+    Message, MessageRole, ProviderCapabilities, StreamCallback, TokenCount, ToolCall,
+    ToolCallCallback, ToolCallDelta,
 };
 
 // ============================================================================
@@ -62,6 +63,40 @@ struct JsonRpcError {
     message: String,
 }
 
+/// Error codes returned by the sidecar (see `error_codes` in llm-sidecar)
+mod error_codes {
+    pub const PARSE_ERROR: i32 = -32700;
+    pub const INVALID_REQUEST: i32 = -32600;
+    pub const METHOD_NOT_FOUND: i32 = -32601;
+    pub const INVALID_PARAMS: i32 = -32602;
+    pub const MODEL_NOT_FOUND: i32 = -32001;
+    pub const INVALID_MODEL: i32 = -32002;
+    pub const MODEL_LOAD_FAILED: i32 = -32003;
+    pub const OUT_OF_MEMORY: i32 = -32004;
+    pub const NO_MODEL_LOADED: i32 = -32005;
+    pub const INFERENCE_FAILED: i32 = -32006;
+}
+
+impl From<JsonRpcError> for LlmError {
+    fn from(error: JsonRpcError) -> Self {
+        match error.code {
+            error_codes::MODEL_NOT_FOUND => LlmError::ModelNotFound(error.message),
+            error_codes::INVALID_MODEL | error_codes::MODEL_LOAD_FAILED => LlmError::ModelLoadFailed(error.message),
+            error_codes::OUT_OF_MEMORY => LlmError::OutOfMemory(error.message),
+            error_codes::NO_MODEL_LOADED => LlmError::NotInitialized,
+            error_codes::INFERENCE_FAILED => LlmError::InferenceFailed(error.message),
+            error_codes::PARSE_ERROR
+            | error_codes::INVALID_REQUEST
+            | error_codes::METHOD_NOT_FOUND
+            | error_codes::INVALID_PARAMS => LlmError::InvalidRequest(error.message),
+            _ => LlmError::RequestFailed(error.message),
+        }
+    }
+}
+
+/// Largest JSON-RPC message the sidecar accepts (must match the sidecar's MAX_MESSAGE_BYTES)
+const MAX_MESSAGE_BYTES: usize = 64 * 1024 * 1024;
+
 // ============================================================================
 // Configuration
 // ============================================================================
@@ -72,6 +107,8 @@ pub struct SidecarConfig {
     pub models_dir: PathBuf,
     /// Path to the sidecar binary
     pub sidecar_path: Option<PathBuf>,
+    /// Send independent requests as a single JSON-RPC batch
+    pub batch_requests: bool,
 }
 
 impl Default for SidecarConfig {
@@ -82,6 +119,7 @@ impl Default for SidecarConfig {
                 .join("meeting-local")
                 .join("llm_models"),
             sidecar_path: None,
+            batch_requests: true,
         }
     }
 }
@@ -98,16 +136,18 @@ struct SidecarProcess {
 }
 
 impl SidecarProcess {
-    async fn send_request(&mut self, method: &str, params: serde_json::Value) -> Result<serde_json::Value, LlmError> {
-        self.request_id += 1;
-        let request = JsonRpcRequest::new(self.request_id, method, params);
-
-        let request_json = serde_json::to_string(&request)
-            .map_err(|e| LlmError::RequestFailed(format!("Failed to serialize request: {}", e)))?;
+    /// Write one newline-delimited JSON message, rejecting oversized payloads up front
+    async fn write_message(&mut self, json: &str) -> Result<(), LlmError> {
+        if json.len() > MAX_MESSAGE_BYTES {
+            return Err(LlmError::InvalidRequest(format!(
+                "Request too large for the embedded model: {:.1} MB (max {} MB). Try a shorter transcript or conversation.",
+                json.len() as f64 / (1024.0 * 1024.0),
+                MAX_MESSAGE_BYTES / (1024 * 1024),
+            )));
+        }
 
-        // Send request
         self.stdin
-            .write_all(request_json.as_bytes())
+            .write_all(json.as_bytes())
             .await
             .map_err(|e| LlmError::RequestFailed(format!("Failed to write to sidecar: {}", e)))?;
         self.stdin
@@ -117,7 +157,18 @@ impl SidecarProcess {
         self.stdin
             .flush()
             .await
-            .map_err(|e| LlmError::RequestFailed(format!("Failed to flush: {}", e)))?;
+            .map_err(|e| LlmError::RequestFailed(format!("Failed to flush: {}", e)))
+    }
+
+    async fn send_request(&mut self, method: &str, params: serde_json::Value) -> Result<serde_json::Value, LlmError> {
+        self.request_id += 1;
+        let request = JsonRpcRequest::new(self.request_id, method, params);
+
+        let request_json = serde_json::to_string(&request)
+            .map_err(|e| LlmError::RequestFailed(format!("Failed to serialize request: {}", e)))?;
+
+        // Send request
+        self.write_message(&request_json).await?;
 
         // Read response
         let mut line = String::new();
@@ -130,17 +181,52 @@ impl SidecarProcess {
             .map_err(|e| LlmError::RequestFailed(format!("Failed to parse response: {}", e)))?;
 
         if let Some(error) = response.error {
-            return Err(LlmError::RequestFailed(error.message));
+            return Err(error.into());
         }
 
         response.result.ok_or_else(|| LlmError::RequestFailed("Empty response".to_string()))
     }
 
+    /// Send several requests as one JSON-RPC batch and return their results in order
+    async fn send_batch(
+        &mut self,
+        calls: Vec<(&str, serde_json::Value)>,
+    ) -> Result<Vec<Result<serde_json::Value, LlmError>>, LlmError> {
+        let first_id = self.request_id + 1;
+        let requests: Vec<JsonRpcRequest> = calls
+            .into_iter()
+            .map(|(method, params)| {
+                self.request_id += 1;
+                JsonRpcRequest::new(self.request_id, method, params)
+            })
+            .collect();
+        let count = requests.len();
+
+        let request_json = serde_json::to_string(&requests)
+            .map_err(|e| LlmError::RequestFailed(format!("Failed to serialize batch: {}", e)))?;
+
+        // Send batch
+        self.write_message(&request_json).await?;
+
+        // Read batch response (a single array line)
+        let mut line = String::new();
+        self.stdout
+            .read_line(&mut line)
+            .await
+            .map_err(|e| LlmError::RequestFailed(format!("Failed to read from sidecar: {}", e)))?;
+
+        let reply: serde_json::Value = serde_json::from_str(&line)
+            .map_err(|e| LlmError::RequestFailed(format!("Failed to parse batch response: {}", e)))?;
+
+        match_batch_responses(reply, first_id, count)
+    }
+
     async fn send_streaming_request(
         &mut self,
         method: &str,
         params: serde_json::Value,
         callback: &StreamCallback,
+        tool_callback: Option<&ToolCallCallback>,
         cancel_token: Option<&CancellationToken>,
     ) -> Result<serde_json::Value, LlmError> {
         self.request_id += 1;
@@ -150,18 +236,7 @@ impl SidecarProcess {
             .map_err(|e| LlmError::RequestFailed(format!("Failed to serialize request: {}", e)))?;
 
         // Send request
-        self.stdin
-            .write_all(request_json.as_bytes())
-            .await
-            .map_err(|e| LlmError::RequestFailed(format!("Failed to write to sidecar: {}", e)))?;
-        self.stdin
-            .write_all(b"\n")
-            .await
-            .map_err(|e| LlmError::RequestFailed(format!("Failed to write newline: {}", e)))?;
-        self.stdin
-            .flush()
-            .await
-            .map_err(|e| LlmError::RequestFailed(format!("Failed to flush: {}", e)))?;
+        self.write_message(&request_json).await?;
 
         // Read streaming responses with cancellation support
         loop {
@@ -187,7 +262,7 @@ impl SidecarProcess {
                 .map_err(|e| LlmError::RequestFailed(format!("Failed to parse response: {}", e)))?;
 
             if let Some(error) = response.error {
-                return Err(LlmError::RequestFailed(error.message));
+                return Err(error.into());
             }
 
             if let Some(ref result) = response.result {
@@ -196,6 +271,14 @@ impl SidecarProcess {
                     callback(token.to_string());
                 }
 
+                // Partial tool call (name/arguments as they generate)
+                if let (Some(delta), Some(tool_callback)) = (result.get("tool_call_delta"), tool_callback) {
+                    match serde_json::from_value::<ToolCallDelta>(delta.clone()) {
+                        Ok(delta) => tool_callback(delta),
+                        Err(e) => log::warn!("Ignoring malformed tool call delta: {}", e),
+                    }
+                }
+
                 if result.get("done").and_then(|d| d.as_bool()).unwrap_or(false) {
                     return Ok(response.result.unwrap());
                 }
@@ -210,6 +293,140 @@ impl SidecarProcess {
     }
 }
 
+/// Match a batch reply to the requests with ids `first_id..first_id + count`,
+/// returning one result per request in request order. Responses may arrive in
+/// any order; a request without a response gets an error.
+fn match_batch_responses(
+    reply: serde_json::Value,
+    first_id: u64,
+    count: usize,
+) -> Result<Vec<Result<serde_json::Value, LlmError>>, LlmError> {
+    let items = match reply {
+        serde_json::Value::Array(items) => items,
+        // The whole batch was rejected with a single error object
+        other => {
+            return Err(match other.get("error").cloned().map(serde_json::from_value::<JsonRpcError>) {
+                Some(Ok(error)) => error.into(),
+                _ => LlmError::RequestFailed("Batch response is not a list".to_string()),
+            });
+        }
+    };
+
+    let mut results: Vec<Result<serde_json::Value, LlmError>> = (0..count)
+        .map(|_| Err(LlmError::RequestFailed("Missing response in batch".to_string())))
+        .collect();
+    for item in items {
+        let response = match serde_json::from_value::<JsonRpcResponse>(item) {
+            Ok(response) => response,
+            Err(e) => {
+                log::warn!("Ignoring batch response without a valid id: {}", e);
+                continue;
+            }
+        };
+        let Some(index) = response.id.checked_sub(first_id).map(|i| i as usize).filter(|i| *i < count) else {
+            continue;
+        };
+        results[index] = match (response.result, response.error) {
+            (_, Some(error)) => Err(error.into()),
+            (Some(result), None) => Ok(result),
+            (None, None) => Err(LlmError::RequestFailed("Empty response".to_string())),
+        };
+    }
+
+    Ok(results)
+}
+
+// ============================================================================
+// Completion Params
+// ============================================================================
+
+/// Build the sidecar "complete" params for a request
+fn build_complete_params(request: &CompletionRequest, stream: bool) -> serde_json::Value {
+    let messages: Vec<serde_json::Value> = request
+        .messages
+        .iter()
+        .map(|m| {
+            let mut msg = serde_json::json!({
+                "role": match m.role {
+                    MessageRole::System => "system",
+                    MessageRole::User => "user",
+                    MessageRole::Assistant => "assistant",
+                    MessageRole::Tool => "tool",
+                },
+                "content": m.content
+            });
+
+            // Add tool_calls for assistant messages
+            if let Some(ref tool_calls) = m.tool_calls {
+                msg["tool_calls"] = serde_json::to_value(tool_calls).unwrap_or_default();
+            }
+
+            // Add tool_call_id for tool result messages
+            if let Some(ref tool_call_id) = m.tool_call_id {
+                msg["tool_call_id"] = serde_json::Value::String(tool_call_id.clone());
+            }
+
+            msg
+        })
+        .collect();
+
+    // Build params with optional tools
+    let mut params = serde_json::json!({
+        "messages": messages,
+        "max_tokens": request.max_tokens.unwrap_or(512),
+        "stream": stream
+    });
+
+    // Add tools if provided
+    if let Some(ref tools) = request.tools {
+        params["tools"] = serde_json::to_value(tools).unwrap_or_default();
+    }
+    if let Some(ref tool_choice) = request.tool_choice {
+        params["tool_choice"] = serde_json::Value::String(tool_choice.clone());
+    }
+    if let Some(ref session_id) = request.session_id {
+        params["session_id"] = serde_json::Value::String(session_id.clone());
+    }
+    if let Some(native_system_role) = request.native_system_role {
+        params["native_system_role"] = serde_json::Value::Bool(native_system_role);
+    }
+    if let Some(ref template) = request.tool_prompt_template {
+        params["tool_prompt_template"] = serde_json::Value::String(template.clone());
+    }
+
+    params
+}
+
+/// Convert the sidecar's final "complete" result into a CompletionResponse
+fn parse_completion_result(result: &serde_json::Value) -> CompletionResponse {
+    let content = result.get("content")
+        .and_then(|c| c.as_str())
+        .unwrap_or("")
+        .to_string();
+    let model = result.get("model")
+        .and_then(|m| m.as_str())
+        .unwrap_or("unknown")
+        .to_string();
+    let finish_reason = result.get("finish_reason")
+        .and_then(|f| f.as_str())
+        .unwrap_or("stop")
+        .to_string();
+
+    // Parse tool_calls if present
+    let tool_calls: Option<Vec<ToolCall>> = result.get("tool_calls")
+        .and_then(|tc| serde_json::from_value(tc.clone()).ok());
+
+    CompletionResponse {
+        content,
+        model,
+        prompt_tokens: result.get("prompt_tokens").and_then(|t| t.as_u64()).map(|t| t as u32),
+        completion_tokens: result.get("completion_tokens").and_then(|t| t.as_u64()).map(|t| t as u32),
+        truncated: false,
+        finish_reason: Some(finish_reason),
+        tool_calls,
+    }
+}
+
 // ============================================================================
 // Provider Implementation
 // ============================================================================
@@ -352,6 +569,39 @@ impl SidecarProvider {
         Ok(())
     }
 
+    /// Streaming completion shared by `complete_streaming` and `complete_streaming_with_tool_calls`
+    async fn stream_completion(
+        &self,
+        request: CompletionRequest,
+        callback: StreamCallback,
+        tool_callback: Option<ToolCallCallback>,
+        cancel_token: Option<CancellationToken>,
+    ) -> Result<CompletionResponse, LlmError> {
+        self.ensure_sidecar().await?;
+
+        let params = build_complete_params(&request, true);
+
+        let result = {
+            let mut guard = self.process.write().await;
+            let process = guard.as_mut().ok_or(LlmError::NotInitialized)?;
+            process.send_streaming_request("complete", params, &callback, tool_callback.as_ref(), cancel_token.as_ref()).await
+        };
+
+        // Handle cancellation - restart sidecar since generation can't be cleanly stopped
+        match &result {
+            Err(LlmError::RequestFailed(msg)) if msg == "Cancelled" => {
+                log::info!("Streaming cancelled, restarting sidecar");
+                self.restart_sidecar().await?;
+                return Err(LlmError::RequestFailed("Cancelled".to_string()));
+            }
+            Err(e) => return Err(e.clone()),
+            Ok(_) => {}
+        }
+
+        let result = result?;
+        Ok(parse_completion_result(&result))
+    }
+
     /// Get list of available GGUF models
     fn available_models(&self) -> Vec<(String, PathBuf, u64)> {
         let mut models = Vec::new();
@@ -470,80 +720,46 @@ impl LlmProvider for SidecarProvider {
     async fn complete(&self, request: CompletionRequest) -> Result<CompletionResponse, LlmError> {
         self.ensure_sidecar().await?;
 
-        let messages: Vec<serde_json::Value> = request
-            .messages
-            .iter()
-            .map(|m| {
-                let mut msg = serde_json::json!({
-                    "role": match m.role {
-                        MessageRole::System => "system",
-                        MessageRole::User => "user",
-                        MessageRole::Assistant => "assistant",
-                        MessageRole::Tool => "tool",
-                    },
-                    "content": m.content
-                });
-
-                // Add tool_calls for assistant messages
-                if let Some(ref tool_calls) = m.tool_calls {
-                    msg["tool_calls"] = serde_json::to_value(tool_calls).unwrap_or_default();
-                }
-
-                // Add tool_call_id for tool result messages
-                if let Some(ref tool_call_id) = m.tool_call_id {
-                    msg["tool_call_id"] = serde_json::Value::String(tool_call_id.clone());
-                }
+        let params = build_complete_params(&request, false);
 
-                msg
-            })
-            .collect();
+        let mut guard = self.process.write().await;
+        let process = guard.as_mut().ok_or(LlmError::NotInitialized)?;
 
-        // Build params with optional tools
-        let mut params = serde_json::json!({
-            "messages": messages,
-            "max_tokens": request.max_tokens.unwrap_or(512),
-            "stream": false
-        });
+        let result = process.send_request("complete", params).await?;
+        Ok(parse_completion_result(&result))
+    }
 
-        // Add tools if provided
-        if let Some(ref tools) = request.tools {
-            params["tools"] = serde_json::to_value(tools).unwrap_or_default();
+    /// Sent as one JSON-RPC batch when `batch_requests` is enabled, otherwise one at a time
+    async fn complete_batch(
+        &self,
+        requests: Vec<CompletionRequest>,
+    ) -> Result<Vec<Result<CompletionResponse, LlmError>>, LlmError> {
+        if requests.is_empty() {
+            return Ok(Vec::new());
         }
-        if let Some(ref tool_choice) = request.tool_choice {
-            params["tool_choice"] = serde_json::Value::String(tool_choice.clone());
+        if !self.config.batch_requests {
+            let mut responses = Vec::with_capacity(requests.len());
+            for request in requests {
+                responses.push(self.complete(request).await);
+            }
+            return Ok(responses);
         }
 
+        self.ensure_sidecar().await?;
+
+        let calls = requests
+            .iter()
+            .map(|request| ("complete", build_complete_params(request, false)))
+            .collect();
+
         let mut guard = self.process.write().await;
         let process = guard.as_mut().ok_or(LlmError::NotInitialized)?;
 
-        let result = process.send_request("complete", params).await?;
-
-        let content = result.get("content")
-            .and_then(|c| c.as_str())
-            .unwrap_or("")
-            .to_string();
-        let model = result.get("model")
-            .and_then(|m| m.as_str())
-            .unwrap_or("unknown")
-            .to_string();
-        let finish_reason = result.get("finish_reason")
-            .and_then(|f| f.as_str())
-            .unwrap_or("stop")
-            .to_string();
-
-        // Parse tool_calls if present
-        let tool_calls: Option<Vec<ToolCall>> = result.get("tool_calls")
-            .and_then(|tc| serde_json::from_value(tc.clone()).ok());
-
-        Ok(CompletionResponse {
-            content,
-            model,
-            prompt_tokens: None,
-            completion_tokens: None,
-            truncated: false,
-            finish_reason: Some(finish_reason),
-            tool_calls,
-        })
+        let results = process.send_batch(calls).await?;
+        Ok(results
+            .into_iter()
+            .map(|result| result.map(|r| parse_completion_result(&r)))
+            .collect())
     }
 
     async fn complete_streaming(
@@ -552,96 +768,42 @@ impl LlmProvider for SidecarProvider {
         callback: StreamCallback,
         cancel_token: Option<CancellationToken>,
     ) -> Result<CompletionResponse, LlmError> {
-        self.ensure_sidecar().await?;
-
-        let messages: Vec<serde_json::Value> = request
-            .messages
-            .iter()
-            .map(|m| {
-                let mut msg = serde_json::json!({
-                    "role": match m.role {
-                        MessageRole::System => "system",
-                        MessageRole::User => "user",
-                        MessageRole::Assistant => "assistant",
-                        MessageRole::Tool => "tool",
-                    },
-                    "content": m.content
-                });
-
-                // Add tool_calls for assistant messages
-                if let Some(ref tool_calls) = m.tool_calls {
-                    msg["tool_calls"] = serde_json::to_value(tool_calls).unwrap_or_default();
-                }
-
-                // Add tool_call_id for tool result messages
-                if let Some(ref tool_call_id) = m.tool_call_id {
-                    msg["tool_call_id"] = serde_json::Value::String(tool_call_id.clone());
-                }
+        self.stream_completion(request, callback, None, cancel_token).await
+    }
 
-                msg
-            })
-            .collect();
+    async fn complete_streaming_with_tool_calls(
+        &self,
+        request: CompletionRequest,
+        callback: StreamCallback,
+        tool_callback: ToolCallCallback,
+        cancel_token: Option<CancellationToken>,
+    ) -> Result<CompletionResponse, LlmError> {
+        self.stream_completion(request, callback, Some(tool_callback), cancel_token).await
+    }
 
-        // Build params with optional tools
-        let mut params = serde_json::json!({
-            "messages": messages,
-            "max_tokens": request.max_tokens.unwrap_or(512),
-            "stream": true
-        });
+    async fn count_tokens(&self, request: &CompletionRequest) -> Result<TokenCount, LlmError> {
+        let params = build_complete_params(request, false);
 
-        // Add tools if provided
-        if let Some(ref tools) = request.tools {
-            params["tools"] = serde_json::to_value(tools).unwrap_or_default();
-        }
-        if let Some(ref tool_choice) = request.tool_choice {
-            params["tool_choice"] = serde_json::Value::String(tool_choice.clone());
-        }
+        let mut guard = self.process.write().await;
+        let process = guard.as_mut().ok_or(LlmError::NotInitialized)?;
 
-        let result = {
-            let mut guard = self.process.write().await;
-            let process = guard.as_mut().ok_or(LlmError::NotInitialized)?;
-            process.send_streaming_request("complete", params, &callback, cancel_token.as_ref()).await
-        };
+        let result = process.send_request("count_tokens", params).await?;
+        let prompt_tokens = result.get("prompt_tokens")
+            .and_then(|v| v.as_u64())
+            .ok_or_else(|| LlmError::RequestFailed("Missing prompt_tokens in response".to_string()))?;
+        Ok(TokenCount {
+            prompt_tokens: prompt_tokens as usize,
+            context_size: result.get("context_size").and_then(|v| v.as_u64()).map(|v| v as usize),
+            exact: true,
+        })
+    }
 
-        // Handle cancellation - restart sidecar since generation can't be cleanly stopped
-        match &result {
-            Err(LlmError::RequestFailed(msg)) if msg == "Cancelled" => {
-                log::info!("Streaming cancelled, restarting sidecar");
-                self.restart_sidecar().await?;
-                return Err(LlmError::RequestFailed("Cancelled".to_string()));
-            }
-            Err(e) => return Err(e.clone()),
-            Ok(_) => {}
+    async fn end_session(&self, session_id: &str) -> Result<(), LlmError> {
+        let mut guard = self.process.write().await;
+        if let Some(process) = guard.as_mut() {
+            process.send_request("end_session", serde_json::json!({ "session_id": session_id })).await?;
         }
-
-        let result = result?;
-
-        let content = result.get("content")
-            .and_then(|c| c.as_str())
-            .unwrap_or("")
-            .to_string();
-        let model = result.get("model")
-            .and_then(|m| m.as_str())
-            .unwrap_or("unknown")
-            .to_string();
-        let finish_reason = result.get("finish_reason")
-            .and_then(|f| f.as_str())
-            .unwrap_or("stop")
-            .to_string();
-
-        // Parse tool_calls if present
-        let tool_calls: Option<Vec<ToolCall>> = result.get("tool_calls")
-            .and_then(|tc| serde_json::from_value(tc.clone()).ok());
-
-        Ok(CompletionResponse {
-            content,
-            model,
-            prompt_tokens: None,
-            completion_tokens: None,
-            truncated: false,
-            finish_reason: Some(finish_reason),
-            tool_calls,
-        })
+        Ok(())
     }
 
     async fn shutdown(&self) -> Result<(), LlmError> {
@@ -659,3 +821,75 @@ impl LlmProvider for SidecarProvider {
         Ok(())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_empty_batch_does_not_start_sidecar() {
+        let dir = tempfile::tempdir().unwrap();
+        let provider = SidecarProvider::new(SidecarConfig {
+            models_dir: dir.path().to_path_buf(),
+            sidecar_path: Some(dir.path().join("missing-sidecar")),
+            batch_requests: true,
+        });
+
+        let responses = provider.complete_batch(Vec::new()).await.unwrap();
+        assert!(responses.is_empty());
+        assert!(provider.process.read().await.is_none());
+    }
+
+    fn response(id: u64, text: &str) -> serde_json::Value {
+        serde_json::json!({"jsonrpc": "2.0", "id": id, "result": {"text": text}})
+    }
+
+    fn text(result: &Result<serde_json::Value, LlmError>) -> Option<&str> {
+        result.as_ref().ok()?.get("text")?.as_str()
+    }
+
+    #[test]
+    fn test_batch_responses_in_request_order() {
+        let reply = serde_json::json!([response(12, "third"), response(10, "first"), response(11, "second")]);
+        let results = match_batch_responses(reply, 10, 3).unwrap();
+        let texts: Vec<_> = results.iter().map(text).collect();
+        assert_eq!(texts, vec![Some("first"), Some("second"), Some("third")]);
+    }
+
+    #[test]
+    fn test_batch_missing_and_unknown_ids() {
+        let reply = serde_json::json!([
+            response(10, "first"),
+            {"jsonrpc": "2.0", "result": {"text": "no id"}},
+            response(42, "not ours"),
+        ]);
+        let results = match_batch_responses(reply, 10, 2).unwrap();
+        assert_eq!(text(&results[0]), Some("first"));
+        assert!(matches!(&results[1], Err(LlmError::RequestFailed(message)) if message.contains("Missing")));
+    }
+
+    #[test]
+    fn test_batch_item_error() {
+        let reply = serde_json::json!([
+            {"jsonrpc": "2.0", "id": 10, "error": {"code": error_codes::OUT_OF_MEMORY, "message": "no memory"}},
+            response(11, "second"),
+        ]);
+        let results = match_batch_responses(reply, 10, 2).unwrap();
+        assert!(matches!(&results[0], Err(LlmError::OutOfMemory(message)) if message == "no memory"));
+        assert_eq!(text(&results[1]), Some("second"));
+    }
+
+    #[test]
+    fn test_batch_reply_not_a_list() {
+        let rejected = serde_json::json!({
+            "jsonrpc": "2.0",
+            "id": null,
+            "error": {"code": error_codes::INVALID_REQUEST, "message": "bad batch"},
+        });
+        assert!(matches!(match_batch_responses(rejected, 10, 2), Err(LlmError::InvalidRequest(_))));
+        assert!(matches!(
+            match_batch_responses(response(10, "single"), 10, 1),
+            Err(LlmError::RequestFailed(_))
+        ));
+    }
+}
@@ -0,0 +1,182 @@
+//! Meeting analytics
+//!
+//! Per-speaker statistics derived from a recording's transcript segments:
+//! talk time and share of the conversation, speaking rate in words per minute,
+//! and the number of questions asked (sentences ending in '?').
+
+use serde::Serialize;
+
+use crate::database::TranscriptSegment;
+use crate::state::AppState;
+
+/// Grouping key for segments without a speaker
+pub(crate) const UNKNOWN_SPEAKER_ID: &str = "unknown";
+pub(crate) const UNKNOWN_SPEAKER_LABEL: &str = "Unknown";
+
+/// Statistics for one speaker
+#[derive(Debug, Clone, Serialize)]
+pub struct SpeakerAnalytics {
+    pub speaker_id: String,
+    pub speaker_label: String,
+    pub segment_count: usize,
+    pub word_count: usize,
+    /// Total talk time in seconds
+    pub talk_seconds: f64,
+    /// Share of the total talk time, 0.0 - 1.0
+    pub talk_ratio: f64,
+    /// Words per minute of talk time
+    pub words_per_minute: f64,
+    pub question_count: usize,
+}
+
+/// Statistics for a whole recording
+#[derive(Debug, Clone, Serialize)]
+pub struct MeetingAnalytics {
+    pub recording_id: String,
+    /// Time from the first to the last transcript segment, in seconds
+    pub duration_seconds: f64,
+    /// Talk time summed over all segments, in seconds
+    pub total_talk_seconds: f64,
+    pub total_words: usize,
+    pub words_per_minute: f64,
+    pub question_count: usize,
+    /// Speakers ordered by talk time, longest first
+    pub speakers: Vec<SpeakerAnalytics>,
+}
+
+fn segment_seconds(segment: &TranscriptSegment) -> f64 {
+    let span = segment.audio_end_time - segment.audio_start_time;
+    if span > 0.0 { span } else { segment.duration.max(0.0) }
+}
+
+/// Number of questions in `text`; runs of '?' count once
+fn count_questions(text: &str) -> usize {
+    text.split(|c: char| c != '?')
+        .filter(|run| !run.is_empty())
+        .count()
+}
+
+fn words_per_minute(words: usize, seconds: f64) -> f64 {
+    if seconds > 0.0 { words as f64 / (seconds / 60.0) } else { 0.0 }
+}
+
+/// Compute analytics over a recording's transcript segments
+pub fn compute_analytics(recording_id: &str, segments: &[TranscriptSegment]) -> MeetingAnalytics {
+    let mut speakers: Vec<SpeakerAnalytics> = Vec::new();
+
+    for segment in segments {
+        let speaker_id = segment.speaker_id.as_deref().unwrap_or(UNKNOWN_SPEAKER_ID);
+        let index = match speakers.iter().position(|s| s.speaker_id == speaker_id) {
+            Some(index) => index,
+            None => {
+                speakers.push(SpeakerAnalytics {
+                    speaker_id: speaker_id.to_string(),
+                    speaker_label: segment
+                        .speaker_label
+                        .clone()
+                        .unwrap_or_else(|| UNKNOWN_SPEAKER_LABEL.to_string()),
+                    segment_count: 0,
+                    word_count: 0,
+                    talk_seconds: 0.0,
+                    talk_ratio: 0.0,
+                    words_per_minute: 0.0,
+                    question_count: 0,
+                });
+                speakers.len() - 1
+            }
+        };
+
+        let speaker = &mut speakers[index];
+        speaker.segment_count += 1;
+        speaker.word_count += segment.text.split_whitespace().count();
+        speaker.talk_seconds += segment_seconds(segment);
+        speaker.question_count += count_questions(&segment.text);
+    }
+
+    let total_talk_seconds: f64 = speakers.iter().map(|s| s.talk_seconds).sum();
+    let total_words: usize = speakers.iter().map(|s| s.word_count).sum();
+    let question_count: usize = speakers.iter().map(|s| s.question_count).sum();
+
+    for speaker in &mut speakers {
+        speaker.words_per_minute = words_per_minute(speaker.word_count, speaker.talk_seconds);
+        speaker.talk_ratio = if total_talk_seconds > 0.0 {
+            speaker.talk_seconds / total_talk_seconds
+        } else {
+            0.0
+        };
+    }
+    speakers.sort_by(|a, b| b.talk_seconds.total_cmp(&a.talk_seconds));
+
+    let start = segments.iter().map(|s| s.audio_start_time).fold(f64::INFINITY, f64::min);
+    let end = segments.iter().map(|s| s.audio_end_time).fold(f64::NEG_INFINITY, f64::max);
+    let duration_seconds = if end > start { end - start } else { 0.0 };
+
+    MeetingAnalytics {
+        recording_id: recording_id.to_string(),
+        duration_seconds,
+        total_talk_seconds,
+        total_words,
+        words_per_minute: words_per_minute(total_words, total_talk_seconds),
+        question_count,
+        speakers,
+    }
+}
+
+/// Compute speaking statistics (WPM, talk ratio, questions) for a recording
+#[tauri::command]
+pub async fn compute_meeting_analytics(
+    state: tauri::State<'_, AppState>,
+    recording_id: String,
+) -> Result<MeetingAnalytics, String> {
+    let segments = state
+        .db()
+        .await
+        .get_transcript_segments(&recording_id)
+        .map_err(|e| e.to_string())?;
+
+    if segments.is_empty() {
+        return Err(format!("Recording {} has no transcript", recording_id));
+    }
+
+    Ok(compute_analytics(&recording_id, &segments))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_compute_analytics() {
+        let segments = vec![
+            TranscriptSegment::test_segment(0.0, 30.0, "one two three four five six seven eight nine ten")
+                .with_speaker("a", "A"),
+            TranscriptSegment::test_segment(30.0, 40.0, "Is that right? Really??").with_speaker("b", "B"),
+            TranscriptSegment::test_segment(40.0, 70.0, "yes it is").with_speaker("a", "A"),
+            TranscriptSegment::test_segment(70.0, 80.0, "hello"),
+        ];
+        let report = compute_analytics("rec", &segments);
+
+        assert_eq!(report.duration_seconds, 80.0);
+        assert_eq!(report.total_words, 18);
+        assert_eq!(report.question_count, 2);
+        assert_eq!(report.speakers.len(), 3);
+
+        let a = &report.speakers[0];
+        assert_eq!(a.speaker_label, "A");
+        assert_eq!(a.word_count, 13);
+        assert!((a.talk_ratio - 0.75).abs() < 1e-9);
+        assert!((a.words_per_minute - 13.0).abs() < 1e-9);
+
+        let b = report.speakers.iter().find(|s| s.speaker_id == "b").unwrap();
+        assert_eq!(b.question_count, 2);
+        assert!((b.words_per_minute - 24.0).abs() < 1e-9);
+
+        assert!(report.speakers.iter().any(|s| s.speaker_label == UNKNOWN_SPEAKER_LABEL));
+    }
+
+    #[test]
+    fn test_count_questions() {
+        assert_eq!(count_questions("No questions here."), 0);
+        assert_eq!(count_questions("What? Why?! How???"), 3);
+    }
+}
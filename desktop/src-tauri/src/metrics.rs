@@ -0,0 +1,271 @@
+//! Prometheus metrics endpoint
+//!
+//! When `metrics_endpoint_enabled` is set, `GET /metrics` is served on
+//! 127.0.0.1 (`metrics_endpoint_port`, default 9464) in the Prometheus text
+//! format, for monitoring long-running or kiosk deployments. Counters are
+//! updated by the transcription workers and the LLM engine; gauges are read
+//! from the app state when scraped.
+
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Mutex;
+
+use log::{info, warn};
+use once_cell::sync::Lazy;
+use serde::Serialize;
+use tauri::{AppHandle, Manager, Runtime};
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::{TcpListener, TcpStream};
+use tokio_util::sync::CancellationToken;
+
+use crate::state::AppState;
+
+/// Settings key enabling the endpoint
+pub const METRICS_ENABLED_SETTING: &str = "metrics_endpoint_enabled";
+/// Settings key for the endpoint port
+pub const METRICS_PORT_SETTING: &str = "metrics_endpoint_port";
+
+pub const DEFAULT_METRICS_PORT: u16 = 9464;
+
+/// Largest request head read before answering
+const MAX_REQUEST_BYTES: usize = 8192;
+
+static TRANSCRIPTION_CHUNKS_QUEUED: AtomicU64 = AtomicU64::new(0);
+static TRANSCRIPTION_CHUNKS_COMPLETED: AtomicU64 = AtomicU64::new(0);
+static LLM_COMPLETIONS: AtomicU64 = AtomicU64::new(0);
+static LLM_TOKENS_GENERATED: AtomicU64 = AtomicU64::new(0);
+
+/// Running server: its port and the token that stops it
+static SERVER: Lazy<Mutex<Option<(u16, CancellationToken)>>> = Lazy::new(|| Mutex::new(None));
+
+/// Count an audio chunk handed to the transcription workers
+pub fn record_transcription_queued() {
+    TRANSCRIPTION_CHUNKS_QUEUED.fetch_add(1, Ordering::Relaxed);
+}
+
+/// Count an audio chunk the transcription workers are done with (transcribed, skipped or failed)
+pub fn record_transcription_completed() {
+    TRANSCRIPTION_CHUNKS_COMPLETED.fetch_add(1, Ordering::Relaxed);
+}
+
+/// Count a finished LLM completion and the tokens it generated
+pub fn record_llm_completion(completion_tokens: Option<u32>) {
+    LLM_COMPLETIONS.fetch_add(1, Ordering::Relaxed);
+    if let Some(tokens) = completion_tokens {
+        LLM_TOKENS_GENERATED.fetch_add(tokens as u64, Ordering::Relaxed);
+    }
+}
+
+/// Values exposed by the endpoint
+#[derive(Debug, Default)]
+struct MetricsSnapshot {
+    recording_active: bool,
+    transcription_chunks_queued: u64,
+    transcription_chunks_completed: u64,
+    whisper_model_loaded: bool,
+    llm_ready: bool,
+    llm_completions: u64,
+    llm_tokens_generated: u64,
+}
+
+async fn collect_snapshot<R: Runtime>(app: &AppHandle<R>) -> MetricsSnapshot {
+    let whisper_engine = crate::whisper_engine::commands::WHISPER_ENGINE
+        .lock()
+        .ok()
+        .and_then(|guard| guard.clone());
+    let whisper_model_loaded = match whisper_engine {
+        Some(engine) => engine.is_model_loaded().await,
+        None => false,
+    };
+
+    let llm_ready = {
+        let state = app.state::<AppState>();
+        let engine = state.llm_engine.read().await;
+        engine.is_ready().await
+    };
+
+    MetricsSnapshot {
+        recording_active: crate::globals::RECORDING_FLAG.load(Ordering::SeqCst),
+        transcription_chunks_queued: TRANSCRIPTION_CHUNKS_QUEUED.load(Ordering::Relaxed),
+        transcription_chunks_completed: TRANSCRIPTION_CHUNKS_COMPLETED.load(Ordering::Relaxed),
+        whisper_model_loaded,
+        llm_ready,
+        llm_completions: LLM_COMPLETIONS.load(Ordering::Relaxed),
+        llm_tokens_generated: LLM_TOKENS_GENERATED.load(Ordering::Relaxed),
+    }
+}
+
+fn write_metric(out: &mut String, name: &str, kind: &str, help: &str, value: u64) {
+    out.push_str(&format!("# HELP {} {}\n# TYPE {} {}\n{} {}\n", name, help, name, kind, name, value));
+}
+
+/// Prometheus text exposition of a snapshot
+fn render_metrics(snapshot: &MetricsSnapshot) -> String {
+    let queue_depth = snapshot
+        .transcription_chunks_queued
+        .saturating_sub(snapshot.transcription_chunks_completed);
+
+    let mut out = String::new();
+    write_metric(&mut out, "meeting_local_recording_active", "gauge",
+                 "Whether a recording is in progress", snapshot.recording_active as u64);
+    write_metric(&mut out, "meeting_local_transcription_queue_depth", "gauge",
+                 "Audio chunks waiting for or being transcribed", queue_depth);
+    write_metric(&mut out, "meeting_local_transcription_chunks_queued_total", "counter",
+                 "Audio chunks sent to the transcription workers", snapshot.transcription_chunks_queued);
+    write_metric(&mut out, "meeting_local_transcription_chunks_completed_total", "counter",
+                 "Audio chunks processed by the transcription workers", snapshot.transcription_chunks_completed);
+    write_metric(&mut out, "meeting_local_whisper_model_loaded", "gauge",
+                 "Whether a transcription model is loaded", snapshot.whisper_model_loaded as u64);
+    write_metric(&mut out, "meeting_local_llm_ready", "gauge",
+                 "Whether an LLM provider is ready", snapshot.llm_ready as u64);
+    write_metric(&mut out, "meeting_local_llm_completions_total", "counter",
+                 "LLM completions finished", snapshot.llm_completions);
+    write_metric(&mut out, "meeting_local_llm_tokens_generated_total", "counter",
+                 "Tokens generated by the LLM", snapshot.llm_tokens_generated);
+    out
+}
+
+async fn handle_connection<R: Runtime>(app: &AppHandle<R>, mut stream: TcpStream) -> std::io::Result<()> {
+    let mut request = Vec::new();
+    let mut buffer = [0u8; 1024];
+    while !request.windows(4).any(|w| w == b"\r\n\r\n") && request.len() < MAX_REQUEST_BYTES {
+        let read = stream.read(&mut buffer).await?;
+        if read == 0 {
+            break;
+        }
+        request.extend_from_slice(&buffer[..read]);
+    }
+
+    let request = String::from_utf8_lossy(&request);
+    let mut request_line = request.lines().next().unwrap_or_default().split_whitespace();
+    let (method, path) = (request_line.next(), request_line.next());
+
+    let (status, content_type, body) = match (method, path) {
+        (Some("GET"), Some("/metrics")) => (
+            "200 OK",
+            "text/plain; version=0.0.4; charset=utf-8",
+            render_metrics(&collect_snapshot(app).await),
+        ),
+        _ => ("404 Not Found", "text/plain; charset=utf-8", "Not found\n".to_string()),
+    };
+
+    let response = format!(
+        "HTTP/1.1 {}\r\nContent-Type: {}\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+        status,
+        content_type,
+        body.len(),
+        body
+    );
+    stream.write_all(response.as_bytes()).await?;
+    stream.shutdown().await
+}
+
+/// Start serving metrics on 127.0.0.1:`port`, replacing a running server
+pub async fn start_metrics_server<R: Runtime>(app: AppHandle<R>, port: u16) -> Result<(), String> {
+    stop_metrics_server();
+
+    let listener = TcpListener::bind(("127.0.0.1", port))
+        .await
+        .map_err(|e| format!("Failed to bind metrics endpoint to 127.0.0.1:{}: {}", port, e))?;
+
+    let token = CancellationToken::new();
+    *SERVER.lock().unwrap() = Some((port, token.clone()));
+    info!("📈 Metrics endpoint listening on http://127.0.0.1:{}/metrics", port);
+
+    tauri::async_runtime::spawn(async move {
+        loop {
+            tokio::select! {
+                _ = token.cancelled() => break,
+                accepted = listener.accept() => match accepted {
+                    Ok((stream, _)) => {
+                        let app = app.clone();
+                        tauri::async_runtime::spawn(async move {
+                            if let Err(e) = handle_connection(&app, stream).await {
+                                warn!("Metrics request failed: {}", e);
+                            }
+                        });
+                    }
+                    Err(e) => warn!("Metrics endpoint accept failed: {}", e),
+                },
+            }
+        }
+        info!("📈 Metrics endpoint on port {} stopped", port);
+    });
+
+    Ok(())
+}
+
+/// Stop the metrics server if it is running
+pub fn stop_metrics_server() {
+    if let Some((_, token)) = SERVER.lock().unwrap().take() {
+        token.cancel();
+    }
+}
+
+/// Metrics endpoint configuration and whether it is serving
+#[derive(Debug, Clone, Serialize)]
+pub struct MetricsEndpointStatus {
+    pub enabled: bool,
+    pub port: u16,
+    pub running: bool,
+}
+
+#[tauri::command]
+pub async fn get_metrics_endpoint(
+    state: tauri::State<'_, AppState>,
+) -> Result<MetricsEndpointStatus, String> {
+    let db = state.db().await;
+    let enabled = db.get_bool_setting(METRICS_ENABLED_SETTING, false).map_err(|e| e.to_string())?;
+    let port = db
+        .get_setting(METRICS_PORT_SETTING)
+        .map_err(|e| e.to_string())?
+        .and_then(|value| value.parse().ok())
+        .unwrap_or(DEFAULT_METRICS_PORT);
+    let running = SERVER.lock().unwrap().is_some();
+    Ok(MetricsEndpointStatus { enabled, port, running })
+}
+
+/// Enable or disable the metrics endpoint; the change applies immediately
+#[tauri::command]
+pub async fn set_metrics_endpoint<R: Runtime>(
+    app: AppHandle<R>,
+    state: tauri::State<'_, AppState>,
+    enabled: bool,
+    port: Option<u16>,
+) -> Result<(), String> {
+    let port = port.unwrap_or(DEFAULT_METRICS_PORT);
+    if port == 0 {
+        return Err("Metrics port must be between 1 and 65535".to_string());
+    }
+
+    if enabled {
+        start_metrics_server(app, port).await?;
+    } else {
+        stop_metrics_server();
+    }
+
+    let db = state.db().await;
+    db.set_bool_setting(METRICS_ENABLED_SETTING, enabled).map_err(|e| e.to_string())?;
+    db.set_setting(METRICS_PORT_SETTING, &port.to_string(), "number").map_err(|e| e.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_render_metrics() {
+        let snapshot = MetricsSnapshot {
+            recording_active: true,
+            transcription_chunks_queued: 10,
+            transcription_chunks_completed: 7,
+            llm_tokens_generated: 512,
+            ..Default::default()
+        };
+        let text = render_metrics(&snapshot);
+
+        assert!(text.contains("# TYPE meeting_local_recording_active gauge\nmeeting_local_recording_active 1\n"));
+        assert!(text.contains("\nmeeting_local_transcription_queue_depth 3\n"));
+        assert!(text.contains("\nmeeting_local_whisper_model_loaded 0\n"));
+        assert!(text.contains("# TYPE meeting_local_llm_tokens_generated_total counter\nmeeting_local_llm_tokens_generated_total 512\n"));
+    }
+}
@@ -0,0 +1,383 @@
+//! Model directories
+//!
+//! Where Whisper, LLM and diarization models are stored. Each kind has a
+//! default folder and can be moved elsewhere, e.g. to a larger disk, with the
+//! `whisper_models_dir`, `llm_models_dir` and `diarization_models_dir`
+//! settings. A new folder must be writable; models already downloaded can
+//! optionally be moved along. `get_all_installed_models` lists the models on
+//! disk across all kinds, for freeing up space.
+
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+
+use log::{info, warn};
+use serde::{Deserialize, Serialize};
+use tauri::{AppHandle, Manager, Runtime};
+
+use crate::state::AppState;
+
+/// File written and deleted to check a folder is writable
+const WRITE_PROBE_FILE: &str = ".meeting-local-write-test";
+
+static WHISPER_MODELS_DIR: Mutex<Option<PathBuf>> = Mutex::new(None);
+static LLM_MODELS_DIR: Mutex<Option<PathBuf>> = Mutex::new(None);
+static DIARIZATION_MODELS_DIR: Mutex<Option<PathBuf>> = Mutex::new(None);
+
+/// Kind of model stored in its own folder
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum ModelKind {
+    Whisper,
+    Llm,
+    Diarization,
+}
+
+impl ModelKind {
+    /// Setting holding the custom folder
+    pub fn setting_key(self) -> &'static str {
+        match self {
+            ModelKind::Whisper => "whisper_models_dir",
+            ModelKind::Llm => "llm_models_dir",
+            ModelKind::Diarization => "diarization_models_dir",
+        }
+    }
+
+    fn custom_dir(self) -> &'static Mutex<Option<PathBuf>> {
+        match self {
+            ModelKind::Whisper => &WHISPER_MODELS_DIR,
+            ModelKind::Llm => &LLM_MODELS_DIR,
+            ModelKind::Diarization => &DIARIZATION_MODELS_DIR,
+        }
+    }
+
+    /// Whether `path` is one of this kind's model files. Whisper and
+    /// diarization share the default folder, so only their own files move.
+    fn is_model_file(self, path: &Path) -> bool {
+        let Some(name) = path.file_name().and_then(|name| name.to_str()) else {
+            return false;
+        };
+        match self {
+            ModelKind::Whisper => name.ends_with(".bin") || name.ends_with(".mlmodelc"),
+            ModelKind::Llm => name.ends_with(".gguf"),
+            ModelKind::Diarization => [
+                crate::diarization::SEGMENTATION_MODEL_NAME,
+                crate::diarization::EMBEDDING_MODEL_NAME,
+                crate::diarization::SORTFORMER_MODEL_NAME,
+            ]
+            .contains(&name),
+        }
+    }
+}
+
+/// Model folders in use, for the settings page
+#[derive(Debug, Clone, Serialize)]
+pub struct ModelDirectories {
+    pub whisper: String,
+    pub llm: String,
+    pub diarization: String,
+}
+
+/// A model file on disk
+#[derive(Debug, Clone, Serialize)]
+pub struct InstalledModel {
+    pub engine: ModelKind,
+    pub name: String,
+    pub path: String,
+    pub size_bytes: u64,
+    /// Currently loaded in memory
+    pub loaded: bool,
+}
+
+/// Use `dir` for `kind`'s models, or the default folder again with None
+pub fn set_custom_models_dir(kind: ModelKind, dir: Option<PathBuf>) {
+    if let Ok(mut guard) = kind.custom_dir().lock() {
+        *guard = dir;
+    }
+}
+
+/// Folder used when no custom folder is set
+pub fn default_models_dir<R: Runtime>(app: &AppHandle<R>, kind: ModelKind) -> Result<PathBuf, String> {
+    match kind {
+        // Matches the folder the LLM engine and model manager start with
+        ModelKind::Llm => Ok(dirs::data_dir()
+            .unwrap_or_else(|| PathBuf::from("."))
+            .join("meeting-local")
+            .join("llm_models")),
+        ModelKind::Whisper | ModelKind::Diarization => app
+            .path()
+            .app_data_dir()
+            .map(|dir| dir.join("models"))
+            .map_err(|e| format!("Failed to get app data dir: {}", e)),
+    }
+}
+
+/// Folder `kind`'s models are stored in
+pub fn models_dir<R: Runtime>(app: &AppHandle<R>, kind: ModelKind) -> Result<PathBuf, String> {
+    let custom = kind.custom_dir().lock().ok().and_then(|guard| guard.clone());
+    match custom {
+        Some(dir) => Ok(dir),
+        None => default_models_dir(app, kind),
+    }
+}
+
+/// Create `dir` if needed and check files can be written to it
+pub fn ensure_writable(dir: &Path) -> Result<(), String> {
+    if !dir.is_absolute() {
+        return Err(format!("Model folder must be an absolute path: {}", dir.display()));
+    }
+    std::fs::create_dir_all(dir)
+        .map_err(|e| format!("Failed to create {}: {}", dir.display(), e))?;
+
+    let probe = dir.join(WRITE_PROBE_FILE);
+    std::fs::write(&probe, b"ok")
+        .map_err(|e| format!("Folder {} is not writable: {}", dir.display(), e))?;
+    let _ = std::fs::remove_file(&probe);
+    Ok(())
+}
+
+/// Move `kind`'s model files from `from` to `to`; returns how many were moved.
+/// Files already present in `to` are left where they are.
+fn move_model_files(kind: ModelKind, from: &Path, to: &Path) -> Result<usize, String> {
+    if from == to || !from.exists() {
+        return Ok(0);
+    }
+    let entries = std::fs::read_dir(from)
+        .map_err(|e| format!("Failed to read {}: {}", from.display(), e))?;
+
+    let mut moved = 0;
+    for entry in entries.flatten() {
+        let path = entry.path();
+        if !kind.is_model_file(&path) {
+            continue;
+        }
+        let target = to.join(entry.file_name());
+        if target.exists() {
+            warn!("{} already exists, leaving {} in place", target.display(), path.display());
+            continue;
+        }
+
+        // Renaming fails across disks, so copy and delete instead
+        if std::fs::rename(&path, &target).is_err() {
+            if path.is_dir() {
+                warn!("Could not move {} to another disk, leaving it in place", path.display());
+                continue;
+            }
+            std::fs::copy(&path, &target)
+                .map_err(|e| format!("Failed to copy {}: {}", path.display(), e))?;
+            std::fs::remove_file(&path)
+                .map_err(|e| format!("Failed to remove {}: {}", path.display(), e))?;
+        }
+        moved += 1;
+    }
+    Ok(moved)
+}
+
+#[tauri::command]
+pub fn get_model_directories<R: Runtime>(app: AppHandle<R>) -> Result<ModelDirectories, String> {
+    let dir = |kind| models_dir(&app, kind).map(|dir| dir.to_string_lossy().to_string());
+    Ok(ModelDirectories {
+        whisper: dir(ModelKind::Whisper)?,
+        llm: dir(ModelKind::Llm)?,
+        diarization: dir(ModelKind::Diarization)?,
+    })
+}
+
+/// Store `kind`'s models in `path`, or in the default folder with None. The
+/// folder must be writable. With `move_existing`, models already downloaded
+/// are moved to the new folder. A loaded Whisper or embedded LLM model is
+/// unloaded and has to be loaded again. Returns the folder now in use.
+#[tauri::command]
+pub async fn set_model_directory<R: Runtime>(
+    app: AppHandle<R>,
+    state: tauri::State<'_, AppState>,
+    kind: ModelKind,
+    path: Option<String>,
+    move_existing: Option<bool>,
+) -> Result<String, String> {
+    if crate::audio::recording::state::is_recording() {
+        return Err("Model folders can't be changed while recording".to_string());
+    }
+
+    let custom = path
+        .map(|path| path.trim().to_string())
+        .filter(|path| !path.is_empty())
+        .map(PathBuf::from);
+    let new_dir = match &custom {
+        Some(dir) => dir.clone(),
+        None => default_models_dir(&app, kind)?,
+    };
+    ensure_writable(&new_dir)?;
+
+    let old_dir = models_dir(&app, kind)?;
+    if move_existing.unwrap_or(false) {
+        let moved = move_model_files(kind, &old_dir, &new_dir)?;
+        info!("Moved {} {:?} model files from {} to {}", moved, kind, old_dir.display(), new_dir.display());
+    }
+
+    {
+        let db = state.db().await;
+        match &custom {
+            Some(dir) => db.set_setting(kind.setting_key(), &dir.to_string_lossy(), "string"),
+            None => db.delete_setting(kind.setting_key()),
+        }
+        .map_err(|e| e.to_string())?;
+    }
+    set_custom_models_dir(kind, custom);
+
+    match kind {
+        ModelKind::Whisper => crate::whisper_engine::commands::use_models_directory(new_dir.clone())?,
+        ModelKind::Llm => state.set_llm_models_dir(new_dir.clone()).await,
+        // Looked up each time diarization models are loaded or downloaded
+        ModelKind::Diarization => {}
+    }
+
+    info!("{:?} models directory set to {}", kind, new_dir.display());
+    Ok(new_dir.to_string_lossy().to_string())
+}
+
+fn file_size(path: &Path) -> u64 {
+    std::fs::metadata(path).map(|meta| meta.len()).unwrap_or(0)
+}
+
+async fn installed_whisper_models() -> Vec<InstalledModel> {
+    let models = match crate::whisper_engine::commands::whisper_get_available_models().await {
+        Ok(models) => models,
+        Err(e) => {
+            warn!("Skipping Whisper models: {}", e);
+            return Vec::new();
+        }
+    };
+    let current = crate::whisper_engine::commands::whisper_get_current_model().await.ok().flatten();
+
+    models
+        .into_iter()
+        .filter(|model| matches!(model.status, crate::whisper_engine::ModelStatus::Available))
+        .map(|model| InstalledModel {
+            engine: ModelKind::Whisper,
+            loaded: current.as_deref() == Some(model.name.as_str()),
+            size_bytes: file_size(&model.path),
+            path: model.path.to_string_lossy().to_string(),
+            name: model.name,
+        })
+        .collect()
+}
+
+async fn installed_llm_models(state: tauri::State<'_, AppState>) -> Vec<InstalledModel> {
+    use crate::llm_engine::provider::{LlmProvider, ProviderType};
+
+    let current = {
+        let engine = state.llm_engine.read().await;
+        match engine.get_provider(&ProviderType::Embedded) {
+            Some(provider) => provider.current_model().await,
+            None => None,
+        }
+    };
+    let models_dir = state.llm_model_manager.read().await.models_dir().clone();
+
+    let models = match crate::llm_engine::commands::llm_get_local_models_info(state).await {
+        Ok(models) => models,
+        Err(e) => {
+            warn!("Skipping LLM models: {}", e);
+            return Vec::new();
+        }
+    };
+    models
+        .into_iter()
+        .map(|model| InstalledModel {
+            engine: ModelKind::Llm,
+            loaded: current.as_deref() == Some(model.id.as_str()),
+            path: models_dir.join(format!("{}.gguf", model.id)).to_string_lossy().to_string(),
+            size_bytes: model.size_bytes,
+            name: model.name,
+        })
+        .collect()
+}
+
+async fn installed_diarization_models<R: Runtime>(app: &AppHandle<R>) -> Vec<InstalledModel> {
+    let models_dir = match models_dir(app, ModelKind::Diarization) {
+        Ok(dir) => dir,
+        Err(e) => {
+            warn!("Skipping diarization models: {}", e);
+            return Vec::new();
+        }
+    };
+    let pyannote_loaded = crate::diarization::DIARIZATION_ENGINE.read().await.is_some();
+    let sortformer_loaded = crate::diarization::SORTFORMER_ENGINE.read().await.is_some();
+
+    let mut models: Vec<InstalledModel> = crate::diarization::get_models_info(&models_dir)
+        .into_iter()
+        .filter_map(|model| {
+            let path = model.path?;
+            Some(InstalledModel {
+                engine: ModelKind::Diarization,
+                name: model.name,
+                size_bytes: file_size(Path::new(&path)),
+                path,
+                loaded: pyannote_loaded,
+            })
+        })
+        .collect();
+
+    let sortformer_path = models_dir.join(crate::diarization::SORTFORMER_MODEL_NAME);
+    if sortformer_path.exists() {
+        models.push(InstalledModel {
+            engine: ModelKind::Diarization,
+            name: "Sortformer".to_string(),
+            size_bytes: file_size(&sortformer_path),
+            path: sortformer_path.to_string_lossy().to_string(),
+            loaded: sortformer_loaded,
+        });
+    }
+    models
+}
+
+/// Every model on disk across Whisper, LLM and diarization, with its size
+/// and whether it is loaded, for a "manage models / free space" screen
+#[tauri::command]
+pub async fn get_all_installed_models<R: Runtime>(
+    app: AppHandle<R>,
+    state: tauri::State<'_, AppState>,
+) -> Result<Vec<InstalledModel>, String> {
+    let mut models = installed_whisper_models().await;
+    models.extend(installed_llm_models(state).await);
+    models.extend(installed_diarization_models(&app).await);
+    Ok(models)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_ensure_writable() {
+        let temp = tempfile::tempdir().unwrap();
+        let dir = temp.path().join("nested").join("models");
+        assert!(ensure_writable(&dir).is_ok());
+        assert!(dir.is_dir());
+        assert!(!dir.join(WRITE_PROBE_FILE).exists());
+
+        assert!(ensure_writable(Path::new("relative/models")).is_err());
+    }
+
+    #[test]
+    fn test_move_model_files_only_moves_own_kind() {
+        let temp = tempfile::tempdir().unwrap();
+        let from = temp.path().join("from");
+        let to = temp.path().join("to");
+        std::fs::create_dir_all(&from).unwrap();
+        std::fs::create_dir_all(&to).unwrap();
+        std::fs::write(from.join("ggml-base.bin"), b"whisper").unwrap();
+        std::fs::write(from.join(crate::diarization::SEGMENTATION_MODEL_NAME), b"onnx").unwrap();
+        std::fs::write(from.join("ggml-small.bin"), b"old").unwrap();
+        std::fs::write(to.join("ggml-small.bin"), b"new").unwrap();
+
+        assert_eq!(move_model_files(ModelKind::Whisper, &from, &to).unwrap(), 1);
+        assert!(to.join("ggml-base.bin").exists());
+        assert!(from.join(crate::diarization::SEGMENTATION_MODEL_NAME).exists());
+        // Existing files in the target are not overwritten
+        assert_eq!(std::fs::read(to.join("ggml-small.bin")).unwrap(), b"new");
+        assert!(from.join("ggml-small.bin").exists());
+
+        assert_eq!(move_model_files(ModelKind::Llm, &from, &to).unwrap(), 0);
+    }
+}
@@ -0,0 +1,198 @@
+//! Automatic model setup for onboarding
+//!
+//! `auto_setup_models` picks the Whisper and LLM models recommended for the
+//! detected hardware, downloads whichever are missing and makes them the
+//! defaults. Progress is reported with `auto-setup-progress` events, and
+//! `cancel_auto_setup_models` stops the download in flight.
+
+use std::sync::Mutex;
+
+use log::{info, warn};
+use once_cell::sync::Lazy;
+use serde::Serialize;
+use tauri::{AppHandle, Emitter, Runtime};
+use tokio_util::sync::CancellationToken;
+
+use crate::audio::HardwareProfile;
+use crate::llm_engine::model_manager::DownloadProgress;
+use crate::state::AppState;
+use crate::whisper_engine::{commands::WHISPER_ENGINE, ModelStatus};
+
+/// Settings key holding the default Whisper model
+const WHISPER_MODEL_SETTING: &str = "current_model";
+/// Provider used for downloaded LLM models
+const EMBEDDED_PROVIDER: &str = "embedded";
+
+/// Token of the running setup, None when idle
+static ACTIVE_SETUP: Lazy<Mutex<Option<CancellationToken>>> = Lazy::new(|| Mutex::new(None));
+
+/// What `auto_setup_models` chose and did
+#[derive(Debug, Clone, Serialize)]
+pub struct AutoSetupResult {
+    pub whisper_model: String,
+    /// False if the model was already downloaded
+    pub whisper_downloaded: bool,
+    /// None if no LLM is recommended for this hardware
+    pub llm_model: Option<String>,
+    pub llm_downloaded: bool,
+}
+
+fn emit_progress<R: Runtime>(app: &AppHandle<R>, stage: &str, model_id: &str, percent: f32, status: &str) {
+    let _ = app.emit("auto-setup-progress", serde_json::json!({
+        "stage": stage,
+        "modelId": model_id,
+        "percent": percent,
+        "status": status,
+    }));
+}
+
+/// Clears the active setup when the command returns, however it exits
+struct ActiveSetupGuard;
+
+impl Drop for ActiveSetupGuard {
+    fn drop(&mut self) {
+        *ACTIVE_SETUP.lock().unwrap() = None;
+    }
+}
+
+/// Download the Whisper model unless it is already available. Returns whether it was downloaded.
+async fn ensure_whisper_model<R: Runtime>(
+    app: &AppHandle<R>,
+    model: &str,
+    cancel: &CancellationToken,
+) -> Result<bool, String> {
+    crate::whisper_engine::whisper_init().await?;
+    let engine = WHISPER_ENGINE
+        .lock()
+        .unwrap()
+        .clone()
+        .ok_or("Whisper engine not initialized")?;
+
+    let available = engine
+        .discover_models()
+        .await
+        .map_err(|e| format!("Failed to discover models: {}", e))?
+        .iter()
+        .any(|m| m.name == model && matches!(m.status, ModelStatus::Available));
+    if available {
+        info!("Auto setup: Whisper model {} already downloaded", model);
+        emit_progress(app, "whisper", model, 100.0, "skipped");
+        return Ok(false);
+    }
+
+    let app_for_progress = app.clone();
+    let model_for_progress = model.to_string();
+    let progress = Box::new(move |percent: u8| {
+        emit_progress(&app_for_progress, "whisper", &model_for_progress, percent as f32, "downloading");
+    });
+
+    tokio::select! {
+        result = engine.download_model(model, Some(progress)) => {
+            result.map_err(|e| format!("Failed to download Whisper model {}: {}", model, e))?;
+        }
+        _ = cancel.cancelled() => {
+            if let Err(e) = engine.cancel_download(model).await {
+                warn!("Auto setup: failed to clean up Whisper download: {}", e);
+            }
+            return Err("Model setup cancelled".to_string());
+        }
+    }
+
+    emit_progress(app, "whisper", model, 100.0, "complete");
+    Ok(true)
+}
+
+/// Download the LLM model unless it is already present. Returns whether it was downloaded.
+async fn ensure_llm_model<R: Runtime>(
+    app: &AppHandle<R>,
+    state: &AppState,
+    model: &str,
+    cancel: &CancellationToken,
+) -> Result<bool, String> {
+    let manager = state.llm_model_manager.read().await;
+    if manager.is_downloaded(model) {
+        info!("Auto setup: LLM model {} already downloaded", model);
+        emit_progress(app, "llm", model, 100.0, "skipped");
+        return Ok(false);
+    }
+
+    let app_for_progress = app.clone();
+    let on_progress = move |progress: DownloadProgress| {
+        emit_progress(&app_for_progress, "llm", &progress.model_id, progress.percent, "downloading");
+    };
+
+    // Dropping the download future stops it; the temp file is removed afterwards
+    tokio::select! {
+        result = manager.download_model(model, on_progress) => {
+            result.map_err(|e| format!("Failed to download LLM model {}: {}", model, e))?;
+        }
+        _ = cancel.cancelled() => {
+            if let Err(e) = manager.cancel_download(model) {
+                warn!("Auto setup: failed to clean up LLM download: {}", e);
+            }
+            return Err("Model setup cancelled".to_string());
+        }
+    }
+
+    emit_progress(app, "llm", model, 100.0, "complete");
+    Ok(true)
+}
+
+/// Pick, download and set default Whisper and LLM models for this hardware.
+/// Models that are already downloaded are reused, so it is safe to run again.
+#[tauri::command]
+pub async fn auto_setup_models<R: Runtime>(
+    app: AppHandle<R>,
+    state: tauri::State<'_, AppState>,
+) -> Result<AutoSetupResult, String> {
+    let cancel = {
+        let mut active = ACTIVE_SETUP.lock().unwrap();
+        if active.is_some() {
+            return Err("Model setup is already running".to_string());
+        }
+        let token = CancellationToken::new();
+        *active = Some(token.clone());
+        token
+    };
+    let _guard = ActiveSetupGuard;
+
+    let recommendations = HardwareProfile::detect().get_model_recommendations();
+    let whisper_model = recommendations.best_whisper_model;
+    let llm_model = recommendations.best_llm_model;
+    info!("Auto setup: selected Whisper {} and LLM {:?}", whisper_model, llm_model);
+
+    let whisper_downloaded = ensure_whisper_model(&app, &whisper_model, &cancel).await?;
+    state
+        .db()
+        .await
+        .set_setting(WHISPER_MODEL_SETTING, &whisper_model, "string")
+        .map_err(|e| e.to_string())?;
+
+    let mut llm_downloaded = false;
+    if let Some(model) = &llm_model {
+        llm_downloaded = ensure_llm_model(&app, &state, model, &cancel).await?;
+        crate::llm_engine::commands::llm_set_default_model(
+            state.clone(),
+            Some(EMBEDDED_PROVIDER.to_string()),
+            Some(model.clone()),
+        )
+        .await?;
+    }
+
+    emit_progress(&app, "done", "", 100.0, "complete");
+    Ok(AutoSetupResult {
+        whisper_model,
+        whisper_downloaded,
+        llm_model,
+        llm_downloaded,
+    })
+}
+
+/// Cancel a running `auto_setup_models`; does nothing if none is running
+#[tauri::command]
+pub async fn cancel_auto_setup_models() -> Result<(), String> {
+    if let Some(token) = ACTIVE_SETUP.lock().unwrap().as_ref() {
+        token.cancel();
+    }
+    Ok(())
+}
@@ -68,6 +68,13 @@ impl AppState {
         *manager = LlmModelManager::new(app_data_dir);
     }
 
+    /// Store LLM models in `models_dir` instead of the default folder
+    pub async fn set_llm_models_dir(&self, models_dir: std::path::PathBuf) {
+        self.llm_engine.write().await.set_models_dir(models_dir.clone()).await;
+        let mut manager = self.llm_model_manager.write().await;
+        *manager = LlmModelManager::with_models_dir(models_dir);
+    }
+
     /// Initialize the database manager and MCP manager
     pub async fn init_database(&self, db: DatabaseManager) {
         let wrapper = DbWrapper::new(db);
@@ -0,0 +1,487 @@
+//! Background task scheduler
+//!
+//! Limits how many heavy tasks (transcription, diarization, LLM generation)
+//! run at once so they don't exhaust a modest machine. A task waits for a
+//! slot with `acquire` and holds the returned permit while it runs; dropping
+//! the permit frees the slot for the next waiting task. The limit comes from
+//! the `max_concurrent_tasks` setting.
+//!
+//! A task must hold at most one permit at a time: a task that waited for a
+//! second slot while holding one could deadlock with a limit of 1.
+//!
+//! An exclusive permit (`acquire_exclusive_or_cancel`) also waits for the
+//! running tasks of its kind to finish, and tasks of that kind wait while it
+//! is held. Chat regeneration uses it to switch the shared LLM engine's model
+//! without moving other LLM tasks onto it.
+//!
+//! `cancel_all_tasks` is the stop-everything button across chat,
+//! retranscription, the retranscription queue, quick transcription, speaker
+//! re-clustering, dictation and every task still waiting for a slot.
+
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicU64, AtomicUsize, Ordering};
+use std::sync::{Mutex, MutexGuard, PoisonError};
+
+use dashmap::DashMap;
+use log::{debug, info};
+use once_cell::sync::Lazy;
+use serde::Serialize;
+use tauri::{AppHandle, Runtime};
+use tokio::sync::Notify;
+use tokio_util::sync::CancellationToken;
+
+use crate::state::AppState;
+
+/// Heavy tasks run at once when no setting is saved
+pub const DEFAULT_MAX_CONCURRENT_TASKS: usize = 2;
+
+/// Kind of heavy work a task does
+#[derive(Debug, Clone, Copy, Serialize, PartialEq, Eq, Hash)]
+#[serde(rename_all = "snake_case")]
+pub enum TaskKind {
+    Transcription,
+    Diarization,
+    Llm,
+}
+
+/// A task waiting for or holding a slot
+#[derive(Debug, Clone, Serialize)]
+pub struct ScheduledTask {
+    pub id: u64,
+    pub kind: TaskKind,
+    /// What the task works on, e.g. "Retranscribe rec-123"
+    pub label: String,
+    pub running: bool,
+    pub queued_at: String,
+    /// Cancels the wait for a slot; has no effect once the task runs
+    #[serde(skip)]
+    cancel_token: CancellationToken,
+    /// Waiting through `acquire_or_cancel`, so the owner tracks the cancel
+    #[serde(skip)]
+    owner_cancels: bool,
+}
+
+/// Current limit and tasks, for the UI
+#[derive(Debug, Clone, Serialize)]
+pub struct SchedulerStatus {
+    pub max_concurrent_tasks: usize,
+    pub running: usize,
+    pub queued: usize,
+    /// Oldest first
+    pub tasks: Vec<ScheduledTask>,
+}
+
+/// Slots taken by running tasks
+#[derive(Default)]
+struct Slots {
+    running: usize,
+    running_by_kind: HashMap<TaskKind, usize>,
+    /// Kind of the running exclusive task, whose other tasks must wait
+    exclusive: Option<TaskKind>,
+}
+
+static MAX_CONCURRENT_TASKS: AtomicUsize = AtomicUsize::new(DEFAULT_MAX_CONCURRENT_TASKS);
+static SLOTS: Lazy<Mutex<Slots>> = Lazy::new(|| Mutex::new(Slots::default()));
+static SLOT_FREED: Lazy<Notify> = Lazy::new(Notify::new);
+static NEXT_TASK_ID: AtomicU64 = AtomicU64::new(1);
+static TASKS: Lazy<DashMap<u64, ScheduledTask>> = Lazy::new(DashMap::new);
+
+/// A held slot; the slot is freed when this is dropped
+pub struct TaskPermit {
+    id: u64,
+    kind: TaskKind,
+    exclusive: bool,
+}
+
+/// The running counts stay valid if a holder panicked, so a poisoned lock is recovered
+fn slots() -> MutexGuard<'static, Slots> {
+    SLOTS.lock().unwrap_or_else(PoisonError::into_inner)
+}
+
+impl Drop for TaskPermit {
+    fn drop(&mut self) {
+        TASKS.remove(&self.id);
+        {
+            let mut slots = slots();
+            slots.running = slots.running.saturating_sub(1);
+            if let Some(count) = slots.running_by_kind.get_mut(&self.kind) {
+                *count = count.saturating_sub(1);
+            }
+            if self.exclusive {
+                slots.exclusive = None;
+            }
+        }
+        SLOT_FREED.notify_waiters();
+    }
+}
+
+/// Removes a task that stopped waiting without getting a slot
+struct QueuedTask {
+    id: u64,
+    acquired: bool,
+}
+
+impl Drop for QueuedTask {
+    fn drop(&mut self) {
+        if !self.acquired {
+            TASKS.remove(&self.id);
+        }
+    }
+}
+
+/// Set how many heavy tasks may run at once (at least 1). Waiting tasks start
+/// immediately if the limit was raised; running tasks are never interrupted.
+pub fn set_max_concurrent_tasks(max: usize) {
+    MAX_CONCURRENT_TASKS.store(max.max(1), Ordering::SeqCst);
+    SLOT_FREED.notify_waiters();
+    info!("Max concurrent background tasks: {}", max.max(1));
+}
+
+pub fn max_concurrent_tasks() -> usize {
+    MAX_CONCURRENT_TASKS.load(Ordering::SeqCst)
+}
+
+/// Whether every slot is taken, so a new task would have to wait
+pub fn is_busy() -> bool {
+    slots().running >= max_concurrent_tasks()
+}
+
+/// Whether a task of `kind` is waiting for or holding a slot
+pub fn has_tasks(kind: TaskKind) -> bool {
+    TASKS.iter().any(|entry| entry.kind == kind)
+}
+
+fn try_take_slot(kind: TaskKind, exclusive: bool) -> bool {
+    let mut slots = slots();
+    let running_of_kind = slots.running_by_kind.get(&kind).copied().unwrap_or(0);
+    if slots.running >= max_concurrent_tasks()
+        || slots.exclusive == Some(kind)
+        || (exclusive && running_of_kind > 0)
+    {
+        return false;
+    }
+    slots.running += 1;
+    *slots.running_by_kind.entry(kind).or_insert(0) += 1;
+    if exclusive {
+        slots.exclusive = Some(kind);
+    }
+    true
+}
+
+/// Wait for a free slot and hold it until the permit is dropped. Fails if the
+/// task is cancelled by `cancel_queued_tasks` while waiting.
+pub async fn acquire(kind: TaskKind, label: impl Into<String>) -> Result<TaskPermit, String> {
+    acquire_slot(kind, label.into(), false, false).await
+}
+
+async fn acquire_slot(
+    kind: TaskKind,
+    label: String,
+    owner_cancels: bool,
+    exclusive: bool,
+) -> Result<TaskPermit, String> {
+    let id = NEXT_TASK_ID.fetch_add(1, Ordering::SeqCst);
+    let cancel_token = CancellationToken::new();
+    TASKS.insert(id, ScheduledTask {
+        id,
+        kind,
+        label: label.clone(),
+        running: false,
+        queued_at: chrono::Utc::now().to_rfc3339(),
+        cancel_token: cancel_token.clone(),
+        owner_cancels,
+    });
+    let mut queued = QueuedTask { id, acquired: false };
+
+    let mut waited = false;
+    loop {
+        // Register for the wake-up before checking, so a slot freed in
+        // between isn't missed
+        let notified = SLOT_FREED.notified();
+        tokio::pin!(notified);
+        notified.as_mut().enable();
+
+        if try_take_slot(kind, exclusive) {
+            break;
+        }
+        if !waited {
+            debug!("Task '{}' waiting for a free slot", label);
+            waited = true;
+        }
+        tokio::select! {
+            _ = notified => {}
+            _ = cancel_token.cancelled() => {
+                info!("Task '{}' was cancelled while waiting for a free slot", label);
+                return Err(format!("'{}' was cancelled before it started", label));
+            }
+        }
+    }
+
+    queued.acquired = true;
+    if let Some(mut task) = TASKS.get_mut(&id) {
+        task.running = true;
+    }
+    Ok(TaskPermit { id, kind, exclusive })
+}
+
+/// Like `acquire`, but gives up and returns None if `cancel_token` is cancelled while waiting
+pub async fn acquire_or_cancel(
+    kind: TaskKind,
+    label: impl Into<String>,
+    cancel_token: &CancellationToken,
+) -> Option<TaskPermit> {
+    tokio::select! {
+        permit = acquire_slot(kind, label.into(), true, false) => permit.ok(),
+        _ = cancel_token.cancelled() => None,
+    }
+}
+
+/// Like `acquire_or_cancel`, but also waits until no other task of `kind`
+/// runs, and tasks of `kind` wait until the permit is dropped
+pub async fn acquire_exclusive_or_cancel(
+    kind: TaskKind,
+    label: impl Into<String>,
+    cancel_token: &CancellationToken,
+) -> Option<TaskPermit> {
+    tokio::select! {
+        permit = acquire_slot(kind, label.into(), true, true) => permit.ok(),
+        _ = cancel_token.cancelled() => None,
+    }
+}
+
+/// Cancel every task still waiting for a slot. Returns how many of them
+/// nothing else counts: LLM tasks waiting through plain `acquire`. Waiting
+/// chat tasks are counted by the chat task registry, and transcription and
+/// diarization tasks by the retranscription module.
+pub fn cancel_queued_tasks() -> usize {
+    let mut uncounted = 0;
+    for task in TASKS.iter().filter(|task| !task.running) {
+        task.cancel_token.cancel();
+        if task.kind == TaskKind::Llm && !task.owner_cancels {
+            uncounted += 1;
+        }
+    }
+    uncounted
+}
+
+/// Current limit and the tasks waiting for or holding a slot
+pub fn scheduler_status() -> SchedulerStatus {
+    let mut tasks: Vec<ScheduledTask> = TASKS.iter().map(|entry| entry.value().clone()).collect();
+    tasks.sort_by_key(|task| task.id);
+    let running = tasks.iter().filter(|task| task.running).count();
+    SchedulerStatus {
+        max_concurrent_tasks: max_concurrent_tasks(),
+        running,
+        queued: tasks.len() - running,
+        tasks,
+    }
+}
+
+#[tauri::command]
+pub fn get_task_scheduler_status() -> SchedulerStatus {
+    scheduler_status()
+}
+
+/// Stop all background work: chat completions, running and queued
+/// retranscriptions and batch transcriptions, quick transcriptions, speaker
+/// re-clustering, dictation and tasks waiting for a slot. Returns how many
+/// tasks and queued jobs were cancelled.
+#[tauri::command]
+pub async fn cancel_all_tasks<R: Runtime>(
+    app: AppHandle<R>,
+    state: tauri::State<'_, AppState>,
+) -> Result<usize, String> {
+    // Empty the queue first so the worker doesn't start the next job
+    let queued_jobs = {
+        let db = state.db().await;
+        crate::audio::retranscription::clear_pending_retranscription_jobs(&app, &db)
+            .map_err(|e| e.to_string())?
+    };
+    let retranscriptions = crate::audio::retranscription::cancel_all_retranscriptions(&app);
+    let standalone_jobs = crate::audio::retranscription::cancel_standalone_jobs();
+    let chat_tasks = crate::chat::task_registry::cancel_all_tasks();
+    let waiting_tasks = cancel_queued_tasks();
+
+    let dictations = if crate::audio::dictation::is_dictating() {
+        match crate::audio::dictation::stop_dictation(app.clone()).await {
+            Ok(_) => 1,
+            Err(e) => {
+                log::warn!("Failed to stop dictation: {}", e);
+                0
+            }
+        }
+    } else {
+        0
+    };
+
+    let total = queued_jobs + retranscriptions + standalone_jobs + chat_tasks + dictations + waiting_tasks;
+    info!(
+        "Cancelled all background tasks: {} chat, {} retranscriptions, {} quick transcriptions and re-clusterings, {} dictations, {} queued jobs, {} tasks waiting for a slot",
+        chat_tasks, retranscriptions, standalone_jobs, dictations, queued_jobs, waiting_tasks
+    );
+    Ok(total)
+}
+
+#[tauri::command]
+pub fn set_max_concurrent_background_tasks(max: usize) -> Result<(), String> {
+    if max == 0 {
+        return Err("At least one background task must be allowed to run".to_string());
+    }
+    set_max_concurrent_tasks(max);
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+
+    /// The scheduler is global, so its tests must not overlap
+    static SERIAL: Lazy<tokio::sync::Mutex<()>> = Lazy::new(|| tokio::sync::Mutex::new(()));
+
+    const WAIT: Duration = Duration::from_millis(50);
+
+    #[tokio::test]
+    async fn test_limit_is_enforced() {
+        let _serial = SERIAL.lock().await;
+        set_max_concurrent_tasks(1);
+
+        let first = acquire(TaskKind::Transcription, "first").await.unwrap();
+        assert!(is_busy());
+        assert!(has_tasks(TaskKind::Transcription) && !has_tasks(TaskKind::Llm));
+        // A second task waits while the slot is held
+        assert!(tokio::time::timeout(WAIT, acquire(TaskKind::Llm, "second")).await.is_err());
+
+        drop(first);
+        assert!(!is_busy());
+        let second = tokio::time::timeout(WAIT, acquire(TaskKind::Llm, "second")).await;
+        assert!(second.is_ok());
+
+        drop(second);
+        set_max_concurrent_tasks(DEFAULT_MAX_CONCURRENT_TASKS);
+    }
+
+    #[tokio::test]
+    async fn test_raising_the_limit_wakes_waiting_tasks() {
+        let _serial = SERIAL.lock().await;
+        set_max_concurrent_tasks(1);
+
+        let first = acquire(TaskKind::Transcription, "first").await.unwrap();
+        let waiting = tokio::spawn(acquire(TaskKind::Diarization, "waiting"));
+        tokio::time::sleep(WAIT).await;
+        assert_eq!(scheduler_status().queued, 1);
+
+        set_max_concurrent_tasks(2);
+        let second = tokio::time::timeout(WAIT, waiting).await.expect("task should start").unwrap().unwrap();
+        assert_eq!(scheduler_status().running, 2);
+
+        drop((first, second));
+        set_max_concurrent_tasks(DEFAULT_MAX_CONCURRENT_TASKS);
+    }
+
+    #[tokio::test]
+    async fn test_cancelled_wait_is_removed() {
+        let _serial = SERIAL.lock().await;
+        set_max_concurrent_tasks(1);
+
+        let first = acquire(TaskKind::Transcription, "first").await.unwrap();
+        let token = CancellationToken::new();
+        let cancel = token.clone();
+        tokio::spawn(async move {
+            tokio::time::sleep(WAIT).await;
+            cancel.cancel();
+        });
+        assert!(acquire_or_cancel(TaskKind::Llm, "cancelled", &token).await.is_none());
+
+        // The cancelled task neither stays queued nor takes the slot
+        let status = scheduler_status();
+        assert_eq!((status.running, status.queued), (1, 0));
+        drop(first);
+        assert_eq!(slots().running, 0);
+        assert!(scheduler_status().tasks.is_empty());
+
+        set_max_concurrent_tasks(DEFAULT_MAX_CONCURRENT_TASKS);
+    }
+
+    #[tokio::test]
+    async fn test_cancel_queued_tasks() {
+        let _serial = SERIAL.lock().await;
+        set_max_concurrent_tasks(1);
+
+        let first = acquire(TaskKind::Transcription, "first").await.unwrap();
+        let summary = tokio::spawn(acquire(TaskKind::Llm, "summary"));
+        let token = CancellationToken::new();
+        let chat_token = token.clone();
+        let chat = tokio::spawn(async move { acquire_or_cancel(TaskKind::Llm, "chat", &chat_token).await });
+        tokio::time::sleep(WAIT).await;
+        assert_eq!(scheduler_status().queued, 2);
+
+        // Both waits end, but the chat task is counted by its registry
+        assert_eq!(cancel_queued_tasks(), 1);
+        assert!(summary.await.unwrap().is_err());
+        assert!(chat.await.unwrap().is_none());
+
+        // The running task is not affected
+        let status = scheduler_status();
+        assert_eq!((status.running, status.queued), (1, 0));
+        drop(first);
+        assert!(scheduler_status().tasks.is_empty());
+
+        set_max_concurrent_tasks(DEFAULT_MAX_CONCURRENT_TASKS);
+    }
+
+    #[tokio::test]
+    async fn test_exclusive_task_runs_alone_in_its_kind() {
+        let _serial = SERIAL.lock().await;
+        set_max_concurrent_tasks(3);
+        let token = CancellationToken::new();
+
+        // Waits for the running LLM task, not for other kinds
+        let chat = acquire(TaskKind::Llm, "chat").await.unwrap();
+        let transcription = acquire(TaskKind::Transcription, "transcription").await.unwrap();
+        assert!(tokio::time::timeout(WAIT, acquire_exclusive_or_cancel(TaskKind::Llm, "regenerate", &token))
+            .await
+            .is_err());
+        drop(chat);
+        let exclusive = tokio::time::timeout(WAIT, acquire_exclusive_or_cancel(TaskKind::Llm, "regenerate", &token))
+            .await
+            .expect("exclusive task should start")
+            .unwrap();
+
+        // Other LLM tasks wait while it runs; other kinds don't
+        assert!(tokio::time::timeout(WAIT, acquire(TaskKind::Llm, "summary")).await.is_err());
+        let diarization = tokio::time::timeout(WAIT, acquire(TaskKind::Diarization, "diarization")).await;
+        assert!(diarization.is_ok());
+
+        drop(exclusive);
+        assert!(tokio::time::timeout(WAIT, acquire(TaskKind::Llm, "summary")).await.is_ok());
+
+        drop((transcription, diarization));
+        assert!(scheduler_status().tasks.is_empty());
+        set_max_concurrent_tasks(DEFAULT_MAX_CONCURRENT_TASKS);
+    }
+
+    #[tokio::test]
+    async fn test_completion_during_regeneration_runs_on_original_model() {
+        let _serial = SERIAL.lock().await;
+        set_max_concurrent_tasks(DEFAULT_MAX_CONCURRENT_TASKS);
+        let model = std::sync::Arc::new(Mutex::new("original"));
+        let token = CancellationToken::new();
+
+        // A regeneration switches the shared model for its completion, then restores it
+        let permit = acquire_exclusive_or_cancel(TaskKind::Llm, "regenerate", &token).await.unwrap();
+        *model.lock().unwrap() = "requested";
+
+        let completion_model = model.clone();
+        let completion = tokio::spawn(async move {
+            let _permit = acquire(TaskKind::Llm, "chat").await.unwrap();
+            *completion_model.lock().unwrap()
+        });
+        tokio::time::sleep(WAIT).await;
+        assert!(!completion.is_finished());
+
+        *model.lock().unwrap() = "original";
+        drop(permit);
+        assert_eq!(completion.await.unwrap(), "original");
+    }
+}
@@ -0,0 +1,253 @@
+//! Transcript cleanup
+//!
+//! Sends a recording's segments to the LLM in batches to fix punctuation,
+//! casing and obvious recognition errors, and stores the result as a new
+//! transcript version with the original segment ids and timing. The original
+//! transcript is left as it is.
+
+use std::collections::HashMap;
+use std::ops::Range;
+
+use log::{info, warn};
+use serde_json::Value;
+use tauri::{AppHandle, Emitter};
+
+use crate::chat::tool_orchestration::extract_json_object;
+use crate::database::{TranscriptSegment, TranscriptVersion, TRANSCRIPT_VERSION_CLEANUP};
+use crate::llm_engine::provider::CompletionRequest;
+use crate::state::AppState;
+use crate::task_scheduler::{self, TaskKind};
+
+const CLEANUP_PROMPT: &str = r#"Clean up the numbered transcript segments below. Fix punctuation, capitalization, obvious speech recognition errors and filler words (um, uh), without changing the meaning, rephrasing or merging segments.
+Reply with JSON only, in exactly this shape, with one entry for every segment:
+{"segments": [{"id": 1, "text": "..."}]}"#;
+
+/// Transcript characters sent per request, to stay within small context windows
+const MAX_BATCH_CHARS: usize = 6000;
+const MAX_BATCH_SEGMENTS: usize = 40;
+
+/// A corrected segment whose word count changed more than this ratio is
+/// treated as rewritten and the original text is kept
+const MAX_WORD_COUNT_CHANGE: f64 = 0.5;
+
+/// Consecutive ranges of segments, each fitting in one request
+pub(crate) fn batch_ranges(segments: &[TranscriptSegment]) -> Vec<Range<usize>> {
+    let mut ranges = Vec::new();
+    let mut start = 0;
+    let mut chars = 0;
+
+    for (index, segment) in segments.iter().enumerate() {
+        let full = index > start
+            && (chars + segment.text.len() > MAX_BATCH_CHARS || index - start >= MAX_BATCH_SEGMENTS);
+        if full {
+            ranges.push(start..index);
+            start = index;
+            chars = 0;
+        }
+        chars += segment.text.len();
+    }
+    if start < segments.len() {
+        ranges.push(start..segments.len());
+    }
+    ranges
+}
+
+/// Segments numbered from 1 for the prompt
+pub(crate) fn batch_text(segments: &[TranscriptSegment]) -> String {
+    segments
+        .iter()
+        .enumerate()
+        .map(|(index, segment)| format!("{}. {}", index + 1, segment.text.trim()))
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// Corrected text by segment number, tolerating code fences and text around the JSON
+fn parse_cleanup(output: &str) -> Result<HashMap<usize, String>, String> {
+    let start = output
+        .find('{')
+        .ok_or_else(|| "The model did not return JSON".to_string())?;
+    let json = extract_json_object(&output[start..])
+        .ok_or_else(|| "The model returned incomplete JSON".to_string())?;
+    let value: Value = serde_json::from_str(&json)
+        .map_err(|e| format!("The model returned invalid JSON: {}", e))?;
+
+    let Some(Value::Array(items)) = value.get("segments") else {
+        return Err("The model's JSON has no segments list".to_string());
+    };
+
+    Ok(items
+        .iter()
+        .filter_map(|item| {
+            let id = match item.get("id")? {
+                Value::Number(n) => n.as_u64()? as usize,
+                Value::String(s) => s.trim().parse().ok()?,
+                _ => return None,
+            };
+            let text = item.get("text")?.as_str()?.trim();
+            (!text.is_empty()).then(|| (id, text.to_string()))
+        })
+        .collect())
+}
+
+/// Whether a correction keeps roughly the same amount of speech
+fn is_plausible_correction(original: &str, corrected: &str) -> bool {
+    let before = original.split_whitespace().count() as f64;
+    let after = corrected.split_whitespace().count() as f64;
+    before == 0.0 || ((after - before).abs() / before) <= MAX_WORD_COUNT_CHANGE
+}
+
+/// Apply the corrections for one batch; returns how many segments changed
+fn apply_corrections(segments: &mut [TranscriptSegment], corrections: &HashMap<usize, String>) -> usize {
+    let mut changed = 0;
+    for (index, segment) in segments.iter_mut().enumerate() {
+        let Some(corrected) = corrections.get(&(index + 1)) else {
+            continue;
+        };
+        if corrected != &segment.text && is_plausible_correction(&segment.text, corrected) {
+            segment.text = corrected.clone();
+            changed += 1;
+        }
+    }
+    changed
+}
+
+/// Clean up a recording's transcript with the LLM and store it as a new
+/// transcript version. Segments the model skipped or rewrote too heavily keep
+/// their original text, as do batches whose request fails. Emits
+/// `transcript-cleanup-progress` after each batch.
+#[tauri::command]
+pub async fn cleanup_transcript(
+    app: AppHandle,
+    state: tauri::State<'_, AppState>,
+    recording_id: String,
+) -> Result<TranscriptVersion, String> {
+    let mut segments = state
+        .db()
+        .await
+        .get_transcript_segments(&recording_id)
+        .map_err(|e| e.to_string())?;
+    if segments.is_empty() {
+        return Err(format!("Recording {} has no transcript", recording_id));
+    }
+
+    let _permit = task_scheduler::acquire(TaskKind::Llm, format!("Clean up transcript of {}", recording_id)).await?;
+    if !state.llm_engine.read().await.is_ready().await {
+        return Err("No LLM provider is ready".to_string());
+    }
+
+    let ranges = batch_ranges(&segments);
+    let mut model_id = None;
+    let mut changed = 0;
+
+    for (batch, range) in ranges.iter().enumerate() {
+        let batch_segments = &mut segments[range.clone()];
+        let request = CompletionRequest {
+            max_tokens: Some(4096),
+            temperature: Some(0.1),
+            ..CompletionRequest::with_system_and_user(CLEANUP_PROMPT, batch_text(batch_segments))
+        };
+
+        // Locked per batch so model switches aren't blocked for the whole cleanup
+        let result = state.llm_engine.read().await.complete(request).await;
+        match result {
+            Ok(response) => {
+                match parse_cleanup(&response.content) {
+                    Ok(corrections) => changed += apply_corrections(batch_segments, &corrections),
+                    Err(e) => warn!(
+                        "Cleanup of recording {} batch {}: {} - keeping original text",
+                        recording_id, batch + 1, e
+                    ),
+                }
+                model_id = Some(response.model);
+            }
+            Err(e) => warn!(
+                "Cleanup of recording {} batch {} failed: {} - keeping original text",
+                recording_id, batch + 1, e
+            ),
+        }
+
+        let _ = app.emit("transcript-cleanup-progress", serde_json::json!({
+            "recording_id": recording_id,
+            "batch": batch + 1,
+            "total_batches": ranges.len(),
+        }));
+    }
+    if model_id.is_none() {
+        return Err(format!("Cleanup of recording {} failed for every batch", recording_id));
+    }
+
+    let version = TranscriptVersion {
+        id: format!("tv_{}", uuid::Uuid::new_v4()),
+        recording_id: recording_id.clone(),
+        kind: TRANSCRIPT_VERSION_CLEANUP.to_string(),
+        segments,
+        model_id,
+        created_at: chrono::Utc::now().to_rfc3339(),
+    };
+    state
+        .db()
+        .await
+        .save_transcript_version(&version)
+        .map_err(|e| e.to_string())?;
+
+    info!(
+        "🧹 Cleaned up transcript of recording {}: {} of {} segments corrected in {} batches",
+        recording_id,
+        changed,
+        version.segments.len(),
+        ranges.len()
+    );
+    Ok(version)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_batch_ranges() {
+        let long = "x".repeat(MAX_BATCH_CHARS / 2 + 1);
+        let segments = vec![
+            TranscriptSegment::test_segment(0.0, 1.0, &long),
+            TranscriptSegment::test_segment(0.0, 1.0, &long),
+            TranscriptSegment::test_segment(0.0, 1.0, "short"),
+        ];
+        assert_eq!(batch_ranges(&segments), vec![0..1, 1..3]);
+
+        let many: Vec<_> = (0..MAX_BATCH_SEGMENTS + 1)
+            .map(|_| TranscriptSegment::test_segment(0.0, 1.0, "hi"))
+            .collect();
+        assert_eq!(batch_ranges(&many), vec![0..MAX_BATCH_SEGMENTS, MAX_BATCH_SEGMENTS..MAX_BATCH_SEGMENTS + 1]);
+        assert!(batch_ranges(&[]).is_empty());
+    }
+
+    #[test]
+    fn test_parse_cleanup() {
+        let output = "```json\n{\"segments\": [{\"id\": 1, \"text\": \"Hello, world.\"}, \
+                      {\"id\": \"2\", \"text\": \"Fine {really}.\"}, {\"id\": 3, \"text\": \"\"}]}\n```";
+        let corrections = parse_cleanup(output).unwrap();
+        assert_eq!(corrections.len(), 2);
+        assert_eq!(corrections[&1], "Hello, world.");
+        assert_eq!(corrections[&2], "Fine {really}.");
+        assert!(parse_cleanup("Sure, here it is").is_err());
+        assert!(parse_cleanup("{\"text\": \"no list\"}").is_err());
+    }
+
+    #[test]
+    fn test_apply_corrections_keeps_rewrites_and_missing() {
+        let mut segments = vec![
+            TranscriptSegment::test_segment(0.0, 1.0, "um so we ship it friday"),
+            TranscriptSegment::test_segment(0.0, 1.0, "the budget is fine"),
+            TranscriptSegment::test_segment(0.0, 1.0, "ok"),
+        ];
+        let corrections = HashMap::from([
+            (1, "So we ship it Friday.".to_string()),
+            (2, "Everyone agreed that the budget for the next quarter is completely fine.".to_string()),
+        ]);
+        assert_eq!(apply_corrections(&mut segments, &corrections), 1);
+        assert_eq!(segments[0].text, "So we ship it Friday.");
+        assert_eq!(segments[1].text, "the budget is fine");
+        assert_eq!(segments[2].text, "ok");
+    }
+}
@@ -0,0 +1,212 @@
+//! Transcript keywords
+//!
+//! Word frequencies over a recording's transcript for a keyword cloud:
+//! text is lowercased and split into words, stopwords, filler words, numbers
+//! and very short words are dropped, and the most frequent terms are returned,
+//! optionally broken down per speaker.
+
+use std::collections::{HashMap, HashSet};
+
+use once_cell::sync::Lazy;
+use serde::Serialize;
+
+use crate::database::TranscriptSegment;
+use crate::meeting_analytics::{UNKNOWN_SPEAKER_ID, UNKNOWN_SPEAKER_LABEL};
+use crate::state::AppState;
+
+/// Words shorter than this carry little meaning on their own
+const MIN_WORD_CHARS: usize = 3;
+
+/// Common English words and speech fillers left out of keyword counts
+static STOPWORDS: Lazy<HashSet<&'static str>> = Lazy::new(|| {
+    [
+        "about", "above", "after", "again", "against", "all", "also", "and", "any", "are", "aren't",
+        "because", "been", "before", "being", "below", "between", "both", "but", "can", "can't",
+        "cannot", "could", "couldn't", "did", "didn't", "does", "doesn't", "doing", "don't", "down",
+        "during", "each", "even", "few", "for", "from", "further", "get", "gets", "getting", "going",
+        "gonna", "got", "had", "hadn't", "has", "hasn't", "have", "haven't", "having", "he'd",
+        "he'll", "her", "here", "hers", "herself", "him", "himself", "his", "how", "i'd", "i'll",
+        "i'm", "i've", "into", "isn't", "it's", "its", "itself", "just", "know", "let's", "like",
+        "maybe", "mean", "more", "most", "much", "mustn't", "myself", "need", "nor", "not", "now",
+        "off", "okay", "once", "one", "only", "other", "ought", "our", "ours", "ourselves", "out",
+        "over", "own", "really", "right", "said", "same", "say", "see", "she", "she'd", "she'll",
+        "she's", "should", "shouldn't", "some", "something", "still", "such", "sure", "than",
+        "that", "that's", "the", "their", "theirs", "them", "themselves", "then", "there",
+        "there's", "these", "they", "they'd", "they'll", "they're", "they've", "thing", "things",
+        "think", "this", "those", "through", "too", "under", "until", "very", "want", "was",
+        "wasn't", "way", "we'd", "we'll", "we're", "we've", "well", "were", "weren't", "what",
+        "what's", "when", "where", "which", "while", "who", "who's", "whom", "why", "will", "with",
+        "won't", "would", "wouldn't", "yeah", "yes", "you", "you'd", "you'll", "you're", "you've",
+        "your", "yours", "yourself", "yourselves", "actually", "basically", "kind", "lot",
+        "uh", "uhm", "umm", "hmm", "mhm", "gotta", "wanna", "stuff",
+    ]
+    .into_iter()
+    .collect()
+});
+
+/// A term and how often it was said
+#[derive(Debug, Clone, Serialize, PartialEq, Eq)]
+pub struct KeywordCount {
+    pub term: String,
+    pub count: usize,
+}
+
+/// Top terms for one speaker
+#[derive(Debug, Clone, Serialize)]
+pub struct SpeakerKeywords {
+    pub speaker_id: String,
+    pub speaker_label: String,
+    pub keywords: Vec<KeywordCount>,
+}
+
+/// Keyword cloud data for a recording
+#[derive(Debug, Clone, Serialize)]
+pub struct TranscriptKeywords {
+    pub recording_id: String,
+    /// Words counted after removing stopwords
+    pub total_terms: usize,
+    /// Most frequent terms, highest count first
+    pub keywords: Vec<KeywordCount>,
+    /// Per-speaker top terms, when requested
+    pub speakers: Option<Vec<SpeakerKeywords>>,
+}
+
+/// Lowercased words of `text` worth counting
+fn tokenize(text: &str) -> impl Iterator<Item = String> + '_ {
+    text.split(|c: char| !(c.is_alphanumeric() || c == '\'' || c == '’'))
+        .map(|word| {
+            let word = word.replace('’', "'").to_lowercase();
+            let word = word.trim_matches('\'');
+            word.strip_suffix("'s").unwrap_or(word).to_string()
+        })
+        .filter(|word| {
+            word.chars().count() >= MIN_WORD_CHARS
+                && !word.chars().all(|c| c.is_numeric() || c == '\'')
+                && !STOPWORDS.contains(word.as_str())
+        })
+}
+
+/// The `top_n` most frequent terms, ties broken alphabetically
+fn top_terms(counts: HashMap<String, usize>, top_n: usize) -> Vec<KeywordCount> {
+    let mut terms: Vec<KeywordCount> = counts
+        .into_iter()
+        .map(|(term, count)| KeywordCount { term, count })
+        .collect();
+    terms.sort_by(|a, b| b.count.cmp(&a.count).then_with(|| a.term.cmp(&b.term)));
+    terms.truncate(top_n);
+    terms
+}
+
+/// Count keywords over a recording's transcript segments
+pub fn compute_keywords(
+    recording_id: &str,
+    segments: &[TranscriptSegment],
+    top_n: usize,
+    by_speaker: bool,
+) -> TranscriptKeywords {
+    let mut counts: HashMap<String, usize> = HashMap::new();
+    // (speaker_id, label, counts) in order of first appearance
+    let mut speakers: Vec<(String, String, HashMap<String, usize>)> = Vec::new();
+    let mut total_terms = 0;
+
+    for segment in segments {
+        let speaker = if by_speaker {
+            let speaker_id = segment.speaker_id.as_deref().unwrap_or(UNKNOWN_SPEAKER_ID);
+            let index = match speakers.iter().position(|(id, _, _)| id == speaker_id) {
+                Some(index) => index,
+                None => {
+                    let label = segment
+                        .speaker_label
+                        .clone()
+                        .unwrap_or_else(|| UNKNOWN_SPEAKER_LABEL.to_string());
+                    speakers.push((speaker_id.to_string(), label, HashMap::new()));
+                    speakers.len() - 1
+                }
+            };
+            Some(index)
+        } else {
+            None
+        };
+
+        for term in tokenize(&segment.text) {
+            total_terms += 1;
+            if let Some(index) = speaker {
+                *speakers[index].2.entry(term.clone()).or_default() += 1;
+            }
+            *counts.entry(term).or_default() += 1;
+        }
+    }
+
+    TranscriptKeywords {
+        recording_id: recording_id.to_string(),
+        total_terms,
+        keywords: top_terms(counts, top_n),
+        speakers: by_speaker.then(|| {
+            speakers
+                .into_iter()
+                .map(|(speaker_id, speaker_label, counts)| SpeakerKeywords {
+                    speaker_id,
+                    speaker_label,
+                    keywords: top_terms(counts, top_n),
+                })
+                .collect()
+        }),
+    }
+}
+
+/// Most frequent meaningful words of a recording, for a keyword cloud.
+/// With `by_speaker`, also returns the top terms of each speaker.
+#[tauri::command]
+pub async fn get_transcript_keywords(
+    state: tauri::State<'_, AppState>,
+    recording_id: String,
+    top_n: usize,
+    by_speaker: Option<bool>,
+) -> Result<TranscriptKeywords, String> {
+    let segments = state
+        .db()
+        .await
+        .get_transcript_segments(&recording_id)
+        .map_err(|e| e.to_string())?;
+
+    if segments.is_empty() {
+        return Err(format!("Recording {} has no transcript", recording_id));
+    }
+
+    Ok(compute_keywords(&recording_id, &segments, top_n, by_speaker.unwrap_or(false)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_tokenize() {
+        let words: Vec<String> = tokenize("Um, the Budget’s budget: we've got 2024 budgets, OK? API").collect();
+        assert_eq!(words, vec!["budget", "budget", "budgets", "api"]);
+    }
+
+    #[test]
+    fn test_compute_keywords() {
+        let segments = vec![
+            TranscriptSegment::test_segment(0.0, 1.0, "The roadmap and the budget. Budget first!")
+                .with_speaker("a", "A"),
+            TranscriptSegment::test_segment(0.0, 1.0, "Roadmap, roadmap, hiring.").with_speaker("b", "B"),
+            TranscriptSegment::test_segment(0.0, 1.0, "Budget"),
+        ];
+
+        let report = compute_keywords("rec", &segments, 2, false);
+        assert_eq!(report.total_terms, 8);
+        assert_eq!(report.keywords, vec![
+            KeywordCount { term: "budget".to_string(), count: 3 },
+            KeywordCount { term: "roadmap".to_string(), count: 3 },
+        ]);
+        assert!(report.speakers.is_none());
+
+        let speakers = compute_keywords("rec", &segments, 5, true).speakers.unwrap();
+        assert_eq!(speakers.len(), 3);
+        assert_eq!(speakers[0].keywords[0], KeywordCount { term: "budget".to_string(), count: 2 });
+        assert_eq!(speakers[1].keywords[0], KeywordCount { term: "roadmap".to_string(), count: 2 });
+        assert_eq!(speakers[2].speaker_label, UNKNOWN_SPEAKER_LABEL);
+    }
+}
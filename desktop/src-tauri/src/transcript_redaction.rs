@@ -0,0 +1,341 @@
+//! Transcript redaction
+//!
+//! Removes personal information from a recording's transcript before it is
+//! shared: email addresses, phone numbers, a list of names and any custom
+//! patterns are replaced by placeholders such as `[EMAIL]`. An optional LLM
+//! pass lists further personal details per batch, which are then replaced
+//! verbatim, so the model never rewrites the transcript itself. The result is
+//! stored as a `redacted` transcript version; the original is left as it is.
+
+use log::{info, warn};
+use once_cell::sync::Lazy;
+use regex::{Regex, RegexBuilder};
+use serde::Deserialize;
+use serde_json::Value;
+use tauri::{AppHandle, Emitter};
+
+use crate::chat::tool_orchestration::extract_json_object;
+use crate::database::{TranscriptSegment, TranscriptVersion, TRANSCRIPT_VERSION_REDACTED};
+use crate::llm_engine::provider::CompletionRequest;
+use crate::state::AppState;
+use crate::task_scheduler::{self, TaskKind};
+use crate::transcript_cleanup::{batch_ranges, batch_text};
+
+const PII_PROMPT: &str = r#"List the personal information in the numbered transcript segments below: names of people, email addresses, phone numbers, street addresses, account or ID numbers. Copy each item exactly as it is written. Don't list company names, products or places that don't identify a person.
+Reply with JSON only, in exactly this shape:
+{"pii": ["...", "..."]}"#;
+
+static EMAIL_RE: Lazy<Regex> =
+    Lazy::new(|| Regex::new(r"[A-Za-z0-9._%+-]+@[A-Za-z0-9.-]+\.[A-Za-z]{2,}").unwrap());
+
+/// Phone numbers with at least 7 digits, allowing spaces, dots, dashes,
+/// brackets and a leading `+`
+static PHONE_RE: Lazy<Regex> =
+    Lazy::new(|| Regex::new(r"(?:\+|\b)\d(?:[\s.\-()]{0,2}\d){6,}\b").unwrap());
+
+/// Items the LLM lists shorter than this are ignored, to avoid redacting
+/// every occurrence of a stray letter or word
+const MIN_LLM_ITEM_CHARS: usize = 3;
+
+/// Literal items compiled into one regex; long lists are split so no single
+/// regex outgrows the compiled size limit
+const LITERALS_PER_PATTERN: usize = 200;
+
+/// What to redact
+#[derive(Debug, Clone, Deserialize)]
+pub struct RedactionPatterns {
+    #[serde(default = "default_true")]
+    pub emails: bool,
+    #[serde(default = "default_true")]
+    pub phone_numbers: bool,
+    /// Names replaced by `[NAME]`, matched as whole words ignoring case
+    #[serde(default)]
+    pub names: Vec<String>,
+    /// Extra regular expressions replaced by `[REDACTED]`
+    #[serde(default)]
+    pub custom_patterns: Vec<String>,
+    /// Also ask the LLM for personal information the patterns miss
+    #[serde(default)]
+    pub use_llm: bool,
+}
+
+fn default_true() -> bool {
+    true
+}
+
+/// Compiled redaction rules, applied in order
+pub struct Redactor {
+    rules: Vec<(Regex, &'static str)>,
+}
+
+impl Redactor {
+    pub fn new(patterns: &RedactionPatterns) -> Result<Self, String> {
+        let mut rules = Vec::new();
+        if patterns.emails {
+            rules.push((EMAIL_RE.clone(), "[EMAIL]"));
+        }
+        if patterns.phone_numbers {
+            rules.push((PHONE_RE.clone(), "[PHONE]"));
+        }
+        for names in literal_patterns(&patterns.names)? {
+            rules.push((names, "[NAME]"));
+        }
+        for pattern in patterns.custom_patterns.iter().filter(|p| !p.trim().is_empty()) {
+            let regex = Regex::new(pattern)
+                .map_err(|e| format!("Invalid redaction pattern '{}': {}", pattern, e))?;
+            rules.push((regex, "[REDACTED]"));
+        }
+        Ok(Self { rules })
+    }
+
+    /// Redactor for items found by the LLM, replaced by `[REDACTED]`
+    fn from_literals(items: &[String]) -> Result<Self, String> {
+        let rules = literal_patterns(items)?
+            .into_iter()
+            .map(|regex| (regex, "[REDACTED]"))
+            .collect();
+        Ok(Self { rules })
+    }
+
+    /// Redacted text and the number of replacements made
+    pub fn redact(&self, text: &str) -> (String, usize) {
+        let mut text = text.to_string();
+        let mut count = 0;
+        for (regex, placeholder) in &self.rules {
+            let matches = regex.find_iter(&text).count();
+            if matches > 0 {
+                count += matches;
+                text = regex.replace_all(&text, *placeholder).into_owned();
+            }
+        }
+        (text, count)
+    }
+
+    /// Redact segment text and speaker labels in place; returns the number of replacements
+    pub fn redact_segments(&self, segments: &mut [TranscriptSegment]) -> usize {
+        let mut count = 0;
+        for segment in segments {
+            let (text, replaced) = self.redact(&segment.text);
+            segment.text = text;
+            count += replaced;
+            if let Some(label) = &segment.speaker_label {
+                let (label, replaced) = self.redact(label);
+                segment.speaker_label = Some(label);
+                count += replaced;
+            }
+        }
+        count
+    }
+}
+
+/// Case-insensitive whole-word regexes matching `items` literally, to apply in
+/// order. Items are sorted longest first so "Anna Smith" wins over "Anna",
+/// also across regexes. Fails rather than leaving items unredacted.
+fn literal_patterns(items: &[String]) -> Result<Vec<Regex>, String> {
+    let mut items: Vec<&str> = items
+        .iter()
+        .map(|item| item.trim())
+        .filter(|item| !item.is_empty())
+        .collect();
+    items.sort_by(|a, b| b.len().cmp(&a.len()).then_with(|| a.cmp(b)));
+    items.dedup();
+
+    // \b only applies next to word characters, so items like "+1 555" still match
+    items
+        .chunks(LITERALS_PER_PATTERN)
+        .map(|chunk| {
+            let alternatives: Vec<String> = chunk
+                .iter()
+                .map(|item| {
+                    let start = if item.starts_with(|c: char| c.is_alphanumeric()) { r"\b" } else { "" };
+                    let end = if item.ends_with(|c: char| c.is_alphanumeric()) { r"\b" } else { "" };
+                    format!("{}{}{}", start, regex::escape(item), end)
+                })
+                .collect();
+            RegexBuilder::new(&alternatives.join("|"))
+                .case_insensitive(true)
+                .build()
+                .map_err(|e| format!("Failed to build redaction pattern: {}", e))
+        })
+        .collect()
+}
+
+/// Personal information items listed by the model
+fn parse_pii(output: &str) -> Result<Vec<String>, String> {
+    let start = output
+        .find('{')
+        .ok_or_else(|| "The model did not return JSON".to_string())?;
+    let json = extract_json_object(&output[start..])
+        .ok_or_else(|| "The model returned incomplete JSON".to_string())?;
+    let value: Value = serde_json::from_str(&json)
+        .map_err(|e| format!("The model returned invalid JSON: {}", e))?;
+
+    let Some(Value::Array(items)) = value.get("pii") else {
+        return Err("The model's JSON has no pii list".to_string());
+    };
+
+    Ok(items
+        .iter()
+        .filter_map(|item| item.as_str())
+        .map(|item| item.trim().to_string())
+        .filter(|item| item.chars().count() >= MIN_LLM_ITEM_CHARS)
+        .collect())
+}
+
+/// Ask the LLM for personal information in the (already pattern-redacted)
+/// segments, sending all batches in one `complete_batch` call
+async fn find_pii_with_llm(
+    app: &AppHandle,
+    state: &AppState,
+    recording_id: &str,
+    segments: &[TranscriptSegment],
+) -> Result<(Vec<String>, Option<String>), String> {
+    let _permit = task_scheduler::acquire(TaskKind::Llm, format!("Find personal information in {}", recording_id)).await?;
+    let engine = state.llm_engine.read().await;
+    if !engine.is_ready().await {
+        return Err("No LLM provider is ready".to_string());
+    }
+
+    let ranges = batch_ranges(segments);
+    let mut items = Vec::new();
+    let mut model_id = None;
+
+    // The batches are independent, so they are sent together
+    let requests = ranges
+        .iter()
+        .map(|range| CompletionRequest {
+            max_tokens: Some(1024),
+            temperature: Some(0.0),
+            ..CompletionRequest::with_system_and_user(PII_PROMPT, batch_text(&segments[range.clone()]))
+        })
+        .collect();
+    let responses = engine.complete_batch(requests).await.map_err(|e| e.to_string())?;
+
+    for (batch, response) in responses.into_iter().enumerate() {
+        let response = response.map_err(|e| e.to_string())?;
+        match parse_pii(&response.content) {
+            Ok(found) => items.extend(found),
+            Err(e) => warn!("PII detection for recording {} batch {}: {}", recording_id, batch + 1, e),
+        }
+        model_id = Some(response.model);
+
+        let _ = app.emit("transcript-redaction-progress", serde_json::json!({
+            "recording_id": recording_id,
+            "batch": batch + 1,
+            "total_batches": ranges.len(),
+        }));
+    }
+
+    Ok((items, model_id))
+}
+
+/// Redact personal information from a recording's transcript and store the
+/// result as a new `redacted` transcript version, which exports can target.
+/// With `use_llm`, emits `transcript-redaction-progress` after each batch.
+#[tauri::command]
+pub async fn redact_transcript(
+    app: AppHandle,
+    state: tauri::State<'_, AppState>,
+    recording_id: String,
+    patterns: RedactionPatterns,
+) -> Result<TranscriptVersion, String> {
+    let redactor = Redactor::new(&patterns)?;
+    let mut segments = state
+        .db()
+        .await
+        .get_transcript_segments(&recording_id)
+        .map_err(|e| e.to_string())?;
+    if segments.is_empty() {
+        return Err(format!("Recording {} has no transcript", recording_id));
+    }
+
+    let mut replaced = redactor.redact_segments(&mut segments);
+    let mut model_id = None;
+
+    if patterns.use_llm {
+        let (items, model) = find_pii_with_llm(&app, &state, &recording_id, &segments).await?;
+        replaced += Redactor::from_literals(&items)?.redact_segments(&mut segments);
+        model_id = model;
+    }
+
+    let version = TranscriptVersion {
+        id: format!("tv_{}", uuid::Uuid::new_v4()),
+        recording_id: recording_id.clone(),
+        kind: TRANSCRIPT_VERSION_REDACTED.to_string(),
+        segments,
+        model_id,
+        created_at: chrono::Utc::now().to_rfc3339(),
+    };
+    state
+        .db()
+        .await
+        .save_transcript_version(&version)
+        .map_err(|e| e.to_string())?;
+
+    info!("🔒 Redacted transcript of recording {}: {} replacements", recording_id, replaced);
+    Ok(version)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn patterns(names: &[&str]) -> RedactionPatterns {
+        RedactionPatterns {
+            emails: true,
+            phone_numbers: true,
+            names: names.iter().map(|n| n.to_string()).collect(),
+            custom_patterns: Vec::new(),
+            use_llm: false,
+        }
+    }
+
+    #[test]
+    fn test_redact_emails_and_phones() {
+        let redactor = Redactor::new(&patterns(&[])).unwrap();
+        let (text, count) = redactor.redact("Mail anna.smith@example.com or call +1 (555) 123-4567.");
+        assert_eq!(text, "Mail [EMAIL] or call [PHONE].");
+        assert_eq!(count, 2);
+        // Short numbers are left alone
+        assert_eq!(redactor.redact("We need 3 people by 2025.").0, "We need 3 people by 2025.");
+    }
+
+    #[test]
+    fn test_redact_names() {
+        let redactor = Redactor::new(&patterns(&["Anna", "anna smith", " "])).unwrap();
+        let (text, count) = redactor.redact("Anna Smith said hi to ANNA, not Annabel.");
+        assert_eq!(text, "[NAME] said hi to [NAME], not Annabel.");
+        assert_eq!(count, 2);
+    }
+
+    #[test]
+    fn test_long_name_lists_are_split() {
+        let mut names: Vec<String> = (0..LITERALS_PER_PATTERN * 2).map(|i| format!("Person{}", i)).collect();
+        names.push("Anna".to_string());
+        names.push("Anna Smith".to_string());
+        let redactor = Redactor::new(&patterns(&names.iter().map(String::as_str).collect::<Vec<_>>())).unwrap();
+        assert_eq!(redactor.rules.len(), 2 + 3);
+
+        let (text, count) = redactor.redact("Anna Smith met Person0 and Person399.");
+        assert_eq!(text, "[NAME] met [NAME] and [NAME].");
+        assert_eq!(count, 3);
+    }
+
+    #[test]
+    fn test_custom_patterns() {
+        let mut custom = patterns(&[]);
+        custom.custom_patterns = vec![r"ACC-\d+".to_string()];
+        let redactor = Redactor::new(&custom).unwrap();
+        assert_eq!(redactor.redact("Account ACC-42 is closed").0, "Account [REDACTED] is closed");
+
+        custom.custom_patterns = vec!["(".to_string()];
+        assert!(Redactor::new(&custom).is_err());
+    }
+
+    #[test]
+    fn test_parse_pii() {
+        let output = "```json\n{\"pii\": [\"Bob Jones\", \"42 Elm Street\", \"x\", 7]}\n```";
+        assert_eq!(parse_pii(output).unwrap(), vec!["Bob Jones", "42 Elm Street"]);
+        assert!(parse_pii("None found").is_err());
+    }
+}
@@ -0,0 +1,217 @@
+//! Transcription quality
+//!
+//! A score from 0 to 1 computed after a recording is transcribed, so the UI
+//! can suggest re-transcribing poor transcripts (quiet audio, heavy accents)
+//! with a bigger model. It combines the average segment confidence, the share
+//! of the recording covered by speech and how many segments the repetition
+//! cleaner would shorten (a sign of Whisper looping).
+//!
+//! Retranscription doesn't extract Whisper's confidence yet and gives every
+//! segment `PLACEHOLDER_CONFIDENCE`. Those segments don't count towards the
+//! average confidence; when no segment has a measured confidence the score is
+//! built from the other two signals alone.
+
+use anyhow::{Context, Result};
+use log::info;
+use serde::Serialize;
+
+use crate::audio::retranscription::PLACEHOLDER_CONFIDENCE;
+use crate::database::{DatabaseManager, TranscriptSegment};
+use crate::state::AppState;
+use crate::whisper_engine::text_cleaner::clean_repetitive_text;
+
+/// Recordings scoring below this are worth re-transcribing
+pub const SUGGEST_RETRANSCRIBE_BELOW: f64 = 0.6;
+
+/// Share of speech at which the speech signal counts as fully healthy;
+/// meetings have pauses, so less than full coverage is normal
+const HEALTHY_SPEECH_RATIO: f64 = 0.5;
+
+const CONFIDENCE_WEIGHT: f64 = 0.6;
+const SPEECH_WEIGHT: f64 = 0.25;
+const REPETITION_WEIGHT: f64 = 0.15;
+
+/// The score and the signals it was computed from
+#[derive(Debug, Clone, Serialize)]
+pub struct TranscriptionQuality {
+    pub recording_id: String,
+    /// 0.0 - 1.0, higher is better
+    pub score: f64,
+    /// Duration-weighted average segment confidence, 0.0 - 1.0;
+    /// None when no segment has a measured confidence
+    pub average_confidence: Option<f64>,
+    /// Share of the recording covered by transcribed speech, 0.0 - 1.0
+    pub speech_ratio: f64,
+    /// Segments containing repeated words or phrases
+    pub repetitive_segments: usize,
+    pub segment_count: usize,
+    pub suggest_retranscribe: bool,
+}
+
+/// Whether the repetition cleaner would drop words from `text`
+fn is_repetitive(text: &str) -> bool {
+    let words = text.split_whitespace().count();
+    words > 0 && clean_repetitive_text(text).split_whitespace().count() < words
+}
+
+/// Duration-weighted average confidence of the segments whose confidence was
+/// measured, or None if there are none
+fn average_measured_confidence(segments: &[TranscriptSegment]) -> Option<f64> {
+    let measured: Vec<&TranscriptSegment> = segments
+        .iter()
+        .filter(|s| s.confidence != PLACEHOLDER_CONFIDENCE)
+        .collect();
+    if measured.is_empty() {
+        return None;
+    }
+
+    let seconds: f64 = measured.iter().map(|s| s.duration.max(0.0)).sum();
+    let average = if seconds > 0.0 {
+        measured.iter().map(|s| s.confidence as f64 * s.duration.max(0.0)).sum::<f64>() / seconds
+    } else {
+        measured.iter().map(|s| s.confidence as f64).sum::<f64>() / measured.len() as f64
+    };
+    Some(average.clamp(0.0, 1.0))
+}
+
+/// Score a transcript. `duration_seconds` is the recording length; without
+/// it the end of the last segment is used. Returns None without segments.
+pub fn assess(
+    recording_id: &str,
+    segments: &[TranscriptSegment],
+    duration_seconds: Option<f64>,
+) -> Option<TranscriptionQuality> {
+    if segments.is_empty() {
+        return None;
+    }
+
+    let speech_seconds: f64 = segments.iter().map(|s| s.duration.max(0.0)).sum();
+    let average_confidence = average_measured_confidence(segments);
+
+    let total_seconds = duration_seconds
+        .filter(|d| *d > 0.0)
+        .unwrap_or_else(|| segments.iter().map(|s| s.audio_end_time).fold(0.0, f64::max));
+    let speech_ratio = if total_seconds > 0.0 {
+        (speech_seconds / total_seconds).clamp(0.0, 1.0)
+    } else {
+        0.0
+    };
+
+    let repetitive_segments = segments.iter().filter(|s| is_repetitive(&s.text)).count();
+    let repetitive_share = repetitive_segments as f64 / segments.len() as f64;
+
+    let other_signals = SPEECH_WEIGHT * (speech_ratio / HEALTHY_SPEECH_RATIO).min(1.0)
+        + REPETITION_WEIGHT * (1.0 - repetitive_share);
+    let score = match average_confidence {
+        Some(confidence) => CONFIDENCE_WEIGHT * confidence + other_signals,
+        None => other_signals / (SPEECH_WEIGHT + REPETITION_WEIGHT),
+    };
+    let score = (score * 100.0).round() / 100.0;
+
+    Some(TranscriptionQuality {
+        recording_id: recording_id.to_string(),
+        score,
+        average_confidence,
+        speech_ratio,
+        repetitive_segments,
+        segment_count: segments.len(),
+        suggest_retranscribe: score < SUGGEST_RETRANSCRIBE_BELOW,
+    })
+}
+
+/// Compute and store the quality score of a recording's current transcript.
+/// Returns None (storing nothing) when the recording has no transcript.
+pub fn update_transcription_quality(
+    db: &DatabaseManager,
+    recording_id: &str,
+) -> Result<Option<TranscriptionQuality>> {
+    let recording = db
+        .get_recording(recording_id)?
+        .with_context(|| format!("Recording {} not found", recording_id))?;
+    let segments = db.get_transcript_segments(recording_id)?;
+
+    let Some(quality) = assess(recording_id, &segments, recording.duration_seconds) else {
+        return Ok(None);
+    };
+    db.set_transcription_quality(recording_id, quality.score)?;
+
+    info!(
+        "Transcription quality of recording {}: {:.2} (confidence {:?}, speech {:.2}, {} repetitive of {} segments)",
+        recording_id,
+        quality.score,
+        quality.average_confidence,
+        quality.speech_ratio,
+        quality.repetitive_segments,
+        quality.segment_count
+    );
+    Ok(Some(quality))
+}
+
+/// Quality score of a recording's transcript with the signals behind it.
+/// Recomputed and stored on each call, so older recordings get a score too.
+#[tauri::command]
+pub async fn get_transcription_quality(
+    state: tauri::State<'_, AppState>,
+    recording_id: String,
+) -> Result<TranscriptionQuality, String> {
+    let db = state.db().await;
+    update_transcription_quality(&db, &recording_id)
+        .map_err(|e| format!("{:#}", e))?
+        .ok_or_else(|| format!("Recording {} has no transcript", recording_id))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_good_transcript() {
+        let segments = vec![
+            TranscriptSegment::test_segment(0.0, 10.0, "Let's go over the roadmap for next quarter."),
+            TranscriptSegment::test_segment(10.0, 20.0, "The budget was approved yesterday."),
+        ];
+        let quality = assess("rec", &segments, Some(30.0)).unwrap();
+        assert!((quality.average_confidence.unwrap() - 0.9).abs() < 1e-6);
+        assert!((quality.speech_ratio - 2.0 / 3.0).abs() < 1e-9);
+        assert_eq!(quality.repetitive_segments, 0);
+        assert_eq!(quality.score, 0.94);
+        assert!(!quality.suggest_retranscribe);
+    }
+
+    #[test]
+    fn test_poor_transcript() {
+        let segments = vec![
+            TranscriptSegment::test_segment(0.0, 2.0, "the the the the the the meeting").with_confidence(0.3),
+            TranscriptSegment::test_segment(50.0, 52.0, "thanks for watching").with_confidence(0.4),
+        ];
+        let quality = assess("rec", &segments, None).unwrap();
+        assert!((quality.speech_ratio - 4.0 / 52.0).abs() < 1e-9);
+        assert_eq!(quality.repetitive_segments, 2);
+        assert!(quality.suggest_retranscribe);
+
+        assert!(assess("rec", &[], Some(60.0)).is_none());
+    }
+
+    #[test]
+    fn test_placeholder_confidence_is_ignored() {
+        let segments = vec![
+            TranscriptSegment::test_segment(0.0, 10.0, "Let's go over the roadmap for next quarter.")
+                .with_confidence(PLACEHOLDER_CONFIDENCE),
+            TranscriptSegment::test_segment(10.0, 20.0, "The budget was approved yesterday.")
+                .with_confidence(PLACEHOLDER_CONFIDENCE),
+        ];
+        let quality = assess("rec", &segments, Some(30.0)).unwrap();
+        assert_eq!(quality.average_confidence, None);
+        // Full speech and no repetition score 1.0 without a confidence signal
+        assert_eq!(quality.score, 1.0);
+
+        // A failed chunk (confidence 0.0) is a measured signal
+        let mut segments = segments;
+        segments.push(
+            TranscriptSegment::test_segment(20.0, 30.0, "[Transcription failed for this section]").with_confidence(0.0),
+        );
+        let quality = assess("rec", &segments, Some(30.0)).unwrap();
+        assert_eq!(quality.average_confidence, Some(0.0));
+        assert!(quality.suggest_retranscribe);
+    }
+}
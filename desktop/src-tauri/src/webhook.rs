@@ -0,0 +1,284 @@
+//! Completion webhook
+//!
+//! When `completion_webhook_url` is set, a JSON payload is POSTed to it once a
+//! recording is completed. Requests are retried with backoff, and
+//! `completion-webhook-failed` is emitted if every attempt fails. Hosts that
+//! resolve to private, loopback or link-local addresses are refused unless
+//! `completion_webhook_allow_private` is enabled. The request is sent to the
+//! address that was checked, so a second DNS answer can't redirect it.
+
+use std::net::{IpAddr, Ipv4Addr, SocketAddr};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::time::Duration;
+
+use log::{info, warn};
+use reqwest::Url;
+use serde::Serialize;
+use tauri::{AppHandle, Emitter, Manager, Runtime};
+use tokio::sync::Notify;
+
+use crate::state::AppState;
+
+/// Settings key for the webhook URL (empty or missing disables it)
+pub const COMPLETION_WEBHOOK_SETTING: &str = "completion_webhook_url";
+/// Settings key allowing webhooks to private/local addresses
+pub const COMPLETION_WEBHOOK_ALLOW_PRIVATE_SETTING: &str = "completion_webhook_allow_private";
+
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(10);
+const MAX_ATTEMPTS: u32 = 3;
+const INITIAL_BACKOFF: Duration = Duration::from_secs(2);
+
+/// Deliveries still being attempted, so the app can wait for them before exiting
+static IN_FLIGHT: AtomicUsize = AtomicUsize::new(0);
+static DELIVERY_FINISHED: Notify = Notify::const_new();
+
+/// Payload sent to the webhook
+#[derive(Debug, Clone, Serialize)]
+pub struct CompletionWebhookPayload {
+    pub event: &'static str,
+    pub recording_id: String,
+    pub title: String,
+    pub duration_seconds: Option<f64>,
+    pub transcript_available: bool,
+    pub completed_at: Option<String>,
+}
+
+/// Whether an address is loopback, private, link-local or otherwise not publicly routable
+fn is_private_address(ip: &IpAddr) -> bool {
+    match ip {
+        IpAddr::V4(v4) => is_private_ipv4(v4),
+        IpAddr::V6(v6) => {
+            if let Some(v4) = v6.to_ipv4_mapped() {
+                return is_private_ipv4(&v4);
+            }
+            let first = v6.segments()[0];
+            v6.is_loopback()
+                || v6.is_unspecified()
+                || (first & 0xfe00) == 0xfc00 // unique local fc00::/7
+                || (first & 0xffc0) == 0xfe80 // link-local fe80::/10
+        }
+    }
+}
+
+fn is_private_ipv4(ip: &Ipv4Addr) -> bool {
+    let [a, b, ..] = ip.octets();
+    ip.is_loopback()
+        || ip.is_private()
+        || ip.is_link_local()
+        || ip.is_unspecified()
+        || ip.is_broadcast()
+        || (a == 100 && (64..128).contains(&b)) // carrier-grade NAT 100.64.0.0/10
+}
+
+/// A webhook URL that passed validation
+struct VettedWebhook {
+    url: Url,
+    /// Checked address to connect to when the host is a domain name
+    pinned_address: Option<SocketAddr>,
+}
+
+/// Parse and check a webhook URL, resolving the host to reject private addresses
+pub async fn validate_webhook_url(url: &str, allow_private: bool) -> Result<Url, String> {
+    vet_webhook_url(url, allow_private).await.map(|vetted| vetted.url)
+}
+
+async fn vet_webhook_url(url: &str, allow_private: bool) -> Result<VettedWebhook, String> {
+    let parsed = Url::parse(url.trim()).map_err(|e| format!("Invalid webhook URL: {}", e))?;
+    if !matches!(parsed.scheme(), "http" | "https") {
+        return Err("Webhook URL must use http or https".to_string());
+    }
+    let host = parsed.host_str().ok_or("Webhook URL has no host")?.to_string();
+
+    if allow_private {
+        return Ok(VettedWebhook { url: parsed, pinned_address: None });
+    }
+
+    let port = parsed.port_or_known_default().unwrap_or(443);
+    let host_for_lookup = host.trim_start_matches('[').trim_end_matches(']');
+    let addresses: Vec<SocketAddr> = tokio::net::lookup_host((host_for_lookup, port))
+        .await
+        .map_err(|e| format!("Failed to resolve webhook host {}: {}", host, e))?
+        .collect();
+
+    if addresses.is_empty() {
+        return Err(format!("Webhook host {} did not resolve", host));
+    }
+    if let Some(addr) = addresses.iter().find(|addr| is_private_address(&addr.ip())) {
+        return Err(format!(
+            "Webhook host {} resolves to private address {}; enable private webhooks to allow it",
+            host, addr.ip()
+        ));
+    }
+
+    // IP literals aren't looked up again, so only domain names need pinning
+    let pinned_address = parsed.domain().map(|_| addresses[0]);
+    Ok(VettedWebhook { url: parsed, pinned_address })
+}
+
+/// POST the completion payload for a recording if a webhook is configured
+pub async fn notify_recording_completed<R: Runtime>(app: &AppHandle<R>, recording_id: &str) {
+    let (url, allow_private, payload) = {
+        let state = app.state::<AppState>();
+        let db = state.db().await;
+
+        let url = match db.get_setting(COMPLETION_WEBHOOK_SETTING) {
+            Ok(Some(url)) if !url.trim().is_empty() => url,
+            _ => return,
+        };
+        let allow_private = db
+            .get_bool_setting(COMPLETION_WEBHOOK_ALLOW_PRIVATE_SETTING, false)
+            .unwrap_or(false);
+
+        let recording = match db.get_recording(recording_id) {
+            Ok(Some(recording)) => recording,
+            _ => return,
+        };
+
+        // Live transcripts are written to the meeting folder before the database copy
+        let transcript_available = db
+            .get_transcript_segments(recording_id)
+            .map(|segments| !segments.is_empty())
+            .unwrap_or(false)
+            || recording
+                .folder_path()
+                .and_then(|folder| std::fs::read_to_string(folder.join("transcripts.json")).ok())
+                .and_then(|json| serde_json::from_str::<serde_json::Value>(&json).ok())
+                .and_then(|json| json.get("total_segments").and_then(|v| v.as_u64()))
+                .is_some_and(|count| count > 0);
+
+        let payload = CompletionWebhookPayload {
+            event: "recording.completed",
+            recording_id: recording.id,
+            title: recording.title,
+            duration_seconds: recording.duration_seconds,
+            transcript_available,
+            completed_at: recording.completed_at,
+        };
+        (url, allow_private, payload)
+    };
+
+    let app = app.clone();
+    IN_FLIGHT.fetch_add(1, Ordering::SeqCst);
+    tauri::async_runtime::spawn(async move {
+        if let Err(e) = send_webhook(&url, allow_private, &payload).await {
+            warn!("Completion webhook for recording {} failed: {}", payload.recording_id, e);
+            let _ = app.emit("completion-webhook-failed", serde_json::json!({
+                "recordingId": payload.recording_id,
+                "error": e,
+            }));
+        }
+        IN_FLIGHT.fetch_sub(1, Ordering::SeqCst);
+        DELIVERY_FINISHED.notify_waiters();
+    });
+}
+
+/// Wait up to `timeout` for webhook deliveries still in flight, so exiting
+/// right after a recording completes doesn't drop its notification
+pub async fn wait_for_pending_deliveries(timeout: Duration) {
+    let all_finished = async {
+        loop {
+            // Registered before the check so a delivery finishing in between isn't missed
+            let finished = DELIVERY_FINISHED.notified();
+            if IN_FLIGHT.load(Ordering::SeqCst) == 0 {
+                return;
+            }
+            finished.await;
+        }
+    };
+    if tokio::time::timeout(timeout, all_finished).await.is_err() {
+        warn!("Completion webhook still pending after {}s, exiting anyway", timeout.as_secs());
+    }
+}
+
+async fn send_webhook(url: &str, allow_private: bool, payload: &CompletionWebhookPayload) -> Result<(), String> {
+    let VettedWebhook { url, pinned_address } = vet_webhook_url(url, allow_private).await?;
+
+    // Redirects could point at a private address, so don't follow them
+    let mut builder = reqwest::Client::builder()
+        .timeout(REQUEST_TIMEOUT)
+        .redirect(reqwest::redirect::Policy::none());
+    // Connect to the vetted address instead of resolving the host again
+    if let (Some(domain), Some(addr)) = (url.domain(), pinned_address) {
+        builder = builder.resolve(domain, addr);
+    }
+    let client = builder.build().map_err(|e| e.to_string())?;
+
+    let mut backoff = INITIAL_BACKOFF;
+    let mut last_error = String::new();
+    for attempt in 1..=MAX_ATTEMPTS {
+        match client.post(url.clone()).json(payload).send().await {
+            Ok(response) if response.status().is_success() => {
+                info!("🔔 Completion webhook delivered for recording {}", payload.recording_id);
+                return Ok(());
+            }
+            Ok(response) => last_error = format!("HTTP {}", response.status()),
+            Err(e) => last_error = e.to_string(),
+        }
+
+        if attempt < MAX_ATTEMPTS {
+            warn!("Completion webhook attempt {}/{} failed: {}", attempt, MAX_ATTEMPTS, last_error);
+            tokio::time::sleep(backoff).await;
+            backoff *= 2;
+        }
+    }
+
+    Err(format!("Gave up after {} attempts: {}", MAX_ATTEMPTS, last_error))
+}
+
+#[tauri::command]
+pub async fn get_completion_webhook_url(
+    state: tauri::State<'_, AppState>,
+) -> Result<Option<String>, String> {
+    let db = state.db().await;
+    db.get_setting(COMPLETION_WEBHOOK_SETTING).map_err(|e| e.to_string())
+}
+
+/// Set the webhook URL (empty string disables it). The URL is validated first.
+#[tauri::command]
+pub async fn set_completion_webhook_url(
+    state: tauri::State<'_, AppState>,
+    url: String,
+    allow_private: Option<bool>,
+) -> Result<(), String> {
+    let allow_private = allow_private.unwrap_or(false);
+    if !url.trim().is_empty() {
+        validate_webhook_url(&url, allow_private).await?;
+    }
+
+    let db = state.db().await;
+    db.set_setting(COMPLETION_WEBHOOK_SETTING, url.trim(), "string").map_err(|e| e.to_string())?;
+    db.set_bool_setting(COMPLETION_WEBHOOK_ALLOW_PRIVATE_SETTING, allow_private)
+        .map_err(|e| e.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_private_addresses() {
+        for ip in ["127.0.0.1", "10.1.2.3", "172.16.0.1", "192.168.1.10", "169.254.169.254",
+                   "100.64.0.1", "0.0.0.0", "::1", "fd00::1", "fe80::1", "::ffff:192.168.0.1"] {
+            assert!(is_private_address(&ip.parse().unwrap()), "{} should be private", ip);
+        }
+        for ip in ["8.8.8.8", "1.1.1.1", "100.128.0.1", "2606:4700::1111"] {
+            assert!(!is_private_address(&ip.parse().unwrap()), "{} should be public", ip);
+        }
+    }
+
+    #[tokio::test]
+    async fn test_validate_webhook_url_rejects_private_and_bad_scheme() {
+        assert!(validate_webhook_url("ftp://example.com/hook", false).await.is_err());
+        assert!(validate_webhook_url("http://127.0.0.1:8080/hook", false).await.is_err());
+        assert!(validate_webhook_url("http://[::1]/hook", false).await.is_err());
+        assert!(validate_webhook_url("http://127.0.0.1:8080/hook", true).await.is_ok());
+    }
+
+    #[tokio::test]
+    async fn test_ip_literal_is_not_pinned() {
+        let vetted = vet_webhook_url("http://8.8.8.8:8080/hook", false).await.unwrap();
+        assert_eq!(vetted.pinned_address, None);
+        let vetted = vet_webhook_url("http://localhost/hook", true).await.unwrap();
+        assert_eq!(vetted.pinned_address, None);
+    }
+}
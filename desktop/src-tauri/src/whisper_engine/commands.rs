@@ -1,4 +1,4 @@
-use crate::whisper_engine::{ModelInfo, WhisperEngine};
+use crate::whisper_engine::{model_registry, ModelInfo, WhisperEngine};
 use std::sync::{Arc, Mutex};
 use std::path::PathBuf;
 use tauri::{command, Emitter, Manager, AppHandle, Runtime};
@@ -9,14 +9,12 @@ pub static WHISPER_ENGINE: Mutex<Option<Arc<WhisperEngine>>> = Mutex::new(None);
 // Global models directory path (set during app initialization)
 static MODELS_DIR: Mutex<Option<PathBuf>> = Mutex::new(None);
 
-/// Initialize the models directory path using app_data_dir
+/// Initialize the models directory path (the `whisper_models_dir` setting or app_data_dir/models)
 /// This should be called during app setup before whisper_init
 pub fn set_models_directory<R: Runtime>(app: &AppHandle<R>) {
-    let app_data_dir = app.path().app_data_dir()
+    let models_dir = crate::model_directories::models_dir(app, crate::model_directories::ModelKind::Whisper)
         .expect("Failed to get app data dir");
 
-    let models_dir = app_data_dir.join("models");
-
     // Create directory if it doesn't exist
     if !models_dir.exists() {
         if let Err(e) = std::fs::create_dir_all(&models_dir) {
@@ -36,6 +34,21 @@ fn get_models_directory() -> Option<PathBuf> {
     MODELS_DIR.lock().unwrap().clone()
 }
 
+/// Switch to another models directory. An initialized engine is replaced by
+/// one using the new directory, so a loaded model has to be loaded again.
+pub fn use_models_directory(models_dir: PathBuf) -> Result<(), String> {
+    let mut guard = WHISPER_ENGINE.lock().unwrap();
+    if guard.is_some() {
+        let engine = WhisperEngine::new_with_models_dir(Some(models_dir.clone()))
+            .map_err(|e| format!("Failed to initialize whisper engine: {}", e))?;
+        *guard = Some(Arc::new(engine));
+    }
+    drop(guard);
+
+    *MODELS_DIR.lock().unwrap() = Some(models_dir);
+    Ok(())
+}
+
 #[command]
 pub async fn whisper_init() -> Result<(), String> {
     let mut guard = WHISPER_ENGINE.lock().unwrap();
@@ -328,6 +341,73 @@ pub async fn whisper_validate_model_ready_with_config<R: tauri::Runtime>(
     }
 }
 
+/// Result of a Whisper warmup run
+#[derive(Debug, Clone, serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct WhisperWarmupResult {
+    pub model_name: String,
+    /// Time spent loading the model (0 if it was already loaded)
+    pub load_ms: u64,
+    /// Time spent on the dummy inference
+    pub inference_ms: u64,
+}
+
+/// Load the configured model and run a tiny dummy inference so the first
+/// real recording doesn't pay for model loading and GPU kernel compilation.
+/// Emits `whisper-warmup-complete` or `whisper-warmup-failed`.
+#[command]
+pub async fn whisper_warmup<R: Runtime>(app: AppHandle<R>) -> Result<WhisperWarmupResult, String> {
+    let result = run_whisper_warmup(&app).await;
+
+    match &result {
+        Ok(warmup) => {
+            log::info!(
+                "Whisper warmup complete: model={}, load={}ms, inference={}ms",
+                warmup.model_name, warmup.load_ms, warmup.inference_ms
+            );
+            let _ = app.emit("whisper-warmup-complete", warmup);
+        }
+        Err(error) => {
+            log::warn!("Whisper warmup failed: {}", error);
+            let _ = app.emit(
+                "whisper-warmup-failed",
+                serde_json::json!({ "error": error }),
+            );
+        }
+    }
+
+    result
+}
+
+async fn run_whisper_warmup<R: Runtime>(app: &AppHandle<R>) -> Result<WhisperWarmupResult, String> {
+    whisper_init().await?;
+
+    let load_started = std::time::Instant::now();
+    let model_name = whisper_validate_model_ready_with_config(app).await?;
+    let load_ms = load_started.elapsed().as_millis() as u64;
+
+    let engine = {
+        let guard = WHISPER_ENGINE.lock().unwrap();
+        guard.as_ref().cloned()
+    }
+    .ok_or_else(|| "Whisper engine not initialized".to_string())?;
+
+    // One second of silence at 16kHz is enough to exercise the full pipeline;
+    // the saved language keeps the path the same as live transcription
+    let inference_started = std::time::Instant::now();
+    engine
+        .transcribe_audio(vec![0.0; 16000], crate::get_language_preference_internal())
+        .await
+        .map_err(|e| format!("Warmup inference failed: {}", e))?;
+    let inference_ms = inference_started.elapsed().as_millis() as u64;
+
+    Ok(WhisperWarmupResult {
+        model_name,
+        load_ms,
+        inference_ms,
+    })
+}
+
 #[command]
 pub async fn whisper_transcribe_audio(audio_data: Vec<f32>) -> Result<String, String> {
     let engine = {
@@ -445,22 +525,128 @@ pub async fn whisper_cancel_download(model_name: String) -> Result<(), String> {
     }
 }
 
-/// Delete a downloaded whisper model (works for both available and corrupted models)
+/// Delete a downloaded whisper model (works for both available and corrupted models).
+/// The loaded model is unloaded first and cleared as the default; this is refused
+/// while a recording, dictation or transcription is using it.
 #[command]
-pub async fn whisper_delete_model(model_name: String) -> Result<String, String> {
+pub async fn whisper_delete_model(
+    state: tauri::State<'_, crate::state::AppState>,
+    model_name: String,
+) -> Result<String, String> {
     let engine = {
         let guard = WHISPER_ENGINE.lock().unwrap();
         guard.as_ref().cloned()
     };
 
-    if let Some(engine) = engine {
+    let Some(engine) = engine else {
+        return Err("Whisper engine not initialized".to_string());
+    };
+
+    if engine.get_current_model().await.as_deref() == Some(model_name.as_str()) {
+        if crate::audio::is_recording().await {
+            return Err(format!(
+                "Cannot delete model '{}' while it is being used for a recording. Stop the recording first.",
+                model_name
+            ));
+        }
+        if crate::audio::dictation::is_dictating()
+            || crate::audio::recording::model_retention::is_model_in_use()
+        {
+            return Err(format!(
+                "Cannot delete model '{}' while it is being used for a transcription. Try again when it has finished.",
+                model_name
+            ));
+        }
+        engine.unload_model().await;
+        log::info!("Unloaded whisper model {} before deleting it", model_name);
+    }
+
+    // Custom models are only removed from the list; the user's file is kept
+    let result = if model_registry::unregister_custom_model(&model_name).is_some() {
+        save_custom_models(&state).await?;
         engine
-            .delete_model(&model_name)
+            .discover_models()
             .await
-            .map_err(|e| format!("Failed to delete model: {}", e))
+            .map_err(|e| format!("Failed to discover models: {}", e))?;
+        format!("Removed custom model '{}'", model_name)
     } else {
-        Err("Whisper engine not initialized".to_string())
+        engine
+            .delete_model(&model_name)
+            .await
+            .map_err(|e| format!("Failed to delete model: {}", e))?
+    };
+
+    let db = state.db().await;
+    if db.get_setting("current_model").ok().flatten().as_deref() == Some(model_name.as_str()) {
+        db.delete_setting("current_model").map_err(|e| e.to_string())?;
+    }
+
+    Ok(result)
+}
+
+/// Settings key holding the custom model files as a JSON object of name -> path
+pub const CUSTOM_WHISPER_MODELS_SETTING: &str = "custom_whisper_models";
+
+async fn save_custom_models(state: &crate::state::AppState) -> Result<(), String> {
+    let json = serde_json::to_string(&model_registry::get_custom_models()).map_err(|e| e.to_string())?;
+    state
+        .db()
+        .await
+        .set_setting(CUSTOM_WHISPER_MODELS_SETTING, &json, "string")
+        .map_err(|e| e.to_string())
+}
+
+/// Load a Whisper GGML model file from any path (e.g. a fine-tuned model).
+/// The file must be a whisper.cpp model; it is added to the available models
+/// as `custom-<file name>` and stays listed across restarts.
+#[command]
+pub async fn whisper_load_model_from_path(
+    app_handle: tauri::AppHandle,
+    state: tauri::State<'_, crate::state::AppState>,
+    path: String,
+) -> Result<ModelInfo, String> {
+    let engine = {
+        let guard = WHISPER_ENGINE.lock().unwrap();
+        guard.as_ref().cloned()
+    };
+    let Some(engine) = engine else {
+        return Err("Whisper engine not initialized".to_string());
+    };
+
+    let model_path = PathBuf::from(&path);
+    if !model_path.is_file() {
+        return Err(format!("Model file not found: {}", path));
+    }
+    model_registry::read_whisper_header(&model_path)
+        .await
+        .map_err(|e| format!("{}: {}", path, e))?;
+    let model_name = model_registry::custom_model_name(&model_path)
+        .ok_or_else(|| format!("Cannot derive a model name from {}", path))?;
+
+    if let Some(existing) = model_registry::get_custom_models().get(&model_name) {
+        if existing != &model_path {
+            return Err(format!(
+                "A custom model named '{}' is already loaded from {}",
+                model_name,
+                existing.display()
+            ));
+        }
     }
+
+    model_registry::register_custom_model(&model_name, model_path);
+    save_custom_models(&state).await?;
+    let models = engine
+        .discover_models()
+        .await
+        .map_err(|e| format!("Failed to discover models: {}", e))?;
+    log::info!("Registered custom whisper model '{}' from {}", model_name, path);
+
+    whisper_load_model(app_handle, model_name.clone()).await?;
+
+    models
+        .into_iter()
+        .find(|m| m.name == model_name)
+        .ok_or_else(|| format!("Model {} not found", model_name))
 }
 
 /// Open the models folder in the system file explorer
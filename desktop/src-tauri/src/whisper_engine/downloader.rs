@@ -10,6 +10,7 @@ use anyhow::{Result, anyhow};
 
 use super::types::{ModelStatus, ModelInfo};
 use super::model_registry::get_model_url;
+use crate::downloads::{DownloadHandle, DownloadKind};
 
 /// Download a model from HuggingFace
 pub async fn download_model(
@@ -37,6 +38,9 @@ pub async fn download_model(
         active.insert(model_name.to_string());
     }
 
+    // Visible to get_all_download_progress until this function returns
+    let tracker = DownloadHandle::start(DownloadKind::Whisper, model_name);
+
     // Clear any previous cancellation flag
     {
         let mut cancel_flag = cancel_download_flag.write().await;
@@ -122,6 +126,7 @@ pub async fn download_model(
             .map_err(|e| anyhow!("Failed to write chunk to file: {}", e))?;
 
         downloaded += chunk.len() as u64;
+        tracker.update(downloaded, total_size);
 
         // Calculate progress
         let progress = if total_size > 0 {
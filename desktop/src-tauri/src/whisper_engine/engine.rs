@@ -136,6 +136,17 @@ impl WhisperEngine {
 
     /// Transcribe audio with confidence and partial detection
     pub async fn transcribe_audio_with_confidence(&self, audio_data: Vec<f32>, language: Option<String>) -> Result<(String, f32, bool)> {
+        self.transcribe_audio_with_prompt(audio_data, language, None).await
+    }
+
+    /// Transcribe audio with confidence and partial detection, priming the
+    /// decoder with `initial_prompt` (e.g. the text preceding this audio)
+    pub async fn transcribe_audio_with_prompt(
+        &self,
+        audio_data: Vec<f32>,
+        language: Option<String>,
+        initial_prompt: Option<String>,
+    ) -> Result<(String, f32, bool)> {
         let ctx_lock = self.current_context.read().await;
         let ctx = ctx_lock.as_ref()
             .ok_or_else(|| anyhow!("No model loaded. Please load a model first."))?;
@@ -171,6 +182,9 @@ impl WhisperEngine {
         params.set_max_len(200);
         params.set_single_segment(false);
         params.set_no_context(true);
+        if let Some(prompt) = initial_prompt.as_deref() {
+            params.set_initial_prompt(prompt);
+        }
 
         let duration_seconds = audio_data.len() as f64 / 16000.0;
         let is_partial = duration_seconds < 15.0;
@@ -355,4 +369,31 @@ impl WhisperEngine {
 
         Ok(cleaned_result)
     }
+
+    /// Detect the spoken language of 16kHz mono audio (Whisper only looks at the first 30s).
+    /// Returns the language code and its probability.
+    pub async fn detect_language(&self, audio_data: &[f32]) -> Result<(String, f32)> {
+        let ctx_lock = self.current_context.read().await;
+        let ctx = ctx_lock.as_ref()
+            .ok_or_else(|| anyhow!("No model loaded. Please load a model first."))?;
+
+        if !ctx.is_multilingual() {
+            return Err(anyhow!("The loaded model is English-only and can't detect languages. Load a multilingual model."));
+        }
+
+        let threads = crate::audio::HardwareProfile::detect()
+            .get_whisper_config()
+            .max_threads
+            .unwrap_or(4);
+
+        let mut state = ctx.create_state()?;
+        state.pcm_to_mel(audio_data, threads)?;
+        let (lang_id, probabilities) = state.lang_detect(0, threads)?;
+
+        let language = whisper_rs::get_lang_str(lang_id)
+            .ok_or_else(|| anyhow!("Unknown language id {}", lang_id))?;
+        let confidence = probabilities.get(lang_id as usize).copied().unwrap_or(0.0);
+
+        Ok((language.to_string(), confidence))
+    }
 }
@@ -1,10 +1,11 @@
 // Whisper Engine - Model Registry and Discovery
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use std::collections::HashMap;
 use tokio::sync::RwLock;
 use tokio::fs;
 use tokio::io::AsyncReadExt;
 use anyhow::{Result, anyhow};
+use once_cell::sync::Lazy;
 
 use super::types::{ModelStatus, ModelInfo};
 
@@ -42,6 +43,123 @@ pub const MODEL_CONFIGS: &[(&str, &str, u32, &str, &str, &str)] = &[
     ("large-v3-turbo-q8_0", "ggml-large-v3-turbo-q8_0.bin", 874, "High", "Medium", "High-quality quantized turbo"),
 ];
 
+/// Prefix of the names given to model files loaded from an arbitrary path
+pub const CUSTOM_MODEL_PREFIX: &str = "custom-";
+
+/// Model files outside the registry, by model name
+static CUSTOM_MODELS: Lazy<std::sync::RwLock<HashMap<String, PathBuf>>> =
+    Lazy::new(|| std::sync::RwLock::new(HashMap::new()));
+
+/// Add a model file to the available models under `name`
+pub fn register_custom_model(name: &str, path: PathBuf) {
+    CUSTOM_MODELS.write().unwrap().insert(name.to_string(), path);
+}
+
+/// Remove a custom model from the available models; the file itself is left alone
+pub fn unregister_custom_model(name: &str) -> Option<PathBuf> {
+    CUSTOM_MODELS.write().unwrap().remove(name)
+}
+
+pub fn get_custom_models() -> HashMap<String, PathBuf> {
+    CUSTOM_MODELS.read().unwrap().clone()
+}
+
+/// Replace all custom models, e.g. from the `custom_whisper_models` setting
+pub fn set_custom_models(models: HashMap<String, PathBuf>) {
+    *CUSTOM_MODELS.write().unwrap() = models;
+}
+
+/// Model name for a custom model file, from its file stem
+pub fn custom_model_name(path: &Path) -> Option<String> {
+    let stem = path.file_stem()?.to_str()?;
+    let stem = stem.strip_prefix("ggml-").unwrap_or(stem);
+    (!stem.is_empty()).then(|| format!("{}{}", CUSTOM_MODEL_PREFIX, stem))
+}
+
+/// Hyperparameters from the header of a whisper.cpp GGML model file
+#[derive(Debug, Clone, PartialEq)]
+pub struct WhisperModelHeader {
+    pub n_vocab: i32,
+    pub n_audio_layer: i32,
+    pub n_text_layer: i32,
+    pub n_mels: i32,
+}
+
+/// whisper.cpp's GGML magic (0x67676d6c, "ggml") as stored little-endian
+const WHISPER_GGML_MAGIC: &[u8; 4] = b"lmgg";
+/// Magic plus the eleven i32 hyperparameters
+const WHISPER_HEADER_LEN: usize = 48;
+/// Audio and text context sizes every Whisper model has
+const WHISPER_AUDIO_CTX: i32 = 1500;
+const WHISPER_TEXT_CTX: i32 = 448;
+
+/// Parse and sanity-check a Whisper GGML header, rejecting other GGML models
+/// (e.g. LLM weights) that share the magic number
+pub fn parse_whisper_header(header: &[u8]) -> Result<WhisperModelHeader> {
+    if header.len() < WHISPER_HEADER_LEN || &header[..4] != WHISPER_GGML_MAGIC {
+        return Err(anyhow!("Not a whisper.cpp GGML model file"));
+    }
+    let field = |index: usize| {
+        let offset = 4 + index * 4;
+        i32::from_le_bytes([header[offset], header[offset + 1], header[offset + 2], header[offset + 3]])
+    };
+    // n_vocab, n_audio_ctx, n_audio_state, n_audio_head, n_audio_layer,
+    // n_text_ctx, n_text_state, n_text_head, n_text_layer, n_mels, ftype
+    if field(1) != WHISPER_AUDIO_CTX || field(5) != WHISPER_TEXT_CTX {
+        return Err(anyhow!("GGML file is not a Whisper model (unexpected context sizes)"));
+    }
+    let n_mels = field(9);
+    if n_mels != 80 && n_mels != 128 {
+        return Err(anyhow!("GGML file is not a Whisper model ({} mel bins)", n_mels));
+    }
+
+    Ok(WhisperModelHeader {
+        n_vocab: field(0),
+        n_audio_layer: field(4),
+        n_text_layer: field(8),
+        n_mels,
+    })
+}
+
+/// Read and check the header of a Whisper model file
+pub async fn read_whisper_header(model_path: &Path) -> Result<WhisperModelHeader> {
+    let mut file = fs::File::open(model_path).await
+        .map_err(|e| anyhow!("Failed to open model file: {}", e))?;
+    let mut header = [0u8; WHISPER_HEADER_LEN];
+    file.read_exact(&mut header).await
+        .map_err(|e| anyhow!("Failed to read model file header: {}", e))?;
+    parse_whisper_header(&header)
+}
+
+/// Model info for a custom model file
+async fn custom_model_info(name: &str, path: &Path) -> ModelInfo {
+    let size_mb = std::fs::metadata(path)
+        .map(|m| (m.len() / (1024 * 1024)) as u32)
+        .unwrap_or(0);
+    let (status, description) = if !path.exists() {
+        (ModelStatus::Missing, format!("Custom model file not found: {}", path.display()))
+    } else {
+        match read_whisper_header(path).await {
+            Ok(header) => (
+                ModelStatus::Available,
+                format!("Custom model ({} encoder / {} decoder layers, {} mel bins)",
+                        header.n_audio_layer, header.n_text_layer, header.n_mels),
+            ),
+            Err(e) => (ModelStatus::Error(e.to_string()), format!("Custom model at {}", path.display())),
+        }
+    };
+
+    ModelInfo {
+        name: name.to_string(),
+        path: path.to_path_buf(),
+        size_mb,
+        accuracy: "Custom".to_string(),
+        speed: "Unknown".to_string(),
+        status,
+        description,
+    }
+}
+
 /// Discover available models in the models directory
 pub async fn discover_models(
     models_dir: &PathBuf,
@@ -117,6 +235,10 @@ pub async fn discover_models(
         models.push(model_info);
     }
 
+    for (name, path) in get_custom_models() {
+        models.push(custom_model_info(&name, &path).await);
+    }
+
     // Update internal cache
     let mut cache = available_models.write().await;
     cache.clear();
@@ -184,3 +306,39 @@ pub fn get_model_url(model_name: &str) -> Option<&'static str> {
         _ => None
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn header(fields: [i32; 11]) -> Vec<u8> {
+        let mut bytes = WHISPER_GGML_MAGIC.to_vec();
+        for field in fields {
+            bytes.extend_from_slice(&field.to_le_bytes());
+        }
+        bytes
+    }
+
+    #[test]
+    fn test_parse_whisper_header() {
+        // ggml-base.bin
+        let base = header([51865, 1500, 512, 8, 6, 448, 512, 8, 6, 80, 1]);
+        assert_eq!(parse_whisper_header(&base).unwrap(), WhisperModelHeader {
+            n_vocab: 51865,
+            n_audio_layer: 6,
+            n_text_layer: 6,
+            n_mels: 80,
+        });
+
+        // Other GGML models share the magic but not the Whisper context sizes
+        assert!(parse_whisper_header(&header([32000, 4096, 32, 32, 32, 11008, 0, 0, 0, 0, 1])).is_err());
+        assert!(parse_whisper_header(b"GGUF\x03\x00\x00\x00").is_err());
+        assert!(parse_whisper_header(&base[..20]).is_err());
+    }
+
+    #[test]
+    fn test_custom_model_name() {
+        assert_eq!(custom_model_name(Path::new("/models/ggml-finetuned-de.bin")).as_deref(), Some("custom-finetuned-de"));
+        assert_eq!(custom_model_name(Path::new("whisper-medical.bin")).as_deref(), Some("custom-whisper-medical"));
+    }
+}
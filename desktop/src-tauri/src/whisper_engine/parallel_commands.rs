@@ -7,6 +7,7 @@ use crate::whisper_engine::{
     ParallelProcessor, ParallelConfig, SystemMonitor,
     AudioChunk, ProcessingStatus
 };
+use crate::whisper_engine::system_monitor::InferenceResourceUsage;
 
 // Global state for parallel processor
 pub struct ParallelProcessorState {
@@ -146,6 +147,30 @@ pub async fn get_system_resources(
         .map_err(|e| format!("Failed to serialize resources: {}", e))
 }
 
+/// Live RAM/VRAM readout while models are loaded, for the resource gauge
+#[tauri::command]
+pub async fn get_inference_resource_usage(
+    state: State<'_, ParallelProcessorState>,
+    app_state: State<'_, crate::state::AppState>,
+) -> Result<InferenceResourceUsage, String> {
+    let mut usage = state.system_monitor.get_inference_resource_usage()
+        .await
+        .map_err(|e| format!("Failed to get inference resource usage: {}", e))?;
+
+    let whisper_engine = crate::whisper_engine::commands::WHISPER_ENGINE
+        .lock()
+        .unwrap()
+        .as_ref()
+        .cloned();
+    if let Some(engine) = whisper_engine {
+        usage.whisper_model = engine.get_current_model().await;
+    }
+
+    usage.llm_model = app_state.llm_engine.read().await.current_model().await;
+
+    Ok(usage)
+}
+
 #[tauri::command]
 pub async fn check_resource_constraints(
     state: State<'_, ParallelProcessorState>,
@@ -5,6 +5,8 @@ use anyhow::Result;
 use log::{info, warn, debug};
 use serde::{Serialize, Deserialize};
 
+use crate::audio::{GpuMemoryInfo, HardwareProfile};
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct SystemResources {
     pub memory_used_percent: f32,
@@ -15,6 +17,24 @@ pub struct SystemResources {
     pub cpu_cores: usize,
 }
 
+/// Live memory readout while models are loaded
+#[derive(Debug, Clone, Serialize)]
+pub struct InferenceResourceUsage {
+    /// Resident memory of the app process (includes Whisper/Parakeet models)
+    pub process_rss_mb: u64,
+    /// Resident memory of the LLM sidecar, if it is running
+    pub llm_sidecar_rss_mb: Option<u64>,
+    pub system_total_memory_mb: u64,
+    pub system_available_memory_mb: u64,
+    pub system_memory_used_percent: f32,
+    /// GPU backend in use (Metal, CUDA, ...)
+    pub gpu_backend: String,
+    pub gpus: Vec<GpuMemoryInfo>,
+    pub whisper_model: Option<String>,
+    pub llm_model: Option<String>,
+    pub warnings: Vec<String>,
+}
+
 #[derive(Debug, Clone)]
 pub struct ResourceLimits {
     pub max_memory_percent: f32,      // Default: 70%
@@ -188,6 +208,74 @@ impl SystemMonitor {
         Ok(safe_workers)
     }
 
+    /// Snapshot process, system and GPU memory for the inference gauge.
+    /// Model names are filled in by the caller.
+    pub async fn get_inference_resource_usage(&self) -> Result<InferenceResourceUsage> {
+        let mut system = self.system.write().await;
+        system.refresh_all();
+
+        let process_rss_mb = sysinfo::get_current_pid()
+            .ok()
+            .and_then(|pid| system.process(pid))
+            .map(|p| p.memory() / 1024 / 1024)
+            .unwrap_or(0);
+
+        let sidecar_rss: Vec<u64> = system
+            .processes()
+            .values()
+            .filter(|p| p.name().to_string_lossy().starts_with("llm-sidecar"))
+            .map(|p| p.memory() / 1024 / 1024)
+            .collect();
+        let llm_sidecar_rss_mb = if sidecar_rss.is_empty() {
+            None
+        } else {
+            Some(sidecar_rss.iter().sum())
+        };
+
+        let total_memory = system.total_memory();
+        let available_memory = system.available_memory();
+        drop(system);
+
+        let system_memory_used_percent = if total_memory > 0 {
+            (total_memory.saturating_sub(available_memory) as f32 / total_memory as f32) * 100.0
+        } else {
+            0.0
+        };
+
+        let profile = HardwareProfile::detect();
+        let gpus = HardwareProfile::query_gpu_memory(&profile.gpu_type);
+
+        let mut warnings = Vec::new();
+        if system_memory_used_percent > self.limits.max_memory_percent {
+            warnings.push(format!(
+                "System memory usage is high ({:.1}%), loading another large model may cause swapping",
+                system_memory_used_percent
+            ));
+        }
+        for gpu in gpus.iter().filter(|g| g.total_vram_mb > 0) {
+            let free_percent = gpu.available_vram_mb as f32 / gpu.total_vram_mb as f32 * 100.0;
+            if free_percent < 15.0 {
+                warnings.push(format!(
+                    "{} has only {}MB of {}MB memory free",
+                    gpu.name, gpu.available_vram_mb, gpu.total_vram_mb
+                ));
+            }
+        }
+
+        Ok(InferenceResourceUsage {
+            process_rss_mb,
+            llm_sidecar_rss_mb,
+            system_total_memory_mb: total_memory / 1024 / 1024,
+            system_available_memory_mb: available_memory / 1024 / 1024,
+            system_memory_used_percent,
+            gpu_backend: format!("{:?}", profile.gpu_type),
+            gpus,
+            whisper_model: None,
+            llm_model: None,
+            warnings,
+        })
+    }
+
     pub fn set_monitoring_enabled(&mut self, enabled: bool) {
         self.monitoring_enabled = enabled;
         if enabled {
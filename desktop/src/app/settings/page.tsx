@@ -259,6 +259,13 @@ export default function SettingsPage() {
         console.error('Failed to load live diarization setting:', e)
       }
 
+      try {
+        const provider = await invoke<string | null>('db_get_setting', { key: 'diarization_provider' })
+        if (provider === 'pyannote' || provider === 'sortformer') setDiarizationProvider(provider)
+      } catch (e) {
+        console.error('Failed to load diarization provider:', e)
+      }
+
       try {
         const available = await invoke<boolean>('is_sortformer_model_available')
         setSortformerModelReady(available)
@@ -423,6 +430,15 @@ export default function SettingsPage() {
     }
   }
 
+  const handleDiarizationProviderChange = async (provider: 'pyannote' | 'sortformer') => {
+    setDiarizationProvider(provider)
+    try {
+      await invoke('db_set_setting', { key: 'diarization_provider', value: provider, valueType: 'string' })
+    } catch (e) {
+      console.error('Failed to save diarization provider:', e)
+    }
+  }
+
   const handleDeleteSpeaker = async (speakerId: string) => {
     setIsDeletingSpeaker(speakerId)
     try {
@@ -1330,7 +1346,7 @@ export default function SettingsPage() {
                 <div className="space-y-6">
                   <div className="space-y-2">
                     <label className="text-sm font-medium text-foreground">Diarization Engine</label>
-                    <Select value={diarizationProvider} onValueChange={(value: 'pyannote' | 'sortformer') => setDiarizationProvider(value)}>
+                    <Select value={diarizationProvider} onValueChange={handleDiarizationProviderChange}>
                       <SelectTrigger className="w-full">
                         <SelectValue placeholder="Select diarization engine" />
                       </SelectTrigger>
@@ -312,8 +312,9 @@ function RecordingDetailContent() {
       setError(null)
 
       // The backend will handle loading the model if needed
-      // Parse maxSpeakers - 'auto' means undefined (use default)
+      // Parse maxSpeakers - 'auto' estimates the speaker count (up to the default limit)
       const maxSpeakersNum = maxSpeakers === 'auto' ? undefined : parseInt(maxSpeakers, 10)
+      const usePyannote = enableDiarization && diarizationProvider === 'pyannote'
 
       await startRetranscription(
         recordingId,
@@ -322,8 +323,9 @@ function RecordingDetailContent() {
         selectedLanguage === 'auto' ? undefined : selectedLanguage,
         enableDiarization,
         enableDiarization ? diarizationProvider : undefined,
-        enableDiarization && diarizationProvider === 'pyannote' ? maxSpeakersNum : undefined,
-        enableDiarization && diarizationProvider === 'pyannote' ? similarityThreshold : undefined
+        usePyannote ? maxSpeakersNum : undefined,
+        usePyannote ? similarityThreshold : undefined,
+        usePyannote && maxSpeakers === 'auto'
       )
 
       // Update current model after successful start (backend will load it)
@@ -1177,7 +1179,7 @@ function RecordingDetailContent() {
                               </div>
                             ) : (
                               <p
-                                className="text-sm text-foreground leading-relaxed group/text cursor-pointer"
+                                className={`text-sm ${segment.low_confidence ? 'text-muted-foreground' : 'text-foreground'} leading-relaxed group/text cursor-pointer`}
                                 onClick={() => handleStartEditSegment(segment)}
                               >
                                 <span className="hover:bg-muted/50 rounded px-1 -mx-1 transition-colors inline">
@@ -15,6 +15,7 @@ export function ChatMessage({ message, streamingContent }: ChatMessageProps) {
   const isStreaming = message.status === 'streaming' || message.status === 'pending'
   const hasError = message.status === 'error'
   const isCancelled = message.status === 'cancelled'
+  const isStopped = message.status === 'stopped'
 
   // Use streaming content if available, otherwise use message content
   const displayContent = isStreaming && streamingContent !== undefined
@@ -90,6 +91,13 @@ export function ChatMessage({ message, streamingContent }: ChatMessageProps) {
             Message cancelled
           </div>
         )}
+
+        {/* Stopped early indicator (partial output kept) */}
+        {isStopped && (
+          <div className="mt-2 text-xs text-muted-foreground italic">
+            Stopped early
+          </div>
+        )}
       </div>
     </div>
   )
@@ -132,7 +132,7 @@ export function useChat({
               m.id === message_id
                 ? {
                     ...m,
-                    status: status === 'complete' ? 'complete' as const : 'error' as const,
+                    status: status === 'complete' || status === 'stopped' ? status : 'error' as const,
                     error_message: errorMsg
                   }
                 : m
@@ -189,7 +189,7 @@ export function useChat({
           )
 
           // Check if completed
-          if (status.status === 'complete' || status.status === 'error' || status.status === 'cancelled') {
+          if (status.status === 'complete' || status.status === 'error' || status.status === 'cancelled' || status.status === 'stopped') {
             setIsProcessing(false)
             setStreamingMessageId(null)
             if (pollIntervalRef.current) {
@@ -282,7 +282,7 @@ export function useChat({
       setMessages(prev =>
         prev.map(m =>
           m.id === targetId
-            ? { ...m, status: 'cancelled' as const }
+            ? { ...m, status: m.content ? 'stopped' as const : 'cancelled' as const }
             : m
         )
       )
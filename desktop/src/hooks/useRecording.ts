@@ -13,6 +13,10 @@ export interface Transcript {
   audioEndTime?: number
   confidence?: number
   sequenceId?: number
+  // Speaker from live diarization (or the audio source), if any
+  speakerId?: string | null
+  speakerLabel?: string | null
+  isRegisteredSpeaker?: boolean
 }
 
 interface TranscriptUpdate {
@@ -21,6 +25,9 @@ interface TranscriptUpdate {
   audio_start_time?: number
   audio_end_time?: number
   confidence?: number
+  speaker_id?: string
+  speaker_label?: string
+  is_registered_speaker?: boolean
 }
 
 // Information about a completed recording for the post-recording modal
@@ -31,6 +38,9 @@ export interface CompletedRecordingInfo {
   transcriptCount: number
   audioPath: string | null
   meetingFolderPath: string | null
+  // The backend already saved this recording and its transcript
+  // (recordings started by the hotkey or a schedule)
+  savedByBackend?: boolean
 }
 
 export function useRecording() {
@@ -74,13 +84,19 @@ export function useRecording() {
 
     const setupListener = async () => {
       try {
-        unlisten = await listen<{ folder_path: string; meeting_name: string }>(
+        unlisten = await listen<{ folder_path: string; meeting_name: string; recording_id?: string | null }>(
           'recording-stopped',
           (event) => {
-            const { folder_path } = event.payload
+            const { folder_path, recording_id } = event.payload
             console.log('Recording stopped, folder path:', folder_path)
             audioFolderPathRef.current = folder_path
 
+            if (recording_id) {
+              setCompletedRecording(prev =>
+                prev ? { ...prev, id: recording_id, savedByBackend: true } : prev
+              )
+            }
+
             // Update completedRecording with actual audio path if modal is showing
             if (folder_path) {
               const audioFilePath = `${folder_path}/audio.mp4`
@@ -101,6 +117,42 @@ export function useRecording() {
     }
   }, [])
 
+  // Recordings started by the hotkey or a schedule get their database row from the backend
+  useEffect(() => {
+    const unlisteners: (() => void)[] = []
+
+    const adoptRecording = (recordingId: string | null | undefined, title: string) => {
+      setTranscripts([])
+      sequenceRef.current = 0
+      recordingStartTimeRef.current = new Date()
+      recordingTitleRef.current = title
+      setCurrentRecordingId(recordingId ?? null)
+    }
+
+    const setupListeners = async () => {
+      try {
+        unlisteners.push(await listen<{ action: string; success: boolean; recordingId?: string | null }>(
+          'hotkey-recording-toggled',
+          (event) => {
+            const { action, success, recordingId } = event.payload
+            if (action === 'started' && success) adoptRecording(recordingId, '')
+          }
+        ))
+        unlisteners.push(await listen<{ name: string; recordingId?: string | null }>(
+          'scheduled-recording-started',
+          (event) => adoptRecording(event.payload.recordingId, event.payload.name)
+        ))
+      } catch (err) {
+        console.error('Failed to setup backend recording listeners:', err)
+      }
+    }
+
+    setupListeners()
+    return () => {
+      unlisteners.forEach(unlisten => unlisten())
+    }
+  }, [])
+
   // Listen for transcript updates
   useEffect(() => {
     let unlisten: (() => void) | undefined
@@ -119,6 +171,9 @@ export function useRecording() {
             audioEndTime: update.audio_end_time,
             confidence: update.confidence,
             sequenceId,
+            speakerId: update.speaker_id ?? null,
+            speakerLabel: update.speaker_label ?? null,
+            isRegisteredSpeaker: update.is_registered_speaker ?? false,
           }
 
           setTranscripts((prev) => [...prev, transcript])
@@ -329,7 +384,7 @@ export function useRecording() {
       console.log('Completed recording in database:', recordingId)
 
       // Save transcript segments to database
-      if (completedTranscripts.length > 0) {
+      if (completedTranscripts.length > 0 && !completedRecording.savedByBackend) {
         const timestamp = Date.now()
         // Filter out empty transcripts and build valid segments
         const segments = completedTranscripts
@@ -344,6 +399,9 @@ export function useRecording() {
             display_time: t.timestamp || `[${Math.floor((t.audioStartTime ?? index * 5) / 60)}:${String(Math.floor((t.audioStartTime ?? index * 5) % 60)).padStart(2, '0')}]`,
             confidence: t.confidence ?? 1.0,
             sequence_id: t.sequenceId ?? index,
+            speaker_id: t.speakerId ?? null,
+            speaker_label: t.speakerLabel ?? null,
+            is_registered_speaker: t.isRegisteredSpeaker ?? false,
           }))
 
         if (segments.length > 0) {
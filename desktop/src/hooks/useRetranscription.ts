@@ -25,6 +25,8 @@ interface RetranscriptionSegment {
   speaker_id?: string | null
   speaker_label?: string | null
   is_registered_speaker?: boolean
+  // Set when confidence is below the requested min_confidence
+  low_confidence?: boolean
 }
 
 // Result from completed retranscription
@@ -34,30 +36,8 @@ interface RetranscriptionResult {
   transcripts: RetranscriptionSegment[]
   error: string | null
   model_used: string
-}
-
-// Database format for transcript segment
-interface TranscriptSegmentDb {
-  id: string
-  recording_id: string
-  text: string
-  audio_start_time: number
-  audio_end_time: number
-  duration: number
-  display_time: string
-  confidence: number
-  sequence_id: number
-  // Speaker diarization fields
-  speaker_id?: string | null
-  speaker_label?: string | null
-  is_registered_speaker?: boolean
-}
-
-// Format seconds to [MM:SS] display time
-function formatTime(seconds: number): string {
-  const mins = Math.floor(seconds / 60)
-  const secs = Math.floor(seconds % 60)
-  return `[${mins.toString().padStart(2, '0')}:${secs.toString().padStart(2, '0')}]`
+  // Chunk indices that failed after retries (transcript contains placeholder gaps)
+  failed_chunks?: number[]
 }
 
 // Status for a single recording
@@ -81,9 +61,6 @@ export function useRetranscription() {
   // Callback for when retranscription completes
   const onCompleteCallbackRef = useRef<((recordingId: string, result: RetranscriptionResult) => void) | null>(null)
 
-  // Track diarization provider per recording (for saving after completion)
-  const diarizationProviderMapRef = useRef<Map<string, string | null>>(new Map())
-
   // Listen for progress events
   useEffect(() => {
     let unlistenProgress: (() => void) | undefined
@@ -119,53 +96,7 @@ export function useRetranscription() {
             const result = event.payload
             console.log('Retranscription complete:', result)
 
-            // If successful, save the new transcripts to the database
-            if (result.success && result.transcripts.length > 0) {
-              try {
-                // Convert to database format
-                const dbSegments: TranscriptSegmentDb[] = result.transcripts.map((t, idx) => ({
-                  id: `retrans-${result.recording_id}-${idx}-${Date.now()}`,
-                  recording_id: result.recording_id,
-                  text: t.text,
-                  audio_start_time: t.audio_start_time,
-                  audio_end_time: t.audio_end_time,
-                  duration: t.audio_end_time - t.audio_start_time,
-                  display_time: formatTime(t.audio_start_time),
-                  confidence: t.confidence,
-                  sequence_id: t.sequence_id,
-                  // Speaker diarization fields
-                  speaker_id: t.speaker_id ?? null,
-                  speaker_label: t.speaker_label ?? null,
-                  is_registered_speaker: t.is_registered_speaker ?? false,
-                }))
-
-                // Replace existing transcripts in database
-                await invoke('db_replace_transcripts', {
-                  recordingId: result.recording_id,
-                  segments: dbSegments,
-                })
-                console.log(`Replaced ${dbSegments.length} transcript segments in database`)
-
-                // Update the recording with the model used and diarization provider
-                // Use empty string to explicitly clear diarization_provider when not used
-                const diarizationProvider = diarizationProviderMapRef.current.get(result.recording_id)
-                await invoke('db_update_recording', {
-                  id: result.recording_id,
-                  updates: {
-                    transcription_model: result.model_used,
-                    // Empty string means "clear the field", null/undefined means "don't update"
-                    diarization_provider: diarizationProvider ?? '',
-                  },
-                })
-                console.log(`Updated recording with model: ${result.model_used}, diarization: ${diarizationProvider ?? '(cleared)'}`)
-
-                // Clean up the provider map
-                diarizationProviderMapRef.current.delete(result.recording_id)
-              } catch (err) {
-                console.error('Failed to save retranscription to database:', err)
-              }
-            }
-
+            // The backend saves the transcript before emitting this event
             setStatusMap((prev) => {
               const newMap = new Map(prev)
               newMap.set(result.recording_id, {
@@ -219,16 +150,10 @@ export function useRetranscription() {
       enableDiarization?: boolean,
       diarizationProvider?: 'pyannote' | 'sortformer',
       maxSpeakers?: number,
-      similarityThreshold?: number
+      similarityThreshold?: number,
+      autoSpeakers?: boolean
     ) => {
-      console.log('Starting retranscription:', { recordingId, audioPath, modelName, language, enableDiarization, diarizationProvider, maxSpeakers, similarityThreshold })
-
-      // Track diarization provider for this recording (to save after completion)
-      if (enableDiarization && diarizationProvider) {
-        diarizationProviderMapRef.current.set(recordingId, diarizationProvider)
-      } else {
-        diarizationProviderMapRef.current.set(recordingId, null)
-      }
+      console.log('Starting retranscription:', { recordingId, audioPath, modelName, language, enableDiarization, diarizationProvider, maxSpeakers, similarityThreshold, autoSpeakers })
 
       // Set initial status
       setStatusMap((prev) => {
@@ -252,6 +177,7 @@ export function useRetranscription() {
           enableDiarization: enableDiarization || false,
           diarizationProvider: diarizationProvider || undefined,
           maxSpeakers: maxSpeakers || undefined,
+          autoSpeakers: autoSpeakers || undefined,
           similarityThreshold: similarityThreshold || undefined,
         })
       } catch (err) {
@@ -2,7 +2,7 @@
 
 export type ChatRole = 'system' | 'user' | 'assistant'
 
-export type ChatMessageStatus = 'pending' | 'streaming' | 'complete' | 'cancelled' | 'error'
+export type ChatMessageStatus = 'pending' | 'streaming' | 'complete' | 'cancelled' | 'stopped' | 'error'
 
 export interface ChatMessage {
   id: string
@@ -16,6 +16,9 @@ export interface ChatMessage {
   error_message?: string
   provider_type?: string
   model_id?: string
+  token_count?: number
+  prompt_tokens?: number
+  generation_ms?: number
 }
 
 export interface ChatSession {
@@ -57,6 +60,6 @@ export interface ChatStreamEvent {
 
 export interface ChatCompleteEvent {
   message_id: string
-  status: 'complete' | 'error'
+  status: 'complete' | 'stopped' | 'error'
   error?: string
 }
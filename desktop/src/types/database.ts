@@ -53,6 +53,8 @@ export interface TranscriptSegment {
   speaker_id?: string | null
   speaker_label?: string | null
   is_registered_speaker?: boolean
+  // Confidence below the retranscription's minimum (text kept, shown greyed out)
+  low_confidence?: boolean
 }
 
 // Speaker colors for visual differentiation
@@ -132,4 +134,7 @@ export interface RecordingUpdate {
   meeting_folder_path?: string
   transcription_model?: string
   diarization_provider?: string
+  microphone_device?: string
+  system_audio_device?: string
+  language?: string
 }